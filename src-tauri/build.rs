@@ -1,3 +1,18 @@
 fn main() {
+    #[cfg(feature = "grpc")]
+    compile_protos();
     tauri_build::build()
 }
+
+/// Generates the gRPC server stubs from `proto/tempus.proto`. The schema is
+/// parsed with `protox`, so no `protoc` install is needed.
+#[cfg(feature = "grpc")]
+fn compile_protos() {
+    println!("cargo:rerun-if-changed=proto/tempus.proto");
+    let descriptors =
+        protox::compile(["proto/tempus.proto"], ["proto"]).expect("proto/tempus.proto is invalid");
+    tonic_build::configure()
+        .build_client(false)
+        .compile_fds(descriptors)
+        .expect("failed to generate gRPC code");
+}
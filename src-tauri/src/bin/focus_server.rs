@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = tauri_app_lib::run_headless() {
+        eprintln!("Focus server failed: {error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(error) = tauri_app_lib::run_cli(&args) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
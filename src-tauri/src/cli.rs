@@ -0,0 +1,246 @@
+//! The `tempus` command-line client (`--features cli`):
+//!
+//! ```text
+//! tempus start          start the next session
+//! tempus status         what the timer is doing
+//! tempus stats [--week] today's focus, or the last seven days'
+//! ```
+//!
+//! `start` and `status` go to the desktop app over its control socket when
+//! it is running. Otherwise `start` runs the session in the terminal and
+//! records it like the app would; overtime doesn't apply there. `stats`
+//! reads the data directory directly.
+//!
+//! Data lives in the desktop app's data directory, or `TEMPUS_DATA_DIR`.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::TimeDelta;
+
+use crate::services::calendar::day_key;
+use crate::services::control_socket::{self, ControlCommand};
+use crate::services::duration_format::format_duration;
+use crate::services::session_recorder;
+use crate::services::session_types::find_custom;
+use crate::services::storage::{StorageService, UserPreferences, APP_IDENTIFIER};
+use crate::services::tick_drift;
+use crate::services::timer_state::{
+    DisplayMode, SessionType, Tick, TimerConfig, TimerData, TimerManager, TimerState,
+};
+
+const DATA_DIR_ENV: &str = "TEMPUS_DATA_DIR";
+const WEEK_DAYS: i64 = 7;
+const USAGE: &str = "Usage: tempus <start | status | stats [--week]>";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Start,
+    Status,
+    Stats { week: bool },
+}
+
+/// The data directory the desktop app uses, as Tauri resolves it.
+fn app_data_dir() -> Result<PathBuf, String> {
+    let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_DATA_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".local/share")))
+    };
+    base.map(|base| base.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Failed to find the app data directory; set TEMPUS_DATA_DIR".to_string())
+}
+
+fn open_storage() -> Result<StorageService, String> {
+    let data_dir = match std::env::var_os(DATA_DIR_ENV) {
+        Some(dir) => PathBuf::from(dir),
+        None => app_data_dir()?,
+    };
+    StorageService::open(data_dir)
+}
+
+fn clock(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// One line describing `data`, such as `Work · running · 12:34 left`.
+fn status_line(data: &TimerData) -> String {
+    let label = match &data.session_type {
+        SessionType::Custom(id) => find_custom(&data.config.custom_session_types, id)
+            .map_or_else(|| id.clone(), |custom| custom.name.clone()),
+        built_in => built_in.display_name().to_string(),
+    };
+    let shown = match (data.state, data.display_mode) {
+        (TimerState::Stopwatch, _) => clock(data.total_time),
+        (TimerState::Overtime, _) => format!("+{}", clock(data.overtime)),
+        (_, DisplayMode::Countdown) => format!("{} left", clock(data.remaining_time)),
+        (_, DisplayMode::CountUp) => format!("{} elapsed", clock(data.elapsed_time)),
+        (_, DisplayMode::Percent) => format!("{}% done", data.percent_complete),
+    };
+    format!("{label} · {} · {shown}", data.state)
+}
+
+/// Runs the next session in the terminal until it ends, recording it.
+fn run_locally(storage: &StorageService, preferences: &UserPreferences) -> Result<(), String> {
+    storage.lock_instance()?;
+    let timer = TimerManager::new(TimerConfig {
+        overtime: false,
+        ..preferences.timer_config.clone()
+    });
+    timer.configure(preferences)?;
+    timer.start(None).map_err(|e| e.to_string())?;
+    let mut stdout = std::io::stdout();
+    loop {
+        std::thread::sleep(tick_drift::next_tick_delay(timer.until_next_second()?));
+        match session_recorder::tick_and_record(&timer, storage)? {
+            Tick::Completed { finished, .. } => {
+                println!("\n{} finished and recorded.", finished.display_name());
+                return Ok(());
+            }
+            tick => {
+                print!("\r{}   ", status_line(tick.data()));
+                stdout
+                    .flush()
+                    .map_err(|e| format!("Failed to write the status: {e}"))?;
+            }
+        }
+    }
+}
+
+fn start(storage: &StorageService) -> Result<(), String> {
+    match control_socket::call(storage, ControlCommand::Start)? {
+        Some(data) => {
+            println!("{}", status_line(&data));
+            Ok(())
+        }
+        None => {
+            println!("Tempus Ring isn't running; timing here. Ctrl+C abandons the session.");
+            run_locally(storage, &storage.load_preferences()?)
+        }
+    }
+}
+
+fn status(storage: &StorageService) -> Result<(), String> {
+    match control_socket::call(storage, ControlCommand::Status)? {
+        Some(data) => println!("{}", status_line(&data)),
+        None => println!("Tempus Ring isn't running."),
+    }
+    Ok(())
+}
+
+fn stats(storage: &StorageService, week: bool) -> Result<(), String> {
+    let preferences = storage.load_preferences()?;
+    let today = preferences.calendar.today();
+    let from = if week {
+        today - TimeDelta::days(WEEK_DAYS - 1)
+    } else {
+        today
+    };
+    let statistics = storage.load_statistics(Some(from), Some(today))?;
+    let duration =
+        |seconds| format_duration(seconds, preferences.duration_style, &preferences.language);
+    let (mut pomodoros, mut focus) = (0, 0);
+    for statistic in &statistics {
+        println!(
+            "{}  {:>3} pomodoros  {}",
+            statistic.date,
+            statistic.completed_pomodoros,
+            duration(statistic.total_focus_time)
+        );
+        pomodoros += statistic.completed_pomodoros;
+        focus += statistic.total_focus_time;
+    }
+    if statistics.is_empty() {
+        println!("Nothing recorded since {}.", day_key(from));
+    } else if week {
+        println!("Total       {pomodoros:>3} pomodoros  {}", duration(focus));
+    }
+    Ok(())
+}
+
+fn parse(args: &[String]) -> Result<Command, String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["start"] => Ok(Command::Start),
+        ["status"] => Ok(Command::Status),
+        ["stats"] => Ok(Command::Stats { week: false }),
+        ["stats", "--week"] => Ok(Command::Stats { week: true }),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// Runs the command in `args`, without the program name.
+pub fn run(args: &[String]) -> Result<(), String> {
+    match parse(args)? {
+        Command::Start => start(&open_storage()?),
+        Command::Status => status(&open_storage()?),
+        Command::Stats { week } => stats(&open_storage()?, week),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(args: &[&str]) -> Result<Command, String> {
+        parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    fn timer_data(display_mode: DisplayMode) -> TimerData {
+        let mut data = TimerManager::new(TimerConfig::default())
+            .get_data()
+            .unwrap();
+        data.display_mode = display_mode;
+        data
+    }
+
+    #[test]
+    fn commands_are_parsed_and_anything_else_gets_the_usage() {
+        assert_eq!(parsed(&["start"]), Ok(Command::Start));
+        assert_eq!(parsed(&["status"]), Ok(Command::Status));
+        assert_eq!(parsed(&["stats"]), Ok(Command::Stats { week: false }));
+        assert_eq!(
+            parsed(&["stats", "--week"]),
+            Ok(Command::Stats { week: true })
+        );
+        let unknown: [&[&str]; 4] = [&[], &["stop"], &["start", "now"], &["stats", "--month"]];
+        for args in unknown {
+            assert_eq!(parsed(args), Err(USAGE.to_string()));
+        }
+    }
+
+    #[test]
+    fn the_status_line_follows_the_display_mode() {
+        let mut data = timer_data(DisplayMode::Countdown);
+        assert_eq!(
+            status_line(&data),
+            format!("Work · {} · 25:00 left", data.state)
+        );
+        data.display_mode = DisplayMode::CountUp;
+        data.elapsed_time = 65;
+        assert_eq!(
+            status_line(&data),
+            format!("Work · {} · 1:05 elapsed", data.state)
+        );
+        data.display_mode = DisplayMode::Percent;
+        data.percent_complete = 40;
+        assert_eq!(
+            status_line(&data),
+            format!("Work · {} · 40% done", data.state)
+        );
+    }
+
+    #[test]
+    fn stopwatches_and_overtime_ignore_the_display_mode() {
+        let mut data = timer_data(DisplayMode::Percent);
+        data.state = TimerState::Stopwatch;
+        data.total_time = 90;
+        assert_eq!(status_line(&data), format!("Work · {} · 1:30", data.state));
+        data.state = TimerState::Overtime;
+        data.overtime = 5;
+        assert_eq!(status_line(&data), format!("Work · {} · +0:05", data.state));
+    }
+}
@@ -0,0 +1,69 @@
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+
+use super::file_access::FileAccessError;
+use super::CommandResult;
+use crate::services::artifacts::{self, ArtifactVersion, TrackedArtifact};
+use crate::services::command_trace::traced;
+use crate::services::storage::StorageService;
+use crate::services::tasks::TaskManager;
+
+/// Asks the user for a file to capture at session boundaries, filed under
+/// `task_id`. Picking a file that is already tracked moves it to the task.
+/// Returns `None` if cancelled.
+#[tauri::command]
+pub async fn choose_artifact_file(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    tasks: State<'_, TaskManager>,
+    task_id: Option<String>,
+) -> CommandResult<Option<TrackedArtifact>> {
+    traced(&app, "choose_artifact_file", async {
+        pick_artifact(&app, &storage, &tasks, task_id).into()
+    })
+    .await
+}
+
+fn pick_artifact(
+    app: &AppHandle,
+    storage: &StorageService,
+    tasks: &TaskManager,
+    task_id: Option<String>,
+) -> Result<Option<TrackedArtifact>, FileAccessError> {
+    if let Some(task_id) = &task_id {
+        tasks.find(task_id)?;
+    }
+    let Some(picked) = app.dialog().file().blocking_pick_file() else {
+        return Ok(None);
+    };
+    let path = picked
+        .into_path()
+        .and_then(|path| Ok(path.canonicalize()?))
+        .map_err(|e| FileAccessError::Invalid {
+            message: format!("Unsupported file location: {e}"),
+        })?;
+    Ok(Some(artifacts::track(storage, &path, task_id)?))
+}
+
+#[tauri::command]
+pub fn get_tracked_artifacts(
+    storage: State<'_, StorageService>,
+) -> CommandResult<Vec<TrackedArtifact>> {
+    artifacts::load_tracked(&storage).into()
+}
+
+/// Stops capturing a file. Versions already captured are kept.
+#[tauri::command]
+pub fn untrack_artifact(storage: State<'_, StorageService>, id: String) -> CommandResult<()> {
+    artifacts::untrack(&storage, &id).into()
+}
+
+/// Captured versions for `task_id`, or for every file if it is omitted,
+/// oldest first.
+#[tauri::command]
+pub fn get_artifact_timeline(
+    storage: State<'_, StorageService>,
+    task_id: Option<String>,
+) -> CommandResult<Vec<ArtifactVersion>> {
+    artifacts::timeline(&storage, task_id.as_deref()).into()
+}
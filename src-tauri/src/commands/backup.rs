@@ -0,0 +1,171 @@
+use tauri::{AppHandle, State};
+
+use super::file_access::FileAccessError;
+use super::files::{authorize_user_path, BACKUP_EXTENSION};
+use super::settings_validation::MAX_KEPT_BACKUPS;
+use super::validation::{check_range, Validate, Validated, ValidationError};
+use super::{CommandError, CommandResult};
+use crate::services::backup::{self, BackupCadence, BackupFile, BackupScheduler};
+use crate::services::backup_crypto::MIN_PASSPHRASE_CHARS;
+use crate::services::backup_format::{BackupInfo, RestoreSelection};
+use crate::services::migrations::MigrationRecord;
+use crate::services::path_grants::PathGrants;
+use crate::services::preferences::configure_services;
+use crate::services::storage::{StorageService, UserPreferences};
+use crate::services::tasks::TaskManager;
+
+/// Turns automatic backups on or off and sets how often they are taken and
+/// how many are kept. The directory is chosen with `choose_auto_backup_dir`.
+#[tauri::command]
+pub fn configure_auto_backup(
+    storage: State<'_, StorageService>,
+    scheduler: State<'_, BackupScheduler>,
+    enabled: bool,
+    cadence: BackupCadence,
+    keep: u32,
+) -> CommandResult<UserPreferences> {
+    check_range("keep", keep, 1, MAX_KEPT_BACKUPS)
+        .map_err(CommandError::from)
+        .and_then(|()| {
+            Ok(storage.update_preferences(|preferences| {
+                preferences.auto_backup.enabled = enabled;
+                preferences.auto_backup.cadence = cadence;
+                preferences.auto_backup.keep = keep;
+            })?)
+        })
+        .and_then(|saved| {
+            scheduler.configure(&saved)?;
+            Ok(saved)
+        })
+        .into()
+}
+
+/// The automatic backups in the configured directory, newest first.
+#[tauri::command]
+pub fn list_backups(storage: State<'_, StorageService>) -> CommandResult<Vec<BackupFile>> {
+    storage
+        .load_preferences()
+        .and_then(|preferences| backup::backup_dir(&storage, &preferences.auto_backup))
+        .and_then(|dir| backup::list_backups(&dir))
+        .into()
+}
+
+/// Every app upgrade recorded for this data, oldest first, with the backup
+/// taken before each one.
+#[tauri::command]
+pub fn get_migration_history(
+    storage: State<'_, StorageService>,
+) -> CommandResult<Vec<MigrationRecord>> {
+    storage.load_migration_history().into()
+}
+
+#[tauri::command]
+pub fn backup_data(
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    path: String,
+) -> CommandResult<()> {
+    authorize_user_path(&storage, &grants, &path, BACKUP_EXTENSION)
+        .and_then(|path| Ok(storage.backup_data(&path, None)?))
+        .into()
+}
+
+/// Like `backup_data`, but encrypts the file with `passphrase`, so backups
+/// kept in synced cloud folders aren't readable there.
+#[tauri::command]
+pub fn backup_data_encrypted(
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    path: String,
+    passphrase: String,
+) -> CommandResult<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return CommandResult::invalid(ValidationError::new(
+            "passphrase",
+            format!("must be at least {MIN_PASSPHRASE_CHARS} characters"),
+        ));
+    }
+    authorize_user_path(&storage, &grants, &path, BACKUP_EXTENSION)
+        .and_then(|path| Ok(storage.backup_data(&path, Some(&passphrase))?))
+        .into()
+}
+
+/// Describes the backup at `path` without restoring it, so the user can
+/// see what it holds and choose what to restore. Encrypted backups need
+/// their `passphrase`.
+#[tauri::command]
+pub fn inspect_backup(
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    path: String,
+    passphrase: Option<String>,
+) -> CommandResult<BackupInfo> {
+    authorize_user_path(&storage, &grants, &path, BACKUP_EXTENSION)
+        .and_then(|path| Ok(storage.inspect_backup(&path, passphrase.as_deref())?))
+        .into()
+}
+
+/// Restores the backup at `path`, or only the parts in `selection`.
+#[tauri::command]
+pub fn restore_data(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    tasks: State<'_, TaskManager>,
+    path: String,
+    selection: Option<Validated<RestoreSelection>>,
+) -> CommandResult<()> {
+    let selection = selection.map(Validated::into_inner).unwrap_or_default();
+    restore(&app, &storage, &grants, &tasks, &path, None, &selection).into()
+}
+
+/// Restores a backup written by `backup_data_encrypted`.
+#[tauri::command]
+pub fn restore_data_encrypted(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    tasks: State<'_, TaskManager>,
+    path: String,
+    passphrase: String,
+    selection: Option<Validated<RestoreSelection>>,
+) -> CommandResult<()> {
+    let selection = selection.map(Validated::into_inner).unwrap_or_default();
+    restore(
+        &app,
+        &storage,
+        &grants,
+        &tasks,
+        &path,
+        Some(&passphrase),
+        &selection,
+    )
+    .into()
+}
+
+fn restore(
+    app: &AppHandle,
+    storage: &StorageService,
+    grants: &PathGrants,
+    tasks: &TaskManager,
+    path: &str,
+    passphrase: Option<&str>,
+    selection: &RestoreSelection,
+) -> Result<(), FileAccessError> {
+    let path = authorize_user_path(storage, grants, path, BACKUP_EXTENSION)?;
+    let backup = storage.read_backup(&path, passphrase)?;
+    let invalid = |error: ValidationError| FileAccessError::Invalid {
+        message: format!("{}: {error}", path.display()),
+    };
+    if selection.preferences {
+        backup.preferences.validate().map_err(invalid)?;
+    }
+    for statistic in &backup.statistics {
+        if selection.includes(&statistic.date) {
+            statistic.validate().map_err(invalid)?;
+        }
+    }
+    storage.restore_backup(backup, selection)?;
+    configure_services(app, &storage.load_preferences()?)?;
+    Ok(tasks.replace(storage.load_tasks()?, storage.load_archived_tasks()?)?)
+}
@@ -0,0 +1,118 @@
+use tauri::{AppHandle, State};
+
+use super::validation::ValidationError;
+use super::{CommandError, CommandResult};
+use crate::services::command_trace::traced;
+use crate::services::display_client::{self, DisplayClient};
+use crate::services::display_server::{DisplayInfo, DisplayRegistry, PairingCode};
+use crate::services::storage::StorageService;
+
+const MAX_DISPLAY_NAME_CHARS: usize = 50;
+
+/// Accepts `host:port` with a DNS name, IPv4 address or bracketed IPv6
+/// address, so nothing but a host can end up in the WebSocket URL.
+fn validate_address(address: &str) -> Result<(), ValidationError> {
+    let invalid = || ValidationError::new("address", "must be host:port");
+    let (host, port) = address.rsplit_once(':').ok_or_else(invalid)?;
+    if !port.parse::<u16>().is_ok_and(|port| port != 0) {
+        return Err(invalid());
+    }
+    let host_ok = match host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        Some(ipv6) => ipv6.parse::<std::net::Ipv6Addr>().is_ok(),
+        None => {
+            !host.is_empty()
+                && host.len() <= 253
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        }
+    };
+    if host_ok {
+        Ok(())
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Shows a six-digit code for a display to pair with, valid for a few minutes.
+#[tauri::command]
+pub fn start_display_pairing(registry: State<'_, DisplayRegistry>) -> CommandResult<PairingCode> {
+    registry.start_pairing().into()
+}
+
+#[tauri::command]
+pub fn get_paired_displays(storage: State<'_, StorageService>) -> CommandResult<Vec<DisplayInfo>> {
+    storage
+        .load_paired_displays()
+        .map(|displays| displays.into_iter().map(DisplayInfo::from).collect())
+        .into()
+}
+
+/// Revokes a display; if it is connected, its stream ends within a second.
+#[tauri::command]
+pub fn unpair_display(storage: State<'_, StorageService>, id: String) -> CommandResult<()> {
+    storage
+        .load_paired_displays()
+        .and_then(|mut displays| {
+            displays.retain(|display| display.id != id);
+            storage.save_paired_displays(&displays)
+        })
+        .into()
+}
+
+/// Turns this machine into a display for the instance at `address`, pairing
+/// with the code shown there. Display mode resumes automatically on launch
+/// until [`leave_display_mode`] is called.
+#[tauri::command]
+pub async fn enter_display_mode(
+    app: AppHandle,
+    client: State<'_, DisplayClient>,
+    storage: State<'_, StorageService>,
+    address: String,
+    code: String,
+    name: String,
+) -> CommandResult<()> {
+    traced(&app, "enter_display_mode", async {
+        pair_and_connect(&app, &client, &storage, address, code, name)
+            .await
+            .into()
+    })
+    .await
+}
+
+async fn pair_and_connect(
+    app: &AppHandle,
+    client: &DisplayClient,
+    storage: &StorageService,
+    address: String,
+    code: String,
+    name: String,
+) -> Result<(), CommandError> {
+    validate_address(&address)?;
+    if code.len() != 6 || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ValidationError::new("code", "must be six digits").into());
+    }
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_DISPLAY_NAME_CHARS {
+        return Err(ValidationError::new(
+            "name",
+            format!("must be 1-{MAX_DISPLAY_NAME_CHARS} characters"),
+        )
+        .into());
+    }
+
+    let connection = display_client::pair(&address, code, name).await?;
+    storage.save_display_connection(Some(&connection))?;
+    Ok(client.connect(app.clone(), connection)?)
+}
+
+#[tauri::command]
+pub fn leave_display_mode(
+    client: State<'_, DisplayClient>,
+    storage: State<'_, StorageService>,
+) -> CommandResult<()> {
+    client
+        .disconnect()
+        .and_then(|()| storage.save_display_connection(None))
+        .into()
+}
@@ -0,0 +1,73 @@
+use tauri::{AppHandle, State};
+
+use super::settings_validation::{validate_block_actions, validate_blocklist};
+use super::CommandResult;
+use crate::services::distraction_blocker::{BlockAction, BlocklistStatus, DistractionBlocker};
+use crate::services::storage::{StorageService, UserPreferences};
+
+/// The blocked domains, whether blocking is on, and whether it is in
+/// effect now.
+#[tauri::command]
+pub fn get_blocklist(blocker: State<'_, DistractionBlocker>) -> CommandResult<BlocklistStatus> {
+    blocker.status().into()
+}
+
+/// Replaces the domains blocked during work sessions. Blocking in effect
+/// is lifted and started again with the new list.
+#[tauri::command]
+pub fn set_blocklist(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    blocker: State<'_, DistractionBlocker>,
+    domains: Vec<String>,
+) -> CommandResult<UserPreferences> {
+    if let Err(error) = validate_blocklist(&domains) {
+        return CommandResult::invalid(error);
+    }
+    storage
+        .update_preferences(|preferences| preferences.distraction_blocker.blocklist = domains)
+        .and_then(|saved| {
+            blocker.configure(&app, &saved)?;
+            Ok(saved)
+        })
+        .into()
+}
+
+/// Turns blocking during work sessions on or off. Turning it off lifts
+/// blocking in effect right away.
+#[tauri::command]
+pub fn set_blocking_enabled(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    blocker: State<'_, DistractionBlocker>,
+    enabled: bool,
+) -> CommandResult<UserPreferences> {
+    storage
+        .update_preferences(|preferences| preferences.distraction_blocker.enabled = enabled)
+        .and_then(|saved| {
+            blocker.configure(&app, &saved)?;
+            Ok(saved)
+        })
+        .into()
+}
+
+/// Replaces how distractions are blocked. Shell actions run commands, so
+/// they change only through this command, after the user confirmed.
+#[tauri::command]
+pub fn set_block_actions(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    blocker: State<'_, DistractionBlocker>,
+    actions: Vec<BlockAction>,
+) -> CommandResult<UserPreferences> {
+    if let Err(error) = validate_block_actions(&actions) {
+        return CommandResult::invalid(error);
+    }
+    storage
+        .update_preferences(|preferences| preferences.distraction_blocker.actions = actions)
+        .and_then(|saved| {
+            blocker.configure(&app, &saved)?;
+            Ok(saved)
+        })
+        .into()
+}
@@ -0,0 +1,62 @@
+use tauri::State;
+
+use super::validation::{Validate, ValidationError};
+use super::CommandResult;
+use crate::services::end_of_day::{EndOfDayConfig, PushState, PushTarget};
+use crate::services::storage::StorageService;
+
+fn is_identifier(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 64
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl Validate for EndOfDayConfig {
+    fn validate(&self) -> Result<(), ValidationError> {
+        for (index, destination) in self.destinations.iter().enumerate() {
+            if !is_identifier(&destination.id) {
+                return Err(ValidationError::new(
+                    "end_of_day.destinations.id",
+                    "must be letters, digits, '-' or '_'",
+                ));
+            }
+            if self.destinations[..index]
+                .iter()
+                .any(|other| other.id == destination.id)
+            {
+                return Err(ValidationError::new(
+                    "end_of_day.destinations.id",
+                    format!("{} is used twice", destination.id),
+                ));
+            }
+            match &destination.target {
+                PushTarget::Webhook { url } => {
+                    if !(url.starts_with("https://") || url.starts_with("http://")) {
+                        return Err(ValidationError::new(
+                            "end_of_day.destinations.url",
+                            "must be an http(s) URL",
+                        ));
+                    }
+                }
+                PushTarget::FileDrop => {}
+                PushTarget::Queue { name } => {
+                    if !is_identifier(name) {
+                        return Err(ValidationError::new(
+                            "end_of_day.destinations.name",
+                            "must be letters, digits, '-' or '_'",
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Last day pushed to every destination and the most recent failure.
+#[tauri::command]
+pub fn get_end_of_day_status(storage: State<'_, StorageService>) -> CommandResult<PushState> {
+    storage.load_push_state().into()
+}
@@ -0,0 +1,25 @@
+use tauri::State;
+
+use super::validation::{validate_date_range, IsoDate};
+use super::CommandResult;
+use crate::services::event_log::TimerEvent;
+use crate::services::storage::StorageService;
+
+/// Timer transitions on the days `from..=to`, oldest first. Either
+/// end may be omitted.
+#[tauri::command]
+pub fn load_event_log(
+    storage: State<'_, StorageService>,
+    from: Option<IsoDate>,
+    to: Option<IsoDate>,
+) -> CommandResult<Vec<TimerEvent>> {
+    if let Err(error) = validate_date_range(from.as_ref(), to.as_ref()) {
+        return CommandResult::invalid(error);
+    }
+    storage
+        .load_events(
+            from.as_ref().map(IsoDate::to_naive),
+            to.as_ref().map(IsoDate::to_naive),
+        )
+        .into()
+}
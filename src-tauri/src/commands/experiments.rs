@@ -0,0 +1,77 @@
+use tauri::State;
+
+use super::validation::Validate;
+use super::{CommandError, CommandResult};
+use crate::services::calendar;
+use crate::services::experiments::{self, Experiment, ExperimentArm, ExperimentReport};
+use crate::services::storage::StorageService;
+use crate::services::timer_state::TimerManager;
+
+/// Starts alternating between the two `arms` daily for `days` days from
+/// today, replacing any earlier experiment. Each arm's configuration applies
+/// from the next session start.
+#[tauri::command]
+pub fn start_experiment(
+    timer: State<'_, TimerManager>,
+    storage: State<'_, StorageService>,
+    name: String,
+    arms: Vec<ExperimentArm>,
+    days: u32,
+) -> CommandResult<Experiment> {
+    let start_date = match calendar::today(&storage) {
+        Ok(today) => today,
+        Err(error) => return CommandResult::err(error),
+    };
+    let experiment = Experiment {
+        name,
+        arms,
+        start_date,
+        days,
+    };
+    if let Err(error) = experiment.validate() {
+        return CommandResult::invalid(error);
+    }
+    storage
+        .update_preferences(|preferences| preferences.experiment = Some(experiment.clone()))
+        .and_then(|saved| {
+            timer.configure(&saved)?;
+            Ok(experiment)
+        })
+        .into()
+}
+
+/// Ends the experiment early and drops it, along with its report; the
+/// tagged sessions remain. A finished experiment needs no stopping.
+#[tauri::command]
+pub fn stop_experiment(
+    timer: State<'_, TimerManager>,
+    storage: State<'_, StorageService>,
+) -> CommandResult<()> {
+    storage
+        .update_preferences(|preferences| preferences.experiment = None)
+        .and_then(|saved| Ok(timer.configure(&saved)?))
+        .into()
+}
+
+/// How the arms of the current or last experiment compare so far, or
+/// `None` without one.
+#[tauri::command]
+pub fn get_experiment_report(
+    storage: State<'_, StorageService>,
+) -> CommandResult<Option<ExperimentReport>> {
+    experiment_report(&storage).into()
+}
+
+fn experiment_report(storage: &StorageService) -> Result<Option<ExperimentReport>, CommandError> {
+    let preferences = storage.load_preferences()?;
+    let Some(experiment) = preferences.experiment else {
+        return Ok(None);
+    };
+    let statistics = storage.load_statistics(experiment.start(), experiment.end())?;
+    Ok(Some(experiments::report(
+        &experiment,
+        &statistics,
+        &preferences.timer_config.custom_session_types,
+        preferences.calendar.today(),
+    )))
+}
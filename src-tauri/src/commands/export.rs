@@ -0,0 +1,88 @@
+use tauri::State;
+
+use super::file_access::FileAccessError;
+use super::files::{authorize_user_path, ExportFormat};
+use super::validation::{validate_date_range, IsoDate};
+use super::CommandResult;
+use crate::services::ics_export;
+use crate::services::path_grants::PathGrants;
+use crate::services::sqlite_export::{self, SqliteExportSummary};
+use crate::services::storage::StorageService;
+use crate::services::tasks::TaskManager;
+
+/// Writes the statistics in the (inclusive, optional) date range plus all
+/// tasks to a standalone SQLite file for offline analysis.
+#[tauri::command]
+pub fn export_sqlite(
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    tasks: State<'_, TaskManager>,
+    path: String,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> CommandResult<SqliteExportSummary> {
+    if let Err(error) = validate_date_range(start_date.as_ref(), end_date.as_ref()) {
+        return CommandResult::invalid(error);
+    }
+    write_sqlite(&storage, &grants, &tasks, &path, start_date, end_date).into()
+}
+
+fn write_sqlite(
+    storage: &StorageService,
+    grants: &PathGrants,
+    tasks: &TaskManager,
+    path: &str,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> Result<SqliteExportSummary, FileAccessError> {
+    let path = authorize_user_path(storage, grants, path, ExportFormat::Sqlite.extension())?;
+    let statistics = storage.load_statistics(
+        start_date.as_ref().map(IsoDate::to_naive),
+        end_date.as_ref().map(IsoDate::to_naive),
+    )?;
+    Ok(sqlite_export::export_sqlite(
+        &path,
+        &statistics,
+        &tasks.list_with_archived()?,
+    )?)
+}
+
+/// Writes the completed focus sessions in the (inclusive, optional) date
+/// range to an iCalendar file, one event per session. Returns the number of
+/// events written.
+#[tauri::command]
+pub fn export_sessions_ics(
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    tasks: State<'_, TaskManager>,
+    path: String,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> CommandResult<usize> {
+    if let Err(error) = validate_date_range(start_date.as_ref(), end_date.as_ref()) {
+        return CommandResult::invalid(error);
+    }
+    write_ics(&storage, &grants, &tasks, &path, start_date, end_date).into()
+}
+
+fn write_ics(
+    storage: &StorageService,
+    grants: &PathGrants,
+    tasks: &TaskManager,
+    path: &str,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> Result<usize, FileAccessError> {
+    let path = authorize_user_path(storage, grants, path, ExportFormat::Ics.extension())?;
+    let statistics = storage.load_statistics(
+        start_date.as_ref().map(IsoDate::to_naive),
+        end_date.as_ref().map(IsoDate::to_naive),
+    )?;
+    Ok(ics_export::export_ics(
+        &path,
+        &statistics,
+        &tasks.list_with_archived()?,
+        &storage.custom_session_types()?,
+        chrono::Utc::now().timestamp().max(0) as u64,
+    )?)
+}
@@ -0,0 +1,191 @@
+//! Path checks for files the webview asks the backend to read or write.
+
+use serde::Serialize;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use ts_rs::TS;
+
+use super::validation::ValidationError;
+use crate::services::storage_file::StorageError;
+
+/// Why a file path from the webview was refused or could not be used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FileAccessError {
+    /// The path is malformed (relative, traversal, wrong extension, ...).
+    Invalid { message: String },
+    /// The path is well-formed but the user never approved it.
+    PermissionDenied { path: String },
+    /// The path was approved but the operation on it failed.
+    Failed { message: String },
+    /// The path was approved but reading or writing its data failed;
+    /// `code` is a [`StorageError`] code such as `not_found` or `io`.
+    Storage { code: String, message: String },
+}
+
+impl FileAccessError {
+    /// Stable, snake_case identifier of the failure.
+    pub fn code(&self) -> &str {
+        match self {
+            FileAccessError::Invalid { .. } => "invalid_input",
+            FileAccessError::PermissionDenied { .. } => "permission_denied",
+            FileAccessError::Failed { .. } => "failed",
+            FileAccessError::Storage { code, .. } => code,
+        }
+    }
+}
+
+impl fmt::Display for FileAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileAccessError::Invalid { message }
+            | FileAccessError::Failed { message }
+            | FileAccessError::Storage { message, .. } => f.write_str(message),
+            FileAccessError::PermissionDenied { path } => {
+                write!(f, "Access to {path} has not been granted")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FileAccessError {}
+
+impl From<ValidationError> for FileAccessError {
+    fn from(error: ValidationError) -> Self {
+        FileAccessError::Invalid {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<StorageError> for FileAccessError {
+    fn from(error: StorageError) -> Self {
+        FileAccessError::Storage {
+            code: error.code().to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<String> for FileAccessError {
+    fn from(message: String) -> Self {
+        FileAccessError::Failed { message }
+    }
+}
+
+/// Checks that `path` names a file with the given `extension` in an existing
+/// directory and returns it with the directory canonicalized.
+///
+/// Traversal components and symlinked targets are rejected so a path can't
+/// be dressed up to escape a directory it appears to be in.
+pub fn resolve_data_path(path: &str, extension: &str) -> Result<PathBuf, ValidationError> {
+    let candidate = Path::new(path);
+    if !candidate.is_absolute() {
+        return Err(ValidationError::new("path", "must be absolute"));
+    }
+    if candidate
+        .components()
+        .any(|component| matches!(component, Component::ParentDir))
+    {
+        return Err(ValidationError::new("path", "must not contain '..'"));
+    }
+    if candidate.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+        return Err(ValidationError::new(
+            "path",
+            format!("must be a .{extension} file"),
+        ));
+    }
+
+    let (Some(parent), Some(file_name)) = (candidate.parent(), candidate.file_name()) else {
+        return Err(ValidationError::new("path", "must name a file"));
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|_| ValidationError::new("path", "directory does not exist"))?;
+
+    let resolved = parent.join(file_name);
+    if resolved
+        .symlink_metadata()
+        .is_ok_and(|meta| meta.file_type().is_symlink())
+    {
+        return Err(ValidationError::new("path", "must not be a symlink"));
+    }
+    Ok(resolved)
+}
+
+/// Resolves `path` and checks that the user approved it, either as an
+/// individually granted file or by living under one of `allowed_roots`.
+pub fn authorize_data_path(
+    path: &str,
+    extension: &str,
+    allowed_roots: &[PathBuf],
+    is_granted: impl Fn(&Path) -> bool,
+) -> Result<PathBuf, FileAccessError> {
+    let resolved = resolve_data_path(path, extension)?;
+    let inside_allowed_root = allowed_roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+    if !inside_allowed_root && !is_granted(&resolved) {
+        return Err(FileAccessError::PermissionDenied {
+            path: resolved.display().to_string(),
+        });
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn data_paths_stay_inside_allowed_roots(segments in prop::collection::vec("[a-z.]{1,6}", 1..5)) {
+            let dir = tempfile::tempdir().unwrap();
+            let root = dir.path().to_path_buf();
+            let path = format!("{}/{}.json", root.display(), segments.join("/"));
+
+            if let Ok(resolved) = authorize_data_path(&path, "json", std::slice::from_ref(&root), |_| false) {
+                prop_assert!(resolved.starts_with(root.canonicalize().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn data_paths_outside_roots_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let allowed = [root.clone()];
+        let deny = |_: &Path| false;
+
+        assert!(authorize_data_path("relative.json", "json", &allowed, deny).is_err());
+        let traversal = format!("{}/../escape.json", root.display());
+        assert!(matches!(
+            authorize_data_path(&traversal, "json", &allowed, deny),
+            Err(FileAccessError::Invalid { .. })
+        ));
+        let not_json = format!("{}/backup.txt", root.display());
+        assert!(authorize_data_path(&not_json, "json", &allowed, deny).is_err());
+        let ok = format!("{}/backup.json", root.display());
+        assert!(authorize_data_path(&ok, "json", &allowed, deny).is_ok());
+    }
+
+    #[test]
+    fn unapproved_paths_are_denied_unless_granted() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path();
+        let path = format!("{}/picked.json", outside.display());
+        let granted = resolve_data_path(&path, "json").unwrap();
+
+        assert!(matches!(
+            authorize_data_path(&path, "json", &[], |_| false),
+            Err(FileAccessError::PermissionDenied { .. })
+        ));
+        assert_eq!(
+            authorize_data_path(&path, "json", &[], |candidate| candidate == granted),
+            Ok(granted.clone())
+        );
+    }
+}
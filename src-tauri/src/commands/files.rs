@@ -0,0 +1,231 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, FilePath};
+use ts_rs::TS;
+
+use super::file_access::{authorize_data_path, resolve_data_path, FileAccessError};
+use super::CommandResult;
+use crate::services::backup::BackupScheduler;
+use crate::services::command_trace::traced;
+use crate::services::door_status::DoorStatusWriter;
+use crate::services::path_grants::PathGrants;
+use crate::services::storage::{StorageService, UserPreferences};
+
+pub const BACKUP_EXTENSION: &str = "json";
+
+/// File formats that can be written through `choose_export_destination`.
+#[derive(Debug, Clone, Copy, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Sqlite,
+    Pdf,
+    Ics,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Sqlite => "sqlite",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Ics => "ics",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ExportFormat::Sqlite => "SQLite database",
+            ExportFormat::Pdf => "PDF document",
+            ExportFormat::Ics => "iCalendar file",
+        }
+    }
+}
+
+/// Resolves a path the webview wants to read or write and checks that the
+/// user approved it: picked through one of the dialogs below, or inside the
+/// configured export or automatic backup directory or the app's own backup
+/// directory.
+pub fn authorize_user_path(
+    storage: &StorageService,
+    grants: &PathGrants,
+    path: &str,
+    extension: &str,
+) -> Result<PathBuf, FileAccessError> {
+    let preferences = storage.load_preferences()?;
+    let mut roots = vec![storage.backup_dir()?];
+    roots.extend(preferences.export_dir.map(PathBuf::from));
+    roots.extend(preferences.auto_backup.dir.map(PathBuf::from));
+    authorize_data_path(path, extension, &roots, |candidate| {
+        grants.is_granted(candidate)
+    })
+}
+
+fn grant_picked(
+    grants: &PathGrants,
+    picked: Option<FilePath>,
+    extension: &str,
+) -> Result<Option<String>, FileAccessError> {
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let path = picked.into_path().map_err(|e| FileAccessError::Invalid {
+        message: format!("Unsupported file location: {e}"),
+    })?;
+    let resolved = resolve_data_path(&path.to_string_lossy(), extension)?;
+    grants.grant(resolved.clone())?;
+    Ok(Some(resolved.display().to_string()))
+}
+
+/// Asks for a directory and stores it in the preferences with `set`.
+/// Returns it with the saved preferences, or `None` if cancelled.
+fn choose_dir(
+    app: &AppHandle,
+    set: impl FnOnce(&mut UserPreferences, String),
+) -> Result<Option<(String, UserPreferences)>, FileAccessError> {
+    let Some(picked) = app.dialog().file().blocking_pick_folder() else {
+        return Ok(None);
+    };
+    let dir = picked
+        .into_path()
+        .and_then(|dir| Ok(dir.canonicalize()?))
+        .map_err(|e| FileAccessError::Invalid {
+            message: format!("Unsupported directory: {e}"),
+        })?;
+    let dir = dir.display().to_string();
+    let saved = app
+        .state::<StorageService>()
+        .update_preferences(|prefs| set(prefs, dir.clone()))?;
+    Ok(Some((dir, saved)))
+}
+
+/// Asks the user where to write a backup. Returns `None` if cancelled.
+#[tauri::command]
+pub async fn choose_backup_destination(app: AppHandle) -> CommandResult<Option<String>> {
+    traced(&app, "choose_backup_destination", async {
+        let picked = app
+            .dialog()
+            .file()
+            .add_filter("Tempus Ring backup", &[BACKUP_EXTENSION])
+            .set_file_name("tempus-ring-backup.json")
+            .blocking_save_file();
+        grant_picked(&app.state::<PathGrants>(), picked, BACKUP_EXTENSION).into()
+    })
+    .await
+}
+
+/// Asks the user which backup to restore. Returns `None` if cancelled.
+#[tauri::command]
+pub async fn choose_restore_source(app: AppHandle) -> CommandResult<Option<String>> {
+    traced(&app, "choose_restore_source", async {
+        let picked = app
+            .dialog()
+            .file()
+            .add_filter("Tempus Ring backup", &[BACKUP_EXTENSION])
+            .blocking_pick_file();
+        grant_picked(&app.state::<PathGrants>(), picked, BACKUP_EXTENSION).into()
+    })
+    .await
+}
+
+/// Asks the user where to write an export of the given format. Returns
+/// `None` if cancelled.
+#[tauri::command]
+pub async fn choose_export_destination(
+    app: AppHandle,
+    format: ExportFormat,
+) -> CommandResult<Option<String>> {
+    traced(&app, "choose_export_destination", async {
+        let extension = format.extension();
+        let picked = app
+            .dialog()
+            .file()
+            .add_filter(format.description(), &[extension])
+            .set_file_name(format!("tempus-ring-export.{extension}"))
+            .blocking_save_file();
+        grant_picked(&app.state::<PathGrants>(), picked, extension).into()
+    })
+    .await
+}
+
+/// Lets the user pick a directory that backups and exports may always be
+/// written to. This is the only way the export directory can be changed.
+#[tauri::command]
+pub async fn choose_export_dir(app: AppHandle) -> CommandResult<Option<String>> {
+    traced(&app, "choose_export_dir", async {
+        choose_dir(&app, |prefs, dir| prefs.export_dir = Some(dir))
+            .map(|chosen| chosen.map(|(dir, _)| dir))
+            .into()
+    })
+    .await
+}
+
+/// Lets the user pick the directory automatic backups are written to. This
+/// is the only way it can be changed.
+#[tauri::command]
+pub async fn choose_auto_backup_dir(app: AppHandle) -> CommandResult<Option<String>> {
+    traced(&app, "choose_auto_backup_dir", async {
+        choose_dir(&app, |prefs, dir| prefs.auto_backup.dir = Some(dir))
+            .and_then(|chosen| {
+                let Some((dir, saved)) = chosen else {
+                    return Ok(None);
+                };
+                app.state::<BackupScheduler>().configure(&saved)?;
+                Ok(Some(dir))
+            })
+            .into()
+    })
+    .await
+}
+
+/// Lets the user pick the folder the door status page is written to. This
+/// is the only way it can be changed.
+#[tauri::command]
+pub async fn choose_door_status_dir(app: AppHandle) -> CommandResult<Option<String>> {
+    traced(&app, "choose_door_status_dir", async {
+        choose_dir(&app, |prefs, dir| prefs.door_status.dir = Some(dir))
+            .and_then(|chosen| {
+                let Some((dir, saved)) = chosen else {
+                    return Ok(None);
+                };
+                app.state::<DoorStatusWriter>().configure(&app, &saved)?;
+                Ok(Some(dir))
+            })
+            .into()
+    })
+    .await
+}
+
+/// Asks the user where to write the dashboard feed. This is the only way the
+/// feed path can be changed. Returns `None` if cancelled.
+#[tauri::command]
+pub async fn choose_dashboard_feed_path(app: AppHandle) -> CommandResult<Option<String>> {
+    traced(&app, "choose_dashboard_feed_path", async {
+        let picked = app
+            .dialog()
+            .file()
+            .add_filter("JSON", &["json"])
+            .set_file_name("tempus-ring-feed.json")
+            .blocking_save_file();
+        set_dashboard_feed_path(&app, picked).into()
+    })
+    .await
+}
+
+fn set_dashboard_feed_path(
+    app: &AppHandle,
+    picked: Option<FilePath>,
+) -> Result<Option<String>, FileAccessError> {
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let path = picked.into_path().map_err(|e| FileAccessError::Invalid {
+        message: format!("Unsupported file location: {e}"),
+    })?;
+    let path = resolve_data_path(&path.to_string_lossy(), "json")?
+        .display()
+        .to_string();
+    app.state::<StorageService>()
+        .update_preferences(|prefs| prefs.dashboard_feed.path = Some(path.clone()))?;
+    Ok(Some(path))
+}
@@ -0,0 +1,45 @@
+use tauri::State;
+
+use super::CommandResult;
+use crate::services::storage::StorageService;
+use crate::services::tasks::TaskManager;
+use crate::services::timer_state::TimerManager;
+use crate::services::undo::{HistoryStatus, UndoContext, UndoManager};
+
+#[tauri::command]
+pub fn get_undo_status(
+    undo: State<'_, UndoManager>,
+    storage: State<'_, StorageService>,
+) -> CommandResult<HistoryStatus> {
+    undo.status(&storage).into()
+}
+
+#[tauri::command]
+pub fn undo(
+    undo: State<'_, UndoManager>,
+    storage: State<'_, StorageService>,
+    tasks: State<'_, TaskManager>,
+    timer: State<'_, TimerManager>,
+) -> CommandResult<HistoryStatus> {
+    let ctx = UndoContext {
+        storage: &storage,
+        tasks: &tasks,
+        timer: &timer,
+    };
+    undo.undo(&ctx).into()
+}
+
+#[tauri::command]
+pub fn redo(
+    undo: State<'_, UndoManager>,
+    storage: State<'_, StorageService>,
+    tasks: State<'_, TaskManager>,
+    timer: State<'_, TimerManager>,
+) -> CommandResult<HistoryStatus> {
+    let ctx = UndoContext {
+        storage: &storage,
+        tasks: &tasks,
+        timer: &timer,
+    };
+    undo.redo(&ctx).into()
+}
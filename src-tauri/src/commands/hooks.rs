@@ -0,0 +1,31 @@
+use tauri::State;
+
+use super::settings_validation::validate_hooks;
+use super::CommandResult;
+use crate::services::hooks::{HookRunner, HookStatus, TimerHook};
+use crate::services::storage::{StorageService, UserPreferences};
+
+/// The configured hooks and their latest runs, with exit codes and output.
+#[tauri::command]
+pub fn get_hooks(runner: State<'_, HookRunner>) -> CommandResult<HookStatus> {
+    runner.status().into()
+}
+
+/// Replaces the shell commands run on timer transitions.
+#[tauri::command]
+pub fn set_hooks(
+    storage: State<'_, StorageService>,
+    runner: State<'_, HookRunner>,
+    hooks: Vec<TimerHook>,
+) -> CommandResult<UserPreferences> {
+    if let Err(error) = validate_hooks(&hooks) {
+        return CommandResult::invalid(error);
+    }
+    storage
+        .update_preferences(|preferences| preferences.hooks = hooks)
+        .and_then(|saved| {
+            runner.configure(&saved)?;
+            Ok(saved)
+        })
+        .into()
+}
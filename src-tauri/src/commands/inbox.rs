@@ -0,0 +1,46 @@
+use tauri::State;
+
+use super::CommandResult;
+use crate::services::inbox::InboxEntry;
+use crate::services::service_health::{ServiceHealth, ServiceStatus};
+use crate::services::storage::StorageService;
+
+/// Inbox entries, newest first, optionally only the unread ones.
+#[tauri::command]
+pub fn list_inbox(
+    storage: State<'_, StorageService>,
+    unread_only: Option<bool>,
+) -> CommandResult<Vec<InboxEntry>> {
+    storage
+        .load_inbox()
+        .map(|entries| {
+            let unread_only = unread_only.unwrap_or(false);
+            entries
+                .into_iter()
+                .rev()
+                .filter(|entry| !unread_only || !entry.read)
+                .collect()
+        })
+        .into()
+}
+
+/// Marks the entries with `ids` as read; unknown ids are ignored. Returns
+/// how many entries are still unread.
+#[tauri::command]
+pub fn mark_read(storage: State<'_, StorageService>, ids: Vec<String>) -> CommandResult<usize> {
+    storage
+        .update_inbox(|entries| {
+            for entry in entries.iter_mut().filter(|entry| ids.contains(&entry.id)) {
+                entry.read = true;
+            }
+        })
+        .map(|entries| entries.iter().filter(|entry| !entry.read).count())
+        .into()
+}
+
+/// How each background integration has fared since launch. Services that
+/// haven't run yet are omitted.
+#[tauri::command]
+pub fn get_service_health(health: State<'_, ServiceHealth>) -> CommandResult<Vec<ServiceStatus>> {
+    health.statuses().into()
+}
@@ -0,0 +1,215 @@
+pub mod artifacts;
+pub mod backup;
+pub mod display;
+pub mod distraction_blocker;
+pub mod end_of_day;
+pub mod event_log;
+pub mod experiments;
+pub mod export;
+pub mod file_access;
+pub mod files;
+pub mod history;
+pub mod hooks;
+pub mod inbox;
+pub mod permissions;
+pub mod planning;
+pub mod presets;
+pub mod projection;
+pub mod review;
+pub mod schemas;
+pub mod sessions;
+pub mod settings;
+pub mod settings_validation;
+pub mod slack;
+pub mod statistics;
+pub mod surface_auth;
+pub mod task_provider;
+pub mod tasks;
+pub mod timer;
+pub mod trace;
+pub mod updater;
+pub mod validation;
+pub mod work_context;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::services::command_trace;
+use crate::services::revision::{MutationError, RevisionConflict};
+use crate::services::storage_file::StorageError;
+use crate::services::timer_error::TimerError;
+use file_access::FileAccessError;
+use validation::ValidationError;
+
+/// Uniform response envelope for commands.
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct CommandResult<T> {
+    #[serde(serialize_with = "command_trace::note_success")]
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+    /// Machine-readable reason for the failure, such as `not_running`,
+    /// `not_found`, `io`, `revision_conflict` or `invalid_input`, and
+    /// `failed` for anything without a more specific reason. Set on every
+    /// failure; branch on this rather than on `error`, which is meant for
+    /// display.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "command_trace::note_code"
+    )]
+    #[ts(optional)]
+    pub code: Option<String>,
+    /// Set when the mutation was rejected because the caller's revision was stale.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub conflict: Option<RevisionConflict>,
+    /// Set when the input was rejected, naming the offending field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub validation: Option<ValidationError>,
+}
+
+impl<T> CommandResult<T> {
+    pub fn ok(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+            code: None,
+            conflict: None,
+            validation: None,
+        }
+    }
+
+    pub fn err(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(error.into()),
+            code: Some("failed".to_string()),
+            conflict: None,
+            validation: None,
+        }
+    }
+
+    /// A failure the frontend can branch on by `code`.
+    pub fn failed(code: &str, error: impl Into<String>) -> Self {
+        Self {
+            code: Some(code.to_string()),
+            ..Self::err(error)
+        }
+    }
+
+    pub fn invalid(error: ValidationError) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(error.to_string()),
+            code: Some("invalid_input".to_string()),
+            conflict: None,
+            validation: Some(error),
+        }
+    }
+}
+
+impl<T> command_trace::Traced for CommandResult<T> {
+    fn outcome(&self) -> command_trace::Outcome {
+        command_trace::Outcome {
+            success: Some(self.success),
+            code: self.code.clone(),
+        }
+    }
+}
+
+impl<T> From<Result<T, String>> for CommandResult<T> {
+    fn from(result: Result<T, String>) -> Self {
+        match result {
+            Ok(data) => Self::ok(data),
+            Err(error) => Self::err(error),
+        }
+    }
+}
+
+impl<T> From<Result<T, MutationError>> for CommandResult<T> {
+    fn from(result: Result<T, MutationError>) -> Self {
+        match result {
+            Ok(data) => Self::ok(data),
+            Err(MutationError::Conflict(conflict)) => Self {
+                success: false,
+                data: None,
+                error: Some(conflict.to_string()),
+                code: Some("revision_conflict".to_string()),
+                conflict: Some(conflict),
+                validation: None,
+            },
+            Err(MutationError::Timer(error)) => Self::failed(error.code(), error),
+            Err(MutationError::Failed(error)) => Self::err(error),
+        }
+    }
+}
+
+impl<T> From<Result<T, TimerError>> for CommandResult<T> {
+    fn from(result: Result<T, TimerError>) -> Self {
+        match result {
+            Ok(data) => Self::ok(data),
+            Err(error) => Self::failed(error.code(), error),
+        }
+    }
+}
+
+impl<T> From<Result<T, StorageError>> for CommandResult<T> {
+    fn from(result: Result<T, StorageError>) -> Self {
+        match result {
+            Ok(data) => Self::ok(data),
+            Err(error) => Self::failed(error.code(), error),
+        }
+    }
+}
+
+/// Failure inside a command that mixes validation and storage, kept
+/// apart so the [`CommandResult`] carries the matching code.
+#[derive(Debug)]
+pub enum CommandError {
+    Invalid(ValidationError),
+    Storage(StorageError),
+    Failed(String),
+}
+
+impl From<ValidationError> for CommandError {
+    fn from(error: ValidationError) -> Self {
+        CommandError::Invalid(error)
+    }
+}
+
+impl From<StorageError> for CommandError {
+    fn from(error: StorageError) -> Self {
+        CommandError::Storage(error)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Failed(message)
+    }
+}
+
+impl<T> From<Result<T, CommandError>> for CommandResult<T> {
+    fn from(result: Result<T, CommandError>) -> Self {
+        match result {
+            Ok(data) => Self::ok(data),
+            Err(CommandError::Invalid(error)) => Self::invalid(error),
+            Err(CommandError::Storage(error)) => Self::failed(error.code(), error),
+            Err(CommandError::Failed(error)) => Self::err(error),
+        }
+    }
+}
+
+impl<T> From<Result<T, FileAccessError>> for CommandResult<T> {
+    fn from(result: Result<T, FileAccessError>) -> Self {
+        match result {
+            Ok(data) => Self::ok(data),
+            Err(error) => Self::failed(error.code(), error.to_string()),
+        }
+    }
+}
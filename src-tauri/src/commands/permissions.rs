@@ -0,0 +1,40 @@
+use tauri::{AppHandle, State};
+
+use super::CommandResult;
+
+use crate::services::battery::BatteryMonitor;
+use crate::services::permissions::{
+    self, PermissionKind, PermissionReport, PermissionStatus, PlatformCapabilities,
+};
+use crate::services::presenter_mode::{PresenterMonitor, PresenterStatus};
+
+/// The status of every permission an optional feature relies on.
+#[tauri::command]
+pub fn check_permissions(app: AppHandle) -> CommandResult<Vec<PermissionReport>> {
+    permissions::check_all(&app).into()
+}
+
+/// Asks for `kind`, either through the system prompt or by opening the
+/// relevant system settings page.
+#[tauri::command]
+pub fn request_permission(app: AppHandle, kind: PermissionKind) -> CommandResult<PermissionStatus> {
+    permissions::request(&app, kind).into()
+}
+
+/// The platform and its battery state. Battery changes are also emitted as
+/// `power://status`.
+#[tauri::command]
+pub fn get_platform_capabilities(
+    battery: State<'_, BatteryMonitor>,
+) -> CommandResult<PlatformCapabilities> {
+    battery.status().map(permissions::capabilities).into()
+}
+
+/// Whether presenter mode is on, for which fullscreen app, and what it
+/// holds back. Changes are also emitted as `presenter://status`.
+#[tauri::command]
+pub fn get_presenter_status(
+    presenter: State<'_, PresenterMonitor>,
+) -> CommandResult<PresenterStatus> {
+    presenter.status().into()
+}
@@ -0,0 +1,86 @@
+use chrono::{NaiveDate, NaiveTime};
+use tauri::State;
+
+use super::validation::{validate_date_range, IsoDate, ValidationError};
+use super::CommandResult;
+use crate::services::calendar::day_key;
+use crate::services::days_off::DayOff;
+use crate::services::energy::{build_energy_plan, EnergyPlan};
+use crate::services::storage::StorageService;
+use crate::services::timeline::{build_day_timeline, DayTimeline};
+
+/// Longest range `get_days_off` lists at once.
+const MAX_DAYS_OFF_RANGE_DAYS: i64 = 366;
+
+/// Suggested deep-work and low-energy blocks for `date`, based on the
+/// user's energy template and timer durations.
+#[tauri::command]
+pub fn get_energy_plan(
+    storage: State<'_, StorageService>,
+    date: IsoDate,
+) -> CommandResult<EnergyPlan> {
+    storage
+        .load_preferences()
+        .map(|preferences| {
+            build_energy_plan(
+                date.to_naive(),
+                chrono::Local::now().naive_local(),
+                &preferences.energy_template,
+                &preferences.timer_config,
+            )
+        })
+        .into()
+}
+
+/// Planned blocks, recorded sessions, idle gaps, implicit breaks and
+/// interruptions for the statistics day `date`, which runs up to the
+/// calendar's boundary hour, ordered by time, for a planned-vs-actual strip.
+#[tauri::command]
+pub fn get_day_timeline(
+    storage: State<'_, StorageService>,
+    date: IsoDate,
+) -> CommandResult<DayTimeline> {
+    day_timeline(&storage, date.to_naive()).into()
+}
+
+fn day_timeline(storage: &StorageService, date: NaiveDate) -> Result<DayTimeline, String> {
+    let preferences = storage.load_preferences()?;
+    let plan = build_energy_plan(
+        date,
+        date.and_time(NaiveTime::MIN),
+        &preferences.energy_template,
+        &preferences.timer_config,
+    );
+    let statistic = storage.load_statistic(&day_key(date))?;
+    Ok(build_day_timeline(
+        date,
+        &plan,
+        statistic.as_ref(),
+        &preferences.break_detection,
+        &preferences.calendar,
+    ))
+}
+
+/// Vacation days and public holidays between the inclusive dates, in
+/// order, for a calendar view. At most a year at a time.
+#[tauri::command]
+pub fn get_days_off(
+    storage: State<'_, StorageService>,
+    start_date: IsoDate,
+    end_date: IsoDate,
+) -> CommandResult<Vec<DayOff>> {
+    if let Err(error) = validate_date_range(Some(&start_date), Some(&end_date)) {
+        return CommandResult::invalid(error);
+    }
+    let (start, end) = (start_date.to_naive(), end_date.to_naive());
+    if (end - start).num_days() >= MAX_DAYS_OFF_RANGE_DAYS {
+        return CommandResult::invalid(ValidationError::new(
+            "end_date",
+            format!("Days off can be listed for at most {MAX_DAYS_OFF_RANGE_DAYS} days at a time"),
+        ));
+    }
+    storage
+        .load_preferences()
+        .map(|preferences| preferences.days_off.between(start, end))
+        .into()
+}
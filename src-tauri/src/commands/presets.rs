@@ -0,0 +1,77 @@
+use tauri::State;
+
+use super::timer::{apply_config, local_only};
+use super::validation::{PresetName, Validate};
+use super::CommandResult;
+use crate::services::presets::{PresetManager, TimerPreset};
+use crate::services::storage::StorageService;
+use crate::services::timer_state::{TimerConfig, TimerData, TimerManager};
+use crate::services::timer_transport::TimerClient;
+use crate::services::undo::{UndoManager, UndoableAction};
+
+#[tauri::command]
+pub fn list_presets(presets: State<'_, PresetManager>) -> CommandResult<Vec<TimerPreset>> {
+    presets.list().into()
+}
+
+/// Saves `config` as `name`, replacing the preset of that name if there is
+/// one.
+#[tauri::command]
+pub fn save_preset(
+    presets: State<'_, PresetManager>,
+    storage: State<'_, StorageService>,
+    name: PresetName,
+    config: TimerConfig,
+) -> CommandResult<TimerPreset> {
+    if let Err(error) = config.validate() {
+        return CommandResult::invalid(error);
+    }
+    presets.save(&storage, name.into_inner(), config).into()
+}
+
+/// Switches the timer to the preset's configuration, as
+/// `update_timer_config` would. The switch can be undone.
+#[tauri::command]
+pub fn apply_preset(
+    presets: State<'_, PresetManager>,
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    storage: State<'_, StorageService>,
+    undo: State<'_, UndoManager>,
+    name: String,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    let preset = match presets.find(&name) {
+        Ok(preset) => preset,
+        Err(error) => return CommandResult::err(error),
+    };
+    let previous = match storage.load_preferences() {
+        Ok(preferences) => preferences.timer_config,
+        Err(error) => return CommandResult::err(error),
+    };
+    apply_config(&timer, &storage, preset.config.clone(), expected_revision)
+        .and_then(|data| {
+            undo.record(
+                &storage,
+                UndoableAction::ApplyPreset {
+                    name: preset.name,
+                    previous,
+                    applied: preset.config,
+                },
+            )?;
+            Ok(data)
+        })
+        .into()
+}
+
+#[tauri::command]
+pub fn delete_preset(
+    presets: State<'_, PresetManager>,
+    storage: State<'_, StorageService>,
+    name: String,
+) -> CommandResult<TimerPreset> {
+    presets.delete(&storage, &name).into()
+}
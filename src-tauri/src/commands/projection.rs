@@ -0,0 +1,82 @@
+//! Field selection for large responses, e.g. `["date", "sessions.duration"]`
+//! to drop everything else from each returned object.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use super::validation::ValidationError;
+
+/// A tree of selected fields. A field with no children is kept whole.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export, type = "string[]")]
+#[serde(try_from = "Vec<String>")]
+pub struct FieldSelection {
+    fields: BTreeMap<String, FieldSelection>,
+}
+
+impl TryFrom<Vec<String>> for FieldSelection {
+    type Error = ValidationError;
+
+    fn try_from(paths: Vec<String>) -> Result<Self, Self::Error> {
+        let mut selection = FieldSelection::default();
+        for path in &paths {
+            let valid_segment = |segment: &str| {
+                !segment.is_empty()
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            };
+            if !path.split('.').all(valid_segment) {
+                return Err(ValidationError::new(
+                    "fields",
+                    format!("'{path}' is not a dotted field path"),
+                ));
+            }
+
+            let mut node = &mut selection;
+            for segment in path.split('.') {
+                node = node.fields.entry(segment.to_string()).or_default();
+            }
+        }
+        Ok(selection)
+    }
+}
+
+impl FieldSelection {
+    /// Keeps only the selected fields of every object in `value`; arrays are
+    /// projected element by element.
+    pub fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(
+                object
+                    .into_iter()
+                    .filter_map(|(key, value)| {
+                        let child = self.fields.get(&key)?;
+                        let value = if child.fields.is_empty() {
+                            value
+                        } else {
+                            child.apply(value)
+                        };
+                        Some((key, value))
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|item| self.apply(item)).collect())
+            }
+            other => other,
+        }
+    }
+}
+
+/// Serializes `data`, applying `fields` when the caller asked for a subset.
+pub fn project<T: Serialize>(data: &T, fields: Option<&FieldSelection>) -> Result<Value, String> {
+    let value =
+        serde_json::to_value(data).map_err(|e| format!("Failed to serialize response: {e}"))?;
+    Ok(match fields {
+        Some(fields) => fields.apply(value),
+        None => value,
+    })
+}
@@ -0,0 +1,95 @@
+use chrono::NaiveDate;
+use std::fs;
+use tauri::State;
+
+use super::file_access::FileAccessError;
+use super::files::{authorize_user_path, ExportFormat};
+use super::validation::validate_thresholds;
+use super::CommandResult;
+use crate::services::contribution::{
+    build_contribution_graph, ContributionGraph, DEFAULT_THRESHOLDS,
+};
+use crate::services::path_grants::PathGrants;
+use crate::services::storage::{StorageService, TimerStatistic};
+use crate::services::tasks::TaskManager;
+use crate::services::year_review::{build_year_in_review, render_pdf, YearInReview};
+
+pub fn year_statistics(storage: &StorageService, year: i32) -> Result<Vec<TimerStatistic>, String> {
+    let day = |month, day| {
+        NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| format!("Invalid year {year}"))
+    };
+    Ok(storage.load_statistics(Some(day(1, 1)?), Some(day(12, 31)?))?)
+}
+
+#[tauri::command]
+pub fn get_year_in_review(
+    storage: State<'_, StorageService>,
+    tasks: State<'_, TaskManager>,
+    year: i32,
+) -> CommandResult<YearInReview> {
+    year_in_review(&storage, &tasks, year).into()
+}
+
+fn year_in_review(
+    storage: &StorageService,
+    tasks: &TaskManager,
+    year: i32,
+) -> Result<YearInReview, String> {
+    Ok(build_year_in_review(
+        year,
+        &year_statistics(storage, year)?,
+        &tasks.list_with_archived()?,
+        &storage.load_preferences()?.days_off,
+    ))
+}
+
+/// Per-day intensity levels for `year`. `thresholds` are the minimum
+/// pomodoros for each level above 0 and default to `[1, 3, 5, 8]`.
+#[tauri::command]
+pub fn get_contribution_graph(
+    storage: State<'_, StorageService>,
+    year: i32,
+    thresholds: Option<Vec<u32>>,
+) -> CommandResult<ContributionGraph> {
+    let thresholds = thresholds.unwrap_or_else(|| DEFAULT_THRESHOLDS.to_vec());
+    if let Err(error) = validate_thresholds(&thresholds) {
+        return CommandResult::invalid(error);
+    }
+    year_statistics(&storage, year)
+        .map(|statistics| build_contribution_graph(year, &statistics, &thresholds))
+        .into()
+}
+
+/// Renders the year's review to a shareable PDF at an approved `path`.
+#[tauri::command]
+pub fn export_year_in_review_pdf(
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    tasks: State<'_, TaskManager>,
+    year: i32,
+    path: String,
+) -> CommandResult<()> {
+    write_year_in_review_pdf(&storage, &grants, &tasks, year, &path).into()
+}
+
+fn write_year_in_review_pdf(
+    storage: &StorageService,
+    grants: &PathGrants,
+    tasks: &TaskManager,
+    year: i32,
+    path: &str,
+) -> Result<(), FileAccessError> {
+    let path = authorize_user_path(storage, grants, path, ExportFormat::Pdf.extension())?;
+    let preferences = storage.load_preferences()?;
+    let review = build_year_in_review(
+        year,
+        &year_statistics(storage, year)?,
+        &tasks.list_with_archived()?,
+        &preferences.days_off,
+    );
+    fs::write(&path, render_pdf(&review, preferences.duration_style)).map_err(|e| {
+        FileAccessError::Failed {
+            message: format!("Failed to write {}: {e}", path.display()),
+        }
+    })
+}
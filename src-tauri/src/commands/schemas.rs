@@ -0,0 +1,23 @@
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use std::collections::BTreeMap;
+
+use crate::services::dashboard_feed::DashboardFeed;
+use crate::services::door_status::DoorStatus;
+use crate::services::storage::TimerStatistic;
+use crate::services::tasks::Task;
+use crate::services::timer_state::TimerData;
+
+/// JSON Schemas for the public payload types, keyed by type name, so
+/// integrators can generate typed clients. They follow the serde
+/// representation, i.e. exactly what the commands and events send.
+#[tauri::command]
+pub fn get_schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("TimerData", schema_for!(TimerData)),
+        ("TimerStatistic", schema_for!(TimerStatistic)),
+        ("Task", schema_for!(Task)),
+        ("DashboardFeed", schema_for!(DashboardFeed)),
+        ("DoorStatus", schema_for!(DoorStatus)),
+    ])
+}
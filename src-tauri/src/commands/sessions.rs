@@ -0,0 +1,203 @@
+use tauri::{AppHandle, State};
+
+use super::validation::{
+    check_range, normalize_tags, validate_date_range, GithubIssue, IsoDate, SessionTag,
+    ValidationError,
+};
+use super::{CommandError, CommandResult};
+use crate::services::calendar;
+use crate::services::github_issues::{self, IssueFocus};
+use crate::services::project_goals;
+use crate::services::session_edit;
+use crate::services::session_query::{self, SessionFilter, SessionPage};
+use crate::services::storage::{SessionData, StorageService};
+use crate::services::tasks::TaskManager;
+use crate::services::undo::{UndoManager, UndoableAction};
+
+/// Sessions a [`query_sessions`] page may hold.
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Replaces the tags on a recorded session. Tagging can complete a
+/// project's weekly goal.
+#[tauri::command]
+pub fn set_session_tags(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    date: IsoDate,
+    index: usize,
+    tags: Vec<SessionTag>,
+) -> CommandResult<SessionData> {
+    match normalize_tags(tags) {
+        Ok(tags) => tag_session(&app, &storage, &date, index, tags).into(),
+        Err(error) => CommandResult::invalid(error),
+    }
+}
+
+fn tag_session(
+    app: &AppHandle,
+    storage: &StorageService,
+    date: &IsoDate,
+    index: usize,
+    tags: Vec<String>,
+) -> Result<SessionData, CommandError> {
+    let before = project_goals::progress(storage, &calendar::today(storage)?)?;
+    let session = storage.set_session_tags(date.as_str(), index, tags)?;
+    project_goals::announce_if_reached(app, storage, &before)?;
+    Ok(session)
+}
+
+/// Attaches a GitHub issue, given as `owner/repo#123` or its URL, to a
+/// recorded session, or detaches it for `None`.
+#[tauri::command]
+pub fn set_session_issue(
+    storage: State<'_, StorageService>,
+    date: IsoDate,
+    index: usize,
+    issue: Option<GithubIssue>,
+) -> CommandResult<SessionData> {
+    storage
+        .set_session_issue(date.as_str(), index, issue.map(GithubIssue::into_inner))
+        .into()
+}
+
+/// The sessions attached to `issue` over the optional date range and the
+/// focus time they add up to.
+#[tauri::command]
+pub fn get_sessions_for_issue(
+    storage: State<'_, StorageService>,
+    issue: GithubIssue,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> CommandResult<IssueFocus> {
+    sessions_for_issue(&storage, &issue, start_date, end_date).into()
+}
+
+fn sessions_for_issue(
+    storage: &StorageService,
+    issue: &GithubIssue,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> Result<IssueFocus, CommandError> {
+    validate_date_range(start_date.as_ref(), end_date.as_ref())?;
+    let statistics = storage.load_statistics(
+        start_date.as_ref().map(IsoDate::to_naive),
+        end_date.as_ref().map(IsoDate::to_naive),
+    )?;
+    Ok(github_issues::sessions_for_issue(
+        &statistics,
+        issue.as_str(),
+        &storage.custom_session_types()?,
+    ))
+}
+
+/// One page of the recorded sessions matching `filter`, newest first, with
+/// the total matching count. Days are read one at a time, so a long
+/// history isn't loaded whole.
+#[tauri::command]
+pub fn query_sessions(
+    storage: State<'_, StorageService>,
+    tasks: State<'_, TaskManager>,
+    filter: Option<SessionFilter>,
+    page: usize,
+    page_size: usize,
+) -> CommandResult<SessionPage> {
+    if let Err(error) = check_range("page_size", page_size, 1, MAX_PAGE_SIZE) {
+        return CommandResult::invalid(error);
+    }
+    tasks
+        .list()
+        .and_then(|tasks| {
+            session_query::query(
+                &storage,
+                &filter.unwrap_or_default(),
+                &tasks,
+                page,
+                page_size,
+            )
+        })
+        .into()
+}
+
+#[tauri::command]
+pub fn delete_session(
+    storage: State<'_, StorageService>,
+    undo: State<'_, UndoManager>,
+    date: IsoDate,
+    index: usize,
+) -> CommandResult<SessionData> {
+    storage
+        .delete_session(date.as_str(), index)
+        .map_err(CommandError::from)
+        .and_then(|(session, remaining)| {
+            undo.record(
+                &storage,
+                UndoableAction::DeleteSession {
+                    date: date.as_str().to_string(),
+                    index,
+                    session: session.clone(),
+                    remaining,
+                },
+            )?;
+            Ok(session)
+        })
+        .into()
+}
+
+/// Splits the session at `index` on `date` in two at Unix time `at`, e.g.
+/// to separate real work from a timer left running. Returns the day's
+/// sessions; totals are recomputed. Recorded in undo history.
+#[tauri::command]
+pub fn split_session(
+    storage: State<'_, StorageService>,
+    undo: State<'_, UndoManager>,
+    date: IsoDate,
+    index: usize,
+    at: u64,
+) -> CommandResult<Vec<SessionData>> {
+    edit_sessions(&storage, &undo, &date, "Split", |sessions| {
+        session_edit::split(sessions, index, at)
+            .map_err(|message| ValidationError::new("at", message))
+    })
+    .into()
+}
+
+/// Merges the sessions at `indices` on `date` into one spanning them all.
+/// Returns the day's sessions; totals are recomputed. Recorded in undo
+/// history.
+#[tauri::command]
+pub fn merge_sessions(
+    storage: State<'_, StorageService>,
+    undo: State<'_, UndoManager>,
+    date: IsoDate,
+    indices: Vec<usize>,
+) -> CommandResult<Vec<SessionData>> {
+    edit_sessions(&storage, &undo, &date, "Merge", |sessions| {
+        session_edit::merge(sessions, &indices)
+            .map_err(|message| ValidationError::new("indices", message))
+    })
+    .into()
+}
+
+fn edit_sessions(
+    storage: &StorageService,
+    undo: &UndoManager,
+    date: &IsoDate,
+    operation: &'static str,
+    edit: impl FnOnce(&[SessionData]) -> Result<Vec<SessionData>, ValidationError>,
+) -> Result<Vec<SessionData>, CommandError> {
+    let mut after = Vec::new();
+    let before = storage.edit_sessions(date.as_str(), |sessions| {
+        after = edit(sessions)?;
+        Ok::<_, CommandError>(after.clone())
+    })?;
+    undo.record(
+        storage,
+        UndoableAction::EditSessions {
+            date: date.as_str().to_string(),
+            operation,
+            before,
+            after: after.clone(),
+        },
+    )?;
+    Ok(after)
+}
@@ -0,0 +1,136 @@
+use tauri::{AppHandle, State};
+
+use super::settings_validation::{validate_project_goals, MAX_DAILY_GOAL};
+use super::validation::{check_range, Validated};
+use super::{CommandError, CommandResult};
+use crate::services::focus_mode::FocusModeService;
+use crate::services::preferences::configure_services;
+use crate::services::project_goals::ProjectGoal;
+use crate::services::quit_guard::{QuitGuard, QuitGuardMode};
+use crate::services::revision::MutationError;
+use crate::services::storage::{StorageService, UserPreferences};
+use crate::services::system_theme::{self, SystemTheme, SystemThemeMonitor};
+use crate::services::window_activity::WindowTracker;
+
+#[tauri::command]
+pub fn load_preferences(storage: State<'_, StorageService>) -> CommandResult<UserPreferences> {
+    storage.load_preferences().into()
+}
+
+#[tauri::command]
+pub fn save_preferences(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    preferences: Validated<UserPreferences>,
+    expected_revision: Option<u64>,
+) -> CommandResult<UserPreferences> {
+    storage
+        .save_preferences(preferences.into_inner(), expected_revision)
+        .and_then(|saved| {
+            configure_services(&app, &saved)?;
+            Ok(saved)
+        })
+        .into()
+}
+
+/// Turns the Do Not Disturb integration on or off. On macOS it runs the
+/// "Tempus Ring Focus On" / "Tempus Ring Focus Off" shortcuts, which the
+/// user creates in the Shortcuts app.
+#[tauri::command]
+pub fn set_dnd_integration(
+    storage: State<'_, StorageService>,
+    focus_mode: State<'_, FocusModeService>,
+    enabled: bool,
+) -> CommandResult<UserPreferences> {
+    storage
+        .load_preferences()
+        .map_err(MutationError::from)
+        .and_then(|mut preferences| {
+            preferences.dnd_integration = enabled;
+            storage.save_preferences(preferences, None)
+        })
+        .and_then(|saved| {
+            focus_mode.configure(&saved)?;
+            Ok(saved)
+        })
+        .into()
+}
+
+/// Sets whether quitting or closing the window during a work session asks
+/// first, is refused, or goes ahead.
+#[tauri::command]
+pub fn set_quit_guard(
+    storage: State<'_, StorageService>,
+    quit_guard: State<'_, QuitGuard>,
+    mode: QuitGuardMode,
+) -> CommandResult<UserPreferences> {
+    storage
+        .update_preferences(|preferences| preferences.quit_guard = mode)
+        .and_then(|saved| {
+            quit_guard.configure(&saved)?;
+            Ok(saved)
+        })
+        .into()
+}
+
+/// Turns recording the windows in front during work sessions on or off.
+/// Turning it off drops what was sampled for the running session.
+#[tauri::command]
+pub fn set_window_annotation(
+    storage: State<'_, StorageService>,
+    tracker: State<'_, WindowTracker>,
+    enabled: bool,
+) -> CommandResult<UserPreferences> {
+    storage
+        .update_preferences(|preferences| preferences.window_annotation = enabled)
+        .and_then(|saved| {
+            tracker.configure(&saved)?;
+            Ok(saved)
+        })
+        .into()
+}
+
+/// Sets how many pomodoros a day count as reaching the goal.
+#[tauri::command]
+pub fn set_daily_goal(
+    storage: State<'_, StorageService>,
+    count: u32,
+) -> CommandResult<UserPreferences> {
+    check_range("count", count, 1, MAX_DAILY_GOAL)
+        .map_err(CommandError::from)
+        .and_then(
+            |()| Ok(storage.update_preferences(|preferences| preferences.daily_goal = count)?),
+        )
+        .into()
+}
+
+/// Replaces the weekly focus-hour goals. Each project is a session tag.
+#[tauri::command]
+pub fn set_project_goals(
+    storage: State<'_, StorageService>,
+    goals: Vec<ProjectGoal>,
+) -> CommandResult<UserPreferences> {
+    if let Err(error) = validate_project_goals(&goals) {
+        return CommandResult::invalid(error);
+    }
+    storage
+        .update_preferences(|preferences| preferences.project_goals = goals)
+        .into()
+}
+
+/// The OS light/dark appearance as last seen by the backend watcher, or
+/// detected now if it hasn't reported yet.
+#[tauri::command]
+pub fn get_system_theme(
+    app: AppHandle,
+    monitor: State<'_, SystemThemeMonitor>,
+) -> CommandResult<SystemTheme> {
+    monitor
+        .current()
+        .and_then(|current| {
+            current
+                .or_else(|| system_theme::detect(&app))
+                .ok_or_else(|| "The system theme could not be determined".to_string())
+        })
+        .into()
+}
@@ -0,0 +1,228 @@
+//! Validation of the lists of entries preferences hold: blocked domains
+//! and actions, hooks, project goals, days off and work contexts.
+
+use super::{
+    MAX_BLOCKED_DOMAINS, MAX_BLOCK_ACTIONS, MAX_DAYS_OFF_ENTRIES, MAX_HOOKS, MAX_HOOK_TIMEOUT_SECS,
+    MAX_PROJECT_GOALS, MAX_WEEKLY_FOCUS_HOURS, MAX_WORK_CONTEXTS,
+};
+use crate::commands::validation::{
+    check_range, validate_date, SessionTag, ValidationError, MAX_TITLE_CHARS,
+};
+use crate::services::days_off::DayOffEntry;
+use crate::services::distraction_blocker::{BlockAction, BlockerSettings};
+use crate::services::hooks::TimerHook;
+use crate::services::project_goals::ProjectGoal;
+use crate::services::work_context::WorkContextSettings;
+
+pub fn validate_blocklist(domains: &[String]) -> Result<(), ValidationError> {
+    const FIELD: &str = "distraction_blocker.blocklist";
+    if domains.len() > MAX_BLOCKED_DOMAINS {
+        return Err(ValidationError::new(
+            FIELD,
+            format!("at most {MAX_BLOCKED_DOMAINS} domains are allowed"),
+        ));
+    }
+    for domain in domains {
+        let valid = !domain.is_empty()
+            && domain.len() <= 253
+            && !domain.starts_with(['.', '-'])
+            && domain
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '-'));
+        if !valid {
+            return Err(ValidationError::new(
+                FIELD,
+                format!("{domain:?} is not a lowercase domain name"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn validate_blocker(settings: &BlockerSettings) -> Result<(), ValidationError> {
+    validate_blocklist(&settings.blocklist)?;
+    validate_block_actions(&settings.actions)
+}
+
+/// Shell actions have both commands; webhooks use HTTPS.
+pub fn validate_block_actions(actions: &[BlockAction]) -> Result<(), ValidationError> {
+    if actions.len() > MAX_BLOCK_ACTIONS {
+        return Err(ValidationError::new(
+            "distraction_blocker.actions",
+            format!("at most {MAX_BLOCK_ACTIONS} actions are allowed"),
+        ));
+    }
+    for action in actions {
+        match action {
+            BlockAction::HostsFile => {}
+            BlockAction::Shell { block, unblock } => {
+                if block.trim().is_empty() || unblock.trim().is_empty() {
+                    return Err(ValidationError::new(
+                        "distraction_blocker.actions.shell",
+                        "needs both a block and an unblock command",
+                    ));
+                }
+            }
+            BlockAction::Webhook { url } => {
+                if !url.starts_with("https://") {
+                    return Err(ValidationError::new(
+                        "distraction_blocker.actions.webhook",
+                        "url must start with https://",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Hooks have a command and a timeout of at most five minutes.
+pub fn validate_hooks(hooks: &[TimerHook]) -> Result<(), ValidationError> {
+    if hooks.len() > MAX_HOOKS {
+        return Err(ValidationError::new(
+            "hooks",
+            format!("at most {MAX_HOOKS} hooks are allowed"),
+        ));
+    }
+    for hook in hooks {
+        if hook.command.trim().is_empty() {
+            return Err(ValidationError::new("hooks.command", "must not be empty"));
+        }
+        check_range(
+            "hooks.timeout_secs",
+            hook.timeout_secs,
+            1,
+            MAX_HOOK_TIMEOUT_SECS,
+        )?;
+    }
+    Ok(())
+}
+
+/// Project goals name a distinct, normalized session tag and a weekly goal
+/// that fits in a week.
+pub fn validate_project_goals(goals: &[ProjectGoal]) -> Result<(), ValidationError> {
+    if goals.len() > MAX_PROJECT_GOALS {
+        return Err(ValidationError::new(
+            "project_goals",
+            format!("at most {MAX_PROJECT_GOALS} project goals are allowed"),
+        ));
+    }
+    for (index, goal) in goals.iter().enumerate() {
+        let tag = SessionTag::try_from(goal.project.clone())
+            .map_err(|error| ValidationError::new("project_goals.project", error.message))?;
+        if tag.into_inner() != goal.project {
+            return Err(ValidationError::new(
+                "project_goals.project",
+                "must be trimmed and lowercase",
+            ));
+        }
+        if goals[..index]
+            .iter()
+            .any(|other| other.project == goal.project)
+        {
+            return Err(ValidationError::new(
+                "project_goals.project",
+                format!("{} already has a goal", goal.project),
+            ));
+        }
+        check_range(
+            "project_goals.weekly_focus_hours",
+            goal.weekly_focus_hours,
+            1,
+            MAX_WEEKLY_FOCUS_HOURS,
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn validate_days_off(entries: &[DayOffEntry]) -> Result<(), ValidationError> {
+    if entries.len() > MAX_DAYS_OFF_ENTRIES {
+        return Err(ValidationError::new(
+            "days_off.entries",
+            format!("at most {MAX_DAYS_OFF_ENTRIES} days off entries are allowed"),
+        ));
+    }
+    for entry in entries {
+        validate_date("days_off.entries.start_date", &entry.start_date)?;
+        validate_date("days_off.entries.end_date", &entry.end_date)?;
+        if entry.end_date < entry.start_date {
+            return Err(ValidationError::new(
+                "days_off.entries.end_date",
+                "must not be before start_date",
+            ));
+        }
+        let label_chars = entry.label.trim().chars().count();
+        if label_chars == 0 || label_chars > MAX_TITLE_CHARS {
+            return Err(ValidationError::new(
+                "days_off.entries.label",
+                format!("must be between 1 and {MAX_TITLE_CHARS} characters"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn validate_work_context(settings: &WorkContextSettings) -> Result<(), ValidationError> {
+    if settings.contexts.len() > MAX_WORK_CONTEXTS {
+        return Err(ValidationError::new(
+            "work_context.contexts",
+            format!("at most {MAX_WORK_CONTEXTS} contexts are allowed"),
+        ));
+    }
+    for (index, context) in settings.contexts.iter().enumerate() {
+        let name_chars = context.name.trim().chars().count();
+        if name_chars == 0 || name_chars > MAX_TITLE_CHARS {
+            return Err(ValidationError::new(
+                "work_context.contexts.name",
+                format!("must be between 1 and {MAX_TITLE_CHARS} characters"),
+            ));
+        }
+        if settings.contexts[..index]
+            .iter()
+            .any(|other| other.name == context.name)
+        {
+            return Err(ValidationError::new(
+                "work_context.contexts.name",
+                format!("{} is already in use", context.name),
+            ));
+        }
+    }
+    if let Some(manual) = &settings.manual {
+        if !settings
+            .contexts
+            .iter()
+            .any(|context| context.name == *manual)
+        {
+            return Err(ValidationError::new(
+                "work_context.manual",
+                format!("unknown context {manual}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_goals_need_a_distinct_tag_and_hours_within_a_week() {
+        let goal = |project: &str, hours| ProjectGoal {
+            project: project.to_string(),
+            weekly_focus_hours: hours,
+        };
+        assert!(validate_project_goals(&[goal("thesis", 10), goal("side-project", 4)]).is_ok());
+
+        let error = validate_project_goals(&[goal("Thesis", 10)]).unwrap_err();
+        assert_eq!(error.field, "project_goals.project");
+        assert!(validate_project_goals(&[goal("thesis", 10), goal("thesis", 2)]).is_err());
+        assert_eq!(
+            validate_project_goals(&[goal("thesis", 0)])
+                .unwrap_err()
+                .field,
+            "project_goals.weekly_focus_hours"
+        );
+        assert!(validate_project_goals(&[goal("thesis", MAX_WEEKLY_FOCUS_HOURS + 1)]).is_err());
+    }
+}
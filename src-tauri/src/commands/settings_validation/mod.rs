@@ -0,0 +1,253 @@
+//! Validation of user preferences and timer configuration. The timer's
+//! own settings are checked in `timer`, the lists of entries in `lists`.
+
+use super::validation::{check_range, Validate, ValidationError};
+use crate::services::appearance::{AppearanceProfile, MINUTES_PER_DAY};
+use crate::services::audio::AlarmPreferences;
+use crate::services::energy::HOURS_PER_DAY;
+use crate::services::session_types::find_custom;
+use crate::services::storage::UserPreferences;
+use crate::services::timer_state::SessionType;
+
+const MIN_SESSION_SECS: u64 = 60;
+const MAX_WORK_SECS: u64 = 4 * 60 * 60;
+const MAX_SHORT_BREAK_SECS: u64 = 60 * 60;
+const MAX_LONG_BREAK_SECS: u64 = 3 * 60 * 60;
+const MAX_SESSIONS_UNTIL_LONG_BREAK: u32 = 12;
+const MAX_CUSTOM_SESSION_TYPES: usize = 20;
+const MAX_SEQUENCE_PHASES: usize = 24;
+const MAX_SKIPPED_BREAKS: u32 = 20;
+pub const MAX_DAILY_GOAL: u32 = 48;
+const MAX_FEED_INTERVAL_MINUTES: u32 = 24 * 60;
+pub const MAX_KEPT_BACKUPS: u32 = 100;
+const MAX_SCHEDULE_RULES: usize = 20;
+const MAX_PROJECT_GOALS: usize = 50;
+const MAX_DAYS_OFF_ENTRIES: usize = 200;
+const MAX_WORK_CONTEXTS: usize = 20;
+const MAX_IGNORED_MONITORS: usize = 16;
+const MAX_BREAK_REMINDER_MINUTES: u32 = 120;
+const MAX_BREAK_REMINDERS: u32 = 20;
+const MAX_BLOCKED_DOMAINS: usize = 200;
+const MAX_BLOCK_ACTIONS: usize = 8;
+const MAX_HOOKS: usize = 20;
+const MAX_HOOK_TIMEOUT_SECS: u32 = 300;
+const MAX_ARCHIVE_AFTER_DAYS: u32 = 10 * 365;
+const MAX_DAILY_FOCUS_HOURS: u32 = 24;
+const MAX_WEEKLY_FOCUS_HOURS: u32 = 7 * 24;
+const EXPERIMENT_ARMS: usize = 2;
+const MAX_EXPERIMENT_DAYS: u32 = 90;
+/// UTC offsets in use range from -12:00 to +14:00.
+const MIN_UTC_OFFSET_MINUTES: i32 = -12 * 60;
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+mod lists;
+mod timer;
+
+pub use lists::{
+    validate_block_actions, validate_blocklist, validate_hooks, validate_project_goals,
+};
+use lists::{validate_blocker, validate_days_off, validate_work_context};
+
+fn validate_volume(field: &str, volume: f32) -> Result<(), ValidationError> {
+    if !volume.is_finite() {
+        return Err(ValidationError::new(field, "must be a number"));
+    }
+    check_range(field, volume, 0.0, 1.0)
+}
+
+fn validate_alarms(prefix: &str, alarms: &AlarmPreferences) -> Result<(), ValidationError> {
+    for alarms in alarms.all() {
+        for stage in [&alarms.pre_alarm, &alarms.alarm, &alarms.overtime] {
+            validate_volume(&format!("{prefix}alarms.volume"), stage.volume)?;
+            if stage.sound.trim().is_empty() {
+                return Err(ValidationError::new(
+                    &format!("{prefix}alarms.sound"),
+                    "must not be empty",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_profile(prefix: &str, profile: &AppearanceProfile) -> Result<(), ValidationError> {
+    if profile.theme.trim().is_empty() {
+        return Err(ValidationError::new(
+            &format!("{prefix}theme"),
+            "must not be empty",
+        ));
+    }
+    validate_volume(&format!("{prefix}volume"), profile.volume)?;
+    validate_alarms(prefix, &profile.alarms)
+}
+
+impl Validate for UserPreferences {
+    fn validate(&self) -> Result<(), ValidationError> {
+        for (phase, settings) in self.notifications.all() {
+            validate_volume(&format!("notifications.{phase}.volume"), settings.volume)?;
+        }
+        validate_alarms("", &self.alarms)?;
+        if self.theme.trim().is_empty() {
+            return Err(ValidationError::new("theme", "must not be empty"));
+        }
+        let schedule = &self.appearance_schedule;
+        let breaks = &self.break_detection;
+        for (field, minute) in [
+            ("appearance_schedule.day_start", schedule.day_start),
+            ("appearance_schedule.night_start", schedule.night_start),
+            (
+                "break_detection.window_start_minute",
+                breaks.window_start_minute,
+            ),
+            (
+                "break_detection.window_end_minute",
+                breaks.window_end_minute,
+            ),
+        ] {
+            check_range(field, minute, 0, MINUTES_PER_DAY - 1)?;
+        }
+        if breaks.window_start_minute > breaks.window_end_minute {
+            return Err(ValidationError::new(
+                "break_detection.window_end_minute",
+                "must not be before window_start_minute",
+            ));
+        }
+        check_range(
+            "break_detection.min_gap_minutes",
+            breaks.min_gap_minutes,
+            1,
+            MINUTES_PER_DAY,
+        )?;
+        validate_profile("appearance_schedule.day.", &schedule.day)?;
+        validate_profile("appearance_schedule.night.", &schedule.night)?;
+        if self.language.trim().is_empty() {
+            return Err(ValidationError::new("language", "must not be empty"));
+        }
+        self.timer_config.validate()?;
+        self.timer_schedule
+            .validate()
+            .map_err(|error| ValidationError {
+                field: format!("timer_schedule.{}", error.field),
+                message: error.message,
+            })?;
+        if let Some(experiment) = &self.experiment {
+            experiment.validate().map_err(|error| ValidationError {
+                field: format!("experiment.{}", error.field),
+                message: error.message,
+            })?;
+        }
+        if self.energy_template.hours.len() != HOURS_PER_DAY {
+            return Err(ValidationError::new(
+                "energy_template.hours",
+                format!("must have exactly {HOURS_PER_DAY} entries"),
+            ));
+        }
+        if let SessionType::Custom(id) = &self.energy_template.low_energy_session {
+            if find_custom(&self.timer_config.custom_session_types, id).is_none() {
+                return Err(ValidationError::new(
+                    "energy_template.low_energy_session",
+                    format!("unknown session type {id}"),
+                ));
+            }
+        }
+        check_range("daily_goal", self.daily_goal, 1, MAX_DAILY_GOAL)?;
+        validate_days_off(&self.days_off.entries)?;
+        validate_project_goals(&self.project_goals)?;
+        validate_work_context(&self.work_context)?;
+        check_range(
+            "dashboard_feed.interval_minutes",
+            self.dashboard_feed.interval_minutes,
+            1,
+            MAX_FEED_INTERVAL_MINUTES,
+        )?;
+        check_range(
+            "auto_backup.keep",
+            self.auto_backup.keep,
+            1,
+            MAX_KEPT_BACKUPS,
+        )?;
+        check_range(
+            "task_archive.after_days",
+            self.task_archive.after_days,
+            1,
+            MAX_ARCHIVE_AFTER_DAYS,
+        )?;
+        check_range(
+            "fatigue.skipped_breaks",
+            self.fatigue.skipped_breaks,
+            1,
+            MAX_SKIPPED_BREAKS,
+        )?;
+        check_range(
+            "fatigue.recovery_break",
+            self.fatigue.recovery_break,
+            MIN_SESSION_SECS,
+            MAX_LONG_BREAK_SECS,
+        )?;
+        check_range(
+            "focus_budget.daily_hours",
+            self.focus_budget.daily_hours,
+            0,
+            MAX_DAILY_FOCUS_HOURS,
+        )?;
+        check_range(
+            "focus_budget.weekly_hours",
+            self.focus_budget.weekly_hours,
+            0,
+            MAX_WEEKLY_FOCUS_HOURS,
+        )?;
+        check_range(
+            "battery_guard.threshold_percent",
+            self.battery_guard.threshold_percent,
+            1,
+            100,
+        )?;
+        check_range(
+            "battery_guard.long_session_minutes",
+            self.battery_guard.long_session_minutes,
+            1,
+            (MAX_WORK_SECS / 60) as u32,
+        )?;
+        check_range(
+            "break_reminders.interval_minutes",
+            self.break_reminders.interval_minutes,
+            1,
+            MAX_BREAK_REMINDER_MINUTES,
+        )?;
+        check_range(
+            "break_reminders.max_reminders",
+            self.break_reminders.max_reminders,
+            1,
+            MAX_BREAK_REMINDERS,
+        )?;
+        check_range(
+            "break_reminders.escalate_after",
+            self.break_reminders.escalate_after,
+            0,
+            self.break_reminders.max_reminders,
+        )?;
+        if self.presenter_mode.ignored_monitors.len() > MAX_IGNORED_MONITORS {
+            return Err(ValidationError::new(
+                "presenter_mode.ignored_monitors",
+                format!("at most {MAX_IGNORED_MONITORS} monitors are allowed"),
+            ));
+        }
+        validate_blocker(&self.distraction_blocker)?;
+        validate_hooks(&self.hooks)?;
+        check_range(
+            "calendar.day_start_hour",
+            self.calendar.day_start_hour,
+            0,
+            23,
+        )?;
+        if let Some(offset) = self.calendar.utc_offset_minutes {
+            check_range(
+                "calendar.utc_offset_minutes",
+                offset,
+                MIN_UTC_OFFSET_MINUTES,
+                MAX_UTC_OFFSET_MINUTES,
+            )?;
+        }
+        self.end_of_day.validate()
+    }
+}
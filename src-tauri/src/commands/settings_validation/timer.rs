@@ -0,0 +1,337 @@
+//! Validation of the timer configuration, its schedule and experiments.
+
+use super::{
+    EXPERIMENT_ARMS, MAX_CUSTOM_SESSION_TYPES, MAX_EXPERIMENT_DAYS, MAX_LONG_BREAK_SECS,
+    MAX_SCHEDULE_RULES, MAX_SEQUENCE_PHASES, MAX_SESSIONS_UNTIL_LONG_BREAK, MAX_SHORT_BREAK_SECS,
+    MAX_WORK_SECS, MIN_SESSION_SECS,
+};
+use crate::commands::validation::{
+    check_range, validate_date, SessionTag, Validate, ValidationError, MAX_TITLE_CHARS,
+};
+use crate::services::appearance::MINUTES_PER_DAY;
+use crate::services::cycle::SequencePhase;
+use crate::services::experiments::Experiment;
+use crate::services::session_types::{find_custom, CustomSessionType};
+use crate::services::timer_schedule::TimerSchedule;
+use crate::services::timer_state::{SessionType, TimerConfig};
+
+impl Validate for TimerConfig {
+    fn validate(&self) -> Result<(), ValidationError> {
+        check_range(
+            "work_duration",
+            self.work_duration,
+            MIN_SESSION_SECS,
+            MAX_WORK_SECS,
+        )?;
+        check_range(
+            "short_break_duration",
+            self.short_break_duration,
+            MIN_SESSION_SECS,
+            MAX_SHORT_BREAK_SECS,
+        )?;
+        check_range(
+            "long_break_duration",
+            self.long_break_duration,
+            MIN_SESSION_SECS,
+            MAX_LONG_BREAK_SECS,
+        )?;
+        check_range(
+            "sessions_until_long_break",
+            self.sessions_until_long_break,
+            1,
+            MAX_SESSIONS_UNTIL_LONG_BREAK,
+        )?;
+        validate_custom_session_types(&self.custom_session_types)?;
+        validate_sequence(&self.sequence, &self.custom_session_types)
+    }
+}
+
+impl Validate for TimerSchedule {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.rules.len() > MAX_SCHEDULE_RULES {
+            return Err(ValidationError::new(
+                "rules",
+                format!("at most {MAX_SCHEDULE_RULES} schedule windows are allowed"),
+            ));
+        }
+        for rule in &self.rules {
+            let name_chars = rule.name.trim().chars().count();
+            if name_chars == 0 || name_chars > MAX_TITLE_CHARS {
+                return Err(ValidationError::new(
+                    "rules.name",
+                    format!("must be between 1 and {MAX_TITLE_CHARS} characters"),
+                ));
+            }
+            check_range(
+                "rules.start_minute",
+                rule.start_minute,
+                0,
+                MINUTES_PER_DAY - 1,
+            )?;
+            check_range("rules.end_minute", rule.end_minute, 0, MINUTES_PER_DAY - 1)?;
+            rule.config.validate().map_err(|error| ValidationError {
+                field: format!("rules.config.{}", error.field),
+                message: error.message,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks `name` is a normalized session tag without the `:` that
+/// separates experiment and arm.
+fn validate_experiment_name(field: &str, name: &str) -> Result<(), ValidationError> {
+    let tag = SessionTag::try_from(name.to_string())
+        .map_err(|error| ValidationError::new(field, error.message))?;
+    if tag.into_inner() != name || name.contains(':') {
+        return Err(ValidationError::new(
+            field,
+            "must be trimmed, lowercase and without ':'",
+        ));
+    }
+    Ok(())
+}
+
+impl Validate for Experiment {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_experiment_name("name", &self.name)?;
+        validate_date("start_date", &self.start_date)?;
+        check_range(
+            "days",
+            self.days,
+            EXPERIMENT_ARMS as u32,
+            MAX_EXPERIMENT_DAYS,
+        )?;
+        if self.arms.len() != EXPERIMENT_ARMS {
+            return Err(ValidationError::new(
+                "arms",
+                format!("must have exactly {EXPERIMENT_ARMS} arms"),
+            ));
+        }
+        for (index, arm) in self.arms.iter().enumerate() {
+            validate_experiment_name("arms.name", &arm.name)?;
+            if self.arms[..index]
+                .iter()
+                .any(|other| other.name == arm.name)
+            {
+                return Err(ValidationError::new(
+                    "arms.name",
+                    format!("{} is used twice", arm.name),
+                ));
+            }
+            SessionTag::try_from(self.tag(arm))
+                .map_err(|error| ValidationError::new("arms.name", error.message))?;
+            arm.config.validate().map_err(|error| ValidationError {
+                field: format!("arms.config.{}", error.field),
+                message: error.message,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Blocked domains are bare, lowercase host names such as `reddit.com`.
+
+fn validate_custom_session_types(types: &[CustomSessionType]) -> Result<(), ValidationError> {
+    const FIELD: &str = "custom_session_types";
+    if types.len() > MAX_CUSTOM_SESSION_TYPES {
+        return Err(ValidationError::new(
+            FIELD,
+            format!("at most {MAX_CUSTOM_SESSION_TYPES} custom session types are allowed"),
+        ));
+    }
+    let built_in = [
+        SessionType::Work,
+        SessionType::ShortBreak,
+        SessionType::LongBreak,
+    ];
+    for (index, custom) in types.iter().enumerate() {
+        let id_ok = !custom.id.is_empty()
+            && custom
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !id_ok {
+            return Err(ValidationError::new(
+                "custom_session_types.id",
+                "must be lowercase letters, digits or underscores",
+            ));
+        }
+        if built_in.iter().any(|session| session.key() == custom.id)
+            || types[..index].iter().any(|other| other.id == custom.id)
+        {
+            return Err(ValidationError::new(
+                "custom_session_types.id",
+                format!("{} is already in use", custom.id),
+            ));
+        }
+        let name_chars = custom.name.trim().chars().count();
+        if name_chars == 0 || name_chars > MAX_TITLE_CHARS {
+            return Err(ValidationError::new(
+                "custom_session_types.name",
+                format!("must be between 1 and {MAX_TITLE_CHARS} characters"),
+            ));
+        }
+        if !is_hex_color(&custom.color) {
+            return Err(ValidationError::new(
+                "custom_session_types.color",
+                "must be a #RRGGBB color",
+            ));
+        }
+        check_range(
+            "custom_session_types.duration",
+            custom.duration,
+            MIN_SESSION_SECS,
+            MAX_WORK_SECS,
+        )?;
+    }
+    Ok(())
+}
+
+fn validate_sequence(
+    phases: &[SequencePhase],
+    custom_types: &[CustomSessionType],
+) -> Result<(), ValidationError> {
+    if phases.len() > MAX_SEQUENCE_PHASES {
+        return Err(ValidationError::new(
+            "sequence",
+            format!("at most {MAX_SEQUENCE_PHASES} phases are allowed"),
+        ));
+    }
+    for phase in phases {
+        if let SessionType::Custom(id) = &phase.session_type {
+            if find_custom(custom_types, id).is_none() {
+                return Err(ValidationError::new(
+                    "sequence.session_type",
+                    format!("unknown session type {id}"),
+                ));
+            }
+        }
+        check_range(
+            "sequence.duration",
+            phase.duration,
+            MIN_SESSION_SECS,
+            MAX_WORK_SECS,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::CommandResult;
+    use crate::services::timer_schedule::ScheduleRule;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn timer_config_bounds_are_enforced(
+            work in 0u64..=10 * 60 * 60,
+            short in 0u64..=10 * 60 * 60,
+            long in 0u64..=10 * 60 * 60,
+            interval in 0u32..=50,
+        ) {
+            let config = TimerConfig {
+                work_duration: work,
+                short_break_duration: short,
+                long_break_duration: long,
+                sessions_until_long_break: interval,
+                ..TimerConfig::default()
+            };
+            let in_bounds = (MIN_SESSION_SECS..=MAX_WORK_SECS).contains(&work)
+                && (MIN_SESSION_SECS..=MAX_SHORT_BREAK_SECS).contains(&short)
+                && (MIN_SESSION_SECS..=MAX_LONG_BREAK_SECS).contains(&long)
+                && (1..=MAX_SESSIONS_UNTIL_LONG_BREAK).contains(&interval);
+            prop_assert_eq!(config.validate().is_ok(), in_bounds);
+        }
+    }
+
+    #[test]
+    fn rejected_timer_configs_name_the_offending_field() {
+        let zero_work = TimerConfig {
+            work_duration: 0,
+            ..TimerConfig::default()
+        };
+        let error = zero_work.validate().unwrap_err();
+        assert_eq!(error.field, "work_duration");
+
+        let ten_hour_break = TimerConfig {
+            long_break_duration: 10 * 60 * 60,
+            ..TimerConfig::default()
+        };
+        assert_eq!(
+            ten_hour_break.validate().unwrap_err().field,
+            "long_break_duration"
+        );
+
+        let response = serde_json::to_value(CommandResult::<()>::invalid(error)).unwrap();
+        assert_eq!(response["success"], false);
+        assert_eq!(response["validation"]["field"], "work_duration");
+    }
+
+    #[test]
+    fn sequence_phases_must_use_known_types_and_sane_durations() {
+        let phase = |session_type, minutes| SequencePhase {
+            session_type,
+            duration: minutes * 60,
+        };
+        let mut config = TimerConfig {
+            sequence: vec![
+                phase(SessionType::Work, 52),
+                phase(SessionType::ShortBreak, 17),
+            ],
+            ..TimerConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.sequence[1].duration = 0;
+        assert_eq!(config.validate().unwrap_err().field, "sequence.duration");
+
+        config.sequence[1] = phase(SessionType::Custom("gardening".to_string()), 20);
+        assert_eq!(
+            config.validate().unwrap_err().field,
+            "sequence.session_type"
+        );
+    }
+
+    #[test]
+    fn schedule_errors_point_into_the_offending_window() {
+        let mut schedule = TimerSchedule {
+            enabled: true,
+            rules: vec![ScheduleRule {
+                name: "Evenings".to_string(),
+                weekdays: Vec::new(),
+                start_minute: 18 * 60,
+                end_minute: 60,
+                config: TimerConfig::default(),
+            }],
+        };
+        assert!(schedule.validate().is_ok());
+
+        schedule.rules[0].config.work_duration = 0;
+        assert_eq!(
+            schedule.validate().unwrap_err().field,
+            "rules.config.work_duration"
+        );
+    }
+
+    #[test]
+    fn custom_session_type_ids_must_be_unique_and_not_built_in() {
+        let mut config = TimerConfig::default();
+        assert!(config.validate().is_ok());
+
+        let mut duplicate = config.custom_session_types[0].clone();
+        duplicate.name = "Another".to_string();
+        config.custom_session_types.push(duplicate);
+        assert!(config.validate().is_err());
+
+        config.custom_session_types.pop();
+        config.custom_session_types[0].id = "work".to_string();
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use tauri::{AppHandle, State};
+
+use super::validation::ValidationError;
+use super::{CommandError, CommandResult};
+
+use crate::services::command_trace::traced;
+use crate::services::slack_status::{self, SlackAccount, SlackStatusService};
+use crate::services::storage::StorageService;
+
+/// Stores a Slack user OAuth token with the `users.profile:write` scope
+/// after checking it with Slack. The status follows the timer from the next
+/// tick, for the phases enabled in `slack_status`.
+#[tauri::command]
+pub async fn connect_slack(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    slack: State<'_, SlackStatusService>,
+    token: String,
+) -> CommandResult<SlackAccount> {
+    traced(&app, "connect_slack", async {
+        connect(&storage, &slack, token.trim()).await.into()
+    })
+    .await
+}
+
+async fn connect(
+    storage: &StorageService,
+    slack: &SlackStatusService,
+    token: &str,
+) -> Result<SlackAccount, CommandError> {
+    if token.is_empty() {
+        return Err(ValidationError::new("token", "is required").into());
+    }
+    let account = slack_status::verify_token(token).await?;
+    storage.save_slack_token(token)?;
+    slack.reset()?;
+    Ok(account)
+}
+
+/// Clears any status this app set and forgets the token. The token is
+/// forgotten even if Slack can't be reached, e.g. because it was revoked.
+#[tauri::command]
+pub async fn disconnect_slack(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    slack: State<'_, SlackStatusService>,
+) -> CommandResult<()> {
+    traced(&app, "disconnect_slack", async {
+        disconnect(&storage, &slack).await.into()
+    })
+    .await
+}
+
+async fn disconnect(storage: &StorageService, slack: &SlackStatusService) -> Result<(), String> {
+    if let Some(token) = storage.load_slack_token()? {
+        if let Err(error) = slack_status::clear_status(&token).await {
+            log::warn!("{error}");
+        }
+    }
+    storage.delete_slack_token()?;
+    slack.reset()
+}
@@ -0,0 +1,286 @@
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use super::projection::{project, FieldSelection};
+use super::validation::{validate_date_range, IsoDate, SessionTag, Validated, ValidationError};
+use super::{CommandError, CommandResult};
+use crate::services::burnout::{self, BurnoutRisk};
+use crate::services::calendar;
+use crate::services::focus_budget;
+use crate::services::goals::{self, GoalProgress};
+use crate::services::integrity::{self, IntegrityReport};
+use crate::services::project_goals::{self, ProjectGoalProgress};
+use crate::services::session_types::{group_by_session_type, SessionTypeTotal};
+use crate::services::storage::{StorageService, TimerStatistic};
+use crate::services::summary::{summarize, StatisticsSummary};
+use crate::services::timeseries::{bucket_statistics, Granularity, Metric, TimeSeries};
+use crate::services::wellbeing::{self, WellbeingReport};
+use crate::services::write_buffer::StorageHealth;
+
+#[tauri::command]
+pub fn save_statistic(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    statistic: Validated<TimerStatistic>,
+) -> CommandResult<()> {
+    save_day(&app, &storage, statistic.into_inner()).into()
+}
+
+/// Saves `statistic`, keeping the stored day's advisories and overwork
+/// events. Sessions are recorded by the backend as they end; this is for
+/// editing a day as a whole.
+fn save_day(
+    app: &AppHandle,
+    storage: &StorageService,
+    mut statistic: TimerStatistic,
+) -> Result<(), CommandError> {
+    let before = storage
+        .load_statistic(&statistic.date)?
+        .map_or(0, |stored| stored.completed_pomodoros);
+    let projects_before = project_goals::progress(storage, &calendar::today(storage)?)?;
+    let usage_before = focus_budget::usage(storage)?;
+    let date = statistic.date.clone();
+    storage.update_statistic(&date, |stored| {
+        statistic.advisories = std::mem::take(&mut stored.advisories);
+        statistic.overwork = std::mem::take(&mut stored.overwork);
+        *stored = statistic;
+        Ok::<_, CommandError>(())
+    })?;
+    focus_budget::announce_if_exceeded(app, storage, usage_before)?;
+    goals::announce_if_reached(app, storage, &date, before)?;
+    Ok(project_goals::announce_if_reached(
+        app,
+        storage,
+        &projects_before,
+    )?)
+}
+
+/// The `YYYY-MM-DD` day statistics for `timestamp` (Unix seconds), or for
+/// now, belong to under the calendar preferences.
+#[tauri::command]
+pub fn get_day(
+    storage: State<'_, StorageService>,
+    timestamp: Option<u64>,
+) -> CommandResult<String> {
+    storage
+        .load_preferences()
+        .map(|preferences| {
+            let calendar = preferences.calendar;
+            calendar::day_key(timestamp.map_or_else(|| calendar.today(), |at| calendar.day_of(at)))
+        })
+        .into()
+}
+
+/// Today's completed pomodoros against the daily goal.
+#[tauri::command]
+pub fn get_goal_progress(storage: State<'_, StorageService>) -> CommandResult<GoalProgress> {
+    calendar::today(&storage)
+        .and_then(|today| goals::progress(&storage, &today))
+        .into()
+}
+
+/// This week's focus time against each project's weekly goal, with a
+/// forecast at the current pace.
+#[tauri::command]
+pub fn get_project_goal_progress(
+    storage: State<'_, StorageService>,
+) -> CommandResult<Vec<ProjectGoalProgress>> {
+    calendar::today(&storage)
+        .and_then(|today| project_goals::progress(&storage, &today))
+        .into()
+}
+
+/// Loads daily statistics in the optional date range. `fields` limits each
+/// entry to the given dotted paths (e.g. `["date", "completed_pomodoros"]`)
+/// so callers that only chart totals don't receive every session.
+///
+/// With `tags`, only sessions carrying at least one of them are kept, day
+/// totals are recomputed from those sessions, and days without any are
+/// dropped.
+#[tauri::command]
+pub fn load_statistics(
+    storage: State<'_, StorageService>,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+    fields: Option<FieldSelection>,
+    tags: Option<Vec<SessionTag>>,
+) -> CommandResult<Value> {
+    filtered_statistics(&storage, start_date, end_date, fields, tags).into()
+}
+
+fn filtered_statistics(
+    storage: &StorageService,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+    fields: Option<FieldSelection>,
+    tags: Option<Vec<SessionTag>>,
+) -> Result<Value, CommandError> {
+    validate_date_range(start_date.as_ref(), end_date.as_ref())?;
+    let mut statistics = storage.load_statistics(
+        start_date.as_ref().map(IsoDate::to_naive),
+        end_date.as_ref().map(IsoDate::to_naive),
+    )?;
+    if let Some(tags) = tags {
+        let tags: Vec<String> = tags.into_iter().map(SessionTag::into_inner).collect();
+        let custom_types = storage.custom_session_types()?;
+        for statistic in &mut statistics {
+            statistic.retain_tagged(&tags, &custom_types);
+        }
+        statistics.retain(|statistic| !statistic.sessions.is_empty());
+    }
+    Ok(project(&statistics, fields.as_ref())?)
+}
+
+/// Returns `metric` over the inclusive date range binned into `buckets`
+/// points, so year-long charts don't need every day shipped to the UI.
+#[tauri::command]
+pub fn get_timeseries(
+    storage: State<'_, StorageService>,
+    metric: Metric,
+    start_date: IsoDate,
+    end_date: IsoDate,
+    buckets: usize,
+) -> CommandResult<TimeSeries> {
+    if let Err(error) = validate_date_range(Some(&start_date), Some(&end_date)) {
+        return CommandResult::invalid(error);
+    }
+    if buckets == 0 {
+        return CommandResult::invalid(ValidationError::new("buckets", "must be at least 1"));
+    }
+    timeseries(&storage, metric, &start_date, &end_date, buckets).into()
+}
+
+fn timeseries(
+    storage: &StorageService,
+    metric: Metric,
+    start_date: &IsoDate,
+    end_date: &IsoDate,
+    buckets: usize,
+) -> Result<TimeSeries, CommandError> {
+    let (start, end) = (start_date.to_naive(), end_date.to_naive());
+    // Sessions recorded the day before or after may run into the range.
+    let statistics = storage.load_statistics(start.pred_opt(), end.succ_opt())?;
+    let preferences = storage.load_preferences()?;
+    Ok(bucket_statistics(
+        &statistics,
+        metric,
+        start,
+        end,
+        buckets,
+        &preferences.calendar,
+        &preferences.timer_config.custom_session_types,
+    ))
+}
+
+/// Skipped breaks, overtime, late-night sessions and focus cap violations
+/// over the inclusive range, with recommendations.
+#[tauri::command]
+pub fn get_wellbeing_report(
+    storage: State<'_, StorageService>,
+    start_date: IsoDate,
+    end_date: IsoDate,
+) -> CommandResult<WellbeingReport> {
+    wellbeing_report(&storage, &start_date, &end_date).into()
+}
+
+fn wellbeing_report(
+    storage: &StorageService,
+    start_date: &IsoDate,
+    end_date: &IsoDate,
+) -> Result<WellbeingReport, CommandError> {
+    validate_date_range(Some(start_date), Some(end_date))?;
+    let (start, end) = (start_date.to_naive(), end_date.to_naive());
+    let statistics = storage.load_statistics(start.pred_opt(), end.succ_opt())?;
+    Ok(wellbeing::build_report(
+        &statistics,
+        &storage.load_events(start.pred_opt(), end.succ_opt())?,
+        start,
+        end,
+        &storage.load_preferences()?,
+    ))
+}
+
+/// Last week's burnout-risk score against the three weeks before, with
+/// the signals that got worse.
+#[tauri::command]
+pub fn get_burnout_risk(storage: State<'_, StorageService>) -> CommandResult<BurnoutRisk> {
+    storage
+        .load_preferences()
+        .and_then(|preferences| burnout::latest(&storage, &preferences))
+        .into()
+}
+
+/// Totals, averages, longest streak and best day over the inclusive range,
+/// with per-period totals rolled up by `granularity`.
+#[tauri::command]
+pub fn get_statistics_summary(
+    storage: State<'_, StorageService>,
+    granularity: Granularity,
+    from: IsoDate,
+    to: IsoDate,
+) -> CommandResult<StatisticsSummary> {
+    statistics_summary(&storage, granularity, &from, &to).into()
+}
+
+fn statistics_summary(
+    storage: &StorageService,
+    granularity: Granularity,
+    from: &IsoDate,
+    to: &IsoDate,
+) -> Result<StatisticsSummary, CommandError> {
+    validate_date_range(Some(from), Some(to))?;
+    let (from, to) = (from.to_naive(), to.to_naive());
+    let statistics = storage.load_statistics(Some(from), Some(to))?;
+    Ok(summarize(
+        &statistics,
+        granularity,
+        from,
+        to,
+        &storage.load_preferences()?.days_off,
+    ))
+}
+
+/// Per-session-type totals over the optional date range, with custom type
+/// names and colors resolved. Idle gaps detected as breaks count as long
+/// breaks.
+#[tauri::command]
+pub fn get_session_type_breakdown(
+    storage: State<'_, StorageService>,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> CommandResult<Vec<SessionTypeTotal>> {
+    session_type_breakdown(&storage, start_date, end_date).into()
+}
+
+fn session_type_breakdown(
+    storage: &StorageService,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> Result<Vec<SessionTypeTotal>, CommandError> {
+    validate_date_range(start_date.as_ref(), end_date.as_ref())?;
+    let statistics = storage.load_statistics(
+        start_date.as_ref().map(IsoDate::to_naive),
+        end_date.as_ref().map(IsoDate::to_naive),
+    )?;
+    let preferences = storage.load_preferences()?;
+    Ok(group_by_session_type(
+        &statistics,
+        &preferences.timer_config.custom_session_types,
+        &preferences.break_detection,
+    ))
+}
+
+/// Whether writes are failing and being held in memory. Changes are also
+/// emitted as `storage://health`.
+#[tauri::command]
+pub fn get_storage_health(storage: State<'_, StorageService>) -> CommandResult<StorageHealth> {
+    storage.storage_health().into()
+}
+
+/// Verifies the checksums of every stored day and automatic backup. Days
+/// that fail are quarantined, or keep what still reads with a copy
+/// quarantined, and listed with why.
+#[tauri::command]
+pub fn check_integrity(storage: State<'_, StorageService>) -> CommandResult<IntegrityReport> {
+    integrity::check(&storage).into()
+}
@@ -0,0 +1,22 @@
+use tauri::State;
+
+use super::CommandResult;
+
+use crate::services::storage::StorageService;
+use crate::services::surface_auth::SurfaceAuth;
+
+/// The token external clients must present, for the settings screen to show
+/// when pairing a client.
+#[tauri::command]
+pub fn get_surface_token(auth: State<'_, SurfaceAuth>) -> CommandResult<String> {
+    auth.token().into()
+}
+
+/// Issues a new token, cutting off every client that used the old one.
+#[tauri::command]
+pub fn rotate_surface_token(
+    auth: State<'_, SurfaceAuth>,
+    storage: State<'_, StorageService>,
+) -> CommandResult<String> {
+    auth.rotate(&storage).into()
+}
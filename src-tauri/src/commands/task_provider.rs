@@ -0,0 +1,127 @@
+use tauri::{AppHandle, Manager, State};
+
+use super::validation::{IsoDate, ValidationError};
+use super::{CommandError, CommandResult};
+use crate::services::command_trace::traced;
+use crate::services::service_health::{self, BackgroundService};
+use crate::services::session_types::counts_as_pomodoro;
+use crate::services::storage::{SessionData, StorageService};
+use crate::services::task_provider::{parse_remote_id, RemoteTask, TaskProviderKind};
+use crate::services::tasks::TaskManager;
+
+fn token(storage: &StorageService, provider: TaskProviderKind) -> Result<String, String> {
+    storage
+        .load_task_provider_token(provider)?
+        .ok_or_else(|| format!("{} is not connected", provider.key()))
+}
+
+/// Stores an API token for `provider` after checking it. Returns the
+/// account it belongs to.
+#[tauri::command]
+pub async fn connect_task_provider(
+    app: AppHandle,
+    provider: TaskProviderKind,
+    token: String,
+) -> CommandResult<String> {
+    traced(&app, "connect_task_provider", async {
+        let token = token.trim();
+        if token.is_empty() {
+            return CommandResult::invalid(ValidationError::new(
+                "token",
+                "An API token is required",
+            ));
+        }
+        let account = match provider.connect(token).verify().await {
+            Ok(account) => account,
+            Err(error) => return CommandResult::err(error),
+        };
+        app.state::<StorageService>()
+            .save_task_provider_token(provider, token)
+            .map(|()| account)
+            .into()
+    })
+    .await
+}
+
+/// Forgets the token. Sessions stay linked to the provider's tasks.
+#[tauri::command]
+pub fn disconnect_task_provider(
+    storage: State<'_, StorageService>,
+    provider: TaskProviderKind,
+) -> CommandResult<()> {
+    storage.delete_task_provider_token(provider).into()
+}
+
+/// The open tasks in `provider`, with the ids to pass to
+/// `link_session_to_task`.
+#[tauri::command]
+pub async fn list_remote_tasks(
+    app: AppHandle,
+    provider: TaskProviderKind,
+) -> CommandResult<Vec<RemoteTask>> {
+    traced(&app, "list_remote_tasks", async {
+        let token = match token(&app.state::<StorageService>(), provider) {
+            Ok(token) => token,
+            Err(error) => return CommandResult::err(error),
+        };
+        provider.connect(&token).list_tasks().await.into()
+    })
+    .await
+}
+
+/// Links the session at `index` on `date` to a local task or a provider
+/// task from `list_remote_tasks`, or unlinks it for `None`. A completed
+/// pomodoro newly linked to a provider task is logged on that task in the
+/// background; repeated failures to log go to the inbox.
+#[tauri::command]
+pub fn link_session_to_task(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+    tasks: State<'_, TaskManager>,
+    date: IsoDate,
+    index: usize,
+    task_id: Option<String>,
+) -> CommandResult<SessionData> {
+    link(&app, &storage, &tasks, &date, index, task_id).into()
+}
+
+fn link(
+    app: &AppHandle,
+    storage: &StorageService,
+    tasks: &TaskManager,
+    date: &IsoDate,
+    index: usize,
+    task_id: Option<String>,
+) -> Result<SessionData, CommandError> {
+    let remote = match task_id.as_deref().map(|id| (id, parse_remote_id(id))) {
+        Some((_, Some((provider, id)))) => Some((provider, id.to_string())),
+        Some((id, None)) => {
+            tasks.find(id)?;
+            None
+        }
+        None => None,
+    };
+    let token = match &remote {
+        Some((provider, _)) => Some(token(storage, *provider)?),
+        None => None,
+    };
+    let (session, previous) = storage.set_session_task(date.as_str(), index, task_id)?;
+
+    let (Some((provider, id)), Some(token)) = (remote, token) else {
+        return Ok(session);
+    };
+    let counted = counts_as_pomodoro(&session.session_type, &storage.custom_session_types()?);
+    if !session.completed || !counted || previous == session.task_id {
+        return Ok(session);
+    }
+    let app = app.clone();
+    let logged = session.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = provider.connect(&token).log_pomodoro(&id, &logged).await;
+        if let Err(error) = &result {
+            log::error!("{error}");
+        }
+        service_health::report(&app, BackgroundService::TaskProvider, &result);
+    });
+    Ok(session)
+}
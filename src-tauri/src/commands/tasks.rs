@@ -0,0 +1,99 @@
+use tauri::State;
+
+use super::validation::{validate_estimate, TaskTitle};
+use super::CommandResult;
+use crate::services::storage::StorageService;
+use crate::services::tasks::{Task, TaskManager};
+use crate::services::undo::{UndoManager, UndoableAction};
+
+#[tauri::command]
+pub fn get_tasks(tasks: State<'_, TaskManager>) -> CommandResult<Vec<Task>> {
+    tasks.list().into()
+}
+
+/// Tasks the archival policy moved out of the task list.
+#[tauri::command]
+pub fn get_archived_tasks(tasks: State<'_, TaskManager>) -> CommandResult<Vec<Task>> {
+    tasks.list_archived().into()
+}
+
+#[tauri::command]
+pub fn create_task(
+    tasks: State<'_, TaskManager>,
+    storage: State<'_, StorageService>,
+    title: TaskTitle,
+    estimated_pomodoros: u32,
+) -> CommandResult<Task> {
+    if let Err(error) = validate_estimate(estimated_pomodoros) {
+        return CommandResult::err(error);
+    }
+    tasks
+        .create(&storage, title.into_inner(), estimated_pomodoros)
+        .into()
+}
+
+#[tauri::command]
+pub fn update_task(
+    tasks: State<'_, TaskManager>,
+    storage: State<'_, StorageService>,
+    id: String,
+    title: TaskTitle,
+    estimated_pomodoros: u32,
+    expected_revision: Option<u64>,
+) -> CommandResult<Task> {
+    if let Err(error) = validate_estimate(estimated_pomodoros) {
+        return CommandResult::err(error);
+    }
+    tasks
+        .update(
+            &storage,
+            &id,
+            title.into_inner(),
+            estimated_pomodoros,
+            expected_revision,
+        )
+        .into()
+}
+
+#[tauri::command]
+pub fn complete_task(
+    tasks: State<'_, TaskManager>,
+    storage: State<'_, StorageService>,
+    undo: State<'_, UndoManager>,
+    id: String,
+    expected_revision: Option<u64>,
+) -> CommandResult<Task> {
+    tasks
+        .complete(&storage, &id, expected_revision)
+        .and_then(|(before, after)| {
+            undo.record(
+                &storage,
+                UndoableAction::CompleteTask {
+                    before,
+                    after: after.clone(),
+                },
+            )?;
+            Ok(after)
+        })
+        .into()
+}
+
+#[tauri::command]
+pub fn delete_task(
+    tasks: State<'_, TaskManager>,
+    storage: State<'_, StorageService>,
+    id: String,
+    expected_revision: Option<u64>,
+) -> CommandResult<Task> {
+    tasks.delete(&storage, &id, expected_revision).into()
+}
+
+/// Moves an archived task back into the task list.
+#[tauri::command]
+pub fn unarchive_task(
+    tasks: State<'_, TaskManager>,
+    storage: State<'_, StorageService>,
+    id: String,
+) -> CommandResult<Task> {
+    tasks.unarchive(&storage, &id).into()
+}
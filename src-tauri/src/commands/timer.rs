@@ -0,0 +1,433 @@
+use futures_util::future::BoxFuture;
+use tauri::{AppHandle, Manager, State};
+
+#[cfg(feature = "simulation")]
+use super::file_access::FileAccessError;
+#[cfg(feature = "simulation")]
+use super::files::authorize_user_path;
+use super::validation::{check_range, Validate, ValidationError};
+use super::CommandResult;
+use crate::services::break_reminder::{break_waiting, BreakReminderService};
+use crate::services::command_trace::traced;
+use crate::services::notifications::notify_completed;
+#[cfg(feature = "simulation")]
+use crate::services::path_grants::PathGrants;
+use crate::services::progress_stream::ProgressStream;
+use crate::services::revision::MutationError;
+#[cfg(feature = "simulation")]
+use crate::services::state_history::{self, ReplayReport};
+use crate::services::state_history::{StateTransition, HISTORY_CAPACITY};
+use crate::services::storage::StorageService;
+use crate::services::tick_drift::{TickDrift, TickMonitor};
+use crate::services::timer_schedule::TimerSchedule;
+use crate::services::timer_state::{SessionType, TimerConfig, TimerData, TimerManager};
+use crate::services::timer_transport::{
+    is_secure_server_url, RemoteTimer, TimerBackend, TimerClient, TimerTransport,
+};
+
+/// Runs `operation` on the focus server selected in the preferences, or on
+/// the local engine.
+async fn on_backend<F>(app: &AppHandle, operation: F) -> Result<TimerData, MutationError>
+where
+    F: for<'a> FnOnce(&'a dyn TimerTransport) -> BoxFuture<'a, Result<TimerData, MutationError>>,
+{
+    match app.state::<TimerClient>().remote()? {
+        Some(remote) => operation(remote.as_ref()).await,
+        None => operation(app.state::<TimerManager>().inner()).await,
+    }
+}
+
+/// Refuses commands the focus server's API has no route for while the timer
+/// runs there, rather than acting on the idle local engine.
+pub(crate) fn local_only<T>(client: &TimerClient) -> Result<(), CommandResult<T>> {
+    match client.remote() {
+        Ok(None) => Ok(()),
+        Ok(Some(_)) => Err(CommandResult::failed(
+            "remote_backend",
+            "Not available while the timer runs on a focus server",
+        )),
+        Err(error) => Err(CommandResult::err(error)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_timer_state(app: AppHandle) -> CommandResult<TimerData> {
+    traced(&app, "get_timer_state", async {
+        on_backend(&app, |timer| timer.get_data()).await.into()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn start_timer(
+    app: AppHandle,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    traced(&app, "start_timer", async {
+        on_backend(&app, |timer| timer.start(expected_revision))
+            .await
+            .into()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn pause_timer(
+    app: AppHandle,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    traced(&app, "pause_timer", async {
+        on_backend(&app, |timer| timer.pause(expected_revision))
+            .await
+            .into()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn resume_timer(
+    app: AppHandle,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    traced(&app, "resume_timer", async {
+        on_backend(&app, |timer| timer.resume(expected_revision))
+            .await
+            .into()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn reset_timer(
+    app: AppHandle,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    traced(&app, "reset_timer", async {
+        on_backend(&app, |timer| timer.reset(expected_revision))
+            .await
+            .into()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn skip_session(
+    app: AppHandle,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    traced(&app, "skip_session", async {
+        on_backend(&app, |timer| timer.skip(expected_revision))
+            .await
+            .into()
+    })
+    .await
+}
+
+async fn connect(app: &AppHandle, url: String, token: &str) -> Result<TimerData, MutationError> {
+    let data = TimerTransport::get_data(&RemoteTimer::new(&url, token)).await?;
+    let storage = app.state::<StorageService>();
+    storage.save_focus_server_token(token)?;
+    let saved = storage.update_preferences(|preferences| {
+        preferences.timer_backend = TimerBackend::Remote { url };
+    })?;
+    app.state::<TimerClient>().configure(&storage, &saved)?;
+    Ok(data)
+}
+
+/// Points the timer commands at the headless focus server at `url` (e.g.
+/// `https://timer.example.com`) after checking `token` against it. Plain
+/// `http://` is only accepted for a server on this machine, since the token
+/// goes with every request. Returns the server's timer.
+#[tauri::command]
+pub async fn connect_focus_server(
+    app: AppHandle,
+    url: String,
+    token: String,
+) -> CommandResult<TimerData> {
+    traced(&app, "connect_focus_server", async {
+        let url = url.trim().trim_end_matches('/').to_string();
+        if !is_secure_server_url(&url) {
+            return CommandResult::invalid(ValidationError::new(
+                "url",
+                "must start with https://, or http:// for a server on this machine",
+            ));
+        }
+        connect(&app, url, token.trim()).await.into()
+    })
+    .await
+}
+
+/// Switches the timer commands back to the local engine.
+#[tauri::command]
+pub fn disconnect_focus_server(
+    storage: State<'_, StorageService>,
+    client: State<'_, TimerClient>,
+) -> CommandResult<()> {
+    storage
+        .update_preferences(|preferences| preferences.timer_backend = TimerBackend::Local)
+        .and_then(|saved| client.configure(&storage, &saved))
+        .into()
+}
+
+/// Starts counting up in an open-ended session of the current type.
+#[tauri::command]
+pub fn start_stopwatch(
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    timer.start_stopwatch(expected_revision).into()
+}
+
+/// Ends the stopwatch, recording its elapsed time in the statistics unless
+/// `record` is `false`.
+#[tauri::command]
+pub fn stop_stopwatch(
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    record: Option<bool>,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    timer
+        .stop_stopwatch(record.unwrap_or(true), expected_revision)
+        .into()
+}
+
+/// The most a session can be extended by at once.
+const MAX_EXTENSION_SECS: u64 = 60 * 60;
+
+/// Adds `seconds` to the running or paused session, e.g. five more
+/// minutes, without resetting it. The extension is kept in the session
+/// record.
+#[tauri::command]
+pub fn extend_session(
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    seconds: u64,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    if let Err(error) = check_range("seconds", seconds, 1, MAX_EXTENSION_SECS) {
+        return CommandResult::invalid(error);
+    }
+    timer.extend_session(seconds, expected_revision).into()
+}
+
+/// The longest a break can be snoozed at once.
+const MAX_SNOOZE_MINUTES: u32 = 60;
+
+/// Puts the reminders about the break waiting to be started off by
+/// `minutes`. Returns the Unix time of the next reminder.
+#[tauri::command]
+pub fn snooze_break(
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    reminders: State<'_, BreakReminderService>,
+    minutes: u32,
+) -> CommandResult<u64> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    if let Err(error) = check_range("minutes", minutes, 1, MAX_SNOOZE_MINUTES) {
+        return CommandResult::invalid(error);
+    }
+    let data = match timer.get_data() {
+        Ok(data) => data,
+        Err(error) => return CommandResult::failed(error.code(), error),
+    };
+    if !break_waiting(&data) {
+        return CommandResult::failed("no_break", "No break is waiting to be started");
+    }
+    reminders.snooze(&data, minutes).into()
+}
+
+/// Switches the idle timer to `session_type`, including custom types.
+#[tauri::command]
+pub fn switch_session(
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    session_type: SessionType,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    timer.switch_session(session_type, expected_revision).into()
+}
+
+#[tauri::command]
+pub fn complete_session(
+    app: AppHandle,
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    let result = timer.complete_session(expected_revision);
+    if let Ok(data) = &result {
+        notify(&app, data);
+    }
+    result.into()
+}
+
+/// Polled by the frontend; returns `true` when the running session just ended.
+#[tauri::command]
+pub fn check_timer_completion(
+    app: AppHandle,
+    timer: State<'_, TimerManager>,
+) -> CommandResult<bool> {
+    let result = timer.check_completion();
+    if let Ok(true) = result {
+        match timer.get_data() {
+            Ok(data) => notify(&app, &data),
+            Err(error) => log::error!("{error}"),
+        }
+    }
+    result.into()
+}
+
+/// A notification that can't be shown shouldn't fail the completion itself.
+fn notify(app: &AppHandle, data: &TimerData) {
+    if let Err(error) = notify_completed(app, data) {
+        log::error!("{error}");
+    }
+}
+
+#[tauri::command]
+pub fn update_timer_config(
+    timer: State<'_, TimerManager>,
+    client: State<'_, TimerClient>,
+    storage: State<'_, StorageService>,
+    config: TimerConfig,
+    expected_revision: Option<u64>,
+) -> CommandResult<TimerData> {
+    if let Err(refused) = local_only(&client) {
+        return refused;
+    }
+    // Validated here rather than through `Validated` so the frontend gets
+    // the offending field back instead of a deserialization error.
+    if let Err(error) = config.validate() {
+        return CommandResult::invalid(error);
+    }
+    apply_config(&timer, &storage, config, expected_revision).into()
+}
+
+/// Switches the engine to `config` and persists it, so it survives restarts.
+pub(crate) fn apply_config(
+    timer: &TimerManager,
+    storage: &StorageService,
+    config: TimerConfig,
+    expected_revision: Option<u64>,
+) -> Result<TimerData, MutationError> {
+    timer.update_config(config, expected_revision, |config| {
+        storage.update_preferences(|prefs| prefs.timer_config = config.clone())?;
+        Ok(())
+    })
+}
+
+/// The weekday and time-of-day windows that use their own timer
+/// configuration.
+#[tauri::command]
+pub fn get_schedule(storage: State<'_, StorageService>) -> CommandResult<TimerSchedule> {
+    storage
+        .load_preferences()
+        .map(|preferences| preferences.timer_schedule)
+        .into()
+}
+
+/// Replaces the schedule. It is consulted whenever a session starts, so a
+/// running session keeps its length.
+#[tauri::command]
+pub fn set_schedule(
+    timer: State<'_, TimerManager>,
+    storage: State<'_, StorageService>,
+    schedule: TimerSchedule,
+) -> CommandResult<TimerSchedule> {
+    if let Err(error) = schedule.validate() {
+        return CommandResult::invalid(error);
+    }
+    storage
+        .update_preferences(|preferences| preferences.timer_schedule = schedule)
+        .and_then(|saved| {
+            timer.configure(&saved)?;
+            Ok(saved.timer_schedule)
+        })
+        .into()
+}
+
+/// The newest `limit` engine transitions (all that are kept by default),
+/// oldest first, each with the state before and after. Saved as a JSON file
+/// they can be fed to `replay_state_history`.
+#[tauri::command]
+pub fn get_state_history(
+    timer: State<'_, TimerManager>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<StateTransition>> {
+    timer.history(limit.unwrap_or(HISTORY_CAPACITY)).into()
+}
+
+/// Turns the `timer://progress` stream on or off; the frontend enables it
+/// while it is animating the progress ring.
+#[tauri::command]
+pub fn set_progress_stream(stream: State<'_, ProgressStream>, enabled: bool) {
+    stream.set_enabled(enabled);
+}
+
+/// How punctually the tick loop has been waking, for diagnosing a timer
+/// that seems to lag.
+#[tauri::command]
+pub fn get_tick_drift(monitor: State<'_, TickMonitor>) -> CommandResult<TickDrift> {
+    monitor.drift().into()
+}
+
+/// Development aid: runs the engine `speed_multiplier` times faster than real
+/// time (e.g. 60×, at most 1000×). Pass `1.0` to return to normal speed.
+#[cfg(feature = "simulation")]
+#[tauri::command]
+pub fn simulate_timer(
+    timer: State<'_, TimerManager>,
+    speed_multiplier: f64,
+) -> CommandResult<TimerData> {
+    timer.set_time_scale(speed_multiplier).into()
+}
+
+/// Development aid: replays a saved `get_state_history` file through a
+/// fresh engine and reports the steps that end in a different state.
+#[cfg(feature = "simulation")]
+#[tauri::command]
+pub fn replay_state_history(
+    storage: State<'_, StorageService>,
+    grants: State<'_, PathGrants>,
+    path: String,
+) -> CommandResult<ReplayReport> {
+    replay(&storage, &grants, &path).into()
+}
+
+#[cfg(feature = "simulation")]
+fn replay(
+    storage: &StorageService,
+    grants: &PathGrants,
+    path: &str,
+) -> Result<ReplayReport, FileAccessError> {
+    let path = authorize_user_path(storage, grants, path, "json")?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let history: Vec<StateTransition> =
+        serde_json::from_str(&content).map_err(|e| FileAccessError::Invalid {
+            message: format!("Not a state history file: {e}"),
+        })?;
+    Ok(state_history::replay(
+        &history,
+        &storage.load_preferences()?,
+    ))
+}
@@ -0,0 +1,18 @@
+use tauri::State;
+
+use super::CommandResult;
+use crate::services::command_trace::CommandTracer;
+
+/// Turns emitting `dev://command` for every command invocation on or off.
+/// Only available in development builds.
+#[tauri::command]
+pub fn set_command_tracing(tracer: State<'_, CommandTracer>, enabled: bool) -> CommandResult<()> {
+    if !cfg!(debug_assertions) {
+        return CommandResult::failed(
+            "unsupported",
+            "Command tracing is only available in development builds",
+        );
+    }
+    tracer.set_enabled(enabled);
+    CommandResult::ok(())
+}
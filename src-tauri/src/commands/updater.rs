@@ -0,0 +1,76 @@
+use tauri::{AppHandle, State};
+
+use super::CommandResult;
+
+use crate::services::command_trace::traced;
+use crate::services::storage::StorageService;
+use crate::services::updater::{UpdateInfo, UpdateInstall};
+
+#[cfg(mobile)]
+const STORE_UPDATES: &str = "Updates are installed through the app store";
+
+/// Looks for a newer release on the channel chosen in preferences. Returns
+/// `None` when this build is current.
+#[tauri::command]
+pub async fn check_for_updates(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+) -> CommandResult<Option<UpdateInfo>> {
+    traced(&app, "check_for_updates", async {
+        #[cfg(desktop)]
+        {
+            find_update(&app, &storage).await.into()
+        }
+        #[cfg(mobile)]
+        {
+            let _ = storage;
+            CommandResult::failed("unsupported", STORE_UPDATES)
+        }
+    })
+    .await
+}
+
+#[cfg(desktop)]
+async fn find_update(
+    app: &AppHandle,
+    storage: &StorageService,
+) -> Result<Option<UpdateInfo>, String> {
+    let channel = storage.load_preferences()?.update_channel;
+    crate::services::updater::check(app, channel)
+        .await
+        .map(|update| update.map(|(_, info)| info))
+}
+
+/// Downloads the latest release and restarts into it. During a focus
+/// session this returns [`UpdateInstall::Deferred`] and the install waits
+/// until the session is over.
+#[tauri::command]
+pub async fn install_update(
+    app: AppHandle,
+    storage: State<'_, StorageService>,
+) -> CommandResult<UpdateInstall> {
+    traced(&app, "install_update", async {
+        #[cfg(desktop)]
+        {
+            install_latest(&app, &storage).await.into()
+        }
+        #[cfg(mobile)]
+        {
+            let _ = storage;
+            CommandResult::failed("unsupported", STORE_UPDATES)
+        }
+    })
+    .await
+}
+
+#[cfg(desktop)]
+async fn install_latest(
+    app: &AppHandle,
+    storage: &StorageService,
+) -> Result<UpdateInstall, String> {
+    let channel = storage.load_preferences()?.update_channel;
+    match crate::services::updater::check(app, channel).await? {
+        Some((update, _)) => crate::services::updater::install(app, update),
+        None => Ok(UpdateInstall::UpToDate),
+    }
+}
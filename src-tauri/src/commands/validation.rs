@@ -0,0 +1,477 @@
+//! Validation of command payloads coming from the webview.
+//!
+//! Structured inputs are wrapped in [`Validated`], which runs the type's
+//! [`Validate`] impl as part of deserialization, so a command body never sees
+//! an out-of-range value. Scalar inputs use newtypes such as [`IsoDate`].
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::ops::Deref;
+use ts_rs::TS;
+
+use crate::services::backup_format::RestoreSelection;
+use crate::services::github_issues::parse_issue;
+use crate::services::revision::MutationError;
+use crate::services::storage::TimerStatistic;
+
+const MAX_RECORDED_SESSION_SECS: u64 = 24 * 60 * 60;
+pub(super) const MAX_TITLE_CHARS: usize = 200;
+const MAX_TAG_CHARS: usize = 50;
+const MAX_TAGS_PER_SESSION: usize = 20;
+const MAX_ESTIMATED_POMODOROS: u32 = 100;
+const MAX_CONTRIBUTION_LEVELS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid {}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<ValidationError> for String {
+    fn from(error: ValidationError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<ValidationError> for MutationError {
+    fn from(error: ValidationError) -> Self {
+        MutationError::Failed(error.to_string())
+    }
+}
+
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+/// A payload that has passed [`Validate`] during deserialization.
+#[derive(Debug, Clone)]
+pub struct Validated<T>(T);
+
+impl<T> Validated<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Validated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Validated<T>
+where
+    T: Deserialize<'de> + Validate,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = T::deserialize(deserializer)?;
+        value.validate().map_err(serde::de::Error::custom)?;
+        Ok(Validated(value))
+    }
+}
+
+pub(crate) fn check_range<T: PartialOrd + fmt::Display>(
+    field: &str,
+    value: T,
+    min: T,
+    max: T,
+) -> Result<(), ValidationError> {
+    if value < min || value > max {
+        return Err(ValidationError::new(
+            field,
+            format!("{value} is outside {min}..={max}"),
+        ));
+    }
+    Ok(())
+}
+
+impl Validate for TimerStatistic {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_date("date", &self.date)?;
+        for session in &self.sessions {
+            check_range(
+                "sessions.duration",
+                session.duration,
+                0,
+                MAX_RECORDED_SESSION_SECS,
+            )?;
+            if session.tags.len() > MAX_TAGS_PER_SESSION {
+                return Err(ValidationError::new(
+                    "sessions.tags",
+                    format!("at most {MAX_TAGS_PER_SESSION} tags per session"),
+                ));
+            }
+            for tag in &session.tags {
+                validate_tag(tag)?;
+            }
+            if session
+                .issue
+                .as_deref()
+                .is_some_and(|issue| parse_issue(issue).as_deref() != Some(issue))
+            {
+                return Err(ValidationError::new(
+                    "sessions.issue",
+                    "must be an owner/repo#123 issue reference",
+                ));
+            }
+            if session.end_time.is_some_and(|end| end < session.start_time) {
+                return Err(ValidationError::new(
+                    "sessions.end_time",
+                    "must not be before start_time",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Validate for RestoreSelection {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(start) = &self.start_date {
+            validate_date("start_date", start)?;
+        }
+        if let Some(end) = &self.end_date {
+            validate_date("end_date", end)?;
+        }
+        if let (Some(start), Some(end)) = (&self.start_date, &self.end_date) {
+            if start > end {
+                return Err(ValidationError::new(
+                    "date range",
+                    format!("start {start} is after end {end}"),
+                ));
+            }
+        }
+        if !(self.preferences || self.tasks || self.statistics) {
+            return Err(ValidationError::new(
+                "selection",
+                "choose at least one part of the backup to restore",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Parses a strict `YYYY-MM-DD` calendar date from 1970 on.
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    // chrono also reads unpadded and signed fields; date keys have neither.
+    let bytes = value.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .filter(|date| date.year() >= 1970)
+}
+
+pub fn validate_date(field: &str, value: &str) -> Result<(), ValidationError> {
+    parse_date(value)
+        .map(|_| ())
+        .ok_or_else(|| ValidationError::new(field, format!("'{value}' is not a YYYY-MM-DD date")))
+}
+
+/// A `YYYY-MM-DD` date argument.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, TS)]
+#[ts(export, type = "string")]
+#[serde(try_from = "String")]
+pub struct IsoDate {
+    date: NaiveDate,
+    text: String,
+}
+
+impl IsoDate {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn to_naive(&self) -> NaiveDate {
+        self.date
+    }
+}
+
+impl TryFrom<String> for IsoDate {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let date = parse_date(&value).ok_or_else(|| {
+            ValidationError::new("date", format!("'{value}' is not a YYYY-MM-DD date"))
+        })?;
+        Ok(IsoDate { date, text: value })
+    }
+}
+
+pub fn validate_date_range(
+    start: Option<&IsoDate>,
+    end: Option<&IsoDate>,
+) -> Result<(), ValidationError> {
+    match (start, end) {
+        (Some(start), Some(end)) if start > end => Err(ValidationError::new(
+            "date range",
+            format!("start {} is after end {}", start.as_str(), end.as_str()),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// A non-blank task title of bounded length.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export, type = "string")]
+#[serde(try_from = "String")]
+pub struct TaskTitle(String);
+
+impl TaskTitle {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+/// Trims `value` and checks it is non-blank and at most
+/// [`MAX_TITLE_CHARS`] long.
+fn trimmed_title(field: &str, value: &str) -> Result<String, ValidationError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(ValidationError::new(field, "must not be empty"));
+    }
+    if trimmed.chars().count() > MAX_TITLE_CHARS {
+        return Err(ValidationError::new(
+            field,
+            format!("must be at most {MAX_TITLE_CHARS} characters"),
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+impl TryFrom<String> for TaskTitle {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        trimmed_title("title", &value).map(TaskTitle)
+    }
+}
+
+/// A non-blank timer preset name of bounded length.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export, type = "string")]
+#[serde(try_from = "String")]
+pub struct PresetName(String);
+
+impl PresetName {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for PresetName {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        trimmed_title("name", &value).map(PresetName)
+    }
+}
+
+/// A free-form session tag, trimmed and lowercased so "Writing" and
+/// "writing" are counted together.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, TS)]
+#[ts(export, type = "string")]
+#[serde(try_from = "String")]
+pub struct SessionTag(String);
+
+impl SessionTag {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for SessionTag {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let tag = value.trim().to_lowercase();
+        validate_tag(&tag)?;
+        Ok(SessionTag(tag))
+    }
+}
+
+/// A GitHub issue given as `owner/repo#123` or its URL, held in the
+/// canonical `owner/repo#123` form.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, TS)]
+#[ts(export, type = "string")]
+#[serde(try_from = "String")]
+pub struct GithubIssue(String);
+
+impl GithubIssue {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<String> for GithubIssue {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_issue(&value).map(GithubIssue).ok_or_else(|| {
+            ValidationError::new(
+                "issue",
+                format!("'{value}' is not an owner/repo#123 reference or GitHub issue URL"),
+            )
+        })
+    }
+}
+
+fn validate_tag(tag: &str) -> Result<(), ValidationError> {
+    let chars = tag.chars().count();
+    if chars == 0 || chars > MAX_TAG_CHARS {
+        return Err(ValidationError::new(
+            "tags",
+            format!("each tag must be between 1 and {MAX_TAG_CHARS} characters"),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the tag count and returns the tags deduplicated, in order.
+pub fn normalize_tags(tags: Vec<SessionTag>) -> Result<Vec<String>, ValidationError> {
+    if tags.len() > MAX_TAGS_PER_SESSION {
+        return Err(ValidationError::new(
+            "tags",
+            format!("at most {MAX_TAGS_PER_SESSION} tags per session"),
+        ));
+    }
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags.into_iter().map(SessionTag::into_inner) {
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    Ok(normalized)
+}
+
+/// Contribution-graph thresholds: at least one level, strictly ascending and
+/// starting above zero so level 0 always means "no pomodoros".
+pub fn validate_thresholds(thresholds: &[u32]) -> Result<(), ValidationError> {
+    const FIELD: &str = "thresholds";
+    if thresholds.is_empty() || thresholds.len() > MAX_CONTRIBUTION_LEVELS {
+        return Err(ValidationError::new(
+            FIELD,
+            format!("must have between 1 and {MAX_CONTRIBUTION_LEVELS} levels"),
+        ));
+    }
+    if thresholds[0] == 0 {
+        return Err(ValidationError::new(FIELD, "must start at 1 or more"));
+    }
+    if thresholds.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(ValidationError::new(FIELD, "must be strictly ascending"));
+    }
+    Ok(())
+}
+
+pub fn validate_estimate(estimated_pomodoros: u32) -> Result<(), ValidationError> {
+    check_range(
+        "estimated_pomodoros",
+        estimated_pomodoros,
+        0,
+        MAX_ESTIMATED_POMODOROS,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn parse_date_never_panics(input in "\\PC{0,16}") {
+            let _ = parse_date(&input);
+        }
+
+        #[test]
+        fn parse_date_accepts_formatted_dates(year in 1970u32..=9999, month in 1u32..=12, day in 1u32..=28) {
+            let formatted = format!("{year:04}-{month:02}-{day:02}");
+            prop_assert_eq!(parse_date(&formatted), NaiveDate::from_ymd_opt(year as i32, month, day));
+        }
+
+        #[test]
+        fn parse_date_rejects_invalid_months(year in 1970u32..=9999, month in 13u32..=99, day in 1u32..=28) {
+            prop_assert_eq!(parse_date(&format!("{year:04}-{month:02}-{day:02}")), None);
+        }
+
+        #[test]
+        fn arbitrary_statistic_payloads_never_panic(input in "\\PC{0,256}") {
+            if let Ok(statistic) = serde_json::from_str::<TimerStatistic>(&input) {
+                let _ = statistic.validate();
+            }
+        }
+    }
+
+    #[test]
+    fn leap_days_follow_the_gregorian_calendar() {
+        assert!(parse_date("2024-02-29").is_some());
+        assert!(parse_date("2000-02-29").is_some());
+        assert!(parse_date("1900-02-29").is_none());
+        assert!(parse_date("2023-02-29").is_none());
+    }
+
+    #[test]
+    fn dates_must_be_padded_and_unsigned() {
+        assert!(parse_date("2026-3-015").is_none());
+        assert!(parse_date("+2026-03-1").is_none());
+        assert!(parse_date("1969-12-31").is_none());
+        assert!(parse_date("2026-03-15").is_some());
+    }
+
+    #[test]
+    fn github_issues_accept_shorthand_and_urls() {
+        let issue =
+            |value: &str| GithubIssue::try_from(value.to_string()).map(GithubIssue::into_inner);
+        assert_eq!(issue("Acme/Widgets#42").unwrap(), "acme/widgets#42");
+        assert_eq!(
+            issue("https://github.com/acme/widgets/issues/42#issuecomment-1").unwrap(),
+            "acme/widgets#42"
+        );
+        assert_eq!(
+            issue("https://github.com/acme/widgets.rs/pull/7/").unwrap(),
+            "acme/widgets.rs#7"
+        );
+        assert!(issue("acme/widgets#0").is_err());
+        assert!(issue("widgets#42").is_err());
+        assert!(issue("https://gitlab.com/acme/widgets/issues/42").is_err());
+        assert!(issue("https://github.com/acme/widgets/commit/42").is_err());
+    }
+
+    #[test]
+    fn restore_selection_bounds_must_be_ordered_dates() {
+        let selection = |start: &str, end: &str| RestoreSelection {
+            start_date: Some(start.to_string()),
+            end_date: Some(end.to_string()),
+            ..RestoreSelection::default()
+        };
+        assert!(selection("2024-01-01", "2024-03-31").validate().is_ok());
+        assert!(selection("2024-03-31", "2024-01-01").validate().is_err());
+        assert!(selection("2024-13-01", "2024-12-31").validate().is_err());
+    }
+}
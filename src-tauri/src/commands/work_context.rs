@@ -0,0 +1,83 @@
+use tauri::State;
+
+use super::validation::{validate_date_range, IsoDate, ValidationError};
+use super::{CommandError, CommandResult};
+use crate::services::context_detection::{ActiveContext, ContextDetector, NetworkObservation};
+use crate::services::storage::{StorageService, UserPreferences};
+use crate::services::work_context::{self, ContextFocus};
+
+/// The context sessions ending now are recorded in.
+#[tauri::command]
+pub fn get_work_context(detector: State<'_, ContextDetector>) -> CommandResult<ActiveContext> {
+    detector.current().into()
+}
+
+/// Picks the context sessions are recorded in, or for `None` leaves it to
+/// detection, if that is on.
+#[tauri::command]
+pub fn set_work_context(
+    storage: State<'_, StorageService>,
+    detector: State<'_, ContextDetector>,
+    name: Option<String>,
+) -> CommandResult<UserPreferences> {
+    pick_context(&storage, &detector, name).into()
+}
+
+fn pick_context(
+    storage: &StorageService,
+    detector: &ContextDetector,
+    name: Option<String>,
+) -> Result<UserPreferences, CommandError> {
+    let contexts = storage.load_preferences()?.work_context.contexts;
+    if let Some(name) = &name {
+        if !contexts.iter().any(|context| context.name == *name) {
+            return Err(ValidationError::new("name", format!("unknown context {name}")).into());
+        }
+    }
+    let saved = storage.update_preferences(|preferences| preferences.work_context.manual = name)?;
+    detector.configure(&saved)?;
+    Ok(saved)
+}
+
+/// The Wi-Fi network and hostname detection sees now, for setting up
+/// contexts. Refused while detection is off.
+#[tauri::command]
+pub fn get_network_observation(
+    detector: State<'_, ContextDetector>,
+) -> CommandResult<NetworkObservation> {
+    match detector.observe() {
+        Ok(Some(observation)) => CommandResult::ok(observation),
+        Ok(None) => CommandResult::failed(
+            "detection_off",
+            "Turn on work context detection to read the current network",
+        ),
+        Err(error) => CommandResult::err(error),
+    }
+}
+
+/// Focus sessions over the optional date range grouped by the context
+/// they were recorded in, to compare focus between places.
+#[tauri::command]
+pub fn get_context_comparison(
+    storage: State<'_, StorageService>,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> CommandResult<Vec<ContextFocus>> {
+    context_comparison(&storage, start_date, end_date).into()
+}
+
+fn context_comparison(
+    storage: &StorageService,
+    start_date: Option<IsoDate>,
+    end_date: Option<IsoDate>,
+) -> Result<Vec<ContextFocus>, CommandError> {
+    validate_date_range(start_date.as_ref(), end_date.as_ref())?;
+    let statistics = storage.load_statistics(
+        start_date.as_ref().map(IsoDate::to_naive),
+        end_date.as_ref().map(IsoDate::to_naive),
+    )?;
+    Ok(work_context::compare(
+        &statistics,
+        &storage.custom_session_types()?,
+    ))
+}
@@ -0,0 +1,196 @@
+//! `tempusring://` links, so browsers, Raycast or Alfred can control the
+//! timer:
+//!
+//! ```text
+//! tempusring://start                 start the next session
+//! tempusring://start?duration=1500   start it for 1500 seconds instead
+//! tempusring://pause
+//! tempusring://resume
+//! tempusring://reset
+//! tempusring://skip
+//! ```
+//!
+//! Links go to the timer backend like the timer commands, within the scope
+//! granted in `UserPreferences::external_access`. Nobody waits for an
+//! answer, so links that keep failing are reported in the inbox.
+
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::commands::validation::{check_range, ValidationError};
+use crate::services::revision::MutationError;
+use crate::services::service_health::{self, BackgroundService};
+use crate::services::storage::StorageService;
+use crate::services::surface_auth::{Access, Surface};
+use crate::services::timer_state::{TimerData, TimerManager};
+use crate::services::timer_transport::{TimerClient, TimerTransport};
+
+const SCHEME: &str = "tempusring";
+const MIN_DURATION_SECS: u64 = 60;
+const MAX_DURATION_SECS: u64 = 4 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkAction {
+    Start { duration: Option<u64> },
+    Pause,
+    Resume,
+    Reset,
+    Skip,
+}
+
+fn parse(url: &Url) -> Result<LinkAction, ValidationError> {
+    if url.scheme() != SCHEME {
+        return Err(ValidationError::new(
+            "url",
+            format!("must start with {SCHEME}://"),
+        ));
+    }
+    // `tempusring://start` names the action as the host, `tempusring:start`
+    // as the path.
+    let action = match url.host_str() {
+        Some(host) if !host.is_empty() => host,
+        _ => url.path().trim_matches('/'),
+    };
+    let mut duration = None;
+    for (key, value) in url.query_pairs() {
+        if action != "start" || key != "duration" {
+            return Err(ValidationError::new(
+                "url",
+                format!("{action} takes no {key} parameter"),
+            ));
+        }
+        let seconds = value
+            .parse()
+            .map_err(|_| ValidationError::new("duration", "must be a number of seconds"))?;
+        check_range("duration", seconds, MIN_DURATION_SECS, MAX_DURATION_SECS)?;
+        duration = Some(seconds);
+    }
+    match action {
+        "start" => Ok(LinkAction::Start { duration }),
+        "pause" => Ok(LinkAction::Pause),
+        "resume" => Ok(LinkAction::Resume),
+        "reset" => Ok(LinkAction::Reset),
+        "skip" => Ok(LinkAction::Skip),
+        other => Err(ValidationError::new(
+            "url",
+            format!("unknown action '{other}'"),
+        )),
+    }
+}
+
+/// Runs `action` on the focus server selected in the preferences, or on
+/// the local engine.
+async fn run(app: &AppHandle, action: LinkAction) -> Result<TimerData, MutationError> {
+    let remote = app.state::<TimerClient>().remote()?;
+    let local = app.state::<TimerManager>();
+    let timer: &dyn TimerTransport = match &remote {
+        Some(remote) => remote.as_ref(),
+        None => local.inner(),
+    };
+    match action {
+        LinkAction::Start {
+            duration: Some(duration),
+        } if remote.is_none() => local.start_for(Some(duration), None),
+        LinkAction::Start { duration: Some(_) } => {
+            Err("A focus server can't start a session for a set duration"
+                .to_string()
+                .into())
+        }
+        LinkAction::Start { duration: None } => timer.start(None).await,
+        LinkAction::Pause => timer.pause(None).await,
+        LinkAction::Resume => timer.resume(None).await,
+        LinkAction::Reset => timer.reset(None).await,
+        LinkAction::Skip => timer.skip(None).await,
+    }
+}
+
+async fn follow(app: &AppHandle, url: &Url) -> Result<(), String> {
+    app.state::<StorageService>()
+        .load_preferences()?
+        .external_access
+        .check(Surface::DeepLink, Access::Control)
+        .map_err(|e| e.to_string())?;
+    let action = parse(url).map_err(|e| e.to_string())?;
+    run(app, action).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn open(app: &AppHandle, url: Url) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = follow(&app, &url)
+            .await
+            .map_err(|error| format!("Link not followed: {url}: {error}"));
+        if let Err(error) = &result {
+            log::error!("{error}");
+        }
+        service_health::report(&app, BackgroundService::DeepLinks, &result);
+    });
+}
+
+/// Registers the scheme where that happens at runtime and follows links
+/// opened from now on, and the one the app was launched with.
+pub fn listen(app: &AppHandle) -> Result<(), String> {
+    #[cfg(any(windows, target_os = "linux"))]
+    app.deep_link()
+        .register_all()
+        .map_err(|e| format!("Failed to register {SCHEME}:// links: {e}"))?;
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, url);
+        }
+    });
+    let current = app
+        .deep_link()
+        .get_current()
+        .map_err(|e| format!("Failed to read the launch link: {e}"))?;
+    for url in current.into_iter().flatten() {
+        open(app, url);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(url: &str) -> Result<LinkAction, ValidationError> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn actions_parse_from_host_or_path() {
+        assert_eq!(
+            parsed("tempusring://start"),
+            Ok(LinkAction::Start { duration: None })
+        );
+        assert_eq!(
+            parsed("tempusring://start/?duration=1500"),
+            Ok(LinkAction::Start {
+                duration: Some(1500)
+            })
+        );
+        assert_eq!(parsed("tempusring:pause"), Ok(LinkAction::Pause));
+        assert_eq!(parsed("tempusring://resume/"), Ok(LinkAction::Resume));
+        assert_eq!(parsed("tempusring://reset"), Ok(LinkAction::Reset));
+        assert_eq!(parsed("tempusring://skip"), Ok(LinkAction::Skip));
+    }
+
+    #[test]
+    fn bad_links_are_rejected() {
+        for url in [
+            "https://start",
+            "tempusring://stop",
+            "tempusring://",
+            "tempusring://pause?duration=60",
+            "tempusring://start?length=60",
+            "tempusring://start?duration=soon",
+            "tempusring://start?duration=59",
+            "tempusring://start?duration=14401",
+        ] {
+            assert!(parsed(url).is_err(), "{url} was accepted");
+        }
+        assert!(parsed("tempusring://start?duration=14400").is_ok());
+    }
+}
@@ -0,0 +1,417 @@
+//! Optional gRPC control interface (`--features grpc`) for scripts and
+//! integrations. The services call the same managers as the Tauri commands,
+//! so both surfaces see one timer, one task list and one set of statistics.
+//!
+//! The server only listens on the loopback interface, and every call must
+//! carry `authorization: Bearer <token>` with the token from
+//! `get_surface_token`. Calls are then limited to the scope granted in
+//! `UserPreferences::external_access`.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status};
+
+use crate::commands::validation::{validate_date_range, validate_estimate, IsoDate, TaskTitle};
+use crate::services::listen_addr::{listen_addr, Interfaces};
+use crate::services::revision::MutationError;
+use crate::services::storage::{SessionData, StorageService, TimerStatistic};
+use crate::services::surface_auth::{Access, AuthError, Surface, SurfaceAuth};
+use crate::services::tasks::{Task, TaskManager};
+use crate::services::timer_error::TimerError;
+use crate::services::timer_state::{DisplayMode, TimerData, TimerManager, TimerState};
+use crate::services::undo::{UndoManager, UndoableAction};
+
+pub mod proto {
+    tonic::include_proto!("tempus.v1");
+}
+
+use proto::stats_server::{Stats, StatsServer};
+use proto::tasks_server::{Tasks, TasksServer};
+use proto::timer_server::{Timer, TimerServer};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:50151";
+/// Overrides [`DEFAULT_ADDR`]; must still be a loopback address.
+const ADDR_ENV: &str = "TEMPUS_GRPC_ADDR";
+/// How often `WatchState` samples the timer for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+fn mutation_status(error: MutationError) -> Status {
+    match error {
+        MutationError::Conflict(conflict) => Status::aborted(conflict.to_string()),
+        MutationError::Timer(error @ TimerError::LockPoisoned(_)) => {
+            Status::internal(error.to_string())
+        }
+        MutationError::Timer(error) => Status::failed_precondition(error.to_string()),
+        MutationError::Failed(message) => Status::failed_precondition(message),
+    }
+}
+
+fn auth_status(error: AuthError) -> Status {
+    match error {
+        AuthError::MissingToken | AuthError::InvalidToken => {
+            Status::unauthenticated(error.to_string())
+        }
+        AuthError::Disabled(_) | AuthError::ReadOnly(_) => {
+            Status::permission_denied(error.to_string())
+        }
+        AuthError::Failed(message) => Status::internal(message),
+    }
+}
+
+/// Rejects a call unless its metadata has a valid token and the gRPC scope
+/// allows `access`.
+fn authorize(app: &AppHandle, metadata: &MetadataMap, access: Access) -> Result<(), AuthError> {
+    let token = metadata
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    app.state::<SurfaceAuth>().authorize(
+        &app.state::<StorageService>(),
+        Surface::Grpc,
+        token,
+        access,
+    )
+}
+
+impl From<TimerData> for proto::TimerData {
+    fn from(data: TimerData) -> Self {
+        let state = match data.state {
+            TimerState::Idle => "idle",
+            TimerState::Running => "running",
+            TimerState::Paused => "paused",
+            TimerState::Stopwatch => "stopwatch",
+            TimerState::Overtime => "overtime",
+        };
+        let display_mode = match data.display_mode {
+            DisplayMode::Countdown => "countdown",
+            DisplayMode::CountUp => "count_up",
+            DisplayMode::Percent => "percent",
+        };
+        Self {
+            state: state.to_string(),
+            session_type: data.session_type.key().to_string(),
+            remaining_time: data.remaining_time,
+            total_time: data.total_time,
+            progress: data.progress,
+            completed_pomodoros: data.completed_pomodoros,
+            start_time: data.start_time,
+            revision: data.revision,
+            pomodoro_in_cycle: data.cycle.pomodoro_in_cycle,
+            pomodoros_per_cycle: data.cycle.pomodoros_per_cycle,
+            next_break_is_long: data.cycle.next_break_is_long,
+            cycle_end_time: data.cycle.projected_end_time,
+            elapsed_ms: data.elapsed_ms,
+            remaining_ms: data.remaining_ms,
+            overtime: data.overtime,
+            elapsed_time: data.elapsed_time,
+            percent_complete: data.percent_complete,
+            display_mode: display_mode.to_string(),
+        }
+    }
+}
+
+impl From<SessionData> for proto::Session {
+    fn from(session: SessionData) -> Self {
+        Self {
+            session_type: session.session_type.key().to_string(),
+            duration: session.duration,
+            completed: session.completed,
+            start_time: session.start_time,
+            end_time: session.end_time,
+            task_id: session.task_id,
+            tags: session.tags,
+            issue: session.issue,
+            context: session.context,
+            overtime: session.overtime,
+            extended: session.extended,
+        }
+    }
+}
+
+impl From<TimerStatistic> for proto::DayStatistic {
+    fn from(statistic: TimerStatistic) -> Self {
+        Self {
+            date: statistic.date,
+            completed_pomodoros: statistic.completed_pomodoros,
+            total_focus_time: statistic.total_focus_time,
+            sessions: statistic.sessions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Task> for proto::Task {
+    fn from(task: Task) -> Self {
+        Self {
+            id: task.id,
+            title: task.title,
+            estimated_pomodoros: task.estimated_pomodoros,
+            completed_pomodoros: task.completed_pomodoros,
+            completed: task.completed,
+            created_at: task.created_at,
+            completed_at: task.completed_at,
+            revision: task.revision,
+        }
+    }
+}
+
+struct TimerService {
+    app: AppHandle,
+}
+
+impl TimerService {
+    fn mutate(
+        &self,
+        request: Request<proto::RevisionRequest>,
+        action: fn(&TimerManager, Option<u64>) -> Result<TimerData, MutationError>,
+    ) -> Result<proto::TimerData, MutationError> {
+        let timer = self.app.state::<TimerManager>();
+        action(&timer, request.into_inner().expected_revision).map(Into::into)
+    }
+}
+
+type TimerStream =
+    Pin<Box<dyn tokio_stream::Stream<Item = Result<proto::TimerData, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Timer for TimerService {
+    type WatchStateStream = TimerStream;
+
+    async fn get_state(
+        &self,
+        request: Request<proto::Empty>,
+    ) -> Result<Response<proto::TimerData>, Status> {
+        authorize(&self.app, request.metadata(), Access::Read).map_err(auth_status)?;
+        let timer = self.app.state::<TimerManager>();
+        timer
+            .get_data()
+            .map(|data| Response::new(data.into()))
+            .map_err(Status::internal)
+    }
+
+    async fn start(
+        &self,
+        request: Request<proto::RevisionRequest>,
+    ) -> Result<Response<proto::TimerData>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        self.mutate(request, TimerManager::start)
+            .map(Response::new)
+            .map_err(mutation_status)
+    }
+
+    async fn pause(
+        &self,
+        request: Request<proto::RevisionRequest>,
+    ) -> Result<Response<proto::TimerData>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        self.mutate(request, TimerManager::pause)
+            .map(Response::new)
+            .map_err(mutation_status)
+    }
+
+    async fn resume(
+        &self,
+        request: Request<proto::RevisionRequest>,
+    ) -> Result<Response<proto::TimerData>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        self.mutate(request, TimerManager::resume)
+            .map(Response::new)
+            .map_err(mutation_status)
+    }
+
+    async fn reset(
+        &self,
+        request: Request<proto::RevisionRequest>,
+    ) -> Result<Response<proto::TimerData>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        self.mutate(request, TimerManager::reset)
+            .map(Response::new)
+            .map_err(mutation_status)
+    }
+
+    async fn skip(
+        &self,
+        request: Request<proto::RevisionRequest>,
+    ) -> Result<Response<proto::TimerData>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        self.mutate(request, TimerManager::skip)
+            .map(Response::new)
+            .map_err(mutation_status)
+    }
+
+    async fn watch_state(
+        &self,
+        request: Request<proto::Empty>,
+    ) -> Result<Response<Self::WatchStateStream>, Status> {
+        authorize(&self.app, request.metadata(), Access::Read).map_err(auth_status)?;
+        let (sender, receiver) = tokio::sync::mpsc::channel(16);
+        let app = self.app.clone();
+        let metadata = request.metadata().clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(WATCH_INTERVAL);
+            let mut last_sent: Option<proto::TimerData> = None;
+            loop {
+                interval.tick().await;
+                // Rotating the token or narrowing the scope ends open streams too.
+                if let Err(error) = authorize(&app, &metadata, Access::Read) {
+                    let _ = sender.send(Err(auth_status(error))).await;
+                    break;
+                }
+                let update = match app.state::<TimerManager>().get_data() {
+                    Ok(data) => {
+                        let data = proto::TimerData::from(data);
+                        if last_sent.as_ref() == Some(&data) {
+                            continue;
+                        }
+                        last_sent = Some(data.clone());
+                        Ok(data)
+                    }
+                    Err(error) => Err(Status::internal(error)),
+                };
+                if sender.send(update).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+}
+
+struct StatsService {
+    app: AppHandle,
+}
+
+#[tonic::async_trait]
+impl Stats for StatsService {
+    async fn load_statistics(
+        &self,
+        request: Request<proto::StatisticsRequest>,
+    ) -> Result<Response<proto::StatisticsResponse>, Status> {
+        authorize(&self.app, request.metadata(), Access::Read).map_err(auth_status)?;
+        let request = request.into_inner();
+        let dates = request
+            .start_date
+            .map(IsoDate::try_from)
+            .transpose()
+            .and_then(|start| Ok((start, request.end_date.map(IsoDate::try_from).transpose()?)))
+            .and_then(|(start, end)| {
+                validate_date_range(start.as_ref(), end.as_ref())?;
+                Ok((start, end))
+            });
+        let (start_date, end_date) = dates.map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let storage = self.app.state::<StorageService>();
+        let days = storage
+            .load_statistics(
+                start_date.as_ref().map(IsoDate::to_naive),
+                end_date.as_ref().map(IsoDate::to_naive),
+            )
+            .map_err(Status::internal)?;
+        Ok(Response::new(proto::StatisticsResponse {
+            days: days.into_iter().map(Into::into).collect(),
+        }))
+    }
+}
+
+struct TasksService {
+    app: AppHandle,
+}
+
+#[tonic::async_trait]
+impl Tasks for TasksService {
+    async fn list(
+        &self,
+        request: Request<proto::Empty>,
+    ) -> Result<Response<proto::TaskList>, Status> {
+        authorize(&self.app, request.metadata(), Access::Read).map_err(auth_status)?;
+        let tasks = self.app.state::<TaskManager>();
+        let tasks = tasks.list().map_err(Status::internal)?;
+        Ok(Response::new(proto::TaskList {
+            tasks: tasks.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn create(
+        &self,
+        request: Request<proto::CreateTaskRequest>,
+    ) -> Result<Response<proto::Task>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        let request = request.into_inner();
+        let title = TaskTitle::try_from(request.title)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        validate_estimate(request.estimated_pomodoros)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let tasks = self.app.state::<TaskManager>();
+        let storage = self.app.state::<StorageService>();
+        tasks
+            .create(&storage, title.into_inner(), request.estimated_pomodoros)
+            .map(|task| Response::new(task.into()))
+            .map_err(Status::internal)
+    }
+
+    /// Recorded in undo history, like `complete_task`.
+    async fn complete(
+        &self,
+        request: Request<proto::TaskRequest>,
+    ) -> Result<Response<proto::Task>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        let request = request.into_inner();
+        let tasks = self.app.state::<TaskManager>();
+        let storage = self.app.state::<StorageService>();
+        let undo = self.app.state::<UndoManager>();
+
+        let (before, after) = tasks
+            .complete(&storage, &request.id, request.expected_revision)
+            .map_err(mutation_status)?;
+        undo.record(
+            &storage,
+            UndoableAction::CompleteTask {
+                before,
+                after: after.clone(),
+            },
+        )
+        .map_err(Status::internal)?;
+        Ok(Response::new(after.into()))
+    }
+
+    async fn delete(
+        &self,
+        request: Request<proto::TaskRequest>,
+    ) -> Result<Response<proto::Task>, Status> {
+        authorize(&self.app, request.metadata(), Access::Control).map_err(auth_status)?;
+        let request = request.into_inner();
+        let tasks = self.app.state::<TaskManager>();
+        let storage = self.app.state::<StorageService>();
+        tasks
+            .delete(&storage, &request.id, request.expected_revision)
+            .map(|task| Response::new(task.into()))
+            .map_err(mutation_status)
+    }
+}
+
+/// Starts the gRPC server in the background. Failures are logged rather than
+/// aborting the app, since the desktop UI doesn't depend on it.
+pub fn spawn_server(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let addr = match listen_addr(ADDR_ENV, DEFAULT_ADDR, Interfaces::Loopback) {
+            Ok(addr) => addr,
+            Err(error) => {
+                log::error!("gRPC server not started: {error}");
+                return;
+            }
+        };
+        let result = tonic::transport::Server::builder()
+            .add_service(TimerServer::new(TimerService { app: app.clone() }))
+            .add_service(StatsServer::new(StatsService { app: app.clone() }))
+            .add_service(TasksServer::new(TasksService { app }))
+            .serve(addr)
+            .await;
+        if let Err(error) = result {
+            log::error!("gRPC server stopped: {error}");
+        }
+    });
+}
@@ -0,0 +1,121 @@
+//! Headless focus server (`--features headless`): the timer engine, storage,
+//! REST API and state socket without the webview, for a shared family or
+//! team timer that desktop clients and overlays attach to. Built as the
+//! `tempus-focus-server` binary.
+//!
+//! Data lives in `TEMPUS_DATA_DIR` (default `tempus-data`). The servers
+//! listen on localhost unless `TEMPUS_HTTP_ADDR` and `TEMPUS_WS_ADDR` name
+//! another interface; they speak plain HTTP, so clients on other machines
+//! should reach them through a TLS proxy. The access token stays in the
+//! file whose path is printed at startup, the same one `get_surface_token`
+//! shows on a desktop sharing that data.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::net::TcpListener;
+
+use crate::http;
+use crate::services::api_context::ApiContext;
+use crate::services::listen_addr::{listen_addr, Interfaces};
+use crate::services::session_recorder;
+use crate::services::state_socket;
+use crate::services::storage::StorageService;
+use crate::services::surface_auth::SurfaceAuth;
+use crate::services::tick_drift;
+use crate::services::timer_state::TimerManager;
+
+const DATA_DIR_ENV: &str = "TEMPUS_DATA_DIR";
+const DEFAULT_DATA_DIR: &str = "tempus-data";
+
+/// Writes log records to stderr, standing in for the log plugin the
+/// desktop app registers.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+struct Services {
+    timer: TimerManager,
+    storage: StorageService,
+    auth: SurfaceAuth,
+}
+
+#[derive(Clone)]
+struct FocusServer(Arc<Services>);
+
+impl ApiContext for FocusServer {
+    fn timer(&self) -> &TimerManager {
+        &self.0.timer
+    }
+
+    fn storage(&self) -> &StorageService {
+        &self.0.storage
+    }
+
+    fn auth(&self) -> &SurfaceAuth {
+        &self.0.auth
+    }
+}
+
+impl FocusServer {
+    async fn run_ticker(self) {
+        loop {
+            let until_next_second = self.timer().until_next_second().unwrap_or_else(|error| {
+                log::error!("{error}");
+                None
+            });
+            tokio::time::sleep(tick_drift::next_tick_delay(until_next_second)).await;
+            if let Err(error) = session_recorder::tick_and_record(self.timer(), self.storage()) {
+                log::error!("Timer tick failed: {error}");
+            }
+        }
+    }
+}
+
+async fn bind(env: &str, default: &str) -> Result<TcpListener, String> {
+    let addr = listen_addr(env, default, Interfaces::Any)?;
+    TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to listen on {addr}: {e}"))
+}
+
+/// Runs the focus server until the REST API stops.
+pub fn run() -> Result<(), String> {
+    log::set_logger(&StderrLogger)
+        .map(|()| log::set_max_level(log::LevelFilter::Info))
+        .map_err(|e| e.to_string())?;
+    let data_dir = std::env::var_os(DATA_DIR_ENV)
+        .map_or_else(|| PathBuf::from(DEFAULT_DATA_DIR), PathBuf::from);
+    let storage = StorageService::open(data_dir)?;
+    storage.lock_instance()?;
+    let preferences = storage.load_preferences()?;
+    let timer = TimerManager::new(preferences.timer_config.clone());
+    timer.configure(&preferences)?;
+    let auth = SurfaceAuth::load(&storage)?;
+    println!("Access token in {}", storage.surface_token_path().display());
+    let server = FocusServer(Arc::new(Services {
+        timer,
+        storage,
+        auth,
+    }));
+
+    tauri::async_runtime::block_on(async move {
+        let api = bind(http::ADDR_ENV, http::DEFAULT_ADDR).await?;
+        let socket = bind(state_socket::ADDR_ENV, state_socket::DEFAULT_ADDR).await?;
+        tauri::async_runtime::spawn(server.clone().run_ticker());
+        tauri::async_runtime::spawn(state_socket::serve(socket, server.clone()));
+        http::serve(api, server).await
+    })
+}
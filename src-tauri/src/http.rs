@@ -0,0 +1,276 @@
+//! Optional local REST API (`--features http`) for scripts, Stream Deck
+//! plugins and status bars that can't use Tauri IPC. The handlers call the
+//! same managers as the Tauri commands, and also back the headless focus
+//! server.
+//!
+//! In the desktop app the server only listens on the loopback interface; the
+//! headless server may listen on any interface named in [`ADDR_ENV`]. Either
+//! way every request must carry `Authorization: Bearer <token>` with the
+//! token from `get_surface_token`. Requests are then limited to the scope granted in
+//! `UserPreferences::external_access`. Failures are returned as
+//! `{ "error": ..., "code": ... }` with a matching status code.
+
+use axum::extract::{Query, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Local;
+use serde::Deserialize;
+use serde_json::json;
+use tauri::AppHandle;
+use tokio::net::TcpListener;
+
+use crate::commands::validation::{validate_date_range, IsoDate, ValidationError};
+use crate::services::api_context::ApiContext;
+use crate::services::door_status::{door_status, DoorStatus};
+use crate::services::listen_addr::{listen_addr, Interfaces};
+use crate::services::revision::{MutationError, RevisionConflict};
+use crate::services::storage::TimerStatistic;
+use crate::services::storage_file::StorageError;
+use crate::services::surface_auth::{Access, AuthError, Surface};
+use crate::services::timer_error::TimerError;
+use crate::services::timer_state::TimerData;
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:50153";
+/// Overrides [`DEFAULT_ADDR`]. The desktop app only accepts a loopback
+/// address here; the headless server accepts any.
+pub const ADDR_ENV: &str = "TEMPUS_HTTP_ADDR";
+
+struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    conflict: Option<RevisionConflict>,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            conflict: None,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut body = json!({ "error": self.message, "code": self.code });
+        if let Some(conflict) = self.conflict {
+            body["conflict"] = json!(conflict);
+        }
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::MissingToken | AuthError::InvalidToken => {
+                ApiError::new(StatusCode::UNAUTHORIZED, "unauthorized", error.to_string())
+            }
+            AuthError::Disabled(_) | AuthError::ReadOnly(_) => {
+                ApiError::new(StatusCode::FORBIDDEN, "forbidden", error.to_string())
+            }
+            AuthError::Failed(message) => {
+                ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "failed", message)
+            }
+        }
+    }
+}
+
+impl From<TimerError> for ApiError {
+    fn from(error: TimerError) -> Self {
+        let status = match error {
+            TimerError::LockPoisoned(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::CONFLICT,
+        };
+        ApiError::new(status, error.code(), error.to_string())
+    }
+}
+
+impl From<MutationError> for ApiError {
+    fn from(error: MutationError) -> Self {
+        match error {
+            MutationError::Conflict(conflict) => ApiError {
+                conflict: Some(conflict.clone()),
+                ..ApiError::new(
+                    StatusCode::CONFLICT,
+                    "revision_conflict",
+                    conflict.to_string(),
+                )
+            },
+            MutationError::Timer(error) => error.into(),
+            MutationError::Failed(message) => {
+                ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "failed", message)
+            }
+        }
+    }
+}
+
+impl From<StorageError> for ApiError {
+    fn from(error: StorageError) -> Self {
+        ApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error.code(),
+            error.to_string(),
+        )
+    }
+}
+
+impl From<ValidationError> for ApiError {
+    fn from(error: ValidationError) -> Self {
+        ApiError::new(StatusCode::BAD_REQUEST, "invalid_input", error.to_string())
+    }
+}
+
+/// Rejects a request unless it has a valid token and the HTTP scope allows
+/// `access`.
+fn authorize(ctx: &impl ApiContext, headers: &HeaderMap, access: Access) -> Result<(), ApiError> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    Ok(ctx
+        .auth()
+        .authorize(ctx.storage(), Surface::Http, token, access)?)
+}
+
+#[derive(Deserialize, Default)]
+struct RevisionBody {
+    expected_revision: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    start_date: Option<String>,
+    end_date: Option<String>,
+}
+
+async fn timer_state<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+) -> Result<Json<TimerData>, ApiError> {
+    authorize(&ctx, &headers, Access::Read)?;
+    Ok(Json(ctx.timer().get_data()?))
+}
+
+/// The body is optional; send `{ "expected_revision": n }` to guard against
+/// acting on a stale state.
+async fn start_timer<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+    body: Option<Json<RevisionBody>>,
+) -> Result<Json<TimerData>, ApiError> {
+    authorize(&ctx, &headers, Access::Control)?;
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    Ok(Json(ctx.timer().start(body.expected_revision)?))
+}
+
+async fn pause_timer<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+    body: Option<Json<RevisionBody>>,
+) -> Result<Json<TimerData>, ApiError> {
+    authorize(&ctx, &headers, Access::Control)?;
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    Ok(Json(ctx.timer().pause(body.expected_revision)?))
+}
+
+async fn resume_timer<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+    body: Option<Json<RevisionBody>>,
+) -> Result<Json<TimerData>, ApiError> {
+    authorize(&ctx, &headers, Access::Control)?;
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    Ok(Json(ctx.timer().resume(body.expected_revision)?))
+}
+
+async fn reset_timer<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+    body: Option<Json<RevisionBody>>,
+) -> Result<Json<TimerData>, ApiError> {
+    authorize(&ctx, &headers, Access::Control)?;
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    Ok(Json(ctx.timer().reset(body.expected_revision)?))
+}
+
+async fn skip_session<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+    body: Option<Json<RevisionBody>>,
+) -> Result<Json<TimerData>, ApiError> {
+    authorize(&ctx, &headers, Access::Control)?;
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    Ok(Json(ctx.timer().skip(body.expected_revision)?))
+}
+
+/// Daily statistics, optionally bounded by inclusive `start_date` and
+/// `end_date` query parameters.
+async fn stats<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+    Query(query): Query<StatsQuery>,
+) -> Result<Json<Vec<TimerStatistic>>, ApiError> {
+    authorize(&ctx, &headers, Access::Read)?;
+    let start_date = query.start_date.map(IsoDate::try_from).transpose()?;
+    let end_date = query.end_date.map(IsoDate::try_from).transpose()?;
+    validate_date_range(start_date.as_ref(), end_date.as_ref())?;
+    let statistics = ctx.storage().load_statistics(
+        start_date.as_ref().map(IsoDate::to_naive),
+        end_date.as_ref().map(IsoDate::to_naive),
+    )?;
+    Ok(Json(statistics))
+}
+
+/// The focus status shown on door displays.
+async fn door<C: ApiContext>(
+    State(ctx): State<C>,
+    headers: HeaderMap,
+) -> Result<Json<DoorStatus>, ApiError> {
+    authorize(&ctx, &headers, Access::Read)?;
+    Ok(Json(door_status(&ctx.timer().get_data()?, Local::now())))
+}
+
+/// Serves the API on `listener` until it fails.
+pub async fn serve<C: ApiContext>(listener: TcpListener, ctx: C) -> Result<(), String> {
+    let router = Router::new()
+        .route("/timer/state", get(timer_state::<C>))
+        .route("/timer/start", post(start_timer::<C>))
+        .route("/timer/pause", post(pause_timer::<C>))
+        .route("/timer/resume", post(resume_timer::<C>))
+        .route("/timer/reset", post(reset_timer::<C>))
+        .route("/timer/skip", post(skip_session::<C>))
+        .route("/stats", get(stats::<C>))
+        .route("/door", get(door::<C>))
+        .with_state(ctx);
+    axum::serve(listener, router)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Starts the REST server in the background. Failures are logged rather
+/// than aborting the app, since the desktop UI doesn't depend on it.
+pub fn spawn_server(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let listener = match listen_addr(ADDR_ENV, DEFAULT_ADDR, Interfaces::Loopback) {
+            Ok(addr) => TcpListener::bind(addr).await.map_err(|e| e.to_string()),
+            Err(error) => Err(error),
+        };
+        let listener = match listener {
+            Ok(listener) => listener,
+            Err(error) => {
+                log::error!("HTTP server not started: {error}");
+                return;
+            }
+        };
+        if let Err(error) = serve(listener, app).await {
+            log::error!("HTTP server stopped: {error}");
+        }
+    });
+}
@@ -1,22 +1,484 @@
+#[cfg(feature = "cli")]
+mod cli;
+mod commands;
+#[cfg(desktop)]
+mod deep_link;
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "headless")]
+mod headless;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "mcp")]
+mod mcp;
+mod services;
+
+use commands::artifacts::{
+    choose_artifact_file, get_artifact_timeline, get_tracked_artifacts, untrack_artifact,
+};
+use commands::backup::{
+    backup_data, backup_data_encrypted, configure_auto_backup, get_migration_history,
+    inspect_backup, list_backups, restore_data, restore_data_encrypted,
+};
+use commands::display::{
+    enter_display_mode, get_paired_displays, leave_display_mode, start_display_pairing,
+    unpair_display,
+};
+use commands::distraction_blocker::{
+    get_blocklist, set_block_actions, set_blocking_enabled, set_blocklist,
+};
+use commands::end_of_day::get_end_of_day_status;
+use commands::event_log::load_event_log;
+use commands::experiments::{get_experiment_report, start_experiment, stop_experiment};
+use commands::export::{export_sessions_ics, export_sqlite};
+use commands::files::{
+    choose_auto_backup_dir, choose_backup_destination, choose_dashboard_feed_path,
+    choose_door_status_dir, choose_export_destination, choose_export_dir, choose_restore_source,
+};
+use commands::history::{get_undo_status, redo, undo};
+use commands::hooks::{get_hooks, set_hooks};
+use commands::inbox::{get_service_health, list_inbox, mark_read};
+use commands::permissions::{
+    check_permissions, get_platform_capabilities, get_presenter_status, request_permission,
+};
+use commands::planning::{get_day_timeline, get_days_off, get_energy_plan};
+use commands::presets::{apply_preset, delete_preset, list_presets, save_preset};
+use commands::review::{
+    export_year_in_review_pdf, get_contribution_graph, get_year_in_review, year_statistics,
+};
+use commands::schemas::get_schemas;
+use commands::sessions::{
+    delete_session, get_sessions_for_issue, merge_sessions, query_sessions, set_session_issue,
+    set_session_tags, split_session,
+};
+use commands::settings::{
+    get_system_theme, load_preferences, save_preferences, set_daily_goal, set_dnd_integration,
+    set_project_goals, set_quit_guard, set_window_annotation,
+};
+use commands::slack::{connect_slack, disconnect_slack};
+use commands::statistics::{
+    check_integrity, get_burnout_risk, get_day, get_goal_progress, get_project_goal_progress,
+    get_session_type_breakdown, get_statistics_summary, get_storage_health, get_timeseries,
+    get_wellbeing_report, load_statistics, save_statistic,
+};
+use commands::surface_auth::{get_surface_token, rotate_surface_token};
+use commands::task_provider::{
+    connect_task_provider, disconnect_task_provider, link_session_to_task, list_remote_tasks,
+};
+use commands::tasks::{
+    complete_task, create_task, delete_task, get_archived_tasks, get_tasks, unarchive_task,
+    update_task,
+};
+use commands::timer::{
+    check_timer_completion, complete_session, connect_focus_server, disconnect_focus_server,
+    extend_session, get_schedule, get_state_history, get_tick_drift, get_timer_state, pause_timer,
+    reset_timer, resume_timer, set_progress_stream, set_schedule, skip_session, snooze_break,
+    start_stopwatch, start_timer, stop_stopwatch, switch_session, update_timer_config,
+};
+use commands::trace::set_command_tracing;
+use commands::updater::{check_for_updates, install_update};
+use commands::work_context::{
+    get_context_comparison, get_network_observation, get_work_context, set_work_context,
+};
+use services::appearance::AppearanceScheduler;
+use services::artifacts::ArtifactWatcher;
+use services::audio::AudioService;
+use services::backup::BackupScheduler;
+use services::battery::BatteryMonitor;
+use services::break_reminder::BreakReminderService;
+use services::command_trace::CommandTracer;
+use services::context_detection::ContextDetector;
+use services::display_client::DisplayClient;
+use services::display_server::DisplayRegistry;
+use services::distraction_blocker::DistractionBlocker;
+use services::door_status::DoorStatusWriter;
+use services::fatigue::FatigueMonitor;
+use services::focus_mode::FocusModeService;
+use services::hooks::HookRunner;
+use services::path_grants::PathGrants;
+use services::power::SleepMonitor;
+use services::presenter_mode::PresenterMonitor;
+use services::presets::PresetManager;
+use services::progress_stream::ProgressStream;
+use services::quit_guard::{self, QuitGuard};
+use services::service_health::ServiceHealth;
+use services::slack_status::SlackStatusService;
+use services::storage::StorageService;
+use services::surface_auth::SurfaceAuth;
+use services::system_theme::SystemThemeMonitor;
+use services::tasks::TaskManager;
+use services::tick_drift::TickMonitor;
+use services::timer_state::TimerManager;
+use services::timer_transport::TimerClient;
+use services::tray::TrayIconRenderer;
+use services::undo::UndoManager;
+use services::updater::UpdaterService;
+use services::window_activity::WindowTracker;
+use tauri::{AppHandle, Manager, RunEvent, WindowEvent};
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {name}! You've been greeted from Rust!")
 }
 
+/// Produces last year's "Year in Focus" report in the background each January.
+fn generate_annual_review(app: AppHandle) {
+    std::thread::spawn(move || {
+        let storage = app.state::<StorageService>();
+        let tasks = app.state::<TaskManager>();
+        let result = storage.reviews_dir().and_then(|dir| {
+            let preferences = storage.load_preferences()?;
+            services::year_review::generate_if_due(
+                &dir,
+                chrono::Local::now().date_naive(),
+                |year| year_statistics(&storage, year),
+                &tasks.list_with_archived()?,
+                &preferences.days_off,
+                preferences.duration_style,
+            )
+        });
+        if let Err(error) = result {
+            log::error!("Failed to generate year in review: {error}");
+        }
+    });
+}
+
+#[cfg(feature = "headless")]
+pub use headless::run as run_headless;
+
+#[cfg(feature = "cli")]
+pub use cli::run as run_cli;
+
+#[cfg(feature = "test-util")]
+pub use services::storage::{StorageServiceBuilder, TestStorage};
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let mut builder = tauri::Builder::default()
+    let mut builder = tauri::Builder::default();
+    // The single-instance plugin has to be registered first. With its
+    // `deep-link` feature it hands links opened while the app runs to the
+    // deep link plugin.
+    #[cfg(desktop)]
+    {
+        builder = builder
+            .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+                if let Err(error) = services::single_instance::on_second_instance(app, args, cwd) {
+                    log::error!("{error}");
+                }
+            }))
+            .plugin(tauri_plugin_deep_link::init());
+    }
+    builder = builder
+        // Logs to stdout and to a file in the app's log directory.
+        .plugin(
+            tauri_plugin_log::Builder::new()
+                .level(log::LevelFilter::Info)
+                .build(),
+        )
         .plugin(tauri_plugin_os::init())
-        .invoke_handler(tauri::generate_handler![greet]);
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            let storage = StorageService::new(app.handle())?;
+            storage.lock_instance()?;
+            let preferences = storage.load_preferences()?;
+            app.manage(AudioService::new(&preferences));
+            app.manage(TrayIconRenderer::new(&preferences));
+            app.manage(FocusModeService::new(&preferences));
+            app.manage(QuitGuard::new(&preferences));
+            app.manage(SlackStatusService::new(&preferences));
+            app.manage(AppearanceScheduler::new(
+                &preferences,
+                storage.load_appearance_state().unwrap_or_default(),
+            ));
+            app.manage(BackupScheduler::new(&preferences));
+            app.manage(SleepMonitor::new(&preferences));
+            app.manage(BatteryMonitor::new(&preferences));
+            app.manage(PresenterMonitor::new(&preferences));
+            app.manage(BreakReminderService::new(&preferences));
+            app.manage(ContextDetector::new(&preferences));
+            app.manage(WindowTracker::new(&preferences));
+            app.manage(DistractionBlocker::new(&preferences));
+            app.manage(DoorStatusWriter::new(&preferences));
+            app.manage(HookRunner::new(&preferences));
+            let timer = TimerManager::new(preferences.timer_config.clone());
+            timer.configure(&preferences)?;
+            app.manage(timer);
+            app.manage(TimerClient::new(&storage, &preferences)?);
+            app.manage(TaskManager::new(
+                storage.load_tasks()?,
+                storage.load_archived_tasks()?,
+            ));
+            app.manage(PresetManager::new(storage.load_presets()?));
+            app.manage(SurfaceAuth::load(&storage)?);
+            let display_connection = storage.load_display_connection()?;
+            app.manage(storage);
+            app.manage(UndoManager::default());
+            app.manage(PathGrants::default());
+            app.manage(DisplayRegistry::default());
+            app.manage(DisplayClient::default());
+            app.manage(UpdaterService::default());
+            app.manage(ArtifactWatcher::default());
+            app.manage(FatigueMonitor::default());
+            app.manage(ServiceHealth::default());
+            app.manage(SystemThemeMonitor::default());
+            app.manage(TickMonitor::default());
+            app.manage(ProgressStream::default());
+            app.manage(CommandTracer::default());
+            if let Some(connection) = display_connection {
+                app.state::<DisplayClient>()
+                    .connect(app.handle().clone(), connection)?;
+            }
+            #[cfg(desktop)]
+            services::tray::create_tray(app.handle())?;
+            #[cfg(desktop)]
+            services::updater::spawn_checker(app.handle().clone());
+            services::distraction_blocker::clear_leftover_block(app.handle());
+            services::ticker::spawn_ticker(app.handle().clone());
+            services::progress_stream::spawn_stream(app.handle().clone());
+            services::write_buffer::spawn_flusher(app.handle().clone());
+            services::end_of_day::spawn_scheduler(app.handle().clone());
+            services::dashboard_feed::spawn_writer(app.handle().clone());
+            services::backup::spawn_scheduler(app.handle().clone());
+            services::task_archive::spawn_scheduler(app.handle().clone());
+            services::burnout::spawn_scheduler(app.handle().clone());
+            #[cfg(desktop)]
+            {
+                let listening = deep_link::listen(app.handle());
+                if let Err(error) = &listening {
+                    log::error!("{error}");
+                }
+                services::service_health::report(
+                    app.handle(),
+                    services::service_health::BackgroundService::DeepLinks,
+                    &listening,
+                );
+            }
+            services::system_theme::spawn_watcher(app.handle().clone());
+            services::appearance::spawn_scheduler(app.handle().clone());
+            generate_annual_review(app.handle().clone());
+            #[cfg(feature = "grpc")]
+            grpc::spawn_server(app.handle().clone());
+            #[cfg(feature = "mcp")]
+            mcp::spawn_server(app.handle().clone());
+            #[cfg(feature = "http")]
+            http::spawn_server(app.handle().clone());
+            services::display_server::spawn_server(app.handle().clone());
+            services::state_socket::spawn_server(app.handle().clone());
+            #[cfg(desktop)]
+            services::control_socket::spawn_server(app.handle().clone());
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            WindowEvent::ThemeChanged(theme) => {
+                let app = window.app_handle();
+                let result = app
+                    .state::<SystemThemeMonitor>()
+                    .update(app, (*theme).into());
+                if let Err(error) = result {
+                    log::error!("System theme update failed: {error}");
+                }
+            }
+            WindowEvent::CloseRequested { api, .. } => {
+                if quit_guard::intercept(window.app_handle()) {
+                    api.prevent_close();
+                }
+            }
+            _ => {}
+        })
+        .invoke_handler(services::command_trace::wrap(tauri::generate_handler![
+            greet,
+            get_timer_state,
+            start_timer,
+            pause_timer,
+            resume_timer,
+            reset_timer,
+            skip_session,
+            connect_focus_server,
+            disconnect_focus_server,
+            switch_session,
+            start_stopwatch,
+            stop_stopwatch,
+            extend_session,
+            snooze_break,
+            complete_session,
+            check_timer_completion,
+            update_timer_config,
+            get_schedule,
+            set_schedule,
+            start_experiment,
+            stop_experiment,
+            get_experiment_report,
+            list_presets,
+            save_preset,
+            apply_preset,
+            delete_preset,
+            get_state_history,
+            get_tick_drift,
+            set_progress_stream,
+            #[cfg(feature = "simulation")]
+            commands::timer::simulate_timer,
+            #[cfg(feature = "simulation")]
+            commands::timer::replay_state_history,
+            load_preferences,
+            save_preferences,
+            save_statistic,
+            load_statistics,
+            get_timeseries,
+            get_statistics_summary,
+            get_wellbeing_report,
+            get_session_type_breakdown,
+            set_session_tags,
+            set_session_issue,
+            get_sessions_for_issue,
+            query_sessions,
+            delete_session,
+            split_session,
+            merge_sessions,
+            get_storage_health,
+            check_integrity,
+            backup_data,
+            inspect_backup,
+            restore_data,
+            backup_data_encrypted,
+            restore_data_encrypted,
+            configure_auto_backup,
+            list_backups,
+            get_migration_history,
+            choose_backup_destination,
+            choose_restore_source,
+            choose_export_dir,
+            choose_auto_backup_dir,
+            choose_dashboard_feed_path,
+            choose_export_destination,
+            export_sqlite,
+            export_sessions_ics,
+            get_end_of_day_status,
+            get_year_in_review,
+            get_contribution_graph,
+            get_energy_plan,
+            get_days_off,
+            get_day_timeline,
+            export_year_in_review_pdf,
+            get_tasks,
+            create_task,
+            update_task,
+            complete_task,
+            delete_task,
+            connect_task_provider,
+            disconnect_task_provider,
+            list_remote_tasks,
+            link_session_to_task,
+            get_undo_status,
+            undo,
+            redo,
+            list_inbox,
+            mark_read,
+            get_service_health,
+            get_schemas,
+            get_surface_token,
+            rotate_surface_token,
+            start_display_pairing,
+            get_paired_displays,
+            unpair_display,
+            enter_display_mode,
+            leave_display_mode,
+            check_for_updates,
+            install_update,
+            check_permissions,
+            request_permission,
+            get_platform_capabilities,
+            get_presenter_status,
+            set_dnd_integration,
+            set_window_annotation,
+            set_quit_guard,
+            connect_slack,
+            disconnect_slack,
+            set_daily_goal,
+            get_goal_progress,
+            get_day,
+            set_project_goals,
+            get_project_goal_progress,
+            get_system_theme,
+            choose_artifact_file,
+            get_tracked_artifacts,
+            untrack_artifact,
+            get_artifact_timeline,
+            load_event_log,
+            set_command_tracing,
+            get_work_context,
+            set_work_context,
+            get_network_observation,
+            get_context_comparison,
+            get_blocklist,
+            set_blocklist,
+            set_blocking_enabled,
+            set_block_actions,
+            get_hooks,
+            set_hooks,
+            get_archived_tasks,
+            unarchive_task,
+            choose_door_status_dir,
+            get_burnout_risk,
+        ]));
 
-    // 只在桌面端添加 opener 插件
+    // 只在桌面端添加 opener 和 updater 插件
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
     {
-        builder = builder.plugin(tauri_plugin_opener::init());
+        builder = builder
+            .plugin(tauri_plugin_opener::init())
+            .plugin(tauri_plugin_updater::Builder::new().build());
     }
 
     builder
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Exits requested with a code come from the app itself, e.g. a
+            // confirmed quit or an update restart.
+            RunEvent::ExitRequested {
+                code: None, api, ..
+            } => {
+                if quit_guard::intercept(app) {
+                    api.prevent_exit();
+                }
+            }
+            RunEvent::Exit => {
+                if let Err(error) = services::ticker::record_sessions(app) {
+                    log::error!("Recording sessions failed: {error}");
+                }
+                let storage = app.state::<StorageService>();
+                // Transitions since the last tick would otherwise be lost.
+                match app.state::<TimerManager>().take_events() {
+                    Ok(events) if !events.is_empty() => {
+                        if let Err(error) = storage.append_events(&events) {
+                            log::error!("{error}");
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(error) => log::error!("{error}"),
+                }
+                // Last chance for writes held while storage was failing.
+                match storage.flush_pending() {
+                    Ok(health) if health.degraded => log::error!(
+                        "{} held writes were lost: {}",
+                        health.pending_writes,
+                        health.last_error.unwrap_or_default()
+                    ),
+                    Ok(_) => {}
+                    Err(error) => log::error!("Flushing held writes failed: {error}"),
+                }
+                if let Err(error) = app.state::<DistractionBlocker>().release() {
+                    log::error!("Lifting the blocklist failed: {error}");
+                }
+                if let Err(error) = app.state::<FocusModeService>().release() {
+                    log::error!("Restoring Do Not Disturb failed: {error}");
+                }
+                if let Err(error) = app.state::<DoorStatusWriter>().release() {
+                    log::error!("Door status failed: {error}");
+                }
+            }
+            _ => {}
+        });
 }
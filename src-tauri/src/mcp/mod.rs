@@ -0,0 +1,298 @@
+//! Optional MCP server (`--features mcp`) so AI assistants can drive the
+//! timer and browse statistics. It speaks JSON-RPC 2.0, one message per
+//! line, on a loopback TCP socket and offers the tools in [`TOOLS`] and a
+//! `stats://YYYY-MM-DD` resource per day, which call the same managers as
+//! the Tauri commands.
+//!
+//! Clients authenticate in `initialize` by passing the token from
+//! `get_surface_token` as `_meta.token`. Every request is then checked
+//! against the scope granted in `UserPreferences::external_access`, so
+//! rotating the token or narrowing the scope affects open connections too.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::commands::validation::IsoDate;
+use crate::services::listen_addr::{listen_addr, Interfaces};
+use crate::services::storage::StorageService;
+use crate::services::surface_auth::{Access, AuthError, Surface, SurfaceAuth};
+
+mod tools;
+
+use tools::{day_statistic, input_schema, run_tool, TOOLS};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:50154";
+/// Overrides [`DEFAULT_ADDR`]; must still be a loopback address.
+const ADDR_ENV: &str = "TEMPUS_MCP_ADDR";
+const PROTOCOL_VERSION: &str = "2025-06-18";
+/// Daily statistics are the resources `stats://YYYY-MM-DD`.
+const STATS_SCHEME: &str = "stats://";
+const JSON_MIME: &str = "application/json";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// Server-defined: missing or wrong token, or a scope that forbids the call.
+const UNAUTHORIZED: i64 = -32001;
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<AuthError> for RpcError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::Failed(message) => RpcError::new(INTERNAL_ERROR, message),
+            error => RpcError::new(UNAUTHORIZED, error.to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    /// Absent for notifications, which get no response.
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct CallParams {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+#[derive(Deserialize)]
+struct ReadParams {
+    uri: String,
+}
+/// The day a `stats://YYYY-MM-DD` URI names.
+fn stats_date(uri: &str) -> Result<IsoDate, RpcError> {
+    uri.strip_prefix(STATS_SCHEME)
+        .ok_or_else(|| format!("Unknown resource: {uri}"))
+        .and_then(|date| IsoDate::try_from(date.to_string()).map_err(|e| e.to_string()))
+        .map_err(|message| RpcError::new(INVALID_PARAMS, message))
+}
+/// Per-connection state: the token presented in `initialize`.
+struct Session {
+    app: AppHandle,
+    token: Option<String>,
+}
+
+impl Session {
+    fn authorize(&self, access: Access) -> Result<(), AuthError> {
+        self.app.state::<SurfaceAuth>().authorize(
+            &self.app.state::<StorageService>(),
+            Surface::Mcp,
+            self.token.as_deref(),
+            access,
+        )
+    }
+
+    fn initialize(&mut self, params: &Value) -> Result<Value, RpcError> {
+        self.token = params
+            .pointer("/_meta/token")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        self.authorize(Access::Read)?;
+        Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {}, "resources": {} },
+            "serverInfo": {
+                "name": "tempus-ring",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        }))
+    }
+
+    fn list_tools(&self) -> Result<Value, RpcError> {
+        self.authorize(Access::Read)?;
+        let tools: Vec<Value> = TOOLS
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": input_schema(tool),
+                })
+            })
+            .collect();
+        Ok(json!({ "tools": tools }))
+    }
+
+    fn call_tool(&self, params: Value) -> Result<Value, RpcError> {
+        let params: CallParams = serde_json::from_value(params)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+        let tool = TOOLS
+            .iter()
+            .find(|tool| tool.name == params.name)
+            .ok_or_else(|| {
+                RpcError::new(INVALID_PARAMS, format!("Unknown tool: {}", params.name))
+            })?;
+        self.authorize(tool.access)?;
+        Ok(match run_tool(&self.app, tool.name, params.arguments)? {
+            Ok(value) => json!({
+                "content": [{ "type": "text", "text": value.to_string() }],
+                "structuredContent": value,
+                "isError": false,
+            }),
+            Err(message) => json!({
+                "content": [{ "type": "text", "text": message }],
+                "isError": true,
+            }),
+        })
+    }
+
+    /// Every recorded day, newest first.
+    fn list_resources(&self) -> Result<Value, RpcError> {
+        self.authorize(Access::Read)?;
+        let statistics = self
+            .app
+            .state::<StorageService>()
+            .load_statistics(None, None)
+            .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+        let resources: Vec<Value> = statistics
+            .iter()
+            .rev()
+            .map(|statistic| {
+                json!({
+                    "uri": format!("{STATS_SCHEME}{}", statistic.date),
+                    "name": statistic.date,
+                    "description": format!(
+                        "{} pomodoros, {} sessions",
+                        statistic.completed_pomodoros,
+                        statistic.sessions.len()
+                    ),
+                    "mimeType": JSON_MIME,
+                })
+            })
+            .collect();
+        Ok(json!({ "resources": resources }))
+    }
+
+    fn list_resource_templates(&self) -> Result<Value, RpcError> {
+        self.authorize(Access::Read)?;
+        Ok(json!({
+            "resourceTemplates": [{
+                "uriTemplate": format!("{STATS_SCHEME}{{date}}"),
+                "name": "Daily statistics",
+                "description": "Pomodoros, focus time in seconds and every session of a YYYY-MM-DD day.",
+                "mimeType": JSON_MIME,
+            }],
+        }))
+    }
+
+    /// Days without any sessions read as empty rather than missing.
+    fn read_resource(&self, params: Value) -> Result<Value, RpcError> {
+        let params: ReadParams = serde_json::from_value(params)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+        let date = stats_date(&params.uri)?;
+        self.authorize(Access::Read)?;
+        let statistic = day_statistic(&self.app.state::<StorageService>(), date.as_str())
+            .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+        Ok(json!({
+            "contents": [{
+                "uri": params.uri,
+                "mimeType": JSON_MIME,
+                "text": json!(statistic).to_string(),
+            }],
+        }))
+    }
+
+    fn handle(&mut self, request: Request) -> Result<Value, RpcError> {
+        match request.method.as_str() {
+            "initialize" => self.initialize(&request.params),
+            "ping" => Ok(json!({})),
+            "tools/list" => self.list_tools(),
+            "tools/call" => self.call_tool(request.params),
+            "resources/list" => self.list_resources(),
+            "resources/templates/list" => self.list_resource_templates(),
+            "resources/read" => self.read_resource(request.params),
+            method => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method: {method}"),
+            )),
+        }
+    }
+
+    /// The response line for `line`, or `None` for notifications.
+    fn respond(&mut self, line: &str) -> Option<Value> {
+        let request: Request = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(error) => {
+                return Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": PARSE_ERROR, "message": error.to_string() },
+                }));
+            }
+        };
+        let id = request.id.clone()?;
+        Some(match self.handle(request) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": error.code, "message": error.message },
+            }),
+        })
+    }
+}
+
+async fn serve(app: AppHandle, stream: TcpStream) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut session = Session { app, token: None };
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = session.respond(&line) {
+            writer.write_all(format!("{response}\n").as_bytes()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Starts the MCP server in the background. Failures are logged rather than
+/// aborting the app, since the desktop UI doesn't depend on it.
+pub fn spawn_server(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let listener = match listen_addr(ADDR_ENV, DEFAULT_ADDR, Interfaces::Loopback) {
+            Ok(addr) => TcpListener::bind(addr).await.map_err(|e| e.to_string()),
+            Err(error) => Err(error),
+        };
+        let listener = match listener {
+            Ok(listener) => listener,
+            Err(error) => {
+                log::error!("MCP server not started: {error}");
+                return;
+            }
+        };
+        while let Ok((stream, _)) = listener.accept().await {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(error) = serve(app, stream).await {
+                    log::warn!("MCP connection closed: {error}");
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,252 @@
+//! The tools MCP clients can call, each backed by the same managers as
+//! the matching Tauri command.
+
+use chrono::{Datelike, Duration};
+use schemars::gen::SchemaSettings;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use super::{RpcError, INVALID_PARAMS};
+use crate::commands::timer::apply_config;
+use crate::commands::validation::{IsoDate, Validate};
+use crate::services::calendar;
+use crate::services::goals::{self, GoalProgress};
+use crate::services::session_types::{group_by_session_type, SessionTypeTotal};
+use crate::services::storage::{StorageService, TimerStatistic};
+use crate::services::summary::{summarize, StatisticsSummary};
+use crate::services::surface_auth::Access;
+use crate::services::timer_state::{TimerConfig, TimerManager, TimerState};
+use crate::services::timeseries::Granularity;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RevisionArguments {
+    expected_revision: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ConfigArguments {
+    config: TimerConfig,
+    #[serde(default)]
+    expected_revision: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct WeekArguments {
+    /// Any day of the week; defaults to today.
+    #[serde(default)]
+    date: Option<IsoDate>,
+}
+#[derive(Serialize)]
+struct TodayStats {
+    statistic: TimerStatistic,
+    goal: GoalProgress,
+}
+
+#[derive(Serialize)]
+struct WeekSummary {
+    /// Monday to Sunday, one period per day.
+    summary: StatisticsSummary,
+    session_types: Vec<SessionTypeTotal>,
+}
+
+pub(super) struct Tool {
+    pub(super) name: &'static str,
+    pub(super) description: &'static str,
+    pub(super) access: Access,
+}
+
+pub(super) const TOOLS: [Tool; 6] = [
+    Tool {
+        name: "start_timer",
+        description: "Start the current session, or resume it if paused.",
+        access: Access::Control,
+    },
+    Tool {
+        name: "pause_timer",
+        description: "Pause the running session.",
+        access: Access::Control,
+    },
+    Tool {
+        name: "get_timer_state",
+        description: "The timer's state, session type and remaining time.",
+        access: Access::Read,
+    },
+    Tool {
+        name: "get_today_stats",
+        description: "Today's completed pomodoros, focus time and sessions, and the daily goal.",
+        access: Access::Read,
+    },
+    Tool {
+        name: "set_config",
+        description: "Replace the timer configuration. Durations are in seconds.",
+        access: Access::Control,
+    },
+    Tool {
+        name: "summarize_week",
+        description: "Daily totals, best day, streak and time per session type for a Monday-to-Sunday week, for weekly reviews. Durations are in seconds.",
+        access: Access::Read,
+    },
+];
+
+fn revision_schema() -> Value {
+    json!({
+        "type": "integer",
+        "minimum": 0,
+        "description": "Fails with a conflict if the timer has changed since this revision.",
+    })
+}
+
+pub(super) fn input_schema(tool: &Tool) -> Value {
+    match tool.name {
+        "start_timer" | "pause_timer" => json!({
+            "type": "object",
+            "properties": { "expected_revision": revision_schema() },
+        }),
+        "set_config" => {
+            let config = SchemaSettings::draft07()
+                .with(|settings| settings.inline_subschemas = true)
+                .into_generator()
+                .into_root_schema_for::<TimerConfig>()
+                .schema;
+            json!({
+                "type": "object",
+                "properties": {
+                    "config": config,
+                    "expected_revision": revision_schema(),
+                },
+                "required": ["config"],
+            })
+        }
+        "summarize_week" => json!({
+            "type": "object",
+            "properties": {
+                "date": {
+                    "type": "string",
+                    "format": "date",
+                    "description": "Any YYYY-MM-DD day of the week; defaults to today.",
+                },
+            },
+        }),
+        _ => json!({ "type": "object", "properties": {} }),
+    }
+}
+
+fn arguments<T: DeserializeOwned>(arguments: Value) -> Result<T, RpcError> {
+    // Clients may send `null` or leave out arguments for tools without any.
+    let arguments = if arguments.is_null() {
+        json!({})
+    } else {
+        arguments
+    };
+    serde_json::from_value(arguments)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid arguments: {e}")))
+}
+
+/// The day's statistic, or an empty one if nothing was recorded.
+pub(super) fn day_statistic(
+    storage: &StorageService,
+    date: &str,
+) -> Result<TimerStatistic, String> {
+    Ok(storage
+        .load_statistic(date)?
+        .unwrap_or_else(|| TimerStatistic {
+            date: date.to_string(),
+            completed_pomodoros: 0,
+            total_focus_time: 0,
+            sessions: Vec::new(),
+            advisories: Vec::new(),
+            overwork: Vec::new(),
+        }))
+}
+
+fn today_stats(storage: &StorageService) -> Result<TodayStats, String> {
+    let today = calendar::today(storage)?;
+    Ok(TodayStats {
+        statistic: day_statistic(storage, &today)?,
+        goal: goals::progress(storage, &today)?,
+    })
+}
+
+fn week_summary(storage: &StorageService, date: Option<IsoDate>) -> Result<WeekSummary, String> {
+    let date = match date {
+        Some(date) => date.to_naive(),
+        None => storage.load_preferences()?.calendar.today(),
+    };
+    let monday = date - Duration::days(date.weekday().num_days_from_monday().into());
+    let sunday = monday + Duration::days(6);
+    let statistics = storage.load_statistics(Some(monday), Some(sunday))?;
+    let preferences = storage.load_preferences()?;
+    Ok(WeekSummary {
+        summary: summarize(
+            &statistics,
+            Granularity::Day,
+            monday,
+            sunday,
+            &preferences.days_off,
+        ),
+        session_types: group_by_session_type(
+            &statistics,
+            &preferences.timer_config.custom_session_types,
+            &preferences.break_detection,
+        ),
+    })
+}
+/// Runs a tool. `Err` is a failure of the tool itself, such as pausing an
+/// idle timer, which MCP reports as a result with `isError` set.
+pub(super) fn run_tool(
+    app: &AppHandle,
+    name: &str,
+    args: Value,
+) -> Result<Result<Value, String>, RpcError> {
+    let timer = app.state::<TimerManager>();
+    let storage = app.state::<StorageService>();
+    let result = match name {
+        "start_timer" => {
+            let args: RevisionArguments = arguments(args)?;
+            let paused = timer
+                .get_data()
+                .is_ok_and(|data| data.state == TimerState::Paused);
+            let data = if paused {
+                timer.resume(args.expected_revision)
+            } else {
+                timer.start(args.expected_revision)
+            };
+            data.map_err(|e| e.to_string()).map(|data| json!(data))
+        }
+        "pause_timer" => {
+            let args: RevisionArguments = arguments(args)?;
+            timer
+                .pause(args.expected_revision)
+                .map_err(|e| e.to_string())
+                .map(|data| json!(data))
+        }
+        "get_timer_state" => timer
+            .get_data()
+            .map_err(String::from)
+            .map(|data| json!(data)),
+        "get_today_stats" => today_stats(&storage).map(|stats| json!(stats)),
+        "set_config" => {
+            let args: ConfigArguments = arguments(args)?;
+            match args.config.validate() {
+                Err(error) => Err(error.to_string()),
+                Ok(()) => apply_config(&timer, &storage, args.config, args.expected_revision)
+                    .map_err(|e| e.to_string())
+                    .map(|data| json!(data)),
+            }
+        }
+        "summarize_week" => {
+            let args: WeekArguments = arguments(args)?;
+            week_summary(&storage, args.date).map(|summary| json!(summary))
+        }
+        _ => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown tool: {name}"),
+            ))
+        }
+    };
+    Ok(result)
+}
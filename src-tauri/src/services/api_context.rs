@@ -0,0 +1,29 @@
+//! The services the HTTP API and the state socket work with, so both can be
+//! served from the desktop app or from the headless focus server.
+
+use tauri::{AppHandle, Manager};
+
+use super::storage::StorageService;
+use super::surface_auth::SurfaceAuth;
+use super::timer_state::TimerManager;
+
+pub trait ApiContext: Clone + Send + Sync + 'static {
+    fn timer(&self) -> &TimerManager;
+    fn storage(&self) -> &StorageService;
+    fn auth(&self) -> &SurfaceAuth;
+}
+
+/// The desktop app, where the services are managed Tauri state.
+impl ApiContext for AppHandle {
+    fn timer(&self) -> &TimerManager {
+        self.state::<TimerManager>().inner()
+    }
+
+    fn storage(&self) -> &StorageService {
+        self.state::<StorageService>().inner()
+    }
+
+    fn auth(&self) -> &SurfaceAuth {
+        self.state::<SurfaceAuth>().inner()
+    }
+}
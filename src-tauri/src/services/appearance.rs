@@ -0,0 +1,231 @@
+//! Switches the theme and sounds between a day and a night profile, by clock
+//! or by following the OS dark mode, so evening sessions are dimmer and
+//! quieter.
+
+use chrono::{Local, Timelike};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::audio::{AlarmPreferences, AudioService};
+use super::storage::{StorageService, UserPreferences};
+
+/// Emitted with an [`AppearanceChange`] after a profile has been applied.
+pub const APPEARANCE_CHANGED_EVENT: &str = "appearance://changed";
+/// How often the clock schedule is re-evaluated.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DayPhase {
+    Day,
+    Night,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleSource {
+    /// Switch at `day_start` and `night_start` local time.
+    #[default]
+    Clock,
+    /// Follow the OS light/dark appearance.
+    System,
+}
+
+/// What a phase switches to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct AppearanceProfile {
+    pub theme: String,
+    /// Volume for every phase, `0.0..=1.0`.
+    pub volume: f32,
+    pub alarms: AlarmPreferences,
+}
+
+impl Default for AppearanceProfile {
+    fn default() -> Self {
+        Self {
+            theme: "cloudlight".to_string(),
+            volume: 0.7,
+            alarms: AlarmPreferences::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct AppearanceSchedule {
+    pub enabled: bool,
+    pub source: ScheduleSource,
+    /// Minutes after local midnight the day profile takes over.
+    pub day_start: u32,
+    /// Minutes after local midnight the night profile takes over.
+    pub night_start: u32,
+    pub day: AppearanceProfile,
+    pub night: AppearanceProfile,
+}
+
+impl Default for AppearanceSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: ScheduleSource::Clock,
+            day_start: 7 * 60,
+            night_start: 19 * 60,
+            day: AppearanceProfile::default(),
+            night: AppearanceProfile {
+                theme: "nightfall".to_string(),
+                volume: 0.4,
+                alarms: AlarmPreferences::default(),
+            },
+        }
+    }
+}
+
+impl AppearanceSchedule {
+    /// The phase in effect, or `None` when the schedule is off or the OS
+    /// appearance isn't known yet.
+    pub fn phase(&self, minute_of_day: u32, system_dark: Option<bool>) -> Option<DayPhase> {
+        if !self.enabled {
+            return None;
+        }
+        let night = match self.source {
+            ScheduleSource::System => system_dark?,
+            // The night may wrap past midnight or, for an inverted schedule,
+            // be the span within the day.
+            ScheduleSource::Clock if self.day_start <= self.night_start => {
+                minute_of_day < self.day_start || minute_of_day >= self.night_start
+            }
+            ScheduleSource::Clock => {
+                minute_of_day >= self.night_start && minute_of_day < self.day_start
+            }
+        };
+        Some(if night {
+            DayPhase::Night
+        } else {
+            DayPhase::Day
+        })
+    }
+
+    pub fn profile(&self, phase: DayPhase) -> &AppearanceProfile {
+        match phase {
+            DayPhase::Day => &self.day,
+            DayPhase::Night => &self.night,
+        }
+    }
+}
+
+/// The profile last written to the preferences. Kept across launches so a
+/// profile is only applied again once the one due actually changes, and
+/// manual tweaks last until then.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppearanceState {
+    pub phase: Option<DayPhase>,
+    pub profile: Option<AppearanceProfile>,
+}
+
+/// Payload of [`APPEARANCE_CHANGED_EVENT`].
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AppearanceChange {
+    pub phase: DayPhase,
+    pub preferences: UserPreferences,
+}
+
+struct SchedulerState {
+    schedule: AppearanceSchedule,
+    applied: AppearanceState,
+    system_dark: Option<bool>,
+}
+
+pub struct AppearanceScheduler {
+    state: Mutex<SchedulerState>,
+}
+
+impl AppearanceScheduler {
+    pub fn new(preferences: &UserPreferences, applied: AppearanceState) -> Self {
+        Self {
+            state: Mutex::new(SchedulerState {
+                schedule: preferences.appearance_schedule.clone(),
+                applied,
+                system_dark: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, SchedulerState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Appearance scheduler lock poisoned: {e}"))
+    }
+
+    /// Picks up a changed schedule. Its profile is applied on the next check
+    /// if it differs from the one last applied.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        self.lock()?.schedule = preferences.appearance_schedule.clone();
+        Ok(())
+    }
+
+    /// Records the OS appearance, reported by the system theme watcher.
+    pub fn set_system_dark(&self, dark: bool) -> Result<(), String> {
+        self.lock()?.system_dark = Some(dark);
+        Ok(())
+    }
+
+    /// Applies the profile for the current phase unless it is the one last
+    /// applied.
+    pub fn check(&self, app: &AppHandle) -> Result<(), String> {
+        let mut state = self.lock()?;
+        let now = Local::now();
+        let minute_of_day = now.hour() * 60 + now.minute();
+        let Some(phase) = state.schedule.phase(minute_of_day, state.system_dark) else {
+            return Ok(());
+        };
+        let due = AppearanceState {
+            phase: Some(phase),
+            profile: Some(state.schedule.profile(phase).clone()),
+        };
+        if state.applied == due {
+            return Ok(());
+        }
+        let storage = app.state::<StorageService>();
+        let profile = state.schedule.profile(phase).clone();
+        let preferences = storage.update_preferences(|preferences| {
+            preferences.theme = profile.theme;
+            preferences.notifications.set_volume(profile.volume);
+            preferences.alarms = profile.alarms;
+        })?;
+        storage.save_appearance_state(&due)?;
+        state.applied = due;
+        drop(state);
+
+        app.state::<AudioService>().configure(&preferences)?;
+        app.emit(
+            APPEARANCE_CHANGED_EVENT,
+            AppearanceChange { phase, preferences },
+        )
+        .map_err(|e| format!("Failed to emit {APPEARANCE_CHANGED_EVENT}: {e}"))
+    }
+}
+
+/// Re-evaluates the schedule every [`CHECK_INTERVAL`]; OS appearance
+/// changes are applied straight away by the system theme watcher.
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(error) = app.state::<AppearanceScheduler>().check(&app) {
+                log::error!("Appearance schedule failed: {error}");
+            }
+        }
+    });
+}
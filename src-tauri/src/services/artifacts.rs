@@ -0,0 +1,368 @@
+//! Opt-in copies of files the user is working on, taken when sessions start
+//! and end, so a task's progress can be reviewed later as a timeline.
+//!
+//! Each tracked file gets its own folder under the artifacts directory:
+//!
+//! ```text
+//! artifacts/
+//!   tracked.json
+//!   <artifact id>/
+//!     versions.json
+//!     <unix secs>-<version number>-<boundary>-<file name>
+//! ```
+//!
+//! Files over [`MAX_ARTIFACT_BYTES`] are skipped. Versions outlive the
+//! tracking, so untracking a file keeps its history.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+use super::storage::StorageService;
+use super::timer_state::{SessionType, Tick, TimerState};
+
+const TRACKED_FILE: &str = "tracked.json";
+const VERSIONS_FILE: &str = "versions.json";
+/// Largest file that is copied; anything bigger is skipped at capture.
+pub const MAX_ARTIFACT_BYTES: u64 = 10 * 1024 * 1024;
+
+/// A file the user asked to have captured. Only added through the file
+/// dialog, never from a path the webview supplies.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TrackedArtifact {
+    pub id: String,
+    pub path: String,
+    /// Task the file's progress is filed under.
+    pub task_id: Option<String>,
+    pub added_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionBoundary {
+    Start,
+    End,
+}
+
+impl SessionBoundary {
+    fn key(self) -> &'static str {
+        match self {
+            SessionBoundary::Start => "start",
+            SessionBoundary::End => "end",
+        }
+    }
+}
+
+/// One saved copy of a tracked file.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ArtifactVersion {
+    pub artifact_id: String,
+    pub task_id: Option<String>,
+    /// Name of the original file.
+    pub file_name: String,
+    /// Absolute path of the copy.
+    pub path: String,
+    pub size: u64,
+    pub boundary: SessionBoundary,
+    pub session_type: SessionType,
+    pub captured_at: u64,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn load_tracked(storage: &StorageService) -> Result<Vec<TrackedArtifact>, String> {
+    let path = storage.artifacts_dir()?.join(TRACKED_FILE);
+    Ok(StorageService::read_json(&path)?.unwrap_or_default())
+}
+
+fn save_tracked(storage: &StorageService, tracked: &[TrackedArtifact]) -> Result<(), String> {
+    StorageService::write_json(&storage.artifacts_dir()?.join(TRACKED_FILE), &tracked)
+}
+
+/// Starts tracking `path`, which must come from a file dialog.
+pub fn track(
+    storage: &StorageService,
+    path: &Path,
+    task_id: Option<String>,
+) -> Result<TrackedArtifact, String> {
+    let path = path.display().to_string();
+    let mut tracked = load_tracked(storage)?;
+    if let Some(existing) = tracked.iter_mut().find(|artifact| artifact.path == path) {
+        existing.task_id = task_id;
+        let existing = existing.clone();
+        save_tracked(storage, &tracked)?;
+        return Ok(existing);
+    }
+    let artifact = TrackedArtifact {
+        id: uuid::Uuid::new_v4().to_string(),
+        path,
+        task_id,
+        added_at: unix_now(),
+    };
+    tracked.push(artifact.clone());
+    save_tracked(storage, &tracked)?;
+    Ok(artifact)
+}
+
+pub fn untrack(storage: &StorageService, id: &str) -> Result<(), String> {
+    let mut tracked = load_tracked(storage)?;
+    tracked.retain(|artifact| artifact.id != id);
+    save_tracked(storage, &tracked)
+}
+
+fn load_versions(dir: &Path) -> Result<Vec<ArtifactVersion>, String> {
+    Ok(StorageService::read_json(&dir.join(VERSIONS_FILE))?.unwrap_or_default())
+}
+
+/// Copies `artifact` unless it is unchanged since its last version.
+fn capture_one(
+    root: &Path,
+    artifact: &TrackedArtifact,
+    boundary: SessionBoundary,
+    session_type: &SessionType,
+) -> Result<(), String> {
+    let source = PathBuf::from(&artifact.path);
+    let size = fs::metadata(&source)
+        .map_err(|e| format!("Failed to read {}: {e}", artifact.path))?
+        .len();
+    if size > MAX_ARTIFACT_BYTES {
+        return Err(format!(
+            "{} is larger than {MAX_ARTIFACT_BYTES} bytes",
+            artifact.path
+        ));
+    }
+    let content =
+        fs::read(&source).map_err(|e| format!("Failed to read {}: {e}", artifact.path))?;
+    let dir = root.join(&artifact.id);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let mut versions = load_versions(&dir)?;
+    if let Some(last) = versions.last() {
+        if fs::read(&last.path).is_ok_and(|previous| previous == content) {
+            return Ok(());
+        }
+    }
+
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "artifact".to_string());
+    let captured_at = unix_now();
+    // The version number keeps copies taken within the same second apart.
+    let copy = dir.join(format!(
+        "{captured_at}-{}-{}-{file_name}",
+        versions.len(),
+        boundary.key()
+    ));
+    fs::write(&copy, &content).map_err(|e| format!("Failed to write {}: {e}", copy.display()))?;
+    versions.push(ArtifactVersion {
+        artifact_id: artifact.id.clone(),
+        task_id: artifact.task_id.clone(),
+        file_name,
+        path: copy.display().to_string(),
+        size: content.len() as u64,
+        boundary,
+        session_type: session_type.clone(),
+        captured_at,
+    });
+    StorageService::write_json(&dir.join(VERSIONS_FILE), &versions)
+}
+
+/// Captures every tracked file if capture is turned on. A file that can't be
+/// read is skipped so the others are still captured.
+pub fn capture(
+    storage: &StorageService,
+    boundary: SessionBoundary,
+    session_type: &SessionType,
+) -> Result<(), String> {
+    if !storage.load_preferences()?.artifact_capture {
+        return Ok(());
+    }
+    let root = storage.artifacts_dir()?;
+    for artifact in load_tracked(storage)? {
+        if let Err(error) = capture_one(&root, &artifact, boundary, session_type) {
+            log::warn!("Artifact capture skipped: {error}");
+        }
+    }
+    Ok(())
+}
+
+/// Versions filed under `task_id` (all versions if `None`), oldest first.
+pub fn timeline(
+    storage: &StorageService,
+    task_id: Option<&str>,
+) -> Result<Vec<ArtifactVersion>, String> {
+    let root = storage.artifacts_dir()?;
+    let entries =
+        fs::read_dir(&root).map_err(|e| format!("Failed to read artifacts directory: {e}"))?;
+    let mut versions = Vec::new();
+    for entry in entries {
+        let dir = entry
+            .map_err(|e| format!("Failed to read artifacts directory: {e}"))?
+            .path();
+        if dir.is_dir() {
+            versions.extend(
+                load_versions(&dir)?
+                    .into_iter()
+                    .filter(|version| task_id.is_none() || version.task_id.as_deref() == task_id),
+            );
+        }
+    }
+    versions.sort_by_key(|version| version.captured_at);
+    Ok(versions)
+}
+
+/// Spots session boundaries in the tick stream.
+pub struct ArtifactWatcher {
+    last_state: Mutex<TimerState>,
+}
+
+impl Default for ArtifactWatcher {
+    fn default() -> Self {
+        Self {
+            last_state: Mutex::new(TimerState::Idle),
+        }
+    }
+}
+
+impl ArtifactWatcher {
+    /// The boundary `tick` crosses, if any. A session that starts
+    /// automatically after another completes shares its end capture.
+    pub fn boundary(&self, tick: &Tick) -> Result<Option<(SessionBoundary, SessionType)>, String> {
+        let mut last_state = self
+            .last_state
+            .lock()
+            .map_err(|e| format!("Artifact watcher lock poisoned: {e}"))?;
+        let data = tick.data();
+        let previous = std::mem::replace(&mut *last_state, data.state);
+        Ok(match tick {
+            Tick::Completed { finished, .. } => Some((SessionBoundary::End, finished.clone())),
+            _ if previous == TimerState::Idle
+                && matches!(data.state, TimerState::Running | TimerState::Stopwatch) =>
+            {
+                Some((SessionBoundary::Start, data.session_type.clone()))
+            }
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{StorageServiceBuilder, UserPreferences};
+    use crate::services::timer_state::{TimerConfig, TimerManager};
+
+    fn tracked(path: &Path) -> TrackedArtifact {
+        TrackedArtifact {
+            id: "notes".to_string(),
+            path: path.display().to_string(),
+            task_id: None,
+            added_at: 0,
+        }
+    }
+
+    #[test]
+    fn quick_changes_each_get_their_own_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let source = root.join("notes.md");
+        let artifact = tracked(&source);
+        for content in ["one", "two", "three"] {
+            fs::write(&source, content).unwrap();
+            capture_one(root, &artifact, SessionBoundary::Start, &SessionType::Work).unwrap();
+        }
+
+        let versions = load_versions(&root.join("notes")).unwrap();
+        let copies: Vec<String> = versions
+            .iter()
+            .map(|version| fs::read_to_string(&version.path).unwrap())
+            .collect();
+        assert_eq!(copies, ["one", "two", "three"]);
+    }
+
+    #[test]
+    fn oversized_files_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let source = root.join("video.mov");
+        fs::File::create(&source)
+            .unwrap()
+            .set_len(MAX_ARTIFACT_BYTES + 1)
+            .unwrap();
+
+        let result = capture_one(
+            root,
+            &tracked(&source),
+            SessionBoundary::End,
+            &SessionType::Work,
+        );
+        assert!(result.is_err());
+        assert!(load_versions(&root.join("notes")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unchanged_files_are_captured_once_under_their_task() {
+        let storage = StorageServiceBuilder::new()
+            .preferences(UserPreferences {
+                artifact_capture: true,
+                ..UserPreferences::default()
+            })
+            .build()
+            .unwrap();
+        let source = storage.path().join("draft.md");
+        fs::write(&source, "draft").unwrap();
+        let first = track(&storage, &source, Some("essay".to_string())).unwrap();
+        // Picking it again moves it to the other task.
+        let again = track(&storage, &source, Some("review".to_string())).unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(load_tracked(&storage).unwrap().len(), 1);
+
+        capture(&storage, SessionBoundary::Start, &SessionType::Work).unwrap();
+        capture(&storage, SessionBoundary::End, &SessionType::Work).unwrap();
+        let versions = timeline(&storage, Some("review")).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].boundary, SessionBoundary::Start);
+        assert!(timeline(&storage, Some("essay")).unwrap().is_empty());
+
+        untrack(&storage, &first.id).unwrap();
+        assert!(load_tracked(&storage).unwrap().is_empty());
+        assert_eq!(timeline(&storage, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn the_watcher_spots_starts_and_completions() {
+        let timer = TimerManager::new(TimerConfig::default());
+        let watcher = ArtifactWatcher::default();
+        let idle = timer.get_data().unwrap();
+        assert_eq!(watcher.boundary(&Tick::Waiting(idle)).unwrap(), None);
+
+        let running = timer.start(None).unwrap();
+        assert_eq!(
+            watcher.boundary(&Tick::Running(running.clone())).unwrap(),
+            Some((SessionBoundary::Start, SessionType::Work))
+        );
+        assert_eq!(watcher.boundary(&Tick::Running(running)).unwrap(), None);
+
+        let next = timer.get_data().unwrap();
+        assert_eq!(
+            watcher
+                .boundary(&Tick::Completed {
+                    finished: SessionType::Work,
+                    next,
+                })
+                .unwrap(),
+            Some((SessionBoundary::End, SessionType::Work))
+        );
+    }
+}
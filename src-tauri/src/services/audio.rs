@@ -0,0 +1,265 @@
+//! Decides which alarm sound is due and at what volume. Playback itself
+//! happens in the webview, which listens for [`PLAY_EVENT`].
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+use super::notifications::NotificationPreferences;
+use super::storage::UserPreferences;
+use super::timer_state::{SessionType, TimerData, TimerState};
+
+/// Emitted with an [`AlarmCue`] whenever a sound should play.
+pub const PLAY_EVENT: &str = "audio://play";
+/// The pre-alarm fires this many seconds before a session ends.
+const PRE_ALARM_LEAD_SECS: u64 = 60;
+/// How often the overtime nag repeats while the next session hasn't started.
+const OVERTIME_NAG_INTERVAL: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct AlarmStage {
+    pub enabled: bool,
+    /// Sound identifier resolved by the frontend.
+    pub sound: String,
+    /// `0.0..=1.0`, scaled by the phase volume.
+    pub volume: f32,
+}
+
+impl AlarmStage {
+    fn new(enabled: bool, sound: &str) -> Self {
+        Self {
+            enabled,
+            sound: sound.to_string(),
+            volume: 1.0,
+        }
+    }
+}
+
+impl Default for AlarmStage {
+    fn default() -> Self {
+        Self::new(true, "bell")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SessionAlarms {
+    /// One-minute warning.
+    pub pre_alarm: AlarmStage,
+    /// Session completion.
+    pub alarm: AlarmStage,
+    /// Repeats after completion until the next session is started.
+    pub overtime: AlarmStage,
+}
+
+impl Default for SessionAlarms {
+    fn default() -> Self {
+        Self {
+            pre_alarm: AlarmStage::new(false, "chime"),
+            alarm: AlarmStage::new(true, "bell"),
+            overtime: AlarmStage::new(false, "nag"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct AlarmPreferences {
+    pub work: SessionAlarms,
+    pub short_break: SessionAlarms,
+    pub long_break: SessionAlarms,
+    /// Shared by all user-defined session types.
+    pub custom: SessionAlarms,
+}
+
+impl AlarmPreferences {
+    pub fn for_session(&self, session_type: &SessionType) -> &SessionAlarms {
+        match session_type {
+            SessionType::Work => &self.work,
+            SessionType::ShortBreak => &self.short_break,
+            SessionType::LongBreak => &self.long_break,
+            SessionType::Custom(_) => &self.custom,
+        }
+    }
+
+    pub fn all(&self) -> [&SessionAlarms; 4] {
+        [
+            &self.work,
+            &self.short_break,
+            &self.long_break,
+            &self.custom,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum AlarmStageKind {
+    PreAlarm,
+    Alarm,
+    Overtime,
+}
+
+/// Payload of [`PLAY_EVENT`].
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AlarmCue {
+    pub stage: AlarmStageKind,
+    pub session_type: SessionType,
+    pub sound: String,
+    /// Final playback volume, phase volume already applied.
+    pub volume: f32,
+}
+
+/// An alarm stage that came due.
+pub struct AlarmFired {
+    pub stage: AlarmStageKind,
+    pub session_type: SessionType,
+    /// The sound to play, unless sounds are off for the phase.
+    pub cue: Option<AlarmCue>,
+    /// Whether the phase wants a native notification.
+    pub notify: bool,
+}
+
+/// A finished session whose follow-up hasn't been started yet.
+struct Overtime {
+    session_type: SessionType,
+    /// Timer revision right after completion; any later change ends the nag.
+    revision: u64,
+    last_cue: Instant,
+}
+
+struct AudioState {
+    notifications: NotificationPreferences,
+    alarms: AlarmPreferences,
+    pre_alarm_played: bool,
+    overtime: Option<Overtime>,
+}
+
+impl AudioState {
+    fn fire(&self, stage: AlarmStageKind, session_type: SessionType) -> Option<AlarmFired> {
+        let alarms = self.alarms.for_session(&session_type);
+        let config = match stage {
+            AlarmStageKind::PreAlarm => &alarms.pre_alarm,
+            AlarmStageKind::Alarm => &alarms.alarm,
+            AlarmStageKind::Overtime => &alarms.overtime,
+        };
+        if !config.enabled {
+            return None;
+        }
+        let phase = self.notifications.for_stage(stage, &session_type);
+        let cue = phase.sound_enabled.then(|| AlarmCue {
+            stage,
+            session_type: session_type.clone(),
+            sound: config.sound.clone(),
+            volume: config.volume * phase.volume,
+        });
+        Some(AlarmFired {
+            stage,
+            session_type,
+            cue,
+            notify: phase.notifications_enabled,
+        })
+    }
+}
+
+/// Tracks alarm stages across timer ticks, fed by the timer's tick loop.
+pub struct AudioService {
+    state: Mutex<AudioState>,
+}
+
+impl AudioService {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(AudioState {
+                notifications: preferences.notifications.clone(),
+                alarms: preferences.alarms.clone(),
+                pre_alarm_played: false,
+                overtime: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, AudioState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Audio state lock poisoned: {e}"))
+    }
+
+    /// Picks up changed sound preferences.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.notifications = preferences.notifications.clone();
+        state.alarms = preferences.alarms.clone();
+        Ok(())
+    }
+
+    /// Observes a tick in which no session finished.
+    pub fn on_tick(&self, data: &TimerData) -> Result<Option<AlarmFired>, String> {
+        let mut state = self.lock()?;
+        if state
+            .overtime
+            .as_ref()
+            .is_some_and(|overtime| overtime.revision != data.revision)
+        {
+            state.overtime = None;
+        }
+
+        if data.state == TimerState::Running {
+            let in_warning = data.remaining_time <= PRE_ALARM_LEAD_SECS;
+            if !in_warning {
+                state.pre_alarm_played = false;
+            } else if !state.pre_alarm_played && data.total_time > PRE_ALARM_LEAD_SECS {
+                state.pre_alarm_played = true;
+                return Ok(state.fire(AlarmStageKind::PreAlarm, data.session_type.clone()));
+            }
+            return Ok(None);
+        }
+
+        let Some(overtime) = state.overtime.as_mut() else {
+            return Ok(None);
+        };
+        if overtime.last_cue.elapsed() < OVERTIME_NAG_INTERVAL {
+            return Ok(None);
+        }
+        overtime.last_cue = Instant::now();
+        let session_type = overtime.session_type.clone();
+        Ok(state.fire(AlarmStageKind::Overtime, session_type))
+    }
+
+    /// The sound `stage` of a `session_type` session plays, if it is on,
+    /// for cues outside the timer's own stages.
+    pub fn cue(
+        &self,
+        stage: AlarmStageKind,
+        session_type: SessionType,
+    ) -> Result<Option<AlarmCue>, String> {
+        Ok(self
+            .lock()?
+            .fire(stage, session_type)
+            .and_then(|fired| fired.cue))
+    }
+
+    /// Observes the end of a `finished` session; `next` is the timer state
+    /// afterwards.
+    pub fn on_completed(
+        &self,
+        finished: SessionType,
+        next: &TimerData,
+    ) -> Result<Option<AlarmFired>, String> {
+        let mut state = self.lock()?;
+        state.pre_alarm_played = false;
+        state.overtime = Some(Overtime {
+            session_type: finished.clone(),
+            revision: next.revision,
+            last_cue: Instant::now(),
+        });
+        Ok(state.fire(AlarmStageKind::Alarm, finished))
+    }
+}
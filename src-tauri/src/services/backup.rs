@@ -0,0 +1,200 @@
+//! Writes timestamped backups on a daily or weekly cadence and keeps only
+//! the most recent few, so data survives without the user remembering
+//! `backup_data`.
+
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::service_health::{self, BackgroundService};
+use super::storage::{StorageService, UserPreferences};
+
+/// How often the scheduler checks whether a backup is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const FILE_PREFIX: &str = "tempus-ring-auto-";
+const FILE_EXTENSION: &str = "json";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupCadence {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl BackupCadence {
+    fn period(self) -> TimeDelta {
+        match self {
+            BackupCadence::Daily => TimeDelta::days(1),
+            BackupCadence::Weekly => TimeDelta::weeks(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct AutoBackupConfig {
+    pub enabled: bool,
+    pub cadence: BackupCadence,
+    /// Number of automatic backups kept; older ones are deleted.
+    pub keep: u32,
+    /// Directory backups are written to, or the app's backup directory when
+    /// unset. Only set through `choose_auto_backup_dir`.
+    pub dir: Option<String>,
+}
+
+impl Default for AutoBackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cadence: BackupCadence::Daily,
+            keep: 7,
+            dir: None,
+        }
+    }
+}
+
+/// An automatic backup on disk.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BackupFile {
+    /// Absolute path, accepted by `restore_data`.
+    pub path: String,
+    /// Local time the backup was taken, `YYYY-MM-DDTHH:MM:SS`.
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+/// When the automatic backup named `name` was taken, or `None` for any
+/// other file.
+fn parse_timestamp(name: &str) -> Option<NaiveDateTime> {
+    let stamp = name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(FILE_EXTENSION)?
+        .strip_suffix('.')?;
+    NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()
+}
+
+fn scan(dir: &Path) -> Result<Vec<(NaiveDateTime, PathBuf)>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+    let mut backups: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let taken = parse_timestamp(entry.file_name().to_str()?)?;
+            Some((taken, entry.path()))
+        })
+        .collect();
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(backups)
+}
+
+/// The automatic backups in `dir`, newest first.
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupFile>, String> {
+    Ok(scan(dir)?
+        .into_iter()
+        .map(|(taken, path)| BackupFile {
+            size_bytes: fs::metadata(&path).map_or(0, |metadata| metadata.len()),
+            path: path.display().to_string(),
+            created_at: taken.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        })
+        .collect())
+}
+
+/// Resolves where automatic backups go for `config`.
+pub fn backup_dir(storage: &StorageService, config: &AutoBackupConfig) -> Result<PathBuf, String> {
+    match &config.dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => storage.backup_dir(),
+    }
+}
+
+/// Deletes all but the newest `keep` automatic backups in `dir`.
+fn rotate(dir: &Path, keep: u32) -> Result<(), String> {
+    for (_, path) in scan(dir)?.into_iter().skip(keep as usize) {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub struct BackupScheduler {
+    config: Mutex<AutoBackupConfig>,
+}
+
+impl BackupScheduler {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            config: Mutex::new(preferences.auto_backup.clone()),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, AutoBackupConfig>, String> {
+        self.config
+            .lock()
+            .map_err(|e| format!("Backup scheduler lock poisoned: {e}"))
+    }
+
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        *self.lock()? = preferences.auto_backup.clone();
+        Ok(())
+    }
+
+    /// Writes a backup if the newest one is at least a cadence old, then
+    /// trims the directory to `keep` backups. Returns the new backup's path.
+    pub fn run_if_due(
+        &self,
+        storage: &StorageService,
+        now: NaiveDateTime,
+    ) -> Result<Option<PathBuf>, String> {
+        // Held throughout so a reconfiguration can't interleave with a run.
+        let config = self.lock()?;
+        if !config.enabled {
+            return Ok(None);
+        }
+        let dir = backup_dir(storage, &config)?;
+        let newest = scan(&dir)?.first().map(|(taken, _)| *taken);
+        if newest.is_some_and(|taken| now - taken < config.cadence.period()) {
+            return Ok(None);
+        }
+
+        let path = dir.join(format!(
+            "{FILE_PREFIX}{}.{FILE_EXTENSION}",
+            now.format(TIMESTAMP_FORMAT)
+        ));
+        storage.backup_data(&path, None)?;
+        rotate(&dir, config.keep)?;
+        Ok(Some(path))
+    }
+}
+
+/// Checks every [`CHECK_INTERVAL`], so a backup missed while the app was
+/// closed is taken shortly after the next launch.
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let storage = app.state::<StorageService>();
+            let result = app
+                .state::<BackupScheduler>()
+                .run_if_due(&storage, Local::now().naive_local());
+            match &result {
+                Ok(None) => continue,
+                Ok(Some(_)) => {}
+                Err(error) => log::error!("Automatic backup failed: {error}"),
+            }
+            service_health::report(&app, BackgroundService::AutoBackup, &result);
+        }
+    });
+}
@@ -0,0 +1,208 @@
+//! Passphrase encryption for backup files: AES-256-GCM with a key derived
+//! by PBKDF2-HMAC-SHA256 from the passphrase and a random salt.
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::num::NonZeroU32;
+
+const CIPHER: &str = "aes-256-gcm";
+const KDF: &str = "pbkdf2-hmac-sha256";
+const ITERATIONS: u32 = 600_000;
+/// Files asking for more iterations than this are refused rather than
+/// stalling the app.
+const MAX_ITERATIONS: u32 = 10_000_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+pub const MIN_PASSPHRASE_CHARS: usize = 8;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Encryption {
+    cipher: String,
+    kdf: String,
+    iterations: u32,
+    /// Hex-encoded.
+    salt: String,
+    /// Hex-encoded.
+    nonce: String,
+}
+
+/// An encrypted backup. The ciphertext decrypts to a plain backup file.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EncryptedBackup {
+    encryption: Encryption,
+    /// Hex-encoded ciphertext followed by the GCM tag.
+    ciphertext: String,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(field: &str, hex: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("Encrypted backup has an invalid {field}");
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    iterations: NonZeroU32,
+) -> Result<LessSafeKey, String> {
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    UnboundKey::new(&AES_256_GCM, &key)
+        .map(LessSafeKey::new)
+        .map_err(|_| "Failed to derive the backup key".to_string())
+}
+
+/// Whether `value` is an encrypted backup rather than a plain one.
+pub fn is_encrypted(value: &Value) -> bool {
+    value.get("encryption").is_some()
+}
+
+/// Encrypts the plain backup file `plaintext` with `passphrase`.
+pub fn encrypt(plaintext: String, passphrase: &str) -> Result<String, String> {
+    encrypt_with(plaintext, passphrase, ITERATIONS)
+}
+
+fn encrypt_with(plaintext: String, passphrase: &str, iterations: u32) -> Result<String, String> {
+    let random = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    random
+        .fill(&mut salt)
+        .and_then(|()| random.fill(&mut nonce))
+        .map_err(|_| "Failed to generate random bytes".to_string())?;
+
+    let key = derive_key(
+        passphrase,
+        &salt,
+        NonZeroU32::new(iterations).ok_or("Invalid iteration count")?,
+    )?;
+    let mut sealed = plaintext.into_bytes();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut sealed,
+    )
+    .map_err(|_| "Failed to encrypt backup".to_string())?;
+
+    let backup = EncryptedBackup {
+        encryption: Encryption {
+            cipher: CIPHER.to_string(),
+            kdf: KDF.to_string(),
+            iterations,
+            salt: to_hex(&salt),
+            nonce: to_hex(&nonce),
+        },
+        ciphertext: to_hex(&sealed),
+    };
+    serde_json::to_string_pretty(&backup).map_err(|e| format!("Failed to serialize backup: {e}"))
+}
+
+/// Decrypts an encrypted backup back to the plain backup file.
+pub fn decrypt(value: Value, passphrase: &str) -> Result<String, String> {
+    let backup: EncryptedBackup =
+        serde_json::from_value(value).map_err(|e| format!("Encrypted backup is invalid: {e}"))?;
+    let encryption = backup.encryption;
+    if encryption.cipher != CIPHER || encryption.kdf != KDF {
+        return Err(format!(
+            "Backup is encrypted with {} / {}, which is not supported",
+            encryption.cipher, encryption.kdf
+        ));
+    }
+    let iterations = NonZeroU32::new(encryption.iterations)
+        .filter(|iterations| iterations.get() <= MAX_ITERATIONS)
+        .ok_or("Encrypted backup has an invalid iteration count")?;
+    let nonce: [u8; NONCE_LEN] = from_hex("nonce", &encryption.nonce)?
+        .try_into()
+        .map_err(|_| "Encrypted backup has an invalid nonce")?;
+    let salt = from_hex("salt", &encryption.salt)?;
+    let mut sealed = from_hex("ciphertext", &backup.ciphertext)?;
+
+    let key = derive_key(passphrase, &salt, iterations)?;
+    let plaintext = key
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut sealed,
+        )
+        .map_err(|_| "Wrong passphrase, or the backup is corrupt".to_string())?;
+    String::from_utf8(plaintext.to_vec())
+        .map_err(|_| "Decrypted backup is not valid text".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Few enough iterations to keep the tests quick.
+    const TEST_ITERATIONS: u32 = 1_000;
+
+    fn encrypted(plaintext: &str, passphrase: &str) -> Value {
+        let content = encrypt_with(plaintext.to_string(), passphrase, TEST_ITERATIONS).unwrap();
+        serde_json::from_str(&content).unwrap()
+    }
+
+    #[test]
+    fn backups_decrypt_to_what_was_encrypted() {
+        let value = encrypted(r#"{"version":3}"#, "correct horse");
+        assert!(is_encrypted(&value));
+        assert!(!value.to_string().contains("version"));
+        assert_eq!(decrypt(value, "correct horse").unwrap(), r#"{"version":3}"#);
+    }
+
+    #[test]
+    fn a_wrong_passphrase_is_rejected() {
+        let value = encrypted("secret", "correct horse");
+        assert_eq!(
+            decrypt(value, "correct horsE").unwrap_err(),
+            "Wrong passphrase, or the backup is corrupt"
+        );
+    }
+
+    #[test]
+    fn tampered_or_unsupported_backups_are_rejected() {
+        let mut value = encrypted("secret", "correct horse");
+        let ciphertext = value["ciphertext"].as_str().unwrap();
+        let flipped = if ciphertext.starts_with('0') {
+            "1"
+        } else {
+            "0"
+        };
+        let tampered = format!("{flipped}{}", &ciphertext[1..]);
+        let mut changed = value.clone();
+        changed["ciphertext"] = Value::String(tampered);
+        assert!(decrypt(changed, "correct horse").is_err());
+
+        value["encryption"]["iterations"] = Value::from(MAX_ITERATIONS + 1);
+        assert!(decrypt(value.clone(), "correct horse")
+            .unwrap_err()
+            .contains("iteration count"));
+        value["encryption"]["cipher"] = Value::String("rot13".to_string());
+        assert!(decrypt(value, "correct horse")
+            .unwrap_err()
+            .contains("not supported"));
+    }
+}
@@ -0,0 +1,336 @@
+//! On-disk format of backup files.
+//!
+//! A backup is an envelope carrying a format version and a SHA-256 checksum
+//! of its `data` section, optionally encrypted as a whole. Files from older
+//! versions are migrated on read; corrupt files and files from newer
+//! versions are rejected.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use super::backup_crypto;
+use super::integrity::checksum;
+use super::notifications::migrate_flat_preferences;
+use super::preferences::UserPreferences;
+use super::storage::TimerStatistic;
+use super::tasks::Task;
+
+/// Bumped whenever `BackupData` changes incompatibly; add a step to
+/// [`migrate`] for the previous version when it is.
+pub const BACKUP_FORMAT_VERSION: u32 = 4;
+
+/// Everything a backup restores.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackupData {
+    pub preferences: UserPreferences,
+    pub statistics: Vec<TimerStatistic>,
+    pub tasks: Vec<Task>,
+}
+
+/// What a backup holds, shown before restoring it.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BackupInfo {
+    pub format_version: u32,
+    /// RFC 3339 time the backup was written; unknown for version 1 files.
+    pub created_at: Option<String>,
+    /// Version of Tempus Ring that wrote it; recorded since format 4.
+    pub app_version: Option<String>,
+    pub encrypted: bool,
+    pub statistics_days: u32,
+    pub sessions: u32,
+    pub tasks: u32,
+    /// Earliest and latest statistics days, `YYYY-MM-DD`.
+    pub first_date: Option<String>,
+    pub last_date: Option<String>,
+}
+
+/// Which parts of a backup to restore. Everything by default.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct RestoreSelection {
+    pub preferences: bool,
+    pub tasks: bool,
+    pub statistics: bool,
+    /// Inclusive `YYYY-MM-DD` bounds on the statistics days restored.
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+impl Default for RestoreSelection {
+    fn default() -> Self {
+        Self {
+            preferences: true,
+            tasks: true,
+            statistics: true,
+            start_date: None,
+            end_date: None,
+        }
+    }
+}
+
+impl RestoreSelection {
+    /// Whether the statistics day `date` is restored.
+    pub fn includes(&self, date: &str) -> bool {
+        self.statistics
+            && self.start_date.as_deref().is_none_or(|start| date >= start)
+            && self.end_date.as_deref().is_none_or(|end| date <= end)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Envelope {
+    format_version: u32,
+    created_at: String,
+    #[serde(default)]
+    app_version: Option<String>,
+    /// `sha256:<hex>` of `data` serialized compactly.
+    checksum: String,
+    data: Value,
+}
+
+/// Serializes `backup` in the current format, encrypted if a `passphrase`
+/// is given.
+pub fn encode(backup: &BackupData, passphrase: Option<&str>) -> Result<String, String> {
+    let data =
+        serde_json::to_value(backup).map_err(|e| format!("Failed to serialize backup: {e}"))?;
+    let envelope = Envelope {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: Local::now().to_rfc3339(),
+        app_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        checksum: checksum(&data),
+        data,
+    };
+    let content = serde_json::to_string_pretty(&envelope)
+        .map_err(|e| format!("Failed to serialize backup: {e}"))?;
+    match passphrase {
+        Some(passphrase) => backup_crypto::encrypt(content, passphrase),
+        None => Ok(content),
+    }
+}
+
+/// Upgrades the `data` section of a `from`-version backup to the current
+/// format, one version at a time.
+fn migrate(from: u32, mut data: Value) -> Result<Value, String> {
+    for version in from..BACKUP_FORMAT_VERSION {
+        data = match version {
+            // Version 1 was the bare data section without an envelope, and
+            // `backup_data` always wrote every section.
+            1 => {
+                let Some(sections) = data.as_object() else {
+                    return Err("Backup is not a JSON object".to_string());
+                };
+                for section in ["preferences", "statistics", "tasks"] {
+                    if !sections.contains_key(section) {
+                        return Err(format!("Backup is missing its {section} section"));
+                    }
+                }
+                data
+            }
+            // Version 2 preferences had flat sound and notification fields.
+            2 => {
+                if let Some(preferences) = data.get_mut("preferences") {
+                    migrate_flat_preferences(preferences);
+                }
+                data
+            }
+            // Version 4 added `app_version` to the envelope only.
+            3 => data,
+            _ => return Err(format!("Backup format {version} is not supported")),
+        };
+    }
+    Ok(data)
+}
+
+/// Parses a backup of any supported version, checking its checksum.
+/// Encrypted backups need their `passphrase`.
+pub fn decode(content: &str, passphrase: Option<&str>) -> Result<BackupData, String> {
+    open(content, passphrase).map(|(_, data)| data)
+}
+
+/// Like [`decode`], also describing the backup.
+pub fn inspect(content: &str, passphrase: Option<&str>) -> Result<BackupInfo, String> {
+    let (mut info, data) = open(content, passphrase)?;
+    let mut dates: Vec<&str> = data
+        .statistics
+        .iter()
+        .map(|day| day.date.as_str())
+        .collect();
+    dates.sort_unstable();
+    info.statistics_days = data.statistics.len() as u32;
+    info.sessions = data
+        .statistics
+        .iter()
+        .map(|day| day.sessions.len() as u32)
+        .sum();
+    info.tasks = data.tasks.len() as u32;
+    info.first_date = dates.first().map(|date| date.to_string());
+    info.last_date = dates.last().map(|date| date.to_string());
+    Ok(info)
+}
+
+/// Decrypts, verifies and migrates a backup. The returned info has only its
+/// envelope fields filled in.
+fn open(content: &str, passphrase: Option<&str>) -> Result<(BackupInfo, BackupData), String> {
+    let mut value: Value =
+        serde_json::from_str(content).map_err(|e| format!("Backup is not valid JSON: {e}"))?;
+    let encrypted = backup_crypto::is_encrypted(&value);
+    if encrypted {
+        let passphrase = passphrase.ok_or("Backup is encrypted; enter its passphrase")?;
+        let content = backup_crypto::decrypt(value, passphrase)?;
+        value = serde_json::from_str(&content)
+            .map_err(|e| format!("Decrypted backup is not valid JSON: {e}"))?;
+    }
+    let version = match value.get("format_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or("Backup format_version is not a number")?,
+    };
+    if version > BACKUP_FORMAT_VERSION {
+        return Err(format!(
+            "Backup format {version} was written by a newer version of Tempus Ring; \
+             this version reads formats up to {BACKUP_FORMAT_VERSION}"
+        ));
+    }
+
+    let mut info = BackupInfo {
+        format_version: version,
+        created_at: None,
+        app_version: None,
+        encrypted,
+        statistics_days: 0,
+        sessions: 0,
+        tasks: 0,
+        first_date: None,
+        last_date: None,
+    };
+    let data = if version == 1 {
+        value
+    } else {
+        let envelope: Envelope = serde_json::from_value(value)
+            .map_err(|e| format!("Backup envelope is invalid: {e}"))?;
+        if checksum(&envelope.data) != envelope.checksum {
+            return Err("Backup is corrupt: its checksum does not match its contents".to_string());
+        }
+        info.created_at = Some(envelope.created_at);
+        info.app_version = envelope.app_version;
+        envelope.data
+    };
+    let data = serde_json::from_value(migrate(version, data)?)
+        .map_err(|e| format!("Backup contents are invalid: {e}"))?;
+    Ok((info, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, session};
+    use crate::services::timer_state::SessionType;
+    use serde_json::json;
+
+    fn backup() -> BackupData {
+        BackupData {
+            preferences: UserPreferences::default(),
+            statistics: vec![day(
+                "2026-03-02",
+                vec![session(SessionType::Work, 1_772_438_400, 1500)],
+            )],
+            tasks: Vec::new(),
+        }
+    }
+
+    /// A backup written by an older version, in its `version` envelope.
+    fn legacy(version: u32, data: Value) -> String {
+        json!({
+            "format_version": version,
+            "created_at": "2026-03-02T09:00:00+01:00",
+            "checksum": checksum(&data),
+            "data": data,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn a_backup_reads_back_as_written() {
+        let content = encode(&backup(), None).unwrap();
+
+        let info = inspect(&content, None).unwrap();
+        assert_eq!(info.format_version, BACKUP_FORMAT_VERSION);
+        assert_eq!(info.sessions, 1);
+        assert_eq!(info.first_date.as_deref(), Some("2026-03-02"));
+        assert_eq!(
+            decode(&content, None).unwrap().statistics[0].date,
+            "2026-03-02"
+        );
+    }
+
+    #[test]
+    fn a_tampered_backup_fails_its_checksum() {
+        let mut envelope: Value = serde_json::from_str(&encode(&backup(), None).unwrap()).unwrap();
+        envelope["data"]["statistics"][0]["completed_pomodoros"] = json!(40);
+
+        let error = decode(&envelope.to_string(), None).unwrap_err();
+        assert!(error.contains("checksum"), "{error}");
+    }
+
+    #[test]
+    fn a_backup_from_a_newer_version_is_rejected() {
+        let data = serde_json::to_value(backup()).unwrap();
+
+        let error = decode(&legacy(BACKUP_FORMAT_VERSION + 1, data), None).unwrap_err();
+        assert!(error.contains("newer version"), "{error}");
+    }
+
+    #[test]
+    fn a_version_1_backup_is_the_bare_data_section() {
+        let content = serde_json::to_value(backup()).unwrap().to_string();
+
+        let info = inspect(&content, None).unwrap();
+        assert_eq!(info.format_version, 1);
+        assert_eq!(info.created_at, None);
+        assert_eq!(decode(&content, None).unwrap().statistics.len(), 1);
+
+        let error = decode(r#"{"preferences": {}, "tasks": []}"#, None).unwrap_err();
+        assert!(error.contains("statistics"), "{error}");
+    }
+
+    #[test]
+    fn version_2_flat_notification_fields_move_into_each_phase() {
+        let mut data = serde_json::to_value(backup()).unwrap();
+        let preferences = data["preferences"].as_object_mut().unwrap();
+        preferences.remove("notifications");
+        preferences.insert("sound_enabled".to_string(), json!(false));
+        preferences.insert("volume".to_string(), json!(0.25));
+        preferences.insert("notifications_enabled".to_string(), json!(true));
+
+        let notifications = decode(&legacy(2, data), None)
+            .unwrap()
+            .preferences
+            .notifications;
+        assert!(!notifications.work_end.sound_enabled);
+        assert_eq!(notifications.break_end.volume, 0.25);
+        assert!(notifications.work_end.notifications_enabled);
+        assert!(!notifications.reminders.notifications_enabled);
+    }
+
+    #[test]
+    fn a_version_3_backup_has_no_app_version() {
+        let data = serde_json::to_value(backup()).unwrap();
+
+        let info = inspect(&legacy(3, data), None).unwrap();
+        assert_eq!(info.format_version, 3);
+        assert_eq!(info.app_version, None);
+        assert_eq!(
+            info.created_at.as_deref(),
+            Some("2026-03-02T09:00:00+01:00")
+        );
+        assert_eq!(info.statistics_days, 1);
+    }
+}
@@ -0,0 +1,390 @@
+//! Battery and charging state, so an unplugged laptop doesn't die in the
+//! middle of a deep-work block. Read from sysfs on Linux, `pmset` on macOS
+//! and `GetSystemPowerStatus` on Windows, at most once a minute and off
+//! the tick loop.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::event_log::{TimerEvent, TimerEventKind};
+use super::inbox::{self, InboxKind};
+use super::session_types::counts_as_pomodoro;
+use super::storage::UserPreferences;
+use super::timer_state::{TimerData, TimerState};
+
+/// Emitted with the new `Option<PowerStatus>` when it changes.
+pub const POWER_STATUS_EVENT: &str = "power://status";
+/// Emitted with a [`LowBatteryWarning`] when a long session starts, or
+/// isn't started, on low battery.
+pub const LOW_BATTERY_EVENT: &str = "power://low_battery";
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct PowerStatus {
+    pub battery_percent: u8,
+    /// Running on battery rather than on a charger.
+    pub on_battery: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryGuardMode {
+    #[default]
+    Off,
+    /// Warns when a long session starts on low battery.
+    Warn,
+    /// Also keeps long sessions from starting on their own.
+    Defer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct BatteryGuard {
+    pub mode: BatteryGuardMode,
+    /// Battery is low below this, while unplugged.
+    pub threshold_percent: u8,
+    /// Focus sessions at least this long count as long; stopwatches always
+    /// do.
+    pub long_session_minutes: u32,
+}
+
+impl Default for BatteryGuard {
+    fn default() -> Self {
+        Self {
+            mode: BatteryGuardMode::default(),
+            threshold_percent: 20,
+            long_session_minutes: 45,
+        }
+    }
+}
+
+impl BatteryGuard {
+    fn is_low(&self, status: PowerStatus) -> bool {
+        self.mode != BatteryGuardMode::Off
+            && status.on_battery
+            && status.battery_percent < self.threshold_percent
+    }
+
+    fn is_long(&self, data: &TimerData) -> bool {
+        counts_as_pomodoro(&data.session_type, &data.config.custom_session_types)
+            && (data.state == TimerState::Stopwatch
+                || data.total_time >= u64::from(self.long_session_minutes) * 60)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct LowBatteryWarning {
+    pub status: PowerStatus,
+    pub timer: TimerData,
+    /// The session was kept from starting on its own.
+    pub deferred: bool,
+}
+
+#[cfg(target_os = "linux")]
+fn read_status() -> Option<PowerStatus> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok();
+    std::fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| read(path.join("type")).is_some_and(|kind| kind.trim() == "Battery"))
+        .find_map(|path| {
+            let percent = read(path.join("capacity"))?.trim().parse::<u8>().ok()?;
+            Some(PowerStatus {
+                battery_percent: percent.min(100),
+                on_battery: read(path.join("status"))?.trim() == "Discharging",
+            })
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn read_status() -> Option<PowerStatus> {
+    // Now drawing from 'Battery Power'
+    //  -InternalBattery-0 (id=1234)	85%; discharging; 4:12 remaining present: true
+    let output = super::focus_mode::run("pmset", &["-g", "batt"]).ok()?;
+    let battery = output
+        .lines()
+        .find(|line| line.contains("InternalBattery"))?;
+    let percent = battery
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix("%;"))?
+        .parse::<u8>()
+        .ok()?;
+    Some(PowerStatus {
+        battery_percent: percent.min(100),
+        on_battery: output.contains("'Battery Power'"),
+    })
+}
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default)]
+struct SystemPowerStatus {
+    ac_line_status: u8,
+    battery_flag: u8,
+    battery_life_percent: u8,
+    _system_status_flag: u8,
+    _battery_life_time: u32,
+    _battery_full_life_time: u32,
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+fn read_status() -> Option<PowerStatus> {
+    const NO_BATTERY: u8 = 128;
+    const UNKNOWN: u8 = 255;
+    let mut status = SystemPowerStatus::default();
+    // SAFETY: only writes the struct passed in, which matches the
+    // SYSTEM_POWER_STATUS layout.
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0
+        || status.battery_flag & NO_BATTERY != 0
+        || status.battery_life_percent == UNKNOWN
+    {
+        return None;
+    }
+    Some(PowerStatus {
+        battery_percent: status.battery_life_percent.min(100),
+        on_battery: status.ac_line_status == 0,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn read_status() -> Option<PowerStatus> {
+    None
+}
+
+struct BatteryState {
+    guard: BatteryGuard,
+    status: Option<PowerStatus>,
+    /// Whether `status` has been read yet.
+    read: bool,
+    /// When the last poll started.
+    polled_at: Option<Instant>,
+}
+
+/// Keeps the last battery reading for the tick loop and commands.
+pub struct BatteryMonitor {
+    state: Mutex<BatteryState>,
+}
+
+impl BatteryMonitor {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(BatteryState {
+                guard: preferences.battery_guard.clone(),
+                status: None,
+                read: false,
+                polled_at: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, BatteryState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Battery monitor lock poisoned: {e}"))
+    }
+
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        self.lock()?.guard = preferences.battery_guard.clone();
+        Ok(())
+    }
+
+    /// The last reading, or `None` without a battery. Reads one if none
+    /// was taken yet.
+    pub fn status(&self) -> Result<Option<PowerStatus>, String> {
+        let mut state = self.lock()?;
+        if !state.read {
+            state.status = read_status();
+            state.read = true;
+        }
+        Ok(state.status)
+    }
+
+    /// Whether to re-read the battery, once `POLL_INTERVAL` has passed.
+    /// Marks the poll as started.
+    fn start_poll(&self) -> Result<bool, String> {
+        let mut state = self.lock()?;
+        if state
+            .polled_at
+            .is_some_and(|polled_at| polled_at.elapsed() < POLL_INTERVAL)
+        {
+            return Ok(false);
+        }
+        state.polled_at = Some(Instant::now());
+        Ok(true)
+    }
+
+    /// Keeps a new reading. Returns it if it changed from an earlier one.
+    fn update(&self, status: Option<PowerStatus>) -> Result<Option<Option<PowerStatus>>, String> {
+        let mut state = self.lock()?;
+        let changed = state.read && status != state.status;
+        state.status = status;
+        state.read = true;
+        Ok(changed.then_some(status))
+    }
+}
+
+/// Reads the battery on a blocking task and emits [`POWER_STATUS_EVENT`]
+/// if it changed.
+fn poll(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let emitted = app
+            .state::<BatteryMonitor>()
+            .update(read_status())
+            .and_then(|changed| match changed {
+                Some(status) => app
+                    .emit(POWER_STATUS_EVENT, status)
+                    .map_err(|e| format!("Failed to emit {POWER_STATUS_EVENT}: {e}")),
+                None => Ok(()),
+            });
+        if let Err(error) = emitted {
+            log::error!("{error}");
+        }
+    });
+}
+
+fn warn(
+    app: &AppHandle,
+    status: PowerStatus,
+    timer: &TimerData,
+    deferred: bool,
+) -> Result<(), String> {
+    inbox::record_or_log(
+        app,
+        InboxKind::LowBattery,
+        format!("Battery at {}%", status.battery_percent),
+        Some(if deferred {
+            "The next long session wasn't started automatically. Plug in to continue.".to_string()
+        } else {
+            "A long session started on battery. Plug in so it isn't cut short.".to_string()
+        }),
+    );
+    let warning = LowBatteryWarning {
+        status,
+        timer: timer.clone(),
+        deferred,
+    };
+    app.emit(LOW_BATTERY_EVENT, warning)
+        .map_err(|e| format!("Failed to emit {LOW_BATTERY_EVENT}: {e}"))
+}
+
+/// Called by the tick loop: re-reads the battery now and then, emitting
+/// [`POWER_STATUS_EVENT`] on a change, and warns when `events` include a
+/// long session starting on low battery.
+pub fn check(app: &AppHandle, events: &[TimerEvent], data: &TimerData) -> Result<(), String> {
+    let monitor = app.state::<BatteryMonitor>();
+    if monitor.start_poll()? {
+        poll(app);
+    }
+    if !events
+        .iter()
+        .any(|event| event.kind == TimerEventKind::Start)
+    {
+        return Ok(());
+    }
+    let status = monitor.status()?;
+    let guard = monitor.lock()?.guard.clone();
+    match status {
+        Some(status) if guard.is_low(status) && guard.is_long(data) => {
+            warn(app, status, data, false)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether `next` may start on its own. Under [`BatteryGuardMode::Defer`]
+/// a long session on low battery may not; the refusal is announced.
+pub fn allows_auto_start(app: &AppHandle, next: &TimerData) -> Result<bool, String> {
+    let monitor = app.state::<BatteryMonitor>();
+    let guard = monitor.lock()?.guard.clone();
+    if guard.mode != BatteryGuardMode::Defer || !guard.is_long(next) {
+        return Ok(true);
+    }
+    match monitor.status()? {
+        Some(status) if guard.is_low(status) => {
+            warn(app, status, next, true)?;
+            Ok(false)
+        }
+        _ => Ok(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::timer_state::{TimerConfig, TimerManager};
+
+    fn guard(mode: BatteryGuardMode) -> BatteryGuard {
+        BatteryGuard {
+            mode,
+            ..BatteryGuard::default()
+        }
+    }
+
+    fn status(battery_percent: u8, on_battery: bool) -> PowerStatus {
+        PowerStatus {
+            battery_percent,
+            on_battery,
+        }
+    }
+
+    #[test]
+    fn battery_is_low_below_the_threshold_while_unplugged() {
+        let warn = guard(BatteryGuardMode::Warn);
+        assert!(warn.is_low(status(19, true)));
+        assert!(!warn.is_low(status(20, true)));
+        assert!(!warn.is_low(status(5, false)));
+        assert!(!guard(BatteryGuardMode::Off).is_low(status(5, true)));
+    }
+
+    #[test]
+    fn long_sessions_are_focus_sessions_of_the_minimum_length_or_stopwatches() {
+        let timer = TimerManager::new(TimerConfig {
+            work_duration: 45 * 60,
+            short_break_duration: 60 * 60,
+            ..TimerConfig::default()
+        });
+        let warn = guard(BatteryGuardMode::Warn);
+        assert!(warn.is_long(&timer.get_data().unwrap()));
+        let short = BatteryGuard {
+            long_session_minutes: 46,
+            ..warn.clone()
+        };
+        assert!(!short.is_long(&timer.get_data().unwrap()));
+        assert!(short.is_long(&timer.start_stopwatch(None).unwrap()));
+
+        timer.stop_stopwatch(false, None).unwrap();
+        // A break never is, however long.
+        assert!(!warn.is_long(&timer.skip(None).unwrap()));
+    }
+
+    #[test]
+    fn only_a_changed_reading_is_reported_and_polls_are_spaced_out() {
+        let monitor = BatteryMonitor::new(&UserPreferences::default());
+        assert_eq!(monitor.update(Some(status(80, true))).unwrap(), None);
+        assert_eq!(monitor.update(Some(status(80, true))).unwrap(), None);
+        assert_eq!(
+            monitor.update(None).unwrap(),
+            Some(None),
+            "losing the battery is a change"
+        );
+        assert_eq!(monitor.status().unwrap(), None);
+
+        assert!(monitor.start_poll().unwrap());
+        assert!(!monitor.start_poll().unwrap());
+    }
+}
@@ -0,0 +1,149 @@
+//! Long natural gaps between sessions, such as a lunch nobody started a
+//! break for, counted as implicit long breaks so break statistics reflect
+//! the day as it went.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::storage::TimerStatistic;
+use super::timeline::local_unix;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct BreakDetection {
+    pub enabled: bool,
+    /// Shortest gap between sessions that counts as a break.
+    pub min_gap_minutes: u32,
+    /// Minutes after local midnight. A gap must overlap this window; the
+    /// same start and end cover the whole day.
+    pub window_start_minute: u32,
+    pub window_end_minute: u32,
+}
+
+impl Default for BreakDetection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_gap_minutes: 45,
+            window_start_minute: 11 * 60,
+            window_end_minute: 15 * 60,
+        }
+    }
+}
+
+impl BreakDetection {
+    /// Whether the idle gap from `start` to `end` (Unix seconds) on `date`
+    /// counts as a break.
+    pub fn is_break(&self, date: NaiveDate, start: u64, end: u64) -> bool {
+        if !self.enabled || end < start + u64::from(self.min_gap_minutes) * 60 {
+            return false;
+        }
+        if self.window_start_minute == self.window_end_minute {
+            return true;
+        }
+        match (
+            local_unix(date, self.window_start_minute),
+            local_unix(date, self.window_end_minute),
+        ) {
+            (Some(window_start), Some(window_end)) => start < window_end && end > window_start,
+            _ => false,
+        }
+    }
+}
+
+/// A detected break, in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImplicitBreak {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The gaps between the day's sessions that count as breaks.
+pub fn detect(statistic: &TimerStatistic, config: &BreakDetection) -> Vec<ImplicitBreak> {
+    let Ok(date) = NaiveDate::parse_from_str(&statistic.date, "%Y-%m-%d") else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<_> = statistic.sessions.iter().collect();
+    sessions.sort_by_key(|session| session.start_time);
+
+    let mut breaks = Vec::new();
+    let mut previous_end: Option<u64> = None;
+    for session in sessions {
+        let start = session.start_time;
+        if let Some(previous_end) = previous_end {
+            if config.is_break(date, previous_end, start) {
+                breaks.push(ImplicitBreak {
+                    start: previous_end,
+                    end: start,
+                });
+            }
+        }
+        let end = session.span_end();
+        previous_end = Some(previous_end.map_or(end, |previous| previous.max(end)));
+    }
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, session};
+    use crate::services::timer_state::SessionType;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()
+    }
+
+    /// Unix seconds at local `hour:minute` on [`date`].
+    fn at(hour: u32, minute: u32) -> u64 {
+        local_unix(date(), hour * 60 + minute).unwrap()
+    }
+
+    fn enabled() -> BreakDetection {
+        BreakDetection {
+            enabled: true,
+            ..BreakDetection::default()
+        }
+    }
+
+    #[test]
+    fn a_break_is_long_enough_and_overlaps_the_window() {
+        let config = enabled();
+        assert!(config.is_break(date(), at(12, 0), at(13, 0)));
+        // Touching the window from outside isn't overlapping it.
+        assert!(!config.is_break(date(), at(9, 0), at(11, 0)));
+        assert!(config.is_break(date(), at(10, 30), at(11, 30)));
+        assert!(!config.is_break(date(), at(12, 0), at(12, 30)));
+        assert!(!BreakDetection::default().is_break(date(), at(12, 0), at(13, 0)));
+
+        let all_day = BreakDetection {
+            window_start_minute: 0,
+            window_end_minute: 0,
+            ..enabled()
+        };
+        assert!(all_day.is_break(date(), at(8, 0), at(9, 0)));
+    }
+
+    #[test]
+    fn gaps_are_measured_from_the_latest_end_so_far() {
+        let work = |start_time, minutes| session(SessionType::Work, start_time, minutes * 60);
+        // Out of order, and the long one covers the short one.
+        let statistic = day(
+            "2026-03-02",
+            vec![
+                work(at(13, 30), 25),
+                work(at(10, 0), 120),
+                work(at(10, 30), 25),
+            ],
+        );
+        assert_eq!(
+            detect(&statistic, &enabled()),
+            [ImplicitBreak {
+                start: at(12, 0),
+                end: at(13, 30),
+            }]
+        );
+    }
+}
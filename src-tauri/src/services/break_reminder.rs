@@ -0,0 +1,307 @@
+//! Reminds again, every few minutes, about a break that came due but was
+//! never started, escalating as the preferences say. `snooze_break` puts
+//! the next reminder off.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::audio::{AlarmStageKind, AudioService, PLAY_EVENT};
+use super::notifications;
+use super::presenter_mode::PresenterMonitor;
+use super::session_types::counts_as_pomodoro;
+use super::storage::UserPreferences;
+use super::timer_state::{SessionType, TimerData, TimerState};
+
+/// Emitted with a [`BreakReminder`] each time a waiting break is
+/// reminded of.
+pub const BREAK_REMINDER_EVENT: &str = "timer://break_reminder";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakEscalation {
+    /// Every reminder is a notification.
+    #[default]
+    Notify,
+    /// Later reminders also play the break's alarm sound.
+    Sound,
+    /// Later reminders also bring the window to the front.
+    Focus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct BreakReminders {
+    pub enabled: bool,
+    /// Minutes between reminders while the break waits.
+    pub interval_minutes: u32,
+    /// Reminders sent before later ones escalate.
+    pub escalate_after: u32,
+    pub escalation: BreakEscalation,
+    /// Reminders per break at most.
+    pub max_reminders: u32,
+}
+
+impl Default for BreakReminders {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 5,
+            escalate_after: 2,
+            escalation: BreakEscalation::default(),
+            max_reminders: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BreakReminder {
+    pub session_type: SessionType,
+    /// 1 for the first reminder about this break.
+    pub count: u32,
+    /// Whether this reminder escalated past a notification.
+    pub escalated: bool,
+}
+
+/// Whether `data` is a break that came due and waits to be started: a
+/// short or long break, or a custom type that isn't focus time.
+pub fn break_waiting(data: &TimerData) -> bool {
+    data.state == TimerState::Idle
+        && !counts_as_pomodoro(&data.session_type, &data.config.custom_session_types)
+}
+
+/// Reminders about the break waiting at timer revision `revision`.
+struct Pending {
+    revision: u64,
+    due_at: Instant,
+    sent: u32,
+    /// Snoozed since the last reminder, which is then sent even past
+    /// `max_reminders`.
+    snoozed: bool,
+}
+
+struct ReminderState {
+    settings: BreakReminders,
+    pending: Option<Pending>,
+}
+
+impl ReminderState {
+    fn interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.settings.interval_minutes) * 60)
+    }
+
+    /// Reminders about the break in `data`, started afresh for a new one.
+    fn pending_for(&mut self, data: &TimerData) -> &mut Pending {
+        if !self
+            .pending
+            .as_ref()
+            .is_some_and(|pending| pending.revision == data.revision)
+        {
+            self.pending = None;
+        }
+        let due_at = Instant::now() + self.interval();
+        self.pending.get_or_insert(Pending {
+            revision: data.revision,
+            due_at,
+            sent: 0,
+            snoozed: false,
+        })
+    }
+}
+
+/// Follows timer ticks and reminds about breaks left waiting.
+pub struct BreakReminderService {
+    state: Mutex<ReminderState>,
+}
+
+impl BreakReminderService {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(ReminderState {
+                settings: preferences.break_reminders.clone(),
+                pending: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, ReminderState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Break reminder lock poisoned: {e}"))
+    }
+
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        self.lock()?.settings = preferences.break_reminders.clone();
+        Ok(())
+    }
+
+    /// Puts the next reminder about the break waiting in `data` off by
+    /// `minutes`. Returns the Unix time it is due.
+    pub fn snooze(&self, data: &TimerData, minutes: u32) -> Result<u64, String> {
+        let delay = Duration::from_secs(u64::from(minutes) * 60);
+        let mut state = self.lock()?;
+        let pending = state.pending_for(data);
+        pending.due_at = Instant::now() + delay;
+        pending.snoozed = true;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("System clock is before 1970: {e}"))?;
+        Ok((now + delay).as_secs())
+    }
+
+    /// The reminder due for `data` now, if any.
+    fn due(&self, data: &TimerData) -> Result<Option<(BreakReminder, BreakEscalation)>, String> {
+        let mut state = self.lock()?;
+        if !state.settings.enabled || !break_waiting(data) {
+            state.pending = None;
+            return Ok(None);
+        }
+        let settings = state.settings.clone();
+        let interval = state.interval();
+        let pending = state.pending_for(data);
+        if Instant::now() < pending.due_at
+            || (pending.sent >= settings.max_reminders && !pending.snoozed)
+        {
+            return Ok(None);
+        }
+        pending.sent += 1;
+        pending.snoozed = false;
+        pending.due_at = Instant::now() + interval;
+        let reminder = BreakReminder {
+            session_type: data.session_type.clone(),
+            count: pending.sent,
+            escalated: settings.escalation != BreakEscalation::Notify
+                && pending.sent > settings.escalate_after,
+        };
+        Ok(Some((reminder, settings.escalation)))
+    }
+}
+
+/// Called by the tick loop: reminds about a waiting break once the
+/// interval since the last reminder or snooze has passed.
+pub fn check(app: &AppHandle, data: &TimerData) -> Result<(), String> {
+    let Some((reminder, escalation)) = app.state::<BreakReminderService>().due(data)? else {
+        return Ok(());
+    };
+    if let Err(error) = notifications::notify_break_reminder(app, &reminder, data) {
+        log::error!("{error}");
+    }
+    if reminder.escalated {
+        match escalation {
+            BreakEscalation::Notify => {}
+            BreakEscalation::Sound => {
+                let presenting = app.state::<PresenterMonitor>().status()?.sounds_suppressed;
+                let cue = app
+                    .state::<AudioService>()
+                    .cue(AlarmStageKind::Alarm, reminder.session_type.clone())?;
+                if let Some(cue) = cue.filter(|_| !presenting) {
+                    app.emit(PLAY_EVENT, cue)
+                        .map_err(|e| format!("Failed to emit {PLAY_EVENT}: {e}"))?;
+                }
+            }
+            BreakEscalation::Focus => {
+                if let Some(window) = app.get_webview_window("main") {
+                    window
+                        .show()
+                        .and_then(|()| window.set_focus())
+                        .map_err(|e| format!("Failed to bring the window forward: {e}"))?;
+                }
+            }
+        }
+    }
+    app.emit(BREAK_REMINDER_EVENT, reminder)
+        .map_err(|e| format!("Failed to emit {BREAK_REMINDER_EVENT}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::timer_state::{TimerConfig, TimerManager};
+
+    fn service(settings: BreakReminders) -> BreakReminderService {
+        BreakReminderService::new(&UserPreferences {
+            break_reminders: settings,
+            ..UserPreferences::default()
+        })
+    }
+
+    fn waiting_break() -> TimerData {
+        let mut data = TimerManager::new(TimerConfig::default())
+            .get_data()
+            .unwrap();
+        data.session_type = SessionType::ShortBreak;
+        data
+    }
+
+    /// Reminders due right away, escalating to sound after two.
+    fn immediate() -> BreakReminders {
+        BreakReminders {
+            enabled: true,
+            interval_minutes: 0,
+            escalation: BreakEscalation::Sound,
+            max_reminders: 3,
+            ..BreakReminders::default()
+        }
+    }
+
+    #[test]
+    fn reminders_are_off_by_default() {
+        let reminders = service(BreakReminders {
+            interval_minutes: 0,
+            ..BreakReminders::default()
+        });
+        assert!(reminders.due(&waiting_break()).unwrap().is_none());
+    }
+
+    #[test]
+    fn reminders_escalate_after_the_threshold_and_stop_at_the_maximum() {
+        let reminders = service(immediate());
+        let data = waiting_break();
+        let sent: Vec<_> = std::iter::from_fn(|| reminders.due(&data).unwrap())
+            .map(|(reminder, _)| (reminder.count, reminder.escalated))
+            .collect();
+        assert_eq!(sent, [(1, false), (2, false), (3, true)]);
+
+        reminders.snooze(&data, 0).unwrap();
+        let (reminder, escalation) = reminders.due(&data).unwrap().unwrap();
+        assert_eq!((reminder.count, escalation), (4, BreakEscalation::Sound));
+        assert!(reminders.due(&data).unwrap().is_none());
+    }
+
+    #[test]
+    fn notify_only_reminders_never_escalate() {
+        let reminders = service(BreakReminders {
+            escalation: BreakEscalation::Notify,
+            ..immediate()
+        });
+        let data = waiting_break();
+        assert!(std::iter::from_fn(|| reminders.due(&data).unwrap())
+            .all(|(reminder, _)| !reminder.escalated));
+    }
+
+    #[test]
+    fn a_new_break_starts_the_count_over() {
+        let reminders = service(immediate());
+        let mut data = waiting_break();
+        while reminders.due(&data).unwrap().is_some() {}
+        data.revision += 1;
+        let (reminder, _) = reminders.due(&data).unwrap().unwrap();
+        assert_eq!(reminder.count, 1);
+    }
+
+    #[test]
+    fn custom_types_wait_as_breaks_unless_they_count_as_focus() {
+        let mut data = waiting_break();
+        data.session_type = SessionType::Custom("exercise".to_string());
+        assert!(break_waiting(&data));
+        data.session_type = SessionType::Custom("reading".to_string());
+        assert!(!break_waiting(&data));
+        data.session_type = SessionType::Work;
+        assert!(!break_waiting(&data));
+    }
+}
@@ -0,0 +1,270 @@
+//! A burnout-risk score for the last finished week, built from four
+//! signals measured against the three weeks before: the completion rate
+//! falling, more focus sessions abandoned, shorter breaks and more
+//! late-night focus. It changes once a week, and a week that looks worse
+//! than the one before raises a gentle advisory in the inbox.
+
+use chrono::{Datelike, NaiveDate, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::calendar::day_key;
+use super::event_log::TimerEvent;
+use super::inbox::{self, InboxKind};
+use super::storage::{StorageService, TimerStatistic, UserPreferences};
+
+mod signals;
+
+use signals::{average, score, signals};
+
+/// How often the scheduler looks for a newly finished week.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Weeks averaged into the baseline a week is compared with.
+const BASELINE_WEEKS: i64 = 3;
+const MODERATE_FROM_SCORE: u32 = 30;
+const HIGH_FROM_SCORE: u32 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum BurnoutLevel {
+    Low,
+    Moderate,
+    High,
+}
+
+/// One week's figures, or the weekly average over several.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct BurnoutSignals {
+    /// Share of focus sessions finished rather than reset or skipped, from
+    /// 0 to 1. `None` without any focus sessions.
+    pub completion_rate: Option<f64>,
+    /// Focus sessions started and then reset or skipped.
+    pub aborted_sessions: f64,
+    /// `None` without any breaks.
+    pub average_break_secs: Option<f64>,
+    /// Focus sessions running between 10pm and 5am.
+    pub late_night_sessions: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BurnoutRisk {
+    /// Monday of the week assessed, the last one finished.
+    pub week_start: String,
+    /// From 0 to 100; higher is worse.
+    pub score: u32,
+    pub level: BurnoutLevel,
+    /// The score of the week before, for the trend.
+    pub previous_score: u32,
+    pub week: BurnoutSignals,
+    /// Weekly averages over the three weeks before.
+    pub baseline: BurnoutSignals,
+    /// The signals that got worse, most pressing first.
+    pub warnings: Vec<String>,
+}
+
+impl BurnoutRisk {
+    /// High, or moderate and worse than the week before.
+    fn trending_badly(&self) -> bool {
+        self.level == BurnoutLevel::High
+            || (self.level == BurnoutLevel::Moderate && self.score > self.previous_score)
+    }
+}
+
+/// Advisory bookkeeping, stored next to the preferences.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BurnoutState {
+    /// Monday of the latest week checked for an advisory.
+    pub last_assessed_week: Option<String>,
+}
+/// Assesses the week beginning on Monday `week_start`. `statistics` should
+/// cover the four weeks before it as well, and the days just around them.
+fn assess(
+    statistics: &[TimerStatistic],
+    events: &[TimerEvent],
+    week_start: NaiveDate,
+    preferences: &UserPreferences,
+) -> BurnoutRisk {
+    // Oldest first, so the week assessed is last and the one before it,
+    // scored for the trend, has a full baseline too.
+    let weeks: Vec<BurnoutSignals> = (0..=BASELINE_WEEKS + 1)
+        .rev()
+        .map(|back| {
+            signals(
+                statistics,
+                events,
+                week_start - TimeDelta::weeks(back),
+                preferences,
+            )
+        })
+        .collect();
+    let count = weeks.len();
+    let (week, previous) = (&weeks[count - 1], &weeks[count - 2]);
+    let baseline = average(&weeks[1..count - 1]);
+    let previous_score = score(previous, &average(&weeks[..count - 2])).0;
+    let (score, warnings) = score(week, &baseline);
+    let level = if score >= HIGH_FROM_SCORE {
+        BurnoutLevel::High
+    } else if score >= MODERATE_FROM_SCORE {
+        BurnoutLevel::Moderate
+    } else {
+        BurnoutLevel::Low
+    };
+    BurnoutRisk {
+        week_start: day_key(week_start),
+        score,
+        level,
+        previous_score,
+        week: week.clone(),
+        baseline,
+        warnings,
+    }
+}
+
+/// The assessment of the last finished week.
+pub fn latest(
+    storage: &StorageService,
+    preferences: &UserPreferences,
+) -> Result<BurnoutRisk, String> {
+    let today = preferences.calendar.today();
+    let this_week = today - TimeDelta::days(today.weekday().num_days_from_monday().into());
+    let week_start = this_week - TimeDelta::weeks(1);
+    let first = week_start - TimeDelta::weeks(BASELINE_WEEKS + 1);
+    let statistics = storage.load_statistics(first.pred_opt(), this_week.succ_opt())?;
+    Ok(assess(
+        &statistics,
+        &storage.load_events(first.pred_opt(), this_week.succ_opt())?,
+        week_start,
+        preferences,
+    ))
+}
+
+/// Assesses a newly finished week once, raising an advisory if it is
+/// trending badly.
+fn check(app: &AppHandle, storage: &StorageService) -> Result<(), String> {
+    let preferences = storage.load_preferences()?;
+    let risk = latest(storage, &preferences)?;
+    let mut state = storage.load_burnout_state()?;
+    if state.last_assessed_week.as_deref() == Some(risk.week_start.as_str()) {
+        return Ok(());
+    }
+    if preferences.burnout_advisories && risk.trending_badly() {
+        inbox::record_or_log(
+            app,
+            InboxKind::BurnoutRisk,
+            "Last week looked more draining than usual",
+            Some(format!(
+                "{} A lighter week, with full breaks and earlier evenings, could help.",
+                risk.warnings.join(" ")
+            )),
+        );
+    }
+    state.last_assessed_week = Some(risk.week_start);
+    storage.save_burnout_state(&state)
+}
+
+/// Checks every [`CHECK_INTERVAL`], starting at launch.
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(error) = check(&app, &app.state::<StorageService>()) {
+                log::error!("Burnout risk check failed: {error}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::calendar::CalendarSettings;
+    use crate::services::event_log::TimerEventKind;
+    use crate::services::timer_state::SessionType;
+
+    const HOUR: u64 = 60 * 60;
+
+    fn preferences() -> UserPreferences {
+        UserPreferences {
+            calendar: CalendarSettings {
+                day_start_hour: 0,
+                utc_offset_minutes: Some(0),
+            },
+            ..UserPreferences::default()
+        }
+    }
+
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+    }
+
+    /// Unix seconds `hour` hours into `date`.
+    fn at(date: NaiveDate, hour: u64) -> u64 {
+        preferences().calendar.start_of(date).unwrap() + hour * HOUR
+    }
+
+    fn event(kind: TimerEventKind, session_type: SessionType, timestamp: u64) -> TimerEvent {
+        TimerEvent {
+            kind,
+            session_type,
+            remaining_time: 0,
+            timestamp,
+        }
+    }
+
+    /// `completed` finished focus sessions and `aborted` ones reset after
+    /// starting, on `date`.
+    fn focus_events(date: NaiveDate, completed: u64, aborted: u64) -> Vec<TimerEvent> {
+        let work = || SessionType::Work;
+        (0..completed + aborted)
+            .flat_map(|index| {
+                let start = at(date, 9 + index);
+                let end = if index < completed {
+                    TimerEventKind::Complete
+                } else {
+                    TimerEventKind::Reset
+                };
+                [
+                    event(TimerEventKind::Start, work(), start),
+                    event(end, work(), start + 1500),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn the_last_week_is_scored_against_the_three_before_the_one_before_it() {
+        let week_start = monday();
+        let mut events = Vec::new();
+        for back in 1..=BASELINE_WEEKS + 1 {
+            events.extend(focus_events(week_start - TimeDelta::weeks(back), 4, 0));
+        }
+        events.extend(focus_events(week_start, 2, 2));
+        // The week after isn't assessed yet.
+        events.extend(focus_events(week_start + TimeDelta::weeks(1), 0, 4));
+
+        let risk = assess(&[], &events, week_start, &preferences());
+        assert_eq!(risk.week_start, "2025-03-10");
+        assert_eq!(risk.baseline.completion_rate, Some(1.0));
+        assert_eq!(risk.week.completion_rate, Some(0.5));
+        // 25 (capped) for the completion rate and 10 for two aborts.
+        assert_eq!(risk.score, 35);
+        assert_eq!(risk.level, BurnoutLevel::Moderate);
+        assert_eq!(risk.previous_score, 0);
+        assert!(risk.trending_badly());
+    }
+
+    #[test]
+    fn weeks_without_a_baseline_are_not_scored() {
+        let events = focus_events(monday(), 0, 4);
+        let risk = assess(&[], &events, monday(), &preferences());
+        assert_eq!(risk.week.aborted_sessions, 4.0);
+        assert_eq!((risk.score, risk.level), (0, BurnoutLevel::Low));
+    }
+}
@@ -0,0 +1,283 @@
+//! The four weekly signals and how a week's are scored against the
+//! baseline's.
+
+use chrono::{NaiveDate, TimeDelta};
+
+use super::BurnoutSignals;
+use crate::services::calendar::parse_day;
+use crate::services::event_log::{TimerEvent, TimerEventKind};
+use crate::services::session_types::counts_as_pomodoro;
+use crate::services::storage::{TimerStatistic, UserPreferences};
+use crate::services::wellbeing::runs_late_at_night;
+
+/// The most each signal adds to the score.
+const MAX_SIGNAL_POINTS: f64 = 25.0;
+/// Points per focus session abandoned, or run late at night, beyond the
+/// baseline.
+const POINTS_PER_EXTRA_SESSION: f64 = 5.0;
+/// Points for breaks half as long as in the baseline.
+const POINTS_FOR_HALVED_BREAKS: f64 = 25.0;
+
+pub(super) fn signals(
+    statistics: &[TimerStatistic],
+    events: &[TimerEvent],
+    week_start: NaiveDate,
+    preferences: &UserPreferences,
+) -> BurnoutSignals {
+    let calendar = &preferences.calendar;
+    let custom_types = &preferences.timer_config.custom_session_types;
+    let week_end = week_start + TimeDelta::days(6);
+    let in_week = |date: NaiveDate| date >= week_start && date <= week_end;
+
+    // Resetting or skipping a session that never started abandons nothing.
+    let (mut completed, mut aborted, mut started) = (0u32, 0u32, false);
+    for event in events {
+        if !counts_as_pomodoro(&event.session_type, custom_types) {
+            started = false;
+            continue;
+        }
+        let counted = in_week(calendar.day_of(event.timestamp));
+        match event.kind {
+            TimerEventKind::Start => started = true,
+            TimerEventKind::Complete => {
+                completed += u32::from(counted);
+                started = false;
+            }
+            TimerEventKind::Reset | TimerEventKind::Skip => {
+                aborted += u32::from(counted && started);
+                started = false;
+            }
+            TimerEventKind::Pause | TimerEventKind::Resume => {}
+        }
+    }
+
+    let sessions: Vec<_> = statistics
+        .iter()
+        .filter(|statistic| parse_day(&statistic.date).is_some_and(in_week))
+        .flat_map(|statistic| &statistic.sessions)
+        .collect();
+    let (focus, breaks): (Vec<_>, Vec<_>) = sessions
+        .into_iter()
+        .partition(|session| counts_as_pomodoro(&session.session_type, custom_types));
+    let break_secs: u64 = breaks
+        .iter()
+        .map(|session| session.span_end() - session.start_time)
+        .sum();
+
+    BurnoutSignals {
+        completion_rate: (completed + aborted > 0)
+            .then(|| f64::from(completed) / f64::from(completed + aborted)),
+        aborted_sessions: f64::from(aborted),
+        average_break_secs: (!breaks.is_empty()).then(|| break_secs as f64 / breaks.len() as f64),
+        late_night_sessions: focus
+            .iter()
+            .filter(|session| runs_late_at_night(session, calendar))
+            .count() as f64,
+    }
+}
+
+pub(super) fn average(weeks: &[BurnoutSignals]) -> BurnoutSignals {
+    let mean = |values: Vec<f64>| {
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    };
+    BurnoutSignals {
+        completion_rate: mean(
+            weeks
+                .iter()
+                .filter_map(|week| week.completion_rate)
+                .collect(),
+        ),
+        aborted_sessions: mean(weeks.iter().map(|week| week.aborted_sessions).collect())
+            .unwrap_or_default(),
+        average_break_secs: mean(
+            weeks
+                .iter()
+                .filter_map(|week| week.average_break_secs)
+                .collect(),
+        ),
+        late_night_sessions: mean(weeks.iter().map(|week| week.late_night_sessions).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// The score of `week` against `baseline` and a line for each signal that
+/// got worse, most pressing first. Nothing is scored without focus
+/// sessions in the baseline to compare with.
+pub(super) fn score(week: &BurnoutSignals, baseline: &BurnoutSignals) -> (u32, Vec<String>) {
+    if baseline.completion_rate.is_none() {
+        return (0, Vec::new());
+    }
+    let mut warnings: Vec<(f64, String)> = Vec::new();
+    if let (Some(rate), Some(usual)) = (week.completion_rate, baseline.completion_rate) {
+        if rate < usual {
+            // A point per percentage point lost.
+            warnings.push((
+                (usual - rate) * 100.0,
+                format!(
+                    "You finished {:.0}% of your focus sessions, down from {:.0}%.",
+                    rate * 100.0,
+                    usual * 100.0
+                ),
+            ));
+        }
+    }
+    if week.aborted_sessions > baseline.aborted_sessions {
+        warnings.push((
+            (week.aborted_sessions - baseline.aborted_sessions) * POINTS_PER_EXTRA_SESSION,
+            format!(
+                "You abandoned {} focus sessions, against {:.1} in a usual week.",
+                week.aborted_sessions, baseline.aborted_sessions
+            ),
+        ));
+    }
+    if let (Some(length), Some(usual)) = (week.average_break_secs, baseline.average_break_secs) {
+        if length < usual {
+            warnings.push((
+                (1.0 - length / usual) * 2.0 * POINTS_FOR_HALVED_BREAKS,
+                format!(
+                    "Your breaks averaged {:.0} minutes, down from {:.0}.",
+                    length / 60.0,
+                    usual / 60.0
+                ),
+            ));
+        }
+    }
+    if week.late_night_sessions > baseline.late_night_sessions {
+        warnings.push((
+            (week.late_night_sessions - baseline.late_night_sessions) * POINTS_PER_EXTRA_SESSION,
+            format!(
+                "{} focus sessions ran late at night, against {:.1} in a usual week.",
+                week.late_night_sessions, baseline.late_night_sessions
+            ),
+        ));
+    }
+    for (points, _) in &mut warnings {
+        *points = points.min(MAX_SIGNAL_POINTS);
+    }
+    warnings.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let score = warnings
+        .iter()
+        .map(|(points, _)| points)
+        .sum::<f64>()
+        .round() as u32;
+    (score, warnings.into_iter().map(|(_, line)| line).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::calendar::{day_key, CalendarSettings};
+    use crate::services::storage::{day, session};
+    use crate::services::timer_state::SessionType;
+
+    const HOUR: u64 = 60 * 60;
+
+    fn preferences() -> UserPreferences {
+        UserPreferences {
+            calendar: CalendarSettings {
+                day_start_hour: 0,
+                utc_offset_minutes: Some(0),
+            },
+            ..UserPreferences::default()
+        }
+    }
+
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+    }
+
+    /// Unix seconds `hour` hours into `date`.
+    fn at(date: NaiveDate, hour: u64) -> u64 {
+        preferences().calendar.start_of(date).unwrap() + hour * HOUR
+    }
+
+    fn event(kind: TimerEventKind, session_type: SessionType, timestamp: u64) -> TimerEvent {
+        TimerEvent {
+            kind,
+            session_type,
+            remaining_time: 0,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn only_started_focus_sessions_count_as_aborted() {
+        let tuesday = monday() + TimeDelta::days(1);
+        let work = || SessionType::Work;
+        let events = vec![
+            // From the week before: neither counts.
+            event(TimerEventKind::Start, work(), at(monday(), 0) - HOUR),
+            event(TimerEventKind::Reset, work(), at(monday(), 0) - 1),
+            event(TimerEventKind::Start, work(), at(tuesday, 9)),
+            event(TimerEventKind::Pause, work(), at(tuesday, 9) + 60),
+            event(TimerEventKind::Resume, work(), at(tuesday, 9) + 120),
+            event(TimerEventKind::Complete, work(), at(tuesday, 10)),
+            // Never started, so nothing was abandoned.
+            event(TimerEventKind::Reset, work(), at(tuesday, 11)),
+            event(TimerEventKind::Start, work(), at(tuesday, 12)),
+            event(TimerEventKind::Skip, work(), at(tuesday, 12) + 60),
+            // A break in between forgets the start before it.
+            event(TimerEventKind::Start, work(), at(tuesday, 13)),
+            event(
+                TimerEventKind::Skip,
+                SessionType::ShortBreak,
+                at(tuesday, 14),
+            ),
+            event(TimerEventKind::Reset, work(), at(tuesday, 15)),
+        ];
+        let week = signals(&[], &events, monday(), &preferences());
+        assert_eq!(week.aborted_sessions, 1.0);
+        assert_eq!(week.completion_rate, Some(0.5));
+        assert_eq!(week.average_break_secs, None);
+    }
+
+    #[test]
+    fn breaks_and_late_nights_come_from_the_week_s_sessions() {
+        let tuesday = monday() + TimeDelta::days(1);
+        let statistics = vec![
+            day(
+                &day_key(tuesday),
+                vec![
+                    session(SessionType::Work, at(tuesday, 9), 1500),
+                    session(SessionType::ShortBreak, at(tuesday, 10), 300),
+                    session(SessionType::LongBreak, at(tuesday, 11), 900),
+                    session(SessionType::Work, at(tuesday, 23), 1500),
+                ],
+            ),
+            // The Sunday before belongs to another week.
+            day(
+                &day_key(monday().pred_opt().unwrap()),
+                vec![session(SessionType::ShortBreak, at(monday(), 0) - HOUR, 60)],
+            ),
+        ];
+        let week = signals(&statistics, &[], monday(), &preferences());
+        assert_eq!(week.average_break_secs, Some(600.0));
+        assert_eq!(week.late_night_sessions, 1.0);
+        assert_eq!(week.completion_rate, None);
+    }
+
+    #[test]
+    fn worse_week_scores_each_signal_up_to_its_cap() {
+        let baseline = BurnoutSignals {
+            completion_rate: Some(0.9),
+            aborted_sessions: 1.0,
+            average_break_secs: Some(600.0),
+            late_night_sessions: 0.0,
+        };
+        assert_eq!(score(&baseline, &baseline), (0, Vec::new()));
+
+        let week = BurnoutSignals {
+            completion_rate: Some(0.7),
+            aborted_sessions: 4.0,
+            average_break_secs: Some(300.0),
+            late_night_sessions: 10.0,
+        };
+        let (points, warnings) = score(&week, &baseline);
+        // 20 for the completion rate, 15 for aborts, 25 each for halved
+        // breaks and (capped) late nights.
+        assert_eq!(points, 85);
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings[3].starts_with("You abandoned"));
+        assert_eq!(score(&week, &BurnoutSignals::default()), (0, Vec::new()));
+    }
+}
@@ -0,0 +1,217 @@
+//! Which statistics day a moment belongs to. A day runs from the boundary
+//! hour to the same hour the next morning in the configured time zone, so
+//! with a 4am boundary a session finishing at 1am still counts towards the
+//! evening it belongs to. Days are keyed `YYYY-MM-DD`.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use super::session_types::CustomSessionType;
+use super::storage::{StorageService, TimerStatistic};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct CalendarSettings {
+    /// Hour, 0 to 23, at which one day ends and the next begins.
+    pub day_start_hour: u32,
+    /// Minutes east of UTC to keep days in, or `None` for the system's time
+    /// zone, daylight saving included.
+    pub utc_offset_minutes: Option<i32>,
+}
+
+/// The `YYYY-MM-DD` key statistics are stored under.
+pub fn day_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+pub fn parse_day(key: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(key, "%Y-%m-%d").ok()
+}
+
+/// Whether the day keyed `key` falls within the optional inclusive bounds.
+/// A key that isn't a date only passes without bounds.
+pub fn in_range(key: &str, start: Option<NaiveDate>, end: Option<NaiveDate>) -> bool {
+    match parse_day(key) {
+        Some(date) => start.is_none_or(|start| date >= start) && end.is_none_or(|end| date <= end),
+        None => start.is_none() && end.is_none(),
+    }
+}
+
+/// Today's key under the stored calendar settings.
+pub fn today(storage: &StorageService) -> Result<String, String> {
+    Ok(day_key(storage.load_preferences()?.calendar.today()))
+}
+
+impl CalendarSettings {
+    fn fixed_offset(&self) -> Option<FixedOffset> {
+        FixedOffset::east_opt(self.utc_offset_minutes? * 60)
+    }
+
+    fn wall_time(&self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self.fixed_offset() {
+            Some(offset) => at.with_timezone(&offset).naive_local(),
+            None => at.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// The wall-clock time `unix` seconds fall on in the configured zone.
+    pub fn wall_time_of(&self, unix: u64) -> NaiveDateTime {
+        self.wall_time(
+            DateTime::from_timestamp(i64::try_from(unix).unwrap_or(i64::MAX), 0)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// The day `unix` seconds fall on.
+    pub fn day_of(&self, unix: u64) -> NaiveDate {
+        (self.wall_time_of(unix) - TimeDelta::hours(self.day_start_hour.into())).date()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        (self.wall_time(Utc::now()) - TimeDelta::hours(self.day_start_hour.into())).date()
+    }
+
+    /// Unix seconds of the wall-clock `time` in the configured zone. A time
+    /// repeated when the clocks go back is its first occurrence; one skipped
+    /// when they go forward is where the clocks land an hour later.
+    pub fn unix_of(&self, time: NaiveDateTime) -> Option<u64> {
+        let timestamp = match self.fixed_offset() {
+            Some(offset) => offset.from_local_datetime(&time).single()?.timestamp(),
+            None => Local
+                .from_local_datetime(&time)
+                .earliest()
+                .or_else(|| {
+                    Local
+                        .from_local_datetime(&(time + TimeDelta::hours(1)))
+                        .latest()
+                })?
+                .timestamp(),
+        };
+        u64::try_from(timestamp).ok()
+    }
+
+    /// Unix seconds at which `date` begins.
+    pub fn start_of(&self, date: NaiveDate) -> Option<u64> {
+        let start = date.and_hms_opt(self.day_start_hour, 0, 0)?;
+        let timestamp = match self.fixed_offset() {
+            Some(offset) => offset.from_local_datetime(&start).single()?.timestamp(),
+            None => Local.from_local_datetime(&start).earliest()?.timestamp(),
+        };
+        u64::try_from(timestamp).ok()
+    }
+
+    /// Splits `start..end` at day boundaries into each day's seconds.
+    pub fn split(&self, start: u64, end: u64) -> Vec<(NaiveDate, u64)> {
+        let mut spans = Vec::new();
+        let mut from = start;
+        while from < end {
+            let day = self.day_of(from);
+            let until = day
+                .succ_opt()
+                .and_then(|next| self.start_of(next))
+                .map_or(end, |next| next.clamp(from + 1, end));
+            spans.push((day, until - from));
+            from = until;
+        }
+        spans
+    }
+
+    /// Focused seconds per day over `statistics`, each session's focus
+    /// spread over the days its span covers in proportion. Focus a day
+    /// records beyond its sessions, e.g. imported without them, stays put.
+    pub fn focus_by_day(
+        &self,
+        statistics: &[TimerStatistic],
+        custom_types: &[CustomSessionType],
+    ) -> BTreeMap<NaiveDate, u64> {
+        let mut focus = BTreeMap::new();
+        for statistic in statistics {
+            let Some(date) = parse_day(&statistic.date) else {
+                continue;
+            };
+            let mut attributed = 0;
+            for session in &statistic.sessions {
+                let seconds = session.contribution(custom_types).1;
+                if seconds == 0 {
+                    continue;
+                }
+                attributed += seconds;
+                let spans = self.split(session.start_time, session.span_end());
+                let total: u64 = spans.iter().map(|(_, span)| span).sum();
+                if total == 0 {
+                    *focus.entry(date).or_default() += seconds;
+                    continue;
+                }
+                let mut left = seconds;
+                for (index, (day, span)) in spans.iter().enumerate() {
+                    let share = if index + 1 == spans.len() {
+                        left
+                    } else {
+                        seconds * span / total
+                    };
+                    left -= share;
+                    *focus.entry(*day).or_default() += share;
+                }
+            }
+            *focus.entry(date).or_default() +=
+                statistic.total_focus_time.saturating_sub(attributed);
+        }
+        focus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UTC+2 with days starting at 4am.
+    const CALENDAR: CalendarSettings = CalendarSettings {
+        day_start_hour: 4,
+        utc_offset_minutes: Some(120),
+    };
+
+    fn unix(date: &str, hour: u32, minute: u32) -> u64 {
+        let at = parse_day(date)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap();
+        // Wall time in UTC+2.
+        (at.and_utc().timestamp() - 2 * 60 * 60) as u64
+    }
+
+    #[test]
+    fn late_night_counts_towards_the_evening_before() {
+        let day = |hour| day_key(CALENDAR.day_of(unix("2025-03-10", hour, 30)));
+        assert_eq!(day(1), "2025-03-09");
+        assert_eq!(day(3), "2025-03-09");
+        assert_eq!(day(4), "2025-03-10");
+        assert_eq!(day(23), "2025-03-10");
+        assert_eq!(
+            CALENDAR.start_of(parse_day("2025-03-10").unwrap()),
+            Some(unix("2025-03-10", 4, 0))
+        );
+    }
+
+    #[test]
+    fn sessions_across_the_boundary_are_split() {
+        let spans = CALENDAR.split(unix("2025-03-10", 3, 45), unix("2025-03-10", 4, 10));
+        assert_eq!(
+            spans,
+            vec![
+                (parse_day("2025-03-09").unwrap(), 15 * 60),
+                (parse_day("2025-03-10").unwrap(), 10 * 60),
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_that_are_not_dates_only_pass_unbounded() {
+        let day = parse_day("2025-03-10");
+        assert!(in_range("2025-03-10", day, day));
+        assert!(!in_range("2025-3-10", day, None));
+        assert!(in_range("2025-3-10", None, None));
+    }
+}
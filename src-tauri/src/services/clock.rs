@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+fn unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Monotonic time source for the timer engine.
+///
+/// Readings are offsets from when the clock was created. In simulation mode
+/// the clock advances `speed` times faster than real time, so a 25 minute
+/// session elapses in 25 seconds at 60×.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineClock {
+    anchor: Instant,
+    anchor_reading: Duration,
+    speed: f64,
+    /// Unix time, in seconds, when the clock was created. Only used to
+    /// derive simulated timestamps.
+    wall_anchor: u64,
+}
+
+impl Default for EngineClock {
+    fn default() -> Self {
+        Self {
+            anchor: Instant::now(),
+            anchor_reading: Duration::ZERO,
+            speed: 1.0,
+            wall_anchor: unix_seconds(),
+        }
+    }
+}
+
+impl EngineClock {
+    pub fn now(&self) -> Duration {
+        self.anchor_reading + self.anchor.elapsed().mul_f64(self.speed)
+    }
+
+    /// Unix timestamp in seconds for recording session boundaries. In
+    /// simulation mode it advances at the simulated rate so recorded start
+    /// and end times agree with session durations.
+    pub fn unix_now(&self) -> u64 {
+        if cfg!(feature = "simulation") {
+            self.wall_anchor + self.now().as_secs()
+        } else {
+            unix_seconds()
+        }
+    }
+
+    /// A clock that reads `reading`, at Unix time `unix`, until it is
+    /// replaced, for replaying recorded transitions.
+    #[cfg(feature = "simulation")]
+    pub fn frozen_at(reading: Duration, unix: u64) -> Self {
+        Self {
+            anchor: Instant::now(),
+            anchor_reading: reading,
+            speed: 0.0,
+            wall_anchor: unix.saturating_sub(reading.as_secs()),
+        }
+    }
+
+    /// Changes the rate without making past readings jump.
+    #[cfg(feature = "simulation")]
+    pub fn set_speed(&mut self, speed: f64) {
+        self.anchor_reading = self.now();
+        self.anchor = Instant::now();
+        self.speed = speed;
+    }
+}
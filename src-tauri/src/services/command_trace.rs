@@ -0,0 +1,141 @@
+//! Development-build tracing of command invocations, so frontend
+//! developers can see which backend calls their UI makes and how long each
+//! takes. Off until `set_command_tracing` turns it on.
+//!
+//! Commands answered synchronously are timed until their response is
+//! serialized, and the [`CommandResult`](crate::commands::CommandResult)
+//! envelope reports its outcome back through a thread-local while that
+//! happens. Async commands answer from another task, so they are traced
+//! twice: when dispatched, without an outcome, and through [`traced`] once
+//! they answer.
+
+use serde::{Serialize, Serializer};
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tauri::ipc::Invoke;
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+/// Emitted with a [`CommandTrace`] after each command while tracing is on.
+pub const COMMAND_TRACE_EVENT: &str = "dev://command";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct CommandTrace {
+    pub command: String,
+    /// Milliseconds until the response was sent; for an async command,
+    /// until dispatch or, on its completion, until it answered.
+    pub duration_ms: f64,
+    /// `None` at an async command's dispatch, and for synchronous commands
+    /// not answering with a `CommandResult`.
+    pub success: Option<bool>,
+    /// The `CommandResult` failure code, if any.
+    pub code: Option<String>,
+    /// Whether this is an async command's completion, following the trace
+    /// of its dispatch.
+    pub completion: bool,
+}
+
+/// How a command went, as far as its answer tells.
+#[derive(Default)]
+pub struct Outcome {
+    pub success: Option<bool>,
+    pub code: Option<String>,
+}
+
+/// An async command's answer, for [`traced`].
+pub trait Traced {
+    fn outcome(&self) -> Outcome;
+}
+
+thread_local! {
+    /// Set while a traced command runs on this thread.
+    static PENDING: RefCell<Option<Outcome>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+pub struct CommandTracer {
+    enabled: AtomicBool,
+}
+
+impl CommandTracer {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+}
+
+/// `serialize_with` for `CommandResult::success`.
+pub fn note_success<S: Serializer>(success: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    PENDING.with_borrow_mut(|pending| {
+        if let Some(outcome) = pending {
+            outcome.success = Some(*success);
+        }
+    });
+    serializer.serialize_bool(*success)
+}
+
+/// `serialize_with` for `CommandResult::code`.
+pub fn note_code<S: Serializer>(code: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    PENDING.with_borrow_mut(|pending| {
+        if let Some(outcome) = pending {
+            outcome.code.clone_from(code);
+        }
+    });
+    code.serialize(serializer)
+}
+
+/// Wraps the invoke handler so each command is traced while tracing is on.
+pub fn wrap<F>(handler: F) -> impl Fn(Invoke) -> bool + Send + Sync + 'static
+where
+    F: Fn(Invoke) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let app = invoke.message.webview().app_handle().clone();
+        let traced = app
+            .try_state::<CommandTracer>()
+            .is_some_and(|tracer| tracer.enabled());
+        if !traced {
+            return handler(invoke);
+        }
+        let command = invoke.message.command().to_string();
+        let started = Instant::now();
+        PENDING.set(Some(Outcome::default()));
+        let handled = handler(invoke);
+        let outcome = PENDING.take().unwrap_or_default();
+        emit(&app, command, started, outcome, false);
+        handled
+    }
+}
+
+/// Runs the body of the async command `command`, tracing its completion
+/// while tracing is on.
+pub async fn traced<T: Traced>(app: &AppHandle, command: &str, body: impl Future<Output = T>) -> T {
+    let traced = app
+        .try_state::<CommandTracer>()
+        .is_some_and(|tracer| tracer.enabled());
+    let started = Instant::now();
+    let answer = body.await;
+    if traced {
+        emit(app, command.to_string(), started, answer.outcome(), true);
+    }
+    answer
+}
+
+fn emit(app: &AppHandle, command: String, started: Instant, outcome: Outcome, completion: bool) {
+    let trace = CommandTrace {
+        command,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        success: outcome.success,
+        code: outcome.code,
+        completion,
+    };
+    if let Err(error) = app.emit(COMMAND_TRACE_EVENT, trace) {
+        log::error!("Failed to emit {COMMAND_TRACE_EVENT}: {error}");
+    }
+}
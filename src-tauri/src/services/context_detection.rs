@@ -0,0 +1,280 @@
+//! Infers the work context from the Wi-Fi network name and hostname. This
+//! is the only code that reads them, and it doesn't unless
+//! `WorkContextSettings::detect` is on. Sessions only ever store the name
+//! of the context that matched; the raw network name is handed out solely
+//! to the settings UI, on request, for setting contexts up. The tick loop
+//! re-reads them now and then on a blocking task, so recording a session
+//! doesn't wait for the subprocesses.
+
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::focus_mode::run;
+use super::storage::UserPreferences;
+use super::work_context::WorkContextSettings;
+
+/// The context sessions ending now are recorded in.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ActiveContext {
+    pub name: Option<String>,
+    /// Whether `name` was inferred rather than picked by hand.
+    pub detected: bool,
+}
+
+/// What detection sees right now.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct NetworkObservation {
+    pub ssid: Option<String>,
+    pub hostname: Option<String>,
+    /// The context these match, if any.
+    pub context: Option<String>,
+}
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The Wi-Fi network the computer is connected to, where the platform
+/// tells.
+fn wifi_ssid() -> Option<String> {
+    let ssid = if cfg!(target_os = "macos") {
+        run("networksetup", &["-getairportnetwork", "en0"])
+            .ok()?
+            .strip_prefix("Current Wi-Fi Network: ")?
+            .to_string()
+    } else if cfg!(target_os = "linux") {
+        run("nmcli", &["-t", "-f", "active,ssid", "dev", "wifi"])
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("yes:"))?
+            .to_string()
+    } else if cfg!(target_os = "windows") {
+        run("netsh", &["wlan", "show", "interfaces"])
+            .ok()?
+            .lines()
+            .find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == "SSID").then(|| value.trim().to_string())
+            })?
+    } else {
+        return None;
+    };
+    Some(ssid).filter(|ssid| !ssid.is_empty())
+}
+
+fn hostname() -> Option<String> {
+    run("hostname", &[])
+        .ok()
+        .filter(|hostname| !hostname.is_empty())
+}
+
+/// The network name and hostname as last read.
+#[derive(Debug, Clone)]
+struct Network {
+    ssid: Option<String>,
+    hostname: Option<String>,
+    read_at: Instant,
+}
+
+impl Network {
+    fn read() -> Self {
+        Self {
+            ssid: wifi_ssid(),
+            hostname: hostname(),
+            read_at: Instant::now(),
+        }
+    }
+}
+
+pub struct ContextDetector {
+    settings: Mutex<WorkContextSettings>,
+    /// Kept only while detection is on.
+    network: Mutex<Option<Network>>,
+}
+
+impl ContextDetector {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            settings: Mutex::new(preferences.work_context.clone()),
+            network: Mutex::new(None),
+        }
+    }
+
+    fn network(&self) -> Result<MutexGuard<'_, Option<Network>>, String> {
+        self.network
+            .lock()
+            .map_err(|e| format!("Work context lock poisoned: {e}"))
+    }
+
+    /// The network as last read, reading it now if it never was.
+    fn last_network(&self) -> Result<Network, String> {
+        let mut network = self.network()?;
+        Ok(network.get_or_insert_with(Network::read).clone())
+    }
+
+    /// Called by the tick loop: re-reads the network on a blocking task
+    /// once `REFRESH_INTERVAL` has passed, while detection is on.
+    pub fn on_tick(&self, app: &AppHandle) -> Result<(), String> {
+        if !self.lock()?.detect {
+            return Ok(());
+        }
+        let mut network = self.network()?;
+        if network
+            .as_ref()
+            .is_some_and(|network| network.read_at.elapsed() < REFRESH_INTERVAL)
+        {
+            return Ok(());
+        }
+        // Marks the refresh as started; the reading stays until replaced.
+        if let Some(network) = network.as_mut() {
+            network.read_at = Instant::now();
+        }
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let detector = app.state::<ContextDetector>();
+            let read = Network::read();
+            match detector.network() {
+                Ok(mut network) => *network = Some(read),
+                Err(error) => log::error!("{error}"),
+            }
+        });
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, WorkContextSettings>, String> {
+        self.settings
+            .lock()
+            .map_err(|e| format!("Work context lock poisoned: {e}"))
+    }
+
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        *self.lock()? = preferences.work_context.clone();
+        if !preferences.work_context.detect {
+            *self.network()? = None;
+        }
+        Ok(())
+    }
+
+    /// The context picked by hand or, with detection on, the one the
+    /// network or hostname matches as last read.
+    pub fn current(&self) -> Result<ActiveContext, String> {
+        let settings = self.lock()?.clone();
+        if let Some(name) = settings.manual {
+            return Ok(ActiveContext {
+                name: Some(name),
+                detected: false,
+            });
+        }
+        if !settings.detect {
+            return Ok(ActiveContext {
+                name: None,
+                detected: false,
+            });
+        }
+        let network = self.last_network()?;
+        let name = settings
+            .matching(network.ssid.as_deref(), network.hostname.as_deref())
+            .map(|context| context.name.clone());
+        Ok(ActiveContext {
+            detected: name.is_some(),
+            name,
+        })
+    }
+
+    /// The network name and hostname as detection sees them, or `None`
+    /// while detection is off.
+    pub fn observe(&self) -> Result<Option<NetworkObservation>, String> {
+        let settings = self.lock()?.clone();
+        if !settings.detect {
+            return Ok(None);
+        }
+        let network = Network::read();
+        *self.network()? = Some(network.clone());
+        let context = settings
+            .matching(network.ssid.as_deref(), network.hostname.as_deref())
+            .map(|context| context.name.clone());
+        Ok(Some(NetworkObservation {
+            ssid: network.ssid,
+            hostname: network.hostname,
+            context,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::work_context::WorkContext;
+
+    fn context(name: &str, ssid: &str, hostname: &str) -> WorkContext {
+        WorkContext {
+            name: name.to_string(),
+            ssids: vec![ssid.to_string()],
+            hostnames: vec![hostname.to_string()],
+        }
+    }
+
+    /// A detector with detection on and the network already read, so no
+    /// subprocess runs.
+    fn detector(ssid: Option<&str>, hostname: Option<&str>) -> ContextDetector {
+        let mut preferences = UserPreferences::default();
+        preferences.work_context = WorkContextSettings {
+            contexts: vec![
+                context("Office", "Corp", "work-laptop"),
+                context("Home", "Flat", "desktop"),
+            ],
+            manual: None,
+            detect: true,
+        };
+        let detector = ContextDetector::new(&preferences);
+        *detector.network().unwrap() = Some(Network {
+            ssid: ssid.map(str::to_string),
+            hostname: hostname.map(str::to_string),
+            read_at: Instant::now(),
+        });
+        detector
+    }
+
+    fn name(detector: &ContextDetector) -> Option<String> {
+        detector.current().unwrap().name
+    }
+
+    #[test]
+    fn the_network_wins_over_the_hostname() {
+        assert_eq!(
+            name(&detector(Some("Flat"), Some("work-laptop"))),
+            Some("Home".to_string())
+        );
+        assert_eq!(
+            name(&detector(Some("Cafe"), Some("Work-Laptop"))),
+            Some("Office".to_string())
+        );
+        let unknown = detector(Some("Cafe"), None);
+        let current = unknown.current().unwrap();
+        assert_eq!((current.name, current.detected), (None, false));
+    }
+
+    #[test]
+    fn a_manual_pick_wins_and_turning_detection_off_forgets_the_network() {
+        let detector = detector(Some("Corp"), None);
+        let mut preferences = UserPreferences::default();
+        preferences.work_context = detector.lock().unwrap().clone();
+        preferences.work_context.manual = Some("Library".to_string());
+        detector.configure(&preferences).unwrap();
+        let current = detector.current().unwrap();
+        assert_eq!(
+            (current.name, current.detected),
+            (Some("Library".to_string()), false)
+        );
+
+        preferences.work_context.manual = None;
+        preferences.work_context.detect = false;
+        detector.configure(&preferences).unwrap();
+        assert!(detector.network().unwrap().is_none());
+        assert_eq!(name(&detector), None);
+        assert!(detector.observe().unwrap().is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use super::storage::TimerStatistic;
+
+/// Minimum pomodoros for intensity levels 1 through 4; level 0 is no activity.
+pub const DEFAULT_THRESHOLDS: [u32; 4] = [1, 3, 5, 8];
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ContributionDay {
+    pub date: String,
+    pub pomodoros: u32,
+    /// `0..=thresholds.len()`
+    pub level: u8,
+}
+
+/// GitHub-style grid data: one entry per calendar day of `year`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ContributionGraph {
+    pub year: i32,
+    pub thresholds: Vec<u32>,
+    pub max_pomodoros: u32,
+    pub days: Vec<ContributionDay>,
+}
+
+/// The highest level whose threshold `pomodoros` reaches, or 0 if none.
+fn level_for(pomodoros: u32, thresholds: &[u32]) -> u8 {
+    thresholds
+        .iter()
+        .take_while(|&&threshold| pomodoros >= threshold)
+        .count() as u8
+}
+
+/// Builds the graph for `year`. `thresholds` must be ascending.
+pub fn build_contribution_graph(
+    year: i32,
+    statistics: &[TimerStatistic],
+    thresholds: &[u32],
+) -> ContributionGraph {
+    let counts: HashMap<&str, u32> = statistics
+        .iter()
+        .map(|statistic| (statistic.date.as_str(), statistic.completed_pomodoros))
+        .collect();
+
+    let days: Vec<ContributionDay> = NaiveDate::from_ymd_opt(year, 1, 1)
+        .into_iter()
+        .flat_map(|first| first.iter_days())
+        .take_while(|date| date.year() == year)
+        .map(|date| {
+            let date = date.format("%Y-%m-%d").to_string();
+            let pomodoros = counts.get(date.as_str()).copied().unwrap_or(0);
+            ContributionDay {
+                level: level_for(pomodoros, thresholds),
+                date,
+                pomodoros,
+            }
+        })
+        .collect();
+
+    ContributionGraph {
+        year,
+        thresholds: thresholds.to_vec(),
+        max_pomodoros: days.iter().map(|day| day.pomodoros).max().unwrap_or(0),
+        days,
+    }
+}
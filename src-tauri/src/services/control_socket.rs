@@ -0,0 +1,212 @@
+//! Local control channel for the `tempus` command-line client. Unlike the
+//! REST API it is always on in the desktop app.
+//!
+//! The app listens on a loopback port the OS picks and writes the port with
+//! a fresh token to `control.json` in the data directory, readable by its
+//! owner only, so only the user's own processes can connect. Each connection
+//! carries one [`ControlRequest`] line and gets one [`ControlResponse`] line
+//! back.
+// The command-line client only runs on desktops.
+#![cfg_attr(mobile, allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::storage::StorageService;
+use super::storage_file;
+use super::timer_state::{TimerData, TimerManager};
+
+/// How long either side waits on the other.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where the running app can be reached, as written to `control.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct ControlEndpoint {
+    port: u16,
+    token: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Starts the next session.
+    Start,
+    /// Reports what the timer is doing.
+    Status,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ControlRequest {
+    pub token: String,
+    pub command: ControlCommand,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlResponse {
+    State(Box<TimerData>),
+    Error(String),
+}
+
+fn handle(timer: &TimerManager, token: &str, request: ControlRequest) -> ControlResponse {
+    if request.token != token {
+        return ControlResponse::Error("Invalid control token".to_string());
+    }
+    let result = match request.command {
+        ControlCommand::Start => timer.start(None).map_err(|error| error.to_string()),
+        ControlCommand::Status => timer.get_data().map_err(|error| error.to_string()),
+    };
+    match result {
+        Ok(data) => ControlResponse::State(Box::new(data)),
+        Err(error) => ControlResponse::Error(error),
+    }
+}
+
+fn serve_client(stream: TcpStream, timer: &TimerManager, token: &str) -> Result<(), String> {
+    let io_error = |e: std::io::Error| format!("Control connection failed: {e}");
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .map_err(io_error)?;
+    stream
+        .set_write_timeout(Some(IO_TIMEOUT))
+        .map_err(io_error)?;
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(io_error)?;
+    let response = match serde_json::from_str(&line) {
+        Ok(request) => handle(timer, token, request),
+        Err(error) => ControlResponse::Error(format!("Invalid control request: {error}")),
+    };
+    let mut reply = serde_json::to_string(&response)
+        .map_err(|e| format!("Failed to serialize control response: {e}"))?;
+    reply.push('\n');
+    (&stream).write_all(reply.as_bytes()).map_err(io_error)
+}
+
+/// Publishes the endpoint and answers clients on a background thread.
+/// Failures are logged rather than aborting the app, since only the
+/// command-line client depends on it.
+pub fn spawn_server(app: AppHandle) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(error) => {
+            log::error!("Control socket not started: {error}");
+            return;
+        }
+    };
+    let endpoint = listener.local_addr().map(|addr| ControlEndpoint {
+        port: addr.port(),
+        token: uuid::Uuid::new_v4().simple().to_string(),
+    });
+    let published = endpoint.map_err(|e| e.to_string()).and_then(|endpoint| {
+        let content = serde_json::to_string(&endpoint).map_err(|e| e.to_string())?;
+        let path = app.state::<StorageService>().control_endpoint_path();
+        storage_file::replace_private(&path, content.as_bytes())?;
+        Ok(endpoint)
+    });
+    let endpoint = match published {
+        Ok(endpoint) => endpoint,
+        Err(error) => {
+            log::error!("Control socket not started: {error}");
+            return;
+        }
+    };
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let result = stream
+                .map_err(|e| format!("Control connection failed: {e}"))
+                .and_then(|stream| {
+                    serve_client(stream, &app.state::<TimerManager>(), &endpoint.token)
+                });
+            if let Err(error) = result {
+                log::error!("{error}");
+            }
+        }
+    });
+}
+
+/// Sends `command` to the app whose endpoint is in `storage`. `Ok(None)`
+/// when no app is running there.
+#[cfg(feature = "cli")]
+pub fn call(
+    storage: &StorageService,
+    command: ControlCommand,
+) -> Result<Option<TimerData>, String> {
+    let Some(content) = storage_file::read_private(&storage.control_endpoint_path())? else {
+        return Ok(None);
+    };
+    let endpoint: ControlEndpoint = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid control endpoint file: {e}"))?;
+    let addr = std::net::SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+    // A leftover endpoint from an app that has quit refuses the connection.
+    let stream = match TcpStream::connect_timeout(&addr, IO_TIMEOUT) {
+        Ok(stream) => stream,
+        Err(error) if error.kind() == std::io::ErrorKind::ConnectionRefused => return Ok(None),
+        Err(error) => return Err(format!("Failed to reach the app: {error}")),
+    };
+    let io_error = |e: std::io::Error| format!("Failed to talk to the app: {e}");
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .map_err(io_error)?;
+    stream
+        .set_write_timeout(Some(IO_TIMEOUT))
+        .map_err(io_error)?;
+    let mut request = serde_json::to_string(&ControlRequest {
+        token: endpoint.token,
+        command,
+    })
+    .map_err(|e| format!("Failed to serialize control request: {e}"))?;
+    request.push('\n');
+    (&stream).write_all(request.as_bytes()).map_err(io_error)?;
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(io_error)?;
+    match serde_json::from_str(&line) {
+        Ok(ControlResponse::State(data)) => Ok(Some(*data)),
+        Ok(ControlResponse::Error(message)) => Err(format!("The app refused: {message}")),
+        Err(error) => Err(format!("Failed to read the app's response: {error}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::timer_state::{TimerConfig, TimerState};
+
+    fn request(token: &str, command: ControlCommand) -> ControlRequest {
+        ControlRequest {
+            token: token.to_string(),
+            command,
+        }
+    }
+
+    #[test]
+    fn requests_need_the_published_token() {
+        let timer = TimerManager::new(TimerConfig::default());
+        let response = handle(&timer, "secret", request("guess", ControlCommand::Start));
+        assert!(matches!(response, ControlResponse::Error(_)));
+        assert_eq!(timer.get_data().unwrap().state, TimerState::Idle);
+    }
+
+    #[test]
+    fn start_and_status_answer_with_the_timer_state() {
+        let timer = TimerManager::new(TimerConfig::default());
+        let ControlResponse::State(data) =
+            handle(&timer, "secret", request("secret", ControlCommand::Start))
+        else {
+            panic!("start was refused");
+        };
+        assert_eq!(data.state, TimerState::Running);
+        let ControlResponse::State(data) =
+            handle(&timer, "secret", request("secret", ControlCommand::Status))
+        else {
+            panic!("status was refused");
+        };
+        assert_eq!(data.state, TimerState::Running);
+    }
+}
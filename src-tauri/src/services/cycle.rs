@@ -0,0 +1,215 @@
+//! Where the timer is within a pomodoro cycle: the run of work sessions and
+//! short breaks that ends with a long break, or the phases of a custom
+//! interval sequence.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::session_types::counts_as_pomodoro;
+use super::timer_state::{SessionType, TimerConfig};
+
+/// One step of a custom interval sequence, such as 52 minutes of work.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct SequencePhase {
+    pub session_type: SessionType,
+    /// Seconds.
+    pub duration: u64,
+}
+
+/// The session that follows `current` under the work/break rules, given
+/// how many pomodoros have been completed including `current` itself.
+fn next_session_type(
+    current: &SessionType,
+    completed_pomodoros: u32,
+    config: &TimerConfig,
+) -> SessionType {
+    match current {
+        SessionType::Work => {
+            let interval = config.sessions_until_long_break.max(1);
+            if completed_pomodoros > 0 && completed_pomodoros.is_multiple_of(interval) {
+                SessionType::LongBreak
+            } else {
+                SessionType::ShortBreak
+            }
+        }
+        SessionType::ShortBreak | SessionType::LongBreak | SessionType::Custom(_) => {
+            SessionType::Work
+        }
+    }
+}
+
+/// The session that follows `current`, at sequence phase `phase`, and the
+/// phase it is at. With a sequence in `config` the phases follow each other,
+/// starting over after the last; otherwise the work/break rules apply,
+/// given how many pomodoros have been completed including `current` itself.
+pub fn next_session(
+    current: &SessionType,
+    phase: usize,
+    completed_pomodoros: u32,
+    config: &TimerConfig,
+) -> (SessionType, usize) {
+    if config.sequence.is_empty() {
+        return (next_session_type(current, completed_pomodoros, config), 0);
+    }
+    let next = (phase + 1) % config.sequence.len();
+    (config.sequence[next].session_type.clone(), next)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct CycleInfo {
+    /// 1-based number of the current pomodoro, or of the one just finished
+    /// during a break. `0` before the first pomodoro of the day.
+    pub pomodoro_in_cycle: u32,
+    pub pomodoros_per_cycle: u32,
+    /// Whether the break after the next pomodoro to finish is a long one.
+    pub next_break_is_long: bool,
+    /// Unix time the cycle's long break, or the sequence's last phase, would
+    /// end if every remaining session ran back to back from now.
+    pub projected_end_time: u64,
+    /// Index of the current phase when the configuration has a sequence.
+    pub sequence_phase: Option<u32>,
+}
+
+impl CycleInfo {
+    /// `completed_pomodoros` excludes the current session; `remaining_time`
+    /// is what is left of it.
+    pub fn new(
+        session_type: &SessionType,
+        phase: usize,
+        completed_pomodoros: u32,
+        remaining_time: u64,
+        config: &TimerConfig,
+        now: u64,
+    ) -> Self {
+        if !config.sequence.is_empty() {
+            return Self::in_sequence(session_type, phase, remaining_time, config, now);
+        }
+        let per_cycle = config.sessions_until_long_break.max(1);
+        let counts =
+            |session: &SessionType| counts_as_pomodoro(session, &config.custom_session_types);
+        let done = completed_pomodoros % per_cycle;
+        let pomodoro_in_cycle = match session_type {
+            SessionType::LongBreak if done == 0 && completed_pomodoros > 0 => per_cycle,
+            current if counts(current) => done + 1,
+            _ => done,
+        };
+
+        // Every session next_session_type can reach eventually leads to a
+        // long break, so this stops within two passes over the cycle.
+        let mut remaining = remaining_time;
+        let mut session = session_type.clone();
+        let mut completed = completed_pomodoros;
+        while session != SessionType::LongBreak {
+            if counts(&session) {
+                completed += 1;
+            }
+            session = next_session_type(&session, completed, config);
+            remaining += config.duration_for(&session);
+        }
+
+        Self {
+            pomodoro_in_cycle,
+            pomodoros_per_cycle: per_cycle,
+            next_break_is_long: (completed_pomodoros + 1).is_multiple_of(per_cycle),
+            projected_end_time: now + remaining,
+            sequence_phase: None,
+        }
+    }
+
+    /// A cycle is one pass over the sequence, its pomodoros the phases that
+    /// count as one.
+    fn in_sequence(
+        session_type: &SessionType,
+        phase: usize,
+        remaining_time: u64,
+        config: &TimerConfig,
+        now: u64,
+    ) -> Self {
+        let phases = &config.sequence;
+        let phase = phase % phases.len();
+        let counts =
+            |session: &SessionType| counts_as_pomodoro(session, &config.custom_session_types);
+        let counted = |phases: &[SequencePhase]| {
+            phases
+                .iter()
+                .filter(|phase| counts(&phase.session_type))
+                .count() as u32
+        };
+        let before = counted(&phases[..phase]);
+        let pomodoro_in_cycle = if counts(session_type) {
+            before + 1
+        } else {
+            before
+        };
+        let next_pomodoro = (0..phases.len())
+            .map(|offset| (phase + offset) % phases.len())
+            .find(|&index| counts(&phases[index].session_type));
+        let next_break_is_long = next_pomodoro.is_some_and(|index| {
+            phases[(index + 1) % phases.len()].session_type == SessionType::LongBreak
+        });
+        let remaining = remaining_time
+            + phases[phase + 1..]
+                .iter()
+                .map(|phase| phase.duration)
+                .sum::<u64>();
+
+        Self {
+            pomodoro_in_cycle,
+            pomodoros_per_cycle: counted(phases),
+            next_break_is_long,
+            projected_end_time: now + remaining,
+            sequence_phase: Some(phase as u32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(session_type: SessionType, minutes: u64) -> SequencePhase {
+        SequencePhase {
+            session_type,
+            duration: minutes * 60,
+        }
+    }
+
+    #[test]
+    fn every_fourth_pomodoro_is_followed_by_a_long_break() {
+        let config = TimerConfig::default();
+        let next = |completed| next_session(&SessionType::Work, 0, completed, &config);
+        assert_eq!(next(3), (SessionType::ShortBreak, 0));
+        assert_eq!(next(4), (SessionType::LongBreak, 0));
+        assert_eq!(
+            next_session(&SessionType::LongBreak, 0, 4, &config),
+            (SessionType::Work, 0)
+        );
+    }
+
+    #[test]
+    fn a_cycle_in_a_sequence_is_one_pass_over_it() {
+        let config = TimerConfig {
+            sequence: vec![
+                phase(SessionType::Work, 50),
+                phase(SessionType::ShortBreak, 10),
+                phase(SessionType::Work, 50),
+                phase(SessionType::LongBreak, 30),
+            ],
+            ..TimerConfig::default()
+        };
+        assert_eq!(
+            next_session(&SessionType::LongBreak, 3, 2, &config),
+            (SessionType::Work, 0)
+        );
+
+        let info = CycleInfo::new(&SessionType::ShortBreak, 1, 1, 60, &config, 1_000);
+        assert_eq!(info.pomodoro_in_cycle, 1);
+        assert_eq!(info.pomodoros_per_cycle, 2);
+        assert!(info.next_break_is_long);
+        assert_eq!(info.projected_end_time, 1_000 + 60 + 80 * 60);
+        assert_eq!(info.sequence_phase, Some(1));
+    }
+}
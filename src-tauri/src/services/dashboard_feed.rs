@@ -0,0 +1,190 @@
+//! A JSON file of rolling 7- and 30-day aggregates, rewritten periodically
+//! so personal dashboards (Grafana, Homepage, ...) can chart focus data
+//! without talking to the app.
+
+use chrono::{Days, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::days_off::DaysOffSettings;
+use super::service_health::{self, BackgroundService};
+use super::storage::{StorageService, TimerStatistic};
+use super::summary::{summarize, StatisticsSummary};
+use super::timeseries::Granularity;
+
+/// Bumped only for incompatible changes; fields may be added within a version.
+pub const FEED_SCHEMA_VERSION: u32 = 1;
+/// How often the scheduler checks whether the feed is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct DashboardFeedConfig {
+    pub enabled: bool,
+    /// File the feed is written to. Only set through
+    /// `choose_dashboard_feed_path`.
+    pub path: Option<String>,
+    /// Minutes between rewrites.
+    pub interval_minutes: u32,
+}
+
+impl Default for DashboardFeedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            interval_minutes: 15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct FeedDay {
+    pub date: String,
+    pub pomodoros: u32,
+    pub focus_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct FeedWindow {
+    pub days: u32,
+    pub from: String,
+    /// Inclusive; today.
+    pub to: String,
+    pub pomodoros: u32,
+    pub focus_seconds: u64,
+    pub active_days: u32,
+    pub average_daily_pomodoros: f64,
+    pub average_daily_focus_seconds: f64,
+    pub longest_streak_days: u32,
+    /// Date with the most focus time, if any.
+    pub best_day: Option<String>,
+    /// One entry per day, oldest first, including empty days.
+    pub daily: Vec<FeedDay>,
+}
+
+impl From<StatisticsSummary> for FeedWindow {
+    fn from(summary: StatisticsSummary) -> Self {
+        Self {
+            days: summary.periods.len() as u32,
+            from: summary.from,
+            to: summary.to,
+            pomodoros: summary.total_pomodoros,
+            focus_seconds: summary.total_focus_time,
+            active_days: summary.active_days,
+            average_daily_pomodoros: summary.average_daily_pomodoros,
+            average_daily_focus_seconds: summary.average_daily_focus_time,
+            longest_streak_days: summary.longest_streak_days,
+            best_day: summary.best_day.map(|best| best.date),
+            daily: summary
+                .periods
+                .into_iter()
+                .map(|day| FeedDay {
+                    date: day.start_date,
+                    pomodoros: day.completed_pomodoros,
+                    focus_seconds: day.total_focus_time,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Contents of the feed file.
+#[derive(Debug, Clone, Serialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct DashboardFeed {
+    pub schema_version: u32,
+    /// Unix time the file was written.
+    pub generated_at: u64,
+    pub last_7_days: FeedWindow,
+    pub last_30_days: FeedWindow,
+}
+
+fn window(
+    statistics: &[TimerStatistic],
+    today: NaiveDate,
+    days: u64,
+    days_off: &DaysOffSettings,
+) -> FeedWindow {
+    let from = today - Days::new(days - 1);
+    summarize(statistics, Granularity::Day, from, today, days_off).into()
+}
+
+pub fn build_feed(
+    statistics: &[TimerStatistic],
+    today: NaiveDate,
+    now: u64,
+    days_off: &DaysOffSettings,
+) -> DashboardFeed {
+    DashboardFeed {
+        schema_version: FEED_SCHEMA_VERSION,
+        generated_at: now,
+        last_7_days: window(statistics, today, 7, days_off),
+        last_30_days: window(statistics, today, 30, days_off),
+    }
+}
+
+/// Writes through a temporary file so readers never see a partial feed.
+fn write_atomically(path: &Path, feed: &DashboardFeed) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(feed)
+        .map_err(|e| format!("Failed to serialize dashboard feed: {e}"))?;
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, content).map_err(|e| format!("Failed to write {}: {e}", temp.display()))?;
+    fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))
+}
+
+/// Writes the feed to `path` from the last 30 days of statistics.
+pub fn write_feed(storage: &StorageService, path: &Path) -> Result<(), String> {
+    let preferences = storage.load_preferences()?;
+    let today = preferences.calendar.today();
+    let statistics = storage.load_statistics(Some(today - Days::new(29)), Some(today))?;
+    let feed = build_feed(
+        &statistics,
+        today,
+        Utc::now().timestamp().max(0) as u64,
+        &preferences.days_off,
+    );
+    write_atomically(path, &feed)
+}
+
+/// Rewrites the feed every `interval_minutes` while it is enabled and has a
+/// path. The first write happens within a minute of enabling it.
+pub fn spawn_writer(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        let mut last_written: Option<Instant> = None;
+        loop {
+            interval.tick().await;
+            let storage = app.state::<StorageService>();
+            let config = match storage.load_preferences() {
+                Ok(preferences) => preferences.dashboard_feed,
+                Err(error) => {
+                    log::warn!("Dashboard feed skipped: {error}");
+                    continue;
+                }
+            };
+            let (true, Some(path)) = (config.enabled, config.path) else {
+                last_written = None;
+                continue;
+            };
+            let period = Duration::from_secs(u64::from(config.interval_minutes) * 60);
+            if last_written.is_some_and(|at| at.elapsed() < period) {
+                continue;
+            }
+            let result = write_feed(&storage, Path::new(&path));
+            if let Err(error) = &result {
+                log::error!("Dashboard feed failed: {error}");
+            }
+            service_health::report(&app, BackgroundService::DashboardFeed, &result);
+            last_written = Some(Instant::now());
+        }
+    });
+}
@@ -0,0 +1,262 @@
+//! Planned days off: vacations entered by hand plus, optionally, a
+//! region's public holidays. Streaks carry over them, the daily goal
+//! doesn't apply on them and summaries leave them out of daily averages.
+//!
+//! Holidays are worked out from rules rather than fetched, so they are
+//! the nationwide ones only; regional holidays can be added as entries.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::calendar::parse_day;
+
+/// A vacation or other planned break, by hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayOffEntry {
+    /// `YYYY-MM-DD`.
+    pub start_date: String,
+    /// `YYYY-MM-DD`, inclusive.
+    pub end_date: String,
+    pub label: String,
+}
+
+/// Countries whose public holidays can count as days off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum HolidayRegion {
+    /// US federal holidays, moved to the nearest weekday when they fall on
+    /// a weekend.
+    Us,
+    /// Bank holidays in England and Wales, moved to the next free weekday
+    /// when they fall on a weekend.
+    Gb,
+    /// German nationwide holidays.
+    De,
+    /// French public holidays.
+    Fr,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct DaysOffSettings {
+    pub entries: Vec<DayOffEntry>,
+    /// Public holidays of this region are days off too.
+    pub holiday_region: Option<HolidayRegion>,
+}
+
+/// A single day off, for showing on a calendar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct DayOff {
+    pub date: String,
+    /// The entry's label or the holiday's name.
+    pub label: String,
+    pub holiday: bool,
+}
+
+/// The `n`th (1-based) `weekday` of `month`, or the last one for `n == 0`.
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    if n > 0 {
+        return NaiveDate::from_weekday_of_month_opt(year, month, weekday, n);
+    }
+    let next_month = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }?;
+    let last = next_month.pred_opt()?;
+    let back = (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    Some(last - Duration::days(back.into()))
+}
+
+/// Easter Sunday in the Gregorian calendar (Meeus/Jones/Butcher).
+fn easter(year: i32) -> Option<NaiveDate> {
+    let (a, b, c) = (year % 19, year / 100, year % 100);
+    let (d, e) = (b / 4, b % 4);
+    let g = (b - (b + 8) / 25 + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let l = (32 + 2 * e + 2 * (c / 4) - h - c % 4) % 7;
+    let n = h + l - 7 * ((a + 11 * h + 22 * l) / 451) + 114;
+    NaiveDate::from_ymd_opt(year, (n / 31) as u32, (n % 31 + 1) as u32)
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// The public holidays of `region` in `year`, by date.
+pub fn holidays(region: HolidayRegion, year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day);
+    let easter = easter(year);
+    let after_easter = |days: i64| easter.map(|easter| easter + Duration::days(days));
+    let rules = match region {
+        HolidayRegion::Us => vec![
+            (date(1, 1), "New Year's Day"),
+            (
+                nth_weekday(year, 1, Weekday::Mon, 3),
+                "Martin Luther King Jr. Day",
+            ),
+            (
+                nth_weekday(year, 2, Weekday::Mon, 3),
+                "Washington's Birthday",
+            ),
+            (nth_weekday(year, 5, Weekday::Mon, 0), "Memorial Day"),
+            (date(6, 19), "Juneteenth"),
+            (date(7, 4), "Independence Day"),
+            (nth_weekday(year, 9, Weekday::Mon, 1), "Labor Day"),
+            (nth_weekday(year, 10, Weekday::Mon, 2), "Columbus Day"),
+            (date(11, 11), "Veterans Day"),
+            (nth_weekday(year, 11, Weekday::Thu, 4), "Thanksgiving Day"),
+            (date(12, 25), "Christmas Day"),
+        ],
+        HolidayRegion::Gb => vec![
+            (date(1, 1), "New Year's Day"),
+            (after_easter(-2), "Good Friday"),
+            (after_easter(1), "Easter Monday"),
+            (
+                nth_weekday(year, 5, Weekday::Mon, 1),
+                "Early May bank holiday",
+            ),
+            (nth_weekday(year, 5, Weekday::Mon, 0), "Spring bank holiday"),
+            (nth_weekday(year, 8, Weekday::Mon, 0), "Summer bank holiday"),
+            (date(12, 25), "Christmas Day"),
+            (date(12, 26), "Boxing Day"),
+        ],
+        HolidayRegion::De => vec![
+            (date(1, 1), "Neujahr"),
+            (after_easter(-2), "Karfreitag"),
+            (after_easter(1), "Ostermontag"),
+            (date(5, 1), "Tag der Arbeit"),
+            (after_easter(39), "Christi Himmelfahrt"),
+            (after_easter(50), "Pfingstmontag"),
+            (date(10, 3), "Tag der Deutschen Einheit"),
+            (date(12, 25), "1. Weihnachtstag"),
+            (date(12, 26), "2. Weihnachtstag"),
+        ],
+        HolidayRegion::Fr => vec![
+            (date(1, 1), "Jour de l'an"),
+            (after_easter(1), "Lundi de Pâques"),
+            (date(5, 1), "Fête du Travail"),
+            (date(5, 8), "Victoire 1945"),
+            (after_easter(39), "Ascension"),
+            (after_easter(50), "Lundi de Pentecôte"),
+            (date(7, 14), "Fête nationale"),
+            (date(8, 15), "Assomption"),
+            (date(11, 1), "Toussaint"),
+            (date(11, 11), "Armistice 1918"),
+            (date(12, 25), "Noël"),
+        ],
+    };
+
+    let mut days: Vec<(NaiveDate, &'static str)> = Vec::new();
+    for (date, name) in rules {
+        let Some(mut date) = date else {
+            continue;
+        };
+        match region {
+            HolidayRegion::Us if date.weekday() == Weekday::Sat => date -= Duration::days(1),
+            HolidayRegion::Us if date.weekday() == Weekday::Sun => date += Duration::days(1),
+            HolidayRegion::Gb => {
+                while is_weekend(date) || days.iter().any(|(taken, _)| *taken == date) {
+                    date += Duration::days(1);
+                }
+            }
+            _ => {}
+        }
+        days.push((date, name));
+    }
+    days.sort();
+    days
+}
+
+impl DaysOffSettings {
+    /// Why `date` is a day off, if it is. Entries win over holidays.
+    pub fn day_off(&self, date: NaiveDate) -> Option<DayOff> {
+        let entry = self.entries.iter().find(|entry| {
+            parse_day(&entry.start_date).is_some_and(|start| start <= date)
+                && parse_day(&entry.end_date).is_some_and(|end| date <= end)
+        });
+        if let Some(entry) = entry {
+            return Some(DayOff {
+                date: date.to_string(),
+                label: entry.label.clone(),
+                holiday: false,
+            });
+        }
+        let region = self.holiday_region?;
+        // New Year's Day can be observed on the last day of the year before.
+        [date.year(), date.year() + 1]
+            .into_iter()
+            .flat_map(|year| holidays(region, year))
+            .find(|(holiday, _)| *holiday == date)
+            .map(|(_, name)| DayOff {
+                date: date.to_string(),
+                label: name.to_string(),
+                holiday: true,
+            })
+    }
+
+    pub fn is_day_off(&self, date: NaiveDate) -> bool {
+        self.day_off(date).is_some()
+    }
+
+    /// Every day off in `start..=end`, in order.
+    pub fn between(&self, start: NaiveDate, end: NaiveDate) -> Vec<DayOff> {
+        start
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .filter_map(|date| self.day_off(date))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(key: &str) -> NaiveDate {
+        parse_day(key).unwrap()
+    }
+
+    #[test]
+    fn easter_and_movable_feasts() {
+        assert_eq!(easter(2024), Some(day("2024-03-31")));
+        assert_eq!(easter(2025), Some(day("2025-04-20")));
+        let german = holidays(HolidayRegion::De, 2025);
+        assert!(german.contains(&(day("2025-05-29"), "Christi Himmelfahrt")));
+        assert!(german.contains(&(day("2025-06-09"), "Pfingstmontag")));
+    }
+
+    #[test]
+    fn weekend_holidays_are_observed_on_weekdays() {
+        // July 4th 2026 is a Saturday, Christmas 2021 a Saturday with
+        // Boxing Day on the Sunday.
+        let us = holidays(HolidayRegion::Us, 2026);
+        assert!(us.contains(&(day("2026-07-03"), "Independence Day")));
+        let gb = holidays(HolidayRegion::Gb, 2021);
+        assert!(gb.contains(&(day("2021-12-27"), "Christmas Day")));
+        assert!(gb.contains(&(day("2021-12-28"), "Boxing Day")));
+    }
+
+    #[test]
+    fn entries_take_precedence_over_holidays() {
+        let settings = DaysOffSettings {
+            entries: vec![DayOffEntry {
+                start_date: "2025-12-24".to_string(),
+                end_date: "2025-12-31".to_string(),
+                label: "Winter break".to_string(),
+            }],
+            holiday_region: Some(HolidayRegion::De),
+        };
+        let days = settings.between(day("2025-12-20"), day("2026-01-02"));
+        assert_eq!(days.len(), 9);
+        assert_eq!(days[1].label, "Winter break");
+        assert!(!days[1].holiday);
+        assert_eq!(days[8].label, "Neujahr");
+        assert!(!settings.is_day_off(day("2025-12-23")));
+    }
+}
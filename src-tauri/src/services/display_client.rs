@@ -0,0 +1,183 @@
+//! Display mode: follows another instance's countdown over its display
+//! server and forwards it to this machine's webview, read-only.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use ts_rs::TS;
+
+use super::display_server::{ClientMessage, ServerMessage};
+
+/// Emitted with the main instance's `TimerData` whenever it changes.
+pub const DISPLAY_STATE_EVENT: &str = "display://state";
+/// Emitted with a [`DisplayStatus`] when the connection comes or goes.
+pub const DISPLAY_STATUS_EVENT: &str = "display://status";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+type Socket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Where to find the main instance and how to prove this display is paired.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayConnection {
+    /// `host:port` of the main instance's display server.
+    pub address: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DisplayStatus {
+    Connected {
+        address: String,
+    },
+    /// Retrying in a few seconds.
+    Reconnecting {
+        address: String,
+        error: String,
+    },
+    /// The main instance refused this display; pair again.
+    Rejected {
+        address: String,
+        error: String,
+    },
+}
+
+async fn open(address: &str) -> Result<Socket, String> {
+    tokio_tungstenite::connect_async(format!("ws://{address}"))
+        .await
+        .map(|(socket, _)| socket)
+        .map_err(|e| format!("Failed to connect to {address}: {e}"))
+}
+
+async fn send(socket: &mut Socket, message: &ClientMessage) -> Result<(), String> {
+    let text = serde_json::to_string(message)
+        .map_err(|e| format!("Failed to serialize display message: {e}"))?;
+    socket
+        .send(Message::text(text))
+        .await
+        .map_err(|e| format!("Failed to send to main instance: {e}"))
+}
+
+/// The next server message, or `None` once the connection closes.
+async fn receive(socket: &mut Socket) -> Result<Option<ServerMessage>, String> {
+    while let Some(frame) = socket.next().await {
+        match frame.map_err(|e| format!("Display connection lost: {e}"))? {
+            Message::Text(text) => {
+                return serde_json::from_str(&text)
+                    .map(Some)
+                    .map_err(|e| format!("Invalid message from main instance: {e}"));
+            }
+            Message::Close(_) => return Ok(None),
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Pairs with the instance at `address` using the code it displays.
+pub async fn pair(address: &str, code: String, name: String) -> Result<DisplayConnection, String> {
+    let mut socket = open(address).await?;
+    send(&mut socket, &ClientMessage::Pair { code, name }).await?;
+    match receive(&mut socket).await? {
+        Some(ServerMessage::Paired { token }) => Ok(DisplayConnection {
+            address: address.to_string(),
+            token,
+        }),
+        Some(ServerMessage::Error { message }) => Err(message),
+        _ => Err("Main instance did not complete pairing".into()),
+    }
+}
+
+enum Ended {
+    /// Network trouble; worth retrying.
+    Lost(String),
+    Rejected(String),
+}
+
+async fn follow(app: &AppHandle, connection: &DisplayConnection) -> Ended {
+    let mut socket = match open(&connection.address).await {
+        Ok(socket) => socket,
+        Err(error) => return Ended::Lost(error),
+    };
+    let hello = ClientMessage::Hello {
+        token: connection.token.clone(),
+    };
+    if let Err(error) = send(&mut socket, &hello).await {
+        return Ended::Lost(error);
+    }
+    loop {
+        match receive(&mut socket).await {
+            Ok(Some(ServerMessage::Welcome)) => {
+                let _ = app.emit(
+                    DISPLAY_STATUS_EVENT,
+                    DisplayStatus::Connected {
+                        address: connection.address.clone(),
+                    },
+                );
+            }
+            Ok(Some(ServerMessage::State { data })) => {
+                let _ = app.emit(DISPLAY_STATE_EVENT, data);
+            }
+            Ok(Some(ServerMessage::Error { message })) => return Ended::Rejected(message),
+            Ok(Some(ServerMessage::Paired { .. })) => {}
+            Ok(None) => return Ended::Lost("Main instance closed the connection".into()),
+            Err(error) => return Ended::Lost(error),
+        }
+    }
+}
+
+/// Runs display mode in the background, reconnecting after network errors.
+#[derive(Default)]
+pub struct DisplayClient {
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl DisplayClient {
+    fn lock(&self) -> Result<MutexGuard<'_, Option<JoinHandle<()>>>, String> {
+        self.task
+            .lock()
+            .map_err(|e| format!("Display client lock poisoned: {e}"))
+    }
+
+    /// Starts following `connection`, replacing any earlier one.
+    pub fn connect(&self, app: AppHandle, connection: DisplayConnection) -> Result<(), String> {
+        let task = tauri::async_runtime::spawn(async move {
+            loop {
+                let address = connection.address.clone();
+                match follow(&app, &connection).await {
+                    Ended::Lost(error) => {
+                        let _ = app.emit(
+                            DISPLAY_STATUS_EVENT,
+                            DisplayStatus::Reconnecting { address, error },
+                        );
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                    Ended::Rejected(error) => {
+                        let _ = app.emit(
+                            DISPLAY_STATUS_EVENT,
+                            DisplayStatus::Rejected { address, error },
+                        );
+                        return;
+                    }
+                }
+            }
+        });
+        if let Some(previous) = self.lock()?.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    pub fn disconnect(&self) -> Result<(), String> {
+        if let Some(task) = self.lock()?.take() {
+            task.abort();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+//! Streams the timer to paired display clients (e.g. a Raspberry Pi screen)
+//! over WebSocket. Displays are read-only: they can watch the countdown but
+//! never change it.
+//!
+//! A display pairs once with a short-lived code shown on this machine and
+//! receives its own token, which it presents on every later connection.
+//!
+//! The server speaks plain `ws://`, so it listens on localhost unless
+//! `TEMPUS_DISPLAY_ADDR` names another interface, e.g. `0.0.0.0:50152` on
+//! a trusted home network.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use ts_rs::TS;
+
+use super::listen_addr::{listen_addr, Interfaces};
+use super::storage::StorageService;
+use super::surface_auth::{generate_token, tokens_match, Access, Surface};
+use super::timer_state::{TimerData, TimerManager};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:50152";
+const ADDR_ENV: &str = "TEMPUS_DISPLAY_ADDR";
+const PAIRING_CODE_TTL: Duration = Duration::from_secs(5 * 60);
+/// Wrong guesses allowed before the pairing code is thrown away.
+const MAX_PAIRING_ATTEMPTS: u32 = 5;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const STREAM_INTERVAL: Duration = Duration::from_secs(1);
+/// Longer display names are cut short.
+const MAX_NAME_CHARS: usize = 64;
+/// Pause after a failed `accept`, e.g. when out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// First message a display sends after connecting.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Pair { code: String, name: String },
+    Hello { token: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Paired { token: String },
+    Welcome,
+    State { data: Box<TimerData> },
+    Error { message: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedDisplay {
+    pub id: String,
+    pub name: String,
+    pub token: String,
+    pub paired_at: u64,
+}
+
+/// A paired display as shown in settings, without its token.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DisplayInfo {
+    pub id: String,
+    pub name: String,
+    pub paired_at: u64,
+}
+
+impl From<PairedDisplay> for DisplayInfo {
+    fn from(display: PairedDisplay) -> Self {
+        Self {
+            id: display.id,
+            name: display.name,
+            paired_at: display.paired_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PairingCode {
+    /// Six digits to type on the display.
+    pub code: String,
+    pub expires_in_secs: u64,
+}
+
+struct PendingPairing {
+    code: String,
+    expires_at: Instant,
+    attempts: u32,
+}
+
+/// Pairing state for the display server.
+#[derive(Default)]
+pub struct DisplayRegistry {
+    pending: Mutex<Option<PendingPairing>>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl DisplayRegistry {
+    fn lock(&self) -> Result<MutexGuard<'_, Option<PendingPairing>>, String> {
+        self.pending
+            .lock()
+            .map_err(|e| format!("Display pairing lock poisoned: {e}"))
+    }
+
+    /// Issues a new pairing code, replacing any earlier one.
+    pub fn start_pairing(&self) -> Result<PairingCode, String> {
+        let random = uuid::Uuid::new_v4();
+        let bytes = random.as_bytes();
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) % 1_000_000;
+        let code = format!("{value:06}");
+        *self.lock()? = Some(PendingPairing {
+            code: code.clone(),
+            expires_at: Instant::now() + PAIRING_CODE_TTL,
+            attempts: 0,
+        });
+        Ok(PairingCode {
+            code,
+            expires_in_secs: PAIRING_CODE_TTL.as_secs(),
+        })
+    }
+
+    /// Consumes the pairing code and registers a new display.
+    fn pair(&self, storage: &StorageService, code: &str, name: String) -> Result<String, String> {
+        {
+            let mut pending = self.lock()?;
+            let Some(pairing) = pending.as_mut().filter(|p| p.expires_at > Instant::now()) else {
+                *pending = None;
+                return Err("No pairing code is active; start pairing on the main machine".into());
+            };
+            if pairing.code != code {
+                pairing.attempts += 1;
+                if pairing.attempts >= MAX_PAIRING_ATTEMPTS {
+                    *pending = None;
+                }
+                return Err("Incorrect pairing code".into());
+            }
+            *pending = None;
+        }
+
+        let token = generate_token();
+        let mut displays = storage.load_paired_displays()?;
+        displays.push(PairedDisplay {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.trim().chars().take(MAX_NAME_CHARS).collect(),
+            token: token.clone(),
+            paired_at: unix_now(),
+        });
+        storage.save_paired_displays(&displays)?;
+        Ok(token)
+    }
+}
+
+/// Fails unless the display surface is enabled and `token` belongs to a
+/// paired display.
+fn verify(storage: &StorageService, token: &str) -> Result<(), String> {
+    storage
+        .load_preferences()?
+        .external_access
+        .check(Surface::Display, Access::Read)
+        .map_err(|e| e.to_string())?;
+    if storage
+        .load_paired_displays()?
+        .iter()
+        .any(|display| tokens_match(token, &display.token))
+    {
+        Ok(())
+    } else {
+        Err("This display is not paired".into())
+    }
+}
+
+async fn send(
+    socket: &mut WebSocketStream<TcpStream>,
+    message: &ServerMessage,
+) -> Result<(), String> {
+    let text = serde_json::to_string(message)
+        .map_err(|e| format!("Failed to serialize display message: {e}"))?;
+    socket
+        .send(Message::text(text))
+        .await
+        .map_err(|e| format!("Failed to send to display: {e}"))
+}
+
+async fn handshake(
+    app: &AppHandle,
+    socket: &mut WebSocketStream<TcpStream>,
+) -> Result<String, String> {
+    let first = tokio::time::timeout(HANDSHAKE_TIMEOUT, socket.next())
+        .await
+        .map_err(|_| "Display did not identify itself in time".to_string())?;
+    let text = match first {
+        Some(Ok(Message::Text(text))) => text,
+        _ => return Err("Expected a pair or hello message".into()),
+    };
+    let message: ClientMessage =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid display message: {e}"))?;
+
+    let storage = app.state::<StorageService>();
+    match message {
+        ClientMessage::Pair { code, name } => {
+            storage
+                .load_preferences()?
+                .external_access
+                .check(Surface::Display, Access::Read)
+                .map_err(|e| e.to_string())?;
+            let token = app.state::<DisplayRegistry>().pair(&storage, &code, name)?;
+            send(
+                socket,
+                &ServerMessage::Paired {
+                    token: token.clone(),
+                },
+            )
+            .await?;
+            Ok(token)
+        }
+        ClientMessage::Hello { token } => {
+            verify(&storage, &token)?;
+            send(socket, &ServerMessage::Welcome).await?;
+            Ok(token)
+        }
+    }
+}
+
+async fn serve_display(app: AppHandle, stream: TcpStream) -> Result<(), String> {
+    let mut socket = tokio_tungstenite::accept_async(stream)
+        .await
+        .map_err(|e| format!("Display handshake failed: {e}"))?;
+    let token = match handshake(&app, &mut socket).await {
+        Ok(token) => token,
+        Err(message) => return send(&mut socket, &ServerMessage::Error { message }).await,
+    };
+
+    let mut interval = tokio::time::interval(STREAM_INTERVAL);
+    let mut last_sent: Option<(u64, u64, u64)> = None;
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            incoming = socket.next() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return Ok(()),
+                Some(Ok(_)) => continue,
+            },
+        }
+        // Unpairing the display or disabling the surface ends the stream.
+        if let Err(message) = verify(&app.state::<StorageService>(), &token) {
+            return send(&mut socket, &ServerMessage::Error { message }).await;
+        }
+        let data = app.state::<TimerManager>().get_data()?;
+        // A stopwatch counts up in `total_time`.
+        let current = (data.revision, data.remaining_time, data.total_time);
+        if last_sent != Some(current) {
+            last_sent = Some(current);
+            send(
+                &mut socket,
+                &ServerMessage::State {
+                    data: Box::new(data),
+                },
+            )
+            .await?;
+        }
+    }
+}
+
+/// Starts the display server if the display surface is enabled. A failed
+/// connection doesn't stop it.
+pub fn spawn_server(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let enabled = app
+            .state::<StorageService>()
+            .load_preferences()
+            .map(|preferences| {
+                preferences
+                    .external_access
+                    .check(Surface::Display, Access::Read)
+                    .is_ok()
+            });
+        if !matches!(enabled, Ok(true)) {
+            return;
+        }
+        let listener = match listen_addr(ADDR_ENV, DEFAULT_ADDR, Interfaces::Any) {
+            Ok(addr) => TcpListener::bind(addr).await.map_err(|e| e.to_string()),
+            Err(error) => Err(error),
+        };
+        let listener = match listener {
+            Ok(listener) => listener,
+            Err(error) => {
+                log::error!("Display server not started: {error}");
+                return;
+            }
+        };
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    log::warn!("Display connection not accepted: {error}");
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(error) = serve_display(app, stream).await {
+                    log::error!("Display connection failed: {error}");
+                }
+            });
+        }
+    });
+}
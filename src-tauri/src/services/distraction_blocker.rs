@@ -0,0 +1,375 @@
+//! Blocks distracting sites during work sessions with the actions the user
+//! configured: a section of the hosts file, shell hooks, or a call to an
+//! external blocker. Blocking starts with a work session, lasts while it
+//! is paused and is lifted once a break starts, the timer is reset or the
+//! app exits. Changes are applied one after another, in order, by a single
+//! worker.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+use ts_rs::TS;
+
+use super::service_health::{self, BackgroundService};
+use super::session_types::focus_session_active;
+use super::storage::UserPreferences;
+use super::storage_file;
+use super::timer_state::TimerData;
+
+#[cfg(windows)]
+const HOSTS_FILE: &str = r"C:\Windows\System32\drivers\etc\hosts";
+#[cfg(not(windows))]
+const HOSTS_FILE: &str = "/etc/hosts";
+const HOSTS_BEGIN: &str = "# BEGIN tempus-ring blocklist";
+const HOSTS_END: &str = "# END tempus-ring blocklist";
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a block or unblock hook may run before it is killed.
+const SHELL_TIMEOUT: Duration = Duration::from_secs(30);
+const WAIT_INTERVAL: Duration = Duration::from_millis(50);
+/// Environment variable hooks get the blocklist in, comma-separated.
+const BLOCKLIST_ENV: &str = "TEMPUS_BLOCKLIST";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BlockAction {
+    /// Points the blocklist at `0.0.0.0` in the hosts file, in a marked
+    /// section. The app needs write access to the file.
+    HostsFile,
+    /// Runs `block` through the shell when blocking starts and `unblock`
+    /// when it ends, with the blocklist in `TEMPUS_BLOCKLIST`.
+    Shell { block: String, unblock: String },
+    /// POSTs `{"blocking": bool, "domains": [...]}` to `url`, which must
+    /// use HTTPS.
+    Webhook { url: String },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct BlockerSettings {
+    pub enabled: bool,
+    /// Domains blocked during work sessions, such as `news.ycombinator.com`.
+    pub blocklist: Vec<String>,
+    pub actions: Vec<BlockAction>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BlocklistStatus {
+    pub blocklist: Vec<String>,
+    pub enabled: bool,
+    /// Blocking is in effect now.
+    pub blocking: bool,
+}
+
+/// `hosts` with the blocklist section replaced by one for `domains`, or
+/// removed when there are none. Only complete sections are removed; a
+/// begin marker without an end is left alone with the lines after it.
+fn with_blocklist(hosts: &str, domains: &[String]) -> String {
+    let lines: Vec<&str> = hosts.lines().collect();
+    let mut kept: Vec<&str> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let section_len = (lines[index].trim() == HOSTS_BEGIN)
+            .then(|| {
+                lines[index..]
+                    .iter()
+                    .position(|line| line.trim() == HOSTS_END)
+            })
+            .flatten();
+        match section_len {
+            Some(last) => index += last + 1,
+            None => {
+                kept.push(lines[index]);
+                index += 1;
+            }
+        }
+    }
+    let mut updated = kept.join("\n");
+    if !domains.is_empty() {
+        updated.push_str(&format!("\n{HOSTS_BEGIN}\n"));
+        for domain in domains {
+            updated.push_str(&format!("0.0.0.0 {domain}\n"));
+            if !domain.starts_with("www.") {
+                updated.push_str(&format!("0.0.0.0 www.{domain}\n"));
+            }
+        }
+        updated.push_str(HOSTS_END);
+    }
+    updated.push('\n');
+    updated
+}
+
+/// Rewrites the hosts file with the blocklist section for `domains`, or
+/// without one. The new version is renamed over the old, so the file is
+/// never left half written.
+fn write_hosts(hosts_file: &Path, domains: &[String]) -> Result<(), String> {
+    let hosts = fs::read_to_string(hosts_file)
+        .map_err(|e| format!("Failed to read {}: {e}", hosts_file.display()))?;
+    let updated = with_blocklist(&hosts, domains);
+    if updated == hosts {
+        return Ok(());
+    }
+    storage_file::replace(hosts_file, updated.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn shell(command: &str, domains: &[String]) -> Result<(), String> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.args(["/C", command]);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.args(["-c", command]);
+        process
+    };
+    let mut child = process
+        .env(BLOCKLIST_ENV, domains.join(","))
+        .spawn()
+        .map_err(|e| format!("Failed to run block hook: {e}"))?;
+    let deadline = Instant::now() + SHELL_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(WAIT_INTERVAL),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "Block hook `{command}` was killed after {}s",
+                    SHELL_TIMEOUT.as_secs()
+                ));
+            }
+            Err(error) => return Err(format!("Failed to wait for block hook: {error}")),
+        }
+    };
+    if !status.success() {
+        return Err(format!("Block hook `{command}` exited with {status}"));
+    }
+    Ok(())
+}
+
+/// Runs `operation` on a blocking thread, off the async runtime.
+async fn run_blocking<F>(operation: F) -> Result<(), String>
+where
+    F: FnOnce() -> Result<(), String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(operation)
+        .await
+        .map_err(|e| format!("Blocker task failed: {e}"))?
+}
+
+async fn run_action(
+    action: &BlockAction,
+    domains: &[String],
+    blocking: bool,
+) -> Result<(), String> {
+    match action {
+        BlockAction::HostsFile => {
+            let wanted = if blocking {
+                domains.to_vec()
+            } else {
+                Vec::new()
+            };
+            run_blocking(move || write_hosts(Path::new(HOSTS_FILE), &wanted)).await
+        }
+        BlockAction::Shell { block, unblock } => {
+            let command = if blocking { block } else { unblock }.clone();
+            let domains = domains.to_vec();
+            run_blocking(move || shell(&command, &domains)).await
+        }
+        BlockAction::Webhook { url } => {
+            let response = reqwest::Client::new()
+                .post(url)
+                .timeout(WEBHOOK_TIMEOUT)
+                .json(&serde_json::json!({ "blocking": blocking, "domains": domains }))
+                .send()
+                .await
+                .map_err(|e| format!("Blocker {url} failed: {e}"))?;
+            if !response.status().is_success() {
+                return Err(format!("Blocker {url} returned {}", response.status()));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Blocking to start (`true`) or lift with the given settings.
+type Change = (BlockerSettings, bool);
+
+/// Runs every action of each change sent, one change after another,
+/// reporting each change's outcome to [`service_health`].
+fn spawn_worker(app: AppHandle) -> mpsc::UnboundedSender<Change> {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<Change>();
+    tauri::async_runtime::spawn(async move {
+        while let Some((settings, blocking)) = receiver.recv().await {
+            let mut errors = Vec::new();
+            for action in &settings.actions {
+                if let Err(error) = run_action(action, &settings.blocklist, blocking).await {
+                    errors.push(error);
+                }
+            }
+            let result = if errors.is_empty() {
+                Ok(())
+            } else {
+                let verb = if blocking { "Blocking" } else { "Unblocking" };
+                Err(format!("{verb} distractions failed: {}", errors.join("; ")))
+            };
+            if let Err(error) = &result {
+                log::error!("{error}");
+            }
+            service_health::report(&app, BackgroundService::DistractionBlocker, &result);
+        }
+    });
+    sender
+}
+
+/// Removes a blocklist section a crash left in the hosts file. Called at
+/// launch, before any blocking starts.
+pub fn clear_leftover_block(app: &AppHandle) {
+    let hosts_file = Path::new(HOSTS_FILE);
+    let leftover = fs::read_to_string(hosts_file)
+        .is_ok_and(|hosts| hosts.lines().any(|line| line.trim() == HOSTS_BEGIN));
+    if !leftover {
+        return;
+    }
+    let result = write_hosts(hosts_file, &[])
+        .map_err(|error| format!("Unblocking distractions failed: {error}"));
+    if let Err(error) = &result {
+        log::error!("{error}");
+    }
+    service_health::report(app, BackgroundService::DistractionBlocker, &result);
+}
+
+struct BlockerState {
+    settings: BlockerSettings,
+    /// The settings blocking was started with, while it lasts, so the
+    /// same actions lift it.
+    blocking: Option<BlockerSettings>,
+}
+
+/// Follows timer ticks and blocks distractions during work sessions.
+pub struct DistractionBlocker {
+    state: Mutex<BlockerState>,
+    /// Feeds the worker applying changes, started with the first one.
+    queue: OnceLock<mpsc::UnboundedSender<Change>>,
+}
+
+impl DistractionBlocker {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(BlockerState {
+                settings: preferences.distraction_blocker.clone(),
+                blocking: None,
+            }),
+            queue: OnceLock::new(),
+        }
+    }
+
+    fn apply(&self, app: &AppHandle, settings: BlockerSettings, blocking: bool) {
+        let sent = self
+            .queue
+            .get_or_init(|| spawn_worker(app.clone()))
+            .send((settings, blocking));
+        if sent.is_err() {
+            log::error!("The distraction blocker worker stopped");
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, BlockerState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Distraction blocker lock poisoned: {e}"))
+    }
+
+    /// Picks up changed settings. Blocking in progress is lifted and, if
+    /// still wanted, started again on the next tick with the new ones.
+    pub fn configure(&self, app: &AppHandle, preferences: &UserPreferences) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.settings = preferences.distraction_blocker.clone();
+        if state.blocking.as_ref() != Some(&state.settings) {
+            if let Some(blocking) = state.blocking.take() {
+                self.apply(app, blocking, false);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<BlocklistStatus, String> {
+        let state = self.lock()?;
+        Ok(BlocklistStatus {
+            blocklist: state.settings.blocklist.clone(),
+            enabled: state.settings.enabled,
+            blocking: state.blocking.is_some(),
+        })
+    }
+
+    pub fn on_tick(&self, app: &AppHandle, data: &TimerData) -> Result<(), String> {
+        let mut state = self.lock()?;
+        let wanted = state.settings.enabled && focus_session_active(data);
+        if wanted == state.blocking.is_some() {
+            return Ok(());
+        }
+        if wanted {
+            let settings = state.settings.clone();
+            state.blocking = Some(settings.clone());
+            self.apply(app, settings, true);
+        } else if let Some(blocking) = state.blocking.take() {
+            self.apply(app, blocking, false);
+        }
+        Ok(())
+    }
+
+    /// Lifts blocking before the app exits. The hosts file and shell hooks
+    /// are undone right away; a webhook is not waited for.
+    pub fn release(&self) -> Result<(), String> {
+        let Some(blocking) = self.lock()?.blocking.take() else {
+            return Ok(());
+        };
+        let mut result = Ok(());
+        for action in &blocking.actions {
+            if matches!(action, BlockAction::Webhook { .. }) {
+                continue;
+            }
+            if let Err(error) =
+                tauri::async_runtime::block_on(run_action(action, &blocking.blocklist, false))
+            {
+                result = Err(error);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocklist_section_is_replaced_and_removed() {
+        let hosts = "127.0.0.1 localhost\n";
+        let domains = vec!["reddit.com".to_string()];
+        let blocked = with_blocklist(hosts, &domains);
+        assert_eq!(
+            blocked,
+            format!(
+                "127.0.0.1 localhost\n{HOSTS_BEGIN}\n0.0.0.0 reddit.com\n0.0.0.0 www.reddit.com\n{HOSTS_END}\n"
+            )
+        );
+        assert_eq!(with_blocklist(&blocked, &domains), blocked);
+        assert_eq!(with_blocklist(&blocked, &[]), hosts);
+    }
+
+    #[test]
+    fn unterminated_section_keeps_the_lines_after_it() {
+        let hosts = format!("127.0.0.1 localhost\n{HOSTS_BEGIN}\n10.0.0.2 nas\n");
+        assert_eq!(with_blocklist(&hosts, &[]), hosts);
+    }
+}
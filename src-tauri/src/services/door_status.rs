@@ -0,0 +1,321 @@
+//! A "focus status" page for e-ink door displays and shared offices, such
+//! as "In a focus session until 15:07 — please don't disturb". It is kept
+//! as `status.json` and `index.html` in a folder the user picked, which a
+//! display can read over a file share or a static web server, and served
+//! at `/door` on the REST API. The folder may be on a slow network share,
+//! so the page is written on a worker thread.
+
+use chrono::{DateTime, Local, TimeDelta};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, MutexGuard, OnceLock};
+use std::thread;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::service_health::{self, BackgroundService};
+use super::session_types::focus_session_active;
+use super::storage::UserPreferences;
+use super::timer_state::{TimerData, TimerState};
+
+const STATUS_JSON_FILE: &str = "status.json";
+const STATUS_HTML_FILE: &str = "index.html";
+/// Seconds between reloads of the HTML page.
+const PAGE_REFRESH_SECS: u32 = 30;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct DoorStatusConfig {
+    pub enabled: bool,
+    /// Folder the page is written to. Only set through
+    /// `choose_door_status_dir`.
+    pub dir: Option<String>,
+}
+
+/// Contents of `status.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct DoorStatus {
+    /// A focus session is running or paused.
+    pub busy: bool,
+    pub message: String,
+    /// Local `HH:MM` the focus session ends, if it has a set end.
+    pub until: Option<String>,
+    /// The same as Unix time.
+    pub until_timestamp: Option<u64>,
+}
+
+impl DoorStatus {
+    fn free(message: &str) -> Self {
+        Self {
+            busy: false,
+            message: message.to_string(),
+            until: None,
+            until_timestamp: None,
+        }
+    }
+
+    /// What the door says while the timer is idle, and once door status is
+    /// turned off or the app quits.
+    pub fn available() -> Self {
+        Self::free("Available")
+    }
+}
+
+/// The status `data` calls for at `now`.
+pub fn door_status(data: &TimerData, now: DateTime<Local>) -> DoorStatus {
+    if !focus_session_active(data) {
+        return if data.state == TimerState::Idle {
+            DoorStatus::available()
+        } else {
+            DoorStatus::free("On a break")
+        };
+    }
+    // Paused, overtime and stopwatch sessions have no set end.
+    let end = (data.state == TimerState::Running)
+        .then(|| now + TimeDelta::seconds(data.remaining_time as i64));
+    let until = end.map(|end| end.format("%H:%M").to_string());
+    let message = match &until {
+        Some(until) => format!("In a focus session until {until} — please don't disturb"),
+        None => "In a focus session — please don't disturb".to_string(),
+    };
+    DoorStatus {
+        busy: true,
+        message,
+        until,
+        until_timestamp: end.map(|end| end.timestamp().max(0) as u64),
+    }
+}
+
+/// A page that fills the screen with the message and reloads itself.
+fn render_html(status: &DoorStatus) -> String {
+    let (background, color) = if status.busy {
+        ("#000", "#fff")
+    } else {
+        ("#fff", "#000")
+    };
+    format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <meta http-equiv=\"refresh\" content=\"{PAGE_REFRESH_SECS}\">\n\
+         <title>{message}</title>\n\
+         <style>body {{ margin: 0; height: 100vh; display: flex; align-items: center; \
+         justify-content: center; text-align: center; font: bold 8vw sans-serif; \
+         background: {background}; color: {color}; }}</style>\n\
+         </head>\n\
+         <body><p>{message}</p></body>\n\
+         </html>\n",
+        message = status.message,
+    )
+}
+
+/// Writes through a temporary file so a display never reads a partial one.
+fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    fs::write(&temp, content)
+        .map_err(|e| format!("Failed to write {}: {e}", Path::new(&temp).display()))?;
+    fs::rename(&temp, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))
+}
+
+fn write_page(dir: &Path, status: &DoorStatus) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(status)
+        .map_err(|e| format!("Failed to serialize door status: {e}"))?;
+    write_atomically(&dir.join(STATUS_JSON_FILE), &json)?;
+    write_atomically(&dir.join(STATUS_HTML_FILE), &render_html(status))
+}
+
+/// Writes each page sent to its folder, in order. A page that fails is
+/// forgotten, so the next tick sends it again.
+fn spawn_worker(app: AppHandle) -> mpsc::Sender<(PathBuf, DoorStatus)> {
+    let (sender, receiver) = mpsc::channel::<(PathBuf, DoorStatus)>();
+    thread::spawn(move || {
+        let writer = app.state::<DoorStatusWriter>();
+        for (dir, status) in receiver {
+            let Some(result) = writer.write(&dir, &status) else {
+                continue;
+            };
+            if let Err(error) = &result {
+                log::error!("Door status failed: {error}");
+                if let Err(error) = writer.forget(&status) {
+                    log::error!("{error}");
+                }
+            }
+            service_health::report(&app, BackgroundService::DoorStatus, &result);
+        }
+    });
+    sender
+}
+
+struct DoorState {
+    config: DoorStatusConfig,
+    /// The status last sent to be written, so each change is written once.
+    written: Option<DoorStatus>,
+}
+
+/// Follows timer ticks and rewrites the page when the status changes.
+pub struct DoorStatusWriter {
+    state: Mutex<DoorState>,
+    /// Feeds the worker writing pages, started with the first one.
+    queue: OnceLock<mpsc::Sender<(PathBuf, DoorStatus)>>,
+    /// Held while a page is written; set once the app is quitting, after
+    /// which no more are.
+    closed: Mutex<bool>,
+}
+
+impl DoorStatusWriter {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(DoorState {
+                config: preferences.door_status.clone(),
+                written: None,
+            }),
+            queue: OnceLock::new(),
+            closed: Mutex::new(false),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, DoorState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Door status lock poisoned: {e}"))
+    }
+
+    /// Writes `status` to `dir`, or returns `None` once the app is quitting.
+    fn write(&self, dir: &Path, status: &DoorStatus) -> Option<Result<(), String>> {
+        let closed = match self.closed.lock() {
+            Ok(closed) => closed,
+            Err(error) => return Some(Err(format!("Door status lock poisoned: {error}"))),
+        };
+        (!*closed).then(|| write_page(dir, status))
+    }
+
+    fn send(&self, app: &AppHandle, dir: &str, status: DoorStatus) {
+        let sent = self
+            .queue
+            .get_or_init(|| spawn_worker(app.clone()))
+            .send((PathBuf::from(dir), status));
+        if sent.is_err() {
+            log::error!("The door status worker stopped");
+        }
+    }
+
+    /// Lets the next tick write `status` again after it failed.
+    fn forget(&self, status: &DoorStatus) -> Result<(), String> {
+        let mut state = self.lock()?;
+        if state.written.as_ref() == Some(status) {
+            state.written = None;
+        }
+        Ok(())
+    }
+
+    /// Picks up a changed folder or toggle; the page is written on the next
+    /// tick. A folder no longer written to is left saying "Available".
+    pub fn configure(&self, app: &AppHandle, preferences: &UserPreferences) -> Result<(), String> {
+        let mut state = self.lock()?;
+        let config = preferences.door_status.clone();
+        if let (true, Some(dir)) = (state.config.enabled, state.config.dir.clone()) {
+            if !config.enabled || config.dir.as_ref() != Some(&dir) {
+                self.send(app, &dir, DoorStatus::available());
+            }
+        }
+        state.config = config;
+        state.written = None;
+        Ok(())
+    }
+
+    /// Rewrites the page when the status changes. A write that fails is
+    /// tried again on the next tick.
+    pub fn on_tick(&self, app: &AppHandle, data: &TimerData) -> Result<(), String> {
+        let mut state = self.lock()?;
+        let (true, Some(dir)) = (state.config.enabled, state.config.dir.clone()) else {
+            return Ok(());
+        };
+        let status = door_status(data, Local::now());
+        if state.written.as_ref() == Some(&status) {
+            return Ok(());
+        }
+        state.written = Some(status.clone());
+        drop(state);
+        self.send(app, &dir, status);
+        Ok(())
+    }
+
+    /// Leaves the page saying "Available" before the app exits, after any
+    /// write under way. Pages still queued are dropped.
+    pub fn release(&self) -> Result<(), String> {
+        let config = self.lock()?.config.clone();
+        let mut closed = self
+            .closed
+            .lock()
+            .map_err(|e| format!("Door status lock poisoned: {e}"))?;
+        *closed = true;
+        match (config.enabled, config.dir) {
+            (true, Some(dir)) => write_page(Path::new(&dir), &DoorStatus::available()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::timer_state::{SessionType, TimerConfig, TimerManager};
+    use chrono::TimeZone;
+
+    fn timer(state: TimerState, session_type: SessionType, remaining_time: u64) -> TimerData {
+        let mut data = TimerManager::new(TimerConfig::default())
+            .get_data()
+            .unwrap();
+        data.state = state;
+        data.session_type = session_type;
+        data.remaining_time = remaining_time;
+        data
+    }
+
+    #[test]
+    fn running_focus_sessions_say_until_when() {
+        let now = Local.with_ymd_and_hms(2025, 3, 14, 14, 50, 0).unwrap();
+        let status = door_status(&timer(TimerState::Running, SessionType::Work, 17 * 60), now);
+
+        assert!(status.busy);
+        assert_eq!(status.until.as_deref(), Some("15:07"));
+        assert_eq!(
+            status.until_timestamp,
+            Some(now.timestamp() as u64 + 17 * 60)
+        );
+        assert_eq!(
+            status.message,
+            "In a focus session until 15:07 — please don't disturb"
+        );
+    }
+
+    #[test]
+    fn paused_focus_sessions_have_no_end() {
+        let now = Local.with_ymd_and_hms(2025, 3, 14, 14, 50, 0).unwrap();
+        let status = door_status(&timer(TimerState::Paused, SessionType::Work, 600), now);
+
+        assert!(status.busy);
+        assert_eq!(status.until, None);
+        assert_eq!(status.message, "In a focus session — please don't disturb");
+    }
+
+    #[test]
+    fn breaks_and_an_idle_timer_are_free() {
+        let now = Local.with_ymd_and_hms(2025, 3, 14, 14, 50, 0).unwrap();
+        let on_break = door_status(
+            &timer(TimerState::Running, SessionType::ShortBreak, 300),
+            now,
+        );
+        let idle = door_status(&timer(TimerState::Idle, SessionType::Work, 1500), now);
+
+        assert!(!on_break.busy);
+        assert_eq!(on_break.message, "On a break");
+        assert_eq!(idle, DoorStatus::available());
+    }
+}
@@ -0,0 +1,104 @@
+//! Durations as the tray tooltip, notifications and reports show them, in
+//! the style the user picked and the units of their language.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationStyle {
+    /// `1 h 05 min`
+    #[default]
+    Units,
+    /// `65 min`
+    Minutes,
+    /// `1:05`
+    Clock,
+}
+
+/// Unit labels of a language and whether they follow the number after a
+/// space.
+struct Units {
+    hour: &'static str,
+    minute: &'static str,
+    second: &'static str,
+    spaced: bool,
+}
+
+/// The units for a `language` preference such as `en_US` or `zh-CN`,
+/// English where the language isn't known.
+fn units(locale: &str) -> Units {
+    let language = locale.split(['_', '-']).next().unwrap_or_default();
+    let (hour, minute, second, spaced) = match language.to_ascii_lowercase().as_str() {
+        "de" => ("Std.", "Min.", "Sek.", true),
+        "fr" | "es" | "it" | "pt" => ("h", "min", "s", true),
+        "nl" => ("u", "min", "s", true),
+        "ru" => ("ч", "мин", "с", true),
+        "ja" => ("時間", "分", "秒", false),
+        "zh" => ("小时", "分钟", "秒", false),
+        "ko" => ("시간", "분", "초", false),
+        _ => ("h", "min", "s", true),
+    };
+    Units {
+        hour,
+        minute,
+        second,
+        spaced,
+    }
+}
+
+/// `seconds` in `style`, to the minute, with the units of `locale`, the
+/// `language` preference. Under a minute is shown in seconds, as `0:40` on
+/// a clock.
+pub fn format_duration(seconds: u64, style: DurationStyle, locale: &str) -> String {
+    let units = units(locale);
+    let space = if units.spaced { " " } else { "" };
+    let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+    match style {
+        DurationStyle::Clock if seconds < 60 => format!("0:{seconds:02}"),
+        DurationStyle::Clock => format!("{hours}:{minutes:02}"),
+        _ if seconds < 60 => format!("{seconds}{space}{}", units.second),
+        DurationStyle::Minutes => format!("{}{space}{}", seconds / 60, units.minute),
+        DurationStyle::Units if hours == 0 => format!("{minutes}{space}{}", units.minute),
+        DurationStyle::Units if minutes == 0 => format!("{hours}{space}{}", units.hour),
+        DurationStyle::Units => format!(
+            "{hours}{space}{}{space}{minutes:02}{space}{}",
+            units.hour, units.minute
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styles_and_locales_agree_on_the_same_duration() {
+        let seconds = 65 * 60 + 20;
+        assert_eq!(
+            format_duration(seconds, DurationStyle::Units, "en_US"),
+            "1 h 05 min"
+        );
+        assert_eq!(
+            format_duration(seconds, DurationStyle::Minutes, "en_US"),
+            "65 min"
+        );
+        assert_eq!(
+            format_duration(seconds, DurationStyle::Clock, "en_US"),
+            "1:05"
+        );
+        assert_eq!(
+            format_duration(seconds, DurationStyle::Units, "zh-CN"),
+            "1小时05分钟"
+        );
+        assert_eq!(
+            format_duration(seconds, DurationStyle::Minutes, "de_DE"),
+            "65 Min."
+        );
+        assert_eq!(format_duration(2 * 3600, DurationStyle::Units, "xx"), "2 h");
+        assert_eq!(format_duration(40, DurationStyle::Units, "en_US"), "40 s");
+        assert_eq!(format_duration(40, DurationStyle::Clock, "en_US"), "0:40");
+        assert_eq!(format_duration(5, DurationStyle::Clock, "en_US"), "0:05");
+    }
+}
@@ -0,0 +1,391 @@
+//! Pushes each finished day's statistic to the user's configured
+//! destinations, exactly once per destination per day.
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::calendar::parse_day;
+use super::service_health::{self, BackgroundService};
+use super::storage::{StorageService, TimerStatistic};
+
+/// How often the scheduler looks for days that still need pushing.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(15);
+/// Days never attempted that are older than this are skipped after a long
+/// absence. Days that were attempted stay pending until they are delivered.
+const MAX_CATCH_UP_DAYS: u64 = 7;
+
+/// Where a finished day is sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PushTarget {
+    /// `POST`s the payload as JSON with an `Idempotency-Key` header.
+    Webhook { url: String },
+    /// Writes `<key>.json` into the user-approved export directory.
+    FileDrop,
+    /// Appends a message to a named queue in the app data directory for
+    /// integrations to consume.
+    Queue { name: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PushDestination {
+    /// Stable identifier used to track delivery.
+    pub id: String,
+    #[serde(flatten)]
+    pub target: PushTarget,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct EndOfDayConfig {
+    pub enabled: bool,
+    pub destinations: Vec<PushDestination>,
+}
+
+/// Delivery bookkeeping, stored next to the preferences.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct PushState {
+    /// Latest day delivered to every destination. Earlier days may still be
+    /// pending in `delivered`.
+    pub last_pushed_date: Option<String>,
+    /// Destinations already served for days still pending.
+    pub delivered: BTreeMap<String, BTreeSet<String>>,
+    pub last_error: Option<String>,
+}
+
+/// Body of every push.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndOfDayPayload<'a> {
+    /// Same for every attempt at the same day, so receivers can drop repeats.
+    pub idempotency_key: String,
+    pub date: &'a str,
+    pub statistic: &'a TimerStatistic,
+}
+
+pub fn idempotency_key(date: &str) -> String {
+    format!("tempus-ring-day-{date}")
+}
+
+async fn deliver(
+    storage: &StorageService,
+    target: &PushTarget,
+    payload: &EndOfDayPayload<'_>,
+) -> Result<(), String> {
+    let body = serde_json::to_string_pretty(payload)
+        .map_err(|e| format!("Failed to serialize end-of-day payload: {e}"))?;
+    let write_message = |dir: &Path| {
+        let path = dir.join(format!("{}.json", payload.idempotency_key));
+        fs::write(&path, &body).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    };
+
+    match target {
+        PushTarget::Webhook { url } => {
+            let response = reqwest::Client::new()
+                .post(url)
+                .timeout(WEBHOOK_TIMEOUT)
+                .header("Idempotency-Key", &payload.idempotency_key)
+                .json(payload)
+                .send()
+                .await
+                .map_err(|e| format!("Webhook {url} failed: {e}"))?;
+            if !response.status().is_success() {
+                return Err(format!("Webhook {url} returned {}", response.status()));
+            }
+            Ok(())
+        }
+        PushTarget::FileDrop => {
+            let dir = storage
+                .load_preferences()?
+                .export_dir
+                .ok_or("File drop needs an export directory; choose one first")?;
+            write_message(Path::new(&dir))
+        }
+        PushTarget::Queue { name } => write_message(&storage.queue_dir(name)?),
+    }
+}
+
+/// Sends `date` to every destination that doesn't have it yet, carrying on
+/// past failures. The day stays pending in `state` until all of them have it.
+async fn push_day(
+    storage: &StorageService,
+    destinations: &[PushDestination],
+    state: &mut PushState,
+    date: NaiveDate,
+) -> Result<(), String> {
+    let day = date.format("%Y-%m-%d").to_string();
+    let delivered = state.delivered.entry(day.clone()).or_default();
+    let statistic = storage.load_statistic(&day)?.unwrap_or(TimerStatistic {
+        date: day.clone(),
+        completed_pomodoros: 0,
+        total_focus_time: 0,
+        sessions: Vec::new(),
+        advisories: Vec::new(),
+        overwork: Vec::new(),
+    });
+    let payload = EndOfDayPayload {
+        idempotency_key: idempotency_key(&day),
+        date: &day,
+        statistic: &statistic,
+    };
+
+    let mut result = Ok(());
+    for destination in destinations {
+        if delivered.contains(&destination.id) {
+            continue;
+        }
+        match deliver(storage, &destination.target, &payload).await {
+            Ok(()) => {
+                delivered.insert(destination.id.clone());
+            }
+            Err(error) => result = Err(error),
+        }
+    }
+    result?;
+    state.delivered.remove(&day);
+    if state.last_pushed_date.as_deref() < Some(day.as_str()) {
+        state.last_pushed_date = Some(day);
+    }
+    Ok(())
+}
+
+/// Pushes every finished day since the last run (yesterday at the latest),
+/// plus the days still pending from earlier runs, to the destinations that
+/// haven't received them yet. A failing day or destination doesn't hold up
+/// the rest; it is retried next run, however long ago the day was.
+pub async fn push_due_days(storage: &StorageService, today: NaiveDate) -> Result<(), String> {
+    let config = storage.load_preferences()?.end_of_day;
+    if !config.enabled || config.destinations.is_empty() {
+        return Ok(());
+    }
+    let Some(yesterday) = today.checked_sub_days(Days::new(1)) else {
+        return Ok(());
+    };
+
+    let mut state = storage.load_push_state()?;
+    let pending: BTreeSet<NaiveDate> = state
+        .delivered
+        .keys()
+        .filter_map(|day| parse_day(day))
+        .filter(|date| *date <= yesterday)
+        .collect();
+    let earliest = today
+        .checked_sub_days(Days::new(MAX_CATCH_UP_DAYS))
+        .unwrap_or(yesterday);
+    let first_new = state
+        .last_pushed_date
+        .as_deref()
+        .and_then(parse_day)
+        .into_iter()
+        .chain(pending.iter().copied())
+        .max()
+        .and_then(|last| last.succ_opt())
+        .map_or(yesterday, |next| next.max(earliest));
+    let days = pending
+        .into_iter()
+        .chain(first_new.iter_days().take_while(|date| *date <= yesterday));
+
+    let mut result = Ok(());
+    for date in days {
+        if let Err(error) = push_day(storage, &config.destinations, &mut state, date).await {
+            result = Err(error);
+        }
+    }
+
+    state.last_error = result.as_ref().err().cloned();
+    storage.save_push_state(&state)?;
+    result
+}
+
+/// Runs [`push_due_days`] shortly after launch and then periodically, so a
+/// day is pushed soon after midnight or on the next start.
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let storage = app.state::<StorageService>();
+            let result = match storage.load_preferences() {
+                Ok(preferences) => push_due_days(&storage, preferences.calendar.today()).await,
+                Err(error) => Err(error),
+            };
+            if let Err(error) = &result {
+                log::error!("End-of-day push failed: {error}");
+            }
+            service_health::report(&app, BackgroundService::EndOfDay, &result);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{StorageServiceBuilder, TestStorage, UserPreferences};
+
+    fn storage() -> TestStorage {
+        let mut preferences = UserPreferences::default();
+        preferences.end_of_day = EndOfDayConfig {
+            enabled: true,
+            destinations: vec![
+                PushDestination {
+                    id: "queue".to_string(),
+                    target: PushTarget::Queue {
+                        name: "days".to_string(),
+                    },
+                },
+                // Fails until an export directory is chosen.
+                PushDestination {
+                    id: "drop".to_string(),
+                    target: PushTarget::FileDrop,
+                },
+            ],
+        };
+        StorageServiceBuilder::new()
+            .preferences(preferences)
+            .build()
+            .unwrap()
+    }
+
+    fn queue_only(enabled: bool) -> TestStorage {
+        let mut preferences = UserPreferences::default();
+        preferences.end_of_day = EndOfDayConfig {
+            enabled,
+            destinations: vec![PushDestination {
+                id: "queue".to_string(),
+                target: PushTarget::Queue {
+                    name: "days".to_string(),
+                },
+            }],
+        };
+        StorageServiceBuilder::new()
+            .preferences(preferences)
+            .build()
+            .unwrap()
+    }
+
+    fn date(day: &str) -> NaiveDate {
+        parse_day(day).unwrap()
+    }
+
+    fn push(storage: &StorageService, today: &str) -> Result<(), String> {
+        tauri::async_runtime::block_on(push_due_days(storage, date(today)))
+    }
+
+    fn queued(storage: &StorageService) -> usize {
+        fs::read_dir(storage.queue_dir("days").unwrap())
+            .unwrap()
+            .count()
+    }
+
+    #[test]
+    fn a_failing_destination_does_not_hold_up_the_others() {
+        let storage = storage();
+        storage
+            .save_push_state(&PushState {
+                last_pushed_date: Some("2026-03-01".to_string()),
+                ..PushState::default()
+            })
+            .unwrap();
+
+        assert!(push(&storage, "2026-03-04").is_err());
+        assert_eq!(queued(&storage), 2);
+        let state = storage.load_push_state().unwrap();
+        assert_eq!(
+            state.delivered.keys().collect::<Vec<_>>(),
+            ["2026-03-02", "2026-03-03"]
+        );
+        assert!(state.last_error.is_some());
+    }
+
+    #[test]
+    fn pending_days_are_retried_however_old() {
+        let storage = storage();
+        storage
+            .save_push_state(&PushState {
+                last_pushed_date: Some("2026-03-01".to_string()),
+                ..PushState::default()
+            })
+            .unwrap();
+        assert!(push(&storage, "2026-03-03").is_err());
+
+        let export = tempfile::tempdir().unwrap();
+        let dir = export.path();
+        storage
+            .update_preferences(|preferences| {
+                preferences.export_dir = Some(dir.display().to_string());
+            })
+            .unwrap();
+        push(&storage, "2026-04-01").unwrap();
+
+        assert!(dir
+            .join(format!("{}.json", idempotency_key("2026-03-02")))
+            .exists());
+        let state = storage.load_push_state().unwrap();
+        assert!(state.delivered.is_empty());
+        assert_eq!(state.last_pushed_date.as_deref(), Some("2026-03-31"));
+        assert!(state.last_error.is_none());
+    }
+
+    #[test]
+    fn the_first_run_pushes_yesterday_and_a_disabled_one_nothing() {
+        let storage = queue_only(false);
+        push(&storage, "2026-03-04").unwrap();
+        assert!(storage
+            .load_push_state()
+            .unwrap()
+            .last_pushed_date
+            .is_none());
+
+        let storage = queue_only(true);
+        push(&storage, "2026-03-04").unwrap();
+        assert_eq!(queued(&storage), 1);
+        let message = fs::read_to_string(
+            storage
+                .queue_dir("days")
+                .unwrap()
+                .join(format!("{}.json", idempotency_key("2026-03-03"))),
+        )
+        .unwrap();
+        let message: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(message["idempotency_key"], "tempus-ring-day-2026-03-03");
+        assert_eq!(message["statistic"]["date"], "2026-03-03");
+
+        // Nothing new the same day.
+        push(&storage, "2026-03-04").unwrap();
+        assert_eq!(queued(&storage), 1);
+    }
+
+    #[test]
+    fn a_long_absence_catches_up_a_week_at_most() {
+        let storage = queue_only(true);
+        storage
+            .save_push_state(&PushState {
+                last_pushed_date: Some("2026-03-01".to_string()),
+                ..PushState::default()
+            })
+            .unwrap();
+        push(&storage, "2026-04-01").unwrap();
+
+        assert_eq!(queued(&storage), MAX_CATCH_UP_DAYS as usize);
+        let queue = storage.queue_dir("days").unwrap();
+        assert!(queue
+            .join(format!("{}.json", idempotency_key("2026-03-25")))
+            .exists());
+        assert!(!queue
+            .join(format!("{}.json", idempotency_key("2026-03-24")))
+            .exists());
+        let state = storage.load_push_state().unwrap();
+        assert_eq!(state.last_pushed_date.as_deref(), Some("2026-03-31"));
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::timer_state::{SessionType, TimerConfig};
+
+pub const HOURS_PER_DAY: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum EnergyLevel {
+    High,
+    Normal,
+    Low,
+}
+
+/// The user's typical energy through the day, one level per hour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct EnergyTemplate {
+    /// Exactly [`HOURS_PER_DAY`] entries, starting at midnight.
+    pub hours: Vec<EnergyLevel>,
+    /// What to schedule in low-energy windows.
+    pub low_energy_session: SessionType,
+}
+
+impl Default for EnergyTemplate {
+    fn default() -> Self {
+        let hours = (0..HOURS_PER_DAY)
+            .map(|hour| match hour {
+                9..=11 => EnergyLevel::High,
+                14 | 15 => EnergyLevel::Low,
+                _ => EnergyLevel::Normal,
+            })
+            .collect();
+        Self {
+            hours,
+            low_energy_session: SessionType::Custom("admin".to_string()),
+        }
+    }
+}
+
+/// A suggested run of sessions inside one energy window.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PlanBlock {
+    /// `HH:MM`, local time.
+    pub start: String,
+    pub end: String,
+    /// Same bounds as minutes since local midnight.
+    pub start_minute: u32,
+    pub end_minute: u32,
+    pub energy: EnergyLevel,
+    pub session_type: SessionType,
+    pub sessions: u32,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct EnergyPlan {
+    pub date: String,
+    pub blocks: Vec<PlanBlock>,
+}
+
+fn clock_label(minutes: u32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Schedules deep work in high-energy windows and the template's
+/// low-energy session type in low windows; normal hours are left free.
+///
+/// When `date` is today, time that has already passed (per `now`) is left
+/// out of the plan.
+pub fn build_energy_plan(
+    date: NaiveDate,
+    now: NaiveDateTime,
+    template: &EnergyTemplate,
+    config: &TimerConfig,
+) -> EnergyPlan {
+    let day_start = if date == now.date() {
+        now.hour() * 60 + now.minute()
+    } else if date < now.date() {
+        (HOURS_PER_DAY * 60) as u32
+    } else {
+        0
+    };
+
+    let mut blocks = Vec::new();
+    let mut hour = 0;
+    while hour < template.hours.len() {
+        let energy = template.hours[hour];
+        let window_end = template.hours[hour..]
+            .iter()
+            .position(|&level| level != energy)
+            .map_or(template.hours.len(), |offset| hour + offset);
+        let start = ((hour * 60) as u32).max(day_start);
+        let end = (window_end * 60) as u32;
+        hour = window_end;
+
+        let (session_type, slot_secs) = match energy {
+            EnergyLevel::High => (
+                SessionType::Work,
+                config.work_duration + config.short_break_duration,
+            ),
+            EnergyLevel::Low => {
+                let session_type = template.low_energy_session.clone();
+                let duration = config.duration_for(&session_type);
+                (session_type, duration)
+            }
+            EnergyLevel::Normal => continue,
+        };
+        let available = u64::from(end.saturating_sub(start)) * 60;
+        let sessions = (available / slot_secs.max(1)) as u32;
+        if sessions > 0 {
+            blocks.push(PlanBlock {
+                start: clock_label(start),
+                end: clock_label(end),
+                start_minute: start,
+                end_minute: end,
+                energy,
+                session_type,
+                sessions,
+            });
+        }
+    }
+
+    EnergyPlan {
+        date: date.format("%Y-%m-%d").to_string(),
+        blocks,
+    }
+}
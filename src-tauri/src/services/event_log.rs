@@ -0,0 +1,59 @@
+//! Audit trail of timer transitions, kept in `events.jsonl` so a lost or
+//! miscounted pomodoro can be traced afterwards.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::calendar::CalendarSettings;
+use super::timer_state::SessionType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerEventKind {
+    Start,
+    Pause,
+    Resume,
+    Reset,
+    /// Finished, either by running out or through `complete_session`.
+    Complete,
+    Skip,
+}
+
+impl TimerEventKind {
+    /// The snake_case name, as serialized.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Reset => "reset",
+            Self::Complete => "complete",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TimerEvent {
+    pub kind: TimerEventKind,
+    /// The session the transition applied to.
+    pub session_type: SessionType,
+    /// Seconds left in that session when it happened.
+    pub remaining_time: u64,
+    pub timestamp: u64,
+}
+
+/// Whether `event`'s day under `calendar` falls within `from..=to`; open
+/// ends are unbounded.
+pub fn in_range(
+    event: &TimerEvent,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    calendar: &CalendarSettings,
+) -> bool {
+    let date = calendar.day_of(event.timestamp);
+    from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+}
@@ -0,0 +1,241 @@
+//! A/B experiments between two timer configurations, e.g. 25 against 40
+//! minute sessions. The arms alternate day by day over the experiment's
+//! period; sessions started under an arm are tagged `<experiment>:<arm>`,
+//! and the report compares how the arms went.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::calendar::{day_key, parse_day};
+use super::session_types::{counts_as_pomodoro, CustomSessionType};
+use super::storage::TimerStatistic;
+use super::timer_state::TimerConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ExperimentArm {
+    /// Lowercase, e.g. `short`; part of the session tag.
+    pub name: String,
+    pub config: TimerConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Experiment {
+    /// Lowercase, e.g. `session-length`; part of the session tag.
+    pub name: String,
+    /// Exactly two. The first runs on the start date.
+    pub arms: Vec<ExperimentArm>,
+    /// `YYYY-MM-DD`.
+    pub start_date: String,
+    /// Length of the period in days.
+    pub days: u32,
+}
+
+impl Experiment {
+    pub fn start(&self) -> Option<NaiveDate> {
+        parse_day(&self.start_date)
+    }
+
+    /// The last day of the period.
+    pub fn end(&self) -> Option<NaiveDate> {
+        self.start()
+            .map(|start| start + chrono::Days::new(u64::from(self.days.saturating_sub(1))))
+    }
+
+    /// The last day of the period, `YYYY-MM-DD`.
+    pub fn end_date(&self) -> String {
+        self.end().map_or_else(|| self.start_date.clone(), day_key)
+    }
+
+    /// Index of the arm that runs on `date`, if it falls in the period.
+    fn arm_index_on(&self, date: NaiveDate) -> Option<usize> {
+        let offset = (date - self.start()?).num_days();
+        if offset < 0 || offset >= i64::from(self.days) || self.arms.is_empty() {
+            return None;
+        }
+        Some(offset as usize % self.arms.len())
+    }
+
+    /// The arm that runs on `date`, if it falls in the period.
+    pub fn arm_on(&self, date: NaiveDate) -> Option<&ExperimentArm> {
+        self.arm_index_on(date).map(|index| &self.arms[index])
+    }
+
+    /// The tag sessions started under `arm` carry.
+    pub fn tag(&self, arm: &ExperimentArm) -> String {
+        format!("{}:{}", self.name, arm.name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ArmResult {
+    pub name: String,
+    /// Days of the period so far that ran this arm.
+    pub days: u32,
+    /// Focus sessions recorded under the arm, finished or not.
+    pub sessions: u32,
+    pub completed: u32,
+    /// `completed / sessions`, or 0 without sessions.
+    pub completion_rate: f64,
+    /// Seconds of completed focus.
+    pub focus_time: u64,
+    /// Minutes of completed focus per day the arm ran.
+    pub focus_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ExperimentReport {
+    pub experiment: Experiment,
+    pub end_date: String,
+    /// The period is over.
+    pub finished: bool,
+    pub arms: Vec<ArmResult>,
+}
+
+/// Compares the arms of `experiment` over `statistics`, counting the days
+/// of the period up to `today`.
+pub fn report(
+    experiment: &Experiment,
+    statistics: &[TimerStatistic],
+    custom_types: &[CustomSessionType],
+    today: NaiveDate,
+) -> ExperimentReport {
+    let mut days = vec![0u32; experiment.arms.len()];
+    if let Some(start) = experiment.start() {
+        for date in start.iter_days().take(experiment.days as usize) {
+            if date > today {
+                break;
+            }
+            if let Some(index) = experiment.arm_index_on(date) {
+                days[index] += 1;
+            }
+        }
+    }
+
+    let arms = experiment
+        .arms
+        .iter()
+        .zip(days)
+        .map(|(arm, days)| {
+            let tag = experiment.tag(arm);
+            let sessions: Vec<_> = statistics
+                .iter()
+                .flat_map(|statistic| &statistic.sessions)
+                .filter(|session| {
+                    session.tags.contains(&tag)
+                        && counts_as_pomodoro(&session.session_type, custom_types)
+                })
+                .collect();
+            let completed = sessions.iter().filter(|session| session.completed).count() as u32;
+            let focus_time: u64 = sessions
+                .iter()
+                .map(|session| session.contribution(custom_types).1)
+                .sum();
+            ArmResult {
+                name: arm.name.clone(),
+                days,
+                sessions: sessions.len() as u32,
+                completed,
+                completion_rate: if sessions.is_empty() {
+                    0.0
+                } else {
+                    f64::from(completed) / sessions.len() as f64
+                },
+                focus_time,
+                focus_score: if days == 0 {
+                    0.0
+                } else {
+                    focus_time as f64 / 60.0 / f64::from(days)
+                },
+            }
+        })
+        .collect();
+
+    ExperimentReport {
+        finished: experiment.end().is_some_and(|end| today > end),
+        end_date: experiment.end_date(),
+        experiment: experiment.clone(),
+        arms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, session, SessionData};
+    use crate::services::timer_state::SessionType;
+
+    fn arm(name: &str, work_minutes: u64) -> ExperimentArm {
+        ExperimentArm {
+            name: name.to_string(),
+            config: TimerConfig {
+                work_duration: work_minutes * 60,
+                ..TimerConfig::default()
+            },
+        }
+    }
+
+    fn experiment() -> Experiment {
+        Experiment {
+            name: "session-length".to_string(),
+            arms: vec![arm("short", 25), arm("long", 40)],
+            start_date: "2026-03-02".to_string(),
+            days: 5,
+        }
+    }
+
+    fn date(key: &str) -> NaiveDate {
+        parse_day(key).unwrap()
+    }
+
+    fn tagged(tag: &str, session_type: SessionType, completed: bool) -> SessionData {
+        SessionData {
+            completed,
+            tags: vec![tag.to_string()],
+            ..session(session_type, 0, 1500)
+        }
+    }
+
+    #[test]
+    fn arms_alternate_by_day_within_the_period() {
+        let experiment = experiment();
+        let arm = |key| experiment.arm_on(date(key)).map(|arm| arm.name.as_str());
+        assert_eq!(arm("2026-03-01"), None);
+        assert_eq!(arm("2026-03-02"), Some("short"));
+        assert_eq!(arm("2026-03-03"), Some("long"));
+        assert_eq!(arm("2026-03-06"), Some("short"));
+        assert_eq!(arm("2026-03-07"), None);
+        assert_eq!(experiment.end_date(), "2026-03-06");
+        assert_eq!(experiment.tag(&experiment.arms[1]), "session-length:long");
+    }
+
+    #[test]
+    fn the_report_scores_each_arm_over_the_days_it_ran() {
+        let experiment = experiment();
+        let statistics = [day(
+            "2026-03-02",
+            vec![
+                tagged("session-length:short", SessionType::Work, true),
+                tagged("session-length:short", SessionType::Work, false),
+                tagged("session-length:short", SessionType::ShortBreak, true),
+                tagged("session-length:long", SessionType::Work, true),
+            ],
+        )];
+
+        let report = report(&experiment, &statistics, &[], date("2026-03-04"));
+        assert!(!report.finished);
+        let short = &report.arms[0];
+        assert_eq!((short.days, short.sessions, short.completed), (2, 2, 1));
+        assert_eq!(short.completion_rate, 0.5);
+        assert_eq!(short.focus_time, 1500);
+        assert_eq!(short.focus_score, 12.5);
+        let long = &report.arms[1];
+        assert_eq!((long.days, long.focus_score), (1, 25.0));
+
+        assert!(super::report(&experiment, &[], &[], date("2026-03-07")).finished);
+    }
+}
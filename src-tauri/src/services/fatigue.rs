@@ -0,0 +1,181 @@
+//! Spots runs of skipped breaks in the timer's transitions and advises a
+//! longer recovery break before fatigue sets in.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::calendar::day_key;
+use super::duration_format::format_duration;
+use super::event_log::{TimerEvent, TimerEventKind};
+use super::inbox::{self, InboxKind};
+use super::storage::StorageService;
+use super::timer_state::SessionType;
+
+/// Emitted with a [`FatigueAdvisory`] when the skipped-break threshold is hit.
+pub const FATIGUE_RISK_EVENT: &str = "analytics://fatigue-risk";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct FatigueThresholds {
+    pub enabled: bool,
+    /// Breaks skipped in a row, without one taken in between, that count
+    /// as a fatigue risk.
+    pub skipped_breaks: u32,
+    /// Length of the suggested recovery break in seconds.
+    pub recovery_break: u64,
+}
+
+impl Default for FatigueThresholds {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            skipped_breaks: 2,
+            recovery_break: 20 * 60,
+        }
+    }
+}
+
+/// Recorded in the day's statistics and sent with [`FATIGUE_RISK_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct FatigueAdvisory {
+    pub timestamp: u64,
+    pub skipped_breaks: u32,
+    /// Suggested recovery break in seconds.
+    pub suggested_break: u64,
+}
+
+/// Counts breaks skipped since the last one that was taken.
+#[derive(Default)]
+pub struct FatigueMonitor {
+    skipped: Mutex<u32>,
+}
+
+impl FatigueMonitor {
+    /// Feeds logged transitions in order. Returns an advisory when the run of
+    /// skipped breaks reaches the threshold, and starts counting afresh.
+    pub fn observe(
+        &self,
+        events: &[TimerEvent],
+        thresholds: &FatigueThresholds,
+    ) -> Result<Option<FatigueAdvisory>, String> {
+        let mut skipped = self
+            .skipped
+            .lock()
+            .map_err(|e| format!("Fatigue monitor lock poisoned: {e}"))?;
+        let mut advisory = None;
+        for event in events {
+            if !matches!(
+                event.session_type,
+                SessionType::ShortBreak | SessionType::LongBreak
+            ) {
+                continue;
+            }
+            match event.kind {
+                TimerEventKind::Skip => *skipped += 1,
+                TimerEventKind::Complete => *skipped = 0,
+                _ => continue,
+            }
+            if thresholds.enabled && *skipped >= thresholds.skipped_breaks.max(1) {
+                advisory = Some(FatigueAdvisory {
+                    timestamp: event.timestamp,
+                    skipped_breaks: std::mem::take(&mut *skipped),
+                    suggested_break: thresholds.recovery_break,
+                });
+            }
+        }
+        Ok(advisory)
+    }
+}
+
+/// Checks newly logged transitions, recording and announcing an advisory if
+/// one is due.
+pub fn check(app: &AppHandle, events: &[TimerEvent]) -> Result<(), String> {
+    let storage = app.state::<StorageService>();
+    let preferences = storage.load_preferences()?;
+    let Some(advisory) = app
+        .state::<FatigueMonitor>()
+        .observe(events, &preferences.fatigue)?
+    else {
+        return Ok(());
+    };
+    let date = day_key(preferences.calendar.day_of(advisory.timestamp));
+    storage.record_advisory(&date, advisory.clone())?;
+    inbox::record_or_log(
+        app,
+        InboxKind::FatigueRisk,
+        format!("{} breaks skipped in a row", advisory.skipped_breaks),
+        Some(format!(
+            "Consider a {} break.",
+            format_duration(
+                advisory.suggested_break,
+                preferences.duration_style,
+                &preferences.language
+            )
+        )),
+    );
+    app.emit(FATIGUE_RISK_EVENT, advisory)
+        .map_err(|e| format!("Failed to emit {FATIGUE_RISK_EVENT}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: TimerEventKind, session_type: SessionType, timestamp: u64) -> TimerEvent {
+        TimerEvent {
+            kind,
+            session_type,
+            remaining_time: 0,
+            timestamp,
+        }
+    }
+
+    fn skip_break(timestamp: u64) -> TimerEvent {
+        event(TimerEventKind::Skip, SessionType::ShortBreak, timestamp)
+    }
+
+    #[test]
+    fn a_run_of_skipped_breaks_advises_once_and_starts_afresh() {
+        let monitor = FatigueMonitor::default();
+        let thresholds = FatigueThresholds::default();
+        // Work skips don't count, and a break taken resets the run.
+        let events = [
+            skip_break(1),
+            event(TimerEventKind::Skip, SessionType::Work, 2),
+            event(TimerEventKind::Complete, SessionType::LongBreak, 3),
+            skip_break(4),
+        ];
+        assert_eq!(monitor.observe(&events, &thresholds).unwrap(), None);
+
+        // The run carries over between calls.
+        let advisory = monitor.observe(&[skip_break(5)], &thresholds).unwrap();
+        assert_eq!(
+            advisory,
+            Some(FatigueAdvisory {
+                timestamp: 5,
+                skipped_breaks: 2,
+                suggested_break: 20 * 60,
+            })
+        );
+        assert_eq!(
+            monitor.observe(&[skip_break(6)], &thresholds).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn disabled_thresholds_never_advise() {
+        let monitor = FatigueMonitor::default();
+        let thresholds = FatigueThresholds {
+            enabled: false,
+            ..FatigueThresholds::default()
+        };
+        let events: Vec<_> = (0..5).map(skip_break).collect();
+        assert_eq!(monitor.observe(&events, &thresholds).unwrap(), None);
+    }
+}
@@ -0,0 +1,239 @@
+//! Daily and weekly caps on focus time, against overwork. Crossing a cap
+//! raises a warning; under [`BudgetSeverity::Block`] work sessions then no
+//! longer start on their own until the period is over. Both are recorded
+//! in the day's statistics as [`OverworkEvent`]s.
+
+use chrono::{Datelike, Duration};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+use super::calendar::{self, day_key};
+use super::inbox::{self, InboxKind};
+use super::storage::StorageService;
+
+/// Emitted with an [`OverworkEvent`] when a cap is crossed or an auto-start
+/// is refused.
+pub const OVERWORK_EVENT: &str = "analytics://overwork";
+const SECS_PER_HOUR: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetSeverity {
+    /// Only warns.
+    Warn,
+    /// Also refuses to auto-start work sessions while over a cap.
+    #[default]
+    Block,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct FocusBudget {
+    /// Most focus hours a day; 0 for no cap.
+    pub daily_hours: u32,
+    /// Most focus hours from Monday to Sunday; 0 for no cap.
+    pub weekly_hours: u32,
+    pub severity: BudgetSeverity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Day,
+    Week,
+}
+
+impl BudgetPeriod {
+    fn adjective(self) -> &'static str {
+        match self {
+            Self::Day => "Daily",
+            Self::Week => "Weekly",
+        }
+    }
+}
+
+/// Recorded in the day's statistics and sent with [`OVERWORK_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct OverworkEvent {
+    pub timestamp: u64,
+    pub period: BudgetPeriod,
+    /// Focused seconds in the period so far.
+    pub focus_time: u64,
+    /// The cap in seconds.
+    pub cap: u64,
+    /// A work session was kept from starting on its own, rather than the
+    /// cap having just been crossed.
+    pub refused_auto_start: bool,
+}
+
+/// Focused seconds today and this week so far.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FocusUsage {
+    day: u64,
+    week: u64,
+}
+
+impl FocusUsage {
+    /// The caps of `budget` this usage has reached, as the period, its
+    /// focus time and the cap in seconds.
+    fn over(self, budget: &FocusBudget) -> impl Iterator<Item = (BudgetPeriod, u64, u64)> {
+        [
+            (BudgetPeriod::Day, self.day, budget.daily_hours),
+            (BudgetPeriod::Week, self.week, budget.weekly_hours),
+        ]
+        .into_iter()
+        .filter(|(_, _, hours)| *hours > 0)
+        .map(|(period, focus_time, hours)| (period, focus_time, u64::from(hours) * SECS_PER_HOUR))
+        .filter(|(_, focus_time, cap)| focus_time >= cap)
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Focus recorded today and in the week up to today.
+pub fn usage(storage: &StorageService) -> Result<FocusUsage, String> {
+    let date = storage.load_preferences()?.calendar.today();
+    let week_start = date - Duration::days(date.weekday().num_days_from_monday().into());
+    let days = storage.load_statistics(Some(week_start), Some(date))?;
+    let today = day_key(date);
+    Ok(FocusUsage {
+        day: days
+            .iter()
+            .filter(|day| day.date == today)
+            .map(|day| day.total_focus_time)
+            .sum(),
+        week: days.iter().map(|day| day.total_focus_time).sum(),
+    })
+}
+
+fn record(app: &AppHandle, storage: &StorageService, event: OverworkEvent) -> Result<(), String> {
+    storage.record_overwork(&calendar::today(storage)?, event.clone())?;
+    inbox::record_or_log(
+        app,
+        InboxKind::Overwork,
+        format!(
+            "{} focus cap of {} hours reached",
+            event.period.adjective(),
+            event.cap / SECS_PER_HOUR
+        ),
+        event
+            .refused_auto_start
+            .then(|| "The next work session wasn't started automatically.".to_string()),
+    );
+    app.emit(OVERWORK_EVENT, event)
+        .map_err(|e| format!("Failed to emit {OVERWORK_EVENT}: {e}"))
+}
+
+/// Warns about each cap crossed since `before` was taken.
+pub fn announce_if_exceeded(
+    app: &AppHandle,
+    storage: &StorageService,
+    before: FocusUsage,
+) -> Result<(), String> {
+    let budget = storage.load_preferences()?.focus_budget;
+    for (period, focus_time, cap) in usage(storage)?.over(&budget) {
+        if before.over(&budget).any(|(earlier, ..)| earlier == period) {
+            continue;
+        }
+        let event = OverworkEvent {
+            timestamp: unix_now(),
+            period,
+            focus_time,
+            cap,
+            refused_auto_start: false,
+        };
+        record(app, storage, event)?;
+    }
+    Ok(())
+}
+
+/// Whether a work session may start on its own. A refusal is recorded.
+pub fn allows_auto_start(app: &AppHandle, storage: &StorageService) -> Result<bool, String> {
+    let budget = storage.load_preferences()?.focus_budget;
+    if budget.severity != BudgetSeverity::Block {
+        return Ok(true);
+    }
+    let Some((period, focus_time, cap)) = usage(storage)?.over(&budget).next() else {
+        return Ok(true);
+    };
+    let event = OverworkEvent {
+        timestamp: unix_now(),
+        period,
+        focus_time,
+        cap,
+        refused_auto_start: true,
+    };
+    record(app, storage, event)?;
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, StorageServiceBuilder, TimerStatistic, UserPreferences};
+
+    const HOUR: u64 = SECS_PER_HOUR;
+
+    fn budget(daily_hours: u32, weekly_hours: u32) -> FocusBudget {
+        FocusBudget {
+            daily_hours,
+            weekly_hours,
+            severity: BudgetSeverity::Block,
+        }
+    }
+
+    fn periods(usage: FocusUsage, budget: &FocusBudget) -> Vec<BudgetPeriod> {
+        usage.over(budget).map(|(period, ..)| period).collect()
+    }
+
+    fn focused(date: chrono::NaiveDate, total_focus_time: u64) -> TimerStatistic {
+        TimerStatistic {
+            total_focus_time,
+            ..day(&day_key(date), Vec::new())
+        }
+    }
+
+    #[test]
+    fn a_cap_is_reached_at_its_hours_and_zero_means_none() {
+        let usage = FocusUsage {
+            day: 4 * HOUR,
+            week: 20 * HOUR,
+        };
+        assert_eq!(periods(usage, &budget(4, 0)), vec![BudgetPeriod::Day]);
+        assert_eq!(periods(usage, &budget(5, 0)), Vec::new());
+        assert_eq!(
+            periods(usage, &budget(4, 20)),
+            vec![BudgetPeriod::Day, BudgetPeriod::Week]
+        );
+        assert_eq!(periods(usage, &budget(0, 21)), Vec::new());
+        assert_eq!(
+            usage.over(&budget(0, 20)).next(),
+            Some((BudgetPeriod::Week, 20 * HOUR, 20 * HOUR))
+        );
+    }
+
+    #[test]
+    fn usage_counts_today_and_the_week_up_to_it() {
+        let today = UserPreferences::default().calendar.today();
+        let storage = StorageServiceBuilder::new()
+            .statistic(focused(today, 2 * HOUR))
+            // Always in an earlier week.
+            .statistic(focused(today - Duration::days(7), 9 * HOUR))
+            .build()
+            .unwrap();
+        let usage = usage(&storage).unwrap();
+        assert_eq!((usage.day, usage.week), (2 * HOUR, 2 * HOUR));
+    }
+}
@@ -0,0 +1,219 @@
+//! Optionally turns on the OS Do Not Disturb mode while a work session is
+//! running and restores it when the break begins or the app exits. The
+//! switching runs on a worker thread, since it shells out.
+
+use std::process::Command;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, OnceLock};
+use std::thread;
+
+use super::session_types::focus_session_active;
+use super::storage::UserPreferences;
+use super::timer_state::TimerData;
+
+/// A platform's Do Not Disturb switch.
+pub trait FocusIntegration: Send + Sync {
+    /// Turns Do Not Disturb on. Returns `false` if it was already on, in
+    /// which case [`FocusIntegration::restore`] won't be called.
+    fn enable(&self) -> Result<bool, String>;
+    /// Undoes a successful [`FocusIntegration::enable`].
+    fn restore(&self) -> Result<(), String>;
+}
+
+/// Runs `program` and returns its trimmed stdout, failing on a non-zero exit.
+pub(super) fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {program}: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// macOS has no public API for Focus, so this runs two shortcuts the user
+/// creates in the Shortcuts app with a "Set Focus" action each.
+pub struct MacShortcuts;
+
+impl MacShortcuts {
+    pub const ENABLE_SHORTCUT: &'static str = "Tempus Ring Focus On";
+    pub const RESTORE_SHORTCUT: &'static str = "Tempus Ring Focus Off";
+}
+
+impl FocusIntegration for MacShortcuts {
+    fn enable(&self) -> Result<bool, String> {
+        run("shortcuts", &["run", Self::ENABLE_SHORTCUT])?;
+        Ok(true)
+    }
+
+    fn restore(&self) -> Result<(), String> {
+        run("shortcuts", &["run", Self::RESTORE_SHORTCUT]).map(|_| ())
+    }
+}
+
+/// GNOME's Do Not Disturb is the `show-banners` notification setting.
+pub struct GnomeBanners;
+
+impl GnomeBanners {
+    const SCHEMA: &'static str = "org.gnome.desktop.notifications";
+    const KEY: &'static str = "show-banners";
+}
+
+impl FocusIntegration for GnomeBanners {
+    fn enable(&self) -> Result<bool, String> {
+        if run("gsettings", &["get", Self::SCHEMA, Self::KEY])? == "false" {
+            return Ok(false);
+        }
+        run("gsettings", &["set", Self::SCHEMA, Self::KEY, "false"])?;
+        Ok(true)
+    }
+
+    fn restore(&self) -> Result<(), String> {
+        run("gsettings", &["set", Self::SCHEMA, Self::KEY, "true"]).map(|_| ())
+    }
+}
+
+/// Platforms without a usable switch. Windows Focus Assist has no public
+/// API, so it lands here too.
+pub struct Unsupported;
+
+impl FocusIntegration for Unsupported {
+    fn enable(&self) -> Result<bool, String> {
+        Err("Do Not Disturb integration is not available on this platform".into())
+    }
+
+    fn restore(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn platform_integration() -> Arc<dyn FocusIntegration> {
+    if cfg!(target_os = "macos") {
+        Arc::new(MacShortcuts)
+    } else if cfg!(target_os = "linux") {
+        Arc::new(GnomeBanners)
+    } else {
+        Arc::new(Unsupported)
+    }
+}
+
+/// Whether Do Not Disturb is on because of us, or `None` once it was
+/// released for exit. Held while it is switched.
+type Engaged = Arc<Mutex<Option<bool>>>;
+
+/// Turns Do Not Disturb on or restores it, unless it already is.
+fn switch(integration: &dyn FocusIntegration, engaged: &Engaged, on: bool) -> Result<(), String> {
+    let mut engaged = engaged
+        .lock()
+        .map_err(|e| format!("Focus mode lock poisoned: {e}"))?;
+    let Some(engaged) = engaged.as_mut() else {
+        return Ok(());
+    };
+    if on {
+        if !*engaged {
+            *engaged = integration.enable()?;
+        }
+    } else if std::mem::take(engaged) {
+        integration.restore()?;
+    }
+    Ok(())
+}
+
+/// Applies each switch sent, in order.
+fn spawn_worker(integration: Arc<dyn FocusIntegration>, engaged: Engaged) -> mpsc::Sender<bool> {
+    let (sender, receiver) = mpsc::channel::<bool>();
+    thread::spawn(move || {
+        for on in receiver {
+            if let Err(error) = switch(integration.as_ref(), &engaged, on) {
+                log::error!("{error}");
+            }
+        }
+    });
+    sender
+}
+
+struct FocusModeState {
+    enabled: bool,
+    /// Whether the last tick wanted Do Not Disturb on.
+    wanted: bool,
+}
+
+/// Follows timer ticks and switches Do Not Disturb on the transitions.
+pub struct FocusModeService {
+    integration: Arc<dyn FocusIntegration>,
+    state: Mutex<FocusModeState>,
+    engaged: Engaged,
+    /// Feeds the worker switching Do Not Disturb, started with the first
+    /// switch.
+    queue: OnceLock<mpsc::Sender<bool>>,
+}
+
+impl FocusModeService {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            integration: platform_integration(),
+            state: Mutex::new(FocusModeState {
+                enabled: preferences.dnd_integration,
+                wanted: false,
+            }),
+            engaged: Arc::new(Mutex::new(Some(false))),
+            queue: OnceLock::new(),
+        }
+    }
+
+    fn switch_later(&self, on: bool) {
+        let sent = self
+            .queue
+            .get_or_init(|| spawn_worker(self.integration.clone(), self.engaged.clone()))
+            .send(on);
+        if sent.is_err() {
+            log::error!("The focus mode worker stopped");
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, FocusModeState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Focus mode lock poisoned: {e}"))
+    }
+
+    /// Picks up the preference; turning it off mid-session restores right away.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.enabled = preferences.dnd_integration;
+        if !state.enabled && std::mem::take(&mut state.wanted) {
+            self.switch_later(false);
+        }
+        Ok(())
+    }
+
+    /// Do Not Disturb stays on while a work session is paused and is only
+    /// restored once a break starts or the timer is reset.
+    pub fn on_tick(&self, data: &TimerData) -> Result<(), String> {
+        let mut state = self.lock()?;
+        let wanted = state.enabled && focus_session_active(data);
+        if wanted == state.wanted {
+            return Ok(());
+        }
+        state.wanted = wanted;
+        self.switch_later(wanted);
+        Ok(())
+    }
+
+    /// Restores Do Not Disturb before the app exits, after any switch
+    /// already under way. Switches still queued are dropped.
+    pub fn release(&self) -> Result<(), String> {
+        self.lock()?.wanted = false;
+        let mut engaged = self
+            .engaged
+            .lock()
+            .map_err(|e| format!("Focus mode lock poisoned: {e}"))?;
+        if engaged.take() == Some(true) {
+            self.integration.restore()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! GitHub issues attached to sessions, to report how much focus time went
+//! into each. Issues are stored as `owner/repo#123`, lowercased since
+//! GitHub names are case-insensitive.
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::session_types::CustomSessionType;
+use super::storage::{SessionData, TimerStatistic};
+
+/// Canonical `owner/repo#123` form of an issue or pull request given as
+/// that shorthand or as its `https://github.com/owner/repo/issues/123`
+/// (or `/pull/123`) URL.
+pub fn parse_issue(value: &str) -> Option<String> {
+    let value = value.trim();
+    let (owner, repo, number) = match value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"))
+    {
+        Some(url) => {
+            let path = url
+                .strip_prefix("www.")
+                .unwrap_or(url)
+                .strip_prefix("github.com/")?;
+            let path = path.split(['?', '#']).next()?;
+            let mut segments = path.trim_end_matches('/').split('/');
+            let (owner, repo, kind, number) = (
+                segments.next()?,
+                segments.next()?,
+                segments.next()?,
+                segments.next()?,
+            );
+            if !matches!(kind, "issues" | "pull") || segments.next().is_some() {
+                return None;
+            }
+            (owner, repo, number)
+        }
+        None => {
+            let (repository, number) = value.split_once('#')?;
+            let (owner, repo) = repository.split_once('/')?;
+            (owner, repo, number)
+        }
+    };
+    let owner_valid =
+        !owner.is_empty() && owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    let repo_valid = !repo.is_empty()
+        && repo != "."
+        && repo != ".."
+        && repo
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    let number: u64 = number.parse().ok().filter(|number| *number > 0)?;
+    (owner_valid && repo_valid).then(|| format!("{owner}/{repo}#{number}").to_lowercase())
+}
+
+/// A session attached to the issue, with the day it was recorded on.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct IssueSession {
+    pub date: String,
+    /// Position in the day's sessions, for editing it.
+    pub index: usize,
+    pub session: SessionData,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct IssueFocus {
+    /// `owner/repo#123`.
+    pub issue: String,
+    /// Completed pomodoros among the sessions.
+    pub pomodoros: u32,
+    /// Focused seconds across the sessions.
+    pub focus_time: u64,
+    /// Oldest first.
+    pub sessions: Vec<IssueSession>,
+}
+
+/// Every session in `statistics` attached to `issue`, which must be in
+/// canonical form, and the focus they add up to.
+pub fn sessions_for_issue(
+    statistics: &[TimerStatistic],
+    issue: &str,
+    custom_types: &[CustomSessionType],
+) -> IssueFocus {
+    let mut focus = IssueFocus {
+        issue: issue.to_string(),
+        pomodoros: 0,
+        focus_time: 0,
+        sessions: Vec::new(),
+    };
+    for statistic in statistics {
+        for (index, session) in statistic.sessions.iter().enumerate() {
+            if session.issue.as_deref() != Some(issue) {
+                continue;
+            }
+            let (pomodoros, focus_time) = session.contribution(custom_types);
+            focus.pomodoros += pomodoros;
+            focus.focus_time += focus_time;
+            focus.sessions.push(IssueSession {
+                date: statistic.date.clone(),
+                index,
+                session: session.clone(),
+            });
+        }
+    }
+    focus.sessions.sort_by_key(|entry| entry.session.start_time);
+    focus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, session};
+    use crate::services::timer_state::SessionType;
+
+    #[test]
+    fn shorthand_and_urls_parse_to_the_same_issue() {
+        for value in [
+            "Tempus-Ring/app.rs#42",
+            " https://github.com/tempus-ring/App.rs/issues/42 ",
+            "http://www.github.com/tempus-ring/app.rs/pull/42/?tab=files#top",
+        ] {
+            assert_eq!(parse_issue(value).as_deref(), Some("tempus-ring/app.rs#42"));
+        }
+        for value in [
+            "tempus-ring/app#0",
+            "tempus-ring/..#1",
+            "tempus ring/app#1",
+            "tempus-ring#1",
+            "https://gitlab.com/tempus-ring/app/issues/1",
+            "https://github.com/tempus-ring/app/commit/1",
+            "https://github.com/tempus-ring/app/issues/1/comments",
+        ] {
+            assert_eq!(parse_issue(value), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn an_issue_gathers_its_sessions_oldest_first() {
+        let work = |issue: &str, start_time, completed| SessionData {
+            issue: Some(issue.to_string()),
+            completed,
+            ..session(SessionType::Work, start_time, 1500)
+        };
+        let statistics = [
+            day(
+                "2026-03-03",
+                vec![work("a/b#2", 300, true), work("a/b#1", 200, true)],
+            ),
+            day("2026-03-02", vec![work("a/b#1", 100, false)]),
+        ];
+
+        let focus = sessions_for_issue(&statistics, "a/b#1", &[]);
+        assert_eq!((focus.pomodoros, focus.focus_time), (1, 1500));
+        let found: Vec<_> = focus
+            .sessions
+            .iter()
+            .map(|entry| (entry.date.as_str(), entry.index))
+            .collect();
+        assert_eq!(found, [("2026-03-02", 0), ("2026-03-03", 1)]);
+    }
+}
@@ -0,0 +1,78 @@
+//! Progress towards the daily pomodoro goal set in the preferences.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+use super::calendar::{self, parse_day};
+use super::inbox::{self, InboxKind};
+use super::storage::StorageService;
+
+/// Emitted with a [`GoalProgress`] when a day's pomodoros reach the goal.
+pub const GOAL_REACHED_EVENT: &str = "goal://reached";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct GoalProgress {
+    pub date: String,
+    pub completed: u32,
+    pub goal: u32,
+    /// Also set on a day off, where the goal doesn't apply.
+    pub reached: bool,
+    pub day_off: bool,
+}
+
+impl GoalProgress {
+    pub fn new(date: &str, completed: u32, goal: u32, day_off: bool) -> Self {
+        Self {
+            date: date.to_string(),
+            completed,
+            goal,
+            reached: day_off || completed >= goal,
+            day_off,
+        }
+    }
+}
+
+/// The day's completed pomodoros against the current goal.
+pub fn progress(storage: &StorageService, date: &str) -> Result<GoalProgress, String> {
+    let completed = storage
+        .load_statistic(date)?
+        .map_or(0, |statistic| statistic.completed_pomodoros);
+    let preferences = storage.load_preferences()?;
+    let day_off = parse_day(date).is_some_and(|day| preferences.days_off.is_day_off(day));
+    Ok(GoalProgress::new(
+        date,
+        completed,
+        preferences.daily_goal,
+        day_off,
+    ))
+}
+
+/// Emits [`GOAL_REACHED_EVENT`] if `date` went from `before` completed
+/// pomodoros to reaching the goal. Nothing is announced on a day off.
+pub fn announce_if_reached(
+    app: &AppHandle,
+    storage: &StorageService,
+    date: &str,
+    before: u32,
+) -> Result<(), String> {
+    if date != calendar::today(storage)? {
+        return Ok(());
+    }
+    let progress = progress(storage, date)?;
+    if progress.day_off || !progress.reached || before >= progress.goal {
+        return Ok(());
+    }
+    inbox::record_or_log(
+        app,
+        InboxKind::GoalReached,
+        "Daily goal reached",
+        Some(format!(
+            "{} of {} pomodoros",
+            progress.completed, progress.goal
+        )),
+    );
+    app.emit(GOAL_REACHED_EVENT, progress)
+        .map_err(|e| format!("Failed to emit {GOAL_REACHED_EVENT}: {e}"))
+}
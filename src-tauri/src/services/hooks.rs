@@ -0,0 +1,381 @@
+//! User-defined shell commands run on timer transitions, such as a script
+//! that dims the lights when a work session starts. Each runs through the
+//! shell on one worker thread off the tick loop, is killed after its
+//! timeout together with whatever it started, and has its exit code and
+//! output kept for `get_hooks`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Mutex, MutexGuard, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::event_log::{TimerEvent, TimerEventKind};
+use super::service_health::{self, BackgroundService};
+use super::session_types::{counts_as_pomodoro, CustomSessionType};
+use super::storage::UserPreferences;
+use super::timer_state::SessionType;
+
+/// Runs kept for `get_hooks`, newest first.
+const RECENT_RUNS: usize = 20;
+/// Bytes of each output stream kept per run.
+const MAX_OUTPUT_BYTES: u64 = 4096;
+const WAIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Hooks due for one tick's transitions, in order.
+type Batch = Vec<(TimerHook, TimerEvent)>;
+
+/// Which sessions a hook runs for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum HookSessions {
+    #[default]
+    Any,
+    /// Sessions that count as pomodoros.
+    Focus,
+    Break,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TimerHook {
+    pub event: TimerEventKind,
+    pub sessions: HookSessions,
+    /// Run through the shell, with `TEMPUS_EVENT`, `TEMPUS_SESSION_TYPE`
+    /// and `TEMPUS_REMAINING` (seconds) set.
+    pub command: String,
+    /// Seconds before it is killed.
+    pub timeout_secs: u32,
+}
+
+impl TimerHook {
+    fn matches(&self, event: &TimerEvent, custom_types: &[CustomSessionType]) -> bool {
+        self.event == event.kind
+            && match self.sessions {
+                HookSessions::Any => true,
+                HookSessions::Focus => counts_as_pomodoro(&event.session_type, custom_types),
+                HookSessions::Break => !counts_as_pomodoro(&event.session_type, custom_types),
+            }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct HookRun {
+    pub command: String,
+    pub event: TimerEventKind,
+    pub session_type: SessionType,
+    /// Unix time of the transition.
+    pub timestamp: u64,
+    /// `None` if it didn't start, was killed or ended by a signal.
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// The start of its output, up to 4 KiB each.
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl HookRun {
+    fn failure(&self, timeout_secs: u32) -> Option<String> {
+        match self.exit_code {
+            Some(0) => None,
+            _ if self.timed_out => Some(format!(
+                "`{}` was killed after {timeout_secs}s",
+                self.command
+            )),
+            Some(code) => Some(format!("`{}` exited with {code}", self.command)),
+            None if self.stderr.is_empty() => {
+                Some(format!("`{}` was ended by a signal", self.command))
+            }
+            None => Some(self.stderr.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct HookStatus {
+    pub hooks: Vec<TimerHook>,
+    /// The latest runs, newest first.
+    pub recent_runs: Vec<HookRun>,
+}
+
+/// Reads `pipe` to the end so the hook never blocks on it, keeping the
+/// first `MAX_OUTPUT_BYTES`.
+fn capture(mut pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut kept = Vec::new();
+        let _ = (&mut pipe).take(MAX_OUTPUT_BYTES).read_to_end(&mut kept);
+        let _ = io::copy(&mut pipe, &mut io::sink());
+        String::from_utf8_lossy(&kept).into_owned()
+    })
+}
+
+/// Kills `child` and the processes it started.
+fn kill_tree(child: &mut Child) {
+    // The hook leads its own process group, see `run`.
+    #[cfg(unix)]
+    // SAFETY: killpg only sends a signal.
+    unsafe {
+        libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+    }
+    #[cfg(windows)]
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &child.id().to_string()])
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn run(hook: &TimerHook, event: &TimerEvent) -> HookRun {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.args(["/C", &hook.command]);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.args(["-c", &hook.command]);
+        process
+    };
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut process, 0);
+    process
+        .env("TEMPUS_EVENT", event.kind.key())
+        .env("TEMPUS_SESSION_TYPE", event.session_type.key())
+        .env("TEMPUS_REMAINING", event.remaining_time.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut result = HookRun {
+        command: hook.command.clone(),
+        event: event.kind,
+        session_type: event.session_type.clone(),
+        timestamp: event.timestamp,
+        exit_code: None,
+        timed_out: false,
+        stdout: String::new(),
+        stderr: String::new(),
+    };
+    let mut child = match process.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            result.stderr = format!("Failed to run `{}`: {error}", hook.command);
+            return result;
+        }
+    };
+    let stdout = child.stdout.take().map(capture);
+    let stderr = child.stderr.take().map(capture);
+    let deadline = Instant::now() + Duration::from_secs(u64::from(hook.timeout_secs));
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                result.exit_code = status.code();
+                break;
+            }
+            Ok(None) if Instant::now() < deadline => thread::sleep(WAIT_INTERVAL),
+            Ok(None) => {
+                kill_tree(&mut child);
+                // Whatever it started may still hold the pipes open, so
+                // its output isn't waited for.
+                result.timed_out = true;
+                return result;
+            }
+            Err(error) => {
+                result.stderr = format!("Failed to wait for `{}`: {error}", hook.command);
+                return result;
+            }
+        }
+    }
+    let output = |reader: Option<JoinHandle<String>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    result.stdout = output(stdout);
+    result.stderr = output(stderr);
+    result
+}
+
+struct HookState {
+    hooks: Vec<TimerHook>,
+    runs: VecDeque<HookRun>,
+}
+
+/// Runs the hooks configured for timer transitions and keeps their latest
+/// results.
+pub struct HookRunner {
+    state: Mutex<HookState>,
+    /// Feeds the thread that runs hooks, started with the first batch.
+    queue: OnceLock<mpsc::Sender<Batch>>,
+}
+
+impl HookRunner {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(HookState {
+                hooks: preferences.hooks.clone(),
+                runs: VecDeque::new(),
+            }),
+            queue: OnceLock::new(),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HookState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Hook runner lock poisoned: {e}"))
+    }
+
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        self.lock()?.hooks = preferences.hooks.clone();
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<HookStatus, String> {
+        let state = self.lock()?;
+        Ok(HookStatus {
+            hooks: state.hooks.clone(),
+            recent_runs: state.runs.iter().cloned().collect(),
+        })
+    }
+
+    fn record(&self, run: HookRun) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.runs.push_front(run);
+        state.runs.truncate(RECENT_RUNS);
+        Ok(())
+    }
+}
+
+/// Runs the hooks of each batch sent, in the order sent.
+fn spawn_worker(app: AppHandle) -> mpsc::Sender<Batch> {
+    let (sender, receiver) = mpsc::channel::<Batch>();
+    thread::spawn(move || {
+        for (hook, event) in receiver.into_iter().flatten() {
+            let result = run(&hook, &event);
+            let outcome = result.failure(hook.timeout_secs).map_or(Ok(()), Err);
+            if let Err(error) = &outcome {
+                log::error!("Hook failed: {error}");
+            }
+            service_health::report(&app, BackgroundService::Hooks, &outcome);
+            if let Err(error) = app.state::<HookRunner>().record(result) {
+                log::error!("{error}");
+            }
+        }
+    });
+    sender
+}
+
+/// Called by the tick loop with newly logged transitions: queues the hooks
+/// they match to run in the background, one at a time and in order,
+/// reporting repeated failures in the inbox.
+pub fn fire(
+    app: &AppHandle,
+    events: &[TimerEvent],
+    custom_types: &[CustomSessionType],
+) -> Result<(), String> {
+    let runner = app.state::<HookRunner>();
+    let hooks = runner.lock()?.hooks.clone();
+    let due: Batch = events
+        .iter()
+        .flat_map(|event| {
+            hooks
+                .iter()
+                .filter(|hook| hook.matches(event, custom_types))
+                .map(|hook| (hook.clone(), event.clone()))
+        })
+        .collect();
+    if due.is_empty() {
+        return Ok(());
+    }
+    runner
+        .queue
+        .get_or_init(|| spawn_worker(app.clone()))
+        .send(due)
+        .map_err(|_| "The hook worker stopped".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(command: &str, sessions: HookSessions, timeout_secs: u32) -> TimerHook {
+        TimerHook {
+            event: TimerEventKind::Complete,
+            sessions,
+            command: command.to_string(),
+            timeout_secs,
+        }
+    }
+
+    fn event(kind: TimerEventKind, session_type: SessionType) -> TimerEvent {
+        TimerEvent {
+            kind,
+            session_type,
+            remaining_time: 0,
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn hooks_match_their_event_and_sessions() {
+        let work = event(TimerEventKind::Complete, SessionType::Work);
+        let short_break = event(TimerEventKind::Complete, SessionType::ShortBreak);
+        let focus = hook("true", HookSessions::Focus, 5);
+        assert!(focus.matches(&work, &[]));
+        assert!(!focus.matches(&short_break, &[]));
+        assert!(hook("true", HookSessions::Break, 5).matches(&short_break, &[]));
+        assert!(hook("true", HookSessions::Any, 5).matches(&short_break, &[]));
+        assert!(!focus.matches(&event(TimerEventKind::Start, SessionType::Work), &[]));
+    }
+
+    #[test]
+    fn failures_describe_how_the_hook_ended() {
+        let mut result = HookRun {
+            command: "true".to_string(),
+            event: TimerEventKind::Complete,
+            session_type: SessionType::Work,
+            timestamp: 1_700_000_000,
+            exit_code: Some(0),
+            timed_out: false,
+            stdout: String::new(),
+            stderr: String::new(),
+        };
+        assert_eq!(result.failure(5), None);
+        result.exit_code = Some(3);
+        assert_eq!(result.failure(5).as_deref(), Some("`true` exited with 3"));
+        result.exit_code = None;
+        result.timed_out = true;
+        assert_eq!(
+            result.failure(5).as_deref(),
+            Some("`true` was killed after 5s")
+        );
+        result.timed_out = false;
+        assert_eq!(
+            result.failure(5).as_deref(),
+            Some("`true` was ended by a signal")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_capture_output_and_time_out() {
+        let work = event(TimerEventKind::Complete, SessionType::Work);
+        let result = run(
+            &hook("echo \"$TEMPUS_EVENT\"; exit 2", HookSessions::Any, 5),
+            &work,
+        );
+        assert_eq!(result.exit_code, Some(2));
+        assert_eq!(result.stdout, "complete\n");
+
+        let started = Instant::now();
+        let result = run(&hook("sleep 30 & sleep 30", HookSessions::Any, 1), &work);
+        assert!(result.timed_out);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
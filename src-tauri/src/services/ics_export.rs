@@ -0,0 +1,204 @@
+//! Renders completed focus sessions as iCalendar (RFC 5545) events, for
+//! overlaying focus time on a calendar or importing it into one.
+
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+use super::session_types::{counts_as_pomodoro, find_custom, CustomSessionType};
+use super::storage::{SessionData, TimerStatistic};
+use super::storage_file;
+use super::tasks::Task;
+use super::timer_state::SessionType;
+
+/// Longest content line in octets before it must be folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Escapes `,`, `;`, `\` and newlines in a TEXT value.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Appends `line` with CRLF, folding it into continuation lines that start
+/// with a space so no line exceeds [`MAX_LINE_OCTETS`].
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn utc_stamp(unix: u64) -> String {
+    DateTime::<Utc>::from_timestamp(unix as i64, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// The event title: the session's task if it had one, else its type.
+fn summary(session: &SessionData, tasks: &[Task], custom_types: &[CustomSessionType]) -> String {
+    let task = session
+        .task_id
+        .as_ref()
+        .and_then(|id| tasks.iter().find(|task| &task.id == id));
+    if let Some(task) = task {
+        return format!("Focus: {}", task.title);
+    }
+    match &session.session_type {
+        SessionType::Custom(id) => {
+            find_custom(custom_types, id).map_or_else(|| id.clone(), |custom| custom.name.clone())
+        }
+        session_type => session_type.display_name().to_string(),
+    }
+}
+
+/// The calendar for every completed session in `statistics` that counts as
+/// a pomodoro, and how many events it holds.
+pub fn render(
+    statistics: &[TimerStatistic],
+    tasks: &[Task],
+    custom_types: &[CustomSessionType],
+    now: u64,
+) -> (String, usize) {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//Tempus Ring//Focus sessions//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+
+    let mut events = 0;
+    let stamp = utc_stamp(now);
+    let sessions = statistics.iter().flat_map(|day| &day.sessions);
+    for session in sessions.filter(|session| {
+        session.completed && counts_as_pomodoro(&session.session_type, custom_types)
+    }) {
+        push_line(&mut out, "BEGIN:VEVENT");
+        // Stable across exports, so re-importing updates instead of duplicating.
+        push_line(
+            &mut out,
+            &format!(
+                "UID:{}-{}@tempus-ring",
+                session.start_time,
+                session.session_type.key()
+            ),
+        );
+        push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+        push_line(
+            &mut out,
+            &format!("DTSTART:{}", utc_stamp(session.start_time)),
+        );
+        push_line(
+            &mut out,
+            &format!("DTEND:{}", utc_stamp(session.span_end())),
+        );
+        push_line(
+            &mut out,
+            &format!(
+                "SUMMARY:{}",
+                escape_text(&summary(session, tasks, custom_types))
+            ),
+        );
+        if !session.tags.is_empty() {
+            let tags: Vec<String> = session.tags.iter().map(|tag| escape_text(tag)).collect();
+            push_line(&mut out, &format!("CATEGORIES:{}", tags.join(",")));
+        }
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+        events += 1;
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    (out, events)
+}
+
+/// Writes the calendar from [`render`] to `path`. Returns the number of
+/// events written.
+pub fn export_ics(
+    path: &Path,
+    statistics: &[TimerStatistic],
+    tasks: &[Task],
+    custom_types: &[CustomSessionType],
+    now: u64,
+) -> Result<usize, String> {
+    let (calendar, events) = render(statistics, tasks, custom_types, now);
+    storage_file::replace(path, calendar.as_bytes())?;
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, session};
+
+    fn essay(session_type: SessionType, completed: bool) -> SessionData {
+        SessionData {
+            completed,
+            task_id: Some("essay".to_string()),
+            tags: vec!["writing".to_string(), "uni, year 2".to_string()],
+            ..session(session_type, 1_772_442_000, 1500)
+        }
+    }
+
+    #[test]
+    fn text_is_escaped_and_long_lines_folded_between_characters() {
+        assert_eq!(escape_text("a,b;c\\d\r\ne"), r"a\,b\;c\\d\ne");
+
+        let mut out = String::new();
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        push_line(&mut out, &line);
+        let lines: Vec<&str> = out.trim_end_matches("\r\n").split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines.concat().replacen(' ', "", 1), line);
+    }
+
+    #[test]
+    fn only_completed_focus_sessions_become_events() {
+        let statistic = day(
+            "2026-03-02",
+            vec![
+                essay(SessionType::Work, true),
+                essay(SessionType::Work, false),
+                essay(SessionType::ShortBreak, true),
+            ],
+        );
+        let tasks = [Task {
+            id: "essay".to_string(),
+            title: "Essay".to_string(),
+            estimated_pomodoros: 0,
+            completed_pomodoros: 0,
+            completed: false,
+            created_at: 0,
+            completed_at: None,
+            archived_at: None,
+            revision: 0,
+        }];
+        let (calendar, events) = render(&[statistic], &tasks, &[], 1_772_500_000);
+        assert_eq!(events, 1);
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("\r\nUID:1772442000-work@tempus-ring\r\n"));
+        assert!(calendar.contains("\r\nDTSTART:20260302T090000Z\r\n"));
+        assert!(calendar.contains("\r\nDTEND:20260302T092500Z\r\n"));
+        assert!(calendar.contains("\r\nSUMMARY:Focus: Essay\r\n"));
+        assert!(calendar.contains("\r\nCATEGORIES:writing,uni\\, year 2\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    }
+}
@@ -0,0 +1,113 @@
+//! A persistent inbox of the notifications and advisories the app raised,
+//! so a toast that went unseen can still be found later. Entries stay
+//! until they are pushed out by newer ones.
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::storage::StorageService;
+
+/// Emitted with each new [`InboxEntry`].
+pub const INBOX_EVENT: &str = "inbox://added";
+/// Entries kept; the oldest are dropped first.
+pub const MAX_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxKind {
+    /// A session ended or a reminder was shown.
+    Notification,
+    GoalReached,
+    ProjectGoalReached,
+    FatigueRisk,
+    /// The weekly burnout-risk score is trending badly.
+    BurnoutRisk,
+    /// A daily or weekly focus cap was reached.
+    Overwork,
+    /// A long session started, or was held back, on low battery.
+    LowBattery,
+    /// Writes are failing and being held in memory.
+    StorageDegraded,
+    /// A push, backup or other integration failed.
+    IntegrationFailed,
+}
+
+impl InboxKind {
+    /// Failures that tend to repeat until fixed.
+    fn collapses(self) -> bool {
+        matches!(self, Self::StorageDegraded | Self::IntegrationFailed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct InboxEntry {
+    pub id: String,
+    pub kind: InboxKind,
+    pub title: String,
+    pub body: Option<String>,
+    /// Unix seconds.
+    pub created_at: u64,
+    pub read: bool,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Adds an entry and emits [`INBOX_EVENT`]. A failure matching an unread
+/// entry isn't added again, so one that recurs every few minutes shows up
+/// once until it is read.
+pub fn record(
+    app: &AppHandle,
+    kind: InboxKind,
+    title: impl Into<String>,
+    body: Option<String>,
+) -> Result<(), String> {
+    let entry = InboxEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind,
+        title: title.into(),
+        body,
+        created_at: unix_now(),
+        read: false,
+    };
+    let mut added = false;
+    app.state::<StorageService>().update_inbox(|entries| {
+        if entry.kind.collapses()
+            && entries
+                .iter()
+                .any(|other| !other.read && other.kind == entry.kind && other.title == entry.title)
+        {
+            return;
+        }
+        entries.push(entry.clone());
+        let excess = entries.len().saturating_sub(MAX_ENTRIES);
+        entries.drain(..excess);
+        added = true;
+    })?;
+    if added {
+        app.emit(INBOX_EVENT, entry)
+            .map_err(|e| format!("Failed to emit {INBOX_EVENT}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Records an entry, logging rather than returning a failure, for callers
+/// already reporting something else.
+pub fn record_or_log(
+    app: &AppHandle,
+    kind: InboxKind,
+    title: impl Into<String>,
+    body: Option<String>,
+) {
+    if let Err(error) = record(app, kind, title, body) {
+        log::error!("{error}");
+    }
+}
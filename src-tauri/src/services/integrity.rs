@@ -0,0 +1,262 @@
+//! Content checksums for stored records, and the quarantine corrupt or
+//! tampered records are moved to. A record that fails verification is set
+//! aside with the reason it failed and listed in the [`IntegrityReport`]
+//! rather than skipped, so nothing disappears from the history unnoticed.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+use super::backup;
+use super::backup_crypto;
+use super::backup_format;
+use super::storage::StorageService;
+use super::storage_file;
+
+const CHECKSUM_PREFIX: &str = "sha256:";
+const CHECKSUM_FIELD: &str = "checksum";
+const ISSUES_FILE: &str = "issues.json";
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// `sha256:<hex>` of `value` serialized compactly.
+pub fn checksum(value: &Value) -> String {
+    let digest = Sha256::digest(value.to_string().as_bytes());
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("{CHECKSUM_PREFIX}{hex}")
+}
+
+/// Serializes `record` as pretty JSON with a `checksum` of its contents.
+pub fn seal<T: Serialize>(record: &T) -> Result<String, String> {
+    let mut value =
+        serde_json::to_value(record).map_err(|e| format!("Failed to serialize record: {e}"))?;
+    let sum = checksum(&value);
+    value
+        .as_object_mut()
+        .ok_or("Only JSON objects can carry a checksum")?
+        .insert(CHECKSUM_FIELD.to_string(), Value::String(sum));
+    serde_json::to_string_pretty(&value).map_err(|e| format!("Failed to serialize record: {e}"))
+}
+
+/// The JSON of a record written by [`seal`], without its checksum once
+/// verified. Records written before checksums were added have none and are
+/// accepted as they are; the next save seals them.
+pub fn verify(content: &str) -> Result<Value, String> {
+    let mut value: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let stored = value
+        .as_object_mut()
+        .and_then(|fields| fields.remove(CHECKSUM_FIELD));
+    if let Some(stored) = stored {
+        if stored.as_str() != Some(checksum(&value).as_str()) {
+            return Err("checksum does not match contents".to_string());
+        }
+    }
+    Ok(value)
+}
+
+/// Parses a record written by [`seal`], verifying its checksum like
+/// [`verify`].
+pub fn unseal<T: DeserializeOwned>(content: &str) -> Result<T, String> {
+    serde_json::from_value(verify(content)?).map_err(|e| e.to_string())
+}
+
+/// A stored file that failed verification.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct IntegrityIssue {
+    /// Where the file was found.
+    pub path: String,
+    pub reason: String,
+    /// Where the file, or a copy of what could not be recovered from it,
+    /// was set aside. `None` if it was left in place.
+    pub quarantined_to: Option<String>,
+    /// Unix seconds.
+    pub detected_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct IntegrityReport {
+    /// Statistics days and automatic backups verified by this check.
+    pub checked_files: u32,
+    /// Files that failed this check.
+    pub issues: Vec<IntegrityIssue>,
+    /// Every file quarantined so far, including those found while loading
+    /// statistics, oldest first.
+    pub quarantined: Vec<IntegrityIssue>,
+}
+
+/// Directory corrupt records are moved to, with a log of why.
+pub struct Quarantine {
+    dir: PathBuf,
+    // Serializes read-modify-write cycles on the issues file.
+    log_lock: Mutex<()>,
+}
+
+impl Quarantine {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            log_lock: Mutex::new(()),
+        }
+    }
+
+    /// Moves `path` into quarantine and records why it failed.
+    pub fn isolate(&self, path: &Path, reason: &str) -> Result<IntegrityIssue, String> {
+        self.set_aside(path, reason, false)
+    }
+
+    /// Copies `path` into quarantine and records why it failed, for a file
+    /// left in place with what could be recovered from it.
+    pub fn preserve(&self, path: &Path, reason: &str) -> Result<IntegrityIssue, String> {
+        self.set_aside(path, reason, true)
+    }
+
+    fn set_aside(&self, path: &Path, reason: &str, copy: bool) -> Result<IntegrityIssue, String> {
+        let _guard = self
+            .log_lock
+            .lock()
+            .map_err(|e| format!("Quarantine lock poisoned: {e}"))?;
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create quarantine directory: {e}"))?;
+        let detected_at = unix_now();
+        let name = path
+            .file_name()
+            .ok_or_else(|| format!("Cannot quarantine {}", path.display()))?
+            .to_string_lossy();
+        // The same file can fail more than once a second, e.g. a day
+        // rewritten and checked again.
+        let target = (0..)
+            .map(|attempt| match attempt {
+                0 => self.dir.join(format!("{name}.{detected_at}")),
+                n => self.dir.join(format!("{name}.{detected_at}.{n}")),
+            })
+            .find(|target| !target.exists())
+            .ok_or_else(|| format!("Cannot quarantine {}", path.display()))?;
+        let moved = if copy {
+            fs::copy(path, &target).map(drop)
+        } else {
+            fs::rename(path, &target)
+        };
+        moved.map_err(|e| format!("Failed to quarantine {}: {e}", path.display()))?;
+        log::warn!(
+            "Quarantined {} ({reason}) as {}",
+            path.display(),
+            target.display()
+        );
+
+        let issue = IntegrityIssue {
+            path: path.display().to_string(),
+            reason: reason.to_string(),
+            quarantined_to: Some(target.display().to_string()),
+            detected_at,
+        };
+        let log = self.dir.join(ISSUES_FILE);
+        let mut issues: Vec<IntegrityIssue> = storage_file::read_json(&log)?.unwrap_or_default();
+        issues.push(issue.clone());
+        storage_file::write_json(&log, &issues)?;
+        Ok(issue)
+    }
+
+    /// Everything quarantined so far, oldest first.
+    pub fn issues(&self) -> Result<Vec<IntegrityIssue>, String> {
+        Ok(storage_file::read_json(&self.dir.join(ISSUES_FILE))?.unwrap_or_default())
+    }
+}
+
+/// Verifies the unencrypted automatic backups in `dir`. Failing backups are
+/// reported but left in place, since they may live outside the app's data.
+/// Encrypted backups can't be verified without their passphrase.
+fn check_backups(dir: &Path) -> Result<(u32, Vec<IntegrityIssue>), String> {
+    let mut checked = 0;
+    let mut issues = Vec::new();
+    for file in backup::list_backups(dir)? {
+        let content = match fs::read_to_string(&file.path) {
+            Ok(content) => content,
+            Err(error) => {
+                log::error!("Failed to read {}: {error}", file.path);
+                continue;
+            }
+        };
+        let encrypted = serde_json::from_str::<Value>(&content)
+            .is_ok_and(|value| backup_crypto::is_encrypted(&value));
+        if encrypted {
+            continue;
+        }
+        checked += 1;
+        if let Err(reason) = backup_format::decode(&content, None) {
+            issues.push(IntegrityIssue {
+                path: file.path,
+                reason,
+                quarantined_to: None,
+                detected_at: unix_now(),
+            });
+        }
+    }
+    Ok((checked, issues))
+}
+
+/// Verifies every statistics day file and automatic backup, quarantining
+/// days that fail.
+pub fn check(storage: &StorageService) -> Result<IntegrityReport, String> {
+    let (days, mut issues) = storage.statistics_files().verify_all()?;
+    let config = storage.load_preferences()?.auto_backup;
+    let (backups, backup_issues) = check_backups(&backup::backup_dir(storage, &config)?)?;
+    issues.extend(backup_issues);
+    Ok(IntegrityReport {
+        checked_files: days + backups,
+        issues,
+        quarantined: storage.quarantine().issues()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sealed_records_read_back_until_tampered_with() {
+        let record = json!({ "date": "2025-03-10", "completed_pomodoros": 4 });
+        let sealed = seal(&record).unwrap();
+        assert_eq!(unseal::<Value>(&sealed).unwrap(), record);
+
+        let tampered = sealed.replace("4", "40");
+        assert!(unseal::<Value>(&tampered).unwrap_err().contains("checksum"));
+        // Written before checksums: accepted as it is.
+        assert_eq!(unseal::<Value>(&record.to_string()).unwrap(), record);
+        assert!(seal(&[1, 2]).is_err());
+    }
+
+    #[test]
+    fn repeated_failures_of_one_file_are_all_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = Quarantine::new(dir.path().join("quarantine"));
+        let path = dir.path().join("2025-03-10.json");
+        for content in ["first", "second", "third"] {
+            fs::write(&path, content).unwrap();
+            quarantine.isolate(&path, "unreadable").unwrap();
+        }
+        fs::write(&path, "fourth").unwrap();
+        quarantine.preserve(&path, "partly unreadable").unwrap();
+        assert!(path.exists());
+
+        let issues = quarantine.issues().unwrap();
+        let kept: Vec<String> = issues
+            .iter()
+            .map(|issue| fs::read_to_string(issue.quarantined_to.as_ref().unwrap()).unwrap())
+            .collect();
+        assert_eq!(kept, ["first", "second", "third", "fourth"]);
+    }
+}
@@ -0,0 +1,52 @@
+//! Where the local servers listen: each has a default address that an
+//! environment variable can override.
+
+use std::net::SocketAddr;
+
+/// Which interfaces an overridden address may name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interfaces {
+    /// Only this machine, for servers that trust whoever can connect.
+    Loopback,
+    /// Any interface, for servers that authenticate their clients.
+    Any,
+}
+
+/// The address in `env`, or `default` when it is unset.
+pub fn listen_addr(env: &str, default: &str, interfaces: Interfaces) -> Result<SocketAddr, String> {
+    let raw = std::env::var(env).unwrap_or_else(|_| default.to_string());
+    parse(env, &raw, interfaces)
+}
+
+fn parse(env: &str, raw: &str, interfaces: Interfaces) -> Result<SocketAddr, String> {
+    let addr: SocketAddr = raw
+        .parse()
+        .map_err(|e| format!("Invalid {env} '{raw}': {e}"))?;
+    if interfaces == Interfaces::Loopback && !addr.ip().is_loopback() {
+        return Err(format!("{env} must be a loopback address, got {addr}"));
+    }
+    Ok(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_variables_fall_back_to_the_default() {
+        let addr = listen_addr(
+            "TEMPUS_TEST_UNSET_ADDR",
+            "127.0.0.1:50150",
+            Interfaces::Loopback,
+        );
+        assert_eq!(addr.unwrap().port(), 50150);
+    }
+
+    #[test]
+    fn loopback_servers_refuse_other_interfaces() {
+        assert!(parse("ADDR", "0.0.0.0:50152", Interfaces::Loopback).is_err());
+        assert!(parse("ADDR", "0.0.0.0:50152", Interfaces::Any).is_ok());
+        assert!(parse("ADDR", "[::1]:50152", Interfaces::Loopback).is_ok());
+        assert!(parse("ADDR", "localhost", Interfaces::Any).is_err());
+    }
+}
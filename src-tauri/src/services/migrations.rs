@@ -0,0 +1,128 @@
+//! Remembers which app version last opened the data directory. When a new
+//! version opens it, a backup is written before any storage migration runs
+//! and the upgrade is recorded, so an upgrade that misbehaves can be rolled
+//! back with `restore_data`.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::storage_file::{self, StorageError};
+
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+const HISTORY_FILE: &str = "migrations.json";
+const FILE_PREFIX: &str = "tempus-ring-pre-";
+const FILE_EXTENSION: &str = "json";
+
+/// One version change of the data directory.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MigrationRecord {
+    /// The version that opened the data before, or `None` if it predates
+    /// this history or the data was new.
+    pub from_version: Option<String>,
+    pub to_version: String,
+    /// Local time of the upgrade, `YYYY-MM-DDTHH:MM:SS`.
+    pub migrated_at: String,
+    /// Absolute path of the backup taken first, accepted by `restore_data`;
+    /// `None` when there was no data yet.
+    pub backup_path: Option<String>,
+}
+
+fn history_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(HISTORY_FILE)
+}
+
+/// Every recorded version change, oldest first.
+pub fn load_history(data_dir: &Path) -> Result<Vec<MigrationRecord>, StorageError> {
+    Ok(storage_file::read_json(&history_path(data_dir))?.unwrap_or_default())
+}
+
+/// Records the upgrade if `data_dir` was last opened by another version,
+/// first calling `backup` with the path to back up to unless the directory
+/// holds no data yet. A failed backup stops the upgrade.
+pub fn record_upgrade(
+    data_dir: &Path,
+    backup_dir: &Path,
+    has_data: bool,
+    backup: impl FnOnce(&Path) -> Result<(), StorageError>,
+) -> Result<(), StorageError> {
+    let mut history = load_history(data_dir)?;
+    let from_version = history.last().map(|record| record.to_version.clone());
+    if from_version.as_deref() == Some(APP_VERSION) {
+        return Ok(());
+    }
+
+    let now = Local::now();
+    let backup_path = if has_data {
+        let path = backup_dir.join(format!(
+            "{FILE_PREFIX}{}-{}.{FILE_EXTENSION}",
+            APP_VERSION,
+            now.format("%Y%m%d-%H%M%S")
+        ));
+        backup(&path)?;
+        Some(path.display().to_string())
+    } else {
+        None
+    };
+    history.push(MigrationRecord {
+        from_version,
+        to_version: APP_VERSION.to_string(),
+        migrated_at: now.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        backup_path,
+    });
+    storage_file::write_json(&history_path(data_dir), &history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn a_new_version_backs_up_first_and_is_recorded_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path();
+        let backup_dir = data_dir.join("backups");
+        let backups = Cell::new(0);
+        let backup = |path: &Path| {
+            assert!(path.starts_with(&backup_dir));
+            backups.set(backups.get() + 1);
+            Ok(())
+        };
+
+        record_upgrade(data_dir, &backup_dir, true, backup).unwrap();
+        record_upgrade(data_dir, &backup_dir, true, backup).unwrap();
+        assert_eq!(backups.get(), 1);
+        let history = load_history(data_dir).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].from_version, None);
+        assert_eq!(history[0].to_version, APP_VERSION);
+        assert!(history[0].backup_path.is_some());
+    }
+
+    #[test]
+    fn upgrades_record_the_version_before_and_stop_if_the_backup_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path();
+        let older = MigrationRecord {
+            from_version: None,
+            to_version: "0.0.1".to_string(),
+            migrated_at: "2025-01-01T00:00:00".to_string(),
+            backup_path: None,
+        };
+        storage_file::write_json(&history_path(data_dir), &[older]).unwrap();
+
+        let failed = record_upgrade(data_dir, data_dir, true, |_| {
+            Err(StorageError::Io("Disk full".to_string()))
+        });
+        assert!(failed.is_err());
+        assert_eq!(load_history(data_dir).unwrap().len(), 1);
+
+        record_upgrade(data_dir, data_dir, false, |_| panic!("nothing to back up")).unwrap();
+        let history = load_history(data_dir).unwrap();
+        assert_eq!(history[1].from_version.as_deref(), Some("0.0.1"));
+        assert_eq!(history[1].backup_path, None);
+    }
+}
@@ -0,0 +1,87 @@
+pub mod api_context;
+pub mod appearance;
+pub mod artifacts;
+pub mod audio;
+pub mod backup;
+pub mod backup_crypto;
+pub mod backup_format;
+pub mod battery;
+pub mod break_detection;
+pub mod break_reminder;
+pub mod burnout;
+pub mod calendar;
+pub mod clock;
+pub mod command_trace;
+pub mod context_detection;
+pub mod contribution;
+pub mod control_socket;
+pub mod cycle;
+pub mod dashboard_feed;
+pub mod days_off;
+pub mod display_client;
+pub mod display_server;
+pub mod distraction_blocker;
+pub mod door_status;
+pub mod duration_format;
+pub mod end_of_day;
+pub mod energy;
+pub mod event_log;
+pub mod experiments;
+pub mod fatigue;
+pub mod focus_budget;
+pub mod focus_mode;
+pub mod github_issues;
+pub mod goals;
+pub mod hooks;
+pub mod ics_export;
+pub mod inbox;
+pub mod integrity;
+pub mod listen_addr;
+pub mod migrations;
+pub mod notifications;
+pub mod path_grants;
+pub mod pdf;
+pub mod permissions;
+pub mod power;
+pub mod preferences;
+pub mod presenter_mode;
+pub mod presets;
+pub mod progress_stream;
+pub mod project_goals;
+pub mod quit_guard;
+pub mod revision;
+pub mod service_health;
+pub mod session_edit;
+pub mod session_query;
+pub mod session_recorder;
+pub mod session_types;
+pub mod single_instance;
+pub mod slack_status;
+pub mod sqlite_export;
+pub mod state_history;
+pub mod state_socket;
+pub mod storage;
+pub mod storage_backend;
+pub mod storage_file;
+pub mod summary;
+pub mod surface_auth;
+pub mod system_theme;
+pub mod task_archive;
+pub mod task_provider;
+pub mod tasks;
+pub mod tick_drift;
+pub mod ticker;
+pub mod timeline;
+pub mod timer_error;
+pub mod timer_schedule;
+pub mod timer_state;
+pub mod timer_transport;
+pub mod timeseries;
+pub mod tray;
+pub mod undo;
+pub mod updater;
+pub mod wellbeing;
+pub mod window_activity;
+pub mod work_context;
+pub mod write_buffer;
+pub mod year_review;
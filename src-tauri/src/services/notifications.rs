@@ -0,0 +1,310 @@
+//! Native OS notifications when a session ends or a reminder is due, and
+//! the per-phase sound and notification settings. Notifications are held
+//! back in presenter mode.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use ts_rs::TS;
+
+use super::audio::AlarmStageKind;
+use super::break_reminder::BreakReminder;
+use super::duration_format::format_duration;
+use super::inbox::{self, InboxKind};
+use super::presenter_mode::PresenterMonitor;
+use super::session_types::find_custom;
+use super::storage::{StorageService, UserPreferences};
+use super::timer_state::{SessionType, TimerData, TimerState};
+
+/// Action type attached to completion notifications. On platforms with
+/// notification actions the frontend registers it with a `start-next` button
+/// and calls `start_timer` when it is pressed; elsewhere it is ignored.
+pub const SESSION_COMPLETE_ACTION_TYPE: &str = "session-complete";
+
+/// How one phase is announced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct PhaseNotifications {
+    pub sound_enabled: bool,
+    /// `0.0..=1.0`, scales the phase's alarm volumes.
+    pub volume: f32,
+    /// Show a native notification.
+    pub notifications_enabled: bool,
+}
+
+impl Default for PhaseNotifications {
+    fn default() -> Self {
+        Self {
+            sound_enabled: true,
+            volume: 0.7,
+            notifications_enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    /// A work session, or a custom session, ends.
+    pub work_end: PhaseNotifications,
+    /// A short or long break ends.
+    pub break_end: PhaseNotifications,
+    /// The one-minute warning and the overtime nag.
+    pub reminders: PhaseNotifications,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            work_end: PhaseNotifications::default(),
+            break_end: PhaseNotifications::default(),
+            // Reminders only ever played a sound before they had their own
+            // phase; notifying on them is opt-in.
+            reminders: PhaseNotifications {
+                notifications_enabled: false,
+                ..PhaseNotifications::default()
+            },
+        }
+    }
+}
+
+impl NotificationPreferences {
+    /// Settings for the end of a `finished` session.
+    pub fn for_end(&self, finished: &SessionType) -> &PhaseNotifications {
+        match finished {
+            SessionType::ShortBreak | SessionType::LongBreak => &self.break_end,
+            SessionType::Work | SessionType::Custom(_) => &self.work_end,
+        }
+    }
+
+    /// Settings for an alarm `stage` of a `session_type` session.
+    pub fn for_stage(
+        &self,
+        stage: AlarmStageKind,
+        session_type: &SessionType,
+    ) -> &PhaseNotifications {
+        match stage {
+            AlarmStageKind::Alarm => self.for_end(session_type),
+            AlarmStageKind::PreAlarm | AlarmStageKind::Overtime => &self.reminders,
+        }
+    }
+
+    pub fn all(&self) -> [(&'static str, &PhaseNotifications); 3] {
+        [
+            ("work_end", &self.work_end),
+            ("break_end", &self.break_end),
+            ("reminders", &self.reminders),
+        ]
+    }
+
+    /// Sets every phase's volume, for appearance profiles.
+    pub fn set_volume(&mut self, volume: f32) {
+        for phase in [&mut self.work_end, &mut self.break_end, &mut self.reminders] {
+            phase.volume = volume;
+        }
+    }
+}
+
+/// Moves the flat `sound_enabled`, `volume` and `notifications_enabled`
+/// fields of preferences saved before [`NotificationPreferences`] into
+/// every phase of it. Reminders keep notifications off, as they were.
+pub fn migrate_flat_preferences(preferences: &mut Value) {
+    let Some(fields) = preferences.as_object_mut() else {
+        return;
+    };
+    let mut phase = Map::new();
+    for key in ["sound_enabled", "volume", "notifications_enabled"] {
+        if let Some(value) = fields.remove(key) {
+            phase.insert(key.to_string(), value);
+        }
+    }
+    if phase.is_empty() || fields.contains_key("notifications") {
+        return;
+    }
+    let mut reminders = phase.clone();
+    reminders.insert("notifications_enabled".to_string(), Value::Bool(false));
+    let phases = [
+        ("work_end", phase.clone()),
+        ("break_end", phase),
+        ("reminders", reminders),
+    ]
+    .map(|(name, phase)| (name.to_string(), Value::Object(phase)));
+    fields.insert(
+        "notifications".to_string(),
+        Value::Object(phases.into_iter().collect()),
+    );
+}
+
+pub(super) fn label(session_type: &SessionType, data: &TimerData) -> String {
+    match session_type {
+        SessionType::Custom(id) => find_custom(&data.config.custom_session_types, id)
+            .map_or_else(|| id.clone(), |custom| custom.name.clone()),
+        built_in => built_in.display_name().to_string(),
+    }
+}
+
+/// Whether presenter mode holds native notifications back. They are still
+/// recorded in the inbox.
+fn presenting(app: &AppHandle) -> bool {
+    app.state::<PresenterMonitor>()
+        .status()
+        .is_ok_and(|status| status.notifications_suppressed)
+}
+
+/// Title and body for a finished `finished` session, given the timer state
+/// it advanced to, with durations as `preferences` ask.
+pub fn completion_message(
+    finished: &SessionType,
+    next: &TimerData,
+    preferences: &UserPreferences,
+) -> (String, String) {
+    let title = match finished {
+        SessionType::Work => "Work session complete — time for a break".to_string(),
+        SessionType::ShortBreak | SessionType::LongBreak => {
+            "Break over — time to focus".to_string()
+        }
+        SessionType::Custom(_) => format!("{} complete", label(finished, next)),
+    };
+    let body = format!(
+        "Up next: {} ({})",
+        label(&next.session_type, next),
+        format_duration(
+            next.total_time,
+            preferences.duration_style,
+            &preferences.language
+        )
+    );
+    (title, body)
+}
+
+/// Shows the completion notification for the session recorded in
+/// `next.last_session`, unless the user turned them off for its phase.
+pub fn notify_completed(app: &AppHandle, next: &TimerData) -> Result<(), String> {
+    let Some(finished) = &next.last_session else {
+        return Ok(());
+    };
+    let preferences = app.state::<StorageService>().load_preferences()?;
+    if !preferences
+        .notifications
+        .for_end(&finished.session_type)
+        .notifications_enabled
+    {
+        return Ok(());
+    }
+
+    let (title, body) = completion_message(&finished.session_type, next, &preferences);
+    inbox::record_or_log(app, InboxKind::Notification, &title, Some(body.clone()));
+    if presenting(app) {
+        return Ok(());
+    }
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .action_type_id(SESSION_COMPLETE_ACTION_TYPE)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {e}"))
+}
+
+/// Shows the notification for a session that ran out and went into
+/// overtime, unless the user turned them off for its phase.
+pub fn notify_overtime(app: &AppHandle, data: &TimerData) -> Result<(), String> {
+    if !app
+        .state::<StorageService>()
+        .load_preferences()?
+        .notifications
+        .for_end(&data.session_type)
+        .notifications_enabled
+    {
+        return Ok(());
+    }
+
+    let title = format!("{} time is up", label(&data.session_type, data));
+    let body = "Overtime is counting — complete the session when you're done".to_string();
+    inbox::record_or_log(app, InboxKind::Notification, &title, Some(body.clone()));
+    if presenting(app) {
+        return Ok(());
+    }
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {e}"))
+}
+
+/// Shows a reminder about `reminder`'s break, which `data` shows waiting,
+/// unless reminder notifications are off.
+pub fn notify_break_reminder(
+    app: &AppHandle,
+    reminder: &BreakReminder,
+    data: &TimerData,
+) -> Result<(), String> {
+    if !app
+        .state::<StorageService>()
+        .load_preferences()?
+        .notifications
+        .reminders
+        .notifications_enabled
+    {
+        return Ok(());
+    }
+
+    let title = format!("Your {} is waiting", label(&reminder.session_type, data));
+    let body = "Start it now, or snooze it for a few minutes".to_string();
+    inbox::record_or_log(app, InboxKind::Notification, &title, Some(body.clone()));
+    if presenting(app) {
+        return Ok(());
+    }
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .action_type_id(SESSION_COMPLETE_ACTION_TYPE)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {e}"))
+}
+
+/// Shows a notification for a reminder `stage` of a `session_type`
+/// session; `data` is the current timer state.
+pub fn notify_reminder(
+    app: &AppHandle,
+    stage: AlarmStageKind,
+    session_type: &SessionType,
+    data: &TimerData,
+) -> Result<(), String> {
+    let title = match stage {
+        AlarmStageKind::PreAlarm => format!("One minute left in {}", label(session_type, data)),
+        AlarmStageKind::Overtime if data.state == TimerState::Overtime => {
+            let preferences = app.state::<StorageService>().load_preferences()?;
+            format!(
+                "{} is {} over",
+                label(session_type, data),
+                format_duration(
+                    data.overtime,
+                    preferences.duration_style,
+                    &preferences.language
+                )
+            )
+        }
+        AlarmStageKind::Overtime => format!(
+            "{} ended — start {} when you're ready",
+            label(session_type, data),
+            label(&data.session_type, data)
+        ),
+        // Completions go through `notify_completed`.
+        AlarmStageKind::Alarm => return Ok(()),
+    };
+    inbox::record_or_log(app, InboxKind::Notification, &title, None);
+    if presenting(app) {
+        return Ok(());
+    }
+    app.notification()
+        .builder()
+        .title(title)
+        .show()
+        .map_err(|e| format!("Failed to show notification: {e}"))
+}
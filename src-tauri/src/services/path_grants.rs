@@ -0,0 +1,29 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Files the user picked through a native dialog during this run.
+///
+/// Grants live in memory only, so after a restart the user has to pick the
+/// file again (or use the configured export directory).
+#[derive(Default)]
+pub struct PathGrants {
+    files: Mutex<HashSet<PathBuf>>,
+}
+
+impl PathGrants {
+    pub fn grant(&self, path: PathBuf) -> Result<(), String> {
+        self.files
+            .lock()
+            .map_err(|e| format!("Path grants lock poisoned: {e}"))?
+            .insert(path);
+        Ok(())
+    }
+
+    pub fn is_granted(&self, path: &Path) -> bool {
+        self.files
+            .lock()
+            .map(|files| files.contains(path))
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,103 @@
+//! Minimal single-page PDF writer used for shareable reports.
+//!
+//! Only the built-in Helvetica faces are used so nothing has to be embedded;
+//! characters outside Latin-1 are rendered as `?`.
+
+use std::fmt::Write;
+
+/// A4 in PDF points.
+pub const PAGE_WIDTH: f32 = 595.0;
+pub const PAGE_HEIGHT: f32 = 842.0;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+/// Drawing operations for one page. Coordinates are in points from the
+/// bottom-left corner, colors are RGB in `0.0..=1.0`.
+#[derive(Debug, Default)]
+pub struct PdfPage {
+    content: String,
+}
+
+fn escape_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            c if (' '..='~').contains(&c) => c.to_string(),
+            c if u32::from(c) <= 0xFF => format!("\\{:03o}", u32::from(c)),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+impl PdfPage {
+    pub fn text(&mut self, x: f32, y: f32, size: f32, font: Font, color: [f32; 3], text: &str) {
+        let [r, g, b] = color;
+        let _ = writeln!(
+            self.content,
+            "BT {r:.3} {g:.3} {b:.3} rg /{} {size:.1} Tf {x:.1} {y:.1} Td ({}) Tj ET",
+            font.resource(),
+            escape_text(text)
+        );
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: [f32; 3]) {
+        let [r, g, b] = color;
+        let _ = writeln!(
+            self.content,
+            "{r:.3} {g:.3} {b:.3} rg {x:.1} {y:.1} {width:.1} {height:.1} re f"
+        );
+    }
+
+    /// Serializes the page into a complete PDF document.
+    pub fn render(&self) -> Vec<u8> {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 5 0 R /F2 6 0 R >> >> /Contents 4 0 R >>"
+            ),
+            format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                self.content.len(),
+                self.content
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            let _ = write!(out, "{} 0 obj\n{object}\nendobj\n", index + 1);
+        }
+
+        let xref_offset = out.len();
+        let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(out, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.into_bytes()
+    }
+}
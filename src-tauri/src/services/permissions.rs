@@ -0,0 +1,184 @@
+//! Preflight for the OS permissions optional features depend on, so the UI
+//! can explain what is missing before a feature silently fails.
+
+use serde::{Deserialize, Serialize};
+use tauri::plugin::PermissionState;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+use ts_rs::TS;
+
+use super::battery::PowerStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    /// Session-complete alerts.
+    Notifications,
+    /// Launching at login.
+    Autostart,
+    /// Idle and active-app tracking.
+    Accessibility,
+    /// Voice commands.
+    Microphone,
+}
+
+impl PermissionKind {
+    const ALL: [PermissionKind; 4] = [
+        PermissionKind::Notifications,
+        PermissionKind::Autostart,
+        PermissionKind::Accessibility,
+        PermissionKind::Microphone,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionStatus {
+    Granted,
+    /// Can be asked for with `request_permission`.
+    Prompt,
+    /// Refused; only the system settings can change it now.
+    Denied,
+    /// This platform doesn't gate the feature.
+    NotRequired,
+    /// The platform gates it but offers no way to ask; the user has to check
+    /// the system settings `request_permission` opens.
+    Unknown,
+    /// The feature isn't available on this platform.
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PermissionReport {
+    pub kind: PermissionKind,
+    pub status: PermissionStatus,
+}
+
+/// The platform the app runs on and what it has to offer.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PlatformCapabilities {
+    /// As named by Rust, e.g. `macos` or `windows`.
+    pub os: String,
+    pub desktop: bool,
+    /// Battery level and charging, or `None` without a battery.
+    pub power: Option<PowerStatus>,
+}
+
+impl From<PermissionState> for PermissionStatus {
+    fn from(state: PermissionState) -> Self {
+        match state {
+            PermissionState::Granted => PermissionStatus::Granted,
+            PermissionState::Denied => PermissionStatus::Denied,
+            PermissionState::Prompt | PermissionState::PromptWithRationale => {
+                PermissionStatus::Prompt
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrusted() -> bool;
+}
+
+/// The system settings page where `kind` is granted, on platforms where
+/// the user has to do it there.
+fn settings_url(kind: PermissionKind) -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        match kind {
+            PermissionKind::Accessibility => Some(
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility",
+            ),
+            PermissionKind::Microphone => {
+                Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone")
+            }
+            PermissionKind::Autostart => {
+                Some("x-apple.systempreferences:com.apple.LoginItems-Settings.extension")
+            }
+            PermissionKind::Notifications => None,
+        }
+    } else if cfg!(target_os = "windows") {
+        match kind {
+            PermissionKind::Microphone => Some("ms-settings:privacy-microphone"),
+            PermissionKind::Autostart => Some("ms-settings:startupapps"),
+            PermissionKind::Notifications | PermissionKind::Accessibility => None,
+        }
+    } else {
+        None
+    }
+}
+
+pub fn check(app: &AppHandle, kind: PermissionKind) -> Result<PermissionStatus, String> {
+    if cfg!(mobile) && kind != PermissionKind::Notifications {
+        return Ok(PermissionStatus::Unsupported);
+    }
+    Ok(match kind {
+        PermissionKind::Notifications => app
+            .notification()
+            .permission_state()
+            .map_err(|e| format!("Failed to check notification permission: {e}"))?
+            .into(),
+        #[cfg(target_os = "macos")]
+        PermissionKind::Accessibility => {
+            // SAFETY: takes no arguments and only reads the process's trust state.
+            if unsafe { AXIsProcessTrusted() } {
+                PermissionStatus::Granted
+            } else {
+                PermissionStatus::Denied
+            }
+        }
+        other if settings_url(other).is_some() => PermissionStatus::Unknown,
+        _ => PermissionStatus::NotRequired,
+    })
+}
+
+pub fn capabilities(power: Option<PowerStatus>) -> PlatformCapabilities {
+    PlatformCapabilities {
+        os: std::env::consts::OS.to_string(),
+        desktop: cfg!(desktop),
+        power,
+    }
+}
+
+pub fn check_all(app: &AppHandle) -> Result<Vec<PermissionReport>, String> {
+    PermissionKind::ALL
+        .into_iter()
+        .map(|kind| {
+            Ok(PermissionReport {
+                kind,
+                status: check(app, kind)?,
+            })
+        })
+        .collect()
+}
+
+/// Shows the system prompt for `kind` where there is one, and otherwise
+/// opens the settings page where it can be granted. Returns the status
+/// afterwards, which for settings pages is usually still the old one.
+pub fn request(app: &AppHandle, kind: PermissionKind) -> Result<PermissionStatus, String> {
+    match check(app, kind)? {
+        PermissionStatus::Prompt if kind == PermissionKind::Notifications => {
+            return app
+                .notification()
+                .request_permission()
+                .map(PermissionStatus::from)
+                .map_err(|e| format!("Failed to request notification permission: {e}"));
+        }
+        PermissionStatus::Denied | PermissionStatus::Unknown => {
+            #[cfg(desktop)]
+            if let Some(url) = settings_url(kind) {
+                use tauri_plugin_opener::OpenerExt;
+                app.opener()
+                    .open_url(url, None::<&str>)
+                    .map_err(|e| format!("Failed to open system settings: {e}"))?;
+            }
+        }
+        _ => {}
+    }
+    check(app, kind)
+}
@@ -0,0 +1,280 @@
+//! System sleep handling. Whether the monotonic clock counts time asleep
+//! differs by platform, so a session running when the lid closes would
+//! otherwise be paused on one machine and finished on another. The tick
+//! loop notices a sleep as a gap between ticks and applies the `on_sleep`
+//! preference to the session being timed.
+//!
+//! Where the OS reports how long it has been suspended, only that counts as
+//! a sleep, so a tick stalled under load or a wall clock set forward isn't
+//! taken for one.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::storage::UserPreferences;
+use super::tick_drift::TICK_INTERVAL;
+use super::timer_state::{TimerData, TimerManager};
+
+/// Emitted with a [`SleepEvent`] after the system wakes.
+pub const WAKE_EVENT: &str = "power://woke";
+/// A gap between ticks longer than this is taken for a sleep. Long enough
+/// that a stalled tick under heavy load doesn't count.
+const MIN_SLEEP: Duration = Duration::from_secs(30);
+
+/// What happens to a session running when the system sleeps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepPolicy {
+    /// Paused as of the last tick before the sleep.
+    #[default]
+    Pause,
+    /// Time asleep counts towards the session, which may have run out.
+    Continue,
+    /// The session is reset without being recorded.
+    Discard,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SleepEvent {
+    /// Unix seconds of the last tick before the sleep.
+    pub slept_at: u64,
+    /// Unix seconds.
+    pub woke_at: u64,
+    /// The policy applied, if a session was being timed.
+    pub applied: Option<SleepPolicy>,
+    pub timer: TimerData,
+}
+
+/// A stretch the tick loop didn't run for.
+#[derive(Debug)]
+struct Gap {
+    slept_at: SystemTime,
+    woke_at: SystemTime,
+    /// By the wall clock.
+    asleep: Duration,
+    /// By the monotonic clock.
+    counted: Duration,
+}
+
+/// The clocks as read at a tick.
+#[derive(Debug, Clone, Copy)]
+struct Reading {
+    instant: Instant,
+    wall: SystemTime,
+    /// Total time the system has been suspended since boot, where the OS
+    /// reports it.
+    suspended: Option<Duration>,
+}
+
+impl Reading {
+    fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            wall: SystemTime::now(),
+            suspended: time_suspended(),
+        }
+    }
+}
+
+/// The gap between two ticks if it was a sleep. With the OS's suspended
+/// time, the gap is the time the monotonic clock counted plus the time
+/// suspended. Without it, either clock may have skipped the time asleep,
+/// so the longer reading decides.
+fn sleep_gap(last: Reading, now: Reading) -> Option<Gap> {
+    let counted = now.instant.duration_since(last.instant);
+    let asleep = match now.suspended.zip(last.suspended) {
+        Some((now_suspended, last_suspended)) => {
+            let suspended = now_suspended.saturating_sub(last_suspended);
+            if suspended < MIN_SLEEP {
+                return None;
+            }
+            counted + suspended
+        }
+        None => {
+            // A wall clock set back reads as no time asleep.
+            let wall = now.wall.duration_since(last.wall).unwrap_or_default();
+            if wall.max(counted) < TICK_INTERVAL + MIN_SLEEP {
+                return None;
+            }
+            wall
+        }
+    };
+    Some(Gap {
+        slept_at: last.wall,
+        woke_at: now.wall,
+        asleep,
+        counted,
+    })
+}
+
+/// Suspended time is the difference between a clock that counts it and one
+/// that doesn't.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn time_suspended() -> Option<Duration> {
+    Some(clock(libc::CLOCK_BOOTTIME)?.saturating_sub(clock(libc::CLOCK_MONOTONIC)?))
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn time_suspended() -> Option<Duration> {
+    Some(clock(libc::CLOCK_MONOTONIC)?.saturating_sub(clock(libc::CLOCK_UPTIME_RAW)?))
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+))]
+fn clock(id: libc::clockid_t) -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: only writes the timespec passed in.
+    if unsafe { libc::clock_gettime(id, &mut time) } != 0 {
+        return None;
+    }
+    Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetTickCount64() -> u64;
+    fn QueryUnbiasedInterruptTime(time: *mut u64) -> i32;
+}
+
+/// The tick count includes time suspended; the unbiased interrupt time, in
+/// 100 ns units, doesn't.
+#[cfg(target_os = "windows")]
+fn time_suspended() -> Option<Duration> {
+    let mut unbiased = 0;
+    // SAFETY: `GetTickCount64` takes nothing and `QueryUnbiasedInterruptTime`
+    // only writes the value passed in.
+    let (ticks, read) = unsafe { (GetTickCount64(), QueryUnbiasedInterruptTime(&mut unbiased)) };
+    (read != 0)
+        .then(|| Duration::from_millis(ticks).saturating_sub(Duration::from_nanos(unbiased * 100)))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "windows"
+)))]
+fn time_suspended() -> Option<Duration> {
+    None
+}
+
+struct MonitorState {
+    policy: SleepPolicy,
+    last_tick: Option<Reading>,
+}
+
+/// Watches the tick loop for sleeps.
+pub struct SleepMonitor {
+    state: Mutex<MonitorState>,
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl SleepMonitor {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(MonitorState {
+                policy: preferences.on_sleep,
+                last_tick: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, MonitorState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Sleep monitor lock poisoned: {e}"))
+    }
+
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        self.lock()?.policy = preferences.on_sleep;
+        Ok(())
+    }
+
+    /// Records a tick and returns the gap since the previous one if it was
+    /// a sleep.
+    fn observe(&self) -> Result<Option<Gap>, String> {
+        let now = Reading::now();
+        let last = self.lock()?.last_tick.replace(now);
+        Ok(last.and_then(|last| sleep_gap(last, now)))
+    }
+}
+
+/// Called by the tick loop before each tick: after a sleep, applies the
+/// policy to the session being timed and emits [`WAKE_EVENT`].
+pub fn check(app: &AppHandle) -> Result<(), String> {
+    let monitor = app.state::<SleepMonitor>();
+    let Some(gap) = monitor.observe()? else {
+        return Ok(());
+    };
+    let policy = monitor.lock()?.policy;
+    let timer = app.state::<TimerManager>();
+    let applied = timer
+        .wake(policy, gap.asleep, gap.counted)
+        .map_err(|error| error.to_string())?;
+    let event = SleepEvent {
+        slept_at: unix_seconds(gap.slept_at),
+        woke_at: unix_seconds(gap.woke_at),
+        applied: applied.as_ref().map(|_| policy),
+        timer: match applied {
+            Some(data) => data,
+            None => timer.get_data()?,
+        },
+    };
+    app.emit(WAKE_EVENT, event)
+        .map_err(|e| format!("Failed to emit {WAKE_EVENT}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(start: Reading, monotonic: u64, wall: u64, suspended: Option<u64>) -> Reading {
+        Reading {
+            instant: start.instant + Duration::from_secs(monotonic),
+            wall: start.wall + Duration::from_secs(wall),
+            suspended: suspended.map(Duration::from_secs),
+        }
+    }
+
+    #[test]
+    fn only_suspended_time_counts_when_the_os_reports_it() {
+        let start = reading(Reading::now(), 0, 0, Some(100));
+        // A tick stalled for two minutes under load.
+        assert!(sleep_gap(start, reading(start, 120, 120, Some(100))).is_none());
+        // The wall clock set forward an hour.
+        assert!(sleep_gap(start, reading(start, 1, 3600, Some(100))).is_none());
+
+        let gap = sleep_gap(start, reading(start, 1, 601, Some(700))).unwrap();
+        assert_eq!(gap.asleep, Duration::from_secs(601));
+        assert_eq!(gap.counted, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn without_suspended_time_the_longer_clock_decides() {
+        let start = reading(Reading::now(), 0, 0, None);
+        assert!(sleep_gap(start, reading(start, 1, 1, None)).is_none());
+
+        let gap = sleep_gap(start, reading(start, 1, 600, None)).unwrap();
+        assert_eq!(gap.asleep, Duration::from_secs(600));
+        assert_eq!(gap.counted, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,218 @@
+//! User preferences, stored as `preferences.json` by [`StorageService`].
+//!
+//! [`StorageService`]: super::storage::StorageService
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::appearance::{AppearanceSchedule, AppearanceScheduler};
+use super::audio::{AlarmPreferences, AudioService};
+use super::backup::{AutoBackupConfig, BackupScheduler};
+use super::battery::{BatteryGuard, BatteryMonitor};
+use super::break_detection::BreakDetection;
+use super::break_reminder::{BreakReminderService, BreakReminders};
+use super::calendar::CalendarSettings;
+use super::context_detection::ContextDetector;
+use super::dashboard_feed::DashboardFeedConfig;
+use super::days_off::DaysOffSettings;
+use super::distraction_blocker::{BlockerSettings, DistractionBlocker};
+use super::door_status::{DoorStatusConfig, DoorStatusWriter};
+use super::duration_format::DurationStyle;
+use super::end_of_day::EndOfDayConfig;
+use super::energy::EnergyTemplate;
+use super::experiments::Experiment;
+use super::fatigue::FatigueThresholds;
+use super::focus_budget::FocusBudget;
+use super::focus_mode::FocusModeService;
+use super::hooks::{HookRunner, TimerHook};
+use super::notifications::NotificationPreferences;
+use super::power::{SleepMonitor, SleepPolicy};
+use super::presenter_mode::{PresenterModeSettings, PresenterMonitor};
+use super::project_goals::ProjectGoal;
+use super::quit_guard::{QuitGuard, QuitGuardMode};
+use super::slack_status::{SlackStatusConfig, SlackStatusService};
+use super::storage::StorageService;
+use super::storage_backend::StorageBackendKind;
+use super::surface_auth::SurfaceAccess;
+use super::task_archive::TaskArchivePolicy;
+use super::timer_schedule::TimerSchedule;
+use super::timer_state::{DisplayMode, TimerConfig, TimerManager};
+use super::timer_transport::{TimerBackend, TimerClient};
+use super::tray::{TrayCountdown, TrayIconRenderer};
+use super::updater::UpdateChannel;
+use super::window_activity::WindowTracker;
+use super::work_context::WorkContextSettings;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct UserPreferences {
+    pub theme: String,
+    pub language: String,
+    /// How durations read in the tray tooltip, notifications and reports.
+    pub duration_style: DurationStyle,
+    /// Sound, volume and native notifications for each phase.
+    pub notifications: NotificationPreferences,
+    /// Per-session-type sounds for the pre-alarm, alarm and overtime nag.
+    pub alarms: AlarmPreferences,
+    /// Day and night profiles that replace `theme`, the phase volumes and
+    /// `alarms` when the phase changes.
+    pub appearance_schedule: AppearanceSchedule,
+    /// Whether the timer shows time left, time elapsed or percent done,
+    /// everywhere it is shown.
+    pub display_mode: DisplayMode,
+    /// What the tray icon shows while a session is running.
+    pub tray_countdown: TrayCountdown,
+    /// Turn on the OS Do Not Disturb mode during work sessions.
+    pub dnd_integration: bool,
+    /// What happens on quit or window close during a work session.
+    pub quit_guard: QuitGuardMode,
+    /// Which phases set a Slack status once `connect_slack` was called.
+    pub slack_status: SlackStatusConfig,
+    /// Copy tracked artifact files at session boundaries.
+    pub artifact_capture: bool,
+    /// Record the windows in front longest during each work session. Off
+    /// unless turned on.
+    pub window_annotation: bool,
+    /// When skipped breaks trigger a fatigue advisory.
+    pub fatigue: FatigueThresholds,
+    /// Inbox advisories when the weekly burnout-risk score trends badly.
+    pub burnout_advisories: bool,
+    /// Caps on focus time against overwork.
+    pub focus_budget: FocusBudget,
+    /// When one statistics day ends and the next begins.
+    pub calendar: CalendarSettings,
+    /// Which idle gaps count as long breaks nobody started a session for.
+    pub break_detection: BreakDetection,
+    /// Pomodoros per day that count as reaching the goal.
+    pub daily_goal: u32,
+    /// Vacations and holidays, which don't break streaks or miss the goal.
+    pub days_off: DaysOffSettings,
+    /// Weekly focus-hour goals, one per project tag.
+    pub project_goals: Vec<ProjectGoal>,
+    /// Places such as Home or Office recorded on sessions.
+    pub work_context: WorkContextSettings,
+    /// What happens to a running session when the system sleeps.
+    pub on_sleep: SleepPolicy,
+    /// Warnings about, or holding back, long sessions on low battery.
+    pub battery_guard: BatteryGuard,
+    /// Reminders about a break that came due but wasn't started.
+    pub break_reminders: BreakReminders,
+    /// What holds back while a fullscreen app is in front.
+    pub presenter_mode: PresenterModeSettings,
+    /// Sites blocked during work sessions, and how they are blocked.
+    pub distraction_blocker: BlockerSettings,
+    /// Shell commands run on timer transitions.
+    pub hooks: Vec<TimerHook>,
+    pub auto_start_breaks: bool,
+    pub auto_start_work: bool,
+    pub timer_config: TimerConfig,
+    /// Whether the timer runs in this app or on a headless focus server.
+    pub timer_backend: TimerBackend,
+    /// Windows of the week that use their own timer configuration.
+    pub timer_schedule: TimerSchedule,
+    /// A/B comparison of two timer configurations; while it runs its arm
+    /// takes precedence over `timer_schedule`. Set through
+    /// `start_experiment`.
+    pub experiment: Option<Experiment>,
+    /// High/low energy hours used by `get_energy_plan`.
+    pub energy_template: EnergyTemplate,
+    /// Destinations for the end-of-day statistic push.
+    pub end_of_day: EndOfDayConfig,
+    /// Periodic JSON feed for personal dashboards.
+    pub dashboard_feed: DashboardFeedConfig,
+    /// Focus status page for door displays.
+    pub door_status: DoorStatusConfig,
+    /// Rotating backups written in the background.
+    pub auto_backup: AutoBackupConfig,
+    /// When completed tasks move out of the task list into the archive.
+    pub task_archive: TaskArchivePolicy,
+    /// Where daily statistics are kept. Takes effect on the next launch.
+    pub storage_backend: StorageBackendKind,
+    /// How much gRPC and other external clients may do.
+    pub external_access: SurfaceAccess,
+    /// Release channel the updater follows.
+    pub update_channel: UpdateChannel,
+    /// Directory backups and exports may be written to without a dialog.
+    /// Only set through `choose_export_dir`.
+    pub export_dir: Option<String>,
+    pub revision: u64,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        Self {
+            theme: "cloudlight".to_string(),
+            language: "en_US".to_string(),
+            duration_style: DurationStyle::default(),
+            notifications: NotificationPreferences::default(),
+            alarms: AlarmPreferences::default(),
+            appearance_schedule: AppearanceSchedule::default(),
+            display_mode: DisplayMode::default(),
+            tray_countdown: TrayCountdown::default(),
+            dnd_integration: false,
+            quit_guard: QuitGuardMode::default(),
+            slack_status: SlackStatusConfig::default(),
+            artifact_capture: false,
+            window_annotation: false,
+            fatigue: FatigueThresholds::default(),
+            burnout_advisories: true,
+            focus_budget: FocusBudget::default(),
+            calendar: CalendarSettings::default(),
+            break_detection: BreakDetection::default(),
+            daily_goal: 8,
+            days_off: DaysOffSettings::default(),
+            project_goals: Vec::new(),
+            work_context: WorkContextSettings::default(),
+            on_sleep: SleepPolicy::default(),
+            battery_guard: BatteryGuard::default(),
+            break_reminders: BreakReminders::default(),
+            presenter_mode: PresenterModeSettings::default(),
+            distraction_blocker: BlockerSettings::default(),
+            hooks: Vec::new(),
+            auto_start_breaks: false,
+            auto_start_work: false,
+            timer_config: TimerConfig::default(),
+            timer_backend: TimerBackend::default(),
+            timer_schedule: TimerSchedule::default(),
+            experiment: None,
+            energy_template: EnergyTemplate::default(),
+            end_of_day: EndOfDayConfig::default(),
+            dashboard_feed: DashboardFeedConfig::default(),
+            door_status: DoorStatusConfig::default(),
+            auto_backup: AutoBackupConfig::default(),
+            task_archive: TaskArchivePolicy::default(),
+            storage_backend: StorageBackendKind::default(),
+            external_access: SurfaceAccess::default(),
+            update_channel: UpdateChannel::default(),
+            export_dir: None,
+            revision: 0,
+        }
+    }
+}
+
+/// Hands newly saved or restored preferences to the services that cache them.
+pub fn configure_services(app: &AppHandle, preferences: &UserPreferences) -> Result<(), String> {
+    app.state::<AudioService>().configure(preferences)?;
+    app.state::<TrayIconRenderer>().configure(preferences)?;
+    app.state::<FocusModeService>().configure(preferences)?;
+    app.state::<QuitGuard>().configure(preferences)?;
+    app.state::<SlackStatusService>().configure(preferences)?;
+    app.state::<BackupScheduler>().configure(preferences)?;
+    app.state::<SleepMonitor>().configure(preferences)?;
+    app.state::<BatteryMonitor>().configure(preferences)?;
+    app.state::<PresenterMonitor>().configure(preferences)?;
+    app.state::<BreakReminderService>().configure(preferences)?;
+    app.state::<ContextDetector>().configure(preferences)?;
+    app.state::<WindowTracker>().configure(preferences)?;
+    app.state::<HookRunner>().configure(preferences)?;
+    app.state::<DoorStatusWriter>()
+        .configure(app, preferences)?;
+    app.state::<DistractionBlocker>()
+        .configure(app, preferences)?;
+    app.state::<TimerManager>().configure(preferences)?;
+    app.state::<TimerClient>()
+        .configure(&app.state::<StorageService>(), preferences)?;
+    app.state::<AppearanceScheduler>().configure(preferences)
+}
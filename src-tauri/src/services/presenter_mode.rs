@@ -0,0 +1,373 @@
+//! Presenter mode: while a fullscreen app such as a slide deck, a game or
+//! a video call is in front, notifications, alarm sounds and the
+//! frontend's overlays and HUDs hold back, as the policy says. Detected
+//! with `xprop`/`xrandr` on X11, System Events on macOS and
+//! `SHQueryUserNotificationState` on Windows, every few seconds on a
+//! blocking task so the tick loop doesn't wait for it. Off by default, since
+//! macOS asks for automation access the first time System Events is used.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::storage::UserPreferences;
+
+/// Emitted with the new [`PresenterStatus`] when it changes.
+pub const PRESENTER_EVENT: &str = "presenter://status";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct PresenterModeSettings {
+    pub enabled: bool,
+    /// Monitors whose fullscreen apps don't count, e.g. a side screen
+    /// playing video. Names as the OS reports them, such as `HDMI-1`.
+    pub ignored_monitors: Vec<String>,
+    pub suppress_notifications: bool,
+    pub suppress_sounds: bool,
+    /// Ask the frontend to hide its overlays and HUDs.
+    pub suppress_overlays: bool,
+}
+
+impl Default for PresenterModeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ignored_monitors: Vec::new(),
+            suppress_notifications: true,
+            suppress_sounds: true,
+            suppress_overlays: true,
+        }
+    }
+}
+
+/// The app in front while it is fullscreen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct FullscreenApp {
+    pub name: Option<String>,
+    /// The monitor it covers, where the platform tells.
+    pub monitor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct PresenterStatus {
+    /// The fullscreen app presenter mode is on for, if it is.
+    pub fullscreen: Option<FullscreenApp>,
+    pub notifications_suppressed: bool,
+    pub sounds_suppressed: bool,
+    pub overlays_suppressed: bool,
+}
+
+/// The X11 id of the window in front, such as `0x3c00007`.
+#[cfg(target_os = "linux")]
+pub(super) fn x11_active_window() -> Option<String> {
+    // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3c00007
+    let active = super::focus_mode::run("xprop", &["-root", "_NET_ACTIVE_WINDOW"]).ok()?;
+    Some(active.rsplit(' ').next()?.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> Option<FullscreenApp> {
+    use super::focus_mode::run;
+
+    let id = x11_active_window()?;
+    let properties = run("xprop", &["-id", &id, "_NET_WM_STATE", "WM_CLASS"]).ok()?;
+    if !properties.contains("_NET_WM_STATE_FULLSCREEN") {
+        return None;
+    }
+    // WM_CLASS(STRING) = "zoom", "zoom"
+    let name = properties
+        .lines()
+        .find_map(|line| line.strip_prefix("WM_CLASS(STRING) = "))
+        .and_then(|classes| classes.rsplit(", ").next())
+        .map(|class| class.trim_matches('"').to_string());
+    Some(FullscreenApp {
+        name,
+        monitor: x11_monitor(&id),
+    })
+}
+
+/// The monitor holding the top-left corner of window `id`.
+#[cfg(target_os = "linux")]
+fn x11_monitor(id: &str) -> Option<String> {
+    use super::focus_mode::run;
+
+    let info = run("xwininfo", &["-id", id]).ok()?;
+    let corner = |label: &str| -> Option<i64> {
+        info.lines()
+            .find_map(|line| line.trim().strip_prefix(label))?
+            .trim()
+            .parse()
+            .ok()
+    };
+    let (x, y) = (
+        corner("Absolute upper-left X:")?,
+        corner("Absolute upper-left Y:")?,
+    );
+    //  0: +*HDMI-1 1920/531x1080/299+0+0  HDMI-1
+    run("xrandr", &["--listactivemonitors"])
+        .ok()?
+        .lines()
+        .skip(1)
+        .find_map(|line| {
+            let mut words = line.split_whitespace().skip(2);
+            let geometry = words.next()?;
+            let name = words.next()?;
+            let (size, offset) = geometry.split_once('+')?;
+            let (left, top) = offset.split_once('+')?;
+            let (width, height) = size.split_once('x')?;
+            let number = |text: &str| text.split('/').next()?.parse::<i64>().ok();
+            let (left, top) = (number(left)?, number(top)?);
+            let (width, height) = (number(width)?, number(height)?);
+            ((left..left + width).contains(&x) && (top..top + height).contains(&y))
+                .then(|| name.to_string())
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> Option<FullscreenApp> {
+    // Needs the accessibility permission; without it nothing is detected.
+    let script = "tell application \"System Events\" to tell (first process whose frontmost is true) \
+                  to return name & \"|\" & (value of attribute \"AXFullScreen\" of front window as text)";
+    let output = super::focus_mode::run("osascript", &["-e", script]).ok()?;
+    let (name, fullscreen) = output.rsplit_once('|')?;
+    (fullscreen == "true").then(|| FullscreenApp {
+        name: Some(name.to_string()),
+        monitor: None,
+    })
+}
+
+#[cfg(target_os = "windows")]
+#[repr(C)]
+struct MonitorInfoEx {
+    size: u32,
+    _monitor: [i32; 4],
+    _work: [i32; 4],
+    _flags: u32,
+    device: [u16; 32],
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "shell32")]
+extern "system" {
+    fn SHQueryUserNotificationState(state: *mut i32) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "user32")]
+extern "system" {
+    fn GetForegroundWindow() -> *mut std::ffi::c_void;
+    fn MonitorFromWindow(window: *mut std::ffi::c_void, flags: u32) -> *mut std::ffi::c_void;
+    fn GetMonitorInfoW(monitor: *mut std::ffi::c_void, info: *mut MonitorInfoEx) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+fn detect() -> Option<FullscreenApp> {
+    const BUSY: i32 = 2;
+    const D3D_FULL_SCREEN: i32 = 3;
+    const PRESENTATION_MODE: i32 = 4;
+    const MONITOR_DEFAULTTONEAREST: u32 = 2;
+    let mut state = 0;
+    // SAFETY: only writes the state passed in.
+    if unsafe { SHQueryUserNotificationState(&mut state) } != 0
+        || !matches!(state, BUSY | D3D_FULL_SCREEN | PRESENTATION_MODE)
+    {
+        return None;
+    }
+    let mut info = MonitorInfoEx {
+        size: std::mem::size_of::<MonitorInfoEx>() as u32,
+        _monitor: [0; 4],
+        _work: [0; 4],
+        _flags: 0,
+        device: [0; 32],
+    };
+    // SAFETY: the handles come straight from the calls before, and
+    // `GetMonitorInfoW` only writes the MONITORINFOEXW-sized struct passed.
+    let monitor = unsafe {
+        let monitor = MonitorFromWindow(GetForegroundWindow(), MONITOR_DEFAULTTONEAREST);
+        (!monitor.is_null() && GetMonitorInfoW(monitor, &mut info) != 0).then(|| {
+            let end = info.device.iter().position(|c| *c == 0).unwrap_or(32);
+            String::from_utf16_lossy(&info.device[..end])
+        })
+    };
+    Some(FullscreenApp {
+        name: (state == PRESENTATION_MODE).then(|| "Presentation".to_string()),
+        monitor,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn detect() -> Option<FullscreenApp> {
+    None
+}
+
+struct PresenterState {
+    settings: PresenterModeSettings,
+    status: PresenterStatus,
+    polled_at: Option<Instant>,
+}
+
+impl PresenterState {
+    fn status_for(&self, fullscreen: Option<FullscreenApp>) -> PresenterStatus {
+        let settings = &self.settings;
+        let fullscreen = fullscreen.filter(|app| {
+            settings.enabled
+                && !app
+                    .monitor
+                    .as_ref()
+                    .is_some_and(|monitor| settings.ignored_monitors.contains(monitor))
+        });
+        let on = fullscreen.is_some();
+        PresenterStatus {
+            fullscreen,
+            notifications_suppressed: on && settings.suppress_notifications,
+            sounds_suppressed: on && settings.suppress_sounds,
+            overlays_suppressed: on && settings.suppress_overlays,
+        }
+    }
+}
+
+/// Keeps whether presenter mode is on for the notification dispatcher and
+/// the frontend.
+pub struct PresenterMonitor {
+    state: Mutex<PresenterState>,
+}
+
+impl PresenterMonitor {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(PresenterState {
+                settings: preferences.presenter_mode.clone(),
+                status: PresenterStatus::default(),
+                polled_at: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, PresenterState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Presenter mode lock poisoned: {e}"))
+    }
+
+    /// Picks up changed settings; they apply from the next poll.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.settings = preferences.presenter_mode.clone();
+        state.polled_at = None;
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<PresenterStatus, String> {
+        Ok(self.lock()?.status.clone())
+    }
+
+    /// Whether a poll is due, once `POLL_INTERVAL` has passed: `Some(true)`
+    /// to detect, `Some(false)` to turn presenter mode off as it's disabled.
+    /// Marks the poll as started.
+    fn start_poll(&self) -> Result<Option<bool>, String> {
+        let mut state = self.lock()?;
+        if state
+            .polled_at
+            .is_some_and(|polled_at| polled_at.elapsed() < POLL_INTERVAL)
+        {
+            return Ok(None);
+        }
+        state.polled_at = Some(Instant::now());
+        Ok(Some(state.settings.enabled))
+    }
+
+    /// Applies what a poll found. Returns the new status if it changed.
+    fn update(&self, fullscreen: Option<FullscreenApp>) -> Result<Option<PresenterStatus>, String> {
+        let mut state = self.lock()?;
+        let status = state.status_for(fullscreen);
+        if status == state.status {
+            return Ok(None);
+        }
+        state.status = status.clone();
+        Ok(Some(status))
+    }
+}
+
+/// Called by the tick loop: detects fullscreen apps now and then, off the
+/// loop, and emits [`PRESENTER_EVENT`] when presenter mode turns on or off.
+pub fn check(app: &AppHandle) -> Result<(), String> {
+    let Some(enabled) = app.state::<PresenterMonitor>().start_poll()? else {
+        return Ok(());
+    };
+    if !enabled {
+        return publish(app, None);
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(error) = publish(&app, detect()) {
+            log::error!("{error}");
+        }
+    });
+    Ok(())
+}
+
+fn publish(app: &AppHandle, fullscreen: Option<FullscreenApp>) -> Result<(), String> {
+    let Some(status) = app.state::<PresenterMonitor>().update(fullscreen)? else {
+        return Ok(());
+    };
+    app.emit(PRESENTER_EVENT, status)
+        .map_err(|e| format!("Failed to emit {PRESENTER_EVENT}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slides(monitor: &str) -> Option<FullscreenApp> {
+        Some(FullscreenApp {
+            name: Some("Keynote".to_string()),
+            monitor: Some(monitor.to_string()),
+        })
+    }
+
+    fn monitor(settings: PresenterModeSettings) -> PresenterMonitor {
+        let mut preferences = UserPreferences::default();
+        preferences.presenter_mode = settings;
+        PresenterMonitor::new(&preferences)
+    }
+
+    #[test]
+    fn fullscreen_apps_on_ignored_monitors_or_while_disabled_do_not_count() {
+        let monitor = monitor(PresenterModeSettings {
+            enabled: true,
+            ignored_monitors: vec!["HDMI-1".to_string()],
+            suppress_sounds: false,
+            ..PresenterModeSettings::default()
+        });
+        assert_eq!(monitor.update(slides("HDMI-1")).unwrap(), None);
+
+        let status = monitor.update(slides("eDP-1")).unwrap().unwrap();
+        assert_eq!(status.fullscreen, slides("eDP-1"));
+        assert!(status.notifications_suppressed && status.overlays_suppressed);
+        assert!(!status.sounds_suppressed);
+        assert_eq!(monitor.update(slides("eDP-1")).unwrap(), None);
+
+        monitor.configure(&UserPreferences::default()).unwrap();
+        assert_eq!(
+            monitor.update(slides("eDP-1")).unwrap(),
+            Some(PresenterStatus::default())
+        );
+    }
+
+    #[test]
+    fn configuring_makes_the_next_poll_due() {
+        let monitor = monitor(PresenterModeSettings::default());
+        assert_eq!(monitor.start_poll().unwrap(), Some(false));
+        assert_eq!(monitor.start_poll().unwrap(), None);
+        let mut preferences = UserPreferences::default();
+        preferences.presenter_mode.enabled = true;
+        monitor.configure(&preferences).unwrap();
+        assert_eq!(monitor.start_poll().unwrap(), Some(true));
+    }
+}
@@ -0,0 +1,136 @@
+//! Named timer configurations, such as "Deep work 50/10", that can be
+//! applied without retyping durations.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use ts_rs::TS;
+
+use super::cycle::SequencePhase;
+use super::storage::StorageService;
+use super::timer_state::{SessionType, TimerConfig};
+
+pub const MAX_PRESETS: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TimerPreset {
+    /// Unique, compared case-insensitively.
+    pub name: String,
+    pub config: TimerConfig,
+}
+
+impl TimerPreset {
+    fn named(&self, name: &str) -> bool {
+        self.name.to_lowercase() == name.to_lowercase()
+    }
+}
+
+/// The presets offered before the user has saved any.
+pub fn default_presets() -> Vec<TimerPreset> {
+    let classic = TimerConfig::default();
+    let deep_work = TimerConfig {
+        work_duration: 50 * 60,
+        short_break_duration: 10 * 60,
+        long_break_duration: 30 * 60,
+        sessions_until_long_break: 3,
+        ..TimerConfig::default()
+    };
+    let rhythm = TimerConfig {
+        sequence: vec![
+            SequencePhase {
+                session_type: SessionType::Work,
+                duration: 52 * 60,
+            },
+            SequencePhase {
+                session_type: SessionType::ShortBreak,
+                duration: 17 * 60,
+            },
+        ],
+        ..TimerConfig::default()
+    };
+    vec![
+        TimerPreset {
+            name: "Classic 25/5".to_string(),
+            config: classic,
+        },
+        TimerPreset {
+            name: "Deep work 50/10".to_string(),
+            config: deep_work,
+        },
+        TimerPreset {
+            name: "Rhythm 52/17".to_string(),
+            config: rhythm,
+        },
+    ]
+}
+
+/// In-memory preset list, written through to `StorageService` on every
+/// change. Memory only changes once the write succeeded.
+pub struct PresetManager {
+    presets: Mutex<Vec<TimerPreset>>,
+}
+
+impl PresetManager {
+    pub fn new(presets: Vec<TimerPreset>) -> Self {
+        Self {
+            presets: Mutex::new(presets),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Vec<TimerPreset>>, String> {
+        self.presets
+            .lock()
+            .map_err(|e| format!("Preset list lock poisoned: {e}"))
+    }
+
+    pub fn list(&self) -> Result<Vec<TimerPreset>, String> {
+        Ok(self.lock()?.clone())
+    }
+
+    pub fn find(&self, name: &str) -> Result<TimerPreset, String> {
+        self.lock()?
+            .iter()
+            .find(|preset| preset.named(name))
+            .cloned()
+            .ok_or_else(|| format!("Preset not found: {name}"))
+    }
+
+    /// Saves `config` as `name`, replacing the preset of that name if there
+    /// is one.
+    pub fn save(
+        &self,
+        storage: &StorageService,
+        name: String,
+        config: TimerConfig,
+    ) -> Result<TimerPreset, String> {
+        let mut presets = self.lock()?;
+        let preset = TimerPreset { name, config };
+        let mut updated = presets.clone();
+        match updated
+            .iter()
+            .position(|existing| existing.named(&preset.name))
+        {
+            Some(index) => updated[index] = preset.clone(),
+            None if updated.len() >= MAX_PRESETS => {
+                return Err(format!("At most {MAX_PRESETS} presets can be saved"));
+            }
+            None => updated.push(preset.clone()),
+        }
+        storage.save_presets(&updated)?;
+        *presets = updated;
+        Ok(preset)
+    }
+
+    pub fn delete(&self, storage: &StorageService, name: &str) -> Result<TimerPreset, String> {
+        let mut presets = self.lock()?;
+        let index = presets
+            .iter()
+            .position(|preset| preset.named(name))
+            .ok_or_else(|| format!("Preset not found: {name}"))?;
+        let mut updated = presets.clone();
+        let removed = updated.remove(index);
+        storage.save_presets(&updated)?;
+        *presets = updated;
+        Ok(removed)
+    }
+}
@@ -0,0 +1,106 @@
+//! Millisecond progress of the running session, streamed several times a
+//! second while the main window is visible so the progress ring animates
+//! smoothly between the once-a-second ticks.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::MissedTickBehavior;
+use ts_rs::TS;
+
+use super::timer_state::TimerManager;
+
+/// Emitted with a [`TimerProgress`] every [`FRAME_INTERVAL`] while the
+/// stream is enabled, a session is running and the main window is visible.
+pub const PROGRESS_EVENT: &str = "timer://progress";
+const FRAME_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct TimerProgress {
+    pub elapsed_ms: u64,
+    pub remaining_ms: u64,
+    pub total_ms: u64,
+    /// `elapsed_ms / total_ms`, from 0 to 1.
+    pub progress: f64,
+}
+
+impl TimerProgress {
+    /// Progress through a session of `duration` seconds after `elapsed`.
+    pub fn new(duration: u64, elapsed: Duration) -> Self {
+        let total_ms = duration.saturating_mul(1000);
+        let elapsed_ms = (elapsed.as_millis() as u64).min(total_ms);
+        let progress = if total_ms == 0 {
+            1.0
+        } else {
+            elapsed_ms as f64 / total_ms as f64
+        };
+        Self {
+            elapsed_ms,
+            remaining_ms: total_ms - elapsed_ms,
+            total_ms,
+            progress,
+        }
+    }
+
+    /// A stopwatch after `elapsed`: all of it is elapsed and there is no
+    /// end to make progress towards.
+    pub fn counting_up(elapsed: Duration) -> Self {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        Self {
+            elapsed_ms,
+            remaining_ms: 0,
+            total_ms: elapsed_ms,
+            progress: 0.0,
+        }
+    }
+}
+
+/// Whether the frontend has asked for [`PROGRESS_EVENT`]s.
+#[derive(Default)]
+pub struct ProgressStream {
+    enabled: AtomicBool,
+}
+
+impl ProgressStream {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+}
+
+fn main_window_visible(app: &AppHandle) -> bool {
+    app.get_webview_window("main").is_some_and(|window| {
+        window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false)
+    })
+}
+
+/// Spawns the loop that emits [`PROGRESS_EVENT`]. The [`ProgressStream`]
+/// and [`TimerManager`] must already be registered on `app`.
+pub fn spawn_stream(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FRAME_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            if !app.state::<ProgressStream>().enabled() || !main_window_visible(&app) {
+                continue;
+            }
+            let progress = match app.state::<TimerManager>().progress() {
+                Ok(Some(progress)) => progress,
+                Ok(None) => continue,
+                Err(error) => {
+                    log::error!("{error}");
+                    continue;
+                }
+            };
+            if let Err(error) = app.emit(PROGRESS_EVENT, progress) {
+                log::error!("Failed to emit {PROGRESS_EVENT}: {error}");
+            }
+        }
+    });
+}
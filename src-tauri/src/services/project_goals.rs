@@ -0,0 +1,184 @@
+//! Weekly focus-hour goals per project, with a forecast of whether the
+//! week's goal is still reachable at the current pace. A project is a
+//! session tag, so a session counts towards every project it is tagged with.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+use super::calendar;
+use super::inbox::{self, InboxKind};
+use super::storage::StorageService;
+
+/// Emitted with a [`ProjectGoalProgress`] when a project's focus time this
+/// week reaches its goal.
+pub const PROJECT_GOAL_REACHED_EVENT: &str = "goal://project-reached";
+const SECS_PER_HOUR: u64 = 60 * 60;
+const DAYS_PER_WEEK: u64 = 7;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProjectGoal {
+    /// The session tag identifying the project.
+    pub project: String,
+    pub weekly_focus_hours: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+pub struct ProjectGoalProgress {
+    pub project: String,
+    /// Monday of the week, `YYYY-MM-DD`.
+    pub week_start: String,
+    /// Focused seconds this week so far.
+    pub focus_time: u64,
+    /// The weekly goal in seconds.
+    pub goal_time: u64,
+    pub reached: bool,
+    /// Focused seconds by the end of the week if the pace so far holds.
+    pub forecast_focus_time: u64,
+    /// Whether the forecast reaches the goal.
+    pub on_track: bool,
+}
+
+impl ProjectGoalProgress {
+    /// Progress after `focus_time` seconds in the first `days_elapsed` days
+    /// of the week.
+    fn new(goal: &ProjectGoal, week_start: NaiveDate, days_elapsed: u64, focus_time: u64) -> Self {
+        let goal_time = u64::from(goal.weekly_focus_hours) * SECS_PER_HOUR;
+        let forecast_focus_time = focus_time * DAYS_PER_WEEK / days_elapsed.max(1);
+        Self {
+            project: goal.project.clone(),
+            week_start: week_start.format("%Y-%m-%d").to_string(),
+            focus_time,
+            goal_time,
+            reached: focus_time >= goal_time,
+            forecast_focus_time,
+            on_track: forecast_focus_time >= goal_time,
+        }
+    }
+}
+
+/// Progress on every project goal for the week containing `date`, counting
+/// days up to and including `date`.
+pub fn progress(storage: &StorageService, date: &str) -> Result<Vec<ProjectGoalProgress>, String> {
+    let goals = storage.load_preferences()?.project_goals;
+    if goals.is_empty() {
+        return Ok(Vec::new());
+    }
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{date}': {e}"))?;
+    let days_before = date.weekday().num_days_from_monday();
+    let week_start = date - Duration::days(days_before.into());
+    let days = storage.load_statistics(Some(week_start), Some(date))?;
+    let custom_types = storage.custom_session_types()?;
+
+    Ok(goals
+        .iter()
+        .map(|goal| {
+            let focus_time = days
+                .iter()
+                .flat_map(|day| &day.sessions)
+                .filter(|session| session.tags.contains(&goal.project))
+                .map(|session| session.contribution(&custom_types).1)
+                .sum();
+            ProjectGoalProgress::new(goal, week_start, u64::from(days_before) + 1, focus_time)
+        })
+        .collect())
+}
+
+/// Emits [`PROJECT_GOAL_REACHED_EVENT`] for each project that reached its
+/// goal this week since `before` was taken.
+pub fn announce_if_reached(
+    app: &AppHandle,
+    storage: &StorageService,
+    before: &[ProjectGoalProgress],
+) -> Result<(), String> {
+    for progress in progress(storage, &calendar::today(storage)?)? {
+        let was_reached = before
+            .iter()
+            .any(|earlier| earlier.project == progress.project && earlier.reached);
+        if !progress.reached || was_reached {
+            continue;
+        }
+        inbox::record_or_log(
+            app,
+            InboxKind::ProjectGoalReached,
+            format!("Weekly goal reached for {}", progress.project),
+            None,
+        );
+        app.emit(PROJECT_GOAL_REACHED_EVENT, progress)
+            .map_err(|e| format!("Failed to emit {PROJECT_GOAL_REACHED_EVENT}: {e}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{
+        day, session, SessionData, StorageServiceBuilder, UserPreferences,
+    };
+    use crate::services::timer_state::SessionType;
+
+    const HOUR: u64 = SECS_PER_HOUR;
+
+    fn goal(project: &str, weekly_focus_hours: u32) -> ProjectGoal {
+        ProjectGoal {
+            project: project.to_string(),
+            weekly_focus_hours,
+        }
+    }
+
+    fn tagged(tags: &[&str], seconds: u64, completed: bool) -> SessionData {
+        SessionData {
+            completed,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..session(SessionType::Work, 1_772_400_000, seconds)
+        }
+    }
+
+    #[test]
+    fn the_forecast_extends_the_pace_so_far_to_the_week() {
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let progress = ProjectGoalProgress::new(&goal("thesis", 10), monday, 2, 2 * HOUR);
+        assert_eq!(progress.week_start, "2026-03-02");
+        assert_eq!(progress.forecast_focus_time, 7 * HOUR);
+        assert!(!progress.reached && !progress.on_track);
+
+        let progress = ProjectGoalProgress::new(&goal("thesis", 10), monday, 3, 5 * HOUR);
+        assert!(!progress.reached && progress.on_track);
+    }
+
+    #[test]
+    fn each_project_counts_its_tagged_focus_this_week() {
+        let storage = StorageServiceBuilder::new()
+            .preferences(UserPreferences {
+                project_goals: vec![goal("thesis", 10), goal("site", 1)],
+                ..UserPreferences::default()
+            })
+            // The Sunday before belongs to the week before.
+            .statistic(day("2026-03-01", vec![tagged(&["thesis"], HOUR, true)]))
+            .statistic(day(
+                "2026-03-02",
+                vec![
+                    tagged(&["thesis", "site"], HOUR, true),
+                    tagged(&["thesis"], HOUR, false),
+                ],
+            ))
+            .statistic(day("2026-03-03", vec![tagged(&["thesis"], 2 * HOUR, true)]))
+            // After the day asked about.
+            .statistic(day("2026-03-05", vec![tagged(&["thesis"], HOUR, true)]))
+            .build()
+            .unwrap();
+
+        let progress = progress(&storage, "2026-03-04").unwrap();
+        let totals: Vec<_> = progress
+            .iter()
+            .map(|goal| (goal.project.as_str(), goal.focus_time, goal.reached))
+            .collect();
+        assert_eq!(totals, [("thesis", 3 * HOUR, false), ("site", HOUR, true)]);
+        assert!(progress.iter().all(|goal| goal.week_start == "2026-03-02"));
+    }
+}
@@ -0,0 +1,116 @@
+//! Holds back quitting the app or closing its window while a work session is
+//! in progress, so a stray shortcut doesn't throw the session away. Wired to
+//! the window and run events rather than left to the frontend, so it also
+//! covers quits the webview never sees.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use ts_rs::TS;
+
+use super::session_types::focus_session_active;
+use super::storage::UserPreferences;
+use super::timer_state::TimerManager;
+
+/// Emitted with the [`QuitGuardMode`] whenever a quit or close is held back.
+pub const QUIT_BLOCKED_EVENT: &str = "guard://quit-blocked";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum QuitGuardMode {
+    /// Quit and close right away.
+    #[default]
+    Off,
+    /// Ask before quitting during a work session.
+    Confirm,
+    /// Refuse to quit until the work session is finished or reset.
+    Strict,
+}
+
+pub struct QuitGuard {
+    mode: Mutex<QuitGuardMode>,
+    /// A dialog is already showing; further requests are just swallowed.
+    prompting: AtomicBool,
+}
+
+impl QuitGuard {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            mode: Mutex::new(preferences.quit_guard),
+            prompting: AtomicBool::new(false),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, QuitGuardMode>, String> {
+        self.mode
+            .lock()
+            .map_err(|e| format!("Quit guard lock poisoned: {e}"))
+    }
+
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        *self.lock()? = preferences.quit_guard;
+        Ok(())
+    }
+
+    /// The mode to apply now, or `None` if nothing needs guarding.
+    fn engaged(&self, app: &AppHandle) -> Option<QuitGuardMode> {
+        let mode = match self.lock() {
+            Ok(mode) => *mode,
+            Err(error) => {
+                log::error!("{error}");
+                return None;
+            }
+        };
+        let working = app
+            .state::<TimerManager>()
+            .get_data()
+            .is_ok_and(|data| focus_session_active(&data));
+        (mode != QuitGuardMode::Off && working).then_some(mode)
+    }
+}
+
+/// Decides on a request to close the window or quit. Returns `true` if it
+/// must be prevented; the app then quits later if the user confirms.
+pub fn intercept(app: &AppHandle) -> bool {
+    let guard = app.state::<QuitGuard>();
+    let Some(mode) = guard.engaged(app) else {
+        return false;
+    };
+    if let Err(error) = app.emit(QUIT_BLOCKED_EVENT, mode) {
+        log::error!("Failed to emit {QUIT_BLOCKED_EVENT}: {error}");
+    }
+    if guard.prompting.swap(true, Ordering::SeqCst) {
+        return true;
+    }
+
+    let (message, buttons) = match mode {
+        QuitGuardMode::Strict => (
+            "A work session is in progress. Finish or reset it before quitting.",
+            MessageDialogButtons::Ok,
+        ),
+        _ => (
+            "A work session is in progress. Quit anyway?",
+            MessageDialogButtons::OkCancelCustom("Quit".to_string(), "Keep working".to_string()),
+        ),
+    };
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Tempus Ring")
+        .kind(MessageDialogKind::Warning)
+        .buttons(buttons)
+        .show(move |confirmed| {
+            handle
+                .state::<QuitGuard>()
+                .prompting
+                .store(false, Ordering::SeqCst);
+            // Exits with an explicit code aren't intercepted again.
+            if confirmed && mode == QuitGuardMode::Confirm {
+                handle.exit(0);
+            }
+        });
+    true
+}
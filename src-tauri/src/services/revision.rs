@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+use super::timer_error::TimerError;
+
+/// Raised when a mutation was based on a stale revision of a resource,
+/// typically because another window changed it in the meantime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RevisionConflict {
+    pub resource: String,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl fmt::Display for RevisionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} was modified elsewhere (expected revision {}, found {})",
+            self.resource, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for RevisionConflict {}
+
+/// Error returned by revision-guarded mutations.
+#[derive(Debug)]
+pub enum MutationError {
+    Conflict(RevisionConflict),
+    Timer(TimerError),
+    Failed(String),
+}
+
+impl fmt::Display for MutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MutationError::Conflict(conflict) => conflict.fmt(f),
+            MutationError::Timer(error) => error.fmt(f),
+            MutationError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<RevisionConflict> for MutationError {
+    fn from(conflict: RevisionConflict) -> Self {
+        MutationError::Conflict(conflict)
+    }
+}
+
+impl From<TimerError> for MutationError {
+    fn from(error: TimerError) -> Self {
+        MutationError::Timer(error)
+    }
+}
+
+impl From<String> for MutationError {
+    fn from(message: String) -> Self {
+        MutationError::Failed(message)
+    }
+}
+
+/// Verifies that the caller's view of a resource is current.
+///
+/// `expected` is optional so callers that don't track revisions keep working;
+/// they simply opt out of conflict detection.
+pub fn check_revision(
+    resource: &str,
+    expected: Option<u64>,
+    actual: u64,
+) -> Result<(), RevisionConflict> {
+    match expected {
+        Some(expected) if expected != actual => Err(RevisionConflict {
+            resource: resource.to_string(),
+            expected,
+            actual,
+        }),
+        _ => Ok(()),
+    }
+}
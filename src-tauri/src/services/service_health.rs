@@ -0,0 +1,244 @@
+//! Tracks how the background services that talk to the outside world are
+//! doing, so repeated failures reach the user instead of only the log.
+//! Each service reports its outcome after every run; after
+//! [`ALERT_AFTER_FAILURES`] failures in a row an alert goes to the inbox.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::inbox::{self, InboxKind};
+
+/// Consecutive failures before a service is reported in the inbox.
+pub const ALERT_AFTER_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundService {
+    /// The end-of-day webhook push.
+    EndOfDay,
+    /// Blocking and unblocking distracting sites during focus sessions.
+    DistractionBlocker,
+    AutoBackup,
+    DashboardFeed,
+    /// Registering and following `tempusring://` links.
+    DeepLinks,
+    /// The focus status page for door displays.
+    DoorStatus,
+    /// Shell commands run on timer events.
+    Hooks,
+    SlackStatus,
+    /// Logging pomodoros to linked tasks in a task manager.
+    TaskProvider,
+}
+
+impl BackgroundService {
+    fn name(self) -> &'static str {
+        match self {
+            Self::EndOfDay => "End-of-day push",
+            Self::DistractionBlocker => "Distraction blocker",
+            Self::AutoBackup => "Automatic backup",
+            Self::DashboardFeed => "Dashboard feed",
+            Self::DeepLinks => "Deep links",
+            Self::DoorStatus => "Door status",
+            Self::Hooks => "Timer hooks",
+            Self::SlackStatus => "Slack status",
+            Self::TaskProvider => "Task sync",
+        }
+    }
+
+    /// What the user can check to fix the failure.
+    fn hint(self) -> &'static str {
+        match self {
+            Self::EndOfDay => "Check the webhook URLs in the end-of-day settings and that they accept POST requests.",
+            Self::DistractionBlocker => "Check the blocker actions: the hosts file needs administrator rights, and shell commands and webhooks must succeed.",
+            Self::AutoBackup => "Check the backup folder still exists and is writable, or choose another one.",
+            Self::DashboardFeed => "Check the feed file's folder still exists and is writable, or choose another one.",
+            Self::DeepLinks => "Turn on deep link access in the external access settings. On Linux, check xdg-utils is installed.",
+            Self::DoorStatus => "Check the door status folder still exists and is writable, or choose another one.",
+            Self::Hooks => "Check the hook commands run from a terminal and finish within their timeout.",
+            Self::SlackStatus => "Reconnect Slack if the token was revoked or lacks the users.profile:write scope.",
+            Self::TaskProvider => "Reconnect the task manager if its API token was revoked.",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ServiceStatus {
+    pub service: BackgroundService,
+    /// The last run succeeded, or there hasn't been one yet.
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// Unix seconds.
+    pub last_success_at: Option<u64>,
+    /// Unix seconds.
+    pub last_failure_at: Option<u64>,
+}
+
+impl ServiceStatus {
+    fn new(service: BackgroundService) -> Self {
+        Self {
+            service,
+            healthy: true,
+            consecutive_failures: 0,
+            last_error: None,
+            last_success_at: None,
+            last_failure_at: None,
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The latest outcome of each background service since launch.
+#[derive(Default)]
+pub struct ServiceHealth {
+    services: Mutex<BTreeMap<BackgroundService, ServiceStatus>>,
+}
+
+impl ServiceHealth {
+    fn lock(&self) -> Result<MutexGuard<'_, BTreeMap<BackgroundService, ServiceStatus>>, String> {
+        self.services
+            .lock()
+            .map_err(|e| format!("Service health lock poisoned: {e}"))
+    }
+
+    /// Services that have run at least once.
+    pub fn statuses(&self) -> Result<Vec<ServiceStatus>, String> {
+        Ok(self.lock()?.values().cloned().collect())
+    }
+
+    /// Records the outcome of a run and returns the status if it just
+    /// crossed [`ALERT_AFTER_FAILURES`].
+    fn record(
+        &self,
+        service: BackgroundService,
+        result: Result<(), &str>,
+    ) -> Result<Option<ServiceStatus>, String> {
+        let mut services = self.lock()?;
+        let status = services
+            .entry(service)
+            .or_insert_with(|| ServiceStatus::new(service));
+        match result {
+            Ok(()) => {
+                status.healthy = true;
+                status.consecutive_failures = 0;
+                status.last_success_at = Some(unix_now());
+            }
+            Err(error) => {
+                status.healthy = false;
+                status.consecutive_failures += 1;
+                status.last_error = Some(error.to_string());
+                status.last_failure_at = Some(unix_now());
+            }
+        }
+        Ok((status.consecutive_failures == ALERT_AFTER_FAILURES).then(|| status.clone()))
+    }
+}
+
+/// Records the outcome of a run of `service`, alerting in the inbox once
+/// it has failed [`ALERT_AFTER_FAILURES`] times in a row.
+pub fn report<T>(app: &AppHandle, service: BackgroundService, result: &Result<T, String>) {
+    let outcome = result.as_ref().map(|_| ()).map_err(String::as_str);
+    let alert = match app.state::<ServiceHealth>().record(service, outcome) {
+        Ok(alert) => alert,
+        Err(error) => {
+            log::error!("{error}");
+            return;
+        }
+    };
+    let Some(status) = alert else {
+        return;
+    };
+    let error = status.last_error.unwrap_or_default();
+    inbox::record_or_log(
+        app,
+        InboxKind::IntegrationFailed,
+        format!("{} keeps failing", service.name()),
+        Some(format!(
+            "Failed {} times in a row: {error}\n{}",
+            status.consecutive_failures,
+            service.hint()
+        )),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alerts_once_when_failures_reach_the_threshold() {
+        let health = ServiceHealth::default();
+        for _ in 1..ALERT_AFTER_FAILURES {
+            let alert = health.record(BackgroundService::Hooks, Err("exit 1"));
+            assert!(alert.unwrap().is_none());
+        }
+        let alert = health
+            .record(BackgroundService::Hooks, Err("exit 2"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(alert.consecutive_failures, ALERT_AFTER_FAILURES);
+        assert_eq!(alert.last_error.as_deref(), Some("exit 2"));
+        assert!(!alert.healthy);
+
+        let again = health.record(BackgroundService::Hooks, Err("exit 3"));
+        assert!(again.unwrap().is_none());
+    }
+
+    #[test]
+    fn a_success_resets_the_count_and_keeps_the_last_error() {
+        let health = ServiceHealth::default();
+        for _ in 1..ALERT_AFTER_FAILURES {
+            health
+                .record(BackgroundService::SlackStatus, Err("revoked"))
+                .unwrap();
+        }
+        health
+            .record(BackgroundService::SlackStatus, Ok(()))
+            .unwrap();
+        let alert = health.record(BackgroundService::SlackStatus, Err("revoked"));
+        assert!(alert.unwrap().is_none());
+
+        let statuses = health.statuses().unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].consecutive_failures, 1);
+        assert!(statuses[0].last_success_at.is_some());
+        assert_eq!(statuses[0].last_error.as_deref(), Some("revoked"));
+    }
+
+    #[test]
+    fn services_are_tracked_separately() {
+        let health = ServiceHealth::default();
+        health
+            .record(BackgroundService::Hooks, Err("exit 1"))
+            .unwrap();
+        health
+            .record(BackgroundService::DoorStatus, Ok(()))
+            .unwrap();
+
+        let statuses = health.statuses().unwrap();
+        let healthy: Vec<(BackgroundService, bool)> = statuses
+            .iter()
+            .map(|status| (status.service, status.healthy))
+            .collect();
+        assert_eq!(
+            healthy,
+            [
+                (BackgroundService::DoorStatus, true),
+                (BackgroundService::Hooks, false)
+            ]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Splitting and merging recorded sessions, for cleaning up a day after a
+//! forgotten timer or a block that got fragmented. Both work on a day's
+//! session list and return the edited list; the caller saves it and
+//! recomputes the day's totals, so neither changes how many pomodoros or
+//! how much focus time a day holds.
+
+use super::storage::SessionData;
+use super::window_activity;
+
+/// Splits the session at `index` at Unix time `at`, which must fall strictly
+/// inside it. Both halves keep the session's type, task and tags; only the
+/// second keeps its completion, so a pomodoro still counts once. Their
+/// durations span any overtime.
+pub fn split(sessions: &[SessionData], index: usize, at: u64) -> Result<Vec<SessionData>, String> {
+    let session = sessions
+        .get(index)
+        .ok_or_else(|| format!("Session {index} not found"))?;
+    let end = session.span_end();
+    if at <= session.start_time || at >= end {
+        return Err(format!(
+            "must fall between the session's start {} and end {end}",
+            session.start_time
+        ));
+    }
+
+    let first = SessionData {
+        duration: at - session.start_time,
+        completed: false,
+        end_time: Some(at),
+        overtime: 0,
+        extended: 0,
+        ..session.clone()
+    };
+    let second = SessionData {
+        duration: end - at,
+        start_time: at,
+        end_time: Some(end),
+        overtime: 0,
+        ..session.clone()
+    };
+    let mut edited = sessions.to_vec();
+    edited.splice(index..=index, [first, second]);
+    Ok(edited)
+}
+
+/// Merges the sessions at `indices`, which must share a type, into one
+/// running from the earliest start to the latest end, at the position of
+/// the first. Its duration and overtime are the parts' added up, so gaps
+/// between them don't count as focus. It is completed if any part was,
+/// keeps the earliest task and issue, and carries every part's tags.
+pub fn merge(sessions: &[SessionData], indices: &[usize]) -> Result<Vec<SessionData>, String> {
+    let mut indices = indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+    if indices.len() < 2 {
+        return Err("at least two different sessions are needed".to_string());
+    }
+    let mut parts = Vec::with_capacity(indices.len());
+    for &index in &indices {
+        parts.push(
+            sessions
+                .get(index)
+                .ok_or_else(|| format!("Session {index} not found"))?,
+        );
+    }
+    if parts
+        .iter()
+        .any(|part| part.session_type != parts[0].session_type)
+    {
+        return Err("only sessions of the same type can be merged".to_string());
+    }
+    parts.sort_by_key(|part| part.start_time);
+
+    let start = parts[0].start_time;
+    let end = parts
+        .iter()
+        .map(|part| part.span_end())
+        .max()
+        .unwrap_or(start);
+    let mut tags: Vec<String> = Vec::new();
+    for tag in parts.iter().flat_map(|part| &part.tags) {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    let merged = SessionData {
+        session_type: parts[0].session_type.clone(),
+        duration: parts.iter().map(|part| part.duration).sum(),
+        completed: parts.iter().any(|part| part.completed),
+        start_time: start,
+        end_time: Some(end),
+        task_id: parts.iter().find_map(|part| part.task_id.clone()),
+        tags,
+        issue: parts.iter().find_map(|part| part.issue.clone()),
+        context: parts.iter().find_map(|part| part.context.clone()),
+        overtime: parts.iter().map(|part| part.overtime).sum(),
+        extended: parts.iter().map(|part| part.extended).sum(),
+        windows: window_activity::top(parts.iter().flat_map(|part| part.windows.clone())),
+    };
+
+    let mut edited: Vec<SessionData> = sessions
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !indices.contains(index))
+        .map(|(_, session)| session.clone())
+        .collect();
+    edited.insert(indices[0], merged);
+    Ok(edited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::session;
+    use crate::services::timer_state::SessionType;
+
+    #[test]
+    fn split_keeps_one_completion() {
+        let sessions = [session(SessionType::Work, 1_000, 1_500)];
+        let split = split(&sessions, 0, 1_600).unwrap();
+        assert_eq!(split.len(), 2);
+        assert_eq!(
+            (split[0].start_time, split[0].duration, split[0].completed),
+            (1_000, 600, false)
+        );
+        assert_eq!(
+            (split[1].start_time, split[1].duration, split[1].completed),
+            (1_600, 900, true)
+        );
+        assert!(split(&sessions, 0, 1_000).is_err());
+        assert!(split(&sessions, 0, 2_500).is_err());
+    }
+
+    #[test]
+    fn merge_adds_up_durations_without_the_gap() {
+        let sessions = [
+            session(SessionType::Work, 1_000, 600),
+            session(SessionType::ShortBreak, 1_600, 300),
+            session(SessionType::Work, 5_000, 900),
+        ];
+        let merged = merge(&sessions, &[2, 0]).unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].start_time, 1_000);
+        assert_eq!(merged[0].end_time, Some(5_900));
+        assert_eq!(merged[0].duration, 1_500);
+        assert_eq!(merged[1].session_type, SessionType::ShortBreak);
+        assert!(merge(&sessions, &[0, 1]).is_err());
+        assert!(merge(&sessions, &[0, 0]).is_err());
+    }
+}
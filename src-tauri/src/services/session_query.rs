@@ -0,0 +1,237 @@
+//! Searching the session history a day at a time, newest first, so a page
+//! of a long history doesn't need all of it in memory.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use super::storage::{SessionData, StorageService};
+use super::tasks::Task;
+use super::timer_state::SessionType;
+
+/// Every field left out matches any session.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SessionFilter {
+    pub session_type: Option<SessionType>,
+    pub completed: Option<bool>,
+    pub task_id: Option<String>,
+    /// Sessions carrying this tag.
+    pub tag: Option<String>,
+    /// Found, ignoring case, in the linked task's title, a tag or the issue.
+    pub text: Option<String>,
+}
+
+/// A recorded session with where it is stored, for editing it.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct HistorySession {
+    pub date: String,
+    /// Position in the day's sessions.
+    pub index: usize,
+    pub session: SessionData,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SessionPage {
+    /// Newest first.
+    pub sessions: Vec<HistorySession>,
+    /// Sessions matching the filter across all pages.
+    pub total: usize,
+    /// Zero-based.
+    pub page: usize,
+    pub page_size: usize,
+}
+
+impl SessionFilter {
+    /// `titles` maps task ids to lowercased titles; `text` is lowercased.
+    fn matches(
+        &self,
+        session: &SessionData,
+        titles: &HashMap<&str, String>,
+        text: Option<&str>,
+    ) -> bool {
+        let type_matches = self
+            .session_type
+            .as_ref()
+            .is_none_or(|session_type| *session_type == session.session_type);
+        let completed_matches = self
+            .completed
+            .is_none_or(|completed| completed == session.completed);
+        let task_matches = self
+            .task_id
+            .as_ref()
+            .is_none_or(|task_id| session.task_id.as_ref() == Some(task_id));
+        let tag_matches = self
+            .tag
+            .as_ref()
+            .is_none_or(|tag| session.tags.contains(tag));
+        let text_matches = text.is_none_or(|text| {
+            let title = session
+                .task_id
+                .as_deref()
+                .and_then(|task_id| titles.get(task_id));
+            title.is_some_and(|title| title.contains(text))
+                || session
+                    .tags
+                    .iter()
+                    .any(|tag| tag.to_lowercase().contains(text))
+                || session
+                    .issue
+                    .as_ref()
+                    .is_some_and(|issue| issue.contains(text))
+        });
+        type_matches && completed_matches && task_matches && tag_matches && text_matches
+    }
+}
+
+/// The `page`th `page_size` sessions matching `filter`, newest first, with
+/// the number matching overall.
+pub fn query(
+    storage: &StorageService,
+    filter: &SessionFilter,
+    tasks: &[Task],
+    page: usize,
+    page_size: usize,
+) -> Result<SessionPage, String> {
+    let titles: HashMap<&str, String> = tasks
+        .iter()
+        .map(|task| (task.id.as_str(), task.title.to_lowercase()))
+        .collect();
+    let text = filter
+        .text
+        .as_deref()
+        .map(|text| text.trim().to_lowercase())
+        .filter(|text| !text.is_empty());
+    let skip = page.saturating_mul(page_size);
+
+    let mut result = SessionPage {
+        sessions: Vec::new(),
+        total: 0,
+        page,
+        page_size,
+    };
+    for date in storage.statistic_dates()?.into_iter().rev() {
+        let Some(statistic) = storage.load_statistic(&date)? else {
+            continue;
+        };
+        let mut sessions: Vec<_> = statistic.sessions.into_iter().enumerate().collect();
+        sessions.sort_by_key(|(_, session)| std::cmp::Reverse(session.start_time));
+        for (index, session) in sessions {
+            if !filter.matches(&session, &titles, text.as_deref()) {
+                continue;
+            }
+            if result.total >= skip && result.sessions.len() < page_size {
+                result.sessions.push(HistorySession {
+                    date: date.clone(),
+                    index,
+                    session,
+                });
+            }
+            result.total += 1;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, session, StorageServiceBuilder};
+
+    fn work(start_time: u64, task_id: Option<&str>, tags: &[&str]) -> SessionData {
+        SessionData {
+            task_id: task_id.map(str::to_string),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..session(SessionType::Work, start_time, 1500)
+        }
+    }
+
+    fn task(id: &str, title: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: title.to_string(),
+            estimated_pomodoros: 0,
+            completed_pomodoros: 0,
+            completed: false,
+            created_at: 0,
+            completed_at: None,
+            archived_at: None,
+            revision: 0,
+        }
+    }
+
+    fn found(page: &SessionPage) -> Vec<(&str, usize)> {
+        page.sessions
+            .iter()
+            .map(|entry| (entry.date.as_str(), entry.index))
+            .collect()
+    }
+
+    #[test]
+    fn pages_run_newest_first_across_days() {
+        let storage = StorageServiceBuilder::new()
+            .statistic(day(
+                "2026-03-02",
+                vec![work(100, None, &[]), work(300, None, &[])],
+            ))
+            .statistic(day(
+                "2026-03-03",
+                vec![work(500, None, &[]), work(400, None, &[])],
+            ))
+            .build()
+            .unwrap();
+        let filter = SessionFilter::default();
+
+        let first = query(&storage, &filter, &[], 0, 3).unwrap();
+        assert_eq!(first.total, 4);
+        assert_eq!(
+            found(&first),
+            [("2026-03-03", 0), ("2026-03-03", 1), ("2026-03-02", 1)]
+        );
+        let second = query(&storage, &filter, &[], 1, 3).unwrap();
+        assert_eq!(found(&second), [("2026-03-02", 0)]);
+        assert!(query(&storage, &filter, &[], 2, 3)
+            .unwrap()
+            .sessions
+            .is_empty());
+    }
+
+    #[test]
+    fn text_matches_task_titles_tags_and_issues_ignoring_case() {
+        let mut with_issue = work(300, None, &[]);
+        with_issue.issue = Some("tempus-ring/app#7".to_string());
+        let storage = StorageServiceBuilder::new()
+            .statistic(day(
+                "2026-03-02",
+                vec![
+                    work(100, Some("essay"), &[]),
+                    work(200, None, &["Writing"]),
+                    with_issue,
+                    work(400, None, &["email"]),
+                ],
+            ))
+            .build()
+            .unwrap();
+        let tasks = [task("essay", "Essay on WRITING")];
+        let search = |text: &str| {
+            let filter = SessionFilter {
+                text: Some(text.to_string()),
+                ..SessionFilter::default()
+            };
+            query(&storage, &filter, &tasks, 0, 10).unwrap().total
+        };
+        assert_eq!(search(" writ "), 2);
+        assert_eq!(search("APP#7"), 1);
+        assert_eq!(search("   "), 4);
+
+        let tagged = SessionFilter {
+            tag: Some("email".to_string()),
+            task_id: Some("essay".to_string()),
+            ..SessionFilter::default()
+        };
+        assert_eq!(query(&storage, &tagged, &tasks, 0, 10).unwrap().total, 0);
+    }
+}
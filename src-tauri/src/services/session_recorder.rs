@@ -0,0 +1,99 @@
+//! Records sessions into the statistics as the timer ends them, so none
+//! are lost when no window is open to save them.
+
+use tauri::{AppHandle, Manager};
+
+use super::calendar::{self, day_key};
+use super::context_detection::ContextDetector;
+use super::focus_budget;
+use super::goals;
+use super::project_goals;
+use super::storage::{SessionData, StorageService};
+use super::storage_file::StorageError;
+use super::timer_state::{Tick, TimerManager};
+use super::window_activity::WindowTracker;
+
+/// Adds `session` to the statistic of the day it started on and returns
+/// that day's key.
+pub fn insert(storage: &StorageService, session: SessionData) -> Result<String, StorageError> {
+    let calendar = storage.load_preferences()?.calendar;
+    let date = day_key(calendar.day_of(session.start_time));
+    storage.insert_session(&date, usize::MAX, session)?;
+    Ok(date)
+}
+
+/// Inserts each of `sessions` in turn, removing it once it is stored or
+/// held for a retry with its day, so on failure `sessions` holds the ones
+/// still to be recorded.
+fn insert_all(storage: &StorageService, sessions: &mut Vec<SessionData>) -> Result<(), String> {
+    let mut result = Ok(());
+    while let Some(session) = sessions.first() {
+        match insert(storage, session.clone()) {
+            Ok(_) => {}
+            Err(error @ StorageError::Held(_)) => result = Err(error.to_string()),
+            Err(error) => return Err(error.to_string()),
+        }
+        sessions.remove(0);
+    }
+    result
+}
+
+/// Takes the sessions `timer` ended and records them with `record`. The
+/// ones not recorded go back to the timer, to be retried with the next.
+pub fn record_taken<F>(timer: &TimerManager, record: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Vec<SessionData>) -> Result<(), String>,
+{
+    let mut sessions = timer.take_sessions()?;
+    if sessions.is_empty() {
+        return Ok(());
+    }
+    let result = record(&mut sessions);
+    if !sessions.is_empty() {
+        timer.return_sessions(sessions)?;
+    }
+    result
+}
+
+/// Ticks `timer` and writes what came of it: transitions to the event log
+/// and ended sessions to the statistics. For the focus server and the CLI,
+/// which run the timer without the app's other services.
+pub fn tick_and_record(timer: &TimerManager, storage: &StorageService) -> Result<Tick, String> {
+    let tick = timer.tick()?;
+    let events = timer.take_events()?;
+    if !events.is_empty() {
+        storage.append_events(&events)?;
+    }
+    record_taken(timer, |sessions| insert_all(storage, sessions))?;
+    Ok(tick)
+}
+
+/// Records `sessions` in the current work context, with the windows in
+/// front during them if annotation is on, and announces goals
+/// reached and focus caps crossed by them. Sessions are removed from
+/// `sessions` as they are stored; on failure the rest stay in it.
+pub fn record(
+    app: &AppHandle,
+    storage: &StorageService,
+    sessions: &mut Vec<SessionData>,
+) -> Result<(), String> {
+    let today = calendar::today(storage)?;
+    let before = storage
+        .load_statistic(&today)?
+        .map_or(0, |statistic| statistic.completed_pomodoros);
+    let projects_before = project_goals::progress(storage, &today)?;
+    let usage_before = focus_budget::usage(storage)?;
+    let context = app.state::<ContextDetector>().current()?.name;
+    let windows = app.state::<WindowTracker>();
+    for session in sessions.iter_mut() {
+        // A retried session keeps what it was annotated with the first time.
+        if session.context.is_none() {
+            session.context = context.clone();
+        }
+        session.windows.extend(windows.take(session.start_time)?);
+    }
+    insert_all(storage, sessions)?;
+    focus_budget::announce_if_exceeded(app, storage, usage_before)?;
+    goals::announce_if_reached(app, storage, &today, before)?;
+    project_goals::announce_if_reached(app, storage, &projects_before)
+}
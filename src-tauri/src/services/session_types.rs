@@ -0,0 +1,146 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use super::break_detection::{self, BreakDetection};
+use super::storage::TimerStatistic;
+use super::timer_state::{SessionType, TimerData, TimerState};
+
+/// A user-defined session such as Reading or Exercise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct CustomSessionType {
+    /// Stable key stored in statistics, e.g. `reading`.
+    pub id: String,
+    pub name: String,
+    /// `#RRGGBB`
+    pub color: String,
+    /// Default length in seconds.
+    pub duration: u64,
+    /// Whether finishing one adds to pomodoro and focus totals.
+    pub counts_as_pomodoro: bool,
+}
+
+impl CustomSessionType {
+    fn new(id: &str, name: &str, color: &str, minutes: u64, counts_as_pomodoro: bool) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            color: color.to_string(),
+            duration: minutes * 60,
+            counts_as_pomodoro,
+        }
+    }
+}
+
+pub fn default_custom_types() -> Vec<CustomSessionType> {
+    vec![
+        CustomSessionType::new("reading", "Reading", "#4F86C6", 30, true),
+        CustomSessionType::new("admin", "Admin", "#9A8C98", 15, false),
+        CustomSessionType::new("exercise", "Exercise", "#5CB85C", 20, false),
+    ]
+}
+
+pub fn find_custom<'a>(types: &'a [CustomSessionType], id: &str) -> Option<&'a CustomSessionType> {
+    types.iter().find(|custom| custom.id == id)
+}
+
+/// Whether a completed session of `session_type` counts as a pomodoro.
+/// Custom types that have since been deleted no longer count.
+pub fn counts_as_pomodoro(session_type: &SessionType, custom_types: &[CustomSessionType]) -> bool {
+    match session_type {
+        SessionType::Work => true,
+        SessionType::ShortBreak | SessionType::LongBreak => false,
+        SessionType::Custom(id) => {
+            find_custom(custom_types, id).is_some_and(|custom| custom.counts_as_pomodoro)
+        }
+    }
+}
+
+/// Whether `data` is in a pomodoro or other session that counts as focus
+/// time, running or paused.
+pub fn focus_session_active(data: &TimerData) -> bool {
+    data.state != TimerState::Idle
+        && counts_as_pomodoro(&data.session_type, &data.config.custom_session_types)
+}
+
+/// Totals for one session type over a date range.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SessionTypeTotal {
+    pub session_type: SessionType,
+    pub name: String,
+    /// Only custom types carry a color; built-ins are themed by the UI.
+    pub color: Option<String>,
+    pub sessions: u32,
+    pub completed_sessions: u32,
+    /// Seconds spent in completed sessions.
+    pub total_time: u64,
+    /// Idle gaps counted as long breaks, included in the other totals.
+    pub implicit_sessions: u32,
+}
+
+type Totals = BTreeMap<(u8, String), SessionTypeTotal>;
+
+fn total_for<'a>(
+    totals: &'a mut Totals,
+    session_type: &SessionType,
+    custom_types: &[CustomSessionType],
+) -> &'a mut SessionTypeTotal {
+    let rank = match session_type {
+        SessionType::Work => 0,
+        SessionType::ShortBreak => 1,
+        SessionType::LongBreak => 2,
+        SessionType::Custom(_) => 3,
+    };
+    totals
+        .entry((rank, session_type.key().to_string()))
+        .or_insert_with(|| {
+            let custom = match session_type {
+                SessionType::Custom(id) => find_custom(custom_types, id),
+                _ => None,
+            };
+            SessionTypeTotal {
+                session_type: session_type.clone(),
+                name: custom.map_or_else(
+                    || session_type.display_name().to_string(),
+                    |custom| custom.name.clone(),
+                ),
+                color: custom.map(|custom| custom.color.clone()),
+                sessions: 0,
+                completed_sessions: 0,
+                total_time: 0,
+                implicit_sessions: 0,
+            }
+        })
+}
+
+/// Groups every recorded session by type, built-ins first and custom types
+/// in key order. Gaps matching `breaks` count as completed long breaks.
+pub fn group_by_session_type(
+    statistics: &[TimerStatistic],
+    custom_types: &[CustomSessionType],
+    breaks: &BreakDetection,
+) -> Vec<SessionTypeTotal> {
+    let mut totals = Totals::new();
+    for session in statistics.iter().flat_map(|statistic| &statistic.sessions) {
+        let entry = total_for(&mut totals, &session.session_type, custom_types);
+        entry.sessions += 1;
+        if session.completed {
+            entry.completed_sessions += 1;
+            entry.total_time += session.duration;
+        }
+    }
+    for gap in statistics
+        .iter()
+        .flat_map(|statistic| break_detection::detect(statistic, breaks))
+    {
+        let entry = total_for(&mut totals, &SessionType::LongBreak, custom_types);
+        entry.sessions += 1;
+        entry.completed_sessions += 1;
+        entry.total_time += gap.end - gap.start;
+        entry.implicit_sessions += 1;
+    }
+    totals.into_values().collect()
+}
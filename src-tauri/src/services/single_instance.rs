@@ -0,0 +1,40 @@
+//! Keeps the desktop app to one instance. A second launch hands its
+//! command line to the running app, which comes to the front, and exits.
+//! The data directory lock in `StorageService` backs this up for the
+//! focus server and launches the plugin can't see, such as another user
+//! session.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+/// Emitted with an [`InstanceInvocation`] when the app is launched again.
+pub const SECOND_INSTANCE_EVENT: &str = "app://second-instance";
+/// Launches that only open a `tempusring://` link are left to the deep
+/// link handler, without bringing the window forward.
+const LINK_PREFIX: &str = "tempusring:";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct InstanceInvocation {
+    /// The second launch's arguments, starting with the program.
+    pub args: Vec<String>,
+    /// Its working directory.
+    pub cwd: String,
+}
+
+/// Called in the running instance when another is launched.
+pub fn on_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) -> Result<(), String> {
+    if args.iter().skip(1).any(|arg| arg.starts_with(LINK_PREFIX)) {
+        return Ok(());
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        window
+            .unminimize()
+            .and_then(|()| window.show())
+            .and_then(|()| window.set_focus())
+            .map_err(|e| format!("Failed to bring the window forward: {e}"))?;
+    }
+    app.emit(SECOND_INSTANCE_EVENT, InstanceInvocation { args, cwd })
+        .map_err(|e| format!("Failed to emit {SECOND_INSTANCE_EVENT}: {e}"))
+}
@@ -0,0 +1,209 @@
+//! Sets the user's Slack status while a session runs ("🍅 Focusing until
+//! 14:25") and clears it on a break or reset, using a user OAuth token with
+//! the `users.profile:write` scope. The status carries an expiration, so it
+//! clears itself if the app quits mid-session.
+
+use chrono::{Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::service_health::{self, BackgroundService};
+use super::session_types::counts_as_pomodoro;
+use super::storage::{StorageService, UserPreferences};
+use super::timer_state::{SessionType, TimerData, TimerState};
+
+const API_BASE: &str = "https://slack.com/api";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Which phases set a status; the others clear it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SlackStatusConfig {
+    /// Work sessions and custom types that count as pomodoros.
+    pub work: bool,
+    pub short_break: bool,
+    /// Long breaks and custom types that don't count as pomodoros.
+    pub long_break: bool,
+}
+
+impl Default for SlackStatusConfig {
+    fn default() -> Self {
+        Self {
+            work: true,
+            short_break: false,
+            long_break: false,
+        }
+    }
+}
+
+/// The Slack account a token belongs to, as reported by `auth.test`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SlackAccount {
+    pub team: String,
+    pub user: String,
+}
+
+#[derive(Debug, Clone)]
+struct SlackStatus {
+    text: String,
+    emoji: &'static str,
+    /// Unix seconds Slack clears the status at, or 0 to keep it.
+    expires_at: u64,
+}
+
+/// Calls the Slack Web API method `method`, failing on `"ok": false`.
+async fn call(token: &str, method: &str, body: Value) -> Result<Value, String> {
+    let response: Value = reqwest::Client::new()
+        .post(format!("{API_BASE}/{method}"))
+        .timeout(REQUEST_TIMEOUT)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Slack {method} failed: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Slack {method} returned an invalid response: {e}"))?;
+    if response["ok"].as_bool() != Some(true) {
+        let error = response["error"].as_str().unwrap_or("unknown error");
+        return Err(format!("Slack {method} failed: {error}"));
+    }
+    Ok(response)
+}
+
+/// Checks `token` and returns the account it belongs to.
+pub async fn verify_token(token: &str) -> Result<SlackAccount, String> {
+    let response = call(token, "auth.test", json!({})).await?;
+    Ok(SlackAccount {
+        team: response["team"].as_str().unwrap_or_default().to_string(),
+        user: response["user"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+/// Sets `status`, or clears the status for `None`.
+async fn set_status(token: &str, status: Option<&SlackStatus>) -> Result<(), String> {
+    let profile = match status {
+        Some(status) => json!({
+            "status_text": status.text,
+            "status_emoji": status.emoji,
+            "status_expiration": status.expires_at,
+        }),
+        None => json!({
+            "status_text": "",
+            "status_emoji": "",
+            "status_expiration": 0,
+        }),
+    };
+    call(token, "users.profile.set", json!({ "profile": profile }))
+        .await
+        .map(|_| ())
+}
+
+pub async fn clear_status(token: &str) -> Result<(), String> {
+    set_status(token, None).await
+}
+
+/// The status `data` calls for under `config`, or `None` to clear it.
+fn wanted_status(config: &SlackStatusConfig, data: &TimerData) -> Option<SlackStatus> {
+    if data.state == TimerState::Idle {
+        return None;
+    }
+    let focus = counts_as_pomodoro(&data.session_type, &data.config.custom_session_types);
+    let enabled = match &data.session_type {
+        _ if focus => config.work,
+        SessionType::ShortBreak => config.short_break,
+        _ => config.long_break,
+    };
+    if !enabled {
+        return None;
+    }
+    let emoji = if focus { ":tomato:" } else { ":coffee:" };
+    let doing = if focus { "Focusing" } else { "On a break" };
+    // A stopwatch or overtime has no end, so its status doesn't expire.
+    if matches!(data.state, TimerState::Stopwatch | TimerState::Overtime) {
+        return Some(SlackStatus {
+            text: doing.to_string(),
+            emoji,
+            expires_at: 0,
+        });
+    }
+    let end = Local::now() + TimeDelta::seconds(data.remaining_time as i64);
+    Some(SlackStatus {
+        text: format!("{doing} until {}", end.format("%H:%M")),
+        emoji,
+        expires_at: end.timestamp().max(0) as u64,
+    })
+}
+
+struct SlackState {
+    config: SlackStatusConfig,
+    /// Text of the status last sent, so each change is sent once.
+    current: Option<String>,
+}
+
+/// Follows timer ticks and updates the Slack status on the transitions.
+pub struct SlackStatusService {
+    state: Mutex<SlackState>,
+}
+
+impl SlackStatusService {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(SlackState {
+                config: preferences.slack_status.clone(),
+                current: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, SlackState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Slack status lock poisoned: {e}"))
+    }
+
+    /// Picks up changed phase toggles; they apply from the next tick.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        self.lock()?.config = preferences.slack_status.clone();
+        Ok(())
+    }
+
+    /// Forgets the status last sent, after it was cleared by disconnecting.
+    pub fn reset(&self) -> Result<(), String> {
+        self.lock()?.current = None;
+        Ok(())
+    }
+
+    /// A paused session keeps its status; its end time is updated once the
+    /// session resumes. Requests are sent in the background.
+    pub fn on_tick(&self, app: &AppHandle, data: &TimerData) -> Result<(), String> {
+        if data.state == TimerState::Paused {
+            return Ok(());
+        }
+        let mut state = self.lock()?;
+        let wanted = wanted_status(&state.config, data);
+        let text = wanted.as_ref().map(|status| status.text.clone());
+        if text == state.current {
+            return Ok(());
+        }
+        let Some(token) = app.state::<StorageService>().load_slack_token()? else {
+            return Ok(());
+        };
+        state.current = text;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = set_status(&token, wanted.as_ref()).await;
+            if let Err(error) = &result {
+                log::error!("{error}");
+            }
+            service_health::report(&app, BackgroundService::SlackStatus, &result);
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use ts_rs::TS;
+
+use super::focus_budget::BudgetPeriod;
+use super::storage::TimerStatistic;
+use super::tasks::Task;
+
+const SCHEMA: &str = "
+CREATE TABLE days (
+    date TEXT PRIMARY KEY,
+    completed_pomodoros INTEGER NOT NULL,
+    total_focus_time INTEGER NOT NULL
+);
+CREATE TABLE tasks (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    estimated_pomodoros INTEGER NOT NULL,
+    completed_pomodoros INTEGER NOT NULL,
+    completed INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    completed_at INTEGER,
+    archived_at INTEGER
+);
+CREATE TABLE sessions (
+    id INTEGER PRIMARY KEY,
+    date TEXT NOT NULL REFERENCES days(date),
+    session_type TEXT NOT NULL,
+    duration INTEGER NOT NULL,
+    completed INTEGER NOT NULL,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER,
+    task_id TEXT REFERENCES tasks(id),
+    issue TEXT,
+    context TEXT,
+    overtime INTEGER NOT NULL,
+    extended INTEGER NOT NULL
+);
+CREATE INDEX sessions_by_date ON sessions(date);
+CREATE INDEX sessions_by_task ON sessions(task_id);
+CREATE INDEX sessions_by_issue ON sessions(issue);
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE session_tags (
+    session_id INTEGER NOT NULL REFERENCES sessions(id),
+    tag_id INTEGER NOT NULL REFERENCES tags(id),
+    PRIMARY KEY (session_id, tag_id)
+);
+CREATE TABLE session_windows (
+    session_id INTEGER NOT NULL REFERENCES sessions(id),
+    app TEXT NOT NULL,
+    title TEXT NOT NULL,
+    seconds INTEGER NOT NULL
+);
+CREATE TABLE fatigue_advisories (
+    id INTEGER PRIMARY KEY,
+    date TEXT NOT NULL REFERENCES days(date),
+    timestamp INTEGER NOT NULL,
+    skipped_breaks INTEGER NOT NULL,
+    suggested_break INTEGER NOT NULL
+);
+CREATE TABLE overwork (
+    id INTEGER PRIMARY KEY,
+    date TEXT NOT NULL REFERENCES days(date),
+    timestamp INTEGER NOT NULL,
+    period TEXT NOT NULL,
+    focus_time INTEGER NOT NULL,
+    cap INTEGER NOT NULL,
+    refused_auto_start INTEGER NOT NULL
+);
+";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SqliteExportSummary {
+    pub days: usize,
+    pub sessions: usize,
+    pub tasks: usize,
+}
+
+/// Writes `statistics` and `tasks` into a fresh, self-contained SQLite file:
+/// each day with its sessions, their tags and windows, and the day's fatigue
+/// advisories and overwork events.
+///
+/// The database is built next to `path` and renamed into place, so a failed
+/// export never leaves a half-written file behind.
+pub fn export_sqlite(
+    path: &Path,
+    statistics: &[TimerStatistic],
+    tasks: &[Task],
+) -> Result<SqliteExportSummary, String> {
+    let staging = path.with_extension("sqlite.partial");
+    let _ = fs::remove_file(&staging);
+
+    let summary = write_database(&staging, statistics, tasks).inspect_err(|_| {
+        let _ = fs::remove_file(&staging);
+    })?;
+    fs::rename(&staging, path).map_err(|e| format!("Failed to move export into place: {e}"))?;
+    Ok(summary)
+}
+
+fn write_database(
+    path: &Path,
+    statistics: &[TimerStatistic],
+    tasks: &[Task],
+) -> Result<SqliteExportSummary, String> {
+    let sql_error = |e: rusqlite::Error| format!("SQLite export failed: {e}");
+    let mut conn = Connection::open(path).map_err(sql_error)?;
+    conn.execute_batch(SCHEMA).map_err(sql_error)?;
+
+    let tx = conn.transaction().map_err(sql_error)?;
+    let mut sessions = 0;
+    {
+        let mut insert_task = tx
+            .prepare("INSERT INTO tasks VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")
+            .map_err(sql_error)?;
+        for task in tasks {
+            insert_task
+                .execute(params![
+                    task.id,
+                    task.title,
+                    task.estimated_pomodoros,
+                    task.completed_pomodoros,
+                    task.completed,
+                    task.created_at,
+                    task.completed_at,
+                    task.archived_at,
+                ])
+                .map_err(sql_error)?;
+        }
+
+        let mut insert_day = tx
+            .prepare("INSERT INTO days VALUES (?1, ?2, ?3)")
+            .map_err(sql_error)?;
+        let mut insert_tag = tx
+            .prepare("INSERT OR IGNORE INTO tags (name) VALUES (?1)")
+            .map_err(sql_error)?;
+        let mut find_tag = tx
+            .prepare("SELECT id FROM tags WHERE name = ?1")
+            .map_err(sql_error)?;
+        let mut tag_session = tx
+            .prepare("INSERT OR IGNORE INTO session_tags VALUES (?1, ?2)")
+            .map_err(sql_error)?;
+        let mut insert_session = tx
+            .prepare(
+                "INSERT INTO sessions
+                 (date, session_type, duration, completed, start_time, end_time, task_id, issue, context, overtime, extended)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )
+            .map_err(sql_error)?;
+        let mut insert_window = tx
+            .prepare("INSERT INTO session_windows VALUES (?1, ?2, ?3, ?4)")
+            .map_err(sql_error)?;
+        let mut insert_advisory = tx
+            .prepare(
+                "INSERT INTO fatigue_advisories
+                 (date, timestamp, skipped_breaks, suggested_break)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .map_err(sql_error)?;
+        let mut insert_overwork = tx
+            .prepare(
+                "INSERT INTO overwork
+                 (date, timestamp, period, focus_time, cap, refused_auto_start)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )
+            .map_err(sql_error)?;
+        for statistic in statistics {
+            insert_day
+                .execute(params![
+                    statistic.date,
+                    statistic.completed_pomodoros,
+                    statistic.total_focus_time,
+                ])
+                .map_err(sql_error)?;
+            for session in &statistic.sessions {
+                insert_session
+                    .execute(params![
+                        statistic.date,
+                        session.session_type.key(),
+                        session.duration,
+                        session.completed,
+                        session.start_time,
+                        session.end_time,
+                        session.task_id,
+                        session.issue,
+                        session.context,
+                        session.overtime,
+                        session.extended,
+                    ])
+                    .map_err(sql_error)?;
+                let session_id = tx.last_insert_rowid();
+                for tag in &session.tags {
+                    insert_tag.execute(params![tag]).map_err(sql_error)?;
+                    let tag_id: i64 = find_tag
+                        .query_row(params![tag], |row| row.get(0))
+                        .map_err(sql_error)?;
+                    tag_session
+                        .execute(params![session_id, tag_id])
+                        .map_err(sql_error)?;
+                }
+                for window in &session.windows {
+                    insert_window
+                        .execute(params![
+                            session_id,
+                            window.app,
+                            window.title,
+                            window.seconds
+                        ])
+                        .map_err(sql_error)?;
+                }
+                sessions += 1;
+            }
+            for advisory in &statistic.advisories {
+                insert_advisory
+                    .execute(params![
+                        statistic.date,
+                        advisory.timestamp,
+                        advisory.skipped_breaks,
+                        advisory.suggested_break,
+                    ])
+                    .map_err(sql_error)?;
+            }
+            for event in &statistic.overwork {
+                let period = match event.period {
+                    BudgetPeriod::Day => "day",
+                    BudgetPeriod::Week => "week",
+                };
+                insert_overwork
+                    .execute(params![
+                        statistic.date,
+                        event.timestamp,
+                        period,
+                        event.focus_time,
+                        event.cap,
+                        event.refused_auto_start,
+                    ])
+                    .map_err(sql_error)?;
+            }
+        }
+    }
+    tx.commit().map_err(sql_error)?;
+
+    Ok(SqliteExportSummary {
+        days: statistics.len(),
+        sessions,
+        tasks: tasks.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::fatigue::FatigueAdvisory;
+    use crate::services::focus_budget::OverworkEvent;
+    use crate::services::storage::{day, session, SessionData, StorageServiceBuilder};
+    use crate::services::tasks::TaskManager;
+    use crate::services::timer_state::SessionType;
+    use crate::services::window_activity::WindowFocus;
+
+    #[test]
+    fn exported_rows_read_back_from_the_file() {
+        let start = 1_772_438_400;
+        let storage = StorageServiceBuilder::new()
+            .statistic(day(
+                "2026-03-02",
+                vec![
+                    SessionData {
+                        tags: vec!["writing".to_string()],
+                        windows: vec![WindowFocus {
+                            app: "Editor".to_string(),
+                            title: "draft.md".to_string(),
+                            seconds: 1200,
+                        }],
+                        ..session(SessionType::Work, start, 1500)
+                    },
+                    session(SessionType::ShortBreak, start + 1500, 300),
+                ],
+            ))
+            .build()
+            .unwrap();
+        storage
+            .record_advisory(
+                "2026-03-02",
+                FatigueAdvisory {
+                    timestamp: start + 1800,
+                    skipped_breaks: 3,
+                    suggested_break: 900,
+                },
+            )
+            .unwrap();
+        storage
+            .record_overwork(
+                "2026-03-02",
+                OverworkEvent {
+                    timestamp: start + 1900,
+                    period: BudgetPeriod::Day,
+                    focus_time: 4 * 3600,
+                    cap: 4 * 3600,
+                    refused_auto_start: true,
+                },
+            )
+            .unwrap();
+        let tasks = TaskManager::new(Vec::new(), Vec::new());
+        tasks.create(&storage, "Draft".to_string(), 2).unwrap();
+
+        let path = storage.path().join("export.sqlite");
+        let summary = export_sqlite(
+            &path,
+            &storage.load_statistics(None, None).unwrap(),
+            &tasks.list_with_archived().unwrap(),
+        )
+        .unwrap();
+        assert_eq!((summary.days, summary.sessions, summary.tasks), (1, 2, 1));
+
+        let conn = Connection::open(&path).unwrap();
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert_eq!(count("days"), 1);
+        assert_eq!(count("sessions"), 2);
+        assert_eq!(count("tasks"), 1);
+        let focus: i64 = conn
+            .query_row("SELECT total_focus_time FROM days", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(focus, 1500);
+        let tagged: (String, String) = conn
+            .query_row(
+                "SELECT sessions.session_type, tags.name FROM session_tags
+                 JOIN sessions ON sessions.id = session_tags.session_id
+                 JOIN tags ON tags.id = session_tags.tag_id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            tagged,
+            (SessionType::Work.key().to_string(), "writing".to_string())
+        );
+        let window: (String, i64) = conn
+            .query_row("SELECT title, seconds FROM session_windows", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(window, ("draft.md".to_string(), 1200));
+        let skipped: i64 = conn
+            .query_row("SELECT skipped_breaks FROM fatigue_advisories", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(skipped, 3);
+        let overwork: (String, bool) = conn
+            .query_row(
+                "SELECT period, refused_auto_start FROM overwork",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(overwork, ("day".to_string(), true));
+    }
+}
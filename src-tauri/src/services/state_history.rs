@@ -0,0 +1,244 @@
+//! A ring buffer of timer engine transitions, with the state before and
+//! after each, so a reported glitch can be inspected and replayed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+#[cfg(feature = "simulation")]
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::power::SleepPolicy;
+#[cfg(feature = "simulation")]
+use super::storage::UserPreferences;
+#[cfg(feature = "simulation")]
+use super::timer_error::TimerError;
+use super::timer_state::{SessionType, TimerConfig, TimerData};
+#[cfg(feature = "simulation")]
+use super::timer_state::{TimerManager, TimerState};
+
+/// Transitions kept; older ones are dropped.
+pub const HISTORY_CAPACITY: usize = 500;
+
+/// What caused a transition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimerOperation {
+    Start,
+    /// Started for `duration` seconds instead of the configured length.
+    StartFor {
+        duration: u64,
+    },
+    StartStopwatch,
+    /// The stopwatch stopped; `recorded` if its time went into the
+    /// statistics.
+    StopStopwatch {
+        recorded: bool,
+    },
+    Pause,
+    Resume,
+    Reset,
+    Skip,
+    Complete,
+    /// The running session ran out.
+    Expire,
+    /// The running session ran out and went into overtime.
+    Overrun,
+    /// `seconds` were added to the session.
+    Extend {
+        seconds: u64,
+    },
+    SwitchSession {
+        session_type: SessionType,
+    },
+    UpdateConfig {
+        config: TimerConfig,
+    },
+    SetTimeScale {
+        speed: f64,
+    },
+    /// The system woke from a sleep of `asleep_ms` by the wall clock, of
+    /// which the engine clock counted `counted_ms`.
+    Wake {
+        policy: SleepPolicy,
+        asleep_ms: u64,
+        counted_ms: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StateTransition {
+    /// Counts transitions since launch, so gaps show dropped entries.
+    pub sequence: u64,
+    pub operation: TimerOperation,
+    /// Engine clock reading in milliseconds.
+    pub clock_ms: u64,
+    /// Time spent in the session before the transition, in milliseconds.
+    pub elapsed_ms: u64,
+    /// Unix time of the transition.
+    pub timestamp: u64,
+    pub before: TimerData,
+    pub after: TimerData,
+}
+
+/// The engine state captured just before a transition.
+pub struct TransitionStart {
+    pub clock_ms: u64,
+    pub elapsed_ms: u64,
+    pub before: TimerData,
+}
+
+#[derive(Default)]
+pub struct StateHistory {
+    entries: VecDeque<StateTransition>,
+    next_sequence: u64,
+}
+
+impl StateHistory {
+    pub fn record(
+        &mut self,
+        start: TransitionStart,
+        operation: TimerOperation,
+        timestamp: u64,
+        after: TimerData,
+    ) {
+        if self.entries.len() == HISTORY_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(StateTransition {
+            sequence: self.next_sequence,
+            operation,
+            clock_ms: start.clock_ms,
+            elapsed_ms: start.elapsed_ms,
+            timestamp,
+            before: start.before,
+            after,
+        });
+        self.next_sequence += 1;
+    }
+
+    /// The newest `limit` transitions, oldest first.
+    pub fn recent(&self, limit: usize) -> Vec<StateTransition> {
+        let skip = self.entries.len().saturating_sub(limit);
+        self.entries.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// A replayed transition that did not end where the recording did.
+#[cfg(feature = "simulation")]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ReplayDivergence {
+    pub sequence: u64,
+    pub operation: TimerOperation,
+    pub expected: TimerData,
+    /// `None` if the engine rejected the operation.
+    pub actual: Option<TimerData>,
+    pub error: Option<String>,
+}
+
+#[cfg(feature = "simulation")]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ReplayReport {
+    pub steps: usize,
+    pub divergences: Vec<ReplayDivergence>,
+}
+
+/// Wall-clock fields differ on every run, so only the engine state is
+/// compared.
+#[cfg(feature = "simulation")]
+fn same_state(a: &TimerData, b: &TimerData) -> bool {
+    a.state == b.state
+        && a.session_type == b.session_type
+        && a.remaining_time == b.remaining_time
+        && a.total_time == b.total_time
+        && a.completed_pomodoros == b.completed_pomodoros
+        && a.revision == b.revision
+}
+
+/// Sets the clock to the `transition`'s readings and applies its operation.
+#[cfg(feature = "simulation")]
+fn apply(timer: &TimerManager, transition: &StateTransition) -> Result<TimerData, String> {
+    timer.set_clock(
+        Duration::from_millis(transition.clock_ms),
+        transition.timestamp,
+    )?;
+    let result = match transition.operation.clone() {
+        TimerOperation::Start => timer.start(None),
+        TimerOperation::StartFor { duration } => timer.start_for(Some(duration), None),
+        TimerOperation::StartStopwatch => timer.start_stopwatch(None),
+        TimerOperation::StopStopwatch { recorded } => timer.stop_stopwatch(recorded, None),
+        TimerOperation::Pause => timer.pause(None),
+        TimerOperation::Resume => timer.resume(None),
+        TimerOperation::Reset => timer.reset(None),
+        TimerOperation::Skip => timer.skip(None),
+        TimerOperation::Complete => timer.complete_session(None),
+        TimerOperation::Expire => {
+            if !timer.check_completion()? {
+                return Err("The session had not run out".to_string());
+            }
+            return Ok(timer.get_data()?);
+        }
+        TimerOperation::Overrun => {
+            if timer.check_completion()? || timer.get_data()?.state != TimerState::Overtime {
+                return Err("The session had not run over".to_string());
+            }
+            return Ok(timer.get_data()?);
+        }
+        TimerOperation::Extend { seconds } => timer.extend_session(seconds, None),
+        TimerOperation::SwitchSession { session_type } => timer.switch_session(session_type, None),
+        TimerOperation::UpdateConfig { config } => timer.update_config(config, None, |_| Ok(())),
+        TimerOperation::SetTimeScale { speed } => return Ok(timer.set_time_scale(speed)?),
+        TimerOperation::Wake {
+            policy,
+            asleep_ms,
+            counted_ms,
+        } => timer
+            .wake(
+                policy,
+                Duration::from_millis(asleep_ms),
+                Duration::from_millis(counted_ms),
+            )
+            .and_then(|data| data.ok_or_else(|| TimerError::NotRunning.into())),
+    };
+    result.map_err(|error| error.to_string())
+}
+
+/// Runs `history` through a fresh engine seeded with the state before its
+/// first transition and the stored `preferences`' schedule, experiment and
+/// day boundary, at the recorded clock readings and times, and reports every
+/// step whose outcome differs from the recording.
+#[cfg(feature = "simulation")]
+pub fn replay(history: &[StateTransition], preferences: &UserPreferences) -> ReplayReport {
+    let mut divergences = Vec::new();
+    if let Some(first) = history.first() {
+        let timer = TimerManager::restore(
+            &first.before,
+            Duration::from_millis(first.elapsed_ms),
+            Duration::from_millis(first.clock_ms),
+            first.timestamp,
+            preferences,
+        );
+        for transition in history {
+            let result = apply(&timer, transition);
+            let (actual, error) = match result {
+                Ok(actual) if same_state(&actual, &transition.after) => continue,
+                Ok(actual) => (Some(actual), None),
+                Err(error) => (None, Some(error)),
+            };
+            divergences.push(ReplayDivergence {
+                sequence: transition.sequence,
+                operation: transition.operation.clone(),
+                expected: transition.after.clone(),
+                actual,
+                error,
+            });
+        }
+    }
+    ReplayReport {
+        steps: history.len(),
+        divergences,
+    }
+}
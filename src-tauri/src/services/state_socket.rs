@@ -0,0 +1,149 @@
+//! Pushes the timer state over a local WebSocket on every tick and
+//! transition, for OBS overlays and dashboards that want the live countdown
+//! without polling. The socket is read-only.
+//!
+//! Clients connect to `ws://127.0.0.1:50155/?token=<token>` with the token
+//! from `get_surface_token`, passed in the URL since browser sources can't
+//! set headers. Messages use the display server's format: a `state` message
+//! with the current [`TimerData`] right away and on every change after, or
+//! an `error` message before the socket closes.
+
+use futures_util::{SinkExt, StreamExt};
+use tauri::AppHandle;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use super::api_context::ApiContext;
+use super::display_server::ServerMessage;
+use super::listen_addr::{listen_addr, Interfaces};
+use super::surface_auth::{Access, Surface};
+use super::timer_state::TimerData;
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:50155";
+/// Overrides [`DEFAULT_ADDR`]. The desktop app only accepts a loopback
+/// address here; the headless server accepts any.
+pub const ADDR_ENV: &str = "TEMPUS_WS_ADDR";
+
+/// The `token` query parameter of the handshake request.
+fn query_token(request: &Request) -> Option<String> {
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .map(str::to_string)
+}
+
+/// Fails unless `token` is the surface token and the overlay surface is
+/// enabled; checked again before every message so rotating the token or
+/// disabling the surface ends open streams.
+fn verify(ctx: &impl ApiContext, token: Option<&str>) -> Result<(), String> {
+    ctx.auth()
+        .authorize(ctx.storage(), Surface::Overlay, token, Access::Read)
+        .map_err(|e| e.to_string())
+}
+
+async fn send(
+    socket: &mut WebSocketStream<TcpStream>,
+    message: &ServerMessage,
+) -> Result<(), String> {
+    let text = serde_json::to_string(message)
+        .map_err(|e| format!("Failed to serialize state message: {e}"))?;
+    socket
+        .send(Message::text(text))
+        .await
+        .map_err(|e| format!("Failed to send to state socket: {e}"))
+}
+
+async fn send_state(
+    ctx: &impl ApiContext,
+    socket: &mut WebSocketStream<TcpStream>,
+    token: Option<&str>,
+    data: TimerData,
+) -> Result<(), String> {
+    if let Err(message) = verify(ctx, token) {
+        send(
+            socket,
+            &ServerMessage::Error {
+                message: message.clone(),
+            },
+        )
+        .await?;
+        return Err(message);
+    }
+    send(
+        socket,
+        &ServerMessage::State {
+            data: Box::new(data),
+        },
+    )
+    .await
+}
+
+async fn serve_client<C: ApiContext>(ctx: C, stream: TcpStream) -> Result<(), String> {
+    let mut token = None;
+    let mut socket = tokio_tungstenite::accept_hdr_async(
+        stream,
+        |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+            token = query_token(request);
+            Ok(response)
+        },
+    )
+    .await
+    .map_err(|e| format!("State socket handshake failed: {e}"))?;
+    let token = token.as_deref();
+
+    // Subscribe before the first snapshot so no transition falls between.
+    let mut updates = ctx.timer().subscribe();
+    let data = ctx.timer().get_data()?;
+    send_state(&ctx, &mut socket, token, data).await?;
+    loop {
+        let data = tokio::select! {
+            update = updates.recv() => match update {
+                Ok(data) => data,
+                // Skip to the current state rather than replaying stale ticks.
+                Err(RecvError::Lagged(_)) => ctx.timer().get_data()?,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            incoming = socket.next() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return Ok(()),
+                Some(Ok(_)) => continue,
+            },
+        };
+        send_state(&ctx, &mut socket, token, data).await?;
+    }
+}
+
+/// Accepts clients on `listener` until it fails.
+pub async fn serve<C: ApiContext>(listener: TcpListener, ctx: C) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let ctx = ctx.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(error) = serve_client(ctx, stream).await {
+                log::error!("State socket connection failed: {error}");
+            }
+        });
+    }
+}
+
+/// Starts the state socket in the background. Failures are logged rather
+/// than aborting the app, since the desktop UI doesn't depend on it.
+pub fn spawn_server(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let listener = match listen_addr(ADDR_ENV, DEFAULT_ADDR, Interfaces::Loopback) {
+            Ok(addr) => TcpListener::bind(addr).await.map_err(|e| e.to_string()),
+            Err(error) => Err(error),
+        };
+        let listener = match listener {
+            Ok(listener) => listener,
+            Err(error) => {
+                log::error!("State socket not started: {error}");
+                return;
+            }
+        };
+        serve(listener, app).await;
+    });
+}
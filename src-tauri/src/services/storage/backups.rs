@@ -0,0 +1,153 @@
+//! Writing, verifying and restoring backups of all stored data.
+
+use std::fs;
+use std::path::Path;
+
+use super::StorageService;
+use crate::services::backup_format::{self, BackupData, BackupInfo, RestoreSelection};
+use crate::services::storage_file::{self, StorageError};
+use crate::services::tasks::Task;
+
+impl StorageService {
+    /// Writes a backup to `path`, encrypted if a `passphrase` is given.
+    pub fn backup_data(&self, path: &Path, passphrase: Option<&str>) -> Result<(), StorageError> {
+        let backup = BackupData {
+            preferences: self.load_preferences()?,
+            statistics: self.load_statistics(None, None)?,
+            tasks: [self.load_tasks()?, self.load_archived_tasks()?].concat(),
+        };
+        let content =
+            backup_format::encode(&backup, passphrase).map_err(StorageError::Serialization)?;
+        storage_file::replace(path, content.as_bytes())
+    }
+
+    fn read_backup_file(path: &Path) -> Result<String, StorageError> {
+        if !path.exists() {
+            return Err(StorageError::NotFound(format!(
+                "Backup file not found: {}",
+                path.display()
+            )));
+        }
+        fs::read_to_string(path)
+            .map_err(|e| StorageError::Io(format!("Failed to read {}: {e}", path.display())))
+    }
+
+    /// Reads and verifies the backup at `path`, migrating older formats.
+    /// Encrypted backups need their `passphrase`.
+    pub fn read_backup(
+        &self,
+        path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<BackupData, StorageError> {
+        let content = Self::read_backup_file(path)?;
+        backup_format::decode(&content, passphrase)
+            .map_err(|e| StorageError::Serialization(format!("{}: {e}", path.display())))
+    }
+
+    /// Verifies the backup at `path` and describes what it holds.
+    pub fn inspect_backup(
+        &self,
+        path: &Path,
+        passphrase: Option<&str>,
+    ) -> Result<BackupInfo, StorageError> {
+        let content = Self::read_backup_file(path)?;
+        backup_format::inspect(&content, passphrase)
+            .map_err(|e| StorageError::Serialization(format!("{}: {e}", path.display())))
+    }
+
+    /// Restores the `selection` of a backup read by [`Self::read_backup`].
+    /// Restored statistics days replace the stored ones; other days are
+    /// kept. Directories that widen file access, and hooks and blocker
+    /// actions that run shell commands, are kept as they are.
+    pub fn restore_backup(
+        &self,
+        mut backup: BackupData,
+        selection: &RestoreSelection,
+    ) -> Result<(), StorageError> {
+        if selection.preferences {
+            self.update_preferences(|current| {
+                backup.preferences.export_dir = current.export_dir.take();
+                backup.preferences.dashboard_feed.path = current.dashboard_feed.path.take();
+                backup.preferences.door_status.dir = current.door_status.dir.take();
+                backup.preferences.auto_backup.dir = current.auto_backup.dir.take();
+                backup.preferences.hooks = std::mem::take(&mut current.hooks);
+                backup.preferences.distraction_blocker.actions =
+                    std::mem::take(&mut current.distraction_blocker.actions);
+                backup.preferences.timer_backend = std::mem::take(&mut current.timer_backend);
+                backup.preferences.revision = current.revision;
+                *current = backup.preferences;
+            })?;
+        }
+        for statistic in &backup.statistics {
+            if selection.includes(&statistic.date) {
+                self.save_statistic(statistic)?;
+            }
+        }
+        if selection.tasks && !backup.tasks.is_empty() {
+            let (archived, tasks): (Vec<Task>, Vec<Task>) = backup
+                .tasks
+                .into_iter()
+                .partition(|task| task.archived_at.is_some());
+            self.save_archived_tasks(&archived)?;
+            self.save_tasks(&tasks)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, StorageServiceBuilder, TimerStatistic, UserPreferences};
+    use crate::services::tasks::TaskManager;
+
+    fn statistic(date: &str, completed_pomodoros: u32) -> TimerStatistic {
+        TimerStatistic {
+            completed_pomodoros,
+            total_focus_time: u64::from(completed_pomodoros) * 25 * 60,
+            ..day(date, Vec::new())
+        }
+    }
+
+    fn titles(tasks: Vec<Task>) -> Vec<String> {
+        tasks.into_iter().map(|task| task.title).collect()
+    }
+
+    #[test]
+    fn backup_restores_into_empty_storage() {
+        let source = StorageServiceBuilder::new()
+            .preferences(UserPreferences {
+                daily_goal: 12,
+                ..UserPreferences::default()
+            })
+            .statistic(statistic("2026-03-01", 4))
+            .build()
+            .unwrap();
+        let tasks = TaskManager::new(Vec::new(), Vec::new());
+        let done = tasks
+            .create(&source, "Write report".to_string(), 2)
+            .unwrap();
+        tasks.complete(&source, &done.id, None).unwrap();
+        tasks.create(&source, "Review PR".to_string(), 1).unwrap();
+        tasks.archive_completed(&source, u64::MAX).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.json");
+        source.backup_data(&path, Some("passphrase")).unwrap();
+
+        let target = StorageServiceBuilder::new().build().unwrap();
+        let backup = target.read_backup(&path, Some("passphrase")).unwrap();
+        target
+            .restore_backup(backup, &RestoreSelection::default())
+            .unwrap();
+
+        assert_eq!(target.load_preferences().unwrap().daily_goal, 12);
+        let day = target.load_statistic("2026-03-01").unwrap().unwrap();
+        assert_eq!(day.completed_pomodoros, 4);
+        assert_eq!(titles(target.load_tasks().unwrap()), ["Review PR"]);
+        assert_eq!(
+            titles(target.load_archived_tasks().unwrap()),
+            ["Write report"]
+        );
+    }
+}
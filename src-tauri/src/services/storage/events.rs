@@ -0,0 +1,126 @@
+//! The timer event log, `events.jsonl`, rotated once it grows too large.
+
+use chrono::NaiveDate;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use super::StorageService;
+use crate::services::event_log::{self, TimerEvent};
+
+const EVENT_LOG_FILE: &str = "events.jsonl";
+/// The event log before it last grew past [`MAX_EVENT_LOG_BYTES`].
+const ROTATED_EVENT_LOG_FILE: &str = "events.1.jsonl";
+const MAX_EVENT_LOG_BYTES: u64 = 4 * 1024 * 1024;
+
+impl StorageService {
+    /// Appends `events` to the timer event log, one JSON object per line.
+    /// If that fails they are held and written by [`Self::flush_pending`].
+    pub fn append_events(&self, events: &[TimerEvent]) -> Result<(), String> {
+        self.write_buffer
+            .append_events(events, |events| self.write_events(events))
+    }
+
+    /// Appends `events` to the log, first moving it aside once it has grown
+    /// past [`MAX_EVENT_LOG_BYTES`] so only the latest two files are kept.
+    pub(super) fn write_events(&self, events: &[TimerEvent]) -> Result<(), String> {
+        let mut lines = String::new();
+        for event in events {
+            lines += &serde_json::to_string(event)
+                .map_err(|e| format!("Failed to serialize timer event: {e}"))?;
+            lines.push('\n');
+        }
+        let path = self.data_dir.join(EVENT_LOG_FILE);
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > MAX_EVENT_LOG_BYTES) {
+            fs::rename(&path, self.data_dir.join(ROTATED_EVENT_LOG_FILE))
+                .map_err(|e| format!("Failed to rotate event log: {e}"))?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .map_err(|e| format!("Failed to append to event log: {e}"))
+    }
+
+    /// The events in the log at `path` that `keep` accepts, read a line at
+    /// a time, and the timestamp of its first event. A line cut short by a
+    /// crash is skipped rather than failing the whole log.
+    fn read_events(
+        path: &Path,
+        keep: impl Fn(&TimerEvent) -> bool,
+    ) -> Result<(Vec<TimerEvent>, Option<u64>), String> {
+        if !path.exists() {
+            return Ok((Vec::new(), None));
+        }
+        let file = File::open(path).map_err(|e| format!("Failed to read event log: {e}"))?;
+        let (mut events, mut first) = (Vec::new(), None);
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| format!("Failed to read event log: {e}"))?;
+            let Ok(event) = serde_json::from_str::<TimerEvent>(&line) else {
+                continue;
+            };
+            first.get_or_insert(event.timestamp);
+            if keep(&event) {
+                events.push(event);
+            }
+        }
+        Ok((events, first))
+    }
+
+    /// Logged timer events on the days `from..=to`, oldest first. Either end
+    /// may be omitted.
+    pub fn load_events(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<TimerEvent>, String> {
+        let calendar = self.load_preferences()?.calendar;
+        let keep = |event: &TimerEvent| event_log::in_range(event, from, to, &calendar);
+        let (mut events, first) = Self::read_events(&self.data_dir.join(EVENT_LOG_FILE), keep)?;
+        // The rotated log only holds older events, so it isn't needed when
+        // the current one already reaches back past `from`.
+        let covered = from
+            .zip(first)
+            .is_some_and(|(from, first)| calendar.day_of(first) < from);
+        if !covered {
+            let (mut older, _) =
+                Self::read_events(&self.data_dir.join(ROTATED_EVENT_LOG_FILE), keep)?;
+            older.append(&mut events);
+            events = older;
+        }
+        events.extend(self.write_buffer.events()?.into_iter().filter(keep));
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::event_log::TimerEventKind;
+    use crate::services::storage::StorageServiceBuilder;
+    use crate::services::timer_state::SessionType;
+
+    #[test]
+    fn the_event_log_rotates_and_loads_by_day() {
+        let storage = StorageServiceBuilder::new().build().unwrap();
+        let day = 24 * 60 * 60;
+        let event = |timestamp: u64| TimerEvent {
+            kind: TimerEventKind::Start,
+            session_type: SessionType::Work,
+            remaining_time: 25 * 60,
+            timestamp,
+        };
+        // Enough noon events on 2026-01-01 to push the log past its limit.
+        let old: Vec<TimerEvent> = (0..60_000).map(|_| event(1_767_268_800)).collect();
+        storage.append_events(&old).unwrap();
+        storage
+            .append_events(&[event(1_767_268_800 + 30 * day)])
+            .unwrap();
+
+        assert!(storage.path().join(ROTATED_EVENT_LOG_FILE).exists());
+        let recent = NaiveDate::from_ymd_opt(2026, 1, 20);
+        assert_eq!(storage.load_events(recent, None).unwrap().len(), 1);
+        assert_eq!(storage.load_events(None, None).unwrap().len(), 60_001);
+    }
+}
@@ -0,0 +1,228 @@
+//! Small state files, access tokens, tasks and presets kept next to the
+//! preferences.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use super::{StorageService, ARCHIVED_TASKS_FILE, TASKS_FILE};
+use crate::services::appearance::AppearanceState;
+use crate::services::burnout::BurnoutState;
+use crate::services::display_client::DisplayConnection;
+use crate::services::display_server::PairedDisplay;
+use crate::services::end_of_day::PushState;
+use crate::services::inbox::InboxEntry;
+use crate::services::presets::{default_presets, TimerPreset};
+use crate::services::storage_file;
+use crate::services::task_provider::TaskProviderKind;
+use crate::services::tasks::Task;
+
+const PRESETS_FILE: &str = "presets.json";
+const PUSH_STATE_FILE: &str = "end_of_day.json";
+const BURNOUT_STATE_FILE: &str = "burnout.json";
+const APPEARANCE_STATE_FILE: &str = "appearance.json";
+const SURFACE_TOKEN_FILE: &str = "surface_token";
+const SLACK_TOKEN_FILE: &str = "slack_token";
+const FOCUS_SERVER_TOKEN_FILE: &str = "focus_server_token";
+const PAIRED_DISPLAYS_FILE: &str = "displays.json";
+const DISPLAY_CONNECTION_FILE: &str = "display_client.json";
+const INBOX_FILE: &str = "inbox.json";
+const CONTROL_ENDPOINT_FILE: &str = "control.json";
+
+impl StorageService {
+    pub fn load_push_state(&self) -> Result<PushState, String> {
+        Ok(Self::read_json(&self.data_dir.join(PUSH_STATE_FILE))?.unwrap_or_default())
+    }
+
+    pub fn save_push_state(&self, state: &PushState) -> Result<(), String> {
+        Self::write_json(&self.data_dir.join(PUSH_STATE_FILE), state)
+    }
+
+    pub fn load_burnout_state(&self) -> Result<BurnoutState, String> {
+        Ok(Self::read_json(&self.data_dir.join(BURNOUT_STATE_FILE))?.unwrap_or_default())
+    }
+
+    pub fn save_burnout_state(&self, state: &BurnoutState) -> Result<(), String> {
+        Self::write_json(&self.data_dir.join(BURNOUT_STATE_FILE), state)
+    }
+
+    pub fn load_appearance_state(&self) -> Result<AppearanceState, String> {
+        Ok(Self::read_json(&self.data_dir.join(APPEARANCE_STATE_FILE))?.unwrap_or_default())
+    }
+
+    pub fn save_appearance_state(&self, state: &AppearanceState) -> Result<(), String> {
+        Self::write_json(&self.data_dir.join(APPEARANCE_STATE_FILE), state)
+    }
+
+    /// Where the running app publishes its control socket.
+    pub fn control_endpoint_path(&self) -> PathBuf {
+        self.data_dir.join(CONTROL_ENDPOINT_FILE)
+    }
+
+    /// Where the surface access token is kept, for pointing users at it
+    /// without printing the token itself.
+    pub fn surface_token_path(&self) -> PathBuf {
+        self.data_dir.join(SURFACE_TOKEN_FILE)
+    }
+
+    pub fn load_surface_token(&self) -> Result<Option<String>, String> {
+        let path = self.surface_token_path();
+        Ok(storage_file::read_private(&path)?)
+    }
+
+    pub fn save_surface_token(&self, token: &str) -> Result<(), String> {
+        let path = self.surface_token_path();
+        Ok(storage_file::replace_private(&path, token.as_bytes())?)
+    }
+
+    /// The Slack OAuth token from `connect_slack`, if connected.
+    pub fn load_slack_token(&self) -> Result<Option<String>, String> {
+        let path = self.data_dir.join(SLACK_TOKEN_FILE);
+        Ok(storage_file::read_private(&path)?)
+    }
+
+    pub fn save_slack_token(&self, token: &str) -> Result<(), String> {
+        let path = self.data_dir.join(SLACK_TOKEN_FILE);
+        Ok(storage_file::replace_private(&path, token.as_bytes())?)
+    }
+
+    pub fn delete_slack_token(&self) -> Result<(), String> {
+        let path = self.data_dir.join(SLACK_TOKEN_FILE);
+        if !path.exists() {
+            return Ok(());
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {e}", path.display()))
+    }
+
+    /// The access token for the focus server in `timer_backend`.
+    pub fn load_focus_server_token(&self) -> Result<Option<String>, String> {
+        let path = self.data_dir.join(FOCUS_SERVER_TOKEN_FILE);
+        Ok(storage_file::read_private(&path)?)
+    }
+
+    pub fn save_focus_server_token(&self, token: &str) -> Result<(), String> {
+        let path = self.data_dir.join(FOCUS_SERVER_TOKEN_FILE);
+        Ok(storage_file::replace_private(&path, token.as_bytes())?)
+    }
+
+    /// The API token for task provider `kind`, if it is connected.
+    pub fn load_task_provider_token(
+        &self,
+        kind: TaskProviderKind,
+    ) -> Result<Option<String>, String> {
+        let path = self.data_dir.join(format!("{}_token", kind.key()));
+        Ok(storage_file::read_private(&path)?)
+    }
+
+    pub fn save_task_provider_token(
+        &self,
+        kind: TaskProviderKind,
+        token: &str,
+    ) -> Result<(), String> {
+        let path = self.data_dir.join(format!("{}_token", kind.key()));
+        Ok(storage_file::replace_private(&path, token.as_bytes())?)
+    }
+
+    pub fn delete_task_provider_token(&self, kind: TaskProviderKind) -> Result<(), String> {
+        let path = self.data_dir.join(format!("{}_token", kind.key()));
+        if !path.exists() {
+            return Ok(());
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {e}", path.display()))
+    }
+
+    /// Display clients paired with this instance.
+    pub fn load_paired_displays(&self) -> Result<Vec<PairedDisplay>, String> {
+        Ok(Self::read_json(&self.data_dir.join(PAIRED_DISPLAYS_FILE))?.unwrap_or_default())
+    }
+
+    pub fn save_paired_displays(&self, displays: &[PairedDisplay]) -> Result<(), String> {
+        Self::write_json(&self.data_dir.join(PAIRED_DISPLAYS_FILE), &displays)
+    }
+
+    /// Inbox entries, oldest first.
+    pub fn load_inbox(&self) -> Result<Vec<InboxEntry>, String> {
+        Ok(Self::read_json(&self.data_dir.join(INBOX_FILE))?.unwrap_or_default())
+    }
+
+    /// Applies `update` to the stored inbox entries and saves them.
+    pub fn update_inbox<F>(&self, update: F) -> Result<Vec<InboxEntry>, String>
+    where
+        F: FnOnce(&mut Vec<InboxEntry>),
+    {
+        let _guard = self
+            .inbox_lock
+            .lock()
+            .map_err(|e| format!("Inbox lock poisoned: {e}"))?;
+        let mut entries = self.load_inbox()?;
+        update(&mut entries);
+        Self::write_json(&self.data_dir.join(INBOX_FILE), &entries)?;
+        Ok(entries)
+    }
+
+    /// The instance this machine shows as a display client, if any.
+    pub fn load_display_connection(&self) -> Result<Option<DisplayConnection>, String> {
+        Ok(Self::read_json(
+            &self.data_dir.join(DISPLAY_CONNECTION_FILE),
+        )?)
+    }
+
+    pub fn save_display_connection(
+        &self,
+        connection: Option<&DisplayConnection>,
+    ) -> Result<(), String> {
+        let path = self.data_dir.join(DISPLAY_CONNECTION_FILE);
+        match connection {
+            Some(connection) => Self::write_json(&path, connection),
+            None if path.exists() => fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {e}", path.display())),
+            None => Ok(()),
+        }
+    }
+
+    pub fn load_tasks(&self) -> Result<Vec<Task>, String> {
+        Ok(Self::read_json(&self.data_dir.join(TASKS_FILE))?.unwrap_or_default())
+    }
+
+    pub fn save_tasks(&self, tasks: &[Task]) -> Result<(), String> {
+        self.mutations.fetch_add(1, Ordering::SeqCst);
+        Self::write_json(&self.data_dir.join(TASKS_FILE), &tasks)
+    }
+
+    /// Tasks moved out of the task list by the archival policy. A write
+    /// cut short between the two files can leave a task in both; the task
+    /// list's copy wins, and an id archived twice is kept once.
+    pub fn load_archived_tasks(&self) -> Result<Vec<Task>, String> {
+        let archived: Vec<Task> =
+            Self::read_json(&self.data_dir.join(ARCHIVED_TASKS_FILE))?.unwrap_or_default();
+        let mut seen: HashSet<String> =
+            self.load_tasks()?.into_iter().map(|task| task.id).collect();
+        let mut kept: Vec<Task> = archived
+            .into_iter()
+            .rev()
+            .filter(|task| seen.insert(task.id.clone()))
+            .collect();
+        kept.reverse();
+        Ok(kept)
+    }
+
+    pub fn save_archived_tasks(&self, tasks: &[Task]) -> Result<(), String> {
+        self.mutations.fetch_add(1, Ordering::SeqCst);
+        Self::write_json(&self.data_dir.join(ARCHIVED_TASKS_FILE), &tasks)
+    }
+
+    /// How many times days and tasks have been changed since launch.
+    pub fn mutation_count(&self) -> u64 {
+        self.mutations.load(Ordering::SeqCst)
+    }
+
+    /// Saved timer presets; the built-in ones until the user changes them.
+    pub fn load_presets(&self) -> Result<Vec<TimerPreset>, String> {
+        Ok(Self::read_json(&self.data_dir.join(PRESETS_FILE))?.unwrap_or_else(default_presets))
+    }
+
+    pub fn save_presets(&self, presets: &[TimerPreset]) -> Result<(), String> {
+        Self::write_json(&self.data_dir.join(PRESETS_FILE), &presets)
+    }
+}
@@ -0,0 +1,333 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
+
+use super::backup_format::{self, BackupData};
+use super::integrity::Quarantine;
+use super::migrations::{self, MigrationRecord};
+use super::notifications::migrate_flat_preferences;
+use super::revision::{check_revision, MutationError};
+use super::session_types::CustomSessionType;
+use super::storage_backend::{JsonBackend, SqliteBackend, StorageBackend, StorageBackendKind};
+use super::storage_file::{self, StorageError};
+use super::tasks::Task;
+use super::write_buffer::WriteBuffer;
+
+mod backups;
+mod events;
+mod files;
+mod records;
+mod statistics;
+#[cfg(any(test, feature = "test-util"))]
+mod testing;
+
+pub use super::preferences::UserPreferences;
+pub use records::{SessionData, TimerStatistic};
+#[cfg(any(test, feature = "test-util"))]
+pub use testing::{day, session, StorageServiceBuilder, TestStorage};
+
+const PREFERENCES_FILE: &str = "preferences.json";
+const TASKS_FILE: &str = "tasks.json";
+const ARCHIVED_TASKS_FILE: &str = "tasks-archive.json";
+const STATISTICS_DIR: &str = "statistics";
+const STATISTICS_DB: &str = "statistics.sqlite";
+const QUARANTINE_DIR: &str = "quarantine";
+const BACKUP_DIR: &str = "backups";
+const REVIEWS_DIR: &str = "reviews";
+const ARTIFACTS_DIR: &str = "artifacts";
+const QUEUES_DIR: &str = "queues";
+/// The `identifier` in `tauri.conf.json`, which names the app data
+/// directory, for tools that find it without Tauri.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub const APP_IDENTIFIER: &str = "com.map.tauri-app";
+/// Backup of the JSON history taken before it is first imported into SQLite.
+const PRE_SQLITE_BACKUP_PREFIX: &str = "tempus-ring-pre-sqlite-";
+/// Held locked by the process running the timer on this data directory.
+const INSTANCE_LOCK_FILE: &str = "instance.lock";
+
+/// File-based persistence rooted in the app data directory.
+pub struct StorageService {
+    data_dir: PathBuf,
+    statistics: Box<dyn StorageBackend>,
+    /// Where statistics days that fail verification are moved.
+    quarantine: Arc<Quarantine>,
+    // Serializes read-modify-write cycles on the preferences file.
+    preferences_lock: Mutex<()>,
+    // Serializes read-modify-write cycles on the inbox file.
+    inbox_lock: Mutex<()>,
+    // Serializes read-modify-write cycles on a day's statistic.
+    statistics_lock: Mutex<()>,
+    /// Statistics and events waiting for a failed write to succeed.
+    write_buffer: WriteBuffer,
+    /// Set by [`Self::lock_instance`]; the lock goes with the process.
+    instance_lock: OnceLock<File>,
+    /// Bumped on every change to a day or the task list, so undo history
+    /// can tell whether anything changed behind its back.
+    mutations: AtomicU64,
+}
+
+impl StorageService {
+    pub fn new(app: &AppHandle) -> Result<Self, String> {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {e}"))?;
+        Self::open(data_dir)
+    }
+
+    /// Storage rooted in `data_dir` rather than the app data directory, for
+    /// the headless focus server.
+    pub fn open(data_dir: PathBuf) -> Result<Self, String> {
+        let statistics_dir = data_dir.join(STATISTICS_DIR);
+        fs::create_dir_all(&statistics_dir)
+            .map_err(|e| format!("Failed to create data directory: {e}"))?;
+
+        let has_data = data_dir.join(PREFERENCES_FILE).exists()
+            || data_dir.join(STATISTICS_DB).exists()
+            || fs::read_dir(&statistics_dir)
+                .map_err(|e| format!("Failed to read {}: {e}", statistics_dir.display()))?
+                .next()
+                .is_some();
+        let preferences = Self::read_preferences(&data_dir.join(PREFERENCES_FILE))?;
+        let quarantine = Arc::new(Quarantine::new(data_dir.join(QUARANTINE_DIR)));
+        let json = JsonBackend::new(statistics_dir, quarantine.clone());
+        let sqlite = match preferences.storage_backend {
+            StorageBackendKind::Json => None,
+            StorageBackendKind::Sqlite => Some(SqliteBackend::open(&data_dir.join(STATISTICS_DB))?),
+        };
+
+        let backup_dir = data_dir.join(BACKUP_DIR);
+        fs::create_dir_all(&backup_dir)
+            .map_err(|e| format!("Failed to create backup directory: {e}"))?;
+        // Writes `statistics` with the tasks and preferences as a backup.
+        let snapshot = |path: &Path, statistics: Vec<TimerStatistic>| -> Result<(), StorageError> {
+            let mut tasks: Vec<Task> =
+                Self::read_json(&data_dir.join(TASKS_FILE))?.unwrap_or_default();
+            tasks.extend(
+                Self::read_json::<Vec<Task>>(&data_dir.join(ARCHIVED_TASKS_FILE))?
+                    .unwrap_or_default(),
+            );
+            let backup = BackupData {
+                preferences: preferences.clone(),
+                statistics,
+                tasks,
+            };
+            let content =
+                backup_format::encode(&backup, None).map_err(StorageError::Serialization)?;
+            storage_file::replace(path, content.as_bytes())
+        };
+        migrations::record_upgrade(&data_dir, &backup_dir, has_data, |path| {
+            // Until the first SQLite launch has imported them, the days are
+            // still only in the JSON files.
+            let statistics = match &sqlite {
+                Some(sqlite) if sqlite.json_migrated()? => sqlite.load_statistics(None, None)?,
+                _ => json.load_statistics(None, None)?,
+            };
+            snapshot(path, statistics)
+        })?;
+
+        let statistics: Box<dyn StorageBackend> = match sqlite {
+            None => Box::new(json),
+            Some(sqlite) => {
+                if !sqlite.json_migrated()? {
+                    let path = backup_dir.join(format!(
+                        "{PRE_SQLITE_BACKUP_PREFIX}{}.json",
+                        Local::now().format("%Y%m%d-%H%M%S")
+                    ));
+                    snapshot(&path, json.load_statistics(None, None)?)?;
+                    sqlite.migrate_from(&json)?;
+                }
+                Box::new(sqlite)
+            }
+        };
+
+        Ok(Self::with_backend(data_dir, statistics, quarantine))
+    }
+
+    fn with_backend(
+        data_dir: PathBuf,
+        statistics: Box<dyn StorageBackend>,
+        quarantine: Arc<Quarantine>,
+    ) -> Self {
+        Self {
+            data_dir,
+            statistics,
+            quarantine,
+            preferences_lock: Mutex::new(()),
+            inbox_lock: Mutex::new(()),
+            statistics_lock: Mutex::new(()),
+            write_buffer: WriteBuffer::default(),
+            instance_lock: OnceLock::new(),
+            mutations: AtomicU64::new(0),
+        }
+    }
+
+    /// Claims the data directory for this process, so a second app or
+    /// focus server can't run a timer against the same files. Readers such
+    /// as `tempus stats` don't need to.
+    pub fn lock_instance(&self) -> Result<(), String> {
+        if self.instance_lock.get().is_some() {
+            return Ok(());
+        }
+        let path = self.data_dir.join(INSTANCE_LOCK_FILE);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(format!(
+                    "Another Tempus Ring instance is using {}",
+                    self.data_dir.display()
+                ))
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(format!("Failed to lock {}: {e}", path.display()))
+            }
+        }
+        let _ = self.instance_lock.set(file);
+        Ok(())
+    }
+
+    /// The per-day JSON files, whichever backend is active. With SQLite they
+    /// hold the history from before the migration.
+    pub fn statistics_files(&self) -> JsonBackend {
+        JsonBackend::new(self.data_dir.join(STATISTICS_DIR), self.quarantine.clone())
+    }
+
+    pub fn quarantine(&self) -> &Quarantine {
+        &self.quarantine
+    }
+
+    /// Default location for backups inside the app data directory.
+    pub fn backup_dir(&self) -> Result<PathBuf, String> {
+        let dir = self.data_dir.join(BACKUP_DIR);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory: {e}"))?;
+        Ok(dir)
+    }
+
+    /// Where generated year-in-review reports are kept.
+    pub fn reviews_dir(&self) -> Result<PathBuf, String> {
+        let dir = self.data_dir.join(REVIEWS_DIR);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create reviews directory: {e}"))?;
+        Ok(dir)
+    }
+
+    /// Where copies of tracked artifact files are kept.
+    pub fn artifacts_dir(&self) -> Result<PathBuf, String> {
+        let dir = self.data_dir.join(ARTIFACTS_DIR);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create artifacts directory: {e}"))?;
+        Ok(dir)
+    }
+
+    /// Directory backing the named integration queue. `name` must already be
+    /// validated as a plain identifier.
+    pub fn queue_dir(&self, name: &str) -> Result<PathBuf, String> {
+        let dir = self.data_dir.join(QUEUES_DIR).join(name);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create queue directory: {e}"))?;
+        Ok(dir)
+    }
+
+    /// Reads a JSON file, falling back to its backup if it is corrupted.
+    pub(super) fn read_json<T: for<'de> Deserialize<'de>>(
+        path: &Path,
+    ) -> Result<Option<T>, StorageError> {
+        storage_file::read_json(path)
+    }
+
+    /// Writes a JSON file atomically, keeping the previous version as a backup.
+    pub(super) fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+        Ok(storage_file::write_json(path, value)?)
+    }
+
+    /// Reads preferences, moving fields saved by older versions into place.
+    fn read_preferences(path: &Path) -> Result<UserPreferences, String> {
+        let Some(mut value) = Self::read_json::<Value>(path)? else {
+            return Ok(UserPreferences::default());
+        };
+        migrate_flat_preferences(&mut value);
+        serde_json::from_value(value)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+    }
+
+    /// Every app version change recorded for this data, oldest first.
+    pub fn load_migration_history(&self) -> Result<Vec<MigrationRecord>, StorageError> {
+        migrations::load_history(&self.data_dir)
+    }
+
+    pub fn load_preferences(&self) -> Result<UserPreferences, String> {
+        Self::read_preferences(&self.data_dir.join(PREFERENCES_FILE))
+    }
+
+    /// Persists `preferences`, rejecting the write if the stored copy has moved
+    /// past `expected_revision`. Returns the saved preferences with their new
+    /// revision.
+    pub fn save_preferences(
+        &self,
+        mut preferences: UserPreferences,
+        expected_revision: Option<u64>,
+    ) -> Result<UserPreferences, MutationError> {
+        let _guard = self
+            .preferences_lock
+            .lock()
+            .map_err(|e| format!("Preferences lock poisoned: {e}"))?;
+        let current = self.load_preferences()?;
+        check_revision("preferences", expected_revision, current.revision)?;
+
+        // These widen file access, so the webview can't set them.
+        preferences.export_dir = current.export_dir;
+        preferences.dashboard_feed.path = current.dashboard_feed.path;
+        preferences.door_status.dir = current.door_status.dir;
+        preferences.auto_backup.dir = current.auto_backup.dir;
+        // Run shell commands, so they only change through `set_hooks` and
+        // `set_block_actions`.
+        preferences.hooks = current.hooks;
+        preferences.distraction_blocker.actions = current.distraction_blocker.actions;
+        // Goes with the stored focus server token.
+        preferences.timer_backend = current.timer_backend;
+        preferences.revision = current.revision + 1;
+        Self::write_json(&self.data_dir.join(PREFERENCES_FILE), &preferences)?;
+        Ok(preferences)
+    }
+
+    /// Applies `update` to the stored preferences without a revision check,
+    /// for backend-initiated changes.
+    pub fn update_preferences<F>(&self, update: F) -> Result<UserPreferences, String>
+    where
+        F: FnOnce(&mut UserPreferences),
+    {
+        let _guard = self
+            .preferences_lock
+            .lock()
+            .map_err(|e| format!("Preferences lock poisoned: {e}"))?;
+        let mut preferences = self.load_preferences()?;
+        update(&mut preferences);
+        preferences.revision += 1;
+        Self::write_json(&self.data_dir.join(PREFERENCES_FILE), &preferences)?;
+        Ok(preferences)
+    }
+
+    pub fn custom_session_types(&self) -> Result<Vec<CustomSessionType>, String> {
+        Ok(self.load_preferences()?.timer_config.custom_session_types)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_app_identifier_matches_the_tauri_config() {
+        let config: Value = serde_json::from_str(include_str!("../../../tauri.conf.json")).unwrap();
+        assert_eq!(config["identifier"], APP_IDENTIFIER);
+    }
+}
@@ -0,0 +1,104 @@
+//! The day records statistics are kept in.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::services::fatigue::FatigueAdvisory;
+use crate::services::focus_budget::OverworkEvent;
+use crate::services::session_types::{counts_as_pomodoro, CustomSessionType};
+use crate::services::timer_state::SessionType;
+use crate::services::window_activity::WindowFocus;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct SessionData {
+    pub session_type: SessionType,
+    /// Planned length of the session in seconds.
+    pub duration: u64,
+    pub completed: bool,
+    pub start_time: u64,
+    #[serde(default)]
+    pub end_time: Option<u64>,
+    #[serde(default)]
+    pub task_id: Option<String>,
+    /// Free-form labels such as `writing` or `email`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// GitHub issue worked on, as `owner/repo#123`.
+    #[serde(default)]
+    pub issue: Option<String>,
+    /// Where the session was worked, such as `Home` or `Office`.
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Seconds worked past the planned `duration` before completing it.
+    #[serde(default)]
+    pub overtime: u64,
+    /// Seconds added to the planned `duration` while the session ran.
+    #[serde(default)]
+    pub extended: u64,
+    /// The windows in front longest during a work session, with window
+    /// annotation on.
+    #[serde(default)]
+    pub windows: Vec<WindowFocus>,
+}
+
+impl SessionData {
+    /// Unix seconds the session ended, or would have if it ran its full
+    /// duration; never before its start.
+    pub fn span_end(&self) -> u64 {
+        self.end_time
+            .unwrap_or(self.start_time + self.duration)
+            .max(self.start_time)
+    }
+
+    /// How much this session adds to its day's `(completed_pomodoros,
+    /// total_focus_time)`.
+    pub fn contribution(&self, custom_types: &[CustomSessionType]) -> (u32, u64) {
+        if self.completed && counts_as_pomodoro(&self.session_type, custom_types) {
+            (1, self.duration)
+        } else {
+            (0, 0)
+        }
+    }
+}
+
+/// Statistics for a single day, stored as `statistics/<date>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct TimerStatistic {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub completed_pomodoros: u32,
+    /// Total focused time in seconds.
+    pub total_focus_time: u64,
+    #[serde(default)]
+    pub sessions: Vec<SessionData>,
+    /// Written by the backend only; `save_statistic` keeps the stored ones.
+    #[serde(default)]
+    pub advisories: Vec<FatigueAdvisory>,
+    /// Written by the backend only, like `advisories`.
+    #[serde(default)]
+    pub overwork: Vec<OverworkEvent>,
+}
+
+impl TimerStatistic {
+    /// Keeps only the sessions carrying at least one of `tags` and recomputes
+    /// the day's totals from them.
+    pub fn retain_tagged(&mut self, tags: &[String], custom_types: &[CustomSessionType]) {
+        self.sessions
+            .retain(|session| session.tags.iter().any(|tag| tags.contains(tag)));
+        self.recompute_totals(custom_types);
+    }
+
+    /// Recomputes the day's pomodoros and focus time from its sessions.
+    pub fn recompute_totals(&mut self, custom_types: &[CustomSessionType]) {
+        let (pomodoros, focus_time) = self
+            .sessions
+            .iter()
+            .map(|session| session.contribution(custom_types))
+            .fold((0, 0), |(p, f), (dp, df)| (p + dp, f + df));
+        self.completed_pomodoros = pomodoros;
+        self.total_focus_time = focus_time;
+    }
+}
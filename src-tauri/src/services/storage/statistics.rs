@@ -0,0 +1,279 @@
+//! Daily statistics, through the configured backend and the write buffer.
+
+use chrono::NaiveDate;
+use std::sync::atomic::Ordering;
+
+use super::{SessionData, StorageService, TimerStatistic};
+use crate::services::calendar::parse_day;
+use crate::services::fatigue::FatigueAdvisory;
+use crate::services::focus_budget::OverworkEvent;
+use crate::services::storage_file::StorageError;
+use crate::services::write_buffer::StorageHealth;
+
+fn session_not_found(date: &str, index: usize) -> StorageError {
+    StorageError::NotFound(format!("Session {index} not found on {date}"))
+}
+
+impl StorageService {
+    /// Saves the day's statistic. If the write fails the statistic is held
+    /// in memory, served by the loaders and written by
+    /// [`Self::flush_pending`].
+    /// Fails with [`StorageError::Held`] when the day couldn't be written
+    /// and is held for a later retry.
+    pub fn save_statistic(&self, statistic: &TimerStatistic) -> Result<(), StorageError> {
+        self.mutations.fetch_add(1, Ordering::SeqCst);
+        self.write_buffer
+            .save_statistic(statistic, |statistic| {
+                Ok(self.statistics.save_statistic(statistic)?)
+            })
+            .map_err(StorageError::Held)
+    }
+
+    pub fn load_statistic(&self, date: &str) -> Result<Option<TimerStatistic>, StorageError> {
+        match self.write_buffer.statistic(date)? {
+            Some(held) => Ok(Some(held)),
+            None => self.statistics.load_statistic(date),
+        }
+    }
+
+    /// Removes the session at `index` from the day's statistic, keeping the
+    /// day's totals consistent. Returns it with the sessions left.
+    pub fn delete_session(
+        &self,
+        date: &str,
+        index: usize,
+    ) -> Result<(SessionData, Vec<SessionData>), StorageError> {
+        let custom_types = self.custom_session_types()?;
+        self.update_statistic(date, |statistic| {
+            if index >= statistic.sessions.len() {
+                return Err(session_not_found(date, index));
+            }
+            let session = statistic.sessions.remove(index);
+            let (pomodoros, focus_time) = session.contribution(&custom_types);
+            statistic.completed_pomodoros = statistic.completed_pomodoros.saturating_sub(pomodoros);
+            statistic.total_focus_time = statistic.total_focus_time.saturating_sub(focus_time);
+            Ok((session, statistic.sessions.clone()))
+        })
+    }
+
+    /// Applies `edit` to the session at `index` and returns the updated
+    /// session with what `edit` returned.
+    fn edit_session<T>(
+        &self,
+        date: &str,
+        index: usize,
+        edit: impl FnOnce(&mut SessionData) -> T,
+    ) -> Result<(SessionData, T), StorageError> {
+        self.update_statistic(date, |statistic| {
+            let session = statistic
+                .sessions
+                .get_mut(index)
+                .ok_or_else(|| session_not_found(date, index))?;
+            let value = edit(session);
+            Ok((session.clone(), value))
+        })
+    }
+
+    /// Replaces the tags on the session at `index` and returns the updated
+    /// session.
+    pub fn set_session_tags(
+        &self,
+        date: &str,
+        index: usize,
+        tags: Vec<String>,
+    ) -> Result<SessionData, StorageError> {
+        let (updated, ()) = self.edit_session(date, index, |session| session.tags = tags)?;
+        Ok(updated)
+    }
+
+    /// Links the session at `index` to `task_id`, or unlinks it for `None`.
+    /// Returns the updated session and the task it was linked to before.
+    pub fn set_session_task(
+        &self,
+        date: &str,
+        index: usize,
+        task_id: Option<String>,
+    ) -> Result<(SessionData, Option<String>), StorageError> {
+        self.edit_session(date, index, |session| {
+            std::mem::replace(&mut session.task_id, task_id)
+        })
+    }
+
+    /// Attaches `issue` to the session at `index`, or detaches it for
+    /// `None`, and returns the updated session.
+    pub fn set_session_issue(
+        &self,
+        date: &str,
+        index: usize,
+        issue: Option<String>,
+    ) -> Result<SessionData, StorageError> {
+        let (updated, ()) = self.edit_session(date, index, |session| session.issue = issue)?;
+        Ok(updated)
+    }
+
+    /// Replaces the day's sessions and recomputes its totals. Returns the
+    /// sessions it replaced.
+    pub fn replace_sessions(
+        &self,
+        date: &str,
+        sessions: Vec<SessionData>,
+    ) -> Result<Vec<SessionData>, StorageError> {
+        self.edit_sessions(date, |_| Ok::<_, StorageError>(sessions))
+    }
+
+    /// Replaces the day's sessions with what `edit` makes of them and
+    /// recomputes its totals. Returns the sessions it replaced.
+    pub fn edit_sessions<E, F>(&self, date: &str, edit: F) -> Result<Vec<SessionData>, E>
+    where
+        E: From<StorageError>,
+        F: FnOnce(&[SessionData]) -> Result<Vec<SessionData>, E>,
+    {
+        let custom_types = self.custom_session_types().map_err(StorageError::from)?;
+        self.update_statistic(date, |statistic| {
+            let edited = edit(&statistic.sessions)?;
+            let replaced = std::mem::replace(&mut statistic.sessions, edited);
+            statistic.recompute_totals(&custom_types);
+            Ok(replaced)
+        })
+    }
+
+    /// Applies `update` to the day's statistic, creating the day if needed,
+    /// and saves it unless `update` fails. Every read-modify-write of a day
+    /// goes through here, so none interleave.
+    pub fn update_statistic<T, E, F>(&self, date: &str, update: F) -> Result<T, E>
+    where
+        E: From<StorageError>,
+        F: FnOnce(&mut TimerStatistic) -> Result<T, E>,
+    {
+        let _guard = self
+            .statistics_lock
+            .lock()
+            .map_err(|e| StorageError::Failed(format!("Statistics lock poisoned: {e}")))?;
+        let mut statistic = self
+            .load_statistic(date)?
+            .unwrap_or_else(|| TimerStatistic {
+                date: date.to_string(),
+                completed_pomodoros: 0,
+                total_focus_time: 0,
+                sessions: Vec::new(),
+                advisories: Vec::new(),
+                overwork: Vec::new(),
+            });
+        let value = update(&mut statistic)?;
+        self.save_statistic(&statistic)?;
+        Ok(value)
+    }
+
+    /// Inserts `session` into the day's statistic at `index` (clamped to the
+    /// end), creating the day if needed.
+    pub fn insert_session(
+        &self,
+        date: &str,
+        index: usize,
+        session: SessionData,
+    ) -> Result<(), StorageError> {
+        let (pomodoros, focus_time) = session.contribution(&self.custom_session_types()?);
+        self.update_statistic(date, |statistic| {
+            statistic.completed_pomodoros += pomodoros;
+            statistic.total_focus_time += focus_time;
+            let index = index.min(statistic.sessions.len());
+            statistic.sessions.insert(index, session);
+            Ok(())
+        })
+    }
+
+    /// Adds `advisory` to the day's statistic, creating the day if needed.
+    pub fn record_advisory(&self, date: &str, advisory: FatigueAdvisory) -> Result<(), String> {
+        self.update_statistic(date, |statistic| {
+            statistic.advisories.push(advisory);
+            Ok(())
+        })
+    }
+
+    /// Adds `event` to the day's statistic, creating the day if needed.
+    pub fn record_overwork(&self, date: &str, event: OverworkEvent) -> Result<(), String> {
+        self.update_statistic(date, |statistic| {
+            statistic.overwork.push(event);
+            Ok(())
+        })
+    }
+
+    /// Keys of every recorded day, held ones included, sorted by date. With
+    /// [`Self::load_statistic`] this walks a history too large to load at
+    /// once.
+    pub fn statistic_dates(&self) -> Result<Vec<String>, StorageError> {
+        let mut dates = self.statistics.dates()?;
+        dates.extend(self.write_buffer.dates()?);
+        dates.sort_by_cached_key(|date| (parse_day(date), date.clone()));
+        dates.dedup();
+        Ok(dates)
+    }
+
+    /// Loads daily statistics, optionally bounded by inclusive dates,
+    /// sorted by date.
+    pub fn load_statistics(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<TimerStatistic>, StorageError> {
+        let mut statistics = self.statistics.load_statistics(start_date, end_date)?;
+        self.write_buffer
+            .overlay(&mut statistics, start_date, end_date)?;
+        Ok(statistics)
+    }
+
+    /// Retries held writes and reports what is still waiting.
+    pub fn flush_pending(&self) -> Result<StorageHealth, String> {
+        self.write_buffer.flush(
+            |statistic| Ok(self.statistics.save_statistic(statistic)?),
+            |events| self.write_events(events),
+        )
+    }
+
+    pub fn storage_health(&self) -> Result<StorageHealth, String> {
+        self.write_buffer.health()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, StorageServiceBuilder};
+
+    fn statistic(date: &str, completed_pomodoros: u32) -> TimerStatistic {
+        TimerStatistic {
+            completed_pomodoros,
+            total_focus_time: u64::from(completed_pomodoros) * 25 * 60,
+            ..day(date, Vec::new())
+        }
+    }
+
+    #[test]
+    fn saved_data_loads_back() {
+        let storage = StorageServiceBuilder::new()
+            .statistic(statistic("2026-03-01", 4))
+            .statistic(statistic("2026-03-02", 6))
+            .build()
+            .unwrap();
+
+        let saved = storage
+            .update_preferences(|preferences| preferences.daily_goal = 5)
+            .unwrap();
+        assert_eq!(saved.revision, 1);
+        assert_eq!(storage.load_preferences().unwrap().daily_goal, 5);
+
+        storage.save_statistic(&statistic("2026-03-03", 2)).unwrap();
+        let from = NaiveDate::from_ymd_opt(2026, 3, 2);
+        let dates: Vec<String> = storage
+            .load_statistics(from, None)
+            .unwrap()
+            .into_iter()
+            .map(|statistic| statistic.date)
+            .collect();
+        assert_eq!(dates, ["2026-03-02", "2026-03-03"]);
+        assert_eq!(
+            storage.statistic_dates().unwrap(),
+            ["2026-03-01", "2026-03-02", "2026-03-03"]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Storage for tests, in a temporary directory.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{
+    SessionData, StorageService, TimerStatistic, UserPreferences, PREFERENCES_FILE, QUARANTINE_DIR,
+};
+use crate::services::integrity::Quarantine;
+use crate::services::storage_backend::MemoryBackend;
+use crate::services::timer_state::SessionType;
+
+/// A completed session of `session_type` running `duration` seconds from
+/// `start_time`, with no task, tags or anything else attached.
+pub fn session(session_type: SessionType, start_time: u64, duration: u64) -> SessionData {
+    SessionData {
+        session_type,
+        duration,
+        completed: true,
+        start_time,
+        end_time: Some(start_time + duration),
+        task_id: None,
+        tags: Vec::new(),
+        issue: None,
+        context: None,
+        overtime: 0,
+        extended: 0,
+        windows: Vec::new(),
+    }
+}
+
+/// The day keyed `date` holding `sessions`, its totals counted from them.
+pub fn day(date: &str, sessions: Vec<SessionData>) -> TimerStatistic {
+    let (completed_pomodoros, total_focus_time) = sessions
+        .iter()
+        .map(|session| session.contribution(&[]))
+        .fold((0, 0), |(count, focus), (one, seconds)| {
+            (count + one, focus + seconds)
+        });
+    TimerStatistic {
+        date: date.to_string(),
+        completed_pomodoros,
+        total_focus_time,
+        sessions,
+        advisories: Vec::new(),
+        overwork: Vec::new(),
+    }
+}
+
+/// Builds a [`StorageService`] for tests: files in a fresh temporary
+/// directory, statistics in memory, and the preferences and days the test
+/// starts from.
+pub struct StorageServiceBuilder {
+    preferences: UserPreferences,
+    statistics: Vec<TimerStatistic>,
+}
+
+impl Default for StorageServiceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StorageServiceBuilder {
+    pub fn new() -> Self {
+        Self {
+            preferences: UserPreferences::default(),
+            statistics: Vec::new(),
+        }
+    }
+
+    pub fn preferences(mut self, preferences: UserPreferences) -> Self {
+        self.preferences = preferences;
+        self
+    }
+
+    pub fn statistic(mut self, statistic: TimerStatistic) -> Self {
+        self.statistics.push(statistic);
+        self
+    }
+
+    pub fn build(self) -> Result<TestStorage, String> {
+        let dir = tempfile::Builder::new()
+            .prefix("tempus-storage-")
+            .tempdir()
+            .map_err(|e| format!("Failed to create data directory: {e}"))?;
+        let data_dir = dir.path().to_path_buf();
+        let quarantine = Arc::new(Quarantine::new(data_dir.join(QUARANTINE_DIR)));
+        let storage =
+            StorageService::with_backend(data_dir, Box::<MemoryBackend>::default(), quarantine);
+        StorageService::write_json(&storage.data_dir.join(PREFERENCES_FILE), &self.preferences)?;
+        for statistic in &self.statistics {
+            storage.save_statistic(statistic)?;
+        }
+        Ok(TestStorage { storage, dir })
+    }
+}
+
+/// A [`StorageService`] from [`StorageServiceBuilder`]. Its data directory
+/// is deleted when this is dropped.
+pub struct TestStorage {
+    // Declared before `dir` so it is dropped while the directory exists.
+    storage: StorageService,
+    dir: tempfile::TempDir,
+}
+
+impl TestStorage {
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl std::ops::Deref for TestStorage {
+    type Target = StorageService;
+
+    fn deref(&self) -> &StorageService {
+        &self.storage
+    }
+}
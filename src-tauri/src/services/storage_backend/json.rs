@@ -0,0 +1,216 @@
+//! The default backend: one checksummed file per day.
+
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::StorageBackend;
+use crate::services::calendar::{self, parse_day};
+use crate::services::fatigue::FatigueAdvisory;
+use crate::services::focus_budget::OverworkEvent;
+use crate::services::integrity::{self, IntegrityIssue, Quarantine};
+use crate::services::storage::{SessionData, TimerStatistic};
+use crate::services::storage_file::{self, StorageError};
+
+/// One checksummed `<date>.json` file per day. Days that fail verification
+/// are moved to the quarantine and treated as missing, unless only some of
+/// their entries no longer read.
+pub struct JsonBackend {
+    dir: PathBuf,
+    quarantine: Arc<Quarantine>,
+}
+
+impl JsonBackend {
+    pub fn new(dir: PathBuf, quarantine: Arc<Quarantine>) -> Self {
+        Self { dir, quarantine }
+    }
+
+    /// Reads the day at `path`, with the issue it was quarantined with if
+    /// it failed. A day whose checksum holds keeps the entries that still
+    /// read, with a copy of the original quarantined; otherwise the whole
+    /// file is.
+    fn check(
+        &self,
+        path: &Path,
+    ) -> Result<(Option<TimerStatistic>, Option<IntegrityIssue>), StorageError> {
+        let reason = match storage_file::read_with(path, integrity::unseal) {
+            Ok(statistic) => return Ok((statistic, None)),
+            Err(StorageError::Corrupted { reason, .. }) => reason,
+            Err(error) => return Err(error),
+        };
+        let salvaged = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| salvage(&content));
+        let Some((statistic, dropped)) = salvaged else {
+            return Ok((None, Some(self.quarantine.isolate(path, &reason)?)));
+        };
+        let reason = format!("{dropped} unreadable entries dropped ({reason})");
+        let issue = self.quarantine.preserve(path, &reason)?;
+        self.save_statistic(&statistic)?;
+        Ok((Some(statistic), Some(issue)))
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<TimerStatistic>, StorageError> {
+        Ok(self.check(path)?.0)
+    }
+
+    fn day_files(&self) -> Result<Vec<PathBuf>, StorageError> {
+        let entries = fs::read_dir(&self.dir)
+            .map_err(|e| StorageError::Io(format!("Failed to read statistics directory: {e}")))?;
+        Ok(entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+            .collect())
+    }
+
+    /// Verifies every day file, returning how many were checked and the
+    /// issues of those quarantined.
+    pub fn verify_all(&self) -> Result<(u32, Vec<IntegrityIssue>), StorageError> {
+        let mut checked = 0;
+        let mut issues = Vec::new();
+        for path in self.day_files()? {
+            checked += 1;
+            issues.extend(self.check(&path)?.1);
+        }
+        Ok((checked, issues))
+    }
+}
+
+/// Drops the entries of `field`, an array in `value`, that don't read as a
+/// `T`, returning how many were dropped.
+fn retain_readable<T: DeserializeOwned>(value: &mut Value, field: &str) -> usize {
+    let Some(entries) = value.get_mut(field).and_then(Value::as_array_mut) else {
+        return 0;
+    };
+    let before = entries.len();
+    entries.retain(|entry| T::deserialize(entry).is_ok());
+    before - entries.len()
+}
+
+/// The day in `content` without the sessions, advisories and overwork
+/// events that no longer read, and how many those were. `None` if its
+/// checksum fails, nothing was dropped, or it still doesn't read.
+fn salvage(content: &str) -> Option<(TimerStatistic, usize)> {
+    let mut value = integrity::verify(content).ok()?;
+    let dropped = retain_readable::<SessionData>(&mut value, "sessions")
+        + retain_readable::<FatigueAdvisory>(&mut value, "advisories")
+        + retain_readable::<OverworkEvent>(&mut value, "overwork");
+    if dropped == 0 {
+        return None;
+    }
+    let statistic = serde_json::from_value(value).ok()?;
+    Some((statistic, dropped))
+}
+
+impl StorageBackend for JsonBackend {
+    fn save_statistic(&self, statistic: &TimerStatistic) -> Result<(), StorageError> {
+        storage_file::write_with(
+            &self.dir.join(format!("{}.json", statistic.date)),
+            integrity::seal(statistic)?.as_bytes(),
+            integrity::unseal::<TimerStatistic>,
+        )
+    }
+
+    fn load_statistic(&self, date: &str) -> Result<Option<TimerStatistic>, StorageError> {
+        self.read(&self.dir.join(format!("{date}.json")))
+    }
+
+    fn dates(&self) -> Result<Vec<String>, StorageError> {
+        let mut dates: Vec<String> = self
+            .day_files()?
+            .iter()
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        dates.sort_by_cached_key(|date| parse_day(date));
+        Ok(dates)
+    }
+
+    fn load_statistics(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<TimerStatistic>, StorageError> {
+        let mut statistics = Vec::new();
+        for path in self.day_files()? {
+            let statistic = match self.read(&path) {
+                Ok(Some(statistic)) => statistic,
+                Ok(None) => continue,
+                Err(error) => {
+                    log::error!("{error}");
+                    continue;
+                }
+            };
+            if calendar::in_range(&statistic.date, start_date, end_date) {
+                statistics.push(statistic);
+            }
+        }
+
+        statistics.sort_by_cached_key(|statistic| parse_day(&statistic.date));
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::session;
+    use crate::services::timer_state::SessionType;
+    use serde_json::json;
+
+    fn work() -> SessionData {
+        session(SessionType::Work, 1_741_600_000, 1500)
+    }
+
+    #[test]
+    fn a_day_keeps_the_sessions_that_still_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = Arc::new(Quarantine::new(dir.path().join("quarantine")));
+        let backend = JsonBackend::new(dir.path().join("statistics"), quarantine.clone());
+        fs::create_dir_all(dir.path().join("statistics")).unwrap();
+        let day = json!({
+            "date": "2025-03-10",
+            "completed_pomodoros": 1,
+            "total_focus_time": 1500,
+            "sessions": [work(), { "session_type": "work", "duration": "long" }],
+        });
+        let path = dir.path().join("statistics/2025-03-10.json");
+        fs::write(&path, integrity::seal(&day).unwrap()).unwrap();
+
+        let statistic = backend.load_statistic("2025-03-10").unwrap().unwrap();
+        assert_eq!(statistic.sessions, [work()]);
+        assert_eq!(statistic.completed_pomodoros, 1);
+        let issues = quarantine.issues().unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].reason.starts_with("1 unreadable entries dropped"));
+
+        // The day was rewritten without the session, so it now verifies.
+        let (checked, issues) = backend.verify_all().unwrap();
+        assert_eq!(checked, 1);
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn a_tampered_day_is_quarantined_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = Arc::new(Quarantine::new(dir.path().join("quarantine")));
+        let backend = JsonBackend::new(dir.path().join("statistics"), quarantine.clone());
+        fs::create_dir_all(dir.path().join("statistics")).unwrap();
+        let day = json!({
+            "date": "2025-03-10",
+            "completed_pomodoros": 1,
+            "total_focus_time": 1500,
+            "sessions": [work()],
+        });
+        let sealed = integrity::seal(&day).unwrap().replace("1500", "9000");
+        let path = dir.path().join("statistics/2025-03-10.json");
+        fs::write(&path, sealed).unwrap();
+
+        assert!(backend.load_statistic("2025-03-10").unwrap().is_none());
+        assert!(!path.exists());
+        assert_eq!(quarantine.issues().unwrap().len(), 1);
+    }
+}
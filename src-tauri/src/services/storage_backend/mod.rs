@@ -0,0 +1,93 @@
+//! Where daily statistics live. JSON files are the default; SQLite keeps
+//! every day in one indexed table for fast date-range queries.
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+#[cfg(any(test, feature = "test-util"))]
+use std::collections::BTreeMap;
+#[cfg(any(test, feature = "test-util"))]
+use std::sync::{Mutex, MutexGuard};
+use ts_rs::TS;
+
+#[cfg(any(test, feature = "test-util"))]
+use super::calendar;
+use super::storage::TimerStatistic;
+use super::storage_file::StorageError;
+
+mod json;
+mod sqlite;
+
+pub use json::JsonBackend;
+pub use sqlite::SqliteBackend;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackendKind {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+/// Persistence for daily statistics.
+pub trait StorageBackend: Send + Sync {
+    fn save_statistic(&self, statistic: &TimerStatistic) -> Result<(), StorageError>;
+
+    fn load_statistic(&self, date: &str) -> Result<Option<TimerStatistic>, StorageError>;
+
+    /// Keys of every stored day, sorted by date.
+    fn dates(&self) -> Result<Vec<String>, StorageError>;
+
+    /// Days within the optional inclusive bounds, sorted by date.
+    fn load_statistics(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<TimerStatistic>, StorageError>;
+}
+/// Days kept in memory only, for tests that don't care how statistics are
+/// stored.
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Default)]
+pub struct MemoryBackend {
+    /// Keyed by date; `YYYY-MM-DD` keys sort by date.
+    days: Mutex<BTreeMap<String, TimerStatistic>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl MemoryBackend {
+    fn lock(&self) -> Result<MutexGuard<'_, BTreeMap<String, TimerStatistic>>, String> {
+        self.days
+            .lock()
+            .map_err(|e| format!("Statistics lock poisoned: {e}"))
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl StorageBackend for MemoryBackend {
+    fn save_statistic(&self, statistic: &TimerStatistic) -> Result<(), StorageError> {
+        self.lock()?
+            .insert(statistic.date.clone(), statistic.clone());
+        Ok(())
+    }
+
+    fn load_statistic(&self, date: &str) -> Result<Option<TimerStatistic>, StorageError> {
+        Ok(self.lock()?.get(date).cloned())
+    }
+
+    fn dates(&self) -> Result<Vec<String>, StorageError> {
+        Ok(self.lock()?.keys().cloned().collect())
+    }
+
+    fn load_statistics(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<TimerStatistic>, StorageError> {
+        Ok(self
+            .lock()?
+            .values()
+            .filter(|statistic| calendar::in_range(&statistic.date, start_date, end_date))
+            .cloned()
+            .collect())
+    }
+}
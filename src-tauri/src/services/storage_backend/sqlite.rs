@@ -0,0 +1,341 @@
+//! The SQLite backend: every day in one indexed table.
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use super::{JsonBackend, StorageBackend};
+use crate::services::calendar::day_key;
+use crate::services::storage::TimerStatistic;
+use crate::services::storage_file::StorageError;
+
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS statistics (
+    date TEXT PRIMARY KEY,
+    completed_pomodoros INTEGER NOT NULL,
+    total_focus_time INTEGER NOT NULL,
+    sessions TEXT NOT NULL,
+    advisories TEXT NOT NULL DEFAULT '[]',
+    overwork TEXT NOT NULL DEFAULT '[]'
+);
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+const JSON_MIGRATED_KEY: &str = "json_migrated";
+const SELECT_COLUMNS: &str = "SELECT date, completed_pomodoros, total_focus_time, sessions, \
+     advisories, overwork FROM statistics";
+
+fn sql_error(e: rusqlite::Error) -> StorageError {
+    StorageError::Io(format!("Statistics database error: {e}"))
+}
+
+/// All days in a single `statistics` table keyed by date.
+pub struct SqliteBackend {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteBackend {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(sql_error)?;
+        conn.execute_batch(SQLITE_SCHEMA).map_err(sql_error)?;
+        add_list_column(&conn, "advisories")?;
+        add_list_column(&conn, "overwork")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        self.conn
+            .lock()
+            .map_err(|e| format!("Statistics database lock poisoned: {e}"))
+    }
+
+    /// Whether [`Self::migrate_from`] has already copied the JSON days in.
+    pub fn json_migrated(&self) -> Result<bool, StorageError> {
+        let migrated = self
+            .lock()?
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![JSON_MIGRATED_KEY],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(sql_error)?;
+        Ok(migrated.is_some())
+    }
+
+    /// Copies every day from `json` into the database the first time the
+    /// SQLite backend is used. The JSON files are left in place, so switching
+    /// back to the JSON backend still finds the pre-migration history, and
+    /// [`StorageService::open`](crate::services::storage::StorageService::open)
+    /// backs them up first.
+    pub fn migrate_from(&self, json: &JsonBackend) -> Result<(), StorageError> {
+        if self.json_migrated()? {
+            return Ok(());
+        }
+
+        let statistics = json.load_statistics(None, None)?;
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(sql_error)?;
+        for statistic in &statistics {
+            upsert(&tx, statistic)?;
+        }
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)",
+            params![JSON_MIGRATED_KEY, statistics.len().to_string()],
+        )
+        .map_err(sql_error)?;
+        tx.commit().map_err(sql_error)
+    }
+}
+
+/// Databases created before advisories or overwork events were recorded
+/// lack their JSON list `column`.
+fn add_list_column(conn: &Connection, column: &str) -> Result<(), StorageError> {
+    let present = conn
+        .prepare("SELECT 1 FROM pragma_table_info('statistics') WHERE name = ?1")
+        .and_then(|mut statement| statement.exists(params![column]))
+        .map_err(sql_error)?;
+    if !present {
+        conn.execute_batch(&format!(
+            "ALTER TABLE statistics ADD COLUMN {column} TEXT NOT NULL DEFAULT '[]'"
+        ))
+        .map_err(sql_error)?;
+    }
+    Ok(())
+}
+
+fn upsert(conn: &Connection, statistic: &TimerStatistic) -> Result<(), StorageError> {
+    let serialization = |what: &str, e: serde_json::Error| {
+        StorageError::Serialization(format!(
+            "Failed to serialize {what} for {}: {e}",
+            statistic.date
+        ))
+    };
+    let sessions =
+        serde_json::to_string(&statistic.sessions).map_err(|e| serialization("sessions", e))?;
+    let advisories =
+        serde_json::to_string(&statistic.advisories).map_err(|e| serialization("advisories", e))?;
+    let overwork =
+        serde_json::to_string(&statistic.overwork).map_err(|e| serialization("overwork", e))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO statistics
+         (date, completed_pomodoros, total_focus_time, sessions, advisories, overwork)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            statistic.date,
+            statistic.completed_pomodoros,
+            statistic.total_focus_time,
+            sessions,
+            advisories,
+            overwork,
+        ],
+    )
+    .map_err(sql_error)?;
+    Ok(())
+}
+
+/// A row with its `sessions`, `advisories` and `overwork` JSON still
+/// unparsed.
+type StatisticRow = (TimerStatistic, String, String, String);
+
+fn statistic_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<StatisticRow> {
+    Ok((
+        TimerStatistic {
+            date: row.get(0)?,
+            completed_pomodoros: row.get(1)?,
+            total_focus_time: row.get(2)?,
+            sessions: Vec::new(),
+            advisories: Vec::new(),
+            overwork: Vec::new(),
+        },
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+    ))
+}
+
+fn with_sessions(
+    (mut statistic, sessions, advisories, overwork): StatisticRow,
+) -> Result<TimerStatistic, StorageError> {
+    let serialization = |what: &str, date: &str, e: serde_json::Error| {
+        StorageError::Serialization(format!("Failed to parse {what} for {date}: {e}"))
+    };
+    statistic.sessions = serde_json::from_str(&sessions)
+        .map_err(|e| serialization("sessions", &statistic.date, e))?;
+    statistic.advisories = serde_json::from_str(&advisories)
+        .map_err(|e| serialization("advisories", &statistic.date, e))?;
+    statistic.overwork = serde_json::from_str(&overwork)
+        .map_err(|e| serialization("overwork", &statistic.date, e))?;
+    Ok(statistic)
+}
+
+impl StorageBackend for SqliteBackend {
+    fn save_statistic(&self, statistic: &TimerStatistic) -> Result<(), StorageError> {
+        upsert(&*self.lock()?, statistic)
+    }
+
+    fn load_statistic(&self, date: &str) -> Result<Option<TimerStatistic>, StorageError> {
+        self.lock()?
+            .query_row(
+                &format!("{SELECT_COLUMNS} WHERE date = ?1"),
+                params![date],
+                statistic_from_row,
+            )
+            .optional()
+            .map_err(sql_error)?
+            .map(with_sessions)
+            .transpose()
+    }
+
+    fn dates(&self) -> Result<Vec<String>, StorageError> {
+        let conn = self.lock()?;
+        let mut statement = conn
+            .prepare("SELECT date FROM statistics ORDER BY date")
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map([], |row| row.get(0))
+            .map_err(sql_error)?;
+        rows.map(|row| row.map_err(sql_error)).collect()
+    }
+
+    fn load_statistics(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<TimerStatistic>, StorageError> {
+        let conn = self.lock()?;
+        let mut statement = conn
+            .prepare(&format!(
+                "{SELECT_COLUMNS}
+                 WHERE (?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)
+                 ORDER BY date"
+            ))
+            .map_err(sql_error)?;
+        let rows = statement
+            .query_map(
+                params![start_date.map(day_key), end_date.map(day_key)],
+                statistic_from_row,
+            )
+            .map_err(sql_error)?;
+        rows.map(|row| with_sessions(row.map_err(sql_error)?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::focus_budget::{BudgetPeriod, OverworkEvent};
+    use crate::services::integrity::Quarantine;
+    use crate::services::storage::{day, session, SessionData};
+    use crate::services::timer_state::SessionType;
+    use std::sync::Arc;
+
+    fn statistic(date: &str, total_focus_time: u64) -> TimerStatistic {
+        let session = SessionData {
+            tags: vec!["writing".to_string()],
+            ..session(SessionType::Work, 1_741_600_000, total_focus_time)
+        };
+        TimerStatistic {
+            overwork: vec![OverworkEvent {
+                timestamp: 1_741_610_000,
+                period: BudgetPeriod::Day,
+                focus_time: total_focus_time,
+                cap: total_focus_time,
+                refused_auto_start: false,
+            }],
+            ..day(date, vec![session])
+        }
+    }
+
+    fn date(key: &str) -> Option<NaiveDate> {
+        crate::services::calendar::parse_day(key)
+    }
+
+    #[test]
+    fn days_round_trip_and_are_replaced_by_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = SqliteBackend::open(&dir.path().join("statistics.db")).unwrap();
+        backend
+            .save_statistic(&statistic("2025-03-11", 1500))
+            .unwrap();
+        backend
+            .save_statistic(&statistic("2025-03-10", 1500))
+            .unwrap();
+        backend
+            .save_statistic(&statistic("2025-03-10", 3000))
+            .unwrap();
+
+        let loaded = backend.load_statistic("2025-03-10").unwrap().unwrap();
+        assert_eq!(loaded.total_focus_time, 3000);
+        assert_eq!(loaded.sessions, statistic("2025-03-10", 3000).sessions);
+        assert_eq!(loaded.overwork, statistic("2025-03-10", 3000).overwork);
+        assert!(backend.load_statistic("2025-03-12").unwrap().is_none());
+        assert_eq!(backend.dates().unwrap(), ["2025-03-10", "2025-03-11"]);
+
+        let dates = |start, end| -> Vec<String> {
+            backend
+                .load_statistics(date(start), date(end))
+                .unwrap()
+                .into_iter()
+                .map(|statistic| statistic.date)
+                .collect()
+        };
+        assert_eq!(dates("2025-03-11", "2025-03-31"), ["2025-03-11"]);
+        assert_eq!(dates("2025-03-01", "2025-03-10"), ["2025-03-10"]);
+        assert_eq!(backend.load_statistics(None, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn json_days_are_migrated_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let quarantine = Arc::new(Quarantine::new(dir.path().join("quarantine")));
+        std::fs::create_dir_all(dir.path().join("statistics")).unwrap();
+        let json = JsonBackend::new(dir.path().join("statistics"), quarantine);
+        json.save_statistic(&statistic("2025-03-10", 1500)).unwrap();
+
+        let backend = SqliteBackend::open(&dir.path().join("statistics.db")).unwrap();
+        assert!(!backend.json_migrated().unwrap());
+        backend.migrate_from(&json).unwrap();
+        assert!(backend.json_migrated().unwrap());
+        assert_eq!(backend.dates().unwrap(), ["2025-03-10"]);
+
+        // Days recorded since aren't overwritten by a second migration.
+        backend
+            .save_statistic(&statistic("2025-03-10", 3000))
+            .unwrap();
+        backend.migrate_from(&json).unwrap();
+        let loaded = backend.load_statistic("2025-03-10").unwrap().unwrap();
+        assert_eq!(loaded.total_focus_time, 3000);
+    }
+
+    #[test]
+    fn databases_from_before_the_list_columns_gain_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("statistics.db");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE statistics (
+                    date TEXT PRIMARY KEY,
+                    completed_pomodoros INTEGER NOT NULL,
+                    total_focus_time INTEGER NOT NULL,
+                    sessions TEXT NOT NULL
+                );
+                INSERT INTO statistics VALUES ('2025-03-10', 0, 0, '[]');",
+            )
+            .unwrap();
+
+        let backend = SqliteBackend::open(&path).unwrap();
+        let loaded = backend.load_statistic("2025-03-10").unwrap().unwrap();
+        assert!(loaded.advisories.is_empty() && loaded.overwork.is_empty());
+        backend
+            .save_statistic(&statistic("2025-03-10", 1500))
+            .unwrap();
+    }
+}
@@ -0,0 +1,362 @@
+//! Crash-safe files for the storage service. Writes go to a temporary file
+//! of their own that is renamed over the target, with the directory synced
+//! after, so a power cut leaves either the old or the new version. The
+//! version being replaced is kept as `<file>.bak` if it is intact, and
+//! reads fall back to that copy when the file no longer parses.
+
+use serde::de::DeserializeOwned;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Error reading or writing stored data.
+#[derive(Debug)]
+pub enum StorageError {
+    /// The requested record or file doesn't exist.
+    NotFound(String),
+    /// The file system refused a read or write.
+    Io(String),
+    /// A value couldn't be converted to or from its stored form.
+    Serialization(String),
+    /// The file doesn't parse and has no usable backup.
+    Corrupted {
+        path: PathBuf,
+        reason: String,
+    },
+    /// The write failed and is held in memory until storage recovers, so
+    /// it must not be repeated.
+    Held(String),
+    Failed(String),
+}
+
+impl StorageError {
+    /// Stable, snake_case identifier of the variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StorageError::NotFound(_) => "not_found",
+            StorageError::Io(_) => "io",
+            StorageError::Serialization(_) => "serialization",
+            StorageError::Corrupted { .. } => "corrupted",
+            StorageError::Held(_) => "held",
+            StorageError::Failed(_) => "storage_failed",
+        }
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Corrupted { path, reason } => write!(
+                f,
+                "{} is corrupted and has no usable backup: {reason}",
+                path.display()
+            ),
+            StorageError::NotFound(message)
+            | StorageError::Io(message)
+            | StorageError::Serialization(message)
+            | StorageError::Failed(message) => f.write_str(message),
+            StorageError::Held(message) => {
+                write!(f, "{message}; kept in memory until storage recovers")
+            }
+        }
+    }
+}
+
+impl From<String> for StorageError {
+    fn from(message: String) -> Self {
+        StorageError::Failed(message)
+    }
+}
+
+impl From<StorageError> for String {
+    fn from(error: StorageError) -> Self {
+        error.to_string()
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Where the previous version of `path` is kept.
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// Replaces `path` with `content` through a synced temporary file.
+pub fn replace(path: &Path, content: &[u8]) -> Result<(), StorageError> {
+    replace_with(path, content, false)
+}
+
+/// Like [`replace`], leaving the file readable by the current user only,
+/// for secrets such as access tokens.
+pub fn replace_private(path: &Path, content: &[u8]) -> Result<(), StorageError> {
+    replace_with(path, content, true)
+}
+
+fn replace_with(path: &Path, content: &[u8], private: bool) -> Result<(), StorageError> {
+    // Named uniquely, so concurrent writers don't write into one another's.
+    let temp = with_suffix(path, &format!(".{}.tmp", uuid::Uuid::new_v4().simple()));
+    let written = File::create(&temp).and_then(|mut file| {
+        if private {
+            restrict(&file)?;
+        }
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(error) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(StorageError::Io(format!(
+            "Failed to write {}: {error}",
+            temp.display()
+        )));
+    }
+    if let Err(error) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(StorageError::Io(format!(
+            "Failed to replace {}: {error}",
+            path.display()
+        )));
+    }
+    sync_parent(path)
+        .map_err(|e| StorageError::Io(format!("Failed to sync {}: {e}", path.display())))
+}
+
+/// Flushes the rename of `path` to disk. Windows has no handle for a
+/// directory to sync; its renames are journaled.
+fn sync_parent(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Makes `file` readable and writable by its owner only. Elsewhere the
+/// user's profile directory already keeps it private.
+fn restrict(file: &File) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = file;
+    Ok(())
+}
+
+/// Reads the secret [`replace_private`] wrote to `path`, or `None` if there
+/// isn't one. Files saved before secrets were kept private are restricted
+/// on the way.
+pub fn read_private(path: &Path) -> Result<Option<String>, StorageError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file = File::open(path)
+        .map_err(|e| StorageError::Io(format!("Failed to read {}: {e}", path.display())))?;
+    restrict(&file)
+        .map_err(|e| StorageError::Io(format!("Failed to restrict {}: {e}", path.display())))?;
+    let content = read(path)?;
+    Ok(Some(String::from_utf8_lossy(&content).trim().to_string()))
+}
+
+/// Like [`replace`], first keeping the current version as its backup if
+/// `decode` accepts it, so a corrupt file never replaces a good backup.
+pub fn write_with<T>(
+    path: &Path,
+    content: &[u8],
+    decode: impl Fn(&str) -> Result<T, String>,
+) -> Result<(), StorageError> {
+    if path.exists() {
+        let current = read(path)?;
+        let intact = std::str::from_utf8(&current)
+            .map_err(|e| e.to_string())
+            .and_then(&decode)
+            .is_ok();
+        if intact {
+            replace(&backup_path(path), &current)?;
+        }
+    }
+    replace(path, content)
+}
+
+/// Serializes `value` as pretty JSON and writes it with [`write_with`].
+pub fn write_json<T: serde::Serialize + ?Sized>(
+    path: &Path,
+    value: &T,
+) -> Result<(), StorageError> {
+    let content = serde_json::to_string_pretty(value).map_err(|e| {
+        StorageError::Serialization(format!("Failed to serialize {}: {e}", path.display()))
+    })?;
+    write_with(path, content.as_bytes(), |content| {
+        serde_json::from_str::<serde_json::Value>(content).map_err(|e| e.to_string())
+    })
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, StorageError> {
+    fs::read(path).map_err(|e| StorageError::Io(format!("Failed to read {}: {e}", path.display())))
+}
+
+fn parse<T>(
+    path: &Path,
+    decode: impl Fn(&str) -> Result<T, String>,
+) -> Result<Option<T>, StorageError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| StorageError::Io(format!("Failed to read {}: {e}", path.display())))?;
+    decode(&content)
+        .map(Some)
+        .map_err(|reason| StorageError::Corrupted {
+            path: path.to_path_buf(),
+            reason,
+        })
+}
+
+/// Reads the JSON file at `path`, or `None` if there isn't one. If it no
+/// longer parses, its backup is used instead and copied back over it.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+    read_with(path, |content| {
+        serde_json::from_str(content).map_err(|e| e.to_string())
+    })
+}
+
+/// Like [`read_json`], with `decode` deciding whether the content is intact.
+pub fn read_with<T>(
+    path: &Path,
+    decode: impl Fn(&str) -> Result<T, String>,
+) -> Result<Option<T>, StorageError> {
+    let reason = match parse(path, &decode) {
+        Err(StorageError::Corrupted { reason, .. }) => reason,
+        result => return result,
+    };
+    let backup = backup_path(path);
+    let Ok(Some(value)) = parse(&backup, &decode) else {
+        return Err(StorageError::Corrupted {
+            path: path.to_path_buf(),
+            reason,
+        });
+    };
+    log::warn!(
+        "{} is corrupted ({reason}); using {}",
+        path.display(),
+        backup.display()
+    );
+    let repaired = read(&backup).and_then(|content| replace(path, &content));
+    if let Err(error) = repaired {
+        log::error!("{error}");
+    }
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files_in(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn writes_keep_the_previous_version_as_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.json");
+        write_json(&path, &1).unwrap();
+        write_json(&path, &2).unwrap();
+
+        assert_eq!(read_json::<u32>(&path).unwrap(), Some(2));
+        assert_eq!(read_json::<u32>(&backup_path(&path)).unwrap(), Some(1));
+        assert_eq!(files_in(dir.path()), ["data.json", "data.json.bak"]);
+    }
+
+    #[test]
+    fn a_corrupt_file_does_not_replace_a_good_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.json");
+        write_json(&path, &1).unwrap();
+        write_json(&path, &2).unwrap();
+        fs::write(&path, "{ not json").unwrap();
+
+        assert_eq!(read_json::<u32>(&path).unwrap(), Some(1));
+        fs::write(&path, "{ not json").unwrap();
+        write_json(&path, &3).unwrap();
+        assert_eq!(read_json::<u32>(&path).unwrap(), Some(3));
+        assert_eq!(read_json::<u32>(&backup_path(&path)).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn unreadable_files_without_backup_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        assert!(matches!(
+            read_json::<u32>(&path),
+            Err(StorageError::Corrupted { .. })
+        ));
+        assert_eq!(
+            read_json::<u32>(&path.with_extension("missing")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn concurrent_writers_each_replace_the_file_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.json");
+        let writers: Vec<_> = (0..8u32)
+            .map(|writer| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..20 {
+                        replace(&path, format!("[{writer}, {writer}]").as_bytes()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let [first, second] = read_json::<[u32; 2]>(&path).unwrap().unwrap();
+        assert_eq!(first, second);
+        assert_eq!(files_in(dir.path()), ["data.json"]);
+    }
+
+    #[test]
+    fn secrets_are_readable_by_their_owner_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        replace(&path, b"old\n").unwrap();
+        #[cfg(unix)]
+        let mode = || {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777
+        };
+
+        assert_eq!(read_private(&path).unwrap().as_deref(), Some("old"));
+        #[cfg(unix)]
+        assert_eq!(mode(), 0o600);
+
+        replace_private(&path, b"new").unwrap();
+        assert_eq!(read_private(&path).unwrap().as_deref(), Some("new"));
+        #[cfg(unix)]
+        assert_eq!(mode(), 0o600);
+    }
+}
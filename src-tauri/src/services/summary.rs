@@ -0,0 +1,252 @@
+//! Totals, averages and records over a date range, rolled up by day, week or
+//! month, so the UI doesn't have to crunch every `TimerStatistic` itself.
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::days_off::DaysOffSettings;
+use super::storage::TimerStatistic;
+use super::timeseries::Granularity;
+use super::year_review::longest_streak;
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+pub struct PeriodTotal {
+    pub start_date: String,
+    /// Inclusive, clipped to the requested range.
+    pub end_date: String,
+    pub completed_pomodoros: u32,
+    pub total_focus_time: u64,
+    /// Days in the period with at least one pomodoro.
+    pub active_days: u32,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BestDay {
+    pub date: String,
+    pub completed_pomodoros: u32,
+    pub total_focus_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct StatisticsSummary {
+    pub granularity: Granularity,
+    pub from: String,
+    pub to: String,
+    pub total_pomodoros: u32,
+    pub total_focus_time: u64,
+    pub active_days: u32,
+    /// Vacation days and holidays in the range.
+    pub days_off: u32,
+    /// Averages over every day in the range but days off, including empty
+    /// ones.
+    pub average_daily_pomodoros: f64,
+    pub average_daily_focus_time: f64,
+    /// Averages over the periods in `periods`.
+    pub average_period_pomodoros: f64,
+    pub average_period_focus_time: f64,
+    /// Most consecutive days with at least one pomodoro; days off don't
+    /// break a streak.
+    pub longest_streak_days: u32,
+    /// The day with the most focus time; ties go to the earlier day.
+    pub best_day: Option<BestDay>,
+    pub periods: Vec<PeriodTotal>,
+}
+
+/// First day of the period after the one starting at `start`.
+fn next_period(start: NaiveDate, granularity: Granularity) -> NaiveDate {
+    match granularity {
+        Granularity::Day => start + Duration::days(1),
+        Granularity::Week => start + Duration::days(7),
+        Granularity::Month => start + Months::new(1),
+    }
+}
+
+/// First day of the period containing `date`. Weeks start on Monday.
+fn period_start(date: NaiveDate, granularity: Granularity) -> NaiveDate {
+    match granularity {
+        Granularity::Day => date,
+        Granularity::Week => date - Duration::days(date.weekday().num_days_from_monday().into()),
+        Granularity::Month => date.with_day(1).unwrap_or(date),
+    }
+}
+
+/// The periods covering `from..=to`, each with its last day.
+fn empty_periods(
+    from: NaiveDate,
+    to: NaiveDate,
+    granularity: Granularity,
+) -> Vec<(NaiveDate, PeriodTotal)> {
+    let mut periods = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let next = next_period(period_start(start, granularity), granularity);
+        let end = (next - Duration::days(1)).min(to);
+        periods.push((
+            end,
+            PeriodTotal {
+                start_date: start.to_string(),
+                end_date: end.to_string(),
+                ..PeriodTotal::default()
+            },
+        ));
+        start = next;
+    }
+    periods
+}
+
+fn average<T: Into<f64>>(total: T, count: usize) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total.into() / count as f64
+    }
+}
+
+/// Summarises `statistics` between `from` and `to` (inclusive) in a single
+/// pass; `statistics` is expected in date order, as storage returns it.
+pub fn summarize(
+    statistics: &[TimerStatistic],
+    granularity: Granularity,
+    from: NaiveDate,
+    to: NaiveDate,
+    days_off: &DaysOffSettings,
+) -> StatisticsSummary {
+    let (ends, mut periods): (Vec<_>, Vec<_>) =
+        empty_periods(from, to, granularity).into_iter().unzip();
+    let mut period = 0;
+    let mut active_dates = Vec::new();
+    let mut best_day: Option<&TimerStatistic> = None;
+    let (mut total_pomodoros, mut total_focus_time) = (0, 0);
+
+    for statistic in statistics {
+        let Ok(date) = NaiveDate::parse_from_str(&statistic.date, "%Y-%m-%d") else {
+            continue;
+        };
+        if date < from || date > to {
+            continue;
+        }
+        while ends.get(period).is_some_and(|end| *end < date) {
+            period += 1;
+        }
+        let Some(total) = periods.get_mut(period) else {
+            break;
+        };
+        total.completed_pomodoros += statistic.completed_pomodoros;
+        total.total_focus_time += statistic.total_focus_time;
+        if statistic.completed_pomodoros > 0 {
+            total.active_days += 1;
+            active_dates.push(date);
+        }
+        total_pomodoros += statistic.completed_pomodoros;
+        total_focus_time += statistic.total_focus_time;
+        if statistic.total_focus_time > best_day.map_or(0, |best| best.total_focus_time) {
+            best_day = Some(statistic);
+        }
+    }
+
+    let off = days_off.between(from, to).len();
+    let days = ((to - from).num_days() as usize + 1).saturating_sub(off);
+    StatisticsSummary {
+        granularity,
+        from: from.to_string(),
+        to: to.to_string(),
+        total_pomodoros,
+        total_focus_time,
+        active_days: active_dates.len() as u32,
+        days_off: off as u32,
+        average_daily_pomodoros: average(total_pomodoros, days),
+        average_daily_focus_time: average(total_focus_time as f64, days),
+        average_period_pomodoros: average(total_pomodoros, periods.len()),
+        average_period_focus_time: average(total_focus_time as f64, periods.len()),
+        longest_streak_days: longest_streak(&active_dates, days_off),
+        best_day: best_day.map(|best| BestDay {
+            date: best.date.clone(),
+            completed_pomodoros: best.completed_pomodoros,
+            total_focus_time: best.total_focus_time,
+        }),
+        periods,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{day, session};
+    use crate::services::timer_state::SessionType;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn focused(date: &str, pomodoros: u64) -> TimerStatistic {
+        let sessions = (0..pomodoros)
+            .map(|n| session(SessionType::Work, n * 3600, 1500))
+            .collect();
+        day(date, sessions)
+    }
+
+    #[test]
+    fn days_are_totalled_into_the_week_they_fall_in() {
+        let statistics = [
+            focused("2026-03-03", 2),
+            focused("2026-03-08", 1),
+            focused("2026-03-10", 3),
+        ];
+        let summary = summarize(
+            &statistics,
+            Granularity::Week,
+            date("2026-03-02"),
+            date("2026-03-15"),
+            &DaysOffSettings::default(),
+        );
+
+        let weeks: Vec<_> = summary
+            .periods
+            .iter()
+            .map(|period| (period.end_date.as_str(), period.completed_pomodoros))
+            .collect();
+        assert_eq!(weeks, [("2026-03-08", 3), ("2026-03-15", 3)]);
+        assert_eq!(summary.periods[0].active_days, 2);
+        assert_eq!(summary.best_day.unwrap().date, "2026-03-10");
+    }
+
+    #[test]
+    fn unpadded_dates_are_placed_by_calendar_order() {
+        let statistics = [focused("2026-03-03", 1), focused("2026-3-10", 2)];
+        let summary = summarize(
+            &statistics,
+            Granularity::Week,
+            date("2026-03-02"),
+            date("2026-03-15"),
+            &DaysOffSettings::default(),
+        );
+
+        assert_eq!(summary.periods[0].completed_pomodoros, 1);
+        assert_eq!(summary.periods[1].completed_pomodoros, 2);
+        assert_eq!(summary.total_pomodoros, 3);
+    }
+
+    #[test]
+    fn days_outside_the_range_are_left_out() {
+        let statistics = [
+            focused("2026-02-28", 4),
+            focused("2026-03-01", 1),
+            focused("2026-04-01", 4),
+        ];
+        let summary = summarize(
+            &statistics,
+            Granularity::Month,
+            date("2026-03-01"),
+            date("2026-03-31"),
+            &DaysOffSettings::default(),
+        );
+
+        assert_eq!(summary.periods.len(), 1);
+        assert_eq!(summary.total_pomodoros, 1);
+        assert_eq!(summary.active_days, 1);
+    }
+}
@@ -0,0 +1,327 @@
+//! Access control for surfaces outside the webview, such as the gRPC, MCP
+//! and HTTP servers. Every external request is checked here before it
+//! reaches the timer, task or storage services.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+use ts_rs::TS;
+
+use super::storage::StorageService;
+
+/// How much an external surface may do, in increasing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SurfaceScope {
+    /// Every request is rejected.
+    Disabled,
+    /// State, statistics and task lists only.
+    ReadOnly,
+    /// Read access plus starting, pausing and editing.
+    Control,
+}
+
+/// Per-surface scopes, stored in the preferences.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SurfaceAccess {
+    pub grpc: SurfaceScope,
+    /// MCP clients such as AI assistants.
+    pub mcp: SurfaceScope,
+    /// Scripts and widgets using the local REST API.
+    pub http: SurfaceScope,
+    /// Network display clients; never more than read-only. The display
+    /// server only listens if this is enabled at launch.
+    pub display: SurfaceScope,
+    /// Overlays and dashboards on the live state socket; never more than
+    /// read-only.
+    pub overlay: SurfaceScope,
+    /// `tempusring://` links from browsers and launchers. Any web page can
+    /// open one and they carry no token, so they are off unless turned on.
+    pub deep_link: SurfaceScope,
+}
+
+impl Default for SurfaceAccess {
+    fn default() -> Self {
+        Self {
+            grpc: SurfaceScope::Control,
+            mcp: SurfaceScope::Control,
+            http: SurfaceScope::Control,
+            display: SurfaceScope::Disabled,
+            overlay: SurfaceScope::ReadOnly,
+            deep_link: SurfaceScope::Disabled,
+        }
+    }
+}
+
+impl SurfaceAccess {
+    fn scope(&self, surface: Surface) -> SurfaceScope {
+        match surface {
+            Surface::Grpc => self.grpc,
+            Surface::Mcp => self.mcp,
+            Surface::Http => self.http,
+            Surface::Display => self.display.min(SurfaceScope::ReadOnly),
+            Surface::Overlay => self.overlay.min(SurfaceScope::ReadOnly),
+            Surface::DeepLink => self.deep_link,
+        }
+    }
+
+    /// Checks `access` against the scope the user granted `surface`.
+    pub fn check(&self, surface: Surface, access: Access) -> Result<(), AuthError> {
+        match (self.scope(surface), access) {
+            (SurfaceScope::Disabled, _) => Err(AuthError::Disabled(surface)),
+            (SurfaceScope::ReadOnly, Access::Control) => Err(AuthError::ReadOnly(surface)),
+            (SurfaceScope::ReadOnly, Access::Read) | (SurfaceScope::Control, _) => Ok(()),
+        }
+    }
+}
+
+/// The gRPC, MCP and HTTP servers are behind the features of those names.
+#[cfg_attr(
+    not(any(feature = "grpc", feature = "mcp", feature = "http")),
+    allow(dead_code)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    Grpc,
+    Mcp,
+    Http,
+    Display,
+    Overlay,
+    DeepLink,
+}
+
+impl fmt::Display for Surface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Surface::Grpc => f.write_str("gRPC"),
+            Surface::Mcp => f.write_str("MCP"),
+            Surface::Http => f.write_str("HTTP"),
+            Surface::Display => f.write_str("Display"),
+            Surface::Overlay => f.write_str("Overlay"),
+            Surface::DeepLink => f.write_str("Deep link"),
+        }
+    }
+}
+
+/// What a request needs to be allowed.
+#[cfg_attr(
+    not(any(feature = "grpc", feature = "mcp", feature = "http")),
+    allow(dead_code)
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Control,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    MissingToken,
+    InvalidToken,
+    Disabled(Surface),
+    ReadOnly(Surface),
+    Failed(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::MissingToken => f.write_str("An access token is required"),
+            AuthError::InvalidToken => f.write_str("The access token is not valid"),
+            AuthError::Disabled(surface) => write!(f, "{surface} access is disabled"),
+            AuthError::ReadOnly(surface) => write!(f, "{surface} access is read-only"),
+            AuthError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl From<String> for AuthError {
+    fn from(message: String) -> Self {
+        AuthError::Failed(message)
+    }
+}
+
+pub fn generate_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Compares without short-circuiting so response timing doesn't reveal how
+/// much of a guessed token was right.
+pub fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Holds the local access token shared by all external surfaces.
+pub struct SurfaceAuth {
+    token: Mutex<String>,
+}
+
+impl SurfaceAuth {
+    /// Loads the stored token, creating one on first launch.
+    pub fn load(storage: &StorageService) -> Result<Self, String> {
+        let token = match storage.load_surface_token()? {
+            Some(token) => token,
+            None => {
+                let token = generate_token();
+                storage.save_surface_token(&token)?;
+                token
+            }
+        };
+        Ok(Self {
+            token: Mutex::new(token),
+        })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, String>, String> {
+        self.token
+            .lock()
+            .map_err(|e| format!("Access token lock poisoned: {e}"))
+    }
+
+    pub fn token(&self) -> Result<String, String> {
+        Ok(self.lock()?.clone())
+    }
+
+    /// Replaces the token; clients holding the old one are rejected from now on.
+    pub fn rotate(&self, storage: &StorageService) -> Result<String, String> {
+        let mut token = self.lock()?;
+        let rotated = generate_token();
+        storage.save_surface_token(&rotated)?;
+        *token = rotated.clone();
+        Ok(rotated)
+    }
+
+    /// Checks `presented` against the token and `access` against the scope
+    /// the user granted `surface`.
+    pub fn authorize(
+        &self,
+        storage: &StorageService,
+        surface: Surface,
+        presented: Option<&str>,
+        access: Access,
+    ) -> Result<(), AuthError> {
+        let presented = presented.ok_or(AuthError::MissingToken)?;
+        if !tokens_match(presented, &self.lock()?) {
+            return Err(AuthError::InvalidToken);
+        }
+        storage
+            .load_preferences()?
+            .external_access
+            .check(surface, access)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{StorageServiceBuilder, TestStorage, UserPreferences};
+
+    fn storage_with(access: SurfaceAccess) -> TestStorage {
+        StorageServiceBuilder::new()
+            .preferences(UserPreferences {
+                external_access: access,
+                ..UserPreferences::default()
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn tokens_match_only_byte_for_byte() {
+        assert!(tokens_match("abc123", "abc123"));
+        assert!(!tokens_match("abc124", "abc123"));
+        assert!(!tokens_match("abc12", "abc123"));
+        assert!(!tokens_match("abc1234", "abc123"));
+        assert!(!tokens_match("", "abc123"));
+    }
+
+    #[test]
+    fn requests_need_the_current_token() {
+        let storage = storage_with(SurfaceAccess::default());
+        let auth = SurfaceAuth::load(&storage).unwrap();
+        let token = auth.token().unwrap();
+        let authorize = |presented: Option<&str>| {
+            auth.authorize(&storage, Surface::Http, presented, Access::Read)
+        };
+
+        assert_eq!(authorize(None), Err(AuthError::MissingToken));
+        assert_eq!(
+            authorize(Some("not the token")),
+            Err(AuthError::InvalidToken)
+        );
+        assert_eq!(authorize(Some(token.as_str())), Ok(()));
+
+        let rotated = auth.rotate(&storage).unwrap();
+        assert_eq!(
+            authorize(Some(token.as_str())),
+            Err(AuthError::InvalidToken)
+        );
+        assert_eq!(authorize(Some(rotated.as_str())), Ok(()));
+    }
+
+    #[test]
+    fn control_needs_a_control_scope() {
+        let storage = storage_with(SurfaceAccess {
+            grpc: SurfaceScope::Disabled,
+            mcp: SurfaceScope::ReadOnly,
+            ..SurfaceAccess::default()
+        });
+        let auth = SurfaceAuth::load(&storage).unwrap();
+        let token = auth.token().unwrap();
+        let authorize =
+            |surface, access| auth.authorize(&storage, surface, Some(token.as_str()), access);
+
+        assert_eq!(
+            authorize(Surface::Grpc, Access::Read),
+            Err(AuthError::Disabled(Surface::Grpc))
+        );
+        assert_eq!(
+            authorize(Surface::Grpc, Access::Control),
+            Err(AuthError::Disabled(Surface::Grpc))
+        );
+        assert_eq!(authorize(Surface::Mcp, Access::Read), Ok(()));
+        assert_eq!(
+            authorize(Surface::Mcp, Access::Control),
+            Err(AuthError::ReadOnly(Surface::Mcp))
+        );
+        assert_eq!(authorize(Surface::Http, Access::Control), Ok(()));
+    }
+
+    #[test]
+    fn display_and_overlay_scopes_are_capped_at_read_only() {
+        let access = SurfaceAccess {
+            display: SurfaceScope::Control,
+            overlay: SurfaceScope::Control,
+            ..SurfaceAccess::default()
+        };
+        let storage = storage_with(access.clone());
+        let auth = SurfaceAuth::load(&storage).unwrap();
+        let token = auth.token().unwrap();
+
+        for surface in [Surface::Display, Surface::Overlay] {
+            assert_eq!(access.scope(surface), SurfaceScope::ReadOnly);
+            assert_eq!(
+                auth.authorize(&storage, surface, Some(token.as_str()), Access::Read),
+                Ok(())
+            );
+            assert_eq!(
+                auth.authorize(&storage, surface, Some(token.as_str()), Access::Control),
+                Err(AuthError::ReadOnly(surface))
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Detects the OS light/dark appearance from the backend, since the webview's
+//! `prefers-color-scheme` doesn't follow the OS everywhere (notably WebKitGTK).
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Theme};
+use ts_rs::TS;
+
+use super::appearance::AppearanceScheduler;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+use super::focus_mode::run;
+
+/// Emitted with the new [`SystemTheme`] whenever the OS appearance changes.
+pub const SYSTEM_THEME_EVENT: &str = "appearance://system-theme";
+/// How often the OS setting is polled, for platforms that don't report
+/// changes to the window.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemTheme {
+    Light,
+    Dark,
+}
+
+impl SystemTheme {
+    fn from_dark(dark: bool) -> Self {
+        if dark {
+            Self::Dark
+        } else {
+            Self::Light
+        }
+    }
+}
+
+impl From<Theme> for SystemTheme {
+    fn from(theme: Theme) -> Self {
+        Self::from_dark(theme == Theme::Dark)
+    }
+}
+
+/// Reads the OS setting directly. `None` if it can't be determined.
+#[cfg(target_os = "macos")]
+fn query_os() -> Option<SystemTheme> {
+    // The key only exists while dark mode is on.
+    let dark = run("defaults", &["read", "-g", "AppleInterfaceStyle"])
+        .is_ok_and(|style| style.eq_ignore_ascii_case("dark"));
+    Some(SystemTheme::from_dark(dark))
+}
+
+#[cfg(target_os = "linux")]
+fn query_os() -> Option<SystemTheme> {
+    const SCHEMA: &str = "org.gnome.desktop.interface";
+    if let Ok(scheme) = run("gsettings", &["get", SCHEMA, "color-scheme"]) {
+        match scheme.trim_matches('\'') {
+            "prefer-dark" => return Some(SystemTheme::Dark),
+            "prefer-light" => return Some(SystemTheme::Light),
+            _ => {}
+        }
+    }
+    // Older desktops only signal dark mode through the GTK theme name.
+    let theme = run("gsettings", &["get", SCHEMA, "gtk-theme"]).ok()?;
+    Some(SystemTheme::from_dark(
+        theme.to_ascii_lowercase().contains("dark"),
+    ))
+}
+
+/// Windows and the mobile platforms report their appearance reliably to the
+/// window, so [`detect`] falls back to that.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn query_os() -> Option<SystemTheme> {
+    None
+}
+
+/// The OS setting, falling back to what the main window reports.
+pub fn detect(app: &AppHandle) -> Option<SystemTheme> {
+    query_os().or_else(|| {
+        app.get_webview_window("main")
+            .and_then(|window| window.theme().ok())
+            .map(SystemTheme::from)
+    })
+}
+
+/// Last appearance seen, so changes are announced once.
+#[derive(Default)]
+pub struct SystemThemeMonitor {
+    current: Mutex<Option<SystemTheme>>,
+}
+
+impl SystemThemeMonitor {
+    pub fn current(&self) -> Result<Option<SystemTheme>, String> {
+        self.current
+            .lock()
+            .map(|current| *current)
+            .map_err(|e| format!("System theme lock poisoned: {e}"))
+    }
+
+    /// Records `theme`, and if it changed, emits [`SYSTEM_THEME_EVENT`] and
+    /// lets the appearance scheduler react.
+    pub fn update(&self, app: &AppHandle, theme: SystemTheme) -> Result<(), String> {
+        {
+            let mut current = self
+                .current
+                .lock()
+                .map_err(|e| format!("System theme lock poisoned: {e}"))?;
+            if *current == Some(theme) {
+                return Ok(());
+            }
+            *current = Some(theme);
+        }
+        let scheduler = app.state::<AppearanceScheduler>();
+        scheduler.set_system_dark(theme == SystemTheme::Dark)?;
+        scheduler.check(app)?;
+        app.emit(SYSTEM_THEME_EVENT, theme)
+            .map_err(|e| format!("Failed to emit {SYSTEM_THEME_EVENT}: {e}"))
+    }
+}
+
+/// Polls the OS appearance every [`POLL_INTERVAL`]; window theme events are
+/// fed to [`SystemThemeMonitor::update`] as well, for an immediate reaction
+/// where the platform reports them.
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(theme) = detect(&app) else {
+                continue;
+            };
+            if let Err(error) = app.state::<SystemThemeMonitor>().update(&app, theme) {
+                log::error!("System theme update failed: {error}");
+            }
+        }
+    });
+}
@@ -0,0 +1,61 @@
+//! Moves tasks completed long enough ago out of the task list into the
+//! archive, which `get_tasks` leaves out but exports and reviews still
+//! include. A task keeps its pomodoro counts in the archive and
+//! `unarchive_task` brings it back.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::storage::StorageService;
+use super::tasks::{Task, TaskManager};
+
+/// How often the scheduler looks for tasks to archive.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct TaskArchivePolicy {
+    pub enabled: bool,
+    /// Days after completion a task is archived.
+    pub after_days: u32,
+}
+
+impl Default for TaskArchivePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_days: 30,
+        }
+    }
+}
+
+/// Archives the tasks due under the saved policy. Returns the tasks moved.
+pub fn run(storage: &StorageService, tasks: &TaskManager) -> Result<Vec<Task>, String> {
+    let policy = storage.load_preferences()?.task_archive;
+    if !policy.enabled {
+        return Ok(Vec::new());
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let cutoff = now.saturating_sub(u64::from(policy.after_days) * SECS_PER_DAY);
+    tasks.archive_completed(storage, cutoff)
+}
+
+/// Checks every [`CHECK_INTERVAL`], starting at launch.
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let storage = app.state::<StorageService>();
+            if let Err(error) = run(&storage, &app.state::<TaskManager>()) {
+                log::error!("Task archival failed: {error}");
+            }
+        }
+    });
+}
@@ -0,0 +1,202 @@
+//! External task managers whose tasks sessions can be linked to. A linked
+//! session's `task_id` is `<provider>:<id>`, e.g. `todoist:6X7rM8997g3RQmvh`,
+//! and each completed pomodoro linked to a task is logged on it as a
+//! comment. The API token of each connected provider is kept in the data
+//! directory.
+
+use chrono::{DateTime, Local};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::storage::SessionData;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskProviderKind {
+    Todoist,
+}
+
+impl TaskProviderKind {
+    /// Prefix of linked task ids and name of the token file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Todoist => "todoist",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "todoist" => Some(Self::Todoist),
+            _ => None,
+        }
+    }
+
+    /// The provider's client for `token`.
+    pub fn connect(self, token: &str) -> Box<dyn TaskProvider> {
+        match self {
+            Self::Todoist => Box::new(Todoist::new(token)),
+        }
+    }
+}
+
+/// A task in an external task manager.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RemoteTask {
+    /// The id to link sessions with, `<provider>:<id>`.
+    pub id: String,
+    pub title: String,
+    pub project: Option<String>,
+    /// Opens the task in the provider's app.
+    pub url: Option<String>,
+}
+
+/// A session `task_id` naming a task in an external task manager.
+pub fn parse_remote_id(task_id: &str) -> Option<(TaskProviderKind, &str)> {
+    let (key, id) = task_id.split_once(':')?;
+    let kind = TaskProviderKind::from_key(key)?;
+    (!id.is_empty()).then_some((kind, id))
+}
+
+pub trait TaskProvider: Send + Sync {
+    /// Checks the token and returns the account it belongs to.
+    fn verify(&self) -> BoxFuture<'_, Result<String, String>>;
+    /// The user's open tasks.
+    fn list_tasks(&self) -> BoxFuture<'_, Result<Vec<RemoteTask>, String>>;
+    /// Records the completed `session` on the task with provider id `id`.
+    fn log_pomodoro<'a>(
+        &'a self,
+        id: &'a str,
+        session: &'a SessionData,
+    ) -> BoxFuture<'a, Result<(), String>>;
+}
+
+/// The comment logged for a completed session.
+fn pomodoro_note(session: &SessionData) -> String {
+    let start = DateTime::from_timestamp(session.start_time as i64, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local);
+    let minutes = (session.span_end() - session.start_time) / 60;
+    format!(
+        "🍅 Focused for {minutes} min, {}",
+        start.format("%Y-%m-%d %H:%M")
+    )
+}
+
+/// Todoist through its unified API (`/api/v1`).
+pub struct Todoist {
+    token: String,
+    client: reqwest::Client,
+}
+
+impl Todoist {
+    const API_BASE: &'static str = "https://api.todoist.com/api/v1";
+
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, what: &str) -> Result<Value, String> {
+        let response = request
+            .timeout(REQUEST_TIMEOUT)
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .map_err(|e| format!("Todoist {what} failed: {e}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Todoist {what} failed: {status}"));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Todoist {what} returned an invalid response: {e}"))
+    }
+
+    /// Every page of a paginated listing.
+    async fn list_all(&self, resource: &str) -> Result<Vec<Value>, String> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut request = self
+                .client
+                .get(format!("{}/{resource}", Self::API_BASE))
+                .query(&[("limit", "200")]);
+            if let Some(cursor) = &cursor {
+                request = request.query(&[("cursor", cursor)]);
+            }
+            let page = self.send(request, resource).await?;
+            if let Some(results) = page["results"].as_array() {
+                items.extend(results.iter().cloned());
+            }
+            match page["next_cursor"].as_str() {
+                Some(next) => cursor = Some(next.to_string()),
+                None => return Ok(items),
+            }
+        }
+    }
+}
+
+impl TaskProvider for Todoist {
+    fn verify(&self) -> BoxFuture<'_, Result<String, String>> {
+        Box::pin(async move {
+            let request = self.client.get(format!("{}/user", Self::API_BASE));
+            let user = self.send(request, "user").await?;
+            Ok(user["email"]
+                .as_str()
+                .or(user["full_name"].as_str())
+                .unwrap_or_default()
+                .to_string())
+        })
+    }
+
+    fn list_tasks(&self) -> BoxFuture<'_, Result<Vec<RemoteTask>, String>> {
+        Box::pin(async move {
+            let projects = self.list_all("projects").await?;
+            let project_name = |id: &str| {
+                projects
+                    .iter()
+                    .find(|project| project["id"].as_str() == Some(id))
+                    .and_then(|project| project["name"].as_str())
+                    .map(str::to_string)
+            };
+            Ok(self
+                .list_all("tasks")
+                .await?
+                .iter()
+                .filter_map(|task| {
+                    let id = task["id"].as_str()?;
+                    Some(RemoteTask {
+                        id: format!("{}:{id}", TaskProviderKind::Todoist.key()),
+                        title: task["content"].as_str().unwrap_or_default().to_string(),
+                        project: task["project_id"].as_str().and_then(&project_name),
+                        url: Some(format!("https://app.todoist.com/app/task/{id}")),
+                    })
+                })
+                .collect())
+        })
+    }
+
+    fn log_pomodoro<'a>(
+        &'a self,
+        id: &'a str,
+        session: &'a SessionData,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let request = self
+                .client
+                .post(format!("{}/comments", Self::API_BASE))
+                .json(&json!({ "task_id": id, "content": pomodoro_note(session) }));
+            self.send(request, "comment").await.map(|_| ())
+        })
+    }
+}
@@ -0,0 +1,395 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+use super::revision::{check_revision, MutationError};
+use super::storage::StorageService;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub estimated_pomodoros: u32,
+    #[serde(default)]
+    pub completed_pomodoros: u32,
+    #[serde(default)]
+    pub completed: bool,
+    pub created_at: u64,
+    #[serde(default)]
+    pub completed_at: Option<u64>,
+    /// When the archival policy moved it out of the task list.
+    #[serde(default)]
+    pub archived_at: Option<u64>,
+    #[serde(default)]
+    pub revision: u64,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// In-memory task list and archive, written through to `StorageService` on
+/// every change. Archived tasks are left out of the list and lookups;
+/// exports and reports ask for them with [`Self::list_with_archived`].
+pub struct TaskManager {
+    tasks: Mutex<Vec<Task>>,
+    /// Always locked after `tasks`.
+    archived: Mutex<Vec<Task>>,
+}
+
+impl TaskManager {
+    pub fn new(tasks: Vec<Task>, archived: Vec<Task>) -> Self {
+        Self {
+            tasks: Mutex::new(tasks),
+            archived: Mutex::new(archived),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Vec<Task>>, String> {
+        self.tasks
+            .lock()
+            .map_err(|e| format!("Task list lock poisoned: {e}"))
+    }
+
+    fn lock_archived(&self) -> Result<MutexGuard<'_, Vec<Task>>, String> {
+        self.archived
+            .lock()
+            .map_err(|e| format!("Task archive lock poisoned: {e}"))
+    }
+
+    pub fn list(&self) -> Result<Vec<Task>, String> {
+        Ok(self.lock()?.clone())
+    }
+
+    pub fn list_archived(&self) -> Result<Vec<Task>, String> {
+        Ok(self.lock_archived()?.clone())
+    }
+
+    /// The task list followed by the archive, for exports and historical
+    /// reports.
+    pub fn list_with_archived(&self) -> Result<Vec<Task>, String> {
+        let tasks = self.lock()?;
+        let archived = self.lock_archived()?;
+        Ok(tasks.iter().chain(archived.iter()).cloned().collect())
+    }
+
+    pub fn find(&self, id: &str) -> Result<Task, String> {
+        self.lock()?
+            .iter()
+            .find(|task| task.id == id)
+            .cloned()
+            .ok_or_else(|| format!("Task not found: {id}"))
+    }
+
+    /// Replaces the in-memory list and archive, e.g. after a restore.
+    pub fn replace(&self, tasks: Vec<Task>, archived: Vec<Task>) -> Result<(), String> {
+        let mut current = self.lock()?;
+        *self.lock_archived()? = archived;
+        *current = tasks;
+        Ok(())
+    }
+
+    /// Moves tasks completed before `completed_before` (Unix time) into the
+    /// archive. Returns the tasks moved. Nothing changes in memory unless
+    /// both files were written.
+    pub fn archive_completed(
+        &self,
+        storage: &StorageService,
+        completed_before: u64,
+    ) -> Result<Vec<Task>, String> {
+        let mut tasks = self.lock()?;
+        let mut archived = self.lock_archived()?;
+        let (mut moved, kept): (Vec<Task>, Vec<Task>) = tasks.iter().cloned().partition(|task| {
+            task.completed && task.completed_at.is_some_and(|at| at < completed_before)
+        });
+        if moved.is_empty() {
+            return Ok(moved);
+        }
+        let now = unix_now();
+        for task in &mut moved {
+            task.archived_at = Some(now);
+            task.revision += 1;
+        }
+        let archive: Vec<Task> = archived.iter().chain(&moved).cloned().collect();
+        // The archive is written first so a failure in between leaves a
+        // task in both files rather than in neither; loading the archive
+        // drops the extra copy.
+        storage.save_archived_tasks(&archive)?;
+        storage.save_tasks(&kept)?;
+        *archived = archive;
+        *tasks = kept;
+        Ok(moved)
+    }
+
+    /// Moves the archived task with `id` back into the task list.
+    pub fn unarchive(&self, storage: &StorageService, id: &str) -> Result<Task, String> {
+        let mut tasks = self.lock()?;
+        let mut archived = self.lock_archived()?;
+        let index = archived
+            .iter()
+            .position(|task| task.id == id)
+            .ok_or_else(|| format!("Archived task not found: {id}"))?;
+        let mut task = archived[index].clone();
+        task.archived_at = None;
+        task.revision += 1;
+        let list: Vec<Task> = tasks.iter().chain([&task]).cloned().collect();
+        let mut archive = archived.clone();
+        archive.remove(index);
+        // The task list is written first, so it has the task back even if
+        // the archive write fails.
+        storage.save_tasks(&list)?;
+        storage.save_archived_tasks(&archive)?;
+        *tasks = list;
+        *archived = archive;
+        Ok(task)
+    }
+
+    pub fn create(
+        &self,
+        storage: &StorageService,
+        title: String,
+        estimated_pomodoros: u32,
+    ) -> Result<Task, String> {
+        let mut tasks = self.lock()?;
+        let task = Task {
+            id: uuid::Uuid::new_v4().to_string(),
+            title,
+            estimated_pomodoros,
+            completed_pomodoros: 0,
+            completed: false,
+            created_at: unix_now(),
+            completed_at: None,
+            archived_at: None,
+            revision: 0,
+        };
+        let list: Vec<Task> = tasks.iter().chain([&task]).cloned().collect();
+        storage.save_tasks(&list)?;
+        *tasks = list;
+        Ok(task)
+    }
+
+    /// Runs `op` on a copy of the task with `id` if `expected_revision` is
+    /// current, and keeps it once the list is persisted. Returns the task
+    /// before and after.
+    fn mutate<F>(
+        &self,
+        storage: &StorageService,
+        id: &str,
+        expected_revision: Option<u64>,
+        op: F,
+    ) -> Result<(Task, Task), MutationError>
+    where
+        F: FnOnce(&mut Task),
+    {
+        let mut tasks = self.lock()?;
+        let index = tasks
+            .iter()
+            .position(|task| task.id == id)
+            .ok_or_else(|| format!("Task not found: {id}"))?;
+        check_revision(
+            &format!("task {id}"),
+            expected_revision,
+            tasks[index].revision,
+        )?;
+
+        let mut list = tasks.clone();
+        let task = &mut list[index];
+        op(task);
+        task.revision += 1;
+        let updated = task.clone();
+        storage.save_tasks(&list)?;
+        let previous = tasks[index].clone();
+        *tasks = list;
+        Ok((previous, updated))
+    }
+
+    pub fn update(
+        &self,
+        storage: &StorageService,
+        id: &str,
+        title: String,
+        estimated_pomodoros: u32,
+        expected_revision: Option<u64>,
+    ) -> Result<Task, MutationError> {
+        self.mutate(storage, id, expected_revision, |task| {
+            task.title = title;
+            task.estimated_pomodoros = estimated_pomodoros;
+        })
+        .map(|(_, updated)| updated)
+    }
+
+    /// Marks the task done. Returns it before and after, for undo history.
+    pub fn complete(
+        &self,
+        storage: &StorageService,
+        id: &str,
+        expected_revision: Option<u64>,
+    ) -> Result<(Task, Task), MutationError> {
+        self.mutate(storage, id, expected_revision, |task| {
+            task.completed = true;
+            task.completed_at = Some(unix_now());
+        })
+    }
+
+    /// Overwrites the stored task with `snapshot` if it is still at
+    /// `expected_revision`, used to roll back or replay history. The
+    /// revision still moves forward so other windows notice the change.
+    pub fn restore(
+        &self,
+        storage: &StorageService,
+        mut snapshot: Task,
+        expected_revision: u64,
+    ) -> Result<Task, MutationError> {
+        let mut tasks = self.lock()?;
+        let index = tasks
+            .iter()
+            .position(|task| task.id == snapshot.id)
+            .ok_or_else(|| format!("Task not found: {}", snapshot.id))?;
+        check_revision(
+            &format!("task {}", snapshot.id),
+            Some(expected_revision),
+            tasks[index].revision,
+        )?;
+
+        snapshot.revision = tasks[index].revision + 1;
+        let mut list = tasks.clone();
+        list[index] = snapshot.clone();
+        storage.save_tasks(&list)?;
+        *tasks = list;
+        Ok(snapshot)
+    }
+
+    pub fn delete(
+        &self,
+        storage: &StorageService,
+        id: &str,
+        expected_revision: Option<u64>,
+    ) -> Result<Task, MutationError> {
+        let mut tasks = self.lock()?;
+        let index = tasks
+            .iter()
+            .position(|task| task.id == id)
+            .ok_or_else(|| format!("Task not found: {id}"))?;
+        check_revision(
+            &format!("task {id}"),
+            expected_revision,
+            tasks[index].revision,
+        )?;
+
+        let mut list = tasks.clone();
+        let removed = list.remove(index);
+        storage.save_tasks(&list)?;
+        *tasks = list;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{StorageServiceBuilder, TestStorage};
+    use std::fs;
+
+    fn storage() -> TestStorage {
+        StorageServiceBuilder::new().build().unwrap()
+    }
+
+    fn task(id: &str, completed_at: Option<u64>) -> Task {
+        Task {
+            id: id.to_string(),
+            title: id.to_string(),
+            estimated_pomodoros: 1,
+            completed_pomodoros: 0,
+            completed: completed_at.is_some(),
+            created_at: 0,
+            completed_at,
+            archived_at: None,
+            revision: 0,
+        }
+    }
+
+    fn ids(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.id.as_str()).collect()
+    }
+
+    #[test]
+    fn completed_tasks_move_to_the_archive_and_back() {
+        let storage = storage();
+        let manager = TaskManager::new(
+            vec![
+                task("old", Some(100)),
+                task("recent", Some(300)),
+                task("open", None),
+            ],
+            Vec::new(),
+        );
+
+        let moved = manager.archive_completed(&storage, 200).unwrap();
+        assert_eq!(ids(&moved), ["old"]);
+        assert!(moved[0].archived_at.is_some());
+        assert_eq!(ids(&manager.list().unwrap()), ["recent", "open"]);
+        assert_eq!(ids(&storage.load_tasks().unwrap()), ["recent", "open"]);
+        assert_eq!(ids(&storage.load_archived_tasks().unwrap()), ["old"]);
+
+        let restored = manager.unarchive(&storage, "old").unwrap();
+        assert_eq!(restored.archived_at, None);
+        assert_eq!(ids(&manager.list().unwrap()), ["recent", "open", "old"]);
+        assert!(manager.list_archived().unwrap().is_empty());
+        assert_eq!(
+            ids(&storage.load_tasks().unwrap()),
+            ["recent", "open", "old"]
+        );
+        assert!(storage.load_archived_tasks().unwrap().is_empty());
+        assert!(manager.unarchive(&storage, "old").is_err());
+    }
+
+    #[test]
+    fn a_failed_archive_write_changes_nothing() {
+        let storage = storage();
+        let manager = TaskManager::new(vec![task("old", Some(100))], Vec::new());
+        // A directory in its place makes the archive unwritable.
+        fs::create_dir_all(storage.path().join("tasks-archive.json")).unwrap();
+
+        assert!(manager.archive_completed(&storage, 200).is_err());
+        assert_eq!(ids(&manager.list().unwrap()), ["old"]);
+        assert!(manager.list_archived().unwrap().is_empty());
+    }
+
+    #[test]
+    fn failed_task_writes_leave_the_list_as_it_was() {
+        let storage = storage();
+        let manager = TaskManager::new(vec![task("open", None)], Vec::new());
+        // A directory in its place makes the task list unwritable.
+        fs::create_dir_all(storage.path().join("tasks.json")).unwrap();
+
+        assert!(manager.create(&storage, "new".to_string(), 1).is_err());
+        assert!(manager.complete(&storage, "open", None).is_err());
+        assert!(manager.delete(&storage, "open", None).is_err());
+        let tasks = manager.list().unwrap();
+        assert_eq!(ids(&tasks), ["open"]);
+        assert!(!tasks[0].completed);
+        assert_eq!(tasks[0].revision, 0);
+    }
+
+    #[test]
+    fn tasks_left_in_both_files_load_once() {
+        let storage = storage();
+        storage.save_tasks(&[task("both", Some(100))]).unwrap();
+        storage
+            .save_archived_tasks(&[
+                task("both", Some(100)),
+                task("twice", Some(100)),
+                task("twice", Some(100)),
+            ])
+            .unwrap();
+
+        assert_eq!(ids(&storage.load_archived_tasks().unwrap()), ["twice"]);
+    }
+}
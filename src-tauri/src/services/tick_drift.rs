@@ -0,0 +1,143 @@
+//! Tick scheduling and drift measurement for the tick loop.
+//!
+//! Each tick is scheduled for just after the running session's next whole
+//! second, computed from the engine clock rather than from the previous
+//! tick, so a tick delayed by CPU load doesn't push the ones after it back
+//! and the error never accumulates.
+
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+use ts_rs::TS;
+
+/// Tick period while no session is running.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// Margin after a second boundary, so the tick sees the new second.
+pub const TICK_SLACK: Duration = Duration::from_millis(5);
+
+/// How long to sleep before the next tick, given how far the running
+/// session is from its next whole second (`None` when nothing is running).
+pub fn next_tick_delay(until_next_second: Option<Duration>) -> Duration {
+    until_next_second.map_or(TICK_INTERVAL, |until| until + TICK_SLACK)
+}
+
+/// How far `elapsed` is from its next whole second.
+pub fn until_next_second(elapsed: Duration) -> Duration {
+    Duration::from_secs(1) - Duration::from_nanos(elapsed.subsec_nanos().into())
+}
+
+/// Tick timing since launch.
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+pub struct TickDrift {
+    pub ticks: u64,
+    /// How late the latest tick woke after its deadline, in milliseconds.
+    pub last_lateness_ms: u64,
+    pub max_lateness_ms: u64,
+    pub mean_lateness_ms: f64,
+    /// Monotonic minus wall clock progress since the first tick, in
+    /// milliseconds. Negative after a system sleep the monotonic clock
+    /// didn't count, or when the wall clock was set forward.
+    pub clock_drift_ms: i64,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    drift: TickDrift,
+    total_lateness_ms: u64,
+    first_tick: Option<(Instant, SystemTime)>,
+}
+
+/// Collects [`TickDrift`] from the tick loop.
+#[derive(Default)]
+pub struct TickMonitor {
+    state: Mutex<MonitorState>,
+}
+
+impl TickMonitor {
+    fn lock(&self) -> Result<MutexGuard<'_, MonitorState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Tick monitor lock poisoned: {e}"))
+    }
+
+    /// Records a tick scheduled for `deadline` that woke at `woke`.
+    pub fn record(&self, deadline: Instant, woke: Instant) -> Result<(), String> {
+        let mut state = self.lock()?;
+        let wall = SystemTime::now();
+        let (first_instant, first_wall) = *state.first_tick.get_or_insert((woke, wall));
+        let monotonic = woke.duration_since(first_instant).as_millis() as i64;
+        let wall = wall.duration_since(first_wall).map_or_else(
+            |e| -(e.duration().as_millis() as i64),
+            |d| d.as_millis() as i64,
+        );
+
+        let lateness_ms = woke.saturating_duration_since(deadline).as_millis() as u64;
+        state.total_lateness_ms += lateness_ms;
+        let total_lateness_ms = state.total_lateness_ms;
+        let drift = &mut state.drift;
+        drift.ticks += 1;
+        drift.last_lateness_ms = lateness_ms;
+        drift.max_lateness_ms = drift.max_lateness_ms.max(lateness_ms);
+        drift.mean_lateness_ms = total_lateness_ms as f64 / drift.ticks as f64;
+        drift.clock_drift_ms = monotonic - wall;
+        Ok(())
+    }
+
+    pub fn drift(&self) -> Result<TickDrift, String> {
+        Ok(self.lock()?.drift.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    /// Runs the tick schedule over a session of `length` starting at zero,
+    /// waking each tick late by the next of `delays`. Returns every wake
+    /// time; the last one is the tick that sees the session end.
+    fn simulate(length: Duration, delays: &[Duration]) -> Vec<Duration> {
+        let mut now = Duration::ZERO;
+        let mut wakes = Vec::new();
+        for delay in delays.iter().cycle() {
+            now += next_tick_delay(Some(until_next_second(now))) + *delay;
+            wakes.push(now);
+            if now >= length {
+                break;
+            }
+        }
+        wakes
+    }
+
+    #[test]
+    fn ticks_land_just_after_each_second() {
+        let wakes = simulate(Duration::from_secs(5), &[Duration::ZERO]);
+        let expected: Vec<_> = (1..=5)
+            .map(|second| Duration::from_secs(second) + TICK_SLACK)
+            .collect();
+        assert_eq!(wakes, expected);
+    }
+
+    proptest! {
+        #[test]
+        fn delayed_ticks_do_not_accumulate_error(
+            delays in prop::collection::vec(0u64..=900, 1..200),
+        ) {
+            let delays: Vec<_> = delays.into_iter().map(Duration::from_millis).collect();
+            let max_delay = delays.iter().copied().max().unwrap_or_default();
+            let wakes = simulate(HOUR, &delays);
+
+            // Each tick is at most one load delay behind its second.
+            for wake in &wakes {
+                let second = Duration::from_secs(wake.saturating_sub(TICK_SLACK).as_secs());
+                prop_assert!(*wake - second <= TICK_SLACK + max_delay);
+            }
+            // The end of an hour-long session is seen within a second.
+            let detected = *wakes.last().unwrap();
+            prop_assert!(detected - HOUR < Duration::from_secs(1));
+        }
+    }
+}
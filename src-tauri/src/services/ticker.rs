@@ -0,0 +1,224 @@
+//! The once-a-second loop that drives timer completion and fans each tick
+//! out to the services that react to it.
+
+use serde::Serialize;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::artifacts::{self, ArtifactWatcher};
+use super::audio::{AlarmStageKind, AudioService, PLAY_EVENT};
+use super::battery;
+use super::break_reminder;
+use super::context_detection::ContextDetector;
+use super::distraction_blocker::DistractionBlocker;
+use super::door_status::DoorStatusWriter;
+use super::fatigue;
+use super::focus_budget;
+use super::focus_mode::FocusModeService;
+use super::hooks;
+use super::notifications;
+use super::power;
+use super::presenter_mode::{self, PresenterMonitor};
+use super::session_recorder;
+use super::session_types::counts_as_pomodoro;
+use super::slack_status::SlackStatusService;
+use super::storage::StorageService;
+use super::tick_drift::{self, TickMonitor};
+use super::timer_state::{Tick, TimerData, TimerManager};
+use super::window_activity::WindowTracker;
+
+/// Emitted every second while a session is running, with `TimerData`.
+pub const TICK_EVENT: &str = "timer://tick";
+/// Emitted with the next session's `TimerData` when a session runs out.
+pub const COMPLETED_EVENT: &str = "timer://completed";
+/// Emitted with `TimerData` when a session runs out and goes into overtime.
+pub const OVERTIME_EVENT: &str = "timer://overtime";
+
+fn emit<T: Serialize + Clone>(app: &AppHandle, event: &str, payload: &T) -> Result<(), String> {
+    app.emit(event, payload.clone())
+        .map_err(|e| format!("Failed to emit {event}: {e}"))
+}
+
+/// Spawns the loop that drives completion once a second and emits
+/// [`TICK_EVENT`], [`COMPLETED_EVENT`] and [`OVERTIME_EVENT`], so the UI
+/// and tray can subscribe instead of polling `check_timer_completion`.
+/// Ticks are scheduled from the engine clock, so a late one doesn't delay
+/// the rest. The services [`dispatch_tick`] feeds and the notification
+/// plugin must already be registered on `app`.
+pub fn spawn_ticker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let until_next_second = app
+                .state::<TimerManager>()
+                .until_next_second()
+                .unwrap_or_else(|error| {
+                    log::error!("{error}");
+                    None
+                });
+            let deadline = Instant::now() + tick_drift::next_tick_delay(until_next_second);
+            tokio::time::sleep_until(deadline.into()).await;
+            if let Err(error) = app.state::<TickMonitor>().record(deadline, Instant::now()) {
+                log::error!("{error}");
+            }
+            if let Err(error) = power::check(&app) {
+                log::error!("{error}");
+            }
+            if let Err(error) = dispatch_tick(&app) {
+                log::error!("Timer tick failed: {error}");
+            }
+        }
+    });
+}
+
+/// Copies tracked files off the tick loop, since they may be large.
+fn capture_artifacts(app: &AppHandle, tick: &Tick) -> Result<(), String> {
+    let Some((boundary, session_type)) = app.state::<ArtifactWatcher>().boundary(tick)? else {
+        return Ok(());
+    };
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let storage = app.state::<StorageService>();
+        if let Err(error) = artifacts::capture(&storage, boundary, &session_type) {
+            log::error!("Artifact capture failed: {error}");
+        }
+    });
+    Ok(())
+}
+
+/// Starts `next` if `auto_start_work` or `auto_start_breaks` asks for it,
+/// unless a work session would go over the focus budget or a long one
+/// would run on low battery.
+fn auto_start(app: &AppHandle, next: &TimerData) -> Result<(), String> {
+    let storage = app.state::<StorageService>();
+    let preferences = storage.load_preferences()?;
+    let is_work = counts_as_pomodoro(&next.session_type, &next.config.custom_session_types);
+    let wanted = if is_work {
+        preferences.auto_start_work
+    } else {
+        preferences.auto_start_breaks
+    };
+    if !wanted
+        || (is_work && !focus_budget::allows_auto_start(app, &storage)?)
+        || !battery::allows_auto_start(app, next)?
+    {
+        return Ok(());
+    }
+    app.state::<TimerManager>()
+        .start(None)
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// Records sessions the timer ended since the last call in the day's
+/// statistics, whether they ran out or were completed or skipped by a
+/// command. Sessions that fail to record are kept for the next call. Also
+/// called on exit so the last one isn't lost.
+pub fn record_sessions(app: &AppHandle) -> Result<(), String> {
+    session_recorder::record_taken(&app.state::<TimerManager>(), |sessions| {
+        session_recorder::record(app, &app.state::<StorageService>(), sessions)
+    })
+}
+
+/// Advances the timer and passes the tick to every service that follows
+/// it: transitions go to the event log, fatigue checks and hooks; ended
+/// sessions to the statistics; the data to the context detector, window
+/// tracker, battery and presenter checks, break reminders, tray, Do Not
+/// Disturb, Slack status, blocklist, door status and artifact capture.
+/// Completions notify and may auto-start the next session, and alarm
+/// stages play unless presenter mode holds sounds back. One service
+/// failing is logged without stopping the rest; services that shell out
+/// or write elsewhere do so off this task.
+fn dispatch_tick(app: &AppHandle) -> Result<(), String> {
+    let audio = app.state::<AudioService>();
+    let timer = app.state::<TimerManager>();
+    let tick = timer.tick()?;
+    let events = timer.take_events()?;
+    if !events.is_empty() {
+        if let Err(error) = app.state::<StorageService>().append_events(&events) {
+            log::error!("{error}");
+        }
+        if let Err(error) = fatigue::check(app, &events) {
+            log::error!("{error}");
+        }
+        if let Err(error) = hooks::fire(app, &events, &tick.data().config.custom_session_types) {
+            log::error!("{error}");
+        }
+    }
+    if let Err(error) = app.state::<ContextDetector>().on_tick(app) {
+        log::error!("{error}");
+    }
+    if let Err(error) = record_sessions(app) {
+        log::error!("Recording sessions failed: {error}");
+    }
+    if let Err(error) = app.state::<WindowTracker>().on_tick(app, tick.data()) {
+        log::error!("{error}");
+    }
+    if let Err(error) = battery::check(app, &events, tick.data()) {
+        log::error!("{error}");
+    }
+    if let Err(error) = presenter_mode::check(app) {
+        log::error!("{error}");
+    }
+    if let Err(error) = break_reminder::check(app, tick.data()) {
+        log::error!("{error}");
+    }
+    #[cfg(desktop)]
+    if let Err(error) = super::tray::update_tray(app, tick.data()) {
+        log::error!("{error}");
+    }
+    if let Err(error) = app.state::<FocusModeService>().on_tick(tick.data()) {
+        log::error!("{error}");
+    }
+    if let Err(error) = app.state::<SlackStatusService>().on_tick(app, tick.data()) {
+        log::error!("{error}");
+    }
+    if let Err(error) = app.state::<DistractionBlocker>().on_tick(app, tick.data()) {
+        log::error!("{error}");
+    }
+    if let Err(error) = app.state::<DoorStatusWriter>().on_tick(app, tick.data()) {
+        log::error!("{error}");
+    }
+    if let Err(error) = capture_artifacts(app, &tick) {
+        log::error!("{error}");
+    }
+    let fired = match &tick {
+        Tick::Waiting(data) => audio.on_tick(data)?,
+        Tick::Running(data) => {
+            emit(app, TICK_EVENT, data)?;
+            audio.on_tick(data)?
+        }
+        Tick::Completed { finished, next } => {
+            emit(app, COMPLETED_EVENT, next)?;
+            if let Err(error) = notifications::notify_completed(app, next) {
+                log::error!("{error}");
+            }
+            if let Err(error) = auto_start(app, next) {
+                log::error!("{error}");
+            }
+            audio.on_completed(finished.clone(), next)?
+        }
+        Tick::Overran(data) => {
+            emit(app, OVERTIME_EVENT, data)?;
+            if let Err(error) = notifications::notify_overtime(app, data) {
+                log::error!("{error}");
+            }
+            audio.on_completed(data.session_type.clone(), data)?
+        }
+    };
+    let Some(fired) = fired else {
+        return Ok(());
+    };
+    // Completions were announced by `notify_completed` above.
+    if fired.notify && fired.stage != AlarmStageKind::Alarm {
+        if let Err(error) =
+            notifications::notify_reminder(app, fired.stage, &fired.session_type, tick.data())
+        {
+            log::error!("{error}");
+        }
+    }
+    let presenting = app.state::<PresenterMonitor>().status()?.sounds_suppressed;
+    match fired.cue {
+        Some(cue) if !presenting => emit(app, PLAY_EVENT, &cue),
+        _ => Ok(()),
+    }
+}
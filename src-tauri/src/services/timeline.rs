@@ -0,0 +1,212 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::break_detection::BreakDetection;
+use super::calendar::{day_key, CalendarSettings};
+use super::energy::EnergyPlan;
+use super::storage::TimerStatistic;
+use super::timer_state::SessionType;
+
+/// Gaps between sessions shorter than this aren't worth showing.
+const MIN_IDLE_GAP_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineKind {
+    Planned,
+    Session,
+    Idle,
+    /// An idle gap long enough to count as a long break; see
+    /// [`BreakDetection`].
+    ImplicitBreak,
+    /// A session that ended without completing; zero-length, at its end.
+    Interruption,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TimelineEntry {
+    pub kind: TimelineKind,
+    /// Unix seconds.
+    pub start: u64,
+    pub end: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_type: Option<SessionType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+}
+
+impl TimelineEntry {
+    fn span(kind: TimelineKind, start: u64, end: u64) -> Self {
+        Self {
+            kind,
+            start,
+            end,
+            session_type: None,
+            completed: None,
+            task_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DayTimeline {
+    pub date: String,
+    /// Ordered by start time; planned blocks come before actual entries
+    /// starting at the same moment.
+    pub entries: Vec<TimelineEntry>,
+}
+
+/// Unix time of the local wall-clock time `minute` minutes after midnight on
+/// `date`, so blocks keep their clock times on DST days. A time repeated when
+/// the clocks go back is its first occurrence; one skipped when they go
+/// forward is where the clocks land an hour later.
+pub(super) fn local_unix(date: NaiveDate, minute: u32) -> Option<u64> {
+    CalendarSettings::default().unix_of(wall_time(date, minute))
+}
+
+fn wall_time(date: NaiveDate, minute: u32) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN) + TimeDelta::minutes(minute.into())
+}
+
+/// The planned blocks falling within the statistics day `date`. The day
+/// runs past midnight up to the calendar's boundary hour, so blocks are
+/// laid on both wall-clock days it covers and cut to its bounds.
+fn planned_entries(
+    date: NaiveDate,
+    plan: &EnergyPlan,
+    calendar: &CalendarSettings,
+) -> Vec<TimelineEntry> {
+    let next = date.succ_opt();
+    let Some((day_start, day_end)) = calendar
+        .start_of(date)
+        .zip(next.and_then(|next| calendar.start_of(next)))
+    else {
+        return Vec::new();
+    };
+    let wall_unix = |wall_date, minute| calendar.unix_of(wall_time(wall_date, minute));
+    [Some(date), next]
+        .into_iter()
+        .flatten()
+        .flat_map(|wall_date| plan.blocks.iter().map(move |block| (wall_date, block)))
+        .filter_map(|(wall_date, block)| {
+            let start = wall_unix(wall_date, block.start_minute)?.max(day_start);
+            let end = wall_unix(wall_date, block.end_minute)?.min(day_end);
+            (start < end).then(|| TimelineEntry {
+                session_type: Some(block.session_type.clone()),
+                ..TimelineEntry::span(TimelineKind::Planned, start, end)
+            })
+        })
+        .collect()
+}
+
+/// Lays the day's recorded sessions against its planned blocks.
+pub fn build_day_timeline(
+    date: NaiveDate,
+    plan: &EnergyPlan,
+    statistic: Option<&TimerStatistic>,
+    breaks: &BreakDetection,
+    calendar: &CalendarSettings,
+) -> DayTimeline {
+    let mut entries = planned_entries(date, plan, calendar);
+
+    let mut sessions: Vec<_> = statistic
+        .map(|statistic| statistic.sessions.iter().collect())
+        .unwrap_or_default();
+    sessions.sort_by_key(|session| session.start_time);
+
+    let mut previous_end: Option<u64> = None;
+    for session in sessions {
+        let start = session.start_time;
+        let end = session.span_end();
+        if let Some(previous_end) = previous_end {
+            if breaks.is_break(date, previous_end, start) {
+                entries.push(TimelineEntry {
+                    session_type: Some(SessionType::LongBreak),
+                    ..TimelineEntry::span(TimelineKind::ImplicitBreak, previous_end, start)
+                });
+            } else if start >= previous_end + MIN_IDLE_GAP_SECS {
+                entries.push(TimelineEntry::span(TimelineKind::Idle, previous_end, start));
+            }
+        }
+        previous_end = Some(previous_end.map_or(end, |previous| previous.max(end)));
+
+        entries.push(TimelineEntry {
+            session_type: Some(session.session_type.clone()),
+            completed: Some(session.completed),
+            task_id: session.task_id.clone(),
+            ..TimelineEntry::span(TimelineKind::Session, start, end)
+        });
+        if !session.completed {
+            entries.push(TimelineEntry {
+                session_type: Some(session.session_type.clone()),
+                ..TimelineEntry::span(TimelineKind::Interruption, end, end)
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| (entry.start, entry.kind != TimelineKind::Planned));
+    DayTimeline {
+        date: day_key(date),
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::energy::{EnergyLevel, PlanBlock};
+
+    fn block(start_hour: u32, end_hour: u32) -> PlanBlock {
+        PlanBlock {
+            start: format!("{start_hour:02}:00"),
+            end: format!("{end_hour:02}:00"),
+            start_minute: start_hour * 60,
+            end_minute: end_hour * 60,
+            energy: EnergyLevel::High,
+            session_type: SessionType::Work,
+            sessions: 1,
+        }
+    }
+
+    #[test]
+    fn planned_blocks_follow_the_calendar_day_past_midnight() {
+        let calendar = CalendarSettings {
+            day_start_hour: 4,
+            utc_offset_minutes: Some(0),
+        };
+        let date = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let plan = EnergyPlan {
+            date: "2025-03-10".to_string(),
+            blocks: vec![block(0, 1), block(3, 6), block(9, 10)],
+        };
+        let hour = |day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2025, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .and_utc()
+                .timestamp() as u64
+        };
+        let spans: Vec<_> = planned_entries(date, &plan, &calendar)
+            .iter()
+            .map(|entry| (entry.start, entry.end))
+            .collect();
+        // Midnight to 4am on the 10th belongs to the 9th; the same hours on
+        // the 11th belong here.
+        assert_eq!(
+            spans,
+            vec![
+                (hour(10, 4), hour(10, 6)),
+                (hour(10, 9), hour(10, 10)),
+                (hour(11, 0), hour(11, 1)),
+                (hour(11, 3), hour(11, 4)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use thiserror::Error;
+
+use super::timer_state::TimerState;
+
+/// Why a [`TimerManager`](super::timer_state::TimerManager) call failed.
+/// Commands report [`TimerError::code`] so the frontend can branch on it
+/// instead of parsing the message.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TimerError {
+    #[error("Cannot {operation} while the timer is {state}")]
+    InvalidTransition {
+        operation: &'static str,
+        state: TimerState,
+    },
+    #[error("Timer is not running")]
+    NotRunning,
+    #[error("Timer is not paused")]
+    NotPaused,
+    #[error("Unknown session type: {0}")]
+    UnknownSessionType(String),
+    #[error("Invalid simulation speed: {0}")]
+    InvalidSpeed(f64),
+    #[error("Timer state lock poisoned: {0}")]
+    LockPoisoned(String),
+}
+
+impl TimerError {
+    /// Stable, snake_case identifier of the variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TimerError::InvalidTransition { .. } => "invalid_transition",
+            TimerError::NotRunning => "not_running",
+            TimerError::NotPaused => "not_paused",
+            TimerError::UnknownSessionType(_) => "unknown_session_type",
+            TimerError::InvalidSpeed(_) => "invalid_speed",
+            TimerError::LockPoisoned(_) => "lock_poisoned",
+        }
+    }
+}
+
+impl From<TimerError> for String {
+    fn from(error: TimerError) -> Self {
+        error.to_string()
+    }
+}
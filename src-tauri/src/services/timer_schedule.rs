@@ -0,0 +1,162 @@
+//! Weekday and time-of-day windows with their own timer configuration, e.g.
+//! shorter sessions in the evening. The timer consults the schedule when a
+//! session starts.
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::timer_state::TimerConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(weekday: chrono::Weekday) -> Self {
+        match weekday {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScheduleRule {
+    pub name: String,
+    /// Days the window opens on; empty means every day.
+    pub weekdays: Vec<Weekday>,
+    /// Minutes after local midnight the window opens.
+    pub start_minute: u32,
+    /// Minutes after local midnight the window closes. An earlier time
+    /// wraps past midnight; the same time covers the whole day.
+    pub end_minute: u32,
+    pub config: TimerConfig,
+}
+
+impl ScheduleRule {
+    fn opens_on(&self, weekday: chrono::Weekday) -> bool {
+        self.weekdays.is_empty() || self.weekdays.contains(&weekday.into())
+    }
+
+    fn covers(&self, at: NaiveDateTime) -> bool {
+        let minute = at.hour() * 60 + at.minute();
+        let today = at.weekday();
+        if self.start_minute == self.end_minute {
+            return self.opens_on(today);
+        }
+        if self.start_minute < self.end_minute {
+            return self.opens_on(today) && (self.start_minute..self.end_minute).contains(&minute);
+        }
+        // A window past midnight belongs to the day it opened on.
+        (minute >= self.start_minute && self.opens_on(today))
+            || (minute < self.end_minute && self.opens_on(today.pred()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct TimerSchedule {
+    pub enabled: bool,
+    /// Checked in order; the first window covering the start time wins.
+    /// Outside every window the regular `timer_config` applies.
+    pub rules: Vec<ScheduleRule>,
+}
+
+impl TimerSchedule {
+    /// The configuration for a session starting at local time `at`, if a
+    /// window covers it.
+    pub fn config_at(&self, at: NaiveDateTime) -> Option<&TimerConfig> {
+        if !self.enabled {
+            return None;
+        }
+        self.rules
+            .iter()
+            .find(|rule| rule.covers(at))
+            .map(|rule| &rule.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn rule(weekdays: Vec<Weekday>, start_minute: u32, end_minute: u32) -> ScheduleRule {
+        ScheduleRule {
+            name: "Evening".to_string(),
+            weekdays,
+            start_minute,
+            end_minute,
+            config: TimerConfig::default(),
+        }
+    }
+
+    /// 2026-03-02 is a Monday.
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn same_day_windows_include_the_start_but_not_the_end() {
+        let evening = rule(Vec::new(), 18 * 60, 22 * 60);
+        assert!(!evening.covers(at(2, 17, 59)));
+        assert!(evening.covers(at(2, 18, 0)));
+        assert!(evening.covers(at(2, 21, 59)));
+        assert!(!evening.covers(at(2, 22, 0)));
+    }
+
+    #[test]
+    fn windows_past_midnight_belong_to_the_day_they_open_on() {
+        let friday_night = rule(vec![Weekday::Friday], 22 * 60, 2 * 60);
+        assert!(friday_night.covers(at(6, 23, 0)));
+        assert!(friday_night.covers(at(7, 1, 30)));
+        assert!(!friday_night.covers(at(7, 23, 0)));
+        assert!(!friday_night.covers(at(6, 1, 30)));
+    }
+
+    #[test]
+    fn equal_start_and_end_cover_the_whole_day() {
+        let weekend = rule(vec![Weekday::Saturday, Weekday::Sunday], 9 * 60, 9 * 60);
+        assert!(weekend.covers(at(7, 0, 0)));
+        assert!(weekend.covers(at(8, 23, 59)));
+        assert!(!weekend.covers(at(9, 12, 0)));
+    }
+
+    #[test]
+    fn the_first_covering_rule_wins_while_enabled() {
+        let mut short = rule(Vec::new(), 0, 0);
+        short.config.work_duration = 15 * 60;
+        let mut schedule = TimerSchedule {
+            enabled: false,
+            rules: vec![short, rule(Vec::new(), 0, 0)],
+        };
+        assert_eq!(schedule.config_at(at(2, 12, 0)), None);
+        schedule.enabled = true;
+        assert_eq!(
+            schedule
+                .config_at(at(2, 12, 0))
+                .map(|config| config.work_duration),
+            Some(15 * 60)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! The engine's own bookkeeping: snapshots, history, the event log and
+//! moving between sessions.
+
+use chrono::{DateTime, Local};
+
+use super::{Due, SessionType, TimerConfig, TimerData, TimerInner, TimerSession, TimerState};
+use crate::services::cycle::{self, CycleInfo};
+use crate::services::event_log::{TimerEvent, TimerEventKind};
+use crate::services::progress_stream::TimerProgress;
+use crate::services::session_types::counts_as_pomodoro;
+use crate::services::state_history::{TimerOperation, TransitionStart};
+use crate::services::storage::SessionData;
+
+impl TimerInner {
+    pub(super) fn snapshot(&self) -> TimerData {
+        let now = self.clock.now();
+        let (total_time, remaining_time, progress) = if self.state == TimerState::Stopwatch {
+            let elapsed = self.session.elapsed(now);
+            (elapsed.as_secs(), 0, TimerProgress::counting_up(elapsed))
+        } else {
+            (
+                self.session.duration,
+                self.session.remaining_secs(now),
+                TimerProgress::new(self.session.duration, self.session.elapsed(now)),
+            )
+        };
+
+        TimerData {
+            state: self.state,
+            session_type: self.session.session_type.clone(),
+            remaining_time,
+            total_time,
+            progress: progress.progress,
+            elapsed_ms: progress.elapsed_ms,
+            remaining_ms: progress.remaining_ms,
+            overtime: match self.state {
+                TimerState::Overtime => self.session.overtime_secs(now),
+                _ => 0,
+            },
+            elapsed_time: self.session.elapsed_secs(now),
+            percent_complete: (progress.progress * 100.0).floor() as u32,
+            display_mode: self.display_mode,
+            completed_pomodoros: self.completed_pomodoros,
+            cycle: CycleInfo::new(
+                &self.session.session_type,
+                self.phase,
+                self.completed_pomodoros,
+                remaining_time,
+                &self.config,
+                self.clock.unix_now(),
+            ),
+            start_time: self.session.start_time,
+            last_session: self.last_session.clone(),
+            config: self.config.clone(),
+            revision: self.revision,
+        }
+    }
+
+    pub(super) fn begin_transition(&self) -> TransitionStart {
+        let now = self.clock.now();
+        TransitionStart {
+            clock_ms: now.as_millis() as u64,
+            elapsed_ms: self.session.elapsed(now).as_millis() as u64,
+            before: self.snapshot(),
+        }
+    }
+
+    /// Records the transition in the history and returns the new state.
+    pub(super) fn end_transition(
+        &mut self,
+        start: TransitionStart,
+        operation: TimerOperation,
+    ) -> TimerData {
+        let after = self.snapshot();
+        let timestamp = self.clock.unix_now();
+        self.history
+            .record(start, operation, timestamp, after.clone());
+        after
+    }
+
+    pub(super) fn log(&mut self, kind: TimerEventKind) {
+        self.events.push(TimerEvent {
+            kind,
+            session_type: self.session.session_type.clone(),
+            remaining_time: self.session.remaining_secs(self.clock.now()),
+            timestamp: self.clock.unix_now(),
+        });
+    }
+
+    /// Records the current session as finished now, if it was ever started.
+    /// A stopwatch session lasted as long as it counted, and only counts as
+    /// completed once that reaches the planned duration; time in overtime
+    /// is recorded apart from the planned duration.
+    pub(super) fn record_session(&mut self, completed: bool) {
+        let now = self.clock.now();
+        let planned = self.session.duration.saturating_sub(self.session.overtime);
+        let (duration, overtime) = match self.state {
+            TimerState::Stopwatch => (self.session.elapsed_secs(now), 0),
+            TimerState::Overtime => (
+                planned,
+                self.session.overtime + self.session.overtime_secs(now),
+            ),
+            _ => (planned, self.session.overtime),
+        };
+        let completed =
+            completed && (self.state != TimerState::Stopwatch || duration >= self.session.duration);
+        if let Some(start_time) = self.session.start_time {
+            let session = SessionData {
+                session_type: self.session.session_type.clone(),
+                duration,
+                completed,
+                start_time,
+                end_time: Some(self.clock.unix_now()),
+                task_id: None,
+                tags: self.experiment_tag.clone().into_iter().collect(),
+                issue: None,
+                context: None,
+                overtime,
+                extended: self.session.extended,
+                windows: Vec::new(),
+            };
+            self.finished.push(session.clone());
+            self.last_session = Some(session);
+        }
+    }
+
+    /// Switches to the configuration the running experiment's arm or else
+    /// the schedule gives for a session starting now, resizing the idle
+    /// session to match.
+    pub(super) fn apply_schedule(&mut self) {
+        let Some(now) = DateTime::from_timestamp(self.clock.unix_now() as i64, 0) else {
+            return;
+        };
+        let at = now.with_timezone(&Local).naive_local();
+        let day = self.calendar.day_of(self.clock.unix_now());
+        let arm = self
+            .experiment
+            .as_ref()
+            .and_then(|experiment| Some((experiment, experiment.arm_on(day)?)));
+        self.experiment_tag = arm.map(|(experiment, arm)| experiment.tag(arm));
+        let config = match arm {
+            Some((_, arm)) => &arm.config,
+            None => self.schedule.config_at(at).unwrap_or(&self.base_config),
+        };
+        if *config != self.config {
+            let config = config.clone();
+            self.set_config(config);
+            self.renew_session();
+        }
+    }
+
+    /// Switches to `config`. A changed sequence starts over from its first
+    /// phase, which an idle timer moves to.
+    pub(super) fn set_config(&mut self, config: TimerConfig) {
+        let restart = config.sequence != self.config.sequence;
+        self.config = config;
+        if restart {
+            self.phase = 0;
+            if let (TimerState::Idle, Some(first)) = (self.state, self.config.sequence.first()) {
+                self.session.session_type = first.session_type.clone();
+            }
+        }
+    }
+
+    /// A fresh session of `session_type`, as long as the current sequence
+    /// phase if it is of that type.
+    pub(super) fn new_session(&self, session_type: SessionType) -> TimerSession {
+        let mut session = TimerSession::new(session_type, &self.config);
+        if let Some(phase) = self.config.sequence.get(self.phase) {
+            if phase.session_type == session.session_type {
+                session.duration = phase.duration;
+            }
+        }
+        session
+    }
+
+    /// Replaces the session with an unstarted one of the same type.
+    pub(super) fn renew_session(&mut self) {
+        self.session = self.new_session(self.session.session_type.clone());
+    }
+
+    pub(super) fn advance(&mut self) {
+        let (next, phase) = cycle::next_session(
+            &self.session.session_type,
+            self.phase,
+            self.completed_pomodoros,
+            &self.config,
+        );
+        self.phase = phase;
+        self.session = self.new_session(next);
+        self.state = TimerState::Idle;
+    }
+
+    /// Counts the current session if its type counts as a pomodoro, then
+    /// moves on.
+    pub(super) fn finish(&mut self) {
+        self.log(TimerEventKind::Complete);
+        if counts_as_pomodoro(
+            &self.session.session_type,
+            &self.config.custom_session_types,
+        ) {
+            self.completed_pomodoros += 1;
+        }
+        self.record_session(true);
+        self.advance();
+    }
+
+    /// Completes the running session if its time is up, or with overtime
+    /// on, lets it run over.
+    pub(super) fn complete_if_due(&mut self) -> Option<Due> {
+        if self.state != TimerState::Running || self.session.remaining_secs(self.clock.now()) > 0 {
+            return None;
+        }
+        let start = self.begin_transition();
+        let (due, operation) = if self.config.overtime {
+            self.state = TimerState::Overtime;
+            (Due::Overran, TimerOperation::Overrun)
+        } else {
+            self.finish();
+            (Due::Completed, TimerOperation::Expire)
+        };
+        self.revision += 1;
+        self.end_transition(start, operation);
+        Some(due)
+    }
+}
@@ -0,0 +1,307 @@
+//! Building the engine, the locking every operation goes through, and
+//! what it answers without a transition of its own.
+
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+#[cfg(feature = "simulation")]
+use super::MAX_TIME_SCALE;
+use super::{
+    DisplayMode, Due, SessionType, Tick, TimerConfig, TimerData, TimerInner, TimerManager,
+    TimerSession, TimerState, UPDATE_CAPACITY,
+};
+use crate::services::calendar::CalendarSettings;
+use crate::services::clock::EngineClock;
+use crate::services::event_log::TimerEvent;
+use crate::services::progress_stream::TimerProgress;
+use crate::services::revision::{check_revision, MutationError};
+use crate::services::state_history::{StateHistory, StateTransition, TimerOperation};
+use crate::services::storage::{SessionData, UserPreferences};
+use crate::services::tick_drift;
+use crate::services::timer_error::TimerError;
+use crate::services::timer_schedule::TimerSchedule;
+
+impl TimerManager {
+    pub fn new(config: TimerConfig) -> Self {
+        let first = config
+            .sequence
+            .first()
+            .map_or(SessionType::Work, |phase| phase.session_type.clone());
+        let session = TimerSession::new(first, &config);
+        let mut inner = TimerInner {
+            state: TimerState::Idle,
+            base_config: config.clone(),
+            config,
+            schedule: TimerSchedule::default(),
+            experiment: None,
+            experiment_tag: None,
+            calendar: CalendarSettings::default(),
+            display_mode: DisplayMode::default(),
+            clock: EngineClock::default(),
+            session,
+            phase: 0,
+            completed_pomodoros: 0,
+            last_session: None,
+            finished: Vec::new(),
+            revision: 0,
+            events: Vec::new(),
+            history: StateHistory::default(),
+        };
+        inner.renew_session();
+        Self {
+            inner: Mutex::new(inner),
+            updates: broadcast::channel(UPDATE_CAPACITY).0,
+        }
+    }
+
+    /// An engine in the state `data` describes, with `elapsed` spent in the
+    /// session and its clock frozen at `clock` and Unix time `unix`, for
+    /// replaying history. The schedule, experiment and day boundary come
+    /// from `preferences`, like [`Self::configure`].
+    #[cfg(feature = "simulation")]
+    pub fn restore(
+        data: &TimerData,
+        elapsed: Duration,
+        clock: Duration,
+        unix: u64,
+        preferences: &UserPreferences,
+    ) -> Self {
+        let mut session = TimerSession::new(data.session_type.clone(), &data.config);
+        session.duration = data.total_time;
+        session.elapsed = elapsed;
+        session.start_time = data.start_time;
+        if matches!(
+            data.state,
+            TimerState::Running | TimerState::Stopwatch | TimerState::Overtime
+        ) {
+            session.started_at = Some(clock);
+        }
+        Self {
+            inner: Mutex::new(TimerInner {
+                state: data.state,
+                config: data.config.clone(),
+                base_config: preferences.timer_config.clone(),
+                schedule: preferences.timer_schedule.clone(),
+                experiment: preferences.experiment.clone(),
+                experiment_tag: None,
+                calendar: preferences.calendar,
+                display_mode: data.display_mode,
+                clock: EngineClock::frozen_at(clock, unix),
+                session,
+                phase: data.cycle.sequence_phase.unwrap_or(0) as usize,
+                completed_pomodoros: data.completed_pomodoros,
+                last_session: data.last_session.clone(),
+                finished: Vec::new(),
+                revision: data.revision,
+                events: Vec::new(),
+                history: StateHistory::default(),
+            }),
+            updates: broadcast::channel(UPDATE_CAPACITY).0,
+        }
+    }
+
+    pub(super) fn lock(&self) -> Result<MutexGuard<'_, TimerInner>, TimerError> {
+        self.inner
+            .lock()
+            .map_err(|e| TimerError::LockPoisoned(e.to_string()))
+    }
+
+    /// Applies `op` under the lock if `expected_revision` is current, bumping
+    /// the revision and recording `operation` in the history when it
+    /// succeeds.
+    pub(super) fn mutate<F>(
+        &self,
+        expected_revision: Option<u64>,
+        operation: TimerOperation,
+        op: F,
+    ) -> Result<TimerData, MutationError>
+    where
+        F: FnOnce(&mut TimerInner) -> Result<(), TimerError>,
+    {
+        let inner = self.lock()?;
+        check_revision("timer", expected_revision, inner.revision)?;
+        self.transition(inner, operation, op)
+    }
+
+    /// The part of [`Self::mutate`] after the revision check, for callers
+    /// that have more to do in between.
+    pub(super) fn transition<F>(
+        &self,
+        mut inner: MutexGuard<'_, TimerInner>,
+        operation: TimerOperation,
+        op: F,
+    ) -> Result<TimerData, MutationError>
+    where
+        F: FnOnce(&mut TimerInner) -> Result<(), TimerError>,
+    {
+        let start = inner.begin_transition();
+        op(&mut inner)?;
+        inner.revision += 1;
+        let data = inner.end_transition(start, operation);
+        drop(inner);
+        self.publish(&data);
+        Ok(data)
+    }
+
+    /// Snapshots sent on every transition and on each tick of a running
+    /// session.
+    pub fn subscribe(&self) -> broadcast::Receiver<TimerData> {
+        self.updates.subscribe()
+    }
+
+    pub(super) fn publish(&self, data: &TimerData) {
+        // Fails only when nobody is subscribed.
+        let _ = self.updates.send(data.clone());
+    }
+
+    pub fn get_data(&self) -> Result<TimerData, TimerError> {
+        Ok(self.lock()?.snapshot())
+    }
+
+    /// Completes the running session once its time is up, or lets it run
+    /// over with overtime on.
+    ///
+    /// Returns `true` if a session was completed by this call.
+    pub fn check_completion(&self) -> Result<bool, TimerError> {
+        Ok(matches!(
+            self.lock()?.complete_if_due(),
+            Some(Due::Completed)
+        ))
+    }
+
+    /// Millisecond progress of the running session or stopwatch, or `None`
+    /// when neither runs.
+    pub fn progress(&self) -> Result<Option<TimerProgress>, TimerError> {
+        let inner = self.lock()?;
+        let elapsed = inner.session.elapsed(inner.clock.now());
+        Ok(match inner.state {
+            TimerState::Running => Some(TimerProgress::new(inner.session.duration, elapsed)),
+            TimerState::Stopwatch => Some(TimerProgress::counting_up(elapsed)),
+            TimerState::Overtime => Some(TimerProgress::new(inner.session.duration, elapsed)),
+            TimerState::Idle | TimerState::Paused => None,
+        })
+    }
+
+    /// Engine time until the running session's remaining seconds, or a
+    /// stopwatch's or overtime's elapsed ones, next change, or `None` when
+    /// none runs.
+    pub fn until_next_second(&self) -> Result<Option<Duration>, TimerError> {
+        let inner = self.lock()?;
+        if !matches!(
+            inner.state,
+            TimerState::Running | TimerState::Stopwatch | TimerState::Overtime
+        ) {
+            return Ok(None);
+        }
+        let elapsed = inner.session.elapsed(inner.clock.now());
+        Ok(Some(tick_drift::until_next_second(elapsed)))
+    }
+
+    /// The newest `limit` transitions, oldest first.
+    pub fn history(&self, limit: usize) -> Result<Vec<StateTransition>, TimerError> {
+        Ok(self.lock()?.history.recent(limit))
+    }
+
+    /// Transitions since the last call, for the event log.
+    pub fn take_events(&self) -> Result<Vec<TimerEvent>, TimerError> {
+        Ok(std::mem::take(&mut self.lock()?.events))
+    }
+
+    /// Sessions ended since the last call, for the statistics.
+    pub fn take_sessions(&self) -> Result<Vec<SessionData>, TimerError> {
+        Ok(std::mem::take(&mut self.lock()?.finished))
+    }
+
+    /// Puts back sessions taken with [`Self::take_sessions`] that couldn't
+    /// be recorded, ahead of any finished since, so the next take retries
+    /// them.
+    pub fn return_sessions(&self, mut sessions: Vec<SessionData>) -> Result<(), TimerError> {
+        let mut inner = self.lock()?;
+        sessions.append(&mut inner.finished);
+        inner.finished = sessions;
+        Ok(())
+    }
+
+    pub fn tick(&self) -> Result<Tick, TimerError> {
+        let mut inner = self.lock()?;
+        let finished = inner.session.session_type.clone();
+        let tick = match inner.complete_if_due() {
+            Some(Due::Completed) => Tick::Completed {
+                finished,
+                next: inner.snapshot(),
+            },
+            Some(Due::Overran) => Tick::Overran(inner.snapshot()),
+            None => match inner.state {
+                TimerState::Running | TimerState::Stopwatch | TimerState::Overtime => {
+                    Tick::Running(inner.snapshot())
+                }
+                TimerState::Idle | TimerState::Paused => {
+                    return Ok(Tick::Waiting(inner.snapshot()))
+                }
+            },
+        };
+        drop(inner);
+        self.publish(tick.data());
+        Ok(tick)
+    }
+
+    /// Switches to `config` once `persist` has saved it, so a failed save
+    /// leaves the engine as it was.
+    pub fn update_config(
+        &self,
+        config: TimerConfig,
+        expected_revision: Option<u64>,
+        persist: impl FnOnce(&TimerConfig) -> Result<(), String>,
+    ) -> Result<TimerData, MutationError> {
+        let inner = self.lock()?;
+        check_revision("timer", expected_revision, inner.revision)?;
+        persist(&config)?;
+        let operation = TimerOperation::UpdateConfig {
+            config: config.clone(),
+        };
+        self.transition(inner, operation, |inner| {
+            inner.base_config = config.clone();
+            inner.set_config(config);
+            if inner.state == TimerState::Idle {
+                inner.renew_session();
+            }
+            Ok(())
+        })
+    }
+
+    /// Picks up a changed schedule, experiment or day boundary, which
+    /// applies from the next start, and the display mode, which applies
+    /// from the next snapshot.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), TimerError> {
+        let mut inner = self.lock()?;
+        inner.schedule = preferences.timer_schedule.clone();
+        inner.experiment = preferences.experiment.clone();
+        inner.calendar = preferences.calendar;
+        inner.display_mode = preferences.display_mode;
+        Ok(())
+    }
+
+    /// Runs the engine clock `speed` times faster than real time so full
+    /// work/break cycles can be exercised in seconds. `1.0` restores real time;
+    /// speeds above 1000× are rejected.
+    #[cfg(feature = "simulation")]
+    pub fn set_time_scale(&self, speed: f64) -> Result<TimerData, TimerError> {
+        if !speed.is_finite() || speed <= 0.0 || speed > MAX_TIME_SCALE {
+            return Err(TimerError::InvalidSpeed(speed));
+        }
+        let mut inner = self.lock()?;
+        let start = inner.begin_transition();
+        inner.clock.set_speed(speed);
+        inner.revision += 1;
+        Ok(inner.end_transition(start, TimerOperation::SetTimeScale { speed }))
+    }
+
+    /// Freezes the engine clock at `clock` and Unix time `unix`, for
+    /// replaying history.
+    #[cfg(feature = "simulation")]
+    pub fn set_clock(&self, clock: Duration, unix: u64) -> Result<(), TimerError> {
+        self.lock()?.clock = EngineClock::frozen_at(clock, unix);
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+//! The pomodoro timer engine: the state [`TimerManager`] guards and the
+//! snapshots it hands out. Transitions live in `transitions`, everything
+//! else the manager answers in `manager`, and the engine's own bookkeeping
+//! in `inner`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use ts_rs::TS;
+
+use super::calendar::CalendarSettings;
+use super::clock::EngineClock;
+use super::cycle::{CycleInfo, SequencePhase};
+use super::event_log::TimerEvent;
+use super::experiments::Experiment;
+use super::session_types::{default_custom_types, find_custom, CustomSessionType};
+use super::state_history::StateHistory;
+use super::storage::SessionData;
+use super::timer_schedule::TimerSchedule;
+
+mod inner;
+mod manager;
+mod transitions;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerState {
+    Idle,
+    Running,
+    Paused,
+    /// Counting up in an open-ended session until `stop_stopwatch`.
+    Stopwatch,
+    /// Past the end of a session, counting how far over until it is
+    /// completed, with `TimerConfig::overtime` on.
+    Overtime,
+}
+
+/// What timer surfaces count: the time left, the time elapsed or the
+/// share done.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayMode {
+    #[default]
+    Countdown,
+    CountUp,
+    Percent,
+}
+
+impl fmt::Display for TimerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TimerState::Idle => "idle",
+            TimerState::Running => "running",
+            TimerState::Paused => "paused",
+            TimerState::Stopwatch => "stopwatch",
+            TimerState::Overtime => "overtime",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionType {
+    Work,
+    ShortBreak,
+    LongBreak,
+    /// A user-defined type, by `CustomSessionType::id`.
+    Custom(String),
+}
+
+impl SessionType {
+    /// Stable identifier: the snake_case name for built-ins, the id for
+    /// custom types.
+    pub fn key(&self) -> &str {
+        match self {
+            SessionType::Work => "work",
+            SessionType::ShortBreak => "short_break",
+            SessionType::LongBreak => "long_break",
+            SessionType::Custom(id) => id,
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            SessionType::Work => "Work",
+            SessionType::ShortBreak => "Short break",
+            SessionType::LongBreak => "Long break",
+            SessionType::Custom(id) => id,
+        }
+    }
+}
+
+/// Durations are expressed in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct TimerConfig {
+    pub work_duration: u64,
+    pub short_break_duration: u64,
+    pub long_break_duration: u64,
+    pub sessions_until_long_break: u32,
+    pub custom_session_types: Vec<CustomSessionType>,
+    /// Phases run in this order, starting over after the last, instead of
+    /// the work/short break/long break cycle. Empty for the classic cycle.
+    pub sequence: Vec<SequencePhase>,
+    /// Keep counting when a session runs out, until it is completed,
+    /// instead of moving on to the next one.
+    pub overtime: bool,
+}
+
+impl Default for TimerConfig {
+    fn default() -> Self {
+        Self {
+            work_duration: 25 * 60,
+            short_break_duration: 5 * 60,
+            long_break_duration: 15 * 60,
+            sessions_until_long_break: 4,
+            custom_session_types: default_custom_types(),
+            sequence: Vec::new(),
+            overtime: false,
+        }
+    }
+}
+
+impl TimerConfig {
+    /// Falls back to the work duration for custom types that no longer exist.
+    pub fn duration_for(&self, session_type: &SessionType) -> u64 {
+        match session_type {
+            SessionType::Work => self.work_duration,
+            SessionType::ShortBreak => self.short_break_duration,
+            SessionType::LongBreak => self.long_break_duration,
+            SessionType::Custom(id) => find_custom(&self.custom_session_types, id)
+                .map_or(self.work_duration, |custom| custom.duration),
+        }
+    }
+}
+
+/// Snapshot of the timer sent to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct TimerData {
+    pub state: TimerState,
+    pub session_type: SessionType,
+    pub remaining_time: u64,
+    pub total_time: u64,
+    /// Fraction of the session elapsed, to the millisecond.
+    pub progress: f64,
+    pub elapsed_ms: u64,
+    pub remaining_ms: u64,
+    /// Seconds past the end of the session, in overtime.
+    #[serde(default)]
+    pub overtime: u64,
+    /// Seconds into the session, overtime included.
+    #[serde(default)]
+    pub elapsed_time: u64,
+    /// Whole percent of the session elapsed, 0 for a stopwatch.
+    #[serde(default)]
+    pub percent_complete: u32,
+    /// How surfaces should show this, from the preferences.
+    #[serde(default)]
+    pub display_mode: DisplayMode,
+    pub completed_pomodoros: u32,
+    /// Position within the current pomodoro cycle.
+    pub cycle: CycleInfo,
+    /// Unix time the current session was first started, if it has been.
+    pub start_time: Option<u64>,
+    /// The most recently finished or skipped session, with real start and
+    /// end timestamps, ready to be recorded in statistics.
+    pub last_session: Option<SessionData>,
+    pub config: TimerConfig,
+    pub revision: u64,
+}
+
+struct TimerSession {
+    session_type: SessionType,
+    duration: u64,
+    /// Clock reading when the session last started or resumed.
+    started_at: Option<Duration>,
+    elapsed: Duration,
+    /// Unix time of the first start; unaffected by pausing.
+    start_time: Option<u64>,
+    /// Seconds added to `duration` by `extend_session`.
+    extended: u64,
+    /// Overtime run before `extend_session` restarted the session; it is
+    /// part of `duration` from then on.
+    overtime: u64,
+}
+
+impl TimerSession {
+    fn new(session_type: SessionType, config: &TimerConfig) -> Self {
+        Self {
+            duration: config.duration_for(&session_type),
+            session_type,
+            started_at: None,
+            elapsed: Duration::ZERO,
+            start_time: None,
+            extended: 0,
+            overtime: 0,
+        }
+    }
+
+    fn elapsed(&self, now: Duration) -> Duration {
+        let running = self
+            .started_at
+            .map(|at| now.saturating_sub(at))
+            .unwrap_or_default();
+        self.elapsed + running
+    }
+
+    fn elapsed_secs(&self, now: Duration) -> u64 {
+        self.elapsed(now).as_secs()
+    }
+
+    fn remaining_secs(&self, now: Duration) -> u64 {
+        self.duration.saturating_sub(self.elapsed_secs(now))
+    }
+
+    fn overtime_secs(&self, now: Duration) -> u64 {
+        self.elapsed_secs(now).saturating_sub(self.duration)
+    }
+}
+
+struct TimerInner {
+    state: TimerState,
+    /// The configuration in effect, from the experiment, the schedule or
+    /// `base_config`.
+    config: TimerConfig,
+    /// The configuration outside scheduled windows.
+    base_config: TimerConfig,
+    schedule: TimerSchedule,
+    experiment: Option<Experiment>,
+    /// Tag of the experiment arm the current session started under.
+    experiment_tag: Option<String>,
+    /// Which day a session starts on, for the experiment's arm.
+    calendar: CalendarSettings,
+    display_mode: DisplayMode,
+    clock: EngineClock,
+    session: TimerSession,
+    /// Index of the current session in `config.sequence`, if it has one.
+    phase: usize,
+    completed_pomodoros: u32,
+    last_session: Option<SessionData>,
+    /// Sessions ended since they were last taken for the statistics.
+    finished: Vec<SessionData>,
+    revision: u64,
+    /// Transitions not yet written to the event log.
+    events: Vec<TimerEvent>,
+    history: StateHistory,
+}
+
+/// What a running session did on reaching its end.
+enum Due {
+    Completed,
+    Overran,
+}
+
+/// What a background tick observed.
+pub enum Tick {
+    /// Idle or paused.
+    Waiting(TimerData),
+    /// A session, stopwatch or overtime is running.
+    Running(TimerData),
+    Completed {
+        finished: SessionType,
+        next: TimerData,
+    },
+    /// The running session reached its end and went into overtime.
+    Overran(TimerData),
+}
+
+impl Tick {
+    /// The timer state after the tick.
+    pub fn data(&self) -> &TimerData {
+        match self {
+            Tick::Waiting(data) | Tick::Running(data) | Tick::Overran(data) => data,
+            Tick::Completed { next, .. } => next,
+        }
+    }
+}
+
+/// Snapshots a slow [`TimerManager::subscribe`] receiver may fall behind by
+/// before it skips ahead.
+const UPDATE_CAPACITY: usize = 16;
+
+/// Fastest [`TimerManager::set_time_scale`] accepts; far beyond it the
+/// clock's scaled durations overflow.
+#[cfg(feature = "simulation")]
+const MAX_TIME_SCALE: f64 = 1000.0;
+
+/// Core pomodoro state machine, shared with commands through `tauri::State`.
+pub struct TimerManager {
+    inner: Mutex<TimerInner>,
+    /// Every transition and running tick, for push clients.
+    updates: broadcast::Sender<TimerData>,
+}
@@ -0,0 +1,516 @@
+//! The operations that move the timer between states.
+
+use std::time::Duration;
+
+use super::{SessionType, TimerData, TimerManager, TimerState};
+use crate::services::event_log::TimerEventKind;
+use crate::services::power::SleepPolicy;
+use crate::services::revision::MutationError;
+use crate::services::session_types::find_custom;
+use crate::services::state_history::TimerOperation;
+use crate::services::timer_error::TimerError;
+
+impl TimerManager {
+    pub fn start(&self, expected_revision: Option<u64>) -> Result<TimerData, MutationError> {
+        self.start_for(None, expected_revision)
+    }
+
+    /// Starts the idle session, lasting `duration` seconds instead of its
+    /// configured length if given.
+    pub fn start_for(
+        &self,
+        duration: Option<u64>,
+        expected_revision: Option<u64>,
+    ) -> Result<TimerData, MutationError> {
+        let operation = match duration {
+            Some(duration) => TimerOperation::StartFor { duration },
+            None => TimerOperation::Start,
+        };
+        self.mutate(expected_revision, operation, |inner| match inner.state {
+            TimerState::Idle => {
+                inner.apply_schedule();
+                if let Some(duration) = duration {
+                    inner.session.duration = duration;
+                }
+                inner.session.started_at = Some(inner.clock.now());
+                inner.session.start_time = Some(inner.clock.unix_now());
+                inner.state = TimerState::Running;
+                inner.log(TimerEventKind::Start);
+                Ok(())
+            }
+            state => Err(TimerError::InvalidTransition {
+                operation: "start",
+                state,
+            }),
+        })
+    }
+
+    pub fn pause(&self, expected_revision: Option<u64>) -> Result<TimerData, MutationError> {
+        self.mutate(expected_revision, TimerOperation::Pause, |inner| {
+            if inner.state != TimerState::Running {
+                return Err(TimerError::NotRunning);
+            }
+            if let Some(started_at) = inner.session.started_at.take() {
+                inner.session.elapsed += inner.clock.now().saturating_sub(started_at);
+            }
+            inner.state = TimerState::Paused;
+            inner.log(TimerEventKind::Pause);
+            Ok(())
+        })
+    }
+
+    pub fn resume(&self, expected_revision: Option<u64>) -> Result<TimerData, MutationError> {
+        self.mutate(expected_revision, TimerOperation::Resume, |inner| {
+            if inner.state != TimerState::Paused {
+                return Err(TimerError::NotPaused);
+            }
+            inner.session.started_at = Some(inner.clock.now());
+            inner.state = TimerState::Running;
+            inner.log(TimerEventKind::Resume);
+            Ok(())
+        })
+    }
+
+    /// Starts an open-ended session of the current type that counts up
+    /// instead of down. It stays outside the pomodoro cycle.
+    pub fn start_stopwatch(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> Result<TimerData, MutationError> {
+        self.mutate(expected_revision, TimerOperation::StartStopwatch, |inner| {
+            if inner.state != TimerState::Idle {
+                return Err(TimerError::InvalidTransition {
+                    operation: "start a stopwatch",
+                    state: inner.state,
+                });
+            }
+            inner.apply_schedule();
+            inner.session.started_at = Some(inner.clock.now());
+            inner.session.start_time = Some(inner.clock.unix_now());
+            inner.state = TimerState::Stopwatch;
+            inner.log(TimerEventKind::Start);
+            Ok(())
+        })
+    }
+
+    /// Stops the stopwatch and, if `record` is set, records the time it
+    /// counted for the statistics, as a completed session if it ran at
+    /// least the planned duration. The timer goes back to an idle session
+    /// of the same type.
+    pub fn stop_stopwatch(
+        &self,
+        record: bool,
+        expected_revision: Option<u64>,
+    ) -> Result<TimerData, MutationError> {
+        let operation = TimerOperation::StopStopwatch { recorded: record };
+        self.mutate(expected_revision, operation, |inner| {
+            if inner.state != TimerState::Stopwatch {
+                return Err(TimerError::InvalidTransition {
+                    operation: "stop the stopwatch",
+                    state: inner.state,
+                });
+            }
+            if record {
+                inner.log(TimerEventKind::Complete);
+                inner.record_session(true);
+            } else {
+                inner.log(TimerEventKind::Reset);
+            }
+            inner.renew_session();
+            inner.state = TimerState::Idle;
+            Ok(())
+        })
+    }
+
+    pub fn reset(&self, expected_revision: Option<u64>) -> Result<TimerData, MutationError> {
+        self.mutate(expected_revision, TimerOperation::Reset, |inner| {
+            inner.log(TimerEventKind::Reset);
+            inner.renew_session();
+            inner.state = TimerState::Idle;
+            Ok(())
+        })
+    }
+
+    /// Applies `policy` to the session being timed after the system slept
+    /// for `asleep` by the wall clock, of which the engine clock counted
+    /// `counted`. A stopwatch or overtime can't be paused, so under
+    /// [`SleepPolicy::Pause`] it leaves the time asleep out and keeps
+    /// counting. Returns `None` if nothing was being timed.
+    pub fn wake(
+        &self,
+        policy: SleepPolicy,
+        asleep: Duration,
+        counted: Duration,
+    ) -> Result<Option<TimerData>, MutationError> {
+        let timing = |state| {
+            matches!(
+                state,
+                TimerState::Running | TimerState::Stopwatch | TimerState::Overtime
+            )
+        };
+        if !timing(self.lock()?.state) {
+            return Ok(None);
+        }
+        let operation = TimerOperation::Wake {
+            policy,
+            asleep_ms: asleep.as_millis() as u64,
+            counted_ms: counted.as_millis() as u64,
+        };
+        self.mutate(None, operation, |inner| {
+            if !timing(inner.state) {
+                return Err(TimerError::NotRunning);
+            }
+            match policy {
+                SleepPolicy::Continue => inner.session.elapsed += asleep.saturating_sub(counted),
+                SleepPolicy::Pause => {
+                    let now = inner.clock.now();
+                    inner.session.elapsed = inner.session.elapsed(now).saturating_sub(counted);
+                    if inner.state == TimerState::Running {
+                        inner.session.started_at = None;
+                        inner.state = TimerState::Paused;
+                        inner.log(TimerEventKind::Pause);
+                    } else {
+                        inner.session.started_at = Some(now);
+                    }
+                }
+                SleepPolicy::Discard => {
+                    inner.log(TimerEventKind::Reset);
+                    inner.renew_session();
+                    inner.state = TimerState::Idle;
+                }
+            }
+            Ok(())
+        })
+        .map(Some)
+    }
+
+    /// Adds `seconds` to the running or paused session without restarting
+    /// it. A session in overtime runs again for `seconds` from now, keeping
+    /// the overtime it has run.
+    pub fn extend_session(
+        &self,
+        seconds: u64,
+        expected_revision: Option<u64>,
+    ) -> Result<TimerData, MutationError> {
+        self.mutate(
+            expected_revision,
+            TimerOperation::Extend { seconds },
+            |inner| {
+                let now = inner.clock.now();
+                let session = &mut inner.session;
+                let duration = match inner.state {
+                    TimerState::Running | TimerState::Paused => session.duration + seconds,
+                    TimerState::Overtime => {
+                        session.overtime += session.overtime_secs(now);
+                        session.elapsed_secs(now) + seconds
+                    }
+                    state => {
+                        return Err(TimerError::InvalidTransition {
+                            operation: "extend",
+                            state,
+                        })
+                    }
+                };
+                session.extended += seconds;
+                session.duration = duration;
+                if inner.state == TimerState::Overtime {
+                    inner.state = TimerState::Running;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Replaces the idle session with a fresh one of `session_type`, e.g. to
+    /// run a custom Reading session between pomodoros.
+    pub fn switch_session(
+        &self,
+        session_type: SessionType,
+        expected_revision: Option<u64>,
+    ) -> Result<TimerData, MutationError> {
+        let operation = TimerOperation::SwitchSession {
+            session_type: session_type.clone(),
+        };
+        self.mutate(expected_revision, operation, |inner| {
+            if inner.state != TimerState::Idle {
+                return Err(TimerError::InvalidTransition {
+                    operation: "switch sessions",
+                    state: inner.state,
+                });
+            }
+            if let SessionType::Custom(id) = &session_type {
+                if find_custom(&inner.config.custom_session_types, id).is_none() {
+                    return Err(TimerError::UnknownSessionType(id.clone()));
+                }
+            }
+            inner.session = inner.new_session(session_type);
+            Ok(())
+        })
+    }
+
+    /// Moves on to the next session without counting the current one.
+    pub fn skip(&self, expected_revision: Option<u64>) -> Result<TimerData, MutationError> {
+        self.mutate(expected_revision, TimerOperation::Skip, |inner| {
+            if matches!(inner.state, TimerState::Stopwatch | TimerState::Overtime) {
+                return Err(TimerError::InvalidTransition {
+                    operation: "skip",
+                    state: inner.state,
+                });
+            }
+            inner.log(TimerEventKind::Skip);
+            inner.record_session(false);
+            inner.advance();
+            Ok(())
+        })
+    }
+
+    /// Finishes the current session, counting it if it was a work session.
+    /// In overtime this records how far past the end it ran.
+    pub fn complete_session(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> Result<TimerData, MutationError> {
+        self.mutate(expected_revision, TimerOperation::Complete, |inner| {
+            if inner.state == TimerState::Stopwatch {
+                return Err(TimerError::InvalidTransition {
+                    operation: "complete",
+                    state: inner.state,
+                });
+            }
+            inner.finish();
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::cycle::SequencePhase;
+    use crate::services::storage::UserPreferences;
+    use crate::services::timer_schedule::{ScheduleRule, TimerSchedule};
+    use crate::services::timer_state::TimerConfig;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn wake(timer: &TimerManager, policy: SleepPolicy) -> Option<TimerData> {
+        timer.wake(policy, 10 * MINUTE, Duration::ZERO).unwrap()
+    }
+
+    #[test]
+    fn a_running_session_counts_or_pauses_for_the_sleep() {
+        let timer = TimerManager::new(TimerConfig::default());
+        timer.start(None).unwrap();
+        let data = wake(&timer, SleepPolicy::Continue).unwrap();
+        assert_eq!(data.state, TimerState::Running);
+        assert!(data.remaining_time <= 15 * 60);
+
+        let data = timer
+            .wake(SleepPolicy::Pause, MINUTE, MINUTE)
+            .unwrap()
+            .unwrap();
+        assert_eq!(data.state, TimerState::Paused);
+        assert!(data.remaining_time <= 16 * 60);
+    }
+
+    #[test]
+    fn elapsed_time_and_percent_complete_follow_the_session() {
+        let timer = TimerManager::new(TimerConfig::default());
+        timer.start(None).unwrap();
+        wake(&timer, SleepPolicy::Continue).unwrap();
+        let data = timer
+            .wake(SleepPolicy::Pause, Duration::ZERO, Duration::ZERO)
+            .unwrap()
+            .unwrap();
+        assert_eq!(data.elapsed_time, 10 * 60);
+        assert_eq!(data.percent_complete, 40);
+        assert_eq!(data.remaining_time, 15 * 60);
+
+        let timer = TimerManager::new(TimerConfig::default());
+        timer.start(None).unwrap();
+        let data = timer
+            .wake(SleepPolicy::Continue, 30 * MINUTE, Duration::ZERO)
+            .unwrap()
+            .unwrap();
+        assert!(data.elapsed_time >= 30 * 60);
+        assert_eq!(data.percent_complete, 100);
+    }
+
+    #[test]
+    fn a_stopwatch_keeps_counting_without_the_sleep_when_paused() {
+        let timer = TimerManager::new(TimerConfig::default());
+        timer.start_stopwatch(None).unwrap();
+        let data = wake(&timer, SleepPolicy::Continue).unwrap();
+        assert_eq!(data.state, TimerState::Stopwatch);
+        assert!(data.elapsed_time >= 10 * 60);
+
+        let data = timer
+            .wake(SleepPolicy::Pause, MINUTE, 5 * MINUTE)
+            .unwrap()
+            .unwrap();
+        assert_eq!(data.state, TimerState::Stopwatch);
+        assert!(data.elapsed_time >= 5 * 60 && data.elapsed_time < 6 * 60);
+
+        let data = wake(&timer, SleepPolicy::Discard).unwrap();
+        assert_eq!(data.state, TimerState::Idle);
+    }
+
+    #[test]
+    fn overtime_can_be_discarded_after_a_sleep() {
+        let timer = TimerManager::new(TimerConfig {
+            overtime: true,
+            ..TimerConfig::default()
+        });
+        timer.start(None).unwrap();
+        timer
+            .wake(SleepPolicy::Continue, 30 * MINUTE, Duration::ZERO)
+            .unwrap();
+        timer.check_completion().unwrap();
+        assert_eq!(timer.get_data().unwrap().state, TimerState::Overtime);
+
+        let data = wake(&timer, SleepPolicy::Continue).unwrap();
+        assert!(data.overtime >= 14 * 60);
+        let data = wake(&timer, SleepPolicy::Discard).unwrap();
+        assert_eq!(data.state, TimerState::Idle);
+    }
+
+    #[test]
+    fn nothing_is_applied_while_idle_or_paused() {
+        let timer = TimerManager::new(TimerConfig::default());
+        assert!(wake(&timer, SleepPolicy::Discard).is_none());
+        timer.start(None).unwrap();
+        timer.pause(None).unwrap();
+        assert!(wake(&timer, SleepPolicy::Discard).is_none());
+        assert_eq!(timer.get_data().unwrap().state, TimerState::Paused);
+    }
+
+    #[test]
+    fn sessions_take_the_scheduled_config_from_their_start() {
+        let regular = TimerConfig::default();
+        let timer = TimerManager::new(regular.clone());
+        let mut preferences = UserPreferences::default();
+        preferences.timer_schedule = TimerSchedule {
+            enabled: true,
+            rules: vec![ScheduleRule {
+                name: "All day".to_string(),
+                weekdays: Vec::new(),
+                start_minute: 0,
+                end_minute: 0,
+                config: TimerConfig {
+                    work_duration: 15 * 60,
+                    ..regular.clone()
+                },
+            }],
+        };
+        timer.configure(&preferences).unwrap();
+        assert_eq!(timer.get_data().unwrap().total_time, regular.work_duration);
+        assert_eq!(timer.start(None).unwrap().total_time, 15 * 60);
+
+        timer.reset(None).unwrap();
+        preferences.timer_schedule.enabled = false;
+        timer.configure(&preferences).unwrap();
+        assert_eq!(timer.start(None).unwrap().total_time, regular.work_duration);
+    }
+
+    fn phase(session_type: SessionType, minutes: u64) -> SequencePhase {
+        SequencePhase {
+            session_type,
+            duration: minutes * 60,
+        }
+    }
+
+    #[test]
+    fn a_sequence_runs_its_phases_in_order_and_starts_over() {
+        let timer = TimerManager::new(TimerConfig {
+            sequence: vec![
+                phase(SessionType::Work, 52),
+                phase(SessionType::ShortBreak, 17),
+                phase(SessionType::Work, 30),
+            ],
+            ..TimerConfig::default()
+        });
+        let step = |data: TimerData| {
+            (
+                data.session_type,
+                data.total_time / 60,
+                data.cycle.sequence_phase,
+            )
+        };
+        assert_eq!(
+            step(timer.get_data().unwrap()),
+            (SessionType::Work, 52, Some(0))
+        );
+        assert_eq!(
+            step(timer.skip(None).unwrap()),
+            (SessionType::ShortBreak, 17, Some(1))
+        );
+        assert_eq!(
+            step(timer.skip(None).unwrap()),
+            (SessionType::Work, 30, Some(2))
+        );
+        assert_eq!(
+            step(timer.skip(None).unwrap()),
+            (SessionType::Work, 52, Some(0))
+        );
+
+        timer.skip(None).unwrap();
+        let mut preferences = UserPreferences::default();
+        preferences.timer_config.sequence = vec![
+            phase(SessionType::LongBreak, 5),
+            phase(SessionType::Work, 10),
+        ];
+        timer.configure(&preferences).unwrap();
+        assert_eq!(
+            step(timer.get_data().unwrap()),
+            (SessionType::LongBreak, 5, Some(0))
+        );
+    }
+
+    #[test]
+    fn extending_adds_to_the_session_or_restarts_it_from_overtime() {
+        let timer = TimerManager::new(TimerConfig {
+            overtime: true,
+            ..TimerConfig::default()
+        });
+        assert!(timer.extend_session(60, None).is_err());
+        timer.start(None).unwrap();
+        assert_eq!(
+            timer.extend_session(5 * 60, None).unwrap().total_time,
+            30 * 60
+        );
+
+        timer
+            .wake(SleepPolicy::Continue, 40 * MINUTE, Duration::ZERO)
+            .unwrap();
+        timer.check_completion().unwrap();
+        let data = timer.extend_session(5 * 60, None).unwrap();
+        assert_eq!(data.state, TimerState::Running);
+        assert!(data.remaining_time > 4 * 60 && data.remaining_time <= 5 * 60);
+        let recorded = timer.complete_session(None).unwrap().last_session.unwrap();
+        assert_eq!(recorded.extended, 10 * 60);
+    }
+
+    #[test]
+    fn extending_from_overtime_keeps_the_overtime_apart() {
+        let timer = TimerManager::new(TimerConfig {
+            overtime: true,
+            ..TimerConfig::default()
+        });
+        timer.start(None).unwrap();
+        timer
+            .wake(SleepPolicy::Continue, 37 * MINUTE, Duration::ZERO)
+            .unwrap();
+        timer.check_completion().unwrap();
+        timer.extend_session(10 * 60, None).unwrap();
+        timer
+            .wake(SleepPolicy::Continue, 13 * MINUTE, Duration::ZERO)
+            .unwrap();
+        timer.check_completion().unwrap();
+        assert_eq!(timer.get_data().unwrap().state, TimerState::Overtime);
+
+        let recorded = timer.complete_session(None).unwrap().last_session.unwrap();
+        assert_eq!(recorded.duration, 35 * 60);
+        assert_eq!(recorded.extended, 10 * 60);
+        assert!(recorded.overtime >= 15 * 60 && recorded.overtime < 16 * 60);
+    }
+}
@@ -0,0 +1,284 @@
+//! Where the timer commands send their work: the in-process
+//! [`TimerManager`], or a headless focus server reached over its REST API
+//! for thin-client setups. The backend is chosen in the preferences and the
+//! server's access token is kept next to them.
+//!
+//! With a remote backend the local engine keeps running but idle, so tick
+//! events describe it rather than the server; the frontend polls
+//! `get_timer_state` instead. Commands the server's API has no route for,
+//! such as the stopwatch, fail with `remote_backend` meanwhile.
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::revision::{MutationError, RevisionConflict};
+use super::storage::{StorageService, UserPreferences};
+use super::timer_state::{TimerData, TimerManager};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimerBackend {
+    /// The engine inside this app.
+    #[default]
+    Local,
+    /// A headless focus server, e.g. `https://timer.example.com`. Only set
+    /// through `connect_focus_server`.
+    Remote { url: String },
+}
+
+/// Whether `url` can carry the access token without exposing it: HTTPS,
+/// or plain HTTP to a server on this machine.
+pub fn is_secure_server_url(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    match url.scheme() {
+        "https" => true,
+        "http" => url.host_str().is_some_and(|host| {
+            host == "localhost"
+                || host
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .parse::<IpAddr>()
+                    .is_ok_and(|ip| ip.is_loopback())
+        }),
+        _ => false,
+    }
+}
+
+/// The timer operations the commands need, wherever the engine runs.
+pub trait TimerTransport: Send + Sync {
+    fn get_data(&self) -> BoxFuture<'_, Result<TimerData, MutationError>>;
+    fn start(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>>;
+    fn pause(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>>;
+    fn resume(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>>;
+    fn reset(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>>;
+    fn skip(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>>;
+}
+
+impl TimerTransport for TimerManager {
+    fn get_data(&self) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let result = TimerManager::get_data(self).map_err(MutationError::from);
+        Box::pin(async move { result })
+    }
+
+    fn start(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let result = TimerManager::start(self, expected_revision);
+        Box::pin(async move { result })
+    }
+
+    fn pause(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let result = TimerManager::pause(self, expected_revision);
+        Box::pin(async move { result })
+    }
+
+    fn resume(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let result = TimerManager::resume(self, expected_revision);
+        Box::pin(async move { result })
+    }
+
+    fn reset(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let result = TimerManager::reset(self, expected_revision);
+        Box::pin(async move { result })
+    }
+
+    fn skip(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let result = TimerManager::skip(self, expected_revision);
+        Box::pin(async move { result })
+    }
+}
+
+/// A focus server's REST API.
+pub struct RemoteTimer {
+    url: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl RemoteTimer {
+    pub fn new(url: &str, token: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Sends the request and turns the API's `{ error, code, conflict }`
+    /// bodies back into a [`MutationError`].
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<TimerData, MutationError> {
+        let response = request
+            .timeout(REQUEST_TIMEOUT)
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .map_err(|e| format!("Focus server {} unreachable: {e}", self.url))?;
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Focus server returned an invalid response: {e}"))?;
+        if status.is_success() {
+            return serde_json::from_value(body)
+                .map_err(|e| format!("Focus server returned an invalid timer state: {e}").into());
+        }
+        if let Some(conflict) = body.get("conflict") {
+            if let Ok(conflict) = serde_json::from_value::<RevisionConflict>(conflict.clone()) {
+                return Err(MutationError::Conflict(conflict));
+            }
+        }
+        let message = body["error"].as_str().unwrap_or("request failed");
+        Err(MutationError::Failed(format!("Focus server: {message}")))
+    }
+
+    fn post(
+        &self,
+        operation: &'static str,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let request = self
+            .client
+            .post(format!("{}/timer/{operation}", self.url))
+            .json(&json!({ "expected_revision": expected_revision }));
+        Box::pin(self.send(request))
+    }
+}
+
+impl TimerTransport for RemoteTimer {
+    fn get_data(&self) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        let request = self.client.get(format!("{}/timer/state", self.url));
+        Box::pin(self.send(request))
+    }
+
+    fn start(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        self.post("start", expected_revision)
+    }
+
+    fn pause(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        self.post("pause", expected_revision)
+    }
+
+    fn resume(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        self.post("resume", expected_revision)
+    }
+
+    fn reset(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        self.post("reset", expected_revision)
+    }
+
+    fn skip(
+        &self,
+        expected_revision: Option<u64>,
+    ) -> BoxFuture<'_, Result<TimerData, MutationError>> {
+        self.post("skip", expected_revision)
+    }
+}
+
+/// The remote backend, if the preferences select one.
+#[derive(Default)]
+pub struct TimerClient {
+    remote: Mutex<Option<Arc<RemoteTimer>>>,
+}
+
+impl TimerClient {
+    pub fn new(storage: &StorageService, preferences: &UserPreferences) -> Result<Self, String> {
+        let client = Self::default();
+        client.configure(storage, preferences)?;
+        Ok(client)
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Option<Arc<RemoteTimer>>>, String> {
+        self.remote
+            .lock()
+            .map_err(|e| format!("Timer client lock poisoned: {e}"))
+    }
+
+    /// Picks up a changed backend. A remote backend without a stored token
+    /// falls back to the local engine.
+    pub fn configure(
+        &self,
+        storage: &StorageService,
+        preferences: &UserPreferences,
+    ) -> Result<(), String> {
+        let remote = match &preferences.timer_backend {
+            TimerBackend::Local => None,
+            TimerBackend::Remote { url } => storage
+                .load_focus_server_token()?
+                .map(|token| Arc::new(RemoteTimer::new(url, &token))),
+        };
+        *self.lock()? = remote;
+        Ok(())
+    }
+
+    /// The remote backend to use instead of the local engine, if any.
+    pub fn remote(&self) -> Result<Option<Arc<RemoteTimer>>, String> {
+        Ok(self.lock()?.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_http_is_only_accepted_on_this_machine() {
+        assert!(is_secure_server_url("https://192.168.1.20:50153"));
+        assert!(is_secure_server_url("http://localhost:50153"));
+        assert!(is_secure_server_url("http://127.0.0.1:50153"));
+        assert!(is_secure_server_url("http://[::1]:50153"));
+        assert!(!is_secure_server_url("http://192.168.1.20:50153"));
+        assert!(!is_secure_server_url("http://localhost.example.com"));
+        assert!(!is_secure_server_url("ftp://localhost"));
+        assert!(!is_secure_server_url("localhost:50153"));
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::calendar::{parse_day, CalendarSettings};
+use super::session_types::CustomSessionType;
+use super::storage::TimerStatistic;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    FocusMinutes,
+    Pomodoros,
+}
+
+/// Suggested axis labelling for the bucket width, or the rollup period of a
+/// statistics summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TimeBucket {
+    pub start_date: String,
+    /// Inclusive.
+    pub end_date: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TimeSeries {
+    pub metric: Metric,
+    pub granularity: Granularity,
+    pub buckets: Vec<TimeBucket>,
+}
+
+/// The metric for each day in `statistics`. Focus is spread over the days
+/// under `calendar` that each session's span covers; pomodoros count on
+/// the day they were recorded on.
+fn daily_values(
+    statistics: &[TimerStatistic],
+    metric: Metric,
+    calendar: &CalendarSettings,
+    custom_types: &[CustomSessionType],
+) -> Vec<(NaiveDate, f64)> {
+    match metric {
+        Metric::FocusMinutes => calendar
+            .focus_by_day(statistics, custom_types)
+            .into_iter()
+            .map(|(date, seconds)| (date, seconds as f64 / 60.0))
+            .collect(),
+        Metric::Pomodoros => statistics
+            .iter()
+            .filter_map(|statistic| {
+                Some((
+                    parse_day(&statistic.date)?,
+                    f64::from(statistic.completed_pomodoros),
+                ))
+            })
+            .collect(),
+    }
+}
+
+/// Bins `statistics` between `start` and `end` (inclusive) into `buckets`
+/// contiguous, near-equal spans of days.
+///
+/// A bucket never covers less than one day, so the count is capped at the
+/// number of days in the range. `statistics` should include the days just
+/// outside the range, whose sessions may run into it.
+pub fn bucket_statistics(
+    statistics: &[TimerStatistic],
+    metric: Metric,
+    start: NaiveDate,
+    end: NaiveDate,
+    buckets: usize,
+    calendar: &CalendarSettings,
+    custom_types: &[CustomSessionType],
+) -> TimeSeries {
+    let days = (end - start).num_days() as usize + 1;
+    let count = buckets.clamp(1, days);
+    // Bucket `i` covers day offsets [i * days / count, (i + 1) * days / count).
+    let bucket_start = |i: usize| i * days / count;
+
+    let mut series: Vec<TimeBucket> = (0..count)
+        .map(|i| TimeBucket {
+            start_date: (start + Duration::days(bucket_start(i) as i64)).to_string(),
+            end_date: (start + Duration::days(bucket_start(i + 1) as i64 - 1)).to_string(),
+            value: 0.0,
+        })
+        .collect();
+
+    for (date, value) in daily_values(statistics, metric, calendar, custom_types) {
+        if date < start || date > end {
+            continue;
+        }
+        let offset = (date - start).num_days() as usize;
+        // The last bucket starting on or before the day, so values land
+        // under the dates their bucket is labelled with.
+        let index = (1..count)
+            .take_while(|&i| bucket_start(i) <= offset)
+            .count();
+        series[index].value += value;
+    }
+
+    let days_per_bucket = days as f64 / count as f64;
+    let granularity = if days_per_bucket < 7.0 {
+        Granularity::Day
+    } else if days_per_bucket < 28.0 {
+        Granularity::Week
+    } else {
+        Granularity::Month
+    };
+
+    TimeSeries {
+        metric,
+        granularity,
+        buckets: series,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::day;
+
+    fn statistic(date: &str, completed_pomodoros: u32) -> TimerStatistic {
+        TimerStatistic {
+            completed_pomodoros,
+            ..day(date, Vec::new())
+        }
+    }
+
+    fn date(key: &str) -> NaiveDate {
+        parse_day(key).unwrap()
+    }
+
+    #[test]
+    fn days_land_in_the_bucket_labelled_with_them() {
+        // Ten days across a month end in three buckets of 3, 3 and 4 days.
+        let statistics = [
+            statistic("2025-01-31", 1),
+            statistic("2025-02-01", 2),
+            statistic("2025-02-02", 4),
+            statistic("2025-02-05", 8),
+            statistic("2025-02-08", 16),
+        ];
+        let series = bucket_statistics(
+            &statistics,
+            Metric::Pomodoros,
+            date("2025-01-30"),
+            date("2025-02-08"),
+            3,
+            &CalendarSettings::default(),
+            &[],
+        );
+
+        let buckets: Vec<_> = series
+            .buckets
+            .iter()
+            .map(|bucket| {
+                (
+                    bucket.start_date.as_str(),
+                    bucket.end_date.as_str(),
+                    bucket.value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            buckets,
+            [
+                ("2025-01-30", "2025-02-01", 3.0),
+                ("2025-02-02", "2025-02-04", 4.0),
+                ("2025-02-05", "2025-02-08", 24.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! Draws the running countdown into the tray icon and keeps its tooltip
+//! current.
+// Mobile has no tray, so the renderer only tracks preferences there.
+#![cfg_attr(mobile, allow(dead_code))]
+
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+use std::sync::{Mutex, MutexGuard};
+use ts_rs::TS;
+
+use super::duration_format::{format_duration, DurationStyle};
+use super::notifications;
+use super::session_types::find_custom;
+use super::storage::UserPreferences;
+use super::timer_state::{DisplayMode, SessionType, TimerData, TimerState};
+
+pub const TRAY_ID: &str = "main";
+const APP_NAME: &str = "Tempus Ring";
+const ICON_SIZE: u32 = 32;
+const CORNER_RADIUS: i32 = 6;
+/// Progress ring redraws in this many steps per session.
+const RING_STEPS: f64 = 64.0;
+const RING_OUTER: f64 = 15.5;
+const RING_INNER: f64 = 10.5;
+
+const WORK_COLOR: Rgba<u8> = Rgba([229, 72, 77, 255]);
+const BREAK_COLOR: Rgba<u8> = Rgba([48, 164, 108, 255]);
+const PAUSED_COLOR: Rgba<u8> = Rgba([139, 141, 152, 255]);
+const TEXT_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const TRACK_COLOR: Rgba<u8> = Rgba([139, 141, 152, 96]);
+
+/// 5x7 digit glyphs, one byte per row, most significant of the low five bits
+/// on the left.
+#[rustfmt::skip]
+const DIGITS: [[u8; 7]; 10] = [
+    [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+    [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+    [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+];
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+/// Glyph scale for one or two digits; three are drawn at half the size.
+const GLYPH_SCALE: u32 = 2;
+/// The badge shows no more than three digits.
+const BADGE_MAX: u64 = 999;
+
+/// What the tray icon shows while a session is running or paused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayCountdown {
+    /// Always the app icon.
+    #[default]
+    Off,
+    /// Minutes left, minutes elapsed or percent done, as the display mode
+    /// says, on a badge in the session's color.
+    Minutes,
+    /// A ring that fills as the session progresses.
+    ProgressRing,
+}
+
+/// Identifies a drawn icon, so it is only regenerated when it would change.
+#[derive(Debug, Clone, PartialEq)]
+enum IconKey {
+    AppIcon,
+    Minutes { minutes: u64, color: Rgba<u8> },
+    Ring { step: u32, color: Rgba<u8> },
+}
+
+pub enum TrayUpdate {
+    Unchanged,
+    AppIcon,
+    Image(RgbaImage),
+}
+
+struct RendererState {
+    style: TrayCountdown,
+    shown: Option<IconKey>,
+    duration_style: DurationStyle,
+    language: String,
+    tooltip: Option<String>,
+}
+
+impl RendererState {
+    fn tooltip_for(&self, data: &TimerData) -> String {
+        let duration = |seconds| format_duration(seconds, self.duration_style, &self.language);
+        let label = notifications::label(&data.session_type, data);
+        let shown = match data.display_mode {
+            // Rounded up like the minutes badge, so both agree.
+            DisplayMode::Countdown => {
+                format!("{} left", duration(data.remaining_time.div_ceil(60) * 60))
+            }
+            DisplayMode::CountUp => format!("{} elapsed", duration(data.elapsed_time)),
+            DisplayMode::Percent => format!("{}% done", data.percent_complete),
+        };
+        match data.state {
+            TimerState::Idle => APP_NAME.to_string(),
+            TimerState::Stopwatch => format!("{label} · {}", duration(data.total_time)),
+            TimerState::Overtime => format!("{label} · {} over", duration(data.overtime)),
+            TimerState::Paused => format!("{label} · paused, {shown}"),
+            TimerState::Running => format!("{label} · {shown}"),
+        }
+    }
+}
+
+/// Turns timer ticks into tray icons, fed by the timer's tick loop.
+pub struct TrayIconRenderer {
+    state: Mutex<RendererState>,
+}
+
+fn parse_hex_color(color: &str) -> Option<Rgba<u8>> {
+    let hex = color.strip_prefix('#')?;
+    let channel = |range| u8::from_str_radix(hex.get(range)?, 16).ok();
+    Some(Rgba([channel(0..2)?, channel(2..4)?, channel(4..6)?, 255]))
+}
+
+fn session_color(data: &TimerData) -> Rgba<u8> {
+    if data.state == TimerState::Paused {
+        return PAUSED_COLOR;
+    }
+    match &data.session_type {
+        SessionType::Work => WORK_COLOR,
+        SessionType::ShortBreak | SessionType::LongBreak => BREAK_COLOR,
+        SessionType::Custom(id) => find_custom(&data.config.custom_session_types, id)
+            .and_then(|custom| parse_hex_color(&custom.color))
+            .unwrap_or(WORK_COLOR),
+    }
+}
+
+fn inside_rounded_square(x: i32, y: i32) -> bool {
+    let size = ICON_SIZE as i32;
+    let nearest = |v: i32| v.clamp(CORNER_RADIUS, size - 1 - CORNER_RADIUS);
+    let (dx, dy) = (x - nearest(x), y - nearest(y));
+    dx * dx + dy * dy <= CORNER_RADIUS * CORNER_RADIUS
+}
+
+fn draw_digit(image: &mut RgbaImage, digit: usize, scale: u32, left: u32, top: u32) {
+    for (row, bits) in DIGITS[digit].iter().enumerate() {
+        for column in 0..GLYPH_WIDTH {
+            if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                continue;
+            }
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    image.put_pixel(
+                        left + column * scale + dx,
+                        top + row as u32 * scale + dy,
+                        TEXT_COLOR,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// A number of minutes, or a percentage, in white on a rounded badge.
+/// Numbers past 99 are drawn smaller, and those past 999 show as 999.
+pub fn render_minutes(minutes: u64, color: Rgba<u8>) -> RgbaImage {
+    let mut image = RgbaImage::from_fn(ICON_SIZE, ICON_SIZE, |x, y| {
+        if inside_rounded_square(x as i32, y as i32) {
+            color
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    });
+
+    let text = minutes.min(BADGE_MAX).to_string();
+    let count = text.len() as u32;
+    let scale = if count > 2 { 1 } else { GLYPH_SCALE };
+    let glyph_width = GLYPH_WIDTH * scale;
+    let gap = scale;
+    let width = count * glyph_width + (count - 1) * gap;
+    let top = (ICON_SIZE - GLYPH_HEIGHT * scale) / 2;
+    for (index, digit) in text.bytes().enumerate() {
+        let left = (ICON_SIZE - width) / 2 + index as u32 * (glyph_width + gap);
+        draw_digit(&mut image, usize::from(digit - b'0'), scale, left, top);
+    }
+    image
+}
+
+/// A ring filled clockwise from twelve o'clock by `progress` (`0.0..=1.0`).
+pub fn render_progress_ring(progress: f64, color: Rgba<u8>) -> RgbaImage {
+    let center = f64::from(ICON_SIZE) / 2.0;
+    RgbaImage::from_fn(ICON_SIZE, ICON_SIZE, |x, y| {
+        let dx = f64::from(x) + 0.5 - center;
+        let dy = f64::from(y) + 0.5 - center;
+        let distance = dx.hypot(dy);
+        if !(RING_INNER..=RING_OUTER).contains(&distance) {
+            return Rgba([0, 0, 0, 0]);
+        }
+        let angle = dx.atan2(-dy).rem_euclid(TAU);
+        if angle <= progress * TAU {
+            color
+        } else {
+            TRACK_COLOR
+        }
+    })
+}
+
+impl TrayIconRenderer {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(RendererState {
+                style: preferences.tray_countdown,
+                shown: None,
+                duration_style: preferences.duration_style,
+                language: preferences.language.clone(),
+                tooltip: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, RendererState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Tray renderer lock poisoned: {e}"))
+    }
+
+    /// Picks up a changed countdown or duration style; the icon and tooltip
+    /// are redrawn on the next tick.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.style = preferences.tray_countdown;
+        state.shown = None;
+        state.duration_style = preferences.duration_style;
+        state.language = preferences.language.clone();
+        state.tooltip = None;
+        Ok(())
+    }
+
+    /// The tooltip for `data`, or `None` if it is already shown.
+    pub fn next_tooltip(&self, data: &TimerData) -> Result<Option<String>, String> {
+        let mut state = self.lock()?;
+        let tooltip = state.tooltip_for(data);
+        if state.tooltip.as_ref() == Some(&tooltip) {
+            return Ok(None);
+        }
+        state.tooltip = Some(tooltip.clone());
+        Ok(Some(tooltip))
+    }
+
+    /// The icon for `data`, or [`TrayUpdate::Unchanged`] if it is already shown.
+    pub fn next_icon(&self, data: &TimerData) -> Result<TrayUpdate, String> {
+        let mut state = self.lock()?;
+        let color = session_color(data);
+        let key = match (state.style, data.state) {
+            (TrayCountdown::Off, _) | (_, TimerState::Idle) => IconKey::AppIcon,
+            (TrayCountdown::Minutes, TimerState::Stopwatch) => IconKey::Minutes {
+                minutes: data.total_time / 60,
+                color,
+            },
+            (TrayCountdown::Minutes, _) => IconKey::Minutes {
+                minutes: match data.display_mode {
+                    DisplayMode::Countdown => data.remaining_time.div_ceil(60),
+                    DisplayMode::CountUp => data.elapsed_time / 60,
+                    DisplayMode::Percent => u64::from(data.percent_complete),
+                },
+                color,
+            },
+            (TrayCountdown::ProgressRing, _) => IconKey::Ring {
+                step: (data.progress.clamp(0.0, 1.0) * RING_STEPS) as u32,
+                color,
+            },
+        };
+        if state.shown.as_ref() == Some(&key) {
+            return Ok(TrayUpdate::Unchanged);
+        }
+
+        let update = match &key {
+            IconKey::AppIcon => TrayUpdate::AppIcon,
+            IconKey::Minutes { minutes, color } => {
+                TrayUpdate::Image(render_minutes(*minutes, *color))
+            }
+            IconKey::Ring { step, color } => {
+                TrayUpdate::Image(render_progress_ring(f64::from(*step) / RING_STEPS, *color))
+            }
+        };
+        state.shown = Some(key);
+        Ok(update)
+    }
+}
+
+/// Adds the app's tray icon. Its picture is then kept current by
+/// [`update_tray`].
+#[cfg(desktop)]
+pub fn create_tray(app: &tauri::AppHandle) -> Result<(), String> {
+    let mut builder = tauri::tray::TrayIconBuilder::with_id(TRAY_ID).tooltip(APP_NAME);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder
+        .build(app)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to create tray icon: {e}"))
+}
+
+/// Redraws the tray icon and tooltip for `data` if they changed. Requires
+/// a managed [`TrayIconRenderer`].
+#[cfg(desktop)]
+pub fn update_tray(app: &tauri::AppHandle, data: &TimerData) -> Result<(), String> {
+    use tauri::Manager;
+
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let renderer = app.state::<TrayIconRenderer>();
+    if let Some(tooltip) = renderer.next_tooltip(data)? {
+        tray.set_tooltip(Some(tooltip))
+            .map_err(|e| format!("Failed to update tray tooltip: {e}"))?;
+    }
+    let result = match renderer.next_icon(data)? {
+        TrayUpdate::Unchanged => return Ok(()),
+        TrayUpdate::AppIcon => tray.set_icon(app.default_window_icon().cloned()),
+        TrayUpdate::Image(image) => {
+            let (width, height) = image.dimensions();
+            tray.set_icon(Some(tauri::image::Image::new_owned(
+                image.into_raw(),
+                width,
+                height,
+            )))
+        }
+    };
+    result.map_err(|e| format!("Failed to update tray icon: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_badge_shows_three_digits_before_it_is_capped() {
+        let badge = |minutes| render_minutes(minutes, WORK_COLOR);
+        assert_ne!(badge(100), badge(99));
+        assert_ne!(badge(250), badge(100));
+        assert_eq!(badge(1000), badge(999));
+    }
+}
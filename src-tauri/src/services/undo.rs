@@ -0,0 +1,485 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use ts_rs::TS;
+
+use super::revision::MutationError;
+use super::storage::{SessionData, StorageService};
+use super::storage_file::StorageError;
+use super::tasks::{Task, TaskManager};
+use super::timer_state::{TimerConfig, TimerManager};
+use crate::commands::timer::apply_config;
+
+/// Oldest entries are dropped once the history grows past this.
+const MAX_HISTORY: usize = 50;
+
+/// A reversible change, holding enough state to apply it in either direction.
+/// Each direction only applies if what it changes is still as the other
+/// direction left it.
+#[derive(Debug, Clone)]
+pub enum UndoableAction {
+    /// `before` and `after` carry the revisions the task had in each state.
+    CompleteTask { before: Task, after: Task },
+    DeleteSession {
+        date: String,
+        index: usize,
+        session: SessionData,
+        /// The day's sessions after the delete.
+        remaining: Vec<SessionData>,
+    },
+    /// A split or merge; the day's sessions before and after it.
+    EditSessions {
+        date: String,
+        operation: &'static str,
+        before: Vec<SessionData>,
+        after: Vec<SessionData>,
+    },
+    /// Switching the timer to the preset `name`, from `previous`.
+    ApplyPreset {
+        name: String,
+        previous: TimerConfig,
+        applied: TimerConfig,
+    },
+}
+
+/// Services an action needs to revert or replay itself.
+pub struct UndoContext<'a> {
+    pub storage: &'a StorageService,
+    pub tasks: &'a TaskManager,
+    pub timer: &'a TimerManager,
+}
+
+/// Why an action was not applied.
+enum Refused {
+    /// What it changes was changed since, so it no longer applies.
+    Stale(String),
+    /// Saving failed; it can be tried again.
+    Failed(String),
+}
+
+impl From<MutationError> for Refused {
+    fn from(error: MutationError) -> Self {
+        match error {
+            MutationError::Conflict(conflict) => Refused::Stale(conflict.to_string()),
+            error => Refused::Failed(error.to_string()),
+        }
+    }
+}
+
+impl From<StorageError> for Refused {
+    fn from(error: StorageError) -> Self {
+        Refused::Failed(error.to_string())
+    }
+}
+
+/// Puts `snapshot` back if the task is still at `expected_revision`.
+/// Returns it with its new revision.
+fn restore_task(
+    ctx: &UndoContext,
+    snapshot: &Task,
+    expected_revision: u64,
+) -> Result<Task, Refused> {
+    if ctx.tasks.find(&snapshot.id).is_err() {
+        return Err(Refused::Stale(format!(
+            "Task \"{}\" was deleted",
+            snapshot.title
+        )));
+    }
+    Ok(ctx
+        .tasks
+        .restore(ctx.storage, snapshot.clone(), expected_revision)?)
+}
+
+/// Replaces the day's sessions with `replacement` if they are still
+/// `expected`. The check and the write happen under the day's lock.
+fn swap_sessions(
+    storage: &StorageService,
+    date: &str,
+    expected: &[SessionData],
+    replacement: Vec<SessionData>,
+) -> Result<(), Refused> {
+    storage.edit_sessions(date, |current| {
+        if current != expected {
+            return Err(Refused::Stale(format!(
+                "The sessions on {date} were changed since"
+            )));
+        }
+        Ok(replacement)
+    })?;
+    Ok(())
+}
+
+/// Switches the timer to `replacement` if the saved configuration is still
+/// `expected`.
+fn swap_config(
+    ctx: &UndoContext,
+    expected: &TimerConfig,
+    replacement: &TimerConfig,
+) -> Result<(), Refused> {
+    let current = ctx
+        .storage
+        .load_preferences()
+        .map_err(Refused::Failed)?
+        .timer_config;
+    if current != *expected {
+        return Err(Refused::Stale(
+            "The timer settings were changed since".to_string(),
+        ));
+    }
+    apply_config(ctx.timer, ctx.storage, replacement.clone(), None)?;
+    Ok(())
+}
+
+fn with_session(
+    remaining: &[SessionData],
+    index: usize,
+    session: &SessionData,
+) -> Vec<SessionData> {
+    let mut sessions = remaining.to_vec();
+    sessions.insert(index.min(sessions.len()), session.clone());
+    sessions
+}
+
+impl UndoableAction {
+    fn label(&self) -> String {
+        match self {
+            UndoableAction::CompleteTask { before, .. } => {
+                format!("Complete task \"{}\"", before.title)
+            }
+            UndoableAction::DeleteSession { date, .. } => format!("Delete session on {date}"),
+            UndoableAction::EditSessions {
+                date, operation, ..
+            } => format!("{operation} sessions on {date}"),
+            UndoableAction::ApplyPreset { name, .. } => format!("Apply preset \"{name}\""),
+        }
+    }
+
+    fn revert(&mut self, ctx: &UndoContext) -> Result<(), Refused> {
+        match self {
+            UndoableAction::CompleteTask { before, after } => {
+                before.revision = restore_task(ctx, before, after.revision)?.revision;
+                Ok(())
+            }
+            UndoableAction::DeleteSession {
+                date,
+                index,
+                session,
+                remaining,
+            } => swap_sessions(
+                ctx.storage,
+                date,
+                remaining,
+                with_session(remaining, *index, session),
+            ),
+            UndoableAction::EditSessions {
+                date,
+                before,
+                after,
+                ..
+            } => swap_sessions(ctx.storage, date, after, before.clone()),
+            UndoableAction::ApplyPreset {
+                previous, applied, ..
+            } => swap_config(ctx, applied, previous),
+        }
+    }
+
+    fn reapply(&mut self, ctx: &UndoContext) -> Result<(), Refused> {
+        match self {
+            UndoableAction::CompleteTask { before, after } => {
+                after.revision = restore_task(ctx, after, before.revision)?.revision;
+                Ok(())
+            }
+            UndoableAction::DeleteSession {
+                date,
+                index,
+                session,
+                remaining,
+            } => swap_sessions(
+                ctx.storage,
+                date,
+                &with_session(remaining, *index, session),
+                remaining.clone(),
+            ),
+            UndoableAction::EditSessions {
+                date,
+                before,
+                after,
+                ..
+            } => swap_sessions(ctx.storage, date, before, after.clone()),
+            UndoableAction::ApplyPreset {
+                previous, applied, ..
+            } => swap_config(ctx, previous, applied),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct HistoryStatus {
+    /// Label of the action that was just undone or redone.
+    pub action: Option<String>,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+#[derive(Default)]
+struct History {
+    undo: VecDeque<UndoableAction>,
+    redo: Vec<UndoableAction>,
+    /// [`StorageService::mutation_count`] after the last change made or
+    /// recorded here.
+    seen: u64,
+}
+
+impl History {
+    fn status(&self, action: Option<String>) -> HistoryStatus {
+        HistoryStatus {
+            action,
+            can_undo: !self.undo.is_empty(),
+            can_redo: !self.redo.is_empty(),
+        }
+    }
+
+    /// Drops the redo history if anything changed since it was made, as it
+    /// no longer follows from the current state.
+    fn truncate_redo(&mut self, storage: &StorageService) {
+        let count = storage.mutation_count();
+        if count != self.seen {
+            self.redo.clear();
+            self.seen = count;
+        }
+    }
+}
+
+/// Bounded undo/redo history of user actions.
+#[derive(Default)]
+pub struct UndoManager {
+    history: Mutex<History>,
+}
+
+impl UndoManager {
+    fn lock(&self) -> Result<MutexGuard<'_, History>, String> {
+        self.history
+            .lock()
+            .map_err(|e| format!("Undo history lock poisoned: {e}"))
+    }
+
+    /// Records an action just applied to `storage`. Any redo history is
+    /// discarded, as it no longer follows from the current state.
+    pub fn record(&self, storage: &StorageService, action: UndoableAction) -> Result<(), String> {
+        let mut history = self.lock()?;
+        history.undo.push_back(action);
+        if history.undo.len() > MAX_HISTORY {
+            history.undo.pop_front();
+        }
+        history.redo.clear();
+        history.seen = storage.mutation_count();
+        Ok(())
+    }
+
+    pub fn status(&self, storage: &StorageService) -> Result<HistoryStatus, String> {
+        let mut history = self.lock()?;
+        history.truncate_redo(storage);
+        Ok(history.status(None))
+    }
+
+    /// Reverts the newest action. One whose changes were since changed
+    /// again is dropped rather than overwriting them.
+    pub fn undo(&self, ctx: &UndoContext) -> Result<HistoryStatus, String> {
+        let mut history = self.lock()?;
+        history.truncate_redo(ctx.storage);
+        let mut action = history
+            .undo
+            .pop_back()
+            .ok_or_else(|| "Nothing to undo".to_string())?;
+        match action.revert(ctx) {
+            Ok(()) => {}
+            Err(Refused::Stale(reason)) => {
+                return Err(format!("Can't undo {}: {reason}", action.label()));
+            }
+            Err(Refused::Failed(error)) => {
+                history.undo.push_back(action);
+                return Err(error);
+            }
+        }
+
+        let label = action.label();
+        history.redo.push(action);
+        history.seen = ctx.storage.mutation_count();
+        Ok(history.status(Some(label)))
+    }
+
+    pub fn redo(&self, ctx: &UndoContext) -> Result<HistoryStatus, String> {
+        let mut history = self.lock()?;
+        history.truncate_redo(ctx.storage);
+        let mut action = history
+            .redo
+            .pop()
+            .ok_or_else(|| "Nothing to redo".to_string())?;
+        match action.reapply(ctx) {
+            Ok(()) => {}
+            Err(Refused::Stale(reason)) => {
+                history.redo.clear();
+                return Err(format!("Can't redo {}: {reason}", action.label()));
+            }
+            Err(Refused::Failed(error)) => {
+                history.redo.push(action);
+                return Err(error);
+            }
+        }
+
+        let label = action.label();
+        history.undo.push_back(action);
+        history.seen = ctx.storage.mutation_count();
+        Ok(history.status(Some(label)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{session, StorageServiceBuilder};
+    use crate::services::timer_state::SessionType;
+
+    fn timer() -> TimerManager {
+        TimerManager::new(TimerConfig::default())
+    }
+
+    #[test]
+    fn undo_leaves_a_task_changed_since_alone() {
+        let storage = StorageServiceBuilder::new().build().unwrap();
+        let tasks = TaskManager::new(Vec::new(), Vec::new());
+        let undo = UndoManager::default();
+        let timer = timer();
+        let ctx = UndoContext {
+            storage: &storage,
+            tasks: &tasks,
+            timer: &timer,
+        };
+        let task = tasks.create(&storage, "Draft".to_string(), 1).unwrap();
+        let (before, after) = tasks.complete(&storage, &task.id, None).unwrap();
+        undo.record(&storage, UndoableAction::CompleteTask { before, after })
+            .unwrap();
+        tasks
+            .update(&storage, &task.id, "Final".to_string(), 2, None)
+            .unwrap();
+
+        assert!(undo.undo(&ctx).is_err());
+        let current = tasks.find(&task.id).unwrap();
+        assert_eq!(current.title, "Final");
+        assert!(current.completed);
+        assert!(!undo.status(&storage).unwrap().can_undo);
+    }
+
+    #[test]
+    fn any_change_after_an_undo_drops_the_redo_history() {
+        let storage = StorageServiceBuilder::new().build().unwrap();
+        let tasks = TaskManager::new(Vec::new(), Vec::new());
+        let undo = UndoManager::default();
+        let timer = timer();
+        let ctx = UndoContext {
+            storage: &storage,
+            tasks: &tasks,
+            timer: &timer,
+        };
+        let task = tasks.create(&storage, "Draft".to_string(), 1).unwrap();
+        let (before, after) = tasks.complete(&storage, &task.id, None).unwrap();
+        undo.record(&storage, UndoableAction::CompleteTask { before, after })
+            .unwrap();
+
+        assert!(undo.undo(&ctx).unwrap().can_redo);
+        assert!(!tasks.find(&task.id).unwrap().completed);
+        assert!(undo.redo(&ctx).unwrap().can_undo);
+        assert!(tasks.find(&task.id).unwrap().completed);
+        undo.undo(&ctx).unwrap();
+
+        tasks.create(&storage, "Other".to_string(), 1).unwrap();
+        assert!(!undo.status(&storage).unwrap().can_redo);
+        assert!(undo.redo(&ctx).is_err());
+    }
+
+    #[test]
+    fn deleted_sessions_come_back_until_the_day_changes() {
+        let storage = StorageServiceBuilder::new().build().unwrap();
+        let tasks = TaskManager::new(Vec::new(), Vec::new());
+        let undo = UndoManager::default();
+        let timer = timer();
+        let ctx = UndoContext {
+            storage: &storage,
+            tasks: &tasks,
+            timer: &timer,
+        };
+        let work = |start_time| session(SessionType::Work, start_time, 1500);
+        let date = "2026-03-02";
+        storage
+            .replace_sessions(date, vec![work(100), work(200)])
+            .unwrap();
+        let (deleted, remaining) = storage.delete_session(date, 0).unwrap();
+        undo.record(
+            &storage,
+            UndoableAction::DeleteSession {
+                date: date.to_string(),
+                index: 0,
+                session: deleted,
+                remaining,
+            },
+        )
+        .unwrap();
+
+        undo.undo(&ctx).unwrap();
+        let starts = |storage: &StorageService| -> Vec<u64> {
+            storage
+                .load_statistic(date)
+                .unwrap()
+                .unwrap()
+                .sessions
+                .iter()
+                .map(|session| session.start_time)
+                .collect()
+        };
+        assert_eq!(starts(&storage), [100, 200]);
+
+        storage.replace_sessions(date, vec![work(300)]).unwrap();
+        assert!(undo.redo(&ctx).is_err());
+        assert_eq!(starts(&storage), [300]);
+    }
+
+    #[test]
+    fn undoing_a_preset_restores_the_previous_timer_config() {
+        let storage = StorageServiceBuilder::new().build().unwrap();
+        let tasks = TaskManager::new(Vec::new(), Vec::new());
+        let undo = UndoManager::default();
+        let timer = timer();
+        let ctx = UndoContext {
+            storage: &storage,
+            tasks: &tasks,
+            timer: &timer,
+        };
+        let previous = storage.load_preferences().unwrap().timer_config;
+        let applied = TimerConfig {
+            work_duration: 50 * 60,
+            short_break_duration: 10 * 60,
+            ..previous.clone()
+        };
+        apply_config(&timer, &storage, applied.clone(), None).unwrap();
+        undo.record(
+            &storage,
+            UndoableAction::ApplyPreset {
+                name: "Deep work".to_string(),
+                previous: previous.clone(),
+                applied: applied.clone(),
+            },
+        )
+        .unwrap();
+        let saved = |storage: &StorageService| storage.load_preferences().unwrap().timer_config;
+
+        let status = undo.undo(&ctx).unwrap();
+        assert_eq!(status.action.as_deref(), Some("Apply preset \"Deep work\""));
+        assert_eq!(saved(&storage), previous);
+        assert_eq!(timer.get_data().unwrap().config, previous);
+
+        undo.redo(&ctx).unwrap();
+        assert_eq!(saved(&storage), applied);
+        assert_eq!(timer.get_data().unwrap().config, applied);
+    }
+}
@@ -0,0 +1,224 @@
+//! Checks the selected release channel for app updates and installs them.
+//! Installing restarts the app, so the download is held back while a focus
+//! session is running or paused, and the restart until the timer is idle.
+// Mobile builds are updated through the app stores.
+#![cfg_attr(mobile, allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::session_types::focus_session_active;
+
+/// Emitted with an [`UpdateInfo`] when the background check finds an update.
+pub const UPDATE_AVAILABLE_EVENT: &str = "updater://available";
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// How often a deferred install looks at the timer again.
+const DEFER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Release signing key, provided by the release pipeline. Builds without it
+/// never look for updates.
+const PUBKEY: Option<&str> = option_env!("TEMPUS_UPDATER_PUBKEY");
+
+/// Which releases to follow. Every beta is promoted to stable once it has
+/// settled, so switching back to stable only waits for the next promotion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    /// Each channel's manifest is attached to a rolling release of that name.
+    fn endpoint(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => {
+                "https://github.com/antlerai/tempus-ring/releases/download/updater-stable/latest.json"
+            }
+            UpdateChannel::Beta => {
+                "https://github.com/antlerai/tempus-ring/releases/download/updater-beta/latest.json"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    /// Release notes in Markdown.
+    pub notes: Option<String>,
+    /// RFC 3339 publish date, when the manifest has one.
+    pub published_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UpdateInstall {
+    UpToDate,
+    /// Downloading now; the app restarts when it is done and the timer is
+    /// idle.
+    Installing {
+        version: String,
+    },
+    /// Waiting for the current focus session to end.
+    Deferred {
+        version: String,
+    },
+}
+
+/// Tracks whether an install is already waiting, so repeated requests don't
+/// queue the download twice.
+#[derive(Default)]
+pub struct UpdaterService {
+    deferred: Mutex<bool>,
+}
+
+impl UpdaterService {
+    fn lock(&self) -> Result<MutexGuard<'_, bool>, String> {
+        self.deferred
+            .lock()
+            .map_err(|e| format!("Updater lock poisoned: {e}"))
+    }
+}
+
+#[cfg(desktop)]
+mod desktop {
+    use tauri::{AppHandle, Emitter, Manager};
+    use tauri_plugin_updater::{Update, UpdaterExt};
+
+    use super::*;
+    use crate::services::storage::StorageService;
+    use crate::services::timer_state::{TimerManager, TimerState};
+
+    fn info(update: &Update, channel: UpdateChannel) -> UpdateInfo {
+        UpdateInfo {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            channel,
+            notes: update.body.clone(),
+            published_at: update
+                .raw_json
+                .get("pub_date")
+                .and_then(|date| date.as_str())
+                .map(str::to_string),
+        }
+    }
+
+    /// The newest release on `channel`, if it is newer than this build.
+    pub async fn check(
+        app: &AppHandle,
+        channel: UpdateChannel,
+    ) -> Result<Option<(Update, UpdateInfo)>, String> {
+        let pubkey = PUBKEY.ok_or("This build was made without update signing")?;
+        let endpoint = channel
+            .endpoint()
+            .parse()
+            .map_err(|e| format!("Invalid update endpoint: {e}"))?;
+        let update = app
+            .updater_builder()
+            .pubkey(pubkey)
+            .endpoints(vec![endpoint])
+            .and_then(|builder| builder.build())
+            .map_err(|e| format!("Failed to set up updater: {e}"))?
+            .check()
+            .await
+            .map_err(|e| format!("Failed to check for updates: {e}"))?;
+        Ok(update.map(|update| {
+            let info = info(&update, channel);
+            (update, info)
+        }))
+    }
+
+    fn focus_running(app: &AppHandle) -> Result<bool, String> {
+        Ok(focus_session_active(
+            &app.state::<TimerManager>().get_data()?,
+        ))
+    }
+
+    fn timer_idle(app: &AppHandle) -> Result<bool, String> {
+        Ok(app.state::<TimerManager>().get_data()?.state == TimerState::Idle)
+    }
+
+    /// Installs `update` and restarts once nothing is being timed. A later
+    /// request may try again once this one is done.
+    async fn download_and_restart(app: AppHandle, update: Update) {
+        let installed = update.download_and_install(|_, _| {}, || {}).await;
+        match app.state::<UpdaterService>().lock() {
+            Ok(mut deferred) => *deferred = false,
+            Err(error) => log::error!("{error}"),
+        }
+        if let Err(error) = installed {
+            log::error!("Failed to install update {}: {error}", update.version);
+            return;
+        }
+        while !matches!(timer_idle(&app), Ok(true)) {
+            tokio::time::sleep(DEFER_POLL_INTERVAL).await;
+        }
+        app.restart();
+    }
+
+    /// Installs `update` now, or once the current focus session is over.
+    pub fn install(app: &AppHandle, update: Update) -> Result<UpdateInstall, String> {
+        let version = update.version.clone();
+        if !focus_running(app)? {
+            tauri::async_runtime::spawn(download_and_restart(app.clone(), update));
+            return Ok(UpdateInstall::Installing { version });
+        }
+
+        let updater = app.state::<UpdaterService>();
+        let mut deferred = updater.lock()?;
+        if !*deferred {
+            *deferred = true;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(DEFER_POLL_INTERVAL).await;
+                    if let Ok(false) = focus_running(&app) {
+                        break;
+                    }
+                }
+                download_and_restart(app, update).await;
+            });
+        }
+        Ok(UpdateInstall::Deferred { version })
+    }
+
+    /// Checks the preferred channel at launch and every few hours, emitting
+    /// [`UPDATE_AVAILABLE_EVENT`] for the UI to offer the update. Nothing is
+    /// installed without the user asking.
+    pub fn spawn_checker(app: AppHandle) {
+        if PUBKEY.is_none() {
+            return;
+        }
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                let channel = match app.state::<StorageService>().load_preferences() {
+                    Ok(preferences) => preferences.update_channel,
+                    Err(error) => {
+                        log::warn!("Update check skipped: {error}");
+                        continue;
+                    }
+                };
+                match check(&app, channel).await {
+                    Ok(Some((_, info))) => {
+                        let _ = app.emit(UPDATE_AVAILABLE_EVENT, info);
+                    }
+                    Ok(None) => {}
+                    Err(error) => log::error!("{error}"),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(desktop)]
+pub use desktop::{check, install, spawn_checker};
@@ -0,0 +1,296 @@
+//! A wellbeing report over a range of days: skipped breaks, overtime,
+//! late-night sessions and focus cap violations, with a few plain
+//! recommendations. A counterweight to the productivity statistics.
+
+use chrono::{NaiveDate, Timelike};
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::calendar::{parse_day, CalendarSettings};
+use super::event_log::{TimerEvent, TimerEventKind};
+use super::session_types::counts_as_pomodoro;
+use super::storage::{SessionData, TimerStatistic, UserPreferences};
+use super::timer_state::SessionType;
+
+/// Focus hours a day beyond which time counts as overtime when no daily
+/// focus cap is set.
+const DEFAULT_WORKDAY_HOURS: u32 = 8;
+/// Focus sessions touching these wall-clock hours count as late-night.
+const LATE_NIGHT_FROM_HOUR: u32 = 22;
+const LATE_NIGHT_UNTIL_HOUR: u32 = 5;
+const SECS_PER_HOUR: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WellbeingReport {
+    pub start_date: String,
+    /// Inclusive.
+    pub end_date: String,
+    pub breaks_taken: u32,
+    pub skipped_breaks: u32,
+    /// Fatigue advisories raised for runs of skipped breaks.
+    pub fatigue_advisories: u32,
+    /// Minutes of focus beyond the daily focus cap, or beyond 8 hours a
+    /// day without one.
+    pub overtime_minutes: u64,
+    /// Focus sessions running between 10pm and 5am.
+    pub late_night_sessions: u32,
+    /// Times a daily or weekly focus cap was crossed.
+    pub cap_violations: u32,
+    /// Work sessions held back from starting on their own by a cap.
+    pub refused_auto_starts: u32,
+    /// Suggestions drawn from the figures above, most pressing first.
+    pub recommendations: Vec<String>,
+}
+
+fn is_late_night(hour: u32) -> bool {
+    !(LATE_NIGHT_UNTIL_HOUR..LATE_NIGHT_FROM_HOUR).contains(&hour)
+}
+
+/// Whether `session` starts or ends between 10pm and 5am.
+pub(super) fn runs_late_at_night(session: &SessionData, calendar: &CalendarSettings) -> bool {
+    [session.start_time, session.span_end()]
+        .into_iter()
+        .any(|at| is_late_night(calendar.wall_time_of(at).hour()))
+}
+
+/// Builds the report for `start..=end` from the statistics and timer
+/// events of that range. `statistics` should include the days just outside
+/// it, whose sessions may run into it.
+pub fn build_report(
+    statistics: &[TimerStatistic],
+    events: &[TimerEvent],
+    start: NaiveDate,
+    end: NaiveDate,
+    preferences: &UserPreferences,
+) -> WellbeingReport {
+    let calendar = &preferences.calendar;
+    let custom_types = &preferences.timer_config.custom_session_types;
+    let in_range = |date: NaiveDate| date >= start && date <= end;
+    let days: Vec<&TimerStatistic> = statistics
+        .iter()
+        .filter(|statistic| parse_day(&statistic.date).is_some_and(in_range))
+        .collect();
+
+    let (mut breaks_taken, mut skipped_breaks) = (0, 0);
+    for event in events {
+        if !matches!(
+            event.session_type,
+            SessionType::ShortBreak | SessionType::LongBreak
+        ) || !in_range(calendar.day_of(event.timestamp))
+        {
+            continue;
+        }
+        match event.kind {
+            TimerEventKind::Complete => breaks_taken += 1,
+            TimerEventKind::Skip => skipped_breaks += 1,
+            _ => {}
+        }
+    }
+
+    let workday_hours = match preferences.focus_budget.daily_hours {
+        0 => DEFAULT_WORKDAY_HOURS,
+        hours => hours,
+    };
+    let workday = u64::from(workday_hours) * SECS_PER_HOUR;
+    let overtime_minutes = calendar
+        .focus_by_day(statistics, custom_types)
+        .into_iter()
+        .filter(|(date, _)| in_range(*date))
+        .map(|(_, focus)| focus.saturating_sub(workday))
+        .sum::<u64>()
+        / 60;
+
+    let late_night_sessions = days
+        .iter()
+        .flat_map(|day| &day.sessions)
+        .filter(|session| counts_as_pomodoro(&session.session_type, custom_types))
+        .filter(|session| runs_late_at_night(session, calendar))
+        .count() as u32;
+
+    let overwork = days.iter().flat_map(|day| &day.overwork);
+    let refused_auto_starts = overwork
+        .clone()
+        .filter(|event| event.refused_auto_start)
+        .count() as u32;
+    let cap_violations = overwork.count() as u32 - refused_auto_starts;
+    let fatigue_advisories = days.iter().map(|day| day.advisories.len() as u32).sum();
+
+    let mut recommendations = Vec::new();
+    if cap_violations > 0 {
+        recommendations.push(format!(
+            "Your focus cap was reached {cap_violations} times. Plan lighter days after long ones."
+        ));
+    }
+    if overtime_minutes > 0 {
+        recommendations.push(format!(
+            "You focused {overtime_minutes} minutes beyond {workday_hours} hours a day. \
+             Consider stopping at a set time."
+        ));
+    }
+    if late_night_sessions > 0 {
+        recommendations.push(format!(
+            "{late_night_sessions} sessions ran late at night. Winding down before 10pm helps sleep."
+        ));
+    }
+    if skipped_breaks > breaks_taken {
+        recommendations.push(
+            "You skipped more breaks than you took. Short breaks keep focus up for longer."
+                .to_string(),
+        );
+    } else if skipped_breaks > 0 {
+        recommendations.push(format!(
+            "You skipped {skipped_breaks} breaks. Try to take each one, even briefly."
+        ));
+    }
+    if recommendations.is_empty() {
+        recommendations.push("No warning signs in this period.".to_string());
+    }
+
+    WellbeingReport {
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.format("%Y-%m-%d").to_string(),
+        breaks_taken,
+        skipped_breaks,
+        fatigue_advisories,
+        overtime_minutes,
+        late_night_sessions,
+        cap_violations,
+        refused_auto_starts,
+        recommendations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::focus_budget::{BudgetPeriod, OverworkEvent};
+    use crate::services::storage::{day, session};
+
+    fn unix(day: u32, hour: u32, minute: u32) -> u64 {
+        NaiveDate::from_ymd_opt(2026, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp() as u64
+    }
+
+    fn march(
+        date: u32,
+        sessions: Vec<SessionData>,
+        overwork: Vec<OverworkEvent>,
+    ) -> TimerStatistic {
+        TimerStatistic {
+            overwork,
+            ..day(&format!("2026-03-{date:02}"), sessions)
+        }
+    }
+
+    fn event(kind: TimerEventKind, session_type: SessionType, timestamp: u64) -> TimerEvent {
+        TimerEvent {
+            kind,
+            session_type,
+            remaining_time: 0,
+            timestamp,
+        }
+    }
+
+    fn overwork(refused_auto_start: bool) -> OverworkEvent {
+        OverworkEvent {
+            timestamp: unix(2, 17, 0),
+            period: BudgetPeriod::Day,
+            focus_time: 8 * SECS_PER_HOUR,
+            cap: 8 * SECS_PER_HOUR,
+            refused_auto_start,
+        }
+    }
+
+    fn preferences() -> UserPreferences {
+        let mut preferences = UserPreferences::default();
+        preferences.calendar.utc_offset_minutes = Some(0);
+        preferences
+    }
+
+    #[test]
+    fn late_night_runs_from_ten_until_five() {
+        assert!(is_late_night(22));
+        assert!(is_late_night(4));
+        assert!(!is_late_night(5));
+        assert!(!is_late_night(21));
+    }
+
+    #[test]
+    fn the_report_counts_only_the_range() {
+        let march_2 = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let statistics = [
+            march(
+                2,
+                vec![
+                    session(
+                        SessionType::Work,
+                        unix(2, 8, 0),
+                        8 * SECS_PER_HOUR + 30 * 60,
+                    ),
+                    session(SessionType::Work, unix(2, 22, 30), 25 * 60),
+                ],
+                vec![overwork(false), overwork(true)],
+            ),
+            march(
+                3,
+                vec![session(SessionType::Work, unix(3, 23, 0), 25 * 60)],
+                vec![overwork(false)],
+            ),
+        ];
+        let events = [
+            event(
+                TimerEventKind::Complete,
+                SessionType::ShortBreak,
+                unix(2, 10, 0),
+            ),
+            event(
+                TimerEventKind::Skip,
+                SessionType::ShortBreak,
+                unix(2, 11, 0),
+            ),
+            event(TimerEventKind::Skip, SessionType::LongBreak, unix(2, 12, 0)),
+            event(TimerEventKind::Skip, SessionType::Work, unix(2, 13, 0)),
+            event(
+                TimerEventKind::Skip,
+                SessionType::ShortBreak,
+                unix(3, 10, 0),
+            ),
+        ];
+        let report = build_report(&statistics, &events, march_2, march_2, &preferences());
+
+        assert_eq!((report.breaks_taken, report.skipped_breaks), (1, 2));
+        // 8h55m of focus against the default 8 hour day.
+        assert_eq!(report.overtime_minutes, 55);
+        assert_eq!(report.late_night_sessions, 1);
+        assert_eq!((report.cap_violations, report.refused_auto_starts), (1, 1));
+        assert_eq!(report.recommendations.len(), 4);
+        assert!(report.recommendations[3].starts_with("You skipped more breaks"));
+    }
+
+    #[test]
+    fn a_daily_cap_replaces_the_default_workday() {
+        let march_2 = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let statistics = [march(
+            2,
+            vec![session(
+                SessionType::Work,
+                unix(2, 8, 0),
+                8 * SECS_PER_HOUR + 30 * 60,
+            )],
+            Vec::new(),
+        )];
+        let mut preferences = preferences();
+        preferences.focus_budget.daily_hours = 9;
+        let report = build_report(&statistics, &[], march_2, march_2, &preferences);
+        assert_eq!(report.overtime_minutes, 0);
+        assert_eq!(
+            report.recommendations,
+            vec!["No warning signs in this period.".to_string()]
+        );
+    }
+}
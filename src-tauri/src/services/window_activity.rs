@@ -0,0 +1,343 @@
+//! Opt-in notes on what a work session was spent on: the app and title of
+//! the window in front are sampled every few seconds while a focus session
+//! runs, on a blocking task off the tick loop, and the three seen most are
+//! kept on the session record. Windows seen too little while the samples
+//! are added up share one "Other windows" entry. Nothing typed is read, and nothing is
+//! sampled unless `window_annotation` is on.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use super::session_types::focus_session_active;
+use super::storage::UserPreferences;
+use super::timer_state::{TimerData, TimerState};
+
+/// Windows kept per session.
+pub const TOP_WINDOWS: usize = 3;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_TITLE_CHARS: usize = 120;
+/// Samples kept before repeats are added up.
+const MAX_SAMPLES: usize = 64;
+/// Distinct windows kept when adding up; the rest go to [`OTHER_WINDOWS`].
+const MAX_WINDOWS: usize = 32;
+/// The app of the bucket holding windows seen too little to keep apart.
+pub const OTHER_WINDOWS: &str = "Other windows";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct WindowFocus {
+    pub app: String,
+    pub title: String,
+    /// About how many seconds it was in front.
+    pub seconds: u64,
+}
+
+/// The `TOP_WINDOWS` windows of `windows` in front longest, adding up
+/// repeats, longest first.
+pub fn top(windows: impl IntoIterator<Item = WindowFocus>) -> Vec<WindowFocus> {
+    let mut windows = summarize(windows);
+    windows.sort_by_key(|window| Reverse(window.seconds));
+    windows.truncate(TOP_WINDOWS);
+    windows
+}
+
+#[cfg(target_os = "linux")]
+fn front_window() -> Option<(String, String)> {
+    let id = super::presenter_mode::x11_active_window()?;
+    // _NET_WM_NAME(UTF8_STRING) = "Inbox - Mail"
+    // WM_CLASS(STRING) = "Navigator", "firefox"
+    let properties =
+        super::focus_mode::run("xprop", &["-id", &id, "_NET_WM_NAME", "WM_CLASS"]).ok()?;
+    let value = |prefix: &str| {
+        properties
+            .lines()
+            .find_map(|line| line.strip_prefix(prefix))
+            .map(str::to_string)
+    };
+    let app = value("WM_CLASS(STRING) = ")?
+        .rsplit(", ")
+        .next()?
+        .trim_matches('"')
+        .to_string();
+    let title = value("_NET_WM_NAME(UTF8_STRING) = ")
+        .map(|title| title.trim_matches('"').to_string())
+        .unwrap_or_default();
+    Some((app, title))
+}
+
+#[cfg(target_os = "macos")]
+fn front_window() -> Option<(String, String)> {
+    // Window titles need the accessibility permission.
+    let script =
+        "tell application \"System Events\" to tell (first process whose frontmost is true) \
+                  to return name & \"|\" & (name of front window)";
+    let output = super::focus_mode::run("osascript", &["-e", script]).ok()?;
+    let (app, title) = output.split_once('|')?;
+    Some((app.to_string(), title.to_string()))
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "user32")]
+extern "system" {
+    fn GetForegroundWindow() -> *mut std::ffi::c_void;
+    fn GetWindowTextW(window: *mut std::ffi::c_void, text: *mut u16, max: i32) -> i32;
+    fn GetWindowThreadProcessId(window: *mut std::ffi::c_void, process: *mut u32) -> u32;
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn OpenProcess(access: u32, inherit: i32, process: u32) -> *mut std::ffi::c_void;
+    fn QueryFullProcessImageNameW(
+        process: *mut std::ffi::c_void,
+        flags: u32,
+        name: *mut u16,
+        size: *mut u32,
+    ) -> i32;
+    fn CloseHandle(handle: *mut std::ffi::c_void) -> i32;
+}
+
+#[cfg(target_os = "windows")]
+fn front_window() -> Option<(String, String)> {
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    let mut title = [0u16; 512];
+    let mut image = [0u16; 1024];
+    let mut image_len = image.len() as u32;
+    // SAFETY: every buffer is passed with its length, and the process
+    // handle is closed before returning.
+    let (title_len, image_len) = unsafe {
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return None;
+        }
+        let title_len = GetWindowTextW(window, title.as_mut_ptr(), title.len() as i32);
+        let mut process_id = 0;
+        GetWindowThreadProcessId(window, &mut process_id);
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+        if process.is_null() {
+            return None;
+        }
+        let found = QueryFullProcessImageNameW(process, 0, image.as_mut_ptr(), &mut image_len);
+        CloseHandle(process);
+        if found == 0 {
+            return None;
+        }
+        (title_len.max(0) as usize, image_len as usize)
+    };
+    let image = String::from_utf16_lossy(&image[..image_len]);
+    let app = std::path::Path::new(&image)
+        .file_stem()?
+        .to_string_lossy()
+        .into_owned();
+    Some((app, String::from_utf16_lossy(&title[..title_len])))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn front_window() -> Option<(String, String)> {
+    None
+}
+
+struct TrackerState {
+    enabled: bool,
+    /// Start of the focus session the samples belong to.
+    session_start: Option<u64>,
+    samples: Vec<WindowFocus>,
+    sampled_at: Option<Instant>,
+}
+
+/// Samples the window in front during focus sessions, for
+/// [`session_recorder`](super::session_recorder) to attach.
+pub struct WindowTracker {
+    state: Mutex<TrackerState>,
+}
+
+impl WindowTracker {
+    pub fn new(preferences: &UserPreferences) -> Self {
+        Self {
+            state: Mutex::new(TrackerState {
+                enabled: preferences.window_annotation,
+                session_start: None,
+                samples: Vec::new(),
+                sampled_at: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, TrackerState>, String> {
+        self.state
+            .lock()
+            .map_err(|e| format!("Window tracker lock poisoned: {e}"))
+    }
+
+    /// Picks up the opt-in; turning it off drops what was sampled.
+    pub fn configure(&self, preferences: &UserPreferences) -> Result<(), String> {
+        let mut state = self.lock()?;
+        state.enabled = preferences.window_annotation;
+        if !state.enabled {
+            state.session_start = None;
+            state.samples.clear();
+        }
+        Ok(())
+    }
+
+    /// Called by the tick loop: samples the window in front once
+    /// `SAMPLE_INTERVAL` has passed, while a focus session counts.
+    pub fn on_tick(&self, app: &AppHandle, data: &TimerData) -> Result<(), String> {
+        let mut state = self.lock()?;
+        if !state.enabled || data.state == TimerState::Paused || !focus_session_active(data) {
+            state.sampled_at = None;
+            return Ok(());
+        }
+        if state.session_start != data.start_time {
+            state.session_start = data.start_time;
+            state.samples.clear();
+        }
+        if state
+            .sampled_at
+            .is_some_and(|sampled_at| sampled_at.elapsed() < SAMPLE_INTERVAL)
+        {
+            return Ok(());
+        }
+        state.sampled_at = Some(Instant::now());
+        let (app, session_start) = (app.clone(), state.session_start);
+        tauri::async_runtime::spawn_blocking(move || {
+            let Some(window) = front_window() else {
+                return;
+            };
+            if let Err(error) = app.state::<WindowTracker>().add(session_start, window) {
+                log::error!("{error}");
+            }
+        });
+        Ok(())
+    }
+
+    /// Keeps a sample taken during the session that started at
+    /// `session_start`, unless that one is over.
+    fn add(
+        &self,
+        session_start: Option<u64>,
+        (app, title): (String, String),
+    ) -> Result<(), String> {
+        let mut state = self.lock()?;
+        if state.session_start != session_start {
+            return Ok(());
+        }
+        state.samples.push(WindowFocus {
+            app,
+            title: title.chars().take(MAX_TITLE_CHARS).collect(),
+            seconds: SAMPLE_INTERVAL.as_secs(),
+        });
+        if state.samples.len() >= MAX_SAMPLES {
+            let samples = std::mem::take(&mut state.samples);
+            state.samples = compact(samples);
+        }
+        Ok(())
+    }
+
+    /// The windows in front longest during the focus session that started
+    /// at `start_time`, if that is the one sampled. They are handed out
+    /// once.
+    pub fn take(&self, start_time: u64) -> Result<Vec<WindowFocus>, String> {
+        let mut state = self.lock()?;
+        if state.session_start != Some(start_time) {
+            return Ok(Vec::new());
+        }
+        state.session_start = None;
+        Ok(top(std::mem::take(&mut state.samples)))
+    }
+}
+
+/// Adds up repeated windows without dropping any.
+fn summarize(samples: impl IntoIterator<Item = WindowFocus>) -> Vec<WindowFocus> {
+    let mut seconds: HashMap<(String, String), u64> = HashMap::new();
+    for sample in samples {
+        *seconds.entry((sample.app, sample.title)).or_default() += sample.seconds;
+    }
+    seconds
+        .into_iter()
+        .map(|((app, title), seconds)| WindowFocus {
+            app,
+            title,
+            seconds,
+        })
+        .collect()
+}
+
+/// Adds up repeated windows, keeping the `MAX_WINDOWS - 1` seen most and
+/// folding the rest into one [`OTHER_WINDOWS`] entry.
+fn compact(samples: impl IntoIterator<Item = WindowFocus>) -> Vec<WindowFocus> {
+    let (mut other, mut windows): (Vec<_>, Vec<_>) = summarize(samples)
+        .into_iter()
+        .partition(|window| window.app == OTHER_WINDOWS && window.title.is_empty());
+    windows.sort_by_key(|window| Reverse(window.seconds));
+    if windows.len() >= MAX_WINDOWS {
+        other.extend(windows.split_off(MAX_WINDOWS - 1));
+    }
+    let seconds: u64 = other.iter().map(|window| window.seconds).sum();
+    if seconds > 0 {
+        windows.push(WindowFocus {
+            app: OTHER_WINDOWS.to_string(),
+            title: String::new(),
+            seconds,
+        });
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(title: &str, seconds: u64) -> WindowFocus {
+        WindowFocus {
+            app: "editor".to_string(),
+            title: title.to_string(),
+            seconds,
+        }
+    }
+
+    #[test]
+    fn compacting_caps_distinct_windows_and_keeps_the_time() {
+        let samples: Vec<_> = (0..100)
+            .map(|index| window(&format!("file {index}"), 5))
+            .chain(std::iter::repeat_n(window("main.rs", 5), 10))
+            .collect();
+        let windows = compact(samples);
+        assert_eq!(windows.len(), MAX_WINDOWS);
+        assert_eq!(windows[0], window("main.rs", 50));
+        assert_eq!(
+            windows.iter().map(|window| window.seconds).sum::<u64>(),
+            550
+        );
+
+        // Compacting again folds the old bucket in rather than adding one.
+        let again = compact(windows.into_iter().chain([window("new", 5)]));
+        assert_eq!(again.len(), MAX_WINDOWS);
+        let others = again
+            .iter()
+            .filter(|window| window.app == OTHER_WINDOWS)
+            .count();
+        assert_eq!(others, 1);
+    }
+
+    #[test]
+    fn a_session_with_endless_new_titles_stays_bounded() {
+        let tracker = WindowTracker::new(&UserPreferences::default());
+        tracker.lock().unwrap().session_start = Some(1);
+        for index in 0..1000 {
+            tracker
+                .add(Some(1), ("browser".to_string(), format!("tab {index}")))
+                .unwrap();
+            assert!(tracker.lock().unwrap().samples.len() < MAX_SAMPLES);
+        }
+        let top = tracker.take(1).unwrap();
+        assert_eq!(top[0].app, OTHER_WINDOWS);
+        assert_eq!(top.len(), TOP_WINDOWS);
+    }
+}
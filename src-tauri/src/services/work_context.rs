@@ -0,0 +1,114 @@
+//! Coarse work contexts such as Home or Office, attached to sessions as
+//! they are recorded so focus can be compared between places. The context
+//! is picked by hand or, only if the user opts in, inferred by
+//! [`ContextDetector`](super::context_detection::ContextDetector).
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+use super::session_types::{counts_as_pomodoro, CustomSessionType};
+use super::storage::TimerStatistic;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkContext {
+    /// Stored on sessions, e.g. `Office`.
+    pub name: String,
+    /// Wi-Fi networks that mean this context.
+    pub ssids: Vec<String>,
+    /// Hostnames that mean this context, such as a work laptop's.
+    pub hostnames: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct WorkContextSettings {
+    pub contexts: Vec<WorkContext>,
+    /// Picked with `set_work_context`; wins over detection.
+    pub manual: Option<String>,
+    /// Infer the context from the Wi-Fi network and hostname. Off unless
+    /// turned on, as it reads which network the computer is on.
+    pub detect: bool,
+}
+
+impl WorkContextSettings {
+    /// The context whose networks include `ssid`, or else whose hostnames
+    /// include `hostname`.
+    pub fn matching(&self, ssid: Option<&str>, hostname: Option<&str>) -> Option<&WorkContext> {
+        let by_ssid = ssid.and_then(|ssid| {
+            self.contexts
+                .iter()
+                .find(|context| context.ssids.iter().any(|known| known == ssid))
+        });
+        by_ssid.or_else(|| {
+            let hostname = hostname?;
+            self.contexts.iter().find(|context| {
+                context
+                    .hostnames
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(hostname))
+            })
+        })
+    }
+}
+
+/// Focus figures for the sessions recorded in one context.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ContextFocus {
+    /// `None` for sessions recorded without a context.
+    pub context: Option<String>,
+    /// Sessions of types that count as pomodoros, finished or not.
+    pub focus_sessions: u32,
+    pub completed_sessions: u32,
+    /// Share of focus sessions completed rather than skipped or reset.
+    pub completion_rate: f64,
+    /// Seconds of completed focus.
+    pub focus_time: u64,
+    /// Mean length of a completed focus session in seconds.
+    pub average_session: u64,
+}
+
+/// Focus sessions in `statistics` grouped by context, most focus first.
+pub fn compare(
+    statistics: &[TimerStatistic],
+    custom_types: &[CustomSessionType],
+) -> Vec<ContextFocus> {
+    let mut by_context: BTreeMap<Option<String>, ContextFocus> = BTreeMap::new();
+    let sessions = statistics
+        .iter()
+        .flat_map(|statistic| &statistic.sessions)
+        .filter(|session| counts_as_pomodoro(&session.session_type, custom_types));
+    for session in sessions {
+        let focus = by_context
+            .entry(session.context.clone())
+            .or_insert_with(|| ContextFocus {
+                context: session.context.clone(),
+                focus_sessions: 0,
+                completed_sessions: 0,
+                completion_rate: 0.0,
+                focus_time: 0,
+                average_session: 0,
+            });
+        focus.focus_sessions += 1;
+        if session.completed {
+            focus.completed_sessions += 1;
+            focus.focus_time += session.duration;
+        }
+    }
+
+    let mut contexts: Vec<ContextFocus> = by_context.into_values().collect();
+    for focus in &mut contexts {
+        focus.completion_rate =
+            f64::from(focus.completed_sessions) / f64::from(focus.focus_sessions);
+        focus.average_session = focus
+            .focus_time
+            .checked_div(u64::from(focus.completed_sessions))
+            .unwrap_or(0);
+    }
+    contexts.sort_by_key(|focus| Reverse(focus.focus_time));
+    contexts
+}
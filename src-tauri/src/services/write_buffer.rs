@@ -0,0 +1,333 @@
+//! Keeps statistics and timer events that could not be written in memory,
+//! e.g. while the disk is full or the data directory is read-only, and
+//! writes them once storage works again.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::calendar::{self, parse_day};
+use super::event_log::TimerEvent;
+use super::inbox::{self, InboxKind};
+use super::storage::{StorageService, TimerStatistic};
+
+/// Emitted with a [`StorageHealth`] whenever it changes.
+pub const STORAGE_HEALTH_EVENT: &str = "storage://health";
+/// How often held writes are retried.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+pub struct StorageHealth {
+    /// Writes are failing and being held in memory until they succeed.
+    pub degraded: bool,
+    /// Days and events waiting to be written.
+    pub pending_writes: usize,
+    pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct Pending {
+    /// Latest unwritten copy of each day, keyed by date.
+    statistics: BTreeMap<String, TimerStatistic>,
+    /// Unwritten events, oldest first.
+    events: Vec<TimerEvent>,
+    last_error: Option<String>,
+}
+
+impl Pending {
+    fn health(&self) -> StorageHealth {
+        let pending_writes = self.statistics.len() + self.events.len();
+        StorageHealth {
+            degraded: pending_writes > 0,
+            pending_writes,
+            last_error: self.last_error.clone(),
+        }
+    }
+
+    fn write_events(
+        &mut self,
+        write: impl FnOnce(&[TimerEvent]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        if self.events.is_empty() {
+            return Ok(());
+        }
+        write(&self.events)?;
+        self.events.clear();
+        Ok(())
+    }
+
+    fn settle(&mut self, result: Result<(), String>) {
+        match result {
+            Err(error) => self.last_error = Some(error),
+            Ok(()) if self.statistics.is_empty() && self.events.is_empty() => {
+                self.last_error = None;
+            }
+            Ok(()) => {}
+        }
+    }
+}
+
+/// Writes go through the buffer so a held copy is never written over a
+/// newer one.
+#[derive(Default)]
+pub struct WriteBuffer {
+    pending: Mutex<Pending>,
+}
+
+impl WriteBuffer {
+    fn lock(&self) -> Result<MutexGuard<'_, Pending>, String> {
+        self.pending
+            .lock()
+            .map_err(|e| format!("Write buffer lock poisoned: {e}"))
+    }
+
+    /// Writes `statistic`, holding it for a later retry if that fails. The
+    /// failure is still returned so callers know it isn't on disk yet.
+    pub fn save_statistic(
+        &self,
+        statistic: &TimerStatistic,
+        write: impl FnOnce(&TimerStatistic) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut pending = self.lock()?;
+        match write(statistic) {
+            Ok(()) => {
+                pending.statistics.remove(&statistic.date);
+                Ok(())
+            }
+            Err(error) => {
+                log::warn!("Holding statistic for {}: {error}", statistic.date);
+                pending
+                    .statistics
+                    .insert(statistic.date.clone(), statistic.clone());
+                pending.last_error = Some(error.clone());
+                Err(error)
+            }
+        }
+    }
+
+    /// Appends `events` after any held ones, holding them all if that fails.
+    pub fn append_events(
+        &self,
+        events: &[TimerEvent],
+        write: impl FnOnce(&[TimerEvent]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut pending = self.lock()?;
+        pending.events.extend_from_slice(events);
+        let result = pending.write_events(write);
+        if let Err(error) = &result {
+            log::warn!("Holding {} timer events: {error}", pending.events.len());
+        }
+        pending.settle(result);
+        Ok(())
+    }
+
+    /// The held copy of the day, which is newer than the stored one.
+    pub fn statistic(&self, date: &str) -> Result<Option<TimerStatistic>, String> {
+        Ok(self.lock()?.statistics.get(date).cloned())
+    }
+
+    /// Keys of the held days.
+    pub fn dates(&self) -> Result<Vec<String>, String> {
+        Ok(self.lock()?.statistics.keys().cloned().collect())
+    }
+
+    /// Replaces stored days in `statistics` with held copies and adds held
+    /// days within the optional inclusive bounds, keeping date order.
+    pub fn overlay(
+        &self,
+        statistics: &mut Vec<TimerStatistic>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<(), String> {
+        let pending = self.lock()?;
+        let held = pending
+            .statistics
+            .values()
+            .filter(|statistic| calendar::in_range(&statistic.date, start_date, end_date));
+        for statistic in held {
+            let date = parse_day(&statistic.date);
+            match statistics.binary_search_by_key(&date, |stored| parse_day(&stored.date)) {
+                Ok(index) => statistics[index] = statistic.clone(),
+                Err(index) => statistics.insert(index, statistic.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Held events, oldest first.
+    pub fn events(&self) -> Result<Vec<TimerEvent>, String> {
+        Ok(self.lock()?.events.clone())
+    }
+
+    /// Retries every held write, stopping at the first failure.
+    pub fn flush(
+        &self,
+        write_statistic: impl Fn(&TimerStatistic) -> Result<(), String>,
+        write_events: impl FnOnce(&[TimerEvent]) -> Result<(), String>,
+    ) -> Result<StorageHealth, String> {
+        let mut pending = self.lock()?;
+        let mut result = Ok(());
+        while let Some(entry) = pending.statistics.first_entry() {
+            if let Err(error) = write_statistic(entry.get()) {
+                result = Err(error);
+                break;
+            }
+            entry.remove();
+        }
+        if result.is_ok() {
+            result = pending.write_events(write_events);
+        }
+        pending.settle(result);
+        Ok(pending.health())
+    }
+
+    pub fn health(&self) -> Result<StorageHealth, String> {
+        Ok(self.lock()?.health())
+    }
+}
+
+/// Retries held writes every [`FLUSH_INTERVAL`] and emits
+/// [`STORAGE_HEALTH_EVENT`] when storage degrades or recovers.
+pub fn spawn_flusher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        let mut reported = StorageHealth::default();
+        loop {
+            interval.tick().await;
+            let health = match app.state::<StorageService>().flush_pending() {
+                Ok(health) => health,
+                Err(error) => {
+                    log::error!("Flushing held writes failed: {error}");
+                    continue;
+                }
+            };
+            if health == reported {
+                continue;
+            }
+            if health.degraded && !reported.degraded {
+                inbox::record_or_log(
+                    &app,
+                    InboxKind::StorageDegraded,
+                    "Saving data is failing",
+                    health.last_error.clone(),
+                );
+            }
+            if let Err(error) = app.emit(STORAGE_HEALTH_EVENT, health.clone()) {
+                log::error!("Failed to emit {STORAGE_HEALTH_EVENT}: {error}");
+            }
+            reported = health;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::event_log::TimerEventKind;
+    use crate::services::storage::day;
+    use crate::services::timer_state::SessionType;
+
+    fn statistic(date: &str) -> TimerStatistic {
+        TimerStatistic {
+            completed_pomodoros: 1,
+            total_focus_time: 25 * 60,
+            ..day(date, Vec::new())
+        }
+    }
+
+    #[test]
+    fn failed_saves_are_reported_and_held_until_flushed() {
+        let buffer = WriteBuffer::default();
+        let day = statistic("2026-03-02");
+        let result = buffer.save_statistic(&day, |_| Err("Disk full".to_string()));
+        assert_eq!(result, Err("Disk full".to_string()));
+        assert!(buffer.statistic("2026-03-02").unwrap().is_some());
+        assert_eq!(buffer.health().unwrap().pending_writes, 1);
+
+        let health = buffer.flush(|_| Ok(()), |_| Ok(())).unwrap();
+        assert_eq!(health, StorageHealth::default());
+        assert!(buffer.statistic("2026-03-02").unwrap().is_none());
+    }
+
+    fn event(timestamp: u64) -> TimerEvent {
+        TimerEvent {
+            kind: TimerEventKind::Start,
+            session_type: SessionType::Work,
+            remaining_time: 0,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn held_events_go_out_first_and_in_order() {
+        let buffer = WriteBuffer::default();
+        buffer
+            .append_events(&[event(1)], |_| Err("Read-only".to_string()))
+            .unwrap();
+        let health = buffer.health().unwrap();
+        assert!(health.degraded);
+        assert_eq!(health.last_error.as_deref(), Some("Read-only"));
+
+        let mut written = Vec::new();
+        buffer
+            .append_events(&[event(2)], |events| {
+                written.extend(events.iter().map(|event| event.timestamp));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(written, [1, 2]);
+        assert_eq!(buffer.health().unwrap(), StorageHealth::default());
+    }
+
+    #[test]
+    fn a_flush_stops_at_the_first_failure() {
+        let buffer = WriteBuffer::default();
+        for date in ["2026-03-01", "2026-03-02"] {
+            let _ = buffer.save_statistic(&statistic(date), |_| Err("Disk full".to_string()));
+        }
+        buffer
+            .append_events(&[event(1)], |_| Err("Disk full".to_string()))
+            .unwrap();
+
+        let health = buffer
+            .flush(
+                |day| match day.date.as_str() {
+                    "2026-03-01" => Ok(()),
+                    _ => Err("Still full".to_string()),
+                },
+                |_| panic!("events are written after the days"),
+            )
+            .unwrap();
+        assert_eq!(buffer.dates().unwrap(), ["2026-03-02"]);
+        assert_eq!(health.pending_writes, 2);
+        assert_eq!(health.last_error.as_deref(), Some("Still full"));
+    }
+
+    #[test]
+    fn held_days_overlay_stored_ones_within_the_range() {
+        let buffer = WriteBuffer::default();
+        for date in ["2026-03-02", "2026-03-03", "2026-03-09"] {
+            let mut held = statistic(date);
+            held.completed_pomodoros = 9;
+            let _ = buffer.save_statistic(&held, |_| Err("Disk full".to_string()));
+        }
+        let mut statistics = vec![statistic("2026-03-01"), statistic("2026-03-03")];
+        buffer
+            .overlay(&mut statistics, None, parse_day("2026-03-05"))
+            .unwrap();
+
+        let days: Vec<_> = statistics
+            .iter()
+            .map(|day| (day.date.as_str(), day.completed_pomodoros))
+            .collect();
+        assert_eq!(
+            days,
+            [("2026-03-01", 1), ("2026-03-02", 9), ("2026-03-03", 9)]
+        );
+    }
+}
@@ -0,0 +1,354 @@
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::contribution::{build_contribution_graph, ContributionGraph, DEFAULT_THRESHOLDS};
+use super::days_off::DaysOffSettings;
+use super::duration_format::{format_duration, DurationStyle};
+use super::pdf::{Font, PdfPage, PAGE_HEIGHT, PAGE_WIDTH};
+use super::storage::TimerStatistic;
+use super::tasks::Task;
+use super::timer_state::SessionType;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const TOP_TASK_COUNT: usize = 5;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct MonthTotal {
+    /// 1-based.
+    pub month: u32,
+    pub name: String,
+    pub focus_hours: f64,
+    pub pomodoros: u32,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TaskTotal {
+    pub task_id: String,
+    pub title: String,
+    pub pomodoros: u32,
+    pub focus_hours: f64,
+}
+
+/// "Year in Focus" summary for one calendar year.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct YearInReview {
+    pub year: i32,
+    pub total_focus_hours: f64,
+    pub total_pomodoros: u32,
+    pub active_days: u32,
+    pub longest_streak_days: u32,
+    pub busiest_month: Option<MonthTotal>,
+    pub months: Vec<MonthTotal>,
+    pub top_tasks: Vec<TaskTotal>,
+    pub comparisons: Vec<String>,
+    pub contributions: ContributionGraph,
+}
+
+fn hours(seconds: u64) -> f64 {
+    seconds as f64 / 3600.0
+}
+
+/// Length of the longest run of consecutive days in `dates`. Days off in
+/// between don't break a run, nor count towards it.
+pub fn longest_streak(dates: &[NaiveDate], days_off: &DaysOffSettings) -> u32 {
+    let mut sorted = dates.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let (mut longest, mut current) = (0, 0);
+    let mut previous: Option<NaiveDate> = None;
+    for date in sorted {
+        let bridged = previous.is_some_and(|previous| {
+            previous
+                .iter_days()
+                .skip(1)
+                .take_while(|day| *day < date)
+                .all(|day| days_off.is_day_off(day))
+        });
+        current = if bridged { current + 1 } else { 1 };
+        longest = longest.max(current);
+        previous = Some(date);
+    }
+    longest
+}
+
+fn comparisons(total_hours: f64) -> Vec<String> {
+    // Rough reference durations, in hours.
+    let references = [
+        (11.4, "watching the extended Lord of the Rings trilogy"),
+        (4.5, "running a marathon at an average pace"),
+        (7.0, "flying from London to New York"),
+    ];
+    references
+        .iter()
+        .filter_map(|&(reference, activity)| {
+            let times = total_hours / reference;
+            (times >= 1.0).then(|| format!("That's like {activity} {times:.0} times"))
+        })
+        .collect()
+}
+
+/// Builds the review for `year` from that year's statistics and the task list
+/// (used to resolve task titles).
+pub fn build_year_in_review(
+    year: i32,
+    statistics: &[TimerStatistic],
+    tasks: &[Task],
+    days_off: &DaysOffSettings,
+) -> YearInReview {
+    let mut months: Vec<MonthTotal> = MONTH_NAMES
+        .iter()
+        .enumerate()
+        .map(|(index, name)| MonthTotal {
+            month: index as u32 + 1,
+            name: name.to_string(),
+            focus_hours: 0.0,
+            pomodoros: 0,
+        })
+        .collect();
+    let mut active_dates = Vec::new();
+    let mut task_totals: HashMap<&str, (u32, u64)> = HashMap::new();
+    let (mut total_seconds, mut total_pomodoros) = (0, 0);
+
+    for statistic in statistics {
+        let Ok(date) = NaiveDate::parse_from_str(&statistic.date, "%Y-%m-%d") else {
+            continue;
+        };
+        if date.year() != year {
+            continue;
+        }
+        total_seconds += statistic.total_focus_time;
+        total_pomodoros += statistic.completed_pomodoros;
+        if statistic.completed_pomodoros > 0 {
+            active_dates.push(date);
+        }
+
+        let month = &mut months[date.month0() as usize];
+        month.focus_hours += hours(statistic.total_focus_time);
+        month.pomodoros += statistic.completed_pomodoros;
+
+        for session in &statistic.sessions {
+            let is_break = matches!(
+                session.session_type,
+                SessionType::ShortBreak | SessionType::LongBreak
+            );
+            let counted = session.completed && !is_break;
+            if let (true, Some(task_id)) = (counted, session.task_id.as_deref()) {
+                let entry = task_totals.entry(task_id).or_default();
+                entry.0 += 1;
+                entry.1 += session.duration;
+            }
+        }
+    }
+
+    let titles: HashMap<&str, &str> = tasks
+        .iter()
+        .map(|task| (task.id.as_str(), task.title.as_str()))
+        .collect();
+    let mut top_tasks: Vec<TaskTotal> = task_totals
+        .into_iter()
+        .map(|(task_id, (pomodoros, seconds))| TaskTotal {
+            task_id: task_id.to_string(),
+            title: titles.get(task_id).unwrap_or(&"Deleted task").to_string(),
+            pomodoros,
+            focus_hours: hours(seconds),
+        })
+        .collect();
+    top_tasks.sort_by(|a, b| b.pomodoros.cmp(&a.pomodoros).then(a.title.cmp(&b.title)));
+    top_tasks.truncate(TOP_TASK_COUNT);
+
+    let busiest_month = months
+        .iter()
+        .filter(|month| month.pomodoros > 0)
+        .max_by(|a, b| a.focus_hours.total_cmp(&b.focus_hours))
+        .cloned();
+    let total_focus_hours = hours(total_seconds);
+
+    YearInReview {
+        year,
+        total_focus_hours,
+        total_pomodoros,
+        active_days: active_dates.len() as u32,
+        longest_streak_days: longest_streak(&active_dates, days_off),
+        busiest_month,
+        months,
+        top_tasks,
+        comparisons: comparisons(total_focus_hours),
+        contributions: build_contribution_graph(year, statistics, &DEFAULT_THRESHOLDS),
+    }
+}
+
+const INK: [f32; 3] = [0.15, 0.15, 0.18];
+const MUTED: [f32; 3] = [0.45, 0.45, 0.5];
+const ACCENT: [f32; 3] = [0.86, 0.33, 0.27];
+
+/// Renders the review as a one-page shareable PDF, with durations in
+/// `duration_style`. The PDF is in English.
+pub fn render_pdf(review: &YearInReview, duration_style: DurationStyle) -> Vec<u8> {
+    let mut page = PdfPage::default();
+    let margin = 56.0;
+    let mut y = PAGE_HEIGHT - 90.0;
+
+    page.text(
+        margin,
+        y,
+        30.0,
+        Font::Bold,
+        INK,
+        &format!("{} in Focus", review.year),
+    );
+    y -= 28.0;
+    page.text(
+        margin,
+        y,
+        12.0,
+        Font::Regular,
+        MUTED,
+        "Your year with Tempus Ring",
+    );
+
+    y -= 56.0;
+    let headline = [
+        (format!("{:.1}", review.total_focus_hours), "hours focused"),
+        (review.total_pomodoros.to_string(), "pomodoros"),
+        (review.longest_streak_days.to_string(), "day longest streak"),
+    ];
+    for (column, (value, label)) in headline.iter().enumerate() {
+        let x = margin + column as f32 * 165.0;
+        page.text(x, y, 26.0, Font::Bold, ACCENT, value);
+        page.text(x, y - 18.0, 11.0, Font::Regular, MUTED, label);
+    }
+
+    y -= 70.0;
+    page.text(margin, y, 14.0, Font::Bold, INK, "Focus by month");
+    let chart_height = 120.0;
+    let chart_bottom = y - 20.0 - chart_height;
+    let bar_slot = (PAGE_WIDTH - 2.0 * margin) / 12.0;
+    let max_hours = review
+        .months
+        .iter()
+        .map(|month| month.focus_hours)
+        .fold(0.0, f64::max);
+    for (index, month) in review.months.iter().enumerate() {
+        let x = margin + index as f32 * bar_slot;
+        let height = if max_hours > 0.0 {
+            (month.focus_hours / max_hours) as f32 * chart_height
+        } else {
+            0.0
+        };
+        page.rect(
+            x + 4.0,
+            chart_bottom,
+            bar_slot - 8.0,
+            height.max(1.0),
+            ACCENT,
+        );
+        page.text(
+            x + 6.0,
+            chart_bottom - 14.0,
+            9.0,
+            Font::Regular,
+            MUTED,
+            &month.name[..3],
+        );
+    }
+    y = chart_bottom - 44.0;
+
+    if let Some(month) = &review.busiest_month {
+        page.text(
+            margin,
+            y,
+            12.0,
+            Font::Regular,
+            INK,
+            &format!(
+                "Busiest month: {} ({})",
+                month.name,
+                format_duration(
+                    (month.focus_hours * 3600.0).round() as u64,
+                    duration_style,
+                    "en"
+                )
+            ),
+        );
+        y -= 30.0;
+    }
+
+    if !review.top_tasks.is_empty() {
+        page.text(margin, y, 14.0, Font::Bold, INK, "Top tasks");
+        y -= 22.0;
+        for (rank, task) in review.top_tasks.iter().enumerate() {
+            page.text(
+                margin,
+                y,
+                11.0,
+                Font::Regular,
+                INK,
+                &format!(
+                    "{}. {} - {} pomodoros",
+                    rank + 1,
+                    task.title,
+                    task.pomodoros
+                ),
+            );
+            y -= 18.0;
+        }
+        y -= 12.0;
+    }
+
+    for comparison in &review.comparisons {
+        page.text(margin, y, 11.0, Font::Regular, MUTED, comparison);
+        y -= 18.0;
+    }
+
+    page.render()
+}
+
+/// Writes last year's review (JSON and PDF) into `reviews_dir` during
+/// January, once. Returns the PDF path when a new review was generated.
+pub fn generate_if_due(
+    reviews_dir: &Path,
+    today: NaiveDate,
+    statistics: impl FnOnce(i32) -> Result<Vec<TimerStatistic>, String>,
+    tasks: &[Task],
+    days_off: &DaysOffSettings,
+    duration_style: DurationStyle,
+) -> Result<Option<PathBuf>, String> {
+    if today.month() != 1 {
+        return Ok(None);
+    }
+    let year = today.year() - 1;
+    let pdf_path = reviews_dir.join(format!("year-in-focus-{year}.pdf"));
+    if pdf_path.exists() {
+        return Ok(None);
+    }
+
+    let review = build_year_in_review(year, &statistics(year)?, tasks, days_off);
+    let json = serde_json::to_string_pretty(&review)
+        .map_err(|e| format!("Failed to serialize year in review: {e}"))?;
+    fs::write(pdf_path.with_extension("json"), json)
+        .map_err(|e| format!("Failed to write year in review: {e}"))?;
+    fs::write(&pdf_path, render_pdf(&review, duration_style))
+        .map_err(|e| format!("Failed to write year in review PDF: {e}"))?;
+    Ok(Some(pdf_path))
+}
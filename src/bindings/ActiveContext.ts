@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The context sessions ending now are recorded in.
+ */
+export type ActiveContext = { name: string | null, 
+/**
+ * Whether `name` was inferred rather than picked by hand.
+ */
+detected: boolean, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlarmStageKind } from "./AlarmStageKind";
+import type { SessionType } from "./SessionType";
+
+/**
+ * Payload of [`PLAY_EVENT`].
+ */
+export type AlarmCue = { stage: AlarmStageKind, session_type: SessionType, sound: string, 
+/**
+ * Final playback volume, phase volume already applied.
+ */
+volume: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionAlarms } from "./SessionAlarms";
+
+export type AlarmPreferences = { work: SessionAlarms, short_break: SessionAlarms, long_break: SessionAlarms, 
+/**
+ * Shared by all user-defined session types.
+ */
+custom: SessionAlarms, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AlarmStage = { enabled: boolean, 
+/**
+ * Sound identifier resolved by the frontend.
+ */
+sound: string, 
+/**
+ * `0.0..=1.0`, scaled by the phase volume.
+ */
+volume: number, };
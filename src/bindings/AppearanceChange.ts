@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayPhase } from "./DayPhase";
+import type { UserPreferences } from "./UserPreferences";
+
+/**
+ * Payload of [`APPEARANCE_CHANGED_EVENT`].
+ */
+export type AppearanceChange = { phase: DayPhase, preferences: UserPreferences, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlarmPreferences } from "./AlarmPreferences";
+
+/**
+ * What a phase switches to.
+ */
+export type AppearanceProfile = { theme: string, 
+/**
+ * Volume for every phase, `0.0..=1.0`.
+ */
+volume: number, alarms: AlarmPreferences, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppearanceProfile } from "./AppearanceProfile";
+import type { ScheduleSource } from "./ScheduleSource";
+
+export type AppearanceSchedule = { enabled: boolean, source: ScheduleSource, 
+/**
+ * Minutes after local midnight the day profile takes over.
+ */
+day_start: number, 
+/**
+ * Minutes after local midnight the night profile takes over.
+ */
+night_start: number, day: AppearanceProfile, night: AppearanceProfile, };
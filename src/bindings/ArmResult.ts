@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ArmResult = { name: string, 
+/**
+ * Days of the period so far that ran this arm.
+ */
+days: number, 
+/**
+ * Focus sessions recorded under the arm, finished or not.
+ */
+sessions: number, completed: number, 
+/**
+ * `completed / sessions`, or 0 without sessions.
+ */
+completion_rate: number, 
+/**
+ * Seconds of completed focus.
+ */
+focus_time: number, 
+/**
+ * Minutes of completed focus per day the arm ran.
+ */
+focus_score: number, };
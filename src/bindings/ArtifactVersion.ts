@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionBoundary } from "./SessionBoundary";
+import type { SessionType } from "./SessionType";
+
+/**
+ * One saved copy of a tracked file.
+ */
+export type ArtifactVersion = { artifact_id: string, task_id: string | null, 
+/**
+ * Name of the original file.
+ */
+file_name: string, 
+/**
+ * Absolute path of the copy.
+ */
+path: string, size: number, boundary: SessionBoundary, session_type: SessionType, captured_at: number, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackupCadence } from "./BackupCadence";
+
+export type AutoBackupConfig = { enabled: boolean, cadence: BackupCadence, 
+/**
+ * Number of automatic backups kept; older ones are deleted.
+ */
+keep: number, 
+/**
+ * Directory backups are written to, or the app's backup directory when
+ * unset. Only set through `choose_auto_backup_dir`.
+ */
+dir: string | null, };
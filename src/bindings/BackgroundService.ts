@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BackgroundService = "end_of_day" | "distraction_blocker" | "auto_backup" | "dashboard_feed" | "deep_links" | "door_status" | "hooks" | "slack_status" | "task_provider";
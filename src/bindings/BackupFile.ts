@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An automatic backup on disk.
+ */
+export type BackupFile = { 
+/**
+ * Absolute path, accepted by `restore_data`.
+ */
+path: string, 
+/**
+ * Local time the backup was taken, `YYYY-MM-DDTHH:MM:SS`.
+ */
+created_at: string, size_bytes: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a backup holds, shown before restoring it.
+ */
+export type BackupInfo = { format_version: number, 
+/**
+ * RFC 3339 time the backup was written; unknown for version 1 files.
+ */
+created_at: string | null, 
+/**
+ * Version of Tempus Ring that wrote it; recorded since format 4.
+ */
+app_version: string | null, encrypted: boolean, statistics_days: number, sessions: number, tasks: number, 
+/**
+ * Earliest and latest statistics days, `YYYY-MM-DD`.
+ */
+first_date: string | null, last_date: string | null, };
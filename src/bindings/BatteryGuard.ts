@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BatteryGuardMode } from "./BatteryGuardMode";
+
+export type BatteryGuard = { mode: BatteryGuardMode, 
+/**
+ * Battery is low below this, while unplugged.
+ */
+threshold_percent: number, 
+/**
+ * Focus sessions at least this long count as long; stopwatches always
+ * do.
+ */
+long_session_minutes: number, };
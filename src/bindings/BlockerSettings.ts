@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockAction } from "./BlockAction";
+
+export type BlockerSettings = { enabled: boolean, 
+/**
+ * Domains blocked during work sessions, such as `news.ycombinator.com`.
+ */
+blocklist: Array<string>, actions: Array<BlockAction>, };
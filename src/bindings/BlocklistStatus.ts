@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BlocklistStatus = { blocklist: Array<string>, enabled: boolean, 
+/**
+ * Blocking is in effect now.
+ */
+blocking: boolean, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BreakDetection = { enabled: boolean, 
+/**
+ * Shortest gap between sessions that counts as a break.
+ */
+min_gap_minutes: number, 
+/**
+ * Minutes after local midnight. A gap must overlap this window; the
+ * same start and end cover the whole day.
+ */
+window_start_minute: number, window_end_minute: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+
+export type BreakReminder = { session_type: SessionType, 
+/**
+ * 1 for the first reminder about this break.
+ */
+count: number, 
+/**
+ * Whether this reminder escalated past a notification.
+ */
+escalated: boolean, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BreakEscalation } from "./BreakEscalation";
+
+export type BreakReminders = { enabled: boolean, 
+/**
+ * Minutes between reminders while the break waits.
+ */
+interval_minutes: number, 
+/**
+ * Reminders sent before later ones escalate.
+ */
+escalate_after: number, escalation: BreakEscalation, 
+/**
+ * Reminders per break at most.
+ */
+max_reminders: number, };
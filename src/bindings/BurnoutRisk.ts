@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BurnoutLevel } from "./BurnoutLevel";
+import type { BurnoutSignals } from "./BurnoutSignals";
+
+export type BurnoutRisk = { 
+/**
+ * Monday of the week assessed, the last one finished.
+ */
+week_start: string, 
+/**
+ * From 0 to 100; higher is worse.
+ */
+score: number, level: BurnoutLevel, 
+/**
+ * The score of the week before, for the trend.
+ */
+previous_score: number, week: BurnoutSignals, 
+/**
+ * Weekly averages over the three weeks before.
+ */
+baseline: BurnoutSignals, 
+/**
+ * The signals that got worse, most pressing first.
+ */
+warnings: Array<string>, };
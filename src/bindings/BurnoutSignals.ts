@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One week's figures, or the weekly average over several.
+ */
+export type BurnoutSignals = { 
+/**
+ * Share of focus sessions finished rather than reset or skipped, from
+ * 0 to 1. `None` without any focus sessions.
+ */
+completion_rate: number | null, 
+/**
+ * Focus sessions started and then reset or skipped.
+ */
+aborted_sessions: number, 
+/**
+ * `None` without any breaks.
+ */
+average_break_secs: number | null, 
+/**
+ * Focus sessions running between 10pm and 5am.
+ */
+late_night_sessions: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CalendarSettings = { 
+/**
+ * Hour, 0 to 23, at which one day ends and the next begins.
+ */
+day_start_hour: number, 
+/**
+ * Minutes east of UTC to keep days in, or `None` for the system's time
+ * zone, daylight saving included.
+ */
+utc_offset_minutes: number | null, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevisionConflict } from "./RevisionConflict";
+import type { ValidationError } from "./ValidationError";
+
+/**
+ * Uniform response envelope for commands.
+ */
+export type CommandResult<T> = { success: boolean, data: T | null, error: string | null, 
+/**
+ * Machine-readable reason for the failure, such as `not_running`,
+ * `not_found`, `io`, `revision_conflict` or `invalid_input`, and
+ * `failed` for anything without a more specific reason. Set on every
+ * failure; branch on this rather than on `error`, which is meant for
+ * display.
+ */
+code?: string, 
+/**
+ * Set when the mutation was rejected because the caller's revision was stale.
+ */
+conflict?: RevisionConflict, 
+/**
+ * Set when the input was rejected, naming the offending field.
+ */
+validation?: ValidationError, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CommandTrace = { command: string, 
+/**
+ * Milliseconds until the response was sent; for an async command,
+ * until dispatch or, on its completion, until it answered.
+ */
+duration_ms: number, 
+/**
+ * `None` at an async command's dispatch, and for synchronous commands
+ * not answering with a `CommandResult`.
+ */
+success: boolean | null, 
+/**
+ * The `CommandResult` failure code, if any.
+ */
+code: string | null, 
+/**
+ * Whether this is an async command's completion, following the trace
+ * of its dispatch.
+ */
+completion: boolean, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Focus figures for the sessions recorded in one context.
+ */
+export type ContextFocus = { 
+/**
+ * `None` for sessions recorded without a context.
+ */
+context: string | null, 
+/**
+ * Sessions of types that count as pomodoros, finished or not.
+ */
+focus_sessions: number, completed_sessions: number, 
+/**
+ * Share of focus sessions completed rather than skipped or reset.
+ */
+completion_rate: number, 
+/**
+ * Seconds of completed focus.
+ */
+focus_time: number, 
+/**
+ * Mean length of a completed focus session in seconds.
+ */
+average_session: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContributionDay } from "./ContributionDay";
+
+/**
+ * GitHub-style grid data: one entry per calendar day of `year`.
+ */
+export type ContributionGraph = { year: number, thresholds: Array<number>, max_pomodoros: number, days: Array<ContributionDay>, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A user-defined session such as Reading or Exercise.
+ */
+export type CustomSessionType = { 
+/**
+ * Stable key stored in statistics, e.g. `reading`.
+ */
+id: string, name: string, 
+/**
+ * `#RRGGBB`
+ */
+color: string, 
+/**
+ * Default length in seconds.
+ */
+duration: number, 
+/**
+ * Whether finishing one adds to pomodoro and focus totals.
+ */
+counts_as_pomodoro: boolean, };
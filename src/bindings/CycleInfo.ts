@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CycleInfo = { 
+/**
+ * 1-based number of the current pomodoro, or of the one just finished
+ * during a break. `0` before the first pomodoro of the day.
+ */
+pomodoro_in_cycle: number, pomodoros_per_cycle: number, 
+/**
+ * Whether the break after the next pomodoro to finish is a long one.
+ */
+next_break_is_long: boolean, 
+/**
+ * Unix time the cycle's long break, or the sequence's last phase, would
+ * end if every remaining session ran back to back from now.
+ */
+projected_end_time: number, 
+/**
+ * Index of the current phase when the configuration has a sequence.
+ */
+sequence_phase: number | null, };
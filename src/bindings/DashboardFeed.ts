@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedWindow } from "./FeedWindow";
+
+/**
+ * Contents of the feed file.
+ */
+export type DashboardFeed = { schema_version: number, 
+/**
+ * Unix time the file was written.
+ */
+generated_at: number, last_7_days: FeedWindow, last_30_days: FeedWindow, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DashboardFeedConfig = { enabled: boolean, 
+/**
+ * File the feed is written to. Only set through
+ * `choose_dashboard_feed_path`.
+ */
+path: string | null, 
+/**
+ * Minutes between rewrites.
+ */
+interval_minutes: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A single day off, for showing on a calendar.
+ */
+export type DayOff = { date: string, 
+/**
+ * The entry's label or the holiday's name.
+ */
+label: string, holiday: boolean, };
\ No newline at end of file
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A vacation or other planned break, by hand.
+ */
+export type DayOffEntry = { 
+/**
+ * `YYYY-MM-DD`.
+ */
+start_date: string, 
+/**
+ * `YYYY-MM-DD`, inclusive.
+ */
+end_date: string, label: string, };
\ No newline at end of file
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimelineEntry } from "./TimelineEntry";
+
+export type DayTimeline = { date: string, 
+/**
+ * Ordered by start time; planned blocks come before actual entries
+ * starting at the same moment.
+ */
+entries: Array<TimelineEntry>, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayOffEntry } from "./DayOffEntry";
+import type { HolidayRegion } from "./HolidayRegion";
+
+export type DaysOffSettings = { entries: Array<DayOffEntry>, 
+/**
+ * Public holidays of this region are days off too.
+ */
+holiday_region: HolidayRegion | null, };
\ No newline at end of file
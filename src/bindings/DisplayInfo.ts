@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A paired display as shown in settings, without its token.
+ */
+export type DisplayInfo = { id: string, name: string, paired_at: number, };
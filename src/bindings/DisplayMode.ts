@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What timer surfaces count: the time left, the time elapsed or the
+ * share done.
+ */
+export type DisplayMode = "countdown" | "count_up" | "percent";
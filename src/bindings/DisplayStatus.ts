@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DisplayStatus = { "status": "connected", address: string, } | { "status": "reconnecting", address: string, error: string, } | { "status": "rejected", address: string, error: string, };
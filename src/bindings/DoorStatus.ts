@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Contents of `status.json`.
+ */
+export type DoorStatus = { 
+/**
+ * A focus session is running or paused.
+ */
+busy: boolean, message: string, 
+/**
+ * Local `HH:MM` the focus session ends, if it has a set end.
+ */
+until: string | null, 
+/**
+ * The same as Unix time.
+ */
+until_timestamp: number | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DoorStatusConfig = { enabled: boolean, 
+/**
+ * Folder the page is written to. Only set through
+ * `choose_door_status_dir`.
+ */
+dir: string | null, };
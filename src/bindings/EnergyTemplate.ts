@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EnergyLevel } from "./EnergyLevel";
+import type { SessionType } from "./SessionType";
+
+/**
+ * The user's typical energy through the day, one level per hour.
+ */
+export type EnergyTemplate = { 
+/**
+ * Exactly [`HOURS_PER_DAY`] entries, starting at midnight.
+ */
+hours: Array<EnergyLevel>, 
+/**
+ * What to schedule in low-energy windows.
+ */
+low_energy_session: SessionType, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExperimentArm } from "./ExperimentArm";
+
+export type Experiment = { 
+/**
+ * Lowercase, e.g. `session-length`; part of the session tag.
+ */
+name: string, 
+/**
+ * Exactly two. The first runs on the start date.
+ */
+arms: Array<ExperimentArm>, 
+/**
+ * `YYYY-MM-DD`.
+ */
+start_date: string, 
+/**
+ * Length of the period in days.
+ */
+days: number, };
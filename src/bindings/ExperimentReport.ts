@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArmResult } from "./ArmResult";
+import type { Experiment } from "./Experiment";
+
+export type ExperimentReport = { experiment: Experiment, end_date: string, 
+/**
+ * The period is over.
+ */
+finished: boolean, arms: Array<ArmResult>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * File formats that can be written through `choose_export_destination`.
+ */
+export type ExportFormat = "sqlite" | "pdf" | "ics";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Recorded in the day's statistics and sent with [`FATIGUE_RISK_EVENT`].
+ */
+export type FatigueAdvisory = { timestamp: number, skipped_breaks: number, 
+/**
+ * Suggested recovery break in seconds.
+ */
+suggested_break: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FatigueThresholds = { enabled: boolean, 
+/**
+ * Breaks skipped in a row, without one taken in between, that count
+ * as a fatigue risk.
+ */
+skipped_breaks: number, 
+/**
+ * Length of the suggested recovery break in seconds.
+ */
+recovery_break: number, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedDay } from "./FeedDay";
+
+export type FeedWindow = { days: number, from: string, 
+/**
+ * Inclusive; today.
+ */
+to: string, pomodoros: number, focus_seconds: number, active_days: number, average_daily_pomodoros: number, average_daily_focus_seconds: number, longest_streak_days: number, 
+/**
+ * Date with the most focus time, if any.
+ */
+best_day: string | null, 
+/**
+ * One entry per day, oldest first, including empty days.
+ */
+daily: Array<FeedDay>, };
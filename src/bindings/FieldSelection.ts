@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A tree of selected fields. A field with no children is kept whole.
+ */
+export type FieldSelection = string[];
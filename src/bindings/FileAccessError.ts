@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a file path from the webview was refused or could not be used.
+ */
+export type FileAccessError = { "kind": "invalid", message: string, } | { "kind": "permission_denied", path: string, } | { "kind": "failed", message: string, } | { "kind": "storage", code: string, message: string, };
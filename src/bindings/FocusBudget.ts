@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BudgetSeverity } from "./BudgetSeverity";
+
+export type FocusBudget = { 
+/**
+ * Most focus hours a day; 0 for no cap.
+ */
+daily_hours: number, 
+/**
+ * Most focus hours from Monday to Sunday; 0 for no cap.
+ */
+weekly_hours: number, severity: BudgetSeverity, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The app in front while it is fullscreen.
+ */
+export type FullscreenApp = { name: string | null, 
+/**
+ * The monitor it covers, where the platform tells.
+ */
+monitor: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A GitHub issue given as `owner/repo#123` or its URL, held in the
+ * canonical `owner/repo#123` form.
+ */
+export type GithubIssue = string;
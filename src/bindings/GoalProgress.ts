@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type GoalProgress = { date: string, completed: number, goal: number, 
+/**
+ * Also set on a day off, where the goal doesn't apply.
+ */
+reached: boolean, day_off: boolean, };
\ No newline at end of file
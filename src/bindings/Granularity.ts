@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Suggested axis labelling for the bucket width, or the rollup period of a
+ * statistics summary.
+ */
+export type Granularity = "day" | "week" | "month";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionData } from "./SessionData";
+
+/**
+ * A recorded session with where it is stored, for editing it.
+ */
+export type HistorySession = { date: string, 
+/**
+ * Position in the day's sessions.
+ */
+index: number, session: SessionData, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HistoryStatus = { 
+/**
+ * Label of the action that was just undone or redone.
+ */
+action: string | null, can_undo: boolean, can_redo: boolean, };
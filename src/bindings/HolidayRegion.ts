@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Countries whose public holidays can count as days off.
+ */
+export type HolidayRegion = "us" | "gb" | "de" | "fr";
\ No newline at end of file
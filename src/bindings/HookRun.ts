@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+import type { TimerEventKind } from "./TimerEventKind";
+
+export type HookRun = { command: string, event: TimerEventKind, session_type: SessionType, 
+/**
+ * Unix time of the transition.
+ */
+timestamp: number, 
+/**
+ * `None` if it didn't start, was killed or ended by a signal.
+ */
+exit_code: number | null, timed_out: boolean, 
+/**
+ * The start of its output, up to 4 KiB each.
+ */
+stdout: string, stderr: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which sessions a hook runs for.
+ */
+export type HookSessions = "any" | "focus" | "break";
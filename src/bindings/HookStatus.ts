@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HookRun } from "./HookRun";
+import type { TimerHook } from "./TimerHook";
+
+export type HookStatus = { hooks: Array<TimerHook>, 
+/**
+ * The latest runs, newest first.
+ */
+recent_runs: Array<HookRun>, };
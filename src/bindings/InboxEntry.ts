@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InboxKind } from "./InboxKind";
+
+export type InboxEntry = { id: string, kind: InboxKind, title: string, body: string | null, 
+/**
+ * Unix seconds.
+ */
+created_at: number, read: boolean, };
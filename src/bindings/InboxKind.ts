@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type InboxKind = "notification" | "goal_reached" | "project_goal_reached" | "fatigue_risk" | "burnout_risk" | "overwork" | "low_battery" | "storage_degraded" | "integration_failed";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type InstanceInvocation = { 
+/**
+ * The second launch's arguments, starting with the program.
+ */
+args: Array<string>, 
+/**
+ * Its working directory.
+ */
+cwd: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stored file that failed verification.
+ */
+export type IntegrityIssue = { 
+/**
+ * Where the file was found.
+ */
+path: string, reason: string, 
+/**
+ * Where the file was moved to, or `None` if it was left in place.
+ */
+quarantined_to: string | null, 
+/**
+ * Unix seconds.
+ */
+detected_at: number, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IntegrityIssue } from "./IntegrityIssue";
+
+export type IntegrityReport = { 
+/**
+ * Statistics days and automatic backups verified by this check.
+ */
+checked_files: number, 
+/**
+ * Files that failed this check.
+ */
+issues: Array<IntegrityIssue>, 
+/**
+ * Every file quarantined so far, including those found while loading
+ * statistics, oldest first.
+ */
+quarantined: Array<IntegrityIssue>, };
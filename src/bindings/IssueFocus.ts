@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IssueSession } from "./IssueSession";
+
+export type IssueFocus = { 
+/**
+ * `owner/repo#123`.
+ */
+issue: string, 
+/**
+ * Completed pomodoros among the sessions.
+ */
+pomodoros: number, 
+/**
+ * Focused seconds across the sessions.
+ */
+focus_time: number, 
+/**
+ * Oldest first.
+ */
+sessions: Array<IssueSession>, };
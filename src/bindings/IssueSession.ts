@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionData } from "./SessionData";
+
+/**
+ * A session attached to the issue, with the day it was recorded on.
+ */
+export type IssueSession = { date: string, 
+/**
+ * Position in the day's sessions, for editing it.
+ */
+index: number, session: SessionData, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PowerStatus } from "./PowerStatus";
+import type { TimerData } from "./TimerData";
+
+export type LowBatteryWarning = { status: PowerStatus, timer: TimerData, 
+/**
+ * The session was kept from starting on its own.
+ */
+deferred: boolean, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One version change of the data directory.
+ */
+export type MigrationRecord = { 
+/**
+ * The version that opened the data before, or `None` if it predates
+ * this history or the data was new.
+ */
+from_version: string | null, to_version: string, 
+/**
+ * Local time of the upgrade, `YYYY-MM-DDTHH:MM:SS`.
+ */
+migrated_at: string, 
+/**
+ * Absolute path of the backup taken first, accepted by `restore_data`;
+ * `None` when there was no data yet.
+ */
+backup_path: string | null, };
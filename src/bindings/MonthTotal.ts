@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MonthTotal = { 
+/**
+ * 1-based.
+ */
+month: number, name: string, focus_hours: number, pomodoros: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What detection sees right now.
+ */
+export type NetworkObservation = { ssid: string | null, hostname: string | null, 
+/**
+ * The context these match, if any.
+ */
+context: string | null, };
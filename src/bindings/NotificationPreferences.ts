@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PhaseNotifications } from "./PhaseNotifications";
+
+export type NotificationPreferences = { 
+/**
+ * A work session, or a custom session, ends.
+ */
+work_end: PhaseNotifications, 
+/**
+ * A short or long break ends.
+ */
+break_end: PhaseNotifications, 
+/**
+ * The one-minute warning and the overtime nag.
+ */
+reminders: PhaseNotifications, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BudgetPeriod } from "./BudgetPeriod";
+
+/**
+ * Recorded in the day's statistics and sent with [`OVERWORK_EVENT`].
+ */
+export type OverworkEvent = { timestamp: number, period: BudgetPeriod, 
+/**
+ * Focused seconds in the period so far.
+ */
+focus_time: number, 
+/**
+ * The cap in seconds.
+ */
+cap: number, 
+/**
+ * A work session was kept from starting on its own, rather than the
+ * cap having just been crossed.
+ */
+refused_auto_start: boolean, };
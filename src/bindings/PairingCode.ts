@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PairingCode = { 
+/**
+ * Six digits to type on the display.
+ */
+code: string, expires_in_secs: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PeriodTotal = { start_date: string, 
+/**
+ * Inclusive, clipped to the requested range.
+ */
+end_date: string, completed_pomodoros: number, total_focus_time: number, 
+/**
+ * Days in the period with at least one pomodoro.
+ */
+active_days: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How one phase is announced.
+ */
+export type PhaseNotifications = { sound_enabled: boolean, 
+/**
+ * `0.0..=1.0`, scales the phase's alarm volumes.
+ */
+volume: number, 
+/**
+ * Show a native notification.
+ */
+notifications_enabled: boolean, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EnergyLevel } from "./EnergyLevel";
+import type { SessionType } from "./SessionType";
+
+/**
+ * A suggested run of sessions inside one energy window.
+ */
+export type PlanBlock = { 
+/**
+ * `HH:MM`, local time.
+ */
+start: string, end: string, 
+/**
+ * Same bounds as minutes since local midnight.
+ */
+start_minute: number, end_minute: number, energy: EnergyLevel, session_type: SessionType, sessions: number, };
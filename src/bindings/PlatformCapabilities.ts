@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PowerStatus } from "./PowerStatus";
+
+/**
+ * The platform the app runs on and what it has to offer.
+ */
+export type PlatformCapabilities = { 
+/**
+ * As named by Rust, e.g. `macos` or `windows`.
+ */
+os: string, desktop: boolean, 
+/**
+ * Battery level and charging, or `None` without a battery.
+ */
+power: PowerStatus | null, };
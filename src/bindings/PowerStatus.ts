@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PowerStatus = { battery_percent: number, 
+/**
+ * Running on battery rather than on a charger.
+ */
+on_battery: boolean, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PresenterModeSettings = { enabled: boolean, 
+/**
+ * Monitors whose fullscreen apps don't count, e.g. a side screen
+ * playing video. Names as the OS reports them, such as `HDMI-1`.
+ */
+ignored_monitors: Array<string>, suppress_notifications: boolean, suppress_sounds: boolean, 
+/**
+ * Ask the frontend to hide its overlays and HUDs.
+ */
+suppress_overlays: boolean, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FullscreenApp } from "./FullscreenApp";
+
+export type PresenterStatus = { 
+/**
+ * The fullscreen app presenter mode is on for, if it is.
+ */
+fullscreen: FullscreenApp | null, notifications_suppressed: boolean, sounds_suppressed: boolean, overlays_suppressed: boolean, };
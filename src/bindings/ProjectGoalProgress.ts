@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ProjectGoalProgress = { project: string, 
+/**
+ * Monday of the week, `YYYY-MM-DD`.
+ */
+week_start: string, 
+/**
+ * Focused seconds this week so far.
+ */
+focus_time: number, 
+/**
+ * The weekly goal in seconds.
+ */
+goal_time: number, reached: boolean, 
+/**
+ * Focused seconds by the end of the week if the pace so far holds.
+ */
+forecast_focus_time: number, 
+/**
+ * Whether the forecast reaches the goal.
+ */
+on_track: boolean, };
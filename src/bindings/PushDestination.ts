@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PushDestination = { 
+/**
+ * Stable identifier used to track delivery.
+ */
+id: string, } & ({ "kind": "webhook", url: string, } | { "kind": "file_drop" } | { "kind": "queue", name: string, });
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Delivery bookkeeping, stored next to the preferences.
+ */
+export type PushState = { 
+/**
+ * Latest day delivered to every destination.
+ */
+last_pushed_date: string | null, 
+/**
+ * Destinations already served for days still in progress.
+ */
+delivered: { [key in string]: Array<string> }, last_error: string | null, };
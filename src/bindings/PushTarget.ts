@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a finished day is sent.
+ */
+export type PushTarget = { "kind": "webhook", url: string, } | { "kind": "file_drop" } | { "kind": "queue", name: string, };
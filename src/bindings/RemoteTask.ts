@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A task in an external task manager.
+ */
+export type RemoteTask = { 
+/**
+ * The id to link sessions with, `<provider>:<id>`.
+ */
+id: string, title: string, project: string | null, 
+/**
+ * Opens the task in the provider's app.
+ */
+url: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which parts of a backup to restore. Everything by default.
+ */
+export type RestoreSelection = { preferences: boolean, tasks: boolean, statistics: boolean, 
+/**
+ * Inclusive `YYYY-MM-DD` bounds on the statistics days restored.
+ */
+start_date: string | null, end_date: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Raised when a mutation was based on a stale revision of a resource,
+ * typically because another window changed it in the meantime.
+ */
+export type RevisionConflict = { resource: string, expected: number, actual: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimerConfig } from "./TimerConfig";
+import type { Weekday } from "./Weekday";
+
+export type ScheduleRule = { name: string, 
+/**
+ * Days the window opens on; empty means every day.
+ */
+weekdays: Array<Weekday>, 
+/**
+ * Minutes after local midnight the window opens.
+ */
+start_minute: number, 
+/**
+ * Minutes after local midnight the window closes. An earlier time
+ * wraps past midnight; the same time covers the whole day.
+ */
+end_minute: number, config: TimerConfig, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+
+/**
+ * One step of a custom interval sequence, such as 52 minutes of work.
+ */
+export type SequencePhase = { session_type: SessionType, 
+/**
+ * Seconds.
+ */
+duration: number, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackgroundService } from "./BackgroundService";
+
+export type ServiceStatus = { service: BackgroundService, 
+/**
+ * The last run succeeded, or there hasn't been one yet.
+ */
+healthy: boolean, consecutive_failures: number, last_error: string | null, 
+/**
+ * Unix seconds.
+ */
+last_success_at: number | null, 
+/**
+ * Unix seconds.
+ */
+last_failure_at: number | null, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlarmStage } from "./AlarmStage";
+
+export type SessionAlarms = { 
+/**
+ * One-minute warning.
+ */
+pre_alarm: AlarmStage, 
+/**
+ * Session completion.
+ */
+alarm: AlarmStage, 
+/**
+ * Repeats after completion until the next session is started.
+ */
+overtime: AlarmStage, };
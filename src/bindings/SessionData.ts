@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+import type { WindowFocus } from "./WindowFocus";
+
+export type SessionData = { session_type: SessionType, 
+/**
+ * Planned length of the session in seconds.
+ */
+duration: number, completed: boolean, start_time: number, end_time: number | null, task_id: string | null, 
+/**
+ * Free-form labels such as `writing` or `email`.
+ */
+tags: Array<string>, 
+/**
+ * GitHub issue worked on, as `owner/repo#123`.
+ */
+issue: string | null, 
+/**
+ * Where the session was worked, such as `Home` or `Office`.
+ */
+context: string | null, 
+/**
+ * Seconds worked past the planned `duration` before completing it.
+ */
+overtime: number, 
+/**
+ * Seconds added to the planned `duration` while the session ran.
+ */
+extended: number, 
+/**
+ * The windows in front longest during a work session, with window
+ * annotation on.
+ */
+windows: Array<WindowFocus>, };
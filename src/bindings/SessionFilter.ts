@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+
+/**
+ * Every field left out matches any session.
+ */
+export type SessionFilter = { session_type: SessionType | null, completed: boolean | null, task_id: string | null, 
+/**
+ * Sessions carrying this tag.
+ */
+tag: string | null, 
+/**
+ * Found, ignoring case, in the linked task's title, a tag or the issue.
+ */
+text: string | null, };
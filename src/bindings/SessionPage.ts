@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HistorySession } from "./HistorySession";
+
+export type SessionPage = { 
+/**
+ * Newest first.
+ */
+sessions: Array<HistorySession>, 
+/**
+ * Sessions matching the filter across all pages.
+ */
+total: number, 
+/**
+ * Zero-based.
+ */
+page: number, page_size: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A free-form session tag, trimmed and lowercased so "Writing" and
+ * "writing" are counted together.
+ */
+export type SessionTag = string;
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+
+/**
+ * Totals for one session type over a date range.
+ */
+export type SessionTypeTotal = { session_type: SessionType, name: string, 
+/**
+ * Only custom types carry a color; built-ins are themed by the UI.
+ */
+color: string | null, sessions: number, completed_sessions: number, 
+/**
+ * Seconds spent in completed sessions.
+ */
+total_time: number, 
+/**
+ * Idle gaps counted as long breaks, included in the other totals.
+ */
+implicit_sessions: number, };
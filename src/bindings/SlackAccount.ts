@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The Slack account a token belongs to, as reported by `auth.test`.
+ */
+export type SlackAccount = { team: string, user: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which phases set a status; the others clear it.
+ */
+export type SlackStatusConfig = { 
+/**
+ * Work sessions and custom types that count as pomodoros.
+ */
+work: boolean, short_break: boolean, 
+/**
+ * Long breaks and custom types that don't count as pomodoros.
+ */
+long_break: boolean, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SleepPolicy } from "./SleepPolicy";
+import type { TimerData } from "./TimerData";
+
+export type SleepEvent = { 
+/**
+ * Unix seconds of the last tick before the sleep.
+ */
+slept_at: number, 
+/**
+ * Unix seconds.
+ */
+woke_at: number, 
+/**
+ * The policy applied, if a session was running.
+ */
+applied: SleepPolicy | null, timer: TimerData, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimerData } from "./TimerData";
+import type { TimerOperation } from "./TimerOperation";
+
+export type StateTransition = { 
+/**
+ * Counts transitions since launch, so gaps show dropped entries.
+ */
+sequence: number, operation: TimerOperation, 
+/**
+ * Engine clock reading in milliseconds.
+ */
+clock_ms: number, 
+/**
+ * Time spent in the session before the transition, in milliseconds.
+ */
+elapsed_ms: number, 
+/**
+ * Unix time of the transition.
+ */
+timestamp: number, before: TimerData, after: TimerData, };
@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BestDay } from "./BestDay";
+import type { Granularity } from "./Granularity";
+import type { PeriodTotal } from "./PeriodTotal";
+
+export type StatisticsSummary = { granularity: Granularity, from: string, to: string, total_pomodoros: number, total_focus_time: number, active_days: number, 
+/**
+ * Vacation days and holidays in the range.
+ */
+days_off: number, 
+/**
+ * Averages over every day in the range but days off, including empty
+ * ones.
+ */
+average_daily_pomodoros: number, average_daily_focus_time: number, 
+/**
+ * Averages over the periods in `periods`.
+ */
+average_period_pomodoros: number, average_period_focus_time: number, 
+/**
+ * Most consecutive days with at least one pomodoro; days off don't
+ * break a streak.
+ */
+longest_streak_days: number, 
+/**
+ * The day with the most focus time; ties go to the earlier day.
+ */
+best_day: BestDay | null, periods: Array<PeriodTotal>, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type StorageHealth = { 
+/**
+ * Writes are failing and being held in memory until they succeed.
+ */
+degraded: boolean, 
+/**
+ * Days and events waiting to be written.
+ */
+pending_writes: number, last_error: string | null, };
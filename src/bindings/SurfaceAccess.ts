@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SurfaceScope } from "./SurfaceScope";
+
+/**
+ * Per-surface scopes, stored in the preferences.
+ */
+export type SurfaceAccess = { grpc: SurfaceScope, 
+/**
+ * MCP clients such as AI assistants.
+ */
+mcp: SurfaceScope, 
+/**
+ * Scripts and widgets using the local REST API.
+ */
+http: SurfaceScope, 
+/**
+ * Network display clients; never more than read-only. The display
+ * server only listens if this is enabled at launch.
+ */
+display: SurfaceScope, 
+/**
+ * Overlays and dashboards on the live state socket; never more than
+ * read-only.
+ */
+overlay: SurfaceScope, 
+/**
+ * `tempusring://` links from browsers and launchers. Any web page can
+ * open one and they carry no token, so they are off unless turned on.
+ */
+deep_link: SurfaceScope, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How much an external surface may do, in increasing order.
+ */
+export type SurfaceScope = "disabled" | "read_only" | "control";
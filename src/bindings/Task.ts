@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Task = { id: string, title: string, estimated_pomodoros: number, completed_pomodoros: number, completed: boolean, created_at: number, completed_at: number | null, 
+/**
+ * When the archival policy moved it out of the task list.
+ */
+archived_at: number | null, revision: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TaskArchivePolicy = { enabled: boolean, 
+/**
+ * Days after completion a task is archived.
+ */
+after_days: number, };
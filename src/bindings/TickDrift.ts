@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Tick timing since launch.
+ */
+export type TickDrift = { ticks: number, 
+/**
+ * How late the latest tick woke after its deadline, in milliseconds.
+ */
+last_lateness_ms: number, max_lateness_ms: number, mean_lateness_ms: number, 
+/**
+ * Monotonic minus wall clock progress since the first tick, in
+ * milliseconds. Negative after a system sleep the monotonic clock
+ * didn't count, or when the wall clock was set forward.
+ */
+clock_drift_ms: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Granularity } from "./Granularity";
+import type { Metric } from "./Metric";
+import type { TimeBucket } from "./TimeBucket";
+
+export type TimeSeries = { metric: Metric, granularity: Granularity, buckets: Array<TimeBucket>, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+import type { TimelineKind } from "./TimelineKind";
+
+export type TimelineEntry = { kind: TimelineKind, 
+/**
+ * Unix seconds.
+ */
+start: number, end: number, session_type: SessionType | null, completed: boolean | null, task_id: string | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CustomSessionType } from "./CustomSessionType";
+import type { SequencePhase } from "./SequencePhase";
+
+/**
+ * Durations are expressed in seconds.
+ */
+export type TimerConfig = { work_duration: number, short_break_duration: number, long_break_duration: number, sessions_until_long_break: number, custom_session_types: Array<CustomSessionType>, 
+/**
+ * Phases run in this order, starting over after the last, instead of
+ * the work/short break/long break cycle. Empty for the classic cycle.
+ */
+sequence: Array<SequencePhase>, 
+/**
+ * Keep counting when a session runs out, until it is completed,
+ * instead of moving on to the next one.
+ */
+overtime: boolean, };
@@ -0,0 +1,45 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CycleInfo } from "./CycleInfo";
+import type { DisplayMode } from "./DisplayMode";
+import type { SessionData } from "./SessionData";
+import type { SessionType } from "./SessionType";
+import type { TimerConfig } from "./TimerConfig";
+import type { TimerState } from "./TimerState";
+
+/**
+ * Snapshot of the timer sent to the frontend.
+ */
+export type TimerData = { state: TimerState, session_type: SessionType, remaining_time: number, total_time: number, 
+/**
+ * Fraction of the session elapsed, to the millisecond.
+ */
+progress: number, elapsed_ms: number, remaining_ms: number, 
+/**
+ * Seconds past the end of the session, in overtime.
+ */
+overtime: number, 
+/**
+ * Seconds into the session, overtime included.
+ */
+elapsed_time: number, 
+/**
+ * Whole percent of the session elapsed, 0 for a stopwatch.
+ */
+percent_complete: number, 
+/**
+ * How surfaces should show this, from the preferences.
+ */
+display_mode: DisplayMode, completed_pomodoros: number, 
+/**
+ * Position within the current pomodoro cycle.
+ */
+cycle: CycleInfo, 
+/**
+ * Unix time the current session was first started, if it has been.
+ */
+start_time: number | null, 
+/**
+ * The most recently finished or skipped session, with real start and
+ * end timestamps, ready to be recorded in statistics.
+ */
+last_session: SessionData | null, config: TimerConfig, revision: number, };
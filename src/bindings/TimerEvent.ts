@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+import type { TimerEventKind } from "./TimerEventKind";
+
+export type TimerEvent = { kind: TimerEventKind, 
+/**
+ * The session the transition applied to.
+ */
+session_type: SessionType, 
+/**
+ * Seconds left in that session when it happened.
+ */
+remaining_time: number, timestamp: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HookSessions } from "./HookSessions";
+import type { TimerEventKind } from "./TimerEventKind";
+
+export type TimerHook = { event: TimerEventKind, sessions: HookSessions, 
+/**
+ * Run through the shell, with `TEMPUS_EVENT`, `TEMPUS_SESSION_TYPE`
+ * and `TEMPUS_REMAINING` (seconds) set.
+ */
+command: string, 
+/**
+ * Seconds before it is killed.
+ */
+timeout_secs: number, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionType } from "./SessionType";
+import type { SleepPolicy } from "./SleepPolicy";
+import type { TimerConfig } from "./TimerConfig";
+
+/**
+ * What caused a transition.
+ */
+export type TimerOperation = { "kind": "start" } | { "kind": "start_for", duration: number, } | { "kind": "start_stopwatch" } | { "kind": "stop_stopwatch", recorded: boolean, } | { "kind": "pause" } | { "kind": "resume" } | { "kind": "reset" } | { "kind": "skip" } | { "kind": "complete" } | { "kind": "expire" } | { "kind": "overrun" } | { "kind": "extend", seconds: number, } | { "kind": "switch_session", session_type: SessionType, } | { "kind": "update_config", config: TimerConfig, } | { "kind": "set_time_scale", speed: number, } | { "kind": "wake", policy: SleepPolicy, asleep_ms: number, counted_ms: number, };
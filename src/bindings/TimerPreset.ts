@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimerConfig } from "./TimerConfig";
+
+export type TimerPreset = { 
+/**
+ * Unique, compared case-insensitively.
+ */
+name: string, config: TimerConfig, };
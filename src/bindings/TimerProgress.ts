@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type TimerProgress = { elapsed_ms: number, remaining_ms: number, total_ms: number, 
+/**
+ * `elapsed_ms / total_ms`, from 0 to 1.
+ */
+progress: number, };
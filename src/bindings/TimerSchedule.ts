@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScheduleRule } from "./ScheduleRule";
+
+export type TimerSchedule = { enabled: boolean, 
+/**
+ * Checked in order; the first window covering the start time wins.
+ * Outside every window the regular `timer_config` applies.
+ */
+rules: Array<ScheduleRule>, };
@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FatigueAdvisory } from "./FatigueAdvisory";
+import type { OverworkEvent } from "./OverworkEvent";
+import type { SessionData } from "./SessionData";
+
+/**
+ * Statistics for a single day, stored as `statistics/<date>.json`.
+ */
+export type TimerStatistic = { 
+/**
+ * `YYYY-MM-DD`
+ */
+date: string, completed_pomodoros: number, 
+/**
+ * Total focused time in seconds.
+ */
+total_focus_time: number, sessions: Array<SessionData>, 
+/**
+ * Written by the backend only; `save_statistic` keeps the stored ones.
+ */
+advisories: Array<FatigueAdvisory>, 
+/**
+ * Written by the backend only, like `advisories`.
+ */
+overwork: Array<OverworkEvent>, };
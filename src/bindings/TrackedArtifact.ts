@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A file the user asked to have captured. Only added through the file
+ * dialog, never from a path the webview supplies.
+ */
+export type TrackedArtifact = { id: string, path: string, 
+/**
+ * Task the file's progress is filed under.
+ */
+task_id: string | null, added_at: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the tray icon shows while a session is running or paused.
+ */
+export type TrayCountdown = "off" | "minutes" | "progress_ring";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which releases to follow. Every beta is promoted to stable once it has
+ * settled, so switching back to stable only waits for the next promotion.
+ */
+export type UpdateChannel = "stable" | "beta";
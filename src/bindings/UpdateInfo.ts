@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UpdateChannel } from "./UpdateChannel";
+
+export type UpdateInfo = { version: string, current_version: string, channel: UpdateChannel, 
+/**
+ * Release notes in Markdown.
+ */
+notes: string | null, 
+/**
+ * RFC 3339 publish date, when the manifest has one.
+ */
+published_at: string | null, };
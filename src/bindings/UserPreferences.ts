@@ -0,0 +1,199 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AlarmPreferences } from "./AlarmPreferences";
+import type { AppearanceSchedule } from "./AppearanceSchedule";
+import type { AutoBackupConfig } from "./AutoBackupConfig";
+import type { BatteryGuard } from "./BatteryGuard";
+import type { BlockerSettings } from "./BlockerSettings";
+import type { BreakDetection } from "./BreakDetection";
+import type { BreakReminders } from "./BreakReminders";
+import type { CalendarSettings } from "./CalendarSettings";
+import type { DashboardFeedConfig } from "./DashboardFeedConfig";
+import type { DaysOffSettings } from "./DaysOffSettings";
+import type { DisplayMode } from "./DisplayMode";
+import type { DoorStatusConfig } from "./DoorStatusConfig";
+import type { DurationStyle } from "./DurationStyle";
+import type { EndOfDayConfig } from "./EndOfDayConfig";
+import type { EnergyTemplate } from "./EnergyTemplate";
+import type { Experiment } from "./Experiment";
+import type { FatigueThresholds } from "./FatigueThresholds";
+import type { FocusBudget } from "./FocusBudget";
+import type { NotificationPreferences } from "./NotificationPreferences";
+import type { PresenterModeSettings } from "./PresenterModeSettings";
+import type { ProjectGoal } from "./ProjectGoal";
+import type { QuitGuardMode } from "./QuitGuardMode";
+import type { SlackStatusConfig } from "./SlackStatusConfig";
+import type { SleepPolicy } from "./SleepPolicy";
+import type { StorageBackendKind } from "./StorageBackendKind";
+import type { SurfaceAccess } from "./SurfaceAccess";
+import type { TaskArchivePolicy } from "./TaskArchivePolicy";
+import type { TimerBackend } from "./TimerBackend";
+import type { TimerConfig } from "./TimerConfig";
+import type { TimerHook } from "./TimerHook";
+import type { TimerSchedule } from "./TimerSchedule";
+import type { TrayCountdown } from "./TrayCountdown";
+import type { UpdateChannel } from "./UpdateChannel";
+import type { WorkContextSettings } from "./WorkContextSettings";
+
+export type UserPreferences = { theme: string, language: string, 
+/**
+ * How durations read in the tray tooltip, notifications and reports.
+ */
+duration_style: DurationStyle, 
+/**
+ * Sound, volume and native notifications for each phase.
+ */
+notifications: NotificationPreferences, 
+/**
+ * Per-session-type sounds for the pre-alarm, alarm and overtime nag.
+ */
+alarms: AlarmPreferences, 
+/**
+ * Day and night profiles that replace `theme`, the phase volumes and
+ * `alarms` when the phase changes.
+ */
+appearance_schedule: AppearanceSchedule, 
+/**
+ * Whether the timer shows time left, time elapsed or percent done,
+ * everywhere it is shown.
+ */
+display_mode: DisplayMode, 
+/**
+ * What the tray icon shows while a session is running.
+ */
+tray_countdown: TrayCountdown, 
+/**
+ * Turn on the OS Do Not Disturb mode during work sessions.
+ */
+dnd_integration: boolean, 
+/**
+ * What happens on quit or window close during a work session.
+ */
+quit_guard: QuitGuardMode, 
+/**
+ * Which phases set a Slack status once `connect_slack` was called.
+ */
+slack_status: SlackStatusConfig, 
+/**
+ * Copy tracked artifact files at session boundaries.
+ */
+artifact_capture: boolean, 
+/**
+ * Record the windows in front longest during each work session. Off
+ * unless turned on.
+ */
+window_annotation: boolean, 
+/**
+ * When skipped breaks trigger a fatigue advisory.
+ */
+fatigue: FatigueThresholds, 
+/**
+ * Inbox advisories when the weekly burnout-risk score trends badly.
+ */
+burnout_advisories: boolean, 
+/**
+ * Caps on focus time against overwork.
+ */
+focus_budget: FocusBudget, 
+/**
+ * When one statistics day ends and the next begins.
+ */
+calendar: CalendarSettings, 
+/**
+ * Which idle gaps count as long breaks nobody started a session for.
+ */
+break_detection: BreakDetection, 
+/**
+ * Pomodoros per day that count as reaching the goal.
+ */
+daily_goal: number, 
+/**
+ * Vacations and holidays, which don't break streaks or miss the goal.
+ */
+days_off: DaysOffSettings, 
+/**
+ * Weekly focus-hour goals, one per project tag.
+ */
+project_goals: Array<ProjectGoal>, 
+/**
+ * Places such as Home or Office recorded on sessions.
+ */
+work_context: WorkContextSettings, 
+/**
+ * What happens to a running session when the system sleeps.
+ */
+on_sleep: SleepPolicy, 
+/**
+ * Warnings about, or holding back, long sessions on low battery.
+ */
+battery_guard: BatteryGuard, 
+/**
+ * Reminders about a break that came due but wasn't started.
+ */
+break_reminders: BreakReminders, 
+/**
+ * What holds back while a fullscreen app is in front.
+ */
+presenter_mode: PresenterModeSettings, 
+/**
+ * Sites blocked during work sessions, and how they are blocked.
+ */
+distraction_blocker: BlockerSettings, 
+/**
+ * Shell commands run on timer transitions.
+ */
+hooks: Array<TimerHook>, auto_start_breaks: boolean, auto_start_work: boolean, timer_config: TimerConfig, 
+/**
+ * Whether the timer runs in this app or on a headless focus server.
+ */
+timer_backend: TimerBackend, 
+/**
+ * Windows of the week that use their own timer configuration.
+ */
+timer_schedule: TimerSchedule, 
+/**
+ * A/B comparison of two timer configurations; while it runs its arm
+ * takes precedence over `timer_schedule`. Set through
+ * `start_experiment`.
+ */
+experiment: Experiment | null, 
+/**
+ * High/low energy hours used by `get_energy_plan`.
+ */
+energy_template: EnergyTemplate, 
+/**
+ * Destinations for the end-of-day statistic push.
+ */
+end_of_day: EndOfDayConfig, 
+/**
+ * Periodic JSON feed for personal dashboards.
+ */
+dashboard_feed: DashboardFeedConfig, 
+/**
+ * Focus status page for door displays.
+ */
+door_status: DoorStatusConfig, 
+/**
+ * Rotating backups written in the background.
+ */
+auto_backup: AutoBackupConfig, 
+/**
+ * When completed tasks move out of the task list into the archive.
+ */
+task_archive: TaskArchivePolicy, 
+/**
+ * Where daily statistics are kept. Takes effect on the next launch.
+ */
+storage_backend: StorageBackendKind, 
+/**
+ * How much gRPC and other external clients may do.
+ */
+external_access: SurfaceAccess, 
+/**
+ * Release channel the updater follows.
+ */
+update_channel: UpdateChannel, 
+/**
+ * Directory backups and exports may be written to without a dialog.
+ * Only set through `choose_export_dir`.
+ */
+export_dir: string | null, revision: number, };
@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WellbeingReport = { start_date: string, 
+/**
+ * Inclusive.
+ */
+end_date: string, breaks_taken: number, skipped_breaks: number, 
+/**
+ * Fatigue advisories raised for runs of skipped breaks.
+ */
+fatigue_advisories: number, 
+/**
+ * Minutes of focus beyond the daily focus cap, or beyond 8 hours a
+ * day without one.
+ */
+overtime_minutes: number, 
+/**
+ * Focus sessions running between 10pm and 5am.
+ */
+late_night_sessions: number, 
+/**
+ * Times a daily or weekly focus cap was crossed.
+ */
+cap_violations: number, 
+/**
+ * Work sessions held back from starting on their own by a cap.
+ */
+refused_auto_starts: number, 
+/**
+ * Suggestions drawn from the figures above, most pressing first.
+ */
+recommendations: Array<string>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WindowFocus = { app: string, title: string, 
+/**
+ * About how many seconds it was in front.
+ */
+seconds: number, };
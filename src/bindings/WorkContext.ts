@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type WorkContext = { 
+/**
+ * Stored on sessions, e.g. `Office`.
+ */
+name: string, 
+/**
+ * Wi-Fi networks that mean this context.
+ */
+ssids: Array<string>, 
+/**
+ * Hostnames that mean this context, such as a work laptop's.
+ */
+hostnames: Array<string>, };
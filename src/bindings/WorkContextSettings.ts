@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorkContext } from "./WorkContext";
+
+export type WorkContextSettings = { contexts: Array<WorkContext>, 
+/**
+ * Picked with `set_work_context`; wins over detection.
+ */
+manual: string | null, 
+/**
+ * Infer the context from the Wi-Fi network and hostname. Off unless
+ * turned on, as it reads which network the computer is on.
+ */
+detect: boolean, };
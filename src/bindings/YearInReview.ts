@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContributionGraph } from "./ContributionGraph";
+import type { MonthTotal } from "./MonthTotal";
+import type { TaskTotal } from "./TaskTotal";
+
+/**
+ * "Year in Focus" summary for one calendar year.
+ */
+export type YearInReview = { year: number, total_focus_hours: number, total_pomodoros: number, active_days: number, longest_streak_days: number, busiest_month: MonthTotal | null, months: Array<MonthTotal>, top_tasks: Array<TaskTotal>, comparisons: Array<string>, contributions: ContributionGraph, };